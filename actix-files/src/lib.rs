@@ -242,7 +242,7 @@ mod tests {
         );
         assert_eq!(
             resp.headers().get(header::CONTENT_DISPOSITION).unwrap(),
-            "inline; filename=\"貨物.toml\"; filename*=UTF-8''%E8%B2%A8%E7%89%A9.toml"
+            "inline; filename*=UTF-8''%E8%B2%A8%E7%89%A9.toml"
         );
     }
 
@@ -12,10 +12,7 @@ use actix_web::{
         ServiceRequest, ServiceResponse,
     },
     http::{
-        header::{
-            self, Charset, ContentDisposition, ContentEncoding, DispositionParam,
-            DispositionType, ExtendedValue, HeaderValue,
-        },
+        header::{self, ContentDisposition, ContentEncoding, DispositionType, HeaderValue},
         StatusCode,
     },
     Error, HttpMessage, HttpRequest, HttpResponse, Responder,
@@ -138,21 +135,11 @@ impl NamedFile {
                 _ => DispositionType::Attachment,
             };
 
-            let mut parameters =
-                vec![DispositionParam::Filename(String::from(filename.as_ref()))];
-
-            if !filename.is_ascii() {
-                parameters.push(DispositionParam::FilenameExt(ExtendedValue {
-                    charset: Charset::Ext(String::from("UTF-8")),
-                    language_tag: None,
-                    value: filename.into_owned().into_bytes(),
-                }))
-            }
-
             let cd = ContentDisposition {
                 disposition,
-                parameters,
-            };
+                parameters: Vec::new(),
+            }
+            .with_filename(filename);
 
             (ct, cd)
         };
@@ -319,7 +306,7 @@ impl NamedFile {
     /// By default the disposition is `inline` for `text/*`, `image/*`, `video/*` and
     /// `application/{javascript, json, wasm}` mime types, and `attachment` otherwise, and the
     /// filename is taken from the path provided in the `open` method after converting it to UTF-8
-    /// (using `to_string_lossy`).
+    /// (using `to_string_lossy`) and sanitizing it with [`ContentDisposition::with_filename`].
     #[inline]
     pub fn set_content_disposition(mut self, cd: ContentDisposition) -> Self {
         self.content_disposition = cd;
@@ -0,0 +1,108 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use actix_http::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    ResponseHead, StatusCode,
+};
+
+const SIZES: [usize; 3] = [4, 12, 40];
+
+fn header_names(n: usize) -> Vec<HeaderName> {
+    (0..n)
+        .map(|i| HeaderName::from_bytes(format!("x-header-{}", i).as_bytes()).unwrap())
+        .collect()
+}
+
+fn bench_insert(c: &mut Criterion) {
+    let mut group = c.benchmark_group("header map insert");
+
+    for size in SIZES {
+        let names = header_names(size);
+        let value = HeaderValue::from_static("value");
+
+        group.bench_with_input(BenchmarkId::new("new", size), &names, |b, names| {
+            b.iter(|| {
+                let mut map = HeaderMap::new();
+                for name in names {
+                    map.insert(name.clone(), value.clone());
+                }
+                map
+            })
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("with_capacity", size),
+            &names,
+            |b, names| {
+                b.iter(|| {
+                    let mut map = HeaderMap::with_capacity(names.len());
+                    for name in names {
+                        map.insert(name.clone(), value.clone());
+                    }
+                    map
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+fn bench_response_head(c: &mut Criterion) {
+    let mut group = c.benchmark_group("response head insert");
+
+    // response heads are created with a capacity hint; compare against growing from empty
+    for size in SIZES {
+        let names = header_names(size);
+        let value = HeaderValue::from_static("value");
+
+        group.bench_with_input(BenchmarkId::new("hinted", size), &names, |b, names| {
+            b.iter(|| {
+                let mut head = ResponseHead::new(StatusCode::OK);
+                for name in names {
+                    head.headers.insert(name.clone(), value.clone());
+                }
+                head
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("unhinted", size), &names, |b, names| {
+            b.iter(|| {
+                let mut head = ResponseHead::new(StatusCode::OK);
+                head.headers = HeaderMap::new();
+                for name in names {
+                    head.headers.insert(name.clone(), value.clone());
+                }
+                head
+            })
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_get(c: &mut Criterion) {
+    let mut group = c.benchmark_group("header map get");
+
+    for size in SIZES {
+        let names = header_names(size);
+
+        let mut map = HeaderMap::new();
+        for name in &names {
+            map.insert(name.clone(), HeaderValue::from_static("value"));
+        }
+
+        group.bench_with_input(BenchmarkId::from_parameter(size), &names, |b, names| {
+            b.iter(|| {
+                for name in names {
+                    assert!(map.get(name).is_some());
+                }
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_insert, bench_response_head, bench_get);
+criterion_main!(benches);
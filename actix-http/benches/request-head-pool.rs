@@ -0,0 +1,169 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use actix_codec::Decoder as _;
+use actix_http::{
+    h1,
+    header::{self, HeaderValue},
+    Message, Request, RequestHead, ServiceConfig,
+};
+use bytes::BytesMut;
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+/// Counts heap allocations so that pooled and fresh decoding can be compared.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Upper bound on the number of heads kept by the thread-local request head pool.
+const POOL_CAP: usize = 128;
+
+/// Number of requests sent back-to-back on one keep-alive connection.
+const PIPELINED: usize = 16;
+
+const REQUEST: &[u8] = b"GET /plaintext HTTP/1.1\r\n\
+    Host: localhost:8080\r\n\
+    User-Agent: bench/1.0\r\n\
+    Accept: text/plain,text/html;q=0.9\r\n\
+    Accept-Encoding: gzip, br\r\n\
+    Accept-Language: en-US,en;q=0.5\r\n\
+    Connection: keep-alive\r\n\
+    \r\n";
+
+fn fill_headers(head: &mut RequestHead) {
+    let headers = head.headers_mut();
+    headers.insert(header::HOST, HeaderValue::from_static("localhost:8080"));
+    headers.insert(header::USER_AGENT, HeaderValue::from_static("bench/1.0"));
+    headers.insert(header::ACCEPT, HeaderValue::from_static("text/plain"));
+    headers.insert(
+        header::ACCEPT_ENCODING,
+        HeaderValue::from_static("gzip, br"),
+    );
+    headers.insert(header::ACCEPT_LANGUAGE, HeaderValue::from_static("en-US"));
+    headers.insert(header::CONNECTION, HeaderValue::from_static("keep-alive"));
+}
+
+fn bench_request_head(c: &mut Criterion) {
+    let mut group = c.benchmark_group("request head");
+
+    group.bench_function("pooled", |b| {
+        b.iter(|| {
+            let mut head = Message::<RequestHead>::new();
+            fill_headers(&mut head);
+            black_box(head);
+        })
+    });
+
+    group.bench_function("fresh", |b| {
+        b.iter(|| {
+            let mut head = RequestHead::default();
+            fill_headers(&mut head);
+            black_box(head);
+        })
+    });
+
+    group.finish();
+}
+
+/// Takes every head out of the thread-local pool, so that following decodes allocate fresh heads
+/// until the returned messages are dropped.
+fn drain_pool() -> Vec<Message<RequestHead>> {
+    (0..POOL_CAP).map(|_| Message::new()).collect()
+}
+
+/// Decodes all requests in `input`, dropping each before decoding the next, as on a keep-alive
+/// connection, so that every request reuses the previous request's head.
+fn decode_pooled(codec: &mut h1::Codec, input: &[u8]) {
+    let mut buf = BytesMut::from(input);
+    let mut decoded = 0;
+
+    while let Some(msg) = codec.decode(&mut buf).unwrap() {
+        black_box(msg);
+        decoded += 1;
+    }
+
+    assert_eq!(decoded, PIPELINED);
+}
+
+/// Decodes all requests in `input` into `retained`, so that no head is returned to the pool and
+/// each request allocates a fresh one when the pool is drained.
+fn decode_fresh(codec: &mut h1::Codec, input: &[u8], retained: &mut Vec<h1::Message<Request>>) {
+    let mut buf = BytesMut::from(input);
+
+    while let Some(msg) = codec.decode(&mut buf).unwrap() {
+        retained.push(msg);
+    }
+
+    assert_eq!(retained.len(), PIPELINED);
+}
+
+/// Returns the number of allocations made by `f`, per pipelined request.
+fn allocations_per_request(f: impl FnOnce()) -> f64 {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / PIPELINED as f64
+}
+
+fn bench_pipelined_decode(c: &mut Criterion) {
+    let input = REQUEST.repeat(PIPELINED);
+
+    // service config spawns its date updater, which needs a runtime
+    let mut codec =
+        actix_rt::System::new().block_on(async { h1::Codec::new(ServiceConfig::default()) });
+
+    // warm up the pool and the codec's buffers before counting
+    decode_pooled(&mut codec, &input);
+
+    let pooled = allocations_per_request(|| decode_pooled(&mut codec, &input));
+
+    let drained = drain_pool();
+    let mut retained = Vec::with_capacity(PIPELINED);
+    let fresh = allocations_per_request(|| decode_fresh(&mut codec, &input, &mut retained));
+    drop((drained, retained));
+
+    println!(
+        "pipelined keep-alive decode: {:.1} allocations per request with pooled heads, {:.1} \
+        with fresh heads",
+        pooled, fresh
+    );
+
+    let mut group = c.benchmark_group("pipelined keep-alive decode");
+    group.throughput(Throughput::Elements(PIPELINED as u64));
+
+    group.bench_function("pooled heads", |b| {
+        b.iter(|| decode_pooled(&mut codec, &input))
+    });
+
+    group.bench_function("fresh heads", |b| {
+        // draining the pool and dropping the decoded requests happen outside of the measurement
+        b.iter_batched(
+            || (drain_pool(), Vec::with_capacity(PIPELINED)),
+            |(drained, mut retained)| {
+                decode_fresh(&mut codec, &input, &mut retained);
+                (drained, retained)
+            },
+            BatchSize::PerIteration,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_request_head, bench_pipelined_decode);
+criterion_main!(benches);
@@ -0,0 +1,172 @@
+use std::{
+    mem,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use actix_utils::future::poll_fn;
+use bytes::{Bytes, BytesMut};
+use pin_project_lite::pin_project;
+
+use super::{BodySize, MessageBody};
+
+pin_project! {
+    /// Body wrapper that buffers small streaming bodies so they can be sent with a known size.
+    ///
+    /// [`BufferAndSize::new`] collects the inner body in memory, up to `max_buffer` bytes. If the
+    /// body ends within that limit, the wrapper reports a [`BodySize::Sized`] size, so it is sent in
+    /// one piece with a `Content-Length` header. Otherwise, the buffered chunks are sent followed by
+    /// the rest of the inner body, which stays chunked.
+    ///
+    /// Bodies of known size are not buffered.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_http::body::{BodySize, BufferAndSize, MessageBody as _};
+    /// use bytes::Bytes;
+    ///
+    /// # async fn test_buffer_and_size() {
+    /// let body = BufferAndSize::new(Bytes::from_static(b"hello"), 16).await;
+    /// assert_eq!(body.size(), BodySize::Sized(5));
+    /// # }
+    /// ```
+    pub struct BufferAndSize<B: MessageBody> {
+        buffered: Bytes,
+        rest: Option<Pin<Box<B>>>,
+        error: Option<B::Error>,
+    }
+}
+
+impl<B: MessageBody> BufferAndSize<B> {
+    /// Collects `inner` until it ends, errors, or exceeds `max_buffer` bytes.
+    pub async fn new(inner: B, max_buffer: usize) -> Self {
+        let mut inner = Box::pin(inner);
+
+        if !matches!(inner.size(), BodySize::Stream) {
+            return Self {
+                buffered: Bytes::new(),
+                rest: Some(inner),
+                error: None,
+            };
+        }
+
+        let mut buf = BytesMut::new();
+
+        loop {
+            match poll_fn(|cx| inner.as_mut().poll_next(cx)).await {
+                Some(Ok(chunk)) => {
+                    buf.extend_from_slice(&chunk);
+
+                    if buf.len() > max_buffer {
+                        return Self {
+                            buffered: buf.freeze(),
+                            rest: Some(inner),
+                            error: None,
+                        };
+                    }
+                }
+
+                Some(Err(err)) => {
+                    return Self {
+                        buffered: buf.freeze(),
+                        rest: None,
+                        error: Some(err),
+                    }
+                }
+
+                None => {
+                    return Self {
+                        buffered: buf.freeze(),
+                        rest: None,
+                        error: None,
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<B: MessageBody> MessageBody for BufferAndSize<B> {
+    type Error = B::Error;
+
+    fn size(&self) -> BodySize {
+        match (&self.rest, &self.error) {
+            (None, None) => BodySize::Sized(self.buffered.len() as u64),
+            (Some(rest), None) if self.buffered.is_empty() => rest.size(),
+            _ => BodySize::Stream,
+        }
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        if !this.buffered.is_empty() {
+            return Poll::Ready(Some(Ok(mem::take(this.buffered))));
+        }
+
+        if let Some(err) = this.error.take() {
+            return Poll::Ready(Some(Err(err)));
+        }
+
+        match this.rest {
+            Some(body) => body.as_mut().poll_next(cx),
+            None => Poll::Ready(None),
+        }
+    }
+
+    fn try_into_bytes(self) -> Result<Bytes, Self> {
+        if self.rest.is_none() && self.error.is_none() {
+            Ok(self.buffered)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{
+        body::{to_bytes, BodyStream},
+        Error,
+    };
+
+    fn chunked(
+        chunks: &'static [&'static str],
+    ) -> BodyStream<impl futures_core::Stream<Item = Result<Bytes, Error>>> {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok::<_, Error>(Bytes::from_static(chunk.as_bytes())));
+
+        BodyStream::new(stream::iter(chunks))
+    }
+
+    #[actix_rt::test]
+    async fn small_body_is_sized() {
+        let body = BufferAndSize::new(chunked(&["hello", " ", "world"]), 16).await;
+
+        assert_eq!(body.size(), BodySize::Sized(11));
+        assert_eq!(body.try_into_bytes().ok().unwrap(), "hello world");
+    }
+
+    #[actix_rt::test]
+    async fn large_body_stays_chunked() {
+        let body = BufferAndSize::new(chunked(&["hello", " ", "world", "!"]), 8).await;
+
+        assert_eq!(body.size(), BodySize::Stream);
+        assert_eq!(to_bytes(body).await.unwrap(), "hello world!");
+    }
+
+    #[actix_rt::test]
+    async fn sized_body_passes_through() {
+        let body = BufferAndSize::new(Bytes::from_static(b"hello world"), 4).await;
+
+        assert_eq!(body.size(), BodySize::Sized(11));
+        assert_eq!(to_bytes(body).await.unwrap(), "hello world");
+    }
+}
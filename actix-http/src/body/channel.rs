@@ -0,0 +1,293 @@
+use std::{
+    collections::VecDeque,
+    error::Error as StdError,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll, Waker},
+};
+
+use actix_utils::future::poll_fn;
+use bytes::Bytes;
+use derive_more::{Display, Error};
+
+use super::{BodySize, MessageBody};
+use crate::Error;
+
+/// Creates a streaming body fed by a bounded channel.
+///
+/// At most `capacity` chunks are buffered between the returned [`BodySender`] and [`ChannelBody`];
+/// once the buffer is full, [`BodySender::send`] waits until the client has consumed a chunk. This
+/// applies back-pressure to producers when the client reads slowly. A `capacity` of zero is treated
+/// as one.
+///
+/// Dropping the sender ends the body cleanly once buffered chunks have been sent, while
+/// [`BodySender::abort`] ends it with an error, causing the connection to be closed instead of the
+/// response looking complete to the client.
+///
+/// The sender can be moved to other threads, so the body can be produced by background tasks.
+///
+/// # Examples
+/// ```
+/// # use actix_http::{body, Response};
+/// # use bytes::Bytes;
+/// # async fn test() {
+/// let (mut tx, body) = body::channel(8);
+///
+/// actix_rt::spawn(async move {
+///     for chunk in ["hello", " ", "world"] {
+///         if tx.send(Bytes::from_static(chunk.as_bytes())).await.is_err() {
+///             // client has gone away
+///             return;
+///         }
+///     }
+/// });
+///
+/// let res = Response::ok().set_body(body);
+/// # }
+/// ```
+pub fn channel(capacity: usize) -> (BodySender, ChannelBody) {
+    let shared = Arc::new(Mutex::new(Shared {
+        queue: VecDeque::with_capacity(capacity.max(1)),
+        capacity: capacity.max(1),
+        error: None,
+        sender_dropped: false,
+        receiver_dropped: false,
+        sender_waker: None,
+        receiver_waker: None,
+    }));
+
+    (
+        BodySender {
+            shared: Arc::clone(&shared),
+        },
+        ChannelBody { shared },
+    )
+}
+
+struct Shared {
+    queue: VecDeque<Bytes>,
+    capacity: usize,
+    error: Option<Box<dyn StdError + Send + Sync>>,
+    sender_dropped: bool,
+    receiver_dropped: bool,
+    sender_waker: Option<Waker>,
+    receiver_waker: Option<Waker>,
+}
+
+fn lock(shared: &Mutex<Shared>) -> MutexGuard<'_, Shared> {
+    // state is kept consistent across panics since no user code runs under the lock
+    shared.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Error returned by [`BodySender::send`] when the response body has been dropped, usually
+/// because the client disconnected.
+///
+/// Contains the chunk that could not be sent.
+#[derive(Debug, Display, Error)]
+#[display(fmt = "response body was dropped")]
+pub struct SendError(#[error(not(source))] pub Bytes);
+
+/// Sending half of a body [`channel`].
+pub struct BodySender {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl BodySender {
+    /// Sends a chunk of the body, waiting for buffer space if the client is reading slowly.
+    ///
+    /// Empty chunks are skipped. Returns the chunk back in an error if the body has been dropped.
+    pub async fn send(&mut self, chunk: Bytes) -> Result<(), SendError> {
+        let mut chunk = Some(chunk);
+
+        poll_fn(|cx| {
+            let mut shared = lock(&self.shared);
+
+            if shared.receiver_dropped {
+                return Poll::Ready(Err(SendError(chunk.take().unwrap())));
+            }
+
+            if shared.queue.len() >= shared.capacity {
+                shared.sender_waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+
+            let chunk = chunk.take().unwrap();
+
+            if !chunk.is_empty() {
+                shared.queue.push_back(chunk);
+
+                if let Some(waker) = shared.receiver_waker.take() {
+                    waker.wake();
+                }
+            }
+
+            Poll::Ready(Ok(()))
+        })
+        .await
+    }
+
+    /// Returns true if the body has been dropped, usually because the client disconnected.
+    pub fn is_closed(&self) -> bool {
+        lock(&self.shared).receiver_dropped
+    }
+
+    /// Ends the body with an error once buffered chunks have been sent.
+    ///
+    /// The error is reported to the server as a body error, which closes the connection so that
+    /// the client does not mistake the truncated body for a complete one.
+    pub fn abort(self, err: impl Into<Box<dyn StdError + Send + Sync>>) {
+        lock(&self.shared).error = Some(err.into());
+    }
+}
+
+impl Drop for BodySender {
+    fn drop(&mut self) {
+        let mut shared = lock(&self.shared);
+        shared.sender_dropped = true;
+
+        if let Some(waker) = shared.receiver_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Receiving half of a body [`channel`], used as a response body.
+pub struct ChannelBody {
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl MessageBody for ChannelBody {
+    type Error = Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let mut shared = lock(&self.shared);
+
+        if let Some(chunk) = shared.queue.pop_front() {
+            if let Some(waker) = shared.sender_waker.take() {
+                waker.wake();
+            }
+
+            return Poll::Ready(Some(Ok(chunk)));
+        }
+
+        if let Some(err) = shared.error.take() {
+            let err: Box<dyn StdError> = err;
+            return Poll::Ready(Some(Err(Error::new_body().with_cause(err))));
+        }
+
+        if shared.sender_dropped {
+            return Poll::Ready(None);
+        }
+
+        shared.receiver_waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Drop for ChannelBody {
+    fn drop(&mut self) {
+        let mut shared = lock(&self.shared);
+        shared.receiver_dropped = true;
+
+        if let Some(waker) = shared.sender_waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    use actix_rt::time::sleep;
+    use static_assertions::assert_impl_all;
+
+    use super::*;
+    use crate::body::to_bytes;
+
+    assert_impl_all!(BodySender: Send, Sync);
+    assert_impl_all!(ChannelBody: MessageBody, Send, Sync);
+
+    #[actix_rt::test]
+    async fn ordered_with_back_pressure() {
+        let (mut tx, mut body) = channel(1);
+        let sent = Arc::new(AtomicUsize::new(0));
+
+        let producer = actix_rt::spawn({
+            let sent = Arc::clone(&sent);
+
+            async move {
+                for chunk in ["one", "two", "three"] {
+                    tx.send(Bytes::from_static(chunk.as_bytes())).await.unwrap();
+                    sent.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        });
+
+        let mut chunks = Vec::new();
+
+        loop {
+            // read slowly, letting the producer fill the buffer
+            sleep(Duration::from_millis(20)).await;
+
+            assert!(lock(&body.shared).queue.len() <= 1);
+            assert!(sent.load(Ordering::SeqCst) <= chunks.len() + 1);
+
+            match poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await {
+                Some(chunk) => chunks.push(chunk.unwrap()),
+                None => break,
+            }
+        }
+
+        producer.await.unwrap();
+        assert_eq!(chunks, ["one", "two", "three"]);
+    }
+
+    #[actix_rt::test]
+    async fn dropped_sender_ends_body() {
+        let (mut tx, body) = channel(4);
+        tx.send(Bytes::from_static(b"hello")).await.unwrap();
+        tx.send(Bytes::new()).await.unwrap();
+        drop(tx);
+
+        assert_eq!(to_bytes(body).await.unwrap(), "hello");
+    }
+
+    #[actix_rt::test]
+    async fn abort_ends_body_with_error() {
+        let (mut tx, mut body) = channel(4);
+        tx.send(Bytes::from_static(b"partial")).await.unwrap();
+        tx.abort("producer failed");
+
+        let chunk = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), "partial");
+
+        let err = poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+            .await
+            .unwrap()
+            .unwrap_err();
+        assert!(err.to_string().contains("producer failed"));
+    }
+
+    #[actix_rt::test]
+    async fn send_fails_after_body_dropped() {
+        let (mut tx, body) = channel(1);
+        assert!(!tx.is_closed());
+        drop(body);
+
+        assert!(tx.is_closed());
+        let err = tx.send(Bytes::from_static(b"lost")).await.unwrap_err();
+        assert_eq!(err.0, "lost");
+    }
+}
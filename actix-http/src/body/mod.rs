@@ -7,6 +7,8 @@
 
 mod body_stream;
 mod boxed;
+mod buffer_and_size;
+mod channel;
 mod either;
 mod message_body;
 mod none;
@@ -16,6 +18,8 @@ mod utils;
 
 pub use self::body_stream::BodyStream;
 pub use self::boxed::BoxBody;
+pub use self::buffer_and_size::BufferAndSize;
+pub use self::channel::{channel, BodySender, ChannelBody, SendError};
 pub use self::either::EitherBody;
 pub use self::message_body::MessageBody;
 pub(crate) use self::message_body::MessageBodyMapErr;
@@ -5,9 +5,11 @@ use actix_service::{IntoServiceFactory, Service, ServiceFactory};
 
 use crate::{
     body::{BoxBody, MessageBody},
+    config::{DEFAULT_MAX_RESPONSE_HEADERS, DEFAULT_MAX_RESPONSE_HEADER_SIZE},
     h1::{self, ExpectHandler, H1Service, UpgradeHandler},
+    lifecycle::{ConnectionCloseHooks, ConnectionClosed},
     service::HttpService,
-    ConnectCallback, Extensions, KeepAlive, Request, Response, ServiceConfig,
+    ConnectCallback, DrainSignal, Extensions, KeepAlive, Request, Response, ServiceConfig,
 };
 
 /// An HTTP service builder.
@@ -17,8 +19,15 @@ pub struct HttpServiceBuilder<T, S, X = ExpectHandler, U = UpgradeHandler> {
     keep_alive: KeepAlive,
     client_request_timeout: Duration,
     client_disconnect_timeout: Duration,
+    client_body_timeout: Duration,
     secure: bool,
     local_addr: Option<net::SocketAddr>,
+    strict_host: bool,
+    drain: Option<(DrainSignal, u32)>,
+    h1_title_case: bool,
+    max_response_header_size: usize,
+    max_response_headers: usize,
+    close_hooks: ConnectionCloseHooks,
     expect: X,
     upgrade: Option<U>,
     on_connect_ext: Option<Rc<ConnectCallback<T>>>,
@@ -38,8 +47,15 @@ where
             keep_alive: KeepAlive::default(),
             client_request_timeout: Duration::from_secs(5),
             client_disconnect_timeout: Duration::ZERO,
+            client_body_timeout: Duration::ZERO,
             secure: false,
             local_addr: None,
+            strict_host: true,
+            drain: None,
+            h1_title_case: false,
+            max_response_header_size: DEFAULT_MAX_RESPONSE_HEADER_SIZE,
+            max_response_headers: DEFAULT_MAX_RESPONSE_HEADERS,
+            close_hooks: ConnectionCloseHooks::default(),
 
             // dispatcher parts
             expect: ExpectHandler,
@@ -124,6 +140,89 @@ where
         self.client_disconnect_timeout(dur)
     }
 
+    /// Set client request body timeout.
+    ///
+    /// Defines the longest time to wait between pieces of an HTTP/1 request body. If the client
+    /// sends no body data within this duration, reading the payload fails with
+    /// [`PayloadError::Timeout`](crate::error::PayloadError::Timeout), which handlers render as a
+    /// `408 Request Timeout` response, and the connection is closed after responding.
+    ///
+    /// A duration of zero disables the timeout.
+    ///
+    /// By default, the body timeout is disabled.
+    pub fn client_body_timeout(mut self, dur: Duration) -> Self {
+        self.client_body_timeout = dur;
+        self
+    }
+
+    /// Set whether HTTP/1 requests are checked for a consistent `Host` header.
+    ///
+    /// When enabled, the following requests are rejected with a `400 Bad Request` response and the
+    /// connection is closed:
+    /// - HTTP/1.1 requests without a `Host` header;
+    /// - requests with more than one `Host` header;
+    /// - requests with an absolute-form target whose authority differs from the `Host` header.
+    ///
+    /// Disable this only to serve legacy clients that do not send a valid `Host` header.
+    ///
+    /// By default, strict host validation is enabled.
+    pub fn strict_host_validation(mut self, enabled: bool) -> Self {
+        self.strict_host = enabled;
+        self
+    }
+
+    /// Respond to new HTTP/1 requests on existing connections with "503 Service Unavailable" once
+    /// `signal` is raised.
+    ///
+    /// Intended for graceful shutdown: requests that are already being handled complete normally,
+    /// but later requests on kept-alive connections are answered immediately with a `Retry-After`
+    /// header set to `retry_after` seconds, and the connection is closed, so that load balancers can
+    /// retry them elsewhere.
+    pub fn reject_while_draining(mut self, signal: DrainSignal, retry_after: u32) -> Self {
+        self.drain = Some((signal, retry_after));
+        self
+    }
+
+    /// Set whether HTTP/1 response header names are written in title case.
+    ///
+    /// Header names are stored in lowercase, which is how they are written by default. When
+    /// enabled, each word of a header name is capitalized instead, such as `Content-Type`, for
+    /// legacy clients that match header names case-sensitively. Names constructed using
+    /// [`PreserveCase::from_static_preserve_case`](crate::header::PreserveCase) are written with
+    /// their exact casing. HTTP/2 responses are not affected, since HTTP/2 requires lowercase
+    /// header names.
+    ///
+    /// By default, header names are written in lowercase.
+    pub fn h1_title_case_headers(mut self, enabled: bool) -> Self {
+        self.h1_title_case = enabled;
+        self
+    }
+
+    /// Set the limit on the size of HTTP/1 response header blocks, in bytes.
+    ///
+    /// Counts each header line written from the response's header map, including its name, value,
+    /// and separators. A response whose headers exceed the limit is not written; a
+    /// `500 Internal Server Error` response is sent in its place and an error is logged, rather
+    /// than sending a header block that peers and proxies are likely to reject.
+    ///
+    /// By default, the limit is 256 KiB.
+    pub fn max_response_header_size(mut self, bytes: usize) -> Self {
+        self.max_response_header_size = bytes;
+        self
+    }
+
+    /// Set the limit on the number of HTTP/1 response header lines.
+    ///
+    /// Each value of a header with multiple values, such as `Set-Cookie`, counts as one line.
+    /// Responses exceeding the limit are handled as described in
+    /// [`max_response_header_size`](Self::max_response_header_size).
+    ///
+    /// By default, the limit is 1024 header lines.
+    pub fn max_response_headers(mut self, count: usize) -> Self {
+        self.max_response_headers = count;
+        self
+    }
+
     /// Provide service for `EXPECT: 100-Continue` support.
     ///
     /// Service get called with request that contains `EXPECT` header.
@@ -140,8 +239,15 @@ where
             keep_alive: self.keep_alive,
             client_request_timeout: self.client_request_timeout,
             client_disconnect_timeout: self.client_disconnect_timeout,
+            client_body_timeout: self.client_body_timeout,
             secure: self.secure,
             local_addr: self.local_addr,
+            strict_host: self.strict_host,
+            drain: self.drain,
+            h1_title_case: self.h1_title_case,
+            max_response_header_size: self.max_response_header_size,
+            max_response_headers: self.max_response_headers,
+            close_hooks: self.close_hooks,
             expect: expect.into_factory(),
             upgrade: self.upgrade,
             on_connect_ext: self.on_connect_ext,
@@ -164,8 +270,15 @@ where
             keep_alive: self.keep_alive,
             client_request_timeout: self.client_request_timeout,
             client_disconnect_timeout: self.client_disconnect_timeout,
+            client_body_timeout: self.client_body_timeout,
             secure: self.secure,
             local_addr: self.local_addr,
+            strict_host: self.strict_host,
+            drain: self.drain,
+            h1_title_case: self.h1_title_case,
+            max_response_header_size: self.max_response_header_size,
+            max_response_headers: self.max_response_headers,
+            close_hooks: self.close_hooks,
             expect: self.expect,
             upgrade: Some(upgrade.into_factory()),
             on_connect_ext: self.on_connect_ext,
@@ -186,6 +299,35 @@ where
         self
     }
 
+    /// Adds a callback run when the server finishes with a connection.
+    ///
+    /// The callback receives the protocol, peer address, number of requests, and duration of the
+    /// connection. Callbacks run in registration order. An HTTP/1 connection is also finished with
+    /// once it is handed to the [`upgrade`](Self::upgrade) service.
+    pub fn on_connection_close<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&ConnectionClosed) + 'static,
+    {
+        self.close_hooks.0.push(Rc::new(f));
+        self
+    }
+
+    fn service_config(&self) -> ServiceConfig {
+        ServiceConfig::with_host_validation(
+            self.keep_alive,
+            self.client_request_timeout,
+            self.client_disconnect_timeout,
+            self.secure,
+            self.local_addr,
+            self.strict_host,
+        )
+        .with_client_body_timeout(self.client_body_timeout)
+        .with_drain(self.drain.clone())
+        .with_title_case_headers(self.h1_title_case)
+        .with_response_header_limits(self.max_response_header_size, self.max_response_headers)
+        .with_connection_close_hooks(self.close_hooks.clone())
+    }
+
     /// Finish service configuration and create a HTTP Service for HTTP/1 protocol.
     pub fn h1<F, B>(self, service: F) -> H1Service<T, S, B, X, U>
     where
@@ -195,13 +337,7 @@ where
         S::InitError: fmt::Debug,
         S::Response: Into<Response<B>>,
     {
-        let cfg = ServiceConfig::new(
-            self.keep_alive,
-            self.client_request_timeout,
-            self.client_disconnect_timeout,
-            self.secure,
-            self.local_addr,
-        );
+        let cfg = self.service_config();
 
         H1Service::with_config(cfg, service.into_factory())
             .expect(self.expect)
@@ -220,13 +356,7 @@ where
 
         B: MessageBody + 'static,
     {
-        let cfg = ServiceConfig::new(
-            self.keep_alive,
-            self.client_request_timeout,
-            self.client_disconnect_timeout,
-            self.secure,
-            self.local_addr,
-        );
+        let cfg = self.service_config();
 
         crate::h2::H2Service::with_config(cfg, service.into_factory())
             .on_connect_ext(self.on_connect_ext)
@@ -242,13 +372,7 @@ where
 
         B: MessageBody + 'static,
     {
-        let cfg = ServiceConfig::new(
-            self.keep_alive,
-            self.client_request_timeout,
-            self.client_disconnect_timeout,
-            self.secure,
-            self.local_addr,
-        );
+        let cfg = self.service_config();
 
         HttpService::with_config(cfg, service.into_factory())
             .expect(self.expect)
@@ -6,7 +6,17 @@ use std::{
 
 use bytes::BytesMut;
 
-use crate::{date::DateService, KeepAlive};
+use crate::{
+    date::DateService,
+    lifecycle::{ConnectionCloseHooks, ConnectionClosed},
+    DrainSignal, KeepAlive,
+};
+
+/// Default limit on the size of the header block of an HTTP/1 response, in bytes.
+pub(crate) const DEFAULT_MAX_RESPONSE_HEADER_SIZE: usize = 256 * 1024;
+
+/// Default limit on the number of header lines in an HTTP/1 response.
+pub(crate) const DEFAULT_MAX_RESPONSE_HEADERS: usize = 1024;
 
 /// HTTP service configuration.
 #[derive(Debug, Clone)]
@@ -19,6 +29,13 @@ struct Inner {
     client_disconnect_timeout: Duration,
     secure: bool,
     local_addr: Option<std::net::SocketAddr>,
+    strict_host: bool,
+    client_body_timeout: Duration,
+    drain: Option<(DrainSignal, u32)>,
+    h1_title_case: bool,
+    max_response_header_size: usize,
+    max_response_headers: usize,
+    close_hooks: ConnectionCloseHooks,
     date_service: DateService,
 }
 
@@ -42,6 +59,24 @@ impl ServiceConfig {
         client_disconnect_timeout: Duration,
         secure: bool,
         local_addr: Option<net::SocketAddr>,
+    ) -> ServiceConfig {
+        Self::with_host_validation(
+            keep_alive,
+            client_request_timeout,
+            client_disconnect_timeout,
+            secure,
+            local_addr,
+            true,
+        )
+    }
+
+    pub(crate) fn with_host_validation(
+        keep_alive: KeepAlive,
+        client_request_timeout: Duration,
+        client_disconnect_timeout: Duration,
+        secure: bool,
+        local_addr: Option<net::SocketAddr>,
+        strict_host: bool,
     ) -> ServiceConfig {
         ServiceConfig(Rc::new(Inner {
             keep_alive: keep_alive.normalize(),
@@ -49,10 +84,71 @@ impl ServiceConfig {
             client_disconnect_timeout,
             secure,
             local_addr,
+            strict_host,
+            client_body_timeout: Duration::ZERO,
+            drain: None,
+            h1_title_case: false,
+            max_response_header_size: DEFAULT_MAX_RESPONSE_HEADER_SIZE,
+            max_response_headers: DEFAULT_MAX_RESPONSE_HEADERS,
+            close_hooks: ConnectionCloseHooks::default(),
             date_service: DateService::new(),
         }))
     }
 
+    /// Sets the drain signal and the `Retry-After` seconds used when rejecting requests.
+    ///
+    /// Must be called before the config is cloned.
+    pub(crate) fn with_drain(mut self, drain: Option<(DrainSignal, u32)>) -> ServiceConfig {
+        Rc::get_mut(&mut self.0)
+            .expect("config should not be shared yet")
+            .drain = drain;
+        self
+    }
+
+    /// Sets the time to wait for request body data before failing the payload.
+    ///
+    /// Must be called before the config is cloned.
+    pub(crate) fn with_client_body_timeout(mut self, timeout: Duration) -> ServiceConfig {
+        Rc::get_mut(&mut self.0)
+            .expect("config should not be shared yet")
+            .client_body_timeout = timeout;
+        self
+    }
+
+    /// Sets whether HTTP/1 response header names are written in title case.
+    ///
+    /// Must be called before the config is cloned.
+    pub(crate) fn with_title_case_headers(mut self, enabled: bool) -> ServiceConfig {
+        Rc::get_mut(&mut self.0)
+            .expect("config should not be shared yet")
+            .h1_title_case = enabled;
+        self
+    }
+
+    /// Sets the limits on the size, in bytes, and number of HTTP/1 response header lines.
+    ///
+    /// Must be called before the config is cloned.
+    pub(crate) fn with_response_header_limits(
+        mut self,
+        size: usize,
+        count: usize,
+    ) -> ServiceConfig {
+        let inner = Rc::get_mut(&mut self.0).expect("config should not be shared yet");
+        inner.max_response_header_size = size;
+        inner.max_response_headers = count;
+        self
+    }
+
+    /// Sets the callbacks run when a connection is closed.
+    ///
+    /// Must be called before the config is cloned.
+    pub(crate) fn with_connection_close_hooks(mut self, hooks: ConnectionCloseHooks) -> Self {
+        Rc::get_mut(&mut self.0)
+            .expect("config should not be shared yet")
+            .close_hooks = hooks;
+        self
+    }
+
     /// Returns `true` if connection is secure (i.e., using TLS / HTTPS).
     #[inline]
     pub fn secure(&self) -> bool {
@@ -67,6 +163,63 @@ impl ServiceConfig {
         self.0.local_addr
     }
 
+    /// Returns `true` if HTTP/1 requests with a missing, repeated, or inconsistent `Host` header
+    /// are rejected.
+    ///
+    /// See [`HttpServiceBuilder::strict_host_validation`](crate::HttpServiceBuilder::strict_host_validation).
+    #[inline]
+    pub fn strict_host_validation(&self) -> bool {
+        self.0.strict_host
+    }
+
+    /// Returns `true` if HTTP/1 response header names are written in title case.
+    ///
+    /// See [`HttpServiceBuilder::h1_title_case_headers`](crate::HttpServiceBuilder::h1_title_case_headers).
+    #[inline]
+    pub fn h1_title_case_headers(&self) -> bool {
+        self.0.h1_title_case
+    }
+
+    /// Returns the limit on the size of HTTP/1 response header blocks, in bytes.
+    ///
+    /// See [`HttpServiceBuilder::max_response_header_size`](crate::HttpServiceBuilder::max_response_header_size).
+    #[inline]
+    pub fn max_response_header_size(&self) -> usize {
+        self.0.max_response_header_size
+    }
+
+    /// Returns true if any callbacks are run when a connection is closed.
+    #[inline]
+    pub(crate) fn has_connection_close_hooks(&self) -> bool {
+        !self.0.close_hooks.0.is_empty()
+    }
+
+    /// Runs the connection close callbacks, in registration order.
+    pub(crate) fn connection_closed(&self, event: &ConnectionClosed) {
+        for hook in &self.0.close_hooks.0 {
+            hook(event);
+        }
+    }
+
+    /// Returns the limit on the number of HTTP/1 response header lines.
+    ///
+    /// See [`HttpServiceBuilder::max_response_headers`](crate::HttpServiceBuilder::max_response_headers).
+    #[inline]
+    pub fn max_response_headers(&self) -> usize {
+        self.0.max_response_headers
+    }
+
+    /// Returns the `Retry-After` seconds to reject new requests with, if the server is draining.
+    ///
+    /// See [`HttpServiceBuilder::reject_while_draining`](crate::HttpServiceBuilder::reject_while_draining).
+    #[inline]
+    pub(crate) fn draining_retry_after(&self) -> Option<u32> {
+        match self.0.drain {
+            Some((ref signal, retry_after)) if signal.is_draining() => Some(retry_after),
+            _ => None,
+        }
+    }
+
     /// Connection keep-alive setting.
     #[inline]
     pub fn keep_alive(&self) -> KeepAlive {
@@ -94,6 +247,23 @@ impl ServiceConfig {
         (timeout != Duration::ZERO).then(|| self.now() + timeout)
     }
 
+    /// Creates a time object representing the deadline for the client to send the next piece of an
+    /// HTTP/1 request body.
+    ///
+    /// Returns `None` if the body timeout is disabled.
+    ///
+    /// See [`HttpServiceBuilder::client_body_timeout`](crate::HttpServiceBuilder::client_body_timeout).
+    pub fn client_body_deadline(&self) -> Option<Instant> {
+        let timeout = self.0.client_body_timeout;
+        (timeout != Duration::ZERO).then(|| self.now() + timeout)
+    }
+
+    /// Returns the time to wait for HTTP/1 request body data, or zero if the wait is unlimited.
+    #[inline]
+    pub(crate) fn client_body_timeout(&self) -> Duration {
+        self.0.client_body_timeout
+    }
+
     /// Creates a time object representing the deadline for the client to disconnect.
     pub fn client_disconnect_deadline(&self) -> Option<Instant> {
         let timeout = self.0.client_disconnect_timeout;
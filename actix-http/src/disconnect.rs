@@ -0,0 +1,171 @@
+//! Client disconnect notification.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// A signal, found in request extensions, that is raised when the client disconnects.
+///
+/// Dispatchers insert a `DisconnectSignal` into the extensions of every request they handle. The
+/// HTTP/1 dispatcher raises it when the client closes or resets its connection while the request
+/// is being handled; the HTTP/2 dispatcher raises it when the client resets the request's stream.
+///
+/// Note that an HTTP/1 client that half-closes its connection after sending a request is also
+/// reported as disconnected.
+#[derive(Clone, Default)]
+pub struct DisconnectSignal {
+    inner: Rc<Inner>,
+}
+
+#[derive(Default)]
+struct Inner {
+    disconnected: Cell<bool>,
+    next_id: Cell<usize>,
+    wakers: RefCell<Vec<(usize, Waker)>>,
+}
+
+impl DisconnectSignal {
+    /// Constructs a new signal that has not been raised.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the client has disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        self.inner.disconnected.get()
+    }
+
+    /// Returns a future that resolves once the client has disconnected.
+    pub fn disconnected(&self) -> Disconnected {
+        let id = self.inner.next_id.get();
+        self.inner.next_id.set(id.wrapping_add(1));
+
+        Disconnected {
+            signal: self.clone(),
+            id,
+        }
+    }
+
+    /// Raises the signal, waking all pending [`Disconnected`] futures.
+    pub fn notify(&self) {
+        if self.inner.disconnected.replace(true) {
+            return;
+        }
+
+        for (_, waker) in self.inner.wakers.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl fmt::Debug for DisconnectSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DisconnectSignal")
+            .field("disconnected", &self.is_disconnected())
+            .finish()
+    }
+}
+
+/// Future returned by [`DisconnectSignal::disconnected`].
+#[must_use = "futures do nothing unless polled"]
+pub struct Disconnected {
+    signal: DisconnectSignal,
+    id: usize,
+}
+
+impl Future for Disconnected {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = &self.signal.inner;
+
+        if inner.disconnected.get() {
+            return Poll::Ready(());
+        }
+
+        let mut wakers = inner.wakers.borrow_mut();
+
+        match wakers.iter_mut().find(|(id, _)| *id == self.id) {
+            Some((_, waker)) => {
+                if !waker.will_wake(cx.waker()) {
+                    *waker = cx.waker().clone();
+                }
+            }
+            None => wakers.push((self.id, cx.waker().clone())),
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Disconnected {
+    fn drop(&mut self) {
+        let id = self.id;
+
+        if let Ok(mut wakers) = self.signal.inner.wakers.try_borrow_mut() {
+            wakers.retain(|(waker_id, _)| *waker_id != id);
+        }
+    }
+}
+
+impl fmt::Debug for Disconnected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Disconnected")
+            .field("signal", &self.signal)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::task::Poll;
+
+    use actix_utils::future::poll_fn;
+
+    use super::*;
+
+    #[actix_rt::test]
+    async fn resolves_after_notify() {
+        let signal = DisconnectSignal::new();
+        let mut fut = Box::pin(signal.disconnected());
+
+        poll_fn(|cx| {
+            assert!(fut.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        assert_eq!(signal.inner.wakers.borrow().len(), 1);
+
+        signal.clone().notify();
+        assert!(signal.is_disconnected());
+        assert!(signal.inner.wakers.borrow().is_empty());
+        fut.await;
+
+        // futures created after the signal is raised resolve immediately
+        signal.disconnected().await;
+    }
+
+    #[actix_rt::test]
+    async fn dropped_futures_unregister() {
+        let signal = DisconnectSignal::new();
+
+        for _ in 0..3 {
+            let mut fut = Box::pin(signal.disconnected());
+
+            poll_fn(|cx| {
+                assert!(fut.as_mut().poll(cx).is_pending());
+                assert!(fut.as_mut().poll(cx).is_pending());
+                Poll::Ready(())
+            })
+            .await;
+        }
+
+        assert!(signal.inner.wakers.borrow().is_empty());
+        assert!(!signal.is_disconnected());
+    }
+}
@@ -0,0 +1,63 @@
+//! Server drain notification.
+
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A signal, shared by all workers of a server, that is raised when the server starts draining.
+///
+/// Once raised, HTTP/1 dispatchers configured using
+/// [`HttpServiceBuilder::reject_while_draining`](crate::HttpServiceBuilder::reject_while_draining)
+/// respond to new requests on existing connections with "503 Service Unavailable" and close the
+/// connection. Requests that are already being handled are not affected.
+#[derive(Clone, Default)]
+pub struct DrainSignal {
+    draining: Arc<AtomicBool>,
+}
+
+impl DrainSignal {
+    /// Constructs a new signal that has not been raised.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns true if the server is draining.
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::Acquire)
+    }
+
+    /// Raises the signal. Draining can not be stopped once started.
+    pub fn start(&self) {
+        self.draining.store(true, Ordering::Release);
+    }
+}
+
+impl fmt::Debug for DrainSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DrainSignal")
+            .field("draining", &self.is_draining())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_between_clones() {
+        let signal = DrainSignal::new();
+        let clone = signal.clone();
+        assert!(!clone.is_draining());
+
+        signal.start();
+        assert!(clone.is_draining());
+
+        signal.start();
+        assert!(signal.is_draining());
+    }
+}
@@ -1,6 +1,8 @@
 //! Error and Result module
 
-use std::{error::Error as StdError, fmt, io, str::Utf8Error, string::FromUtf8Error};
+use std::{
+    error::Error as StdError, fmt, io, str::Utf8Error, string::FromUtf8Error, time::Duration,
+};
 
 use derive_more::{Display, Error, From};
 use http::{uri::InvalidUri, StatusCode};
@@ -176,10 +178,14 @@ pub enum ParseError {
     #[display(fmt = "Invalid Header provided")]
     Header,
 
-    /// A message head is too large to be reasonable.
+    /// A message head is too large to be reasonable, such as one with too many header fields.
     #[display(fmt = "Message head is too large")]
     TooLarge,
 
+    /// A request target is too long to be reasonable.
+    #[display(fmt = "Uri is too long")]
+    UriTooLong,
+
     /// A message reached EOF, but is not complete.
     #[display(fmt = "Message is incomplete")]
     Incomplete,
@@ -253,10 +259,17 @@ impl From<ParseError> for Response<BoxBody> {
 }
 
 /// A set of errors that can occur during payload parsing.
+///
+/// Variants document whether a response can still reach the client after they occur. Even when it
+/// can, the server closes the connection after responding, unless noted otherwise, since the rest
+/// of the request body is not read.
 #[derive(Debug, Display)]
 #[non_exhaustive]
 pub enum PayloadError {
     /// A payload reached EOF, but is not complete.
+    ///
+    /// The client closed or reset the connection before sending the whole payload, so no response
+    /// can be sent.
     #[display(
         fmt = "A payload reached EOF, but is not complete. Inner error: {:?}",
         _0
@@ -264,17 +277,49 @@ pub enum PayloadError {
     Incomplete(Option<io::Error>),
 
     /// Content encoding stream corruption.
+    ///
+    /// The connection is still usable.
     #[display(fmt = "Can not decode content-encoding.")]
     EncodingCorrupted,
 
     /// Payload reached size limit.
+    ///
+    /// Returned by limits that do not report their value; see [`LimitExceeded`] otherwise. A
+    /// response can still be sent.
+    ///
+    /// [`LimitExceeded`]: Self::LimitExceeded
     #[display(fmt = "Payload reached size limit.")]
     Overflow,
 
+    /// Payload is larger than the configured limit of `limit` bytes.
+    ///
+    /// A response can still be sent.
+    #[display(fmt = "Payload reached size limit of {} bytes.", limit)]
+    LimitExceeded {
+        /// Limit that was exceeded, in bytes.
+        limit: usize,
+    },
+
     /// Payload length is unknown.
     #[display(fmt = "Payload length is unknown.")]
     UnknownLength,
 
+    /// Chunked transfer encoding of the payload is malformed.
+    ///
+    /// A response can still be sent, but the connection cannot be reused since the end of the
+    /// payload cannot be found.
+    #[display(fmt = "Malformed chunked encoding: {}", _0)]
+    MalformedChunkedEncoding(io::Error),
+
+    /// The client did not send any payload data for `timeout`.
+    ///
+    /// A response can still be sent.
+    #[display(fmt = "No payload data received for {:?}.", timeout)]
+    Timeout {
+        /// Time waited for payload data.
+        timeout: Duration,
+    },
+
     /// HTTP/2 payload error.
     #[cfg(feature = "http2")]
     #[display(fmt = "{}", _0)]
@@ -292,7 +337,10 @@ impl std::error::Error for PayloadError {
             PayloadError::Incomplete(Some(err)) => Some(err),
             PayloadError::EncodingCorrupted => None,
             PayloadError::Overflow => None,
+            PayloadError::LimitExceeded { .. } => None,
             PayloadError::UnknownLength => None,
+            PayloadError::MalformedChunkedEncoding(err) => Some(err),
+            PayloadError::Timeout { .. } => None,
             #[cfg(feature = "http2")]
             PayloadError::Http2Payload(err) => Some(err),
             PayloadError::Io(err) => Some(err),
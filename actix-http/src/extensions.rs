@@ -1,17 +1,129 @@
 use std::{
     any::{Any, TypeId},
-    fmt,
+    fmt, mem,
 };
 
 use ahash::AHashMap;
 
+/// Number of entries stored inline before spilling to a hash map.
+///
+/// Most requests only ever carry a handful of extensions so this avoids allocating a map for them.
+const INLINE_CAP: usize = 3;
+
 /// A type map for request extensions.
 ///
 /// All entries into this map must be owned types (or static references).
 #[derive(Default)]
 pub struct Extensions {
+    storage: Storage,
+}
+
+/// Backing storage for [`Extensions`].
+enum Storage {
+    /// Small number of entries kept in a fixed array, found using a linear scan.
+    Inline([Option<(TypeId, Box<dyn Any>)>; INLINE_CAP]),
+
     /// Use AHasher with a std HashMap with for faster lookups on the small `TypeId` keys.
-    map: AHashMap<TypeId, Box<dyn Any>>,
+    Hashed(AHashMap<TypeId, Box<dyn Any>>),
+}
+
+impl Default for Storage {
+    fn default() -> Self {
+        Storage::Inline(Default::default())
+    }
+}
+
+impl Storage {
+    fn get(&self, id: TypeId) -> Option<&Box<dyn Any>> {
+        match self {
+            Storage::Inline(entries) => entries
+                .iter()
+                .flatten()
+                .find(|(key, _)| *key == id)
+                .map(|(_, val)| val),
+
+            Storage::Hashed(map) => map.get(&id),
+        }
+    }
+
+    fn get_mut(&mut self, id: TypeId) -> Option<&mut Box<dyn Any>> {
+        match self {
+            Storage::Inline(entries) => entries
+                .iter_mut()
+                .flatten()
+                .find(|(key, _)| *key == id)
+                .map(|(_, val)| val),
+
+            Storage::Hashed(map) => map.get_mut(&id),
+        }
+    }
+
+    fn insert(&mut self, id: TypeId, val: Box<dyn Any>) -> Option<Box<dyn Any>> {
+        if let Some(existing) = self.get_mut(id) {
+            return Some(mem::replace(existing, val));
+        }
+
+        self.get_or_insert_with(id, || val);
+        None
+    }
+
+    /// Returns the value stored for `id`, inserting the result of `default` if there is none.
+    fn get_or_insert_with(
+        &mut self,
+        id: TypeId,
+        default: impl FnOnce() -> Box<dyn Any>,
+    ) -> &mut Box<dyn Any> {
+        // index of either the existing entry or the first free slot
+        let slot = match self {
+            Storage::Inline(entries) => entries
+                .iter()
+                .position(|entry| matches!(entry, Some((key, _)) if *key == id))
+                .or_else(|| entries.iter().position(Option::is_none)),
+
+            Storage::Hashed(_) => None,
+        };
+
+        if slot.is_none() {
+            self.spill();
+        }
+
+        match self {
+            Storage::Inline(entries) => {
+                let idx = slot.expect("inline storage has a free slot");
+                &mut entries[idx].get_or_insert_with(|| (id, default())).1
+            }
+
+            Storage::Hashed(map) => map.entry(id).or_insert_with(default),
+        }
+    }
+
+    /// Moves all inline entries into a hash map.
+    fn spill(&mut self) {
+        if let Storage::Inline(entries) = self {
+            let mut map = AHashMap::with_capacity(INLINE_CAP * 2);
+            map.extend(entries.iter_mut().filter_map(Option::take));
+            *self = Storage::Hashed(map);
+        }
+    }
+
+    fn remove(&mut self, id: TypeId) -> Option<Box<dyn Any>> {
+        match self {
+            Storage::Inline(entries) => entries
+                .iter_mut()
+                .find(|entry| matches!(entry, Some((key, _)) if *key == id))
+                .and_then(Option::take)
+                .map(|(_, val)| val),
+
+            Storage::Hashed(map) => map.remove(&id),
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            Storage::Inline(entries) => entries.iter_mut().for_each(|entry| *entry = None),
+            Storage::Hashed(map) => map.clear(),
+        }
+    }
 }
 
 impl Extensions {
@@ -19,7 +131,7 @@ impl Extensions {
     #[inline]
     pub fn new() -> Extensions {
         Extensions {
-            map: AHashMap::new(),
+            storage: Storage::default(),
         }
     }
 
@@ -36,7 +148,7 @@ impl Extensions {
     /// assert_eq!(*map.get::<u32>().unwrap(), 2u32);
     /// ```
     pub fn insert<T: 'static>(&mut self, val: T) -> Option<T> {
-        self.map
+        self.storage
             .insert(TypeId::of::<T>(), Box::new(val))
             .and_then(downcast_owned)
     }
@@ -52,7 +164,7 @@ impl Extensions {
     /// assert!(map.contains::<u32>());
     /// ```
     pub fn contains<T: 'static>(&self) -> bool {
-        self.map.contains_key(&TypeId::of::<T>())
+        self.storage.get(TypeId::of::<T>()).is_some()
     }
 
     /// Get a reference to an item of a given type.
@@ -64,8 +176,8 @@ impl Extensions {
     /// assert_eq!(map.get::<u32>(), Some(&1u32));
     /// ```
     pub fn get<T: 'static>(&self) -> Option<&T> {
-        self.map
-            .get(&TypeId::of::<T>())
+        self.storage
+            .get(TypeId::of::<T>())
             .and_then(|boxed| boxed.downcast_ref())
     }
 
@@ -78,11 +190,29 @@ impl Extensions {
     /// assert_eq!(map.get_mut::<u32>(), Some(&mut 1u32));
     /// ```
     pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
-        self.map
-            .get_mut(&TypeId::of::<T>())
+        self.storage
+            .get_mut(TypeId::of::<T>())
             .and_then(|boxed| boxed.downcast_mut())
     }
 
+    /// Get a mutable reference to an item of a given type, inserting the result of `default` if
+    /// no such item is stored.
+    ///
+    /// ```
+    /// # use actix_http::Extensions;
+    /// let mut map = Extensions::new();
+    /// assert_eq!(*map.get_or_insert_with(|| 1u32), 1u32);
+    ///
+    /// *map.get_or_insert_with(|| 1u32) += 1;
+    /// assert_eq!(map.get::<u32>(), Some(&2u32));
+    /// ```
+    pub fn get_or_insert_with<T: 'static, F: FnOnce() -> T>(&mut self, default: F) -> &mut T {
+        self.storage
+            .get_or_insert_with(TypeId::of::<T>(), || Box::new(default()))
+            .downcast_mut()
+            .expect("extensions entries are keyed by their type")
+    }
+
     /// Remove an item from the map of a given type.
     ///
     /// If an item of this type was already stored, it will be returned.
@@ -98,7 +228,9 @@ impl Extensions {
     /// assert!(!map.contains::<u32>());
     /// ```
     pub fn remove<T: 'static>(&mut self) -> Option<T> {
-        self.map.remove(&TypeId::of::<T>()).and_then(downcast_owned)
+        self.storage
+            .remove(TypeId::of::<T>())
+            .and_then(downcast_owned)
     }
 
     /// Clear the `Extensions` of all inserted extensions.
@@ -115,12 +247,24 @@ impl Extensions {
     /// ```
     #[inline]
     pub fn clear(&mut self) {
-        self.map.clear();
+        self.storage.clear();
     }
 
     /// Extends self with the items from another `Extensions`.
     pub fn extend(&mut self, other: Extensions) {
-        self.map.extend(other.map);
+        match other.storage {
+            Storage::Inline(entries) => {
+                for (id, val) in IntoIterator::into_iter(entries).flatten() {
+                    self.storage.insert(id, val);
+                }
+            }
+
+            Storage::Hashed(map) => {
+                for (id, val) in map {
+                    self.storage.insert(id, val);
+                }
+            }
+        }
     }
 }
 
@@ -277,4 +421,88 @@ mod tests {
         assert_eq!(extensions.get(), Some(&20u8));
         assert_eq!(extensions.get_mut(), Some(&mut 20u8));
     }
+
+    #[test]
+    fn spills_to_hashed_storage() {
+        let mut map = Extensions::new();
+
+        map.insert::<i8>(8);
+        map.insert::<i16>(16);
+        map.insert::<i32>(32);
+        assert!(matches!(map.storage, Storage::Inline(_)));
+
+        map.insert::<i64>(64);
+        assert!(matches!(map.storage, Storage::Hashed(_)));
+
+        assert_eq!(map.get::<i8>(), Some(&8));
+        assert_eq!(map.get::<i16>(), Some(&16));
+        assert_eq!(map.get::<i32>(), Some(&32));
+        assert_eq!(map.get::<i64>(), Some(&64));
+
+        assert_eq!(map.insert::<i8>(-8), Some(8));
+        assert_eq!(map.remove::<i16>(), Some(16));
+        assert!(!map.contains::<i16>());
+        assert_eq!(map.get::<i8>(), Some(&-8));
+    }
+
+    #[test]
+    fn inline_slots_are_reused() {
+        let mut map = Extensions::new();
+
+        map.insert::<i8>(8);
+        map.insert::<i16>(16);
+        map.insert::<i32>(32);
+
+        // replacing an existing item must not take up another slot
+        assert_eq!(map.insert::<i8>(-8), Some(8));
+        assert!(matches!(map.storage, Storage::Inline(_)));
+
+        assert_eq!(map.remove::<i16>(), Some(16));
+        map.insert::<u8>(8);
+        assert!(matches!(map.storage, Storage::Inline(_)));
+
+        assert_eq!(map.get::<i8>(), Some(&-8));
+        assert_eq!(map.get::<i16>(), None);
+        assert_eq!(map.get::<u8>(), Some(&8));
+    }
+
+    #[test]
+    fn test_get_or_insert_with() {
+        let mut map = Extensions::new();
+
+        assert_eq!(*map.get_or_insert_with(|| 1u32), 1);
+        assert_eq!(*map.get_or_insert_with::<u32, _>(|| unreachable!()), 1);
+
+        *map.get_or_insert_with(|| 0u32) += 1;
+        assert_eq!(map.get::<u32>(), Some(&2));
+
+        // fill inline storage so the next new type spills
+        map.insert::<i8>(8);
+        map.insert::<i16>(16);
+        assert_eq!(*map.get_or_insert_with(|| 64i64), 64);
+        assert!(matches!(map.storage, Storage::Hashed(_)));
+
+        assert_eq!(*map.get_or_insert_with::<u32, _>(|| unreachable!()), 2);
+        assert_eq!(map.get::<i8>(), Some(&8));
+    }
+
+    #[test]
+    fn extend_spilled_storage() {
+        let mut extensions = Extensions::new();
+        extensions.insert(5i32);
+
+        let mut other = Extensions::new();
+        other.insert(1u8);
+        other.insert(2u16);
+        other.insert(3u32);
+        other.insert(4u64);
+        other.insert(15i32);
+        assert!(matches!(other.storage, Storage::Hashed(_)));
+
+        extensions.extend(other);
+
+        assert_eq!(extensions.get(), Some(&15i32));
+        assert_eq!(extensions.get(), Some(&1u8));
+        assert_eq!(extensions.get(), Some(&4u64));
+    }
 }
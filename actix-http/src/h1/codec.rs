@@ -1,16 +1,19 @@
-use std::{fmt, io};
+use std::{convert::TryFrom as _, fmt, io};
 
 use actix_codec::{Decoder, Encoder};
 use bitflags::bitflags;
 use bytes::BytesMut;
-use http::{Method, Version};
+use http::{
+    uri::{Authority, Scheme},
+    Method, Uri, Version,
+};
 
 use super::{
     decoder::{self, PayloadDecoder, PayloadItem, PayloadType},
     encoder, Message, MessageType,
 };
 use crate::{
-    body::BodySize, error::ParseError, ConnectionType, Request, Response, ServiceConfig,
+    body::BodySize, error::ParseError, header, ConnectionType, Request, Response, ServiceConfig,
 };
 
 bitflags! {
@@ -121,6 +124,10 @@ impl Decoder for Codec {
                 None => None,
             })
         } else if let Some((req, payload)) = self.decoder.decode(src)? {
+            if self.config.strict_host_validation() {
+                validate_host(&req)?;
+            }
+
             let head = req.head();
             self.flags.set(Flags::HEAD, head.method == Method::HEAD);
             self.version = head.version;
@@ -147,6 +154,49 @@ impl Decoder for Codec {
     }
 }
 
+/// Checks that a request has a single `Host` header that agrees with its request target.
+fn validate_host(req: &Request) -> Result<(), ParseError> {
+    let head = req.head();
+    let mut hosts = head.headers.get_all(header::HOST);
+    let host = hosts.next();
+
+    if hosts.next().is_some() {
+        return Err(ParseError::Header);
+    }
+
+    match (host, head.uri.authority()) {
+        (None, _) if head.version == Version::HTTP_11 => Err(ParseError::Header),
+
+        (Some(host), Some(authority))
+            if !host_matches(host.as_bytes(), authority, &head.uri) =>
+        {
+            Err(ParseError::Header)
+        }
+
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a `Host` header names the same host and port as the authority of `uri`.
+///
+/// Userinfo in the request target is ignored, and a missing port is taken to be the default port
+/// of the request target's scheme. The `Host` header itself may not carry userinfo.
+fn host_matches(host: &[u8], authority: &Authority, uri: &Uri) -> bool {
+    let host = match Authority::try_from(host) {
+        Ok(host) if !host.as_str().contains('@') => host,
+        _ => return false,
+    };
+
+    let default_port = match uri.scheme() {
+        Some(scheme) if *scheme == Scheme::HTTP => Some(80),
+        Some(scheme) if *scheme == Scheme::HTTPS => Some(443),
+        _ => None,
+    };
+
+    host.host().eq_ignore_ascii_case(authority.host())
+        && host.port_u16().or(default_port) == authority.port_u16().or(default_port)
+}
+
 impl Encoder<Message<(Response<()>, BodySize)>> for Codec {
     type Error = io::Error;
 
@@ -202,8 +252,10 @@ mod tests {
     use bytes::BytesMut;
     use http::Method;
 
+    use std::time::Duration;
+
     use super::*;
-    use crate::HttpMessage as _;
+    use crate::{HttpMessage as _, KeepAlive};
 
     #[actix_rt::test]
     async fn test_http_request_chunked_payload_and_next_message() {
@@ -211,6 +263,7 @@ mod tests {
 
         let mut buf = BytesMut::from(
             "GET /test HTTP/1.1\r\n\
+             host: example.com\r\n\
              transfer-encoding: chunked\r\n\r\n",
         );
         let item = codec.decode(&mut buf).unwrap().unwrap();
@@ -222,6 +275,7 @@ mod tests {
         buf.extend(
             b"4\r\ndata\r\n4\r\nline\r\n0\r\n\r\n\
                POST /test2 HTTP/1.1\r\n\
+               host: example.com\r\n\
                transfer-encoding: chunked\r\n\r\n"
                 .iter(),
         );
@@ -241,4 +295,64 @@ mod tests {
         assert_eq!(*req.method(), Method::POST);
         assert!(req.chunked().unwrap());
     }
+
+    #[actix_rt::test]
+    async fn strict_host_validation() {
+        fn decode(config: ServiceConfig, req: &str) -> Result<(), ParseError> {
+            let mut codec = Codec::new(config);
+            let mut buf = BytesMut::from(req);
+            codec.decode(&mut buf).map(|item| assert!(item.is_some()))
+        }
+
+        let strict = ServiceConfig::default;
+        assert!(strict().strict_host_validation());
+
+        decode(strict(), "GET / HTTP/1.1\r\nhost: example.com\r\n\r\n").unwrap();
+        decode(strict(), "GET / HTTP/1.0\r\n\r\n").unwrap();
+        decode(
+            strict(),
+            "GET http://example.com/ HTTP/1.1\r\nhost: EXAMPLE.com\r\n\r\n",
+        )
+        .unwrap();
+        decode(
+            strict(),
+            "GET http://user@a.com/ HTTP/1.1\r\nhost: a.com\r\n\r\n",
+        )
+        .unwrap();
+        decode(
+            strict(),
+            "GET http://a.com:80/ HTTP/1.1\r\nhost: a.com\r\n\r\n",
+        )
+        .unwrap();
+        decode(
+            strict(),
+            "GET https://a.com/ HTTP/1.1\r\nhost: a.com:443\r\n\r\n",
+        )
+        .unwrap();
+
+        let rejected = [
+            "GET / HTTP/1.1\r\n\r\n",
+            "GET / HTTP/1.1\r\nhost: a.com\r\nhost: b.com\r\n\r\n",
+            "GET / HTTP/1.0\r\nhost: a.com\r\nhost: a.com\r\n\r\n",
+            "GET http://a.com/ HTTP/1.1\r\nhost: b.com\r\n\r\n",
+            "GET http://a.com:8080/ HTTP/1.1\r\nhost: a.com\r\n\r\n",
+            "GET https://a.com/ HTTP/1.1\r\nhost: a.com:80\r\n\r\n",
+            "GET http://a.com/ HTTP/1.1\r\nhost: user@a.com\r\n\r\n",
+            "GET http://a.com/ HTTP/1.1\r\n\r\n",
+        ];
+
+        for req in rejected {
+            assert!(matches!(decode(strict(), req), Err(ParseError::Header)));
+
+            let legacy = ServiceConfig::with_host_validation(
+                KeepAlive::default(),
+                Duration::from_secs(5),
+                Duration::ZERO,
+                false,
+                None,
+                false,
+            );
+            decode(legacy, req).unwrap();
+        }
+    }
 }
@@ -14,6 +14,9 @@ use crate::{error::ParseError, header::HeaderMap, ConnectionType, Request, Respo
 pub(crate) const MAX_BUFFER_SIZE: usize = 131_072;
 const MAX_HEADERS: usize = 96;
 
+/// Longest request target accepted by [`Uri`].
+const MAX_URI_LEN: usize = u16::MAX as usize - 1;
+
 /// Incoming message decoder
 pub(crate) struct MessageDecoder<T: MessageType>(PhantomData<T>);
 
@@ -88,6 +91,10 @@ pub(crate) trait MessageType: Sized {
         {
             let headers = self.headers_mut();
 
+            // size the map once up-front; header sets too large for the linear list are hashed from
+            // the start instead of spilling part way through
+            headers.reserve(raw_headers.len());
+
             for idx in raw_headers.iter() {
                 let name = HeaderName::from_bytes(&slice[idx.name.0..idx.name.1]).unwrap();
 
@@ -249,7 +256,13 @@ impl MessageType for Request {
                 httparse::Status::Complete(len) => {
                     let method = Method::from_bytes(req.method.unwrap().as_bytes())
                         .map_err(|_| ParseError::Method)?;
-                    let uri = Uri::try_from(req.path.unwrap())?;
+
+                    let path = req.path.unwrap();
+                    if path.len() > MAX_URI_LEN {
+                        return Err(ParseError::UriTooLong);
+                    }
+
+                    let uri = Uri::try_from(path)?;
                     let version = if req.version.unwrap() == 1 {
                         Version::HTTP_11
                     } else {
@@ -263,7 +276,13 @@ impl MessageType for Request {
                 httparse::Status::Partial => {
                     return if src.len() >= MAX_BUFFER_SIZE {
                         trace!("MAX_BUFFER_SIZE unprocessed data reached, closing");
-                        Err(ParseError::TooLarge)
+
+                        // a buffer without any line ends is still in the request line
+                        if !src.contains(&b'\n') {
+                            Err(ParseError::UriTooLong)
+                        } else {
+                            Err(ParseError::TooLarge)
+                        }
                     } else {
                         // Return None to notify more read are needed for parsing request
                         Ok(None)
@@ -842,6 +861,28 @@ mod tests {
         assert_eq!(val[1], "c2=cookie2");
     }
 
+    #[test]
+    fn header_map_sized_by_line_count() {
+        use crate::header::map::{Inner, INLINE_CAP};
+
+        let req = parse_ready!(&mut BytesMut::from(
+            "GET /test HTTP/1.1\r\nHost: example.com\r\nAccept: */*\r\n\r\n"
+        ));
+        assert!(matches!(req.headers().inner, Inner::Inline(_)));
+
+        let mut raw = String::from("GET /test HTTP/1.1\r\nHost: example.com\r\n");
+        for i in 0..11 {
+            raw.push_str(&format!("x-header-{}: v\r\n", i));
+        }
+        raw.push_str("\r\n");
+
+        let req = parse_ready!(&mut BytesMut::from(raw.as_str()));
+
+        assert_eq!(req.headers().len_keys(), 12);
+        assert!(matches!(req.headers().inner, Inner::Hashed(_)));
+        assert!(req.headers().capacity() > INLINE_CAP);
+    }
+
     #[test]
     fn test_conn_default_1_0() {
         let req = parse_ready!(&mut BytesMut::from("GET /test HTTP/1.0\r\n\r\n"));
@@ -1065,6 +1106,41 @@ mod tests {
         expect_parse_err!(&mut BytesMut::from("GET //get HT/11\r\n\r\n"));
     }
 
+    #[test]
+    fn oversized_request_heads() {
+        let mut reader = MessageDecoder::<Request>::default();
+
+        // complete request line with a target longer than `Uri` accepts
+        let mut buf = BytesMut::from(
+            format!("GET /{} HTTP/1.1\r\n\r\n", "a".repeat(MAX_URI_LEN)).as_str(),
+        );
+        assert!(matches!(
+            reader.decode(&mut buf),
+            Err(ParseError::UriTooLong)
+        ));
+
+        // buffer filled before the request line ends
+        let mut buf = BytesMut::from(format!("GET /{}", "a".repeat(MAX_BUFFER_SIZE)).as_str());
+        assert!(matches!(
+            reader.decode(&mut buf),
+            Err(ParseError::UriTooLong)
+        ));
+
+        // buffer filled while reading header fields
+        let mut buf = BytesMut::from(
+            format!("GET / HTTP/1.1\r\nx-big: {}", "a".repeat(MAX_BUFFER_SIZE)).as_str(),
+        );
+        assert!(matches!(reader.decode(&mut buf), Err(ParseError::TooLarge)));
+
+        // too many header fields
+        let mut buf = BytesMut::from("GET / HTTP/1.1\r\n");
+        for idx in 0..=MAX_HEADERS {
+            buf.extend_from_slice(format!("x-header-{}: value\r\n", idx).as_bytes());
+        }
+        buf.extend_from_slice(b"\r\n");
+        assert!(matches!(reader.decode(&mut buf), Err(ParseError::TooLarge)));
+    }
+
     #[test]
     fn test_response_http10_read_until_eof() {
         let mut buf = BytesMut::from("HTTP/1.0 200 Ok\r\n\r\ntest data");
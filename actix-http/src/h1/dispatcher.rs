@@ -21,13 +21,17 @@ use crate::{
     body::{BodySize, BoxBody, MessageBody},
     config::ServiceConfig,
     error::{DispatchError, ParseError, PayloadError},
+    header,
+    lifecycle::{ConnectionLifecycle, ResponseProgress},
     service::HttpFlow,
-    Error, Extensions, OnConnectData, Request, Response, StatusCode,
+    ConnectionType, DisconnectSignal, Error, Extensions, OnConnectData, Protocol, Request,
+    Response, ResponseRoute, StatusCode,
 };
 
 use super::{
     codec::Codec,
     decoder::MAX_BUFFER_SIZE,
+    encoder::ResponseHeadersTooLarge,
     payload::{Payload, PayloadSender, PayloadStatus},
     timer::TimerState,
     Message, MessageType,
@@ -150,6 +154,10 @@ pin_project! {
         conn_data: Option<Rc<Extensions>>,
         config: ServiceConfig,
         error: Option<DispatchError>,
+        disconnect: DisconnectSignal,
+        lifecycle: ConnectionLifecycle,
+        // progress of the response being sent, if it is observed
+        response: Option<ResponseProgress>,
 
         #[pin]
         pub(super) state: State<S, B, X>,
@@ -160,6 +168,7 @@ pin_project! {
         head_timer: TimerState,
         ka_timer: TimerState,
         shutdown_timer: TimerState,
+        body_timer: TimerState,
 
         pub(super) io: Option<T>,
         read_buf: BytesMut,
@@ -266,6 +275,9 @@ where
                     conn_data: conn_data.0.map(Rc::new),
                     config: config.clone(),
                     error: None,
+                    disconnect: DisconnectSignal::new(),
+                    lifecycle: ConnectionLifecycle::new(&config, Protocol::Http1, peer_addr),
+                    response: None,
 
                     state: State::None,
                     payload: None,
@@ -276,6 +288,7 @@ where
                     shutdown_timer: TimerState::new(
                         config.client_disconnect_deadline().is_some(),
                     ),
+                    body_timer: TimerState::new(config.client_body_deadline().is_some()),
 
                     io: Some(io),
                     read_buf: BytesMut::with_capacity(HW_BUFFER_SIZE),
@@ -321,6 +334,7 @@ where
 
         this.flags
             .insert(Flags::READ_DISCONNECT | Flags::WRITE_DISCONNECT);
+        this.disconnect.notify();
 
         if let Some(mut payload) = this.payload.take() {
             payload.set_error(PayloadError::Incomplete(None));
@@ -359,22 +373,54 @@ where
 
     fn send_response_inner(
         self: Pin<&mut Self>,
-        res: Response<()>,
+        mut res: Response<()>,
         body: &impl MessageBody,
     ) -> Result<BodySize, DispatchError> {
         let this = self.project();
 
-        let size = body.size();
+        let mut size = body.size();
+        let mut status = res.status();
+        let route = res.extensions_mut().remove::<ResponseRoute>();
+        let progress = ResponseProgress::take(&mut res);
 
-        this.codec
-            .encode(Message::Item((res, size)), this.write_buf)
-            .map_err(|err| {
-                if let Some(mut payload) = this.payload.take() {
-                    payload.set_error(PayloadError::Incomplete(None));
-                }
+        let mut encoded = this
+            .codec
+            .encode(Message::Item((res, size)), this.write_buf);
+
+        if let Some(too_large) = encoded
+            .as_ref()
+            .err()
+            .and_then(ResponseHeadersTooLarge::from_io)
+        {
+            let route = route.and_then(|route| route.describe());
+
+            error!(
+                "{} (route: {}); responding with 500 Internal Server Error instead",
+                too_large,
+                route.as_deref().unwrap_or("unknown"),
+            );
+
+            // the original response's body is dropped by the caller
+            size = BodySize::Sized(0);
+            status = StatusCode::INTERNAL_SERVER_ERROR;
+            let res = Response::with_body(status, ());
+            encoded = this
+                .codec
+                .encode(Message::Item((res, size)), this.write_buf);
+        }
+
+        encoded.map_err(|err| {
+            if let Some(mut payload) = this.payload.take() {
+                payload.set_error(PayloadError::Incomplete(None));
+            }
 
-                DispatchError::Io(err)
-            })?;
+            DispatchError::Io(err)
+        })?;
+
+        if let Some(progress) = progress {
+            progress.headers_written(status);
+            *this.response = Some(progress);
+        }
 
         Ok(size)
     }
@@ -388,6 +434,10 @@ where
         let mut this = self.project();
         this.state.set(match size {
             BodySize::None | BodySize::Sized(0) => {
+                if let Some(progress) = this.response.take() {
+                    progress.finish();
+                }
+
                 this.flags.insert(Flags::FINISHED);
                 State::None
             }
@@ -406,6 +456,10 @@ where
         let mut this = self.project();
         this.state.set(match size {
             BodySize::None | BodySize::Sized(0) => {
+                if let Some(progress) = this.response.take() {
+                    progress.finish();
+                }
+
                 this.flags.insert(Flags::FINISHED);
                 State::None
             }
@@ -500,6 +554,10 @@ where
                     while this.write_buf.len() < super::payload::MAX_BUFFER_SIZE {
                         match body.as_mut().poll_next(cx) {
                             Poll::Ready(Some(Ok(item))) => {
+                                if let Some(progress) = this.response.as_mut() {
+                                    progress.body_chunk(item.len());
+                                }
+
                                 this.codec
                                     .encode(Message::Chunk(Some(item)), this.write_buf)?;
                             }
@@ -507,6 +565,10 @@ where
                             Poll::Ready(None) => {
                                 this.codec.encode(Message::Chunk(None), this.write_buf)?;
 
+                                if let Some(progress) = this.response.take() {
+                                    progress.finish();
+                                }
+
                                 // payload stream finished.
                                 // set state to None and handle next message
                                 this.state.set(State::None);
@@ -516,6 +578,8 @@ where
                             }
 
                             Poll::Ready(Some(Err(err))) => {
+                                // reports the body as incomplete
+                                this.response.take();
                                 this.flags.insert(Flags::FINISHED);
                                 return Err(DispatchError::Body(err.into()));
                             }
@@ -537,6 +601,10 @@ where
                     while this.write_buf.len() < super::payload::MAX_BUFFER_SIZE {
                         match body.as_mut().poll_next(cx) {
                             Poll::Ready(Some(Ok(item))) => {
+                                if let Some(progress) = this.response.as_mut() {
+                                    progress.body_chunk(item.len());
+                                }
+
                                 this.codec
                                     .encode(Message::Chunk(Some(item)), this.write_buf)?;
                             }
@@ -544,6 +612,10 @@ where
                             Poll::Ready(None) => {
                                 this.codec.encode(Message::Chunk(None), this.write_buf)?;
 
+                                if let Some(progress) = this.response.take() {
+                                    progress.finish();
+                                }
+
                                 // payload stream finished
                                 // set state to None and handle next message
                                 this.state.set(State::None);
@@ -553,6 +625,8 @@ where
                             }
 
                             Poll::Ready(Some(Err(err))) => {
+                                // reports the body as incomplete
+                                this.response.take();
                                 this.flags.insert(Flags::FINISHED);
                                 return Err(DispatchError::Body(
                                     Error::new_body().with_cause(err).into(),
@@ -707,12 +781,30 @@ where
 
                     match msg {
                         Message::Item(mut req) => {
+                            this.lifecycle.request_received();
+
                             // head timer only applies to first request on connection
                             this.head_timer.clear(line!());
 
+                            // while draining, new requests are rejected and the connection closed
+                            if let Some(retry_after) = this.config.draining_retry_after() {
+                                trace!("server is draining; returning 503 response");
+
+                                let mut res =
+                                    Response::with_body(StatusCode::SERVICE_UNAVAILABLE, ());
+                                res.head_mut().set_connection_type(ConnectionType::Close);
+                                res.headers_mut()
+                                    .insert(header::RETRY_AFTER, retry_after.into());
+                                this.messages.push_back(DispatcherMessage::Error(res));
+
+                                this.flags.insert(Flags::READ_DISCONNECT);
+                                break;
+                            }
+
                             req.head_mut().peer_addr = *this.peer_addr;
 
                             req.conn_data = this.conn_data.as_ref().map(Rc::clone);
+                            req.extensions.get_mut().insert(this.disconnect.clone());
 
                             match this.codec.message_type() {
                                 // request has no payload
@@ -736,6 +828,14 @@ where
                                     let (sender, payload) = Payload::create(false);
                                     *req.payload() = crate::Payload::H1 { payload };
                                     *this.payload = Some(sender);
+
+                                    if let Some(deadline) = this.config.client_body_deadline() {
+                                        this.body_timer.set_and_init(
+                                            cx,
+                                            sleep_until(deadline.into()),
+                                            line!(),
+                                        );
+                                    }
                                 }
                             }
 
@@ -751,6 +851,14 @@ where
                         Message::Chunk(Some(chunk)) => {
                             if let Some(ref mut payload) = this.payload {
                                 payload.feed_data(chunk);
+
+                                if let Some(deadline) = this.config.client_body_deadline() {
+                                    this.body_timer.set_and_init(
+                                        cx,
+                                        sleep_until(deadline.into()),
+                                        line!(),
+                                    );
+                                }
                             } else {
                                 error!("Internal server error: unexpected payload chunk");
                                 this.flags.insert(Flags::READ_DISCONNECT);
@@ -765,6 +873,10 @@ where
                         Message::Chunk(None) => {
                             if let Some(mut payload) = this.payload.take() {
                                 payload.feed_eof();
+
+                                if this.body_timer.is_enabled() {
+                                    this.body_timer.clear(line!());
+                                }
                             } else {
                                 error!("Internal server error: unexpected eof");
                                 this.flags.insert(Flags::READ_DISCONNECT);
@@ -782,6 +894,22 @@ where
                 // break and wait for more read
                 Ok(None) => break,
 
+                // the only I/O errors raised while decoding are chunked encoding errors
+                Err(ParseError::Io(err)) if this.payload.is_some() => {
+                    trace!("malformed chunked encoding: {}", &err);
+
+                    // the handler owning the payload responds to the error; since the end of the
+                    // payload is unknown, the connection is closed after that response
+                    if let Some(mut payload) = this.payload.take() {
+                        let payload_err = io::Error::new(err.kind(), err.to_string());
+                        payload.set_error(PayloadError::MalformedChunkedEncoding(payload_err));
+                    }
+
+                    this.flags.insert(Flags::READ_DISCONNECT);
+                    *this.error = Some(DispatchError::Io(err));
+                    break;
+                }
+
                 Err(ParseError::Io(err)) => {
                     trace!("I/O error: {}", &err);
                     self.as_mut().client_disconnected();
@@ -790,22 +918,25 @@ where
                     break;
                 }
 
-                Err(ParseError::TooLarge) => {
-                    trace!("request head was too big; returning 431 response");
+                Err(err @ (ParseError::TooLarge | ParseError::UriTooLong)) => {
+                    // request heads that overflow limits return a 431 or 414 error
+                    let status = match err {
+                        ParseError::UriTooLong => StatusCode::URI_TOO_LONG,
+                        _ => StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
+                    };
+
+                    trace!("request head was too big; returning {} response", status);
 
                     if let Some(mut payload) = this.payload.take() {
                         payload.set_error(PayloadError::Overflow);
                     }
 
-                    // request heads that overflow buffer size return a 431 error
-                    this.messages
-                        .push_back(DispatcherMessage::Error(Response::with_body(
-                            StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE,
-                            (),
-                        )));
+                    let mut res = Response::with_body(status, ());
+                    res.head_mut().set_connection_type(ConnectionType::Close);
+                    this.messages.push_back(DispatcherMessage::Error(res));
 
                     this.flags.insert(Flags::READ_DISCONNECT);
-                    *this.error = Some(ParseError::TooLarge.into());
+                    *this.error = Some(err.into());
 
                     break;
                 }
@@ -902,6 +1033,43 @@ where
         Ok(())
     }
 
+    fn poll_body_timer(mut self: Pin<&mut Self>, cx: &mut Context<'_>) {
+        let this = self.as_mut().project();
+
+        let expired = match this.body_timer {
+            TimerState::Active { timer } => timer.as_mut().poll(cx).is_ready(),
+            _ => false,
+        };
+
+        if !expired {
+            return;
+        }
+
+        match this.payload {
+            // client did not send body data in time; fail the payload so the handler can respond
+            Some(payload) if payload.need_read(cx) == PayloadStatus::Read => {
+                trace!("timed out waiting for request body data");
+
+                let timeout = this.config.client_body_timeout();
+                payload.set_error(PayloadError::Timeout { timeout });
+                *this.payload = None;
+
+                this.flags.insert(Flags::READ_DISCONNECT);
+                this.body_timer.clear(line!());
+            }
+
+            // handler is not reading the body yet, so the client is not at fault; wait again
+            Some(_) => {
+                if let Some(deadline) = this.config.client_body_deadline() {
+                    this.body_timer
+                        .set_and_init(cx, sleep_until(deadline.into()), line!());
+                }
+            }
+
+            None => this.body_timer.clear(line!()),
+        }
+    }
+
     fn poll_shutdown_timer(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
@@ -923,13 +1091,14 @@ where
         Ok(())
     }
 
-    /// Poll head, keep-alive, and disconnect timer.
+    /// Poll head, keep-alive, body, and disconnect timer.
     fn poll_timers(
         mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Result<(), DispatchError> {
         self.as_mut().poll_head_timer(cx)?;
         self.as_mut().poll_ka_timer(cx)?;
+        self.as_mut().poll_body_timer(cx);
         self.as_mut().poll_shutdown_timer(cx)?;
 
         Ok(())
@@ -1102,7 +1271,13 @@ where
                     }
                 } else {
                     // read from I/O stream and fill read buffer
-                    let should_disconnect = inner.as_mut().read_available(cx)?;
+                    let should_disconnect = match inner.as_mut().read_available(cx) {
+                        Ok(should_disconnect) => should_disconnect,
+                        Err(err) => {
+                            inner.disconnect.notify();
+                            return Poll::Ready(Err(err));
+                        }
+                    };
 
                     // after reading something from stream, clear keep-alive timer
                     if !inner.read_buf.is_empty() && inner.flags.contains(Flags::KEEP_ALIVE) {
@@ -1129,8 +1304,15 @@ where
                         // I/O stream should to be closed
                         let inner = inner.as_mut().project();
                         inner.flags.insert(Flags::READ_DISCONNECT);
+                        inner.disconnect.notify();
+
+                        // only upgrade-like streams end at EOF; other payloads are cut short
                         if let Some(mut payload) = inner.payload.take() {
-                            payload.feed_eof();
+                            if inner.codec.message_type() == MessageType::Stream {
+                                payload.feed_eof();
+                            } else {
+                                payload.set_error(PayloadError::Incomplete(None));
+                            }
                         }
                     };
 
@@ -110,7 +110,9 @@ async fn late_request() {
         // polls: initial
         assert_eq!(h1.poll_count, 1);
 
-        buf.extend_read_buf("GET /abcd HTTP/1.1\r\nConnection: close\r\n\r\n");
+        buf.extend_read_buf(
+            "GET /abcd HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        );
 
         match h1.as_mut().poll(cx) {
             Poll::Pending => panic!("second poll should not be pending"),
@@ -146,7 +148,7 @@ async fn late_request() {
 
 #[actix_rt::test]
 async fn oneshot_connection() {
-    let buf = TestBuffer::new("GET /abcd HTTP/1.1\r\n\r\n");
+    let buf = TestBuffer::new("GET /abcd HTTP/1.1\r\nHost: localhost\r\n\r\n");
 
     let cfg = ServiceConfig::new(
         KeepAlive::Disabled,
@@ -207,7 +209,7 @@ async fn oneshot_connection() {
 
 #[actix_rt::test]
 async fn keep_alive_timeout() {
-    let buf = TestBuffer::new("GET /abcd HTTP/1.1\r\n\r\n");
+    let buf = TestBuffer::new("GET /abcd HTTP/1.1\r\nHost: localhost\r\n\r\n");
 
     let cfg = ServiceConfig::new(
         KeepAlive::Timeout(Duration::from_millis(200)),
@@ -286,7 +288,7 @@ async fn keep_alive_timeout() {
 
 #[actix_rt::test]
 async fn keep_alive_follow_up_req() {
-    let mut buf = TestBuffer::new("GET /abcd HTTP/1.1\r\n\r\n");
+    let mut buf = TestBuffer::new("GET /abcd HTTP/1.1\r\nHost: localhost\r\n\r\n");
 
     let cfg = ServiceConfig::new(
         KeepAlive::Timeout(Duration::from_millis(500)),
@@ -365,7 +367,7 @@ async fn keep_alive_follow_up_req() {
     lazy(|cx| {
         buf.extend_read_buf(
             "\
-            GET /efg HTTP/1.1\r\n\
+            GET /efg HTTP/1.1\r\nHost: localhost\r\n\
             Connection: close\r\n\
             \r\n\r\n",
         );
@@ -447,8 +449,8 @@ async fn pipelining_ok_then_ok() {
     lazy(|cx| {
         let buf = TestBuffer::new(
             "\
-                GET /abcd HTTP/1.1\r\n\r\n\
-                GET /def HTTP/1.1\r\n\r\n\
+                GET /abcd HTTP/1.1\r\nHost: localhost\r\n\r\n\
+                GET /def HTTP/1.1\r\nHost: localhost\r\n\r\n\
                 ",
         );
 
@@ -512,12 +514,82 @@ async fn pipelining_ok_then_ok() {
     .await;
 }
 
+#[actix_rt::test]
+async fn pipelining_does_not_leak_request_state() {
+    lazy(|cx| {
+        // each request reuses the previous request's pooled head
+        let buf = TestBuffer::new(
+            "\
+                POST /first?a=1 HTTP/1.0\r\nHost: localhost\r\nX-First: 1\r\n\
+                Content-Length: 0\r\n\r\n\
+                GET /second HTTP/1.1\r\nHost: localhost\r\n\r\n\
+                ",
+        );
+
+        let cfg = ServiceConfig::new(
+            KeepAlive::Disabled,
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+            false,
+            None,
+        );
+
+        let service = fn_service(|req: Request| {
+            let seen = req.extensions().get::<&str>().copied();
+            req.extensions_mut().insert("seen");
+
+            let body = format!(
+                "[{} {} {:?} headers={} seen={:?}]",
+                req.method(),
+                req.uri(),
+                req.version(),
+                req.headers().len(),
+                seen,
+            );
+
+            ready(Ok::<_, Error>(Response::ok().set_body(body)))
+        });
+
+        let services = HttpFlow::new(service, ExpectHandler, None);
+
+        let h1 = Dispatcher::<_, _, _, _, UpgradeHandler>::new(
+            buf.clone(),
+            services,
+            cfg,
+            None,
+            OnConnectData::default(),
+        );
+
+        pin!(h1);
+
+        match h1.as_mut().poll(cx) {
+            Poll::Pending => panic!("first poll should not be pending"),
+            Poll::Ready(res) => assert!(res.is_ok()),
+        }
+
+        let res = buf.write_buf_slice();
+        let res = str::from_utf8(&res).unwrap();
+
+        assert!(
+            res.contains("[POST /first?a=1 HTTP/1.0 headers=3 seen=None]"),
+            "{}",
+            res
+        );
+        assert!(
+            res.contains("[GET /second HTTP/1.1 headers=1 seen=None]"),
+            "{}",
+            res
+        );
+    })
+    .await;
+}
+
 #[actix_rt::test]
 async fn pipelining_ok_then_bad() {
     lazy(|cx| {
         let buf = TestBuffer::new(
             "\
-                GET /abcd HTTP/1.1\r\n\r\n\
+                GET /abcd HTTP/1.1\r\nHost: localhost\r\n\r\n\
                 GET /def HTTP/1\r\n\r\n\
                 ",
         );
@@ -605,7 +677,7 @@ async fn expect_handling() {
 
         buf.extend_read_buf(
             "\
-                POST /upload HTTP/1.1\r\n\
+                POST /upload HTTP/1.1\r\nHost: localhost\r\n\
                 Content-Length: 5\r\n\
                 Expect: 100-continue\r\n\
                 \r\n\
@@ -682,7 +754,7 @@ async fn expect_eager() {
 
         buf.extend_read_buf(
             "\
-                POST /upload HTTP/1.1\r\n\
+                POST /upload HTTP/1.1\r\nHost: localhost\r\n\
                 Content-Length: 5\r\n\
                 Expect: 100-continue\r\n\
                 \r\n\
@@ -765,7 +837,7 @@ async fn upgrade_handling() {
 
         buf.extend_read_buf(
             "\
-                GET /ws HTTP/1.1\r\n\
+                GET /ws HTTP/1.1\r\nHost: localhost\r\n\
                 Connection: Upgrade\r\n\
                 Upgrade: websocket\r\n\
                 \r\n\
@@ -793,6 +865,7 @@ async fn handler_drop_payload() {
     let mut buf = TestBuffer::new(http_msg(
         r"
         POST /drop-payload HTTP/1.1
+        Host: localhost
         Content-Length: 3
         
         abc
@@ -855,6 +928,7 @@ async fn handler_drop_payload() {
         buf.extend_read_buf(http_msg(
             r"
             POST /drop-payload HTTP/1.1
+            Host: localhost
             Content-Length: 200
             
             abc
@@ -974,3 +1048,185 @@ fn http_msg_creates_msg() {
         "GET / HTTP/1.1\r\nContent-Length: 3\r\n\r\n"
     );
 }
+
+#[actix_rt::test]
+async fn draining_rejects_requests() {
+    lazy(|cx| {
+        let buf = TestBuffer::new("GET /abcd HTTP/1.1\r\nHost: localhost\r\n\r\n");
+
+        let drain = crate::DrainSignal::new();
+
+        let cfg = ServiceConfig::new(
+            KeepAlive::Timeout(Duration::from_secs(5)),
+            Duration::from_millis(1),
+            Duration::from_millis(1),
+            false,
+            None,
+        )
+        .with_drain(Some((drain.clone(), 30)));
+
+        drain.start();
+
+        let services = HttpFlow::new(echo_path_service(), ExpectHandler, None);
+
+        let h1 = Dispatcher::<_, _, _, _, UpgradeHandler>::new(
+            buf.clone(),
+            services,
+            cfg,
+            None,
+            OnConnectData::default(),
+        );
+
+        pin!(h1);
+
+        match h1.as_mut().poll(cx) {
+            Poll::Pending => panic!("first poll should not be pending"),
+            Poll::Ready(res) => assert!(res.is_ok()),
+        }
+
+        let res = buf.write_buf_slice();
+        let res = str::from_utf8(&res).unwrap();
+
+        assert!(res.starts_with("HTTP/1.1 503 Service Unavailable\r\n"), "{}", res);
+        assert!(res.contains("connection: close\r\n"), "{}", res);
+        assert!(res.contains("retry-after: 30\r\n"), "{}", res);
+        assert!(!res.contains("/abcd"), "{}", res);
+    })
+    .await;
+}
+
+#[actix_rt::test]
+async fn response_header_limits() {
+    fn cookie_service(
+    ) -> impl Service<Request, Response = Response<&'static str>, Error = Error> {
+        fn_service(|_req: Request| {
+            let mut res = Response::with_body(StatusCode::OK, "body");
+
+            for cookie in ["a=1", "b=2", "c=3"] {
+                res.headers_mut().append(
+                    crate::header::SET_COOKIE,
+                    crate::header::HeaderValue::from_static(cookie),
+                );
+            }
+
+            ready(Ok::<_, Error>(res))
+        })
+    }
+
+    async fn respond(size: usize, count: usize) -> String {
+        lazy(|cx| {
+            let buf = TestBuffer::new("GET / HTTP/1.1\r\nHost: localhost\r\n\r\n");
+
+            let cfg = ServiceConfig::default().with_response_header_limits(size, count);
+            let services = HttpFlow::new(cookie_service(), ExpectHandler, None);
+
+            let h1 = Dispatcher::<_, _, _, _, UpgradeHandler>::new(
+                buf.clone(),
+                services,
+                cfg,
+                None,
+                OnConnectData::default(),
+            );
+
+            pin!(h1);
+            assert!(h1.as_mut().poll(cx).is_pending());
+
+            let res = buf.write_buf_slice();
+            str::from_utf8(&res).unwrap().to_owned()
+        })
+        .await
+    }
+
+    // each cookie line is 17 bytes
+    let res = respond(51, 3).await;
+    assert!(res.starts_with("HTTP/1.1 200 OK\r\n"), "{}", res);
+    assert!(res.contains("set-cookie: c=3\r\n"), "{}", res);
+    assert!(res.ends_with("\r\n\r\nbody"), "{}", res);
+
+    for (size, count) in [(50, 3), (51, 2)] {
+        let res = respond(size, count).await;
+        assert!(
+            res.starts_with("HTTP/1.1 500 Internal Server Error\r\n"),
+            "{}",
+            res
+        );
+        assert!(res.contains("content-length: 0\r\n"), "{}", res);
+        assert!(!res.contains("set-cookie"), "{}", res);
+        assert!(!res.contains("body"), "{}", res);
+    }
+}
+
+#[actix_rt::test]
+async fn lifecycle_events() {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::{lifecycle::ConnectionCloseHooks, ObserveResponse, ResponseObserver};
+
+    type Events = Rc<RefCell<Vec<String>>>;
+
+    struct Recorder(Events);
+
+    impl ResponseObserver for Recorder {
+        fn headers_written(&self, status: StatusCode) {
+            self.0.borrow_mut().push(format!("headers {}", status.as_u16()));
+        }
+
+        fn body_finished(&self, bytes: u64, complete: bool) {
+            self.0.borrow_mut().push(format!("body {} {}", bytes, complete));
+        }
+    }
+
+    let events = Events::default();
+
+    let close_events = Rc::clone(&events);
+    let hooks = ConnectionCloseHooks(vec![Rc::new(move |conn: &crate::ConnectionClosed| {
+        close_events
+            .borrow_mut()
+            .push(format!("closed {:?} {}", conn.protocol(), conn.requests()));
+    })]);
+
+    let res_events = Rc::clone(&events);
+    let svc = fn_service(move |_req: Request| {
+        let mut res = Response::with_body(StatusCode::OK, "body");
+        res.extensions_mut()
+            .insert(ObserveResponse(Rc::new(Recorder(Rc::clone(&res_events)))));
+        ready(Ok::<_, Error>(res))
+    });
+
+    lazy(|cx| {
+        let buf = TestBuffer::new(
+            "GET / HTTP/1.1\r\nHost: localhost\r\n\r\n\
+            GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        );
+
+        let cfg = ServiceConfig::default().with_connection_close_hooks(hooks);
+        let services = HttpFlow::new(svc, ExpectHandler, None);
+
+        let h1 = Dispatcher::<_, _, _, _, UpgradeHandler>::new(
+            buf.clone(),
+            services,
+            cfg,
+            None,
+            OnConnectData::default(),
+        );
+
+        pin!(h1);
+
+        match h1.as_mut().poll(cx) {
+            Poll::Ready(res) => assert!(res.is_ok(), "{:?}", res.err()),
+            Poll::Pending => panic!("dispatcher should close the connection"),
+        }
+    })
+    .await;
+
+    assert_eq!(
+        *events.borrow(),
+        [
+            "headers 200",
+            "body 4 true",
+            "headers 200",
+            "body 4 true",
+            "closed Http1 2",
+        ]
+    );
+}
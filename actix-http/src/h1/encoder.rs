@@ -1,5 +1,5 @@
 use std::{
-    cmp,
+    cmp, fmt,
     io::{self, Write as _},
     marker::PhantomData,
     ptr::copy_nonoverlapping,
@@ -11,13 +11,42 @@ use bytes::{BufMut, BytesMut};
 use crate::{
     body::BodySize,
     header::{
-        map::Value, HeaderMap, HeaderName, CONNECTION, CONTENT_LENGTH, DATE, TRANSFER_ENCODING,
+        map::Value, preserved_case, HeaderMap, HeaderName, CONNECTION, CONTENT_LENGTH, DATE,
+        TRANSFER_ENCODING,
     },
     helpers, ConnectionType, RequestHeadType, Response, ServiceConfig, StatusCode, Version,
 };
 
 const AVERAGE_HEADER_SIZE: usize = 30;
 
+/// Error for response header blocks that exceed the limits set in [`ServiceConfig`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResponseHeadersTooLarge {
+    /// Header lines exceed the given size, in bytes.
+    Size(usize),
+
+    /// More than the given number of header lines.
+    Count(usize),
+}
+
+impl ResponseHeadersTooLarge {
+    /// Returns the limit that was exceeded, if `err` was caused by one.
+    pub(crate) fn from_io(err: &io::Error) -> Option<Self> {
+        err.get_ref()?.downcast_ref::<Self>().copied()
+    }
+}
+
+impl fmt::Display for ResponseHeadersTooLarge {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Size(limit) => write!(f, "response headers exceed {} bytes", limit),
+            Self::Count(limit) => write!(f, "response has more than {} header lines", limit),
+        }
+    }
+}
+
+impl std::error::Error for ResponseHeadersTooLarge {}
+
 #[derive(Debug)]
 pub(crate) struct MessageEncoder<T: MessageType> {
     #[allow(dead_code)]
@@ -61,16 +90,14 @@ pub(crate) trait MessageType: Sized {
     ) -> io::Result<()> {
         let chunked = self.chunked();
         let mut skip_len = length != BodySize::Stream;
-        let camel_case = self.camel_case();
+        let camel_case = self.camel_case() || config.h1_title_case_headers();
 
         // Content length
         if let Some(status) = self.status() {
             match status {
-                StatusCode::CONTINUE
-                | StatusCode::SWITCHING_PROTOCOLS
-                | StatusCode::PROCESSING
-                | StatusCode::NO_CONTENT => {
-                    // skip content-length and transfer-encoding headers
+                status if status.is_informational() || status == StatusCode::NO_CONTENT => {
+                    // skip content-length and transfer-encoding headers for all 1xx responses
+                    // (e.g., 103 Early Hints) and 204 responses
                     // see https://datatracker.ietf.org/doc/html/rfc7230#section-3.3.1
                     // and https://datatracker.ietf.org/doc/html/rfc7230#section-3.3.2
                     skip_len = true;
@@ -111,7 +138,13 @@ pub(crate) trait MessageType: Sized {
 
         // Connection
         match conn_type {
-            ConnectionType::Upgrade => dst.put_slice(b"connection: upgrade\r\n"),
+            ConnectionType::Upgrade => {
+                if camel_case {
+                    dst.put_slice(b"Connection: upgrade\r\n")
+                } else {
+                    dst.put_slice(b"connection: upgrade\r\n")
+                }
+            }
             ConnectionType::KeepAlive if version < Version::HTTP_11 => {
                 if camel_case {
                     dst.put_slice(b"Connection: keep-alive\r\n")
@@ -133,6 +166,19 @@ pub(crate) trait MessageType: Sized {
 
         let mut has_date = false;
 
+        // only responses are subject to the header limits
+        let (max_size, max_count) = match self.status() {
+            Some(_) => (
+                config.max_response_header_size(),
+                config.max_response_headers(),
+            ),
+            None => (usize::MAX, usize::MAX),
+        };
+
+        let mut header_size = 0;
+        let mut header_count = 0;
+        let mut too_large = None;
+
         let mut buf = dst.chunk_mut().as_mut_ptr();
         let mut remaining = dst.capacity() - dst.len();
 
@@ -159,6 +205,21 @@ pub(crate) trait MessageType: Sized {
                 // key length + value length + colon + space + \r\n
                 let len = k_len + v_len + 4;
 
+                if too_large.is_some() {
+                    return;
+                }
+
+                header_size += len;
+                header_count += 1;
+
+                if header_count > max_count {
+                    too_large = Some(ResponseHeadersTooLarge::Count(max_count));
+                    return;
+                } else if header_size > max_size {
+                    too_large = Some(ResponseHeadersTooLarge::Size(max_size));
+                    return;
+                }
+
                 if len > remaining {
                     // SAFETY: all the bytes written up to position "pos" are initialized
                     // the written byte count and pointer advancement are kept in sync
@@ -179,8 +240,12 @@ pub(crate) trait MessageType: Sized {
                 // the cursor matches the number of bytes written
                 unsafe {
                     if camel_case {
-                        // use Camel-Case headers
-                        write_camel_case(k, buf, k_len);
+                        match preserved_case(k) {
+                            // registered names keep their exact casing
+                            Some(name) => write_data(name.as_bytes(), buf, k_len),
+                            // use Camel-Case headers
+                            None => write_camel_case(k, buf, k_len),
+                        }
                     } else {
                         write_data(k, buf, k_len);
                     }
@@ -210,6 +275,10 @@ pub(crate) trait MessageType: Sized {
             dst.advance_mut(pos);
         }
 
+        if let Some(err) = too_large {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, err));
+        }
+
         if !has_date {
             // optimized date header, write_date_header writes its own \r\n
             config.write_date_header(dst, camel_case);
@@ -358,8 +427,16 @@ impl<T: MessageType> MessageEncoder<T> {
             self.te = TransferEncoding::empty();
         }
 
+        let start = dst.len();
+
         message.encode_status(dst)?;
-        message.encode_headers(dst, version, length, conn_type, config)
+        message
+            .encode_headers(dst, version, length, conn_type, config)
+            .map_err(|err| {
+                // leave no partial message head behind
+                dst.truncate(start);
+                err
+            })
     }
 }
 
@@ -615,6 +692,35 @@ mod tests {
         assert!(data.contains("date: date\r\n"));
     }
 
+    #[actix_rt::test]
+    async fn test_title_case_config() {
+        use crate::header::PreserveCase as _;
+
+        let mut bytes = BytesMut::with_capacity(2048);
+        let config = ServiceConfig::default().with_title_case_headers(true);
+
+        let mut res = Response::with_body(StatusCode::OK, ());
+        res.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("plain/text"));
+        res.headers_mut().insert(
+            HeaderName::from_static_preserve_case("X-Encoder-ETag"),
+            HeaderValue::from_static("1"),
+        );
+
+        let _ = res.encode_headers(
+            &mut bytes,
+            Version::HTTP_11,
+            BodySize::Sized(0),
+            ConnectionType::Upgrade,
+            &config,
+        );
+        let data = String::from_utf8(Vec::from(bytes.split().freeze().as_ref())).unwrap();
+        assert!(data.contains("Content-Length: 0\r\n"));
+        assert!(data.contains("Connection: upgrade\r\n"));
+        assert!(data.contains("Content-Type: plain/text\r\n"));
+        assert!(data.contains("X-Encoder-ETag: 1\r\n"));
+    }
+
     #[actix_rt::test]
     async fn test_extra_headers() {
         let mut bytes = BytesMut::with_capacity(2048);
@@ -668,4 +774,25 @@ mod tests {
         assert!(!data.contains("content-length: 0\r\n"));
         assert!(!data.contains("transfer-encoding: chunked\r\n"));
     }
+
+    #[actix_rt::test]
+    async fn test_early_hints_no_content_length() {
+        let mut bytes = BytesMut::with_capacity(2048);
+
+        let mut res = Response::with_body(StatusCode::from_u16(103).unwrap(), ());
+        res.headers_mut().insert(DATE, HeaderValue::from_static(""));
+        res.headers_mut()
+            .insert(CONTENT_LENGTH, HeaderValue::from_static("10"));
+
+        let _ = res.encode_headers(
+            &mut bytes,
+            Version::HTTP_11,
+            BodySize::Sized(10),
+            ConnectionType::KeepAlive,
+            &ServiceConfig::default(),
+        );
+        let data = String::from_utf8(Vec::from(bytes.split().freeze().as_ref())).unwrap();
+        assert!(!data.contains("content-length"));
+        assert!(!data.contains("transfer-encoding"));
+    }
 }
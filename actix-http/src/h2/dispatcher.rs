@@ -28,8 +28,10 @@ use crate::{
     header::{
         HeaderName, HeaderValue, CONNECTION, CONTENT_LENGTH, DATE, TRANSFER_ENCODING, UPGRADE,
     },
+    lifecycle::{ConnectionLifecycle, ResponseProgress},
     service::HttpFlow,
-    Extensions, OnConnectData, Payload, Request, Response, ResponseHead,
+    DisconnectSignal, Extensions, OnConnectData, Payload, Protocol, Request, Response,
+    ResponseHead,
 };
 
 const CHUNK_SIZE: usize = 16_384;
@@ -43,6 +45,7 @@ pin_project! {
         config: ServiceConfig,
         peer_addr: Option<net::SocketAddr>,
         ping_pong: Option<H2PingPong>,
+        lifecycle: ConnectionLifecycle,
         _phantom: PhantomData<B>
     }
 }
@@ -71,6 +74,8 @@ where
             ping_pong: conn.ping_pong().unwrap(),
         });
 
+        let lifecycle = ConnectionLifecycle::new(&config, Protocol::Http2, peer_addr);
+
         Self {
             flow,
             config,
@@ -78,6 +83,7 @@ where
             connection: conn,
             conn_data: conn_data.0.map(Rc::new),
             ping_pong,
+            lifecycle,
             _phantom: PhantomData,
         }
     }
@@ -109,6 +115,8 @@ where
         loop {
             match Pin::new(&mut this.connection).poll_accept(cx)? {
                 Poll::Ready(Some((req, tx))) => {
+                    this.lifecycle.request_received();
+
                     let (parts, body) = req.into_parts();
                     let payload = crate::h2::Payload::new(body);
                     let pl = Payload::H2 { payload };
@@ -123,13 +131,35 @@ where
 
                     req.conn_data = this.conn_data.as_ref().map(Rc::clone);
 
+                    let disconnect = DisconnectSignal::new();
+                    req.extensions.get_mut().insert(disconnect.clone());
+
                     let fut = this.flow.service.call(req);
                     let config = this.config.clone();
 
                     // multiplex request handling with spawn task
                     actix_rt::spawn(async move {
-                        // resolve service call and send response.
-                        let res = match fut.await {
+                        actix_rt::pin!(fut);
+                        let mut tx = tx;
+
+                        // resolve service call, raising the disconnect signal if the client
+                        // resets the stream in the meantime
+                        let res = poll_fn(|cx| {
+                            if let Poll::Ready(res) = fut.as_mut().poll(cx) {
+                                return Poll::Ready(res);
+                            }
+
+                            if !disconnect.is_disconnected() && tx.poll_reset(cx).is_ready() {
+                                trace!("HTTP/2 stream reset by client");
+                                disconnect.notify();
+                            }
+
+                            Poll::Pending
+                        })
+                        .await;
+
+                        // send response.
+                        let res = match res {
                             Ok(res) => handle_response(res.into(), tx, config).await,
                             Err(err) => {
                                 let res: Response<BoxBody> = err.into();
@@ -203,7 +233,9 @@ async fn handle_response<B>(
 where
     B: MessageBody,
 {
-    let (res, body) = res.replace_body(());
+    let (mut res, body) = res.replace_body(());
+    let mut progress = ResponseProgress::take(&mut res);
+    let status = res.status();
 
     // prepare response.
     let mut size = body.size();
@@ -215,7 +247,15 @@ where
         .send_response(res, eof)
         .map_err(DispatchError::SendResponse)?;
 
+    if let Some(progress) = &progress {
+        progress.headers_written(status);
+    }
+
     if eof {
+        if let Some(progress) = progress {
+            progress.finish();
+        }
+
         return Ok(());
     }
 
@@ -242,6 +282,10 @@ where
                     let len = chunk.len();
                     let bytes = chunk.split_to(cmp::min(len, cap));
 
+                    if let Some(progress) = progress.as_mut() {
+                        progress.body_chunk(bytes.len());
+                    }
+
                     stream
                         .send_data(bytes, false)
                         .map_err(DispatchError::SendData)?;
@@ -260,6 +304,10 @@ where
         .send_data(Bytes::new(), true)
         .map_err(DispatchError::SendData)?;
 
+    if let Some(progress) = progress {
+        progress.finish();
+    }
+
     Ok(())
 }
 
@@ -277,13 +325,16 @@ fn prepare_response(
 
     // Content length
     match head.status {
-        http::StatusCode::NO_CONTENT
-        | http::StatusCode::CONTINUE
-        | http::StatusCode::PROCESSING => *size = BodySize::None,
         http::StatusCode::SWITCHING_PROTOCOLS => {
             skip_len = true;
             *size = BodySize::Stream;
         }
+        status if status.is_informational() => {
+            // 1xx responses can never carry a body or content-length
+            skip_len = true;
+            *size = BodySize::None;
+        }
+        http::StatusCode::NO_CONTENT => *size = BodySize::None,
         _ => {}
     }
 
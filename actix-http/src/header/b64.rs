@@ -0,0 +1,190 @@
+//! Base64 encoding and decoding for header values, as defined in [RFC 4648].
+//!
+//! Headers such as `Authorization: Basic`, `Content-MD5`, `Sec-WebSocket-Key`, and structured
+//! field byte sequences carry binary data as base64. Encoding and decoding use the `base64` crate;
+//! this module adds strict padding checks on top. Characters outside the alphabet, malformed
+//! padding, and non-zero pad bits are rejected.
+//!
+//! [RFC 4648]: https://datatracker.ietf.org/doc/html/rfc4648
+
+use crate::error::ParseError;
+
+/// Base64 alphabet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabet {
+    /// Standard alphabet, using `+` and `/`, with `=` padding.
+    Standard,
+
+    /// URL and filename safe alphabet, using `-` and `_`, without padding.
+    UrlSafe,
+}
+
+impl Alphabet {
+    fn config(self) -> base64::Config {
+        match self {
+            Alphabet::Standard => base64::STANDARD,
+            Alphabet::UrlSafe => base64::URL_SAFE_NO_PAD,
+        }
+    }
+
+    /// Config for decoding data with its padding already removed.
+    fn unpadded_config(self) -> base64::Config {
+        match self {
+            Alphabet::Standard => base64::STANDARD_NO_PAD,
+            Alphabet::UrlSafe => base64::URL_SAFE_NO_PAD,
+        }
+    }
+}
+
+/// Encodes `bytes` as base64.
+///
+/// Standard encodings are padded with `=`; URL-safe encodings are not.
+pub fn encode(bytes: &[u8], alphabet: Alphabet) -> String {
+    base64::encode_config(bytes, alphabet.config())
+}
+
+/// Decodes base64 using `alphabet`.
+///
+/// Standard encodings must be padded; padding is optional for URL-safe encodings, but must be
+/// correct if present.
+pub fn decode(encoded: &[u8], alphabet: Alphabet) -> Result<Vec<u8>, ParseError> {
+    decode_inner(encoded, alphabet, alphabet == Alphabet::Standard, true)
+}
+
+/// Decodes base64 that may be unpadded or have non-zero pad bits, as structured field parsers
+/// are advised to accept.
+pub(crate) fn decode_lenient(
+    encoded: &[u8],
+    alphabet: Alphabet,
+) -> Result<Vec<u8>, ParseError> {
+    decode_inner(encoded, alphabet, false, false)
+}
+
+fn decode_inner(
+    encoded: &[u8],
+    alphabet: Alphabet,
+    require_padding: bool,
+    require_zero_bits: bool,
+) -> Result<Vec<u8>, ParseError> {
+    let (data, padding) = match encoded.iter().position(|&byte| byte == b'=') {
+        Some(pos) => encoded.split_at(pos),
+        None => (encoded, &[][..]),
+    };
+
+    // padding completes the last group of four characters
+    let valid_padding = match padding.len() {
+        0 => !require_padding || data.len() % 4 == 0,
+        len => {
+            padding.iter().all(|&byte| byte == b'=')
+                && data.len() % 4 != 0
+                && (data.len() + len) % 4 == 0
+        }
+    };
+
+    if !valid_padding || data.len() % 4 == 1 {
+        return Err(ParseError::Header);
+    }
+
+    let config = alphabet
+        .unpadded_config()
+        .decode_allow_trailing_bits(!require_zero_bits);
+
+    base64::decode_config(data, config).map_err(|_| ParseError::Header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn standard_round_trip() {
+        let cases: &[(&[u8], &str)] = &[
+            (b"", ""),
+            (b"f", "Zg=="),
+            (b"fo", "Zm8="),
+            (b"foo", "Zm9v"),
+            (b"foob", "Zm9vYg=="),
+            (b"username:password", "dXNlcm5hbWU6cGFzc3dvcmQ="),
+            (&[0xfb, 0xff, 0xbf], "+/+/"),
+        ];
+
+        for &(bytes, encoded) in cases {
+            assert_eq!(encode(bytes, Alphabet::Standard), encoded);
+            assert_eq!(
+                decode(encoded.as_bytes(), Alphabet::Standard).unwrap(),
+                bytes
+            );
+        }
+
+        assert!(decode(b"-_-_", Alphabet::Standard).is_err());
+    }
+
+    #[test]
+    fn url_safe_round_trip() {
+        let cases: &[(&[u8], &str)] = &[
+            (b"", ""),
+            (b"f", "Zg"),
+            (b"fo", "Zm8"),
+            (b"foo", "Zm9v"),
+            (&[0xfb, 0xff, 0xbf], "-_-_"),
+        ];
+
+        for &(bytes, encoded) in cases {
+            assert_eq!(encode(bytes, Alphabet::UrlSafe), encoded);
+            assert_eq!(
+                decode(encoded.as_bytes(), Alphabet::UrlSafe).unwrap(),
+                bytes
+            );
+        }
+
+        // correct padding is accepted
+        assert_eq!(decode(b"Zg==", Alphabet::UrlSafe).unwrap(), b"f");
+        assert!(decode(b"+/+/", Alphabet::UrlSafe).is_err());
+    }
+
+    #[test]
+    fn round_trip_all_lengths() {
+        let bytes = (0..=255).rev().collect::<Vec<u8>>();
+
+        for len in 0..=bytes.len() {
+            let bytes = &bytes[..len];
+
+            for alphabet in [Alphabet::Standard, Alphabet::UrlSafe] {
+                let encoded = encode(bytes, alphabet);
+                assert_eq!(decode(encoded.as_bytes(), alphabet).unwrap(), bytes);
+                assert_eq!(decode_lenient(encoded.as_bytes(), alphabet).unwrap(), bytes);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_padding() {
+        for encoded in [
+            &b"Zg"[..],
+            b"Zg=",
+            b"Zg===",
+            b"Zm8==",
+            b"Zm9v=",
+            b"Z===",
+            b"Z",
+            b"Zg=a",
+            b"Z=g=",
+            b"=",
+        ] {
+            assert!(
+                decode(encoded, Alphabet::Standard).is_err(),
+                "{:?}",
+                encoded
+            );
+        }
+
+        for encoded in [&b"Zg="[..], b"Zm8==", b"Zm9v=", b"Z", b"Zg=a"] {
+            assert!(decode(encoded, Alphabet::UrlSafe).is_err(), "{:?}", encoded);
+        }
+
+        // non-zero pad bits
+        assert!(decode(b"Zh==", Alphabet::Standard).is_err());
+        assert!(decode(b"Zm9=", Alphabet::Standard).is_err());
+        assert_eq!(decode_lenient(b"Zh", Alphabet::Standard).unwrap(), b"f");
+    }
+}
@@ -0,0 +1,60 @@
+//! Common header names not defined in [`http`].
+//!
+//! Any headers added to this file will need to be re-exported from the list at `crate::header`.
+
+use http::header::HeaderName;
+
+/// Response header field that reports how caches along the response's path handled the request.
+///
+/// See [RFC 9211](https://datatracker.ietf.org/doc/html/rfc9211).
+pub const CACHE_STATUS: HeaderName = HeaderName::from_static("cache-status");
+
+/// Header field that carries digests of the message content, such as `sha-256=:...:`.
+///
+/// See [RFC 9530 §2](https://datatracker.ietf.org/doc/html/rfc9530#section-2).
+pub const CONTENT_DIGEST: HeaderName = HeaderName::from_static("content-digest");
+
+/// Header field that carries the base64-encoded MD5 digest of the message content.
+///
+/// Obsoleted by `Content-Digest`, but still sent by some clients. See
+/// [RFC 1864](https://datatracker.ietf.org/doc/html/rfc1864).
+pub const CONTENT_MD5: HeaderName = HeaderName::from_static("content-md5");
+
+/// Response header field that signals that the resource is deprecated.
+///
+/// See [RFC 9745](https://datatracker.ietf.org/doc/html/rfc9745).
+pub const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+
+/// Response header field that registers a Network Error Logging policy for the origin.
+///
+/// See [Network Error Logging](https://www.w3.org/TR/network-error-logging/).
+pub const NEL: HeaderName = HeaderName::from_static("nel");
+
+/// Response header field that controls which browser features the document and its frames may use.
+///
+/// See [Permissions Policy](https://www.w3.org/TR/permissions-policy-1/).
+pub const PERMISSIONS_POLICY: HeaderName = HeaderName::from_static("permissions-policy");
+
+/// Request header field that makes a CalDAV scheduling request conditional on the current
+/// schedule tag of the calendar object resource.
+///
+/// See [RFC 6638 §8.3](https://datatracker.ietf.org/doc/html/rfc6638#section-8.3).
+pub const IF_SCHEDULE_TAG_MATCH: HeaderName = HeaderName::from_static("if-schedule-tag-match");
+
+/// Response header field that provides the current schedule tag of a CalDAV calendar object
+/// resource.
+///
+/// See [RFC 6638 §8.4](https://datatracker.ietf.org/doc/html/rfc6638#section-8.4).
+pub const SCHEDULE_TAG: HeaderName = HeaderName::from_static("schedule-tag");
+
+/// Response header field that gives the date at which the resource is expected to become
+/// unresponsive.
+///
+/// See [RFC 8594](https://datatracker.ietf.org/doc/html/rfc8594).
+pub const SUNSET: HeaderName = HeaderName::from_static("sunset");
+
+/// Request header field, set by proxies, that identifies the port the client connected to.
+///
+/// Not standardized, but widely used alongside `X-Forwarded-For`, `X-Forwarded-Host`, and
+/// `X-Forwarded-Proto`.
+pub const X_FORWARDED_PORT: HeaderName = HeaderName::from_static("x-forwarded-port");
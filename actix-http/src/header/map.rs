@@ -1,12 +1,12 @@
 //! A multi-value [`HeaderMap`] and its iterators.
 
-use std::{borrow::Cow, collections::hash_map, iter, ops};
+use std::{borrow::Cow, collections::hash_map, iter, mem, ops, slice};
 
 use ahash::AHashMap;
-use http::header::{HeaderName, HeaderValue};
+use http::header::{HeaderName, HeaderValue, InvalidHeaderValue, VIA, WARNING};
 use smallvec::{smallvec, SmallVec};
 
-use super::AsHeaderName;
+use super::{AsHeaderName, TryIntoHeaderValue as _, ViaEntry, WarningEntry};
 
 /// A multi-map of HTTP headers.
 ///
@@ -31,7 +31,249 @@ use super::AsHeaderName;
 /// ```
 #[derive(Debug, Clone, Default)]
 pub struct HeaderMap {
-    pub(crate) inner: AHashMap<HeaderName, Value>,
+    pub(crate) inner: Inner,
+}
+
+/// Number of distinct header names a map can hold before switching to hashed storage.
+pub(crate) const INLINE_CAP: usize = 8;
+
+/// Storage for a [`HeaderMap`].
+///
+/// Small maps keep their entries in a flat list and find keys by linear scan, which avoids hashing
+/// entirely. Once more than [`INLINE_CAP`] distinct names are inserted, the entries spill into a
+/// hash map.
+///
+/// The list is a heap `Vec` rather than an inline array because each entry is around 200 bytes;
+/// an inline array of [`INLINE_CAP`] entries would make every `HeaderMap` (and the request and
+/// response heads that own one) over 1.6KB.
+#[derive(Debug, Clone)]
+pub(crate) enum Inner {
+    Inline(Vec<(HeaderName, Value)>),
+    Hashed(AHashMap<HeaderName, Value>),
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Inner::Inline(Vec::new())
+    }
+}
+
+impl Inner {
+    fn with_capacity(capacity: usize) -> Self {
+        if capacity <= INLINE_CAP {
+            Inner::Inline(Vec::with_capacity(capacity))
+        } else {
+            Inner::Hashed(AHashMap::with_capacity(capacity))
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Inner::Inline(entries) => entries.len(),
+            Inner::Hashed(map) => map.len(),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        match self {
+            Inner::Inline(entries) => entries.capacity(),
+            Inner::Hashed(map) => map.capacity(),
+        }
+    }
+
+    fn clear(&mut self) {
+        match self {
+            Inner::Inline(entries) => entries.clear(),
+            Inner::Hashed(map) => map.clear(),
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        match self {
+            Inner::Inline(entries) => {
+                let required = entries.len() + additional;
+
+                if required > INLINE_CAP {
+                    self.spill(required);
+                } else {
+                    entries.reserve(additional);
+                }
+            }
+            Inner::Hashed(map) => map.reserve(additional),
+        }
+    }
+
+    /// Moves inline entries into hashed storage able to hold at least `capacity` names.
+    fn spill(&mut self, capacity: usize) {
+        if let Inner::Inline(entries) = self {
+            let mut map = AHashMap::with_capacity(capacity.max(INLINE_CAP * 2));
+            map.extend(entries.drain(..));
+            *self = Inner::Hashed(map);
+        }
+    }
+
+    fn get(&self, key: &HeaderName) -> Option<&Value> {
+        match self {
+            Inner::Inline(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            Inner::Hashed(map) => map.get(key),
+        }
+    }
+
+    fn get_mut(&mut self, key: &HeaderName) -> Option<&mut Value> {
+        match self {
+            Inner::Inline(entries) => entries
+                .iter_mut()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            Inner::Hashed(map) => map.get_mut(key),
+        }
+    }
+
+    fn contains_key(&self, key: &HeaderName) -> bool {
+        self.get(key).is_some()
+    }
+
+    fn insert(&mut self, key: HeaderName, value: Value) -> Option<Value> {
+        match self {
+            Inner::Inline(entries) => {
+                if let Some((_, existing)) = entries.iter_mut().find(|(name, _)| *name == key) {
+                    return Some(mem::replace(existing, value));
+                }
+
+                if entries.len() < INLINE_CAP {
+                    // when full, grow straight to the inline limit to avoid repeated small
+                    // reallocations; an explicitly reserved capacity is used up first
+                    if entries.len() == entries.capacity() {
+                        entries.reserve_exact(INLINE_CAP - entries.len());
+                    }
+
+                    entries.push((key, value));
+                    return None;
+                }
+
+                self.spill(INLINE_CAP + 1);
+                self.insert(key, value)
+            }
+            Inner::Hashed(map) => map.insert(key, value),
+        }
+    }
+
+    fn append(&mut self, key: HeaderName, value: HeaderValue) {
+        match self.get_mut(&key) {
+            Some(existing) => existing.append(value),
+            None => {
+                self.insert(key, Value::one(value));
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &HeaderName) -> Option<Value> {
+        match self {
+            Inner::Inline(entries) => {
+                let idx = entries.iter().position(|(name, _)| name == key)?;
+                Some(entries.remove(idx).1)
+            }
+            Inner::Hashed(map) => map.remove(key),
+        }
+    }
+
+    pub(crate) fn iter(&self) -> InnerIter<'_> {
+        match self {
+            Inner::Inline(entries) => InnerIter::Inline(entries.iter()),
+            Inner::Hashed(map) => InnerIter::Hashed(map.iter()),
+        }
+    }
+
+    fn drain(&mut self) -> InnerDrain<'_> {
+        match self {
+            Inner::Inline(entries) => InnerDrain::Inline(entries.drain(..)),
+            Inner::Hashed(map) => InnerDrain::Hashed(map.drain()),
+        }
+    }
+}
+
+/// Iterator over borrowed entries of either storage kind.
+#[derive(Debug)]
+pub(crate) enum InnerIter<'a> {
+    Inline(slice::Iter<'a, (HeaderName, Value)>),
+    Hashed(hash_map::Iter<'a, HeaderName, Value>),
+}
+
+impl<'a> Iterator for InnerIter<'a> {
+    type Item = (&'a HeaderName, &'a Value);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            InnerIter::Inline(iter) => iter.next().map(|(name, value)| (name, value)),
+            InnerIter::Hashed(iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            InnerIter::Inline(iter) => iter.size_hint(),
+            InnerIter::Hashed(iter) => iter.size_hint(),
+        }
+    }
+}
+
+/// Iterator over drained entries of either storage kind.
+#[derive(Debug)]
+enum InnerDrain<'a> {
+    Inline(std::vec::Drain<'a, (HeaderName, Value)>),
+    Hashed(hash_map::Drain<'a, HeaderName, Value>),
+}
+
+impl Iterator for InnerDrain<'_> {
+    type Item = (HeaderName, Value);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            InnerDrain::Inline(iter) => iter.next(),
+            InnerDrain::Hashed(iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            InnerDrain::Inline(iter) => iter.size_hint(),
+            InnerDrain::Hashed(iter) => iter.size_hint(),
+        }
+    }
+}
+
+/// Iterator over owned entries of either storage kind.
+#[derive(Debug)]
+enum InnerIntoIter {
+    Inline(std::vec::IntoIter<(HeaderName, Value)>),
+    Hashed(hash_map::IntoIter<HeaderName, Value>),
+}
+
+impl Iterator for InnerIntoIter {
+    type Item = (HeaderName, Value);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            InnerIntoIter::Inline(iter) => iter.next(),
+            InnerIntoIter::Hashed(iter) => iter.next(),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            InnerIntoIter::Inline(iter) => iter.size_hint(),
+            InnerIntoIter::Hashed(iter) => iter.size_hint(),
+        }
+    }
 }
 
 /// A bespoke non-empty list for HeaderMap values.
@@ -100,7 +342,7 @@ impl HeaderMap {
     /// ```
     pub fn with_capacity(capacity: usize) -> Self {
         HeaderMap {
-            inner: AHashMap::with_capacity(capacity),
+            inner: Inner::with_capacity(capacity),
         }
     }
 
@@ -396,14 +638,47 @@ impl HeaderMap {
     /// assert_eq!(map.len(), 3);
     /// ```
     pub fn append(&mut self, key: HeaderName, value: HeaderValue) {
-        match self.inner.entry(key) {
-            hash_map::Entry::Occupied(mut entry) => {
-                entry.get_mut().append(value);
-            }
-            hash_map::Entry::Vacant(entry) => {
-                entry.insert(Value::one(value));
-            }
-        };
+        self.inner.append(key, value);
+    }
+
+    /// Appends a hop to the `Via` header, after any existing entries.
+    ///
+    /// Returns an error, leaving the map unchanged, if the entry contains characters that are not
+    /// valid in a header value.
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_http::header::{self, HeaderMap, ViaEntry};
+    /// let mut map = HeaderMap::new();
+    ///
+    /// map.append_via(ViaEntry::new("1.0", "fred")).unwrap();
+    /// map.append_via(ViaEntry::new("1.1", "gw").comment("cached")).unwrap();
+    ///
+    /// let mut via = map.get_all(header::VIA);
+    /// assert_eq!(via.next().unwrap(), "1.0 fred");
+    /// assert_eq!(via.next().unwrap(), "1.1 gw (cached)");
+    /// ```
+    pub fn append_via(&mut self, entry: ViaEntry) -> Result<(), InvalidHeaderValue> {
+        self.append(VIA, entry.try_into_value()?);
+        Ok(())
+    }
+
+    /// Appends a warning to the `Warning` header, after any existing warnings.
+    ///
+    /// Returns an error, leaving the map unchanged, if the warning contains characters that are
+    /// not valid in a header value.
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_http::header::{self, HeaderMap, WarningEntry};
+    /// let mut map = HeaderMap::new();
+    ///
+    /// map.append_warning(WarningEntry::new(110, "-", "Response is Stale")).unwrap();
+    /// assert_eq!(map.get(header::WARNING).unwrap(), r#"110 - "Response is Stale""#);
+    /// ```
+    pub fn append_warning(&mut self, entry: WarningEntry) -> Result<(), InvalidHeaderValue> {
+        self.append(WARNING, entry.try_into_value()?);
+        Ok(())
     }
 
     /// Removes all headers for a particular header name from the map.
@@ -549,7 +824,7 @@ impl HeaderMap {
     /// assert!(keys.contains(&header::SET_COOKIE));
     /// ```
     pub fn keys(&self) -> Keys<'_> {
-        Keys(self.inner.keys())
+        Keys(self.inner.iter())
     }
 
     /// Clears the map, returning all name-value sets as an iterator.
@@ -591,7 +866,12 @@ impl IntoIterator for HeaderMap {
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
-        IntoIter::new(self.inner.into_iter())
+        let inner = match self.inner {
+            Inner::Inline(entries) => InnerIntoIter::Inline(entries.into_iter()),
+            Inner::Hashed(map) => InnerIntoIter::Hashed(map.into_iter()),
+        };
+
+        IntoIter::new(inner)
     }
 }
 
@@ -663,14 +943,14 @@ impl iter::FusedIterator for Removed {}
 
 /// Iterator over all names in the map.
 #[derive(Debug)]
-pub struct Keys<'a>(hash_map::Keys<'a, HeaderName, Value>);
+pub struct Keys<'a>(InnerIter<'a>);
 
 impl<'a> Iterator for Keys<'a> {
     type Item = &'a HeaderName;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next()
+        self.0.next().map(|(name, _)| name)
     }
 
     #[inline]
@@ -686,13 +966,13 @@ impl iter::FusedIterator for Keys<'_> {}
 /// Iterator over borrowed name-value pairs.
 #[derive(Debug)]
 pub struct Iter<'a> {
-    inner: hash_map::Iter<'a, HeaderName, Value>,
+    inner: InnerIter<'a>,
     multi_inner: Option<(&'a HeaderName, &'a SmallVec<[HeaderValue; 4]>)>,
     multi_idx: usize,
 }
 
 impl<'a> Iter<'a> {
-    fn new(iter: hash_map::Iter<'a, HeaderName, Value>) -> Self {
+    fn new(iter: InnerIter<'a>) -> Self {
         Self {
             inner: iter,
             multi_idx: 0,
@@ -744,13 +1024,13 @@ impl iter::FusedIterator for Iter<'_> {}
 /// Iterator items are `(Option<HeaderName>, HeaderValue)` to avoid cloning.
 #[derive(Debug)]
 pub struct Drain<'a> {
-    inner: hash_map::Drain<'a, HeaderName, Value>,
+    inner: InnerDrain<'a>,
     multi_inner: Option<(Option<HeaderName>, SmallVec<[HeaderValue; 4]>)>,
     multi_idx: usize,
 }
 
 impl<'a> Drain<'a> {
-    fn new(iter: hash_map::Drain<'a, HeaderName, Value>) -> Self {
+    fn new(iter: InnerDrain<'a>) -> Self {
         Self {
             inner: iter,
             multi_inner: None,
@@ -799,12 +1079,12 @@ impl iter::FusedIterator for Drain<'_> {}
 /// Implementation necessarily clones header names for each value.
 #[derive(Debug)]
 pub struct IntoIter {
-    inner: hash_map::IntoIter<HeaderName, Value>,
+    inner: InnerIntoIter,
     multi_inner: Option<(HeaderName, smallvec::IntoIter<[HeaderValue; 4]>)>,
 }
 
 impl IntoIter {
-    fn new(inner: hash_map::IntoIter<HeaderName, Value>) -> Self {
+    fn new(inner: InnerIntoIter) -> Self {
         Self {
             inner,
             multi_inner: None,
@@ -865,6 +1145,17 @@ mod tests {
     assert_impl_all!(IntoIter: Iterator, ExactSizeIterator, FusedIterator);
     assert_impl_all!(Drain<'_>: Iterator, ExactSizeIterator, FusedIterator);
 
+    /// Runs `test` against a map using inline storage and one using hashed storage.
+    fn with_both_reprs(test: impl Fn(HeaderMap)) {
+        let inline = HeaderMap::new();
+        assert!(matches!(inline.inner, Inner::Inline(_)));
+        test(inline);
+
+        let hashed = HeaderMap::with_capacity(INLINE_CAP * 4);
+        assert!(matches!(hashed.inner, Inner::Hashed(_)));
+        test(hashed);
+    }
+
     #[test]
     fn create() {
         let map = HeaderMap::new();
@@ -878,166 +1169,265 @@ mod tests {
 
     #[test]
     fn insert() {
-        let mut map = HeaderMap::new();
-
-        map.insert(header::LOCATION, HeaderValue::from_static("/test"));
-        assert_eq!(map.len(), 1);
+        with_both_reprs(|mut map| {
+            map.insert(header::LOCATION, HeaderValue::from_static("/test"));
+            assert_eq!(map.len(), 1);
+        });
     }
 
     #[test]
     fn contains() {
-        let mut map = HeaderMap::new();
-        assert!(!map.contains_key(header::LOCATION));
-
-        map.insert(header::LOCATION, HeaderValue::from_static("/test"));
-        assert!(map.contains_key(header::LOCATION));
-        assert!(map.contains_key("Location"));
-        assert!(map.contains_key("Location".to_owned()));
-        assert!(map.contains_key("location"));
+        with_both_reprs(|mut map| {
+            assert!(!map.contains_key(header::LOCATION));
+
+            map.insert(header::LOCATION, HeaderValue::from_static("/test"));
+            assert!(map.contains_key(header::LOCATION));
+            assert!(map.contains_key("Location"));
+            assert!(map.contains_key("Location".to_owned()));
+            assert!(map.contains_key("location"));
+        });
     }
 
     #[test]
     fn entries_iter() {
-        let mut map = HeaderMap::new();
-
-        map.append(header::HOST, HeaderValue::from_static("duck.com"));
-        map.append(header::COOKIE, HeaderValue::from_static("one=1"));
-        map.append(header::COOKIE, HeaderValue::from_static("two=2"));
-
-        let mut iter = map.iter();
-        assert!(iter.next().is_some());
-        assert!(iter.next().is_some());
-        assert!(iter.next().is_some());
-        assert!(iter.next().is_none());
-
-        let pairs = map.iter().collect::<Vec<_>>();
-        assert!(pairs.contains(&(&header::HOST, &HeaderValue::from_static("duck.com"))));
-        assert!(pairs.contains(&(&header::COOKIE, &HeaderValue::from_static("one=1"))));
-        assert!(pairs.contains(&(&header::COOKIE, &HeaderValue::from_static("two=2"))));
+        with_both_reprs(|mut map| {
+            map.append(header::HOST, HeaderValue::from_static("duck.com"));
+            map.append(header::COOKIE, HeaderValue::from_static("one=1"));
+            map.append(header::COOKIE, HeaderValue::from_static("two=2"));
+
+            let mut iter = map.iter();
+            assert!(iter.next().is_some());
+            assert!(iter.next().is_some());
+            assert!(iter.next().is_some());
+            assert!(iter.next().is_none());
+
+            let pairs = map.iter().collect::<Vec<_>>();
+            assert!(pairs.contains(&(&header::HOST, &HeaderValue::from_static("duck.com"))));
+            assert!(pairs.contains(&(&header::COOKIE, &HeaderValue::from_static("one=1"))));
+            assert!(pairs.contains(&(&header::COOKIE, &HeaderValue::from_static("two=2"))));
+        });
     }
 
     #[test]
     fn drain_iter() {
-        let mut map = HeaderMap::new();
+        with_both_reprs(|mut map| {
+            map.append(header::COOKIE, HeaderValue::from_static("one=1"));
+            map.append(header::COOKIE, HeaderValue::from_static("two=2"));
 
-        map.append(header::COOKIE, HeaderValue::from_static("one=1"));
-        map.append(header::COOKIE, HeaderValue::from_static("two=2"));
+            let mut vals = vec![];
+            let mut iter = map.drain();
 
-        let mut vals = vec![];
-        let mut iter = map.drain();
+            let (name, val) = iter.next().unwrap();
+            assert_eq!(name, Some(header::COOKIE));
+            vals.push(val);
 
-        let (name, val) = iter.next().unwrap();
-        assert_eq!(name, Some(header::COOKIE));
-        vals.push(val);
+            let (name, val) = iter.next().unwrap();
+            assert!(name.is_none());
+            vals.push(val);
 
-        let (name, val) = iter.next().unwrap();
-        assert!(name.is_none());
-        vals.push(val);
+            assert!(vals.contains(&HeaderValue::from_static("one=1")));
+            assert!(vals.contains(&HeaderValue::from_static("two=2")));
 
-        assert!(vals.contains(&HeaderValue::from_static("one=1")));
-        assert!(vals.contains(&HeaderValue::from_static("two=2")));
+            assert!(iter.next().is_none());
+            drop(iter);
 
-        assert!(iter.next().is_none());
-        drop(iter);
-
-        assert!(map.is_empty());
+            assert!(map.is_empty());
+        });
     }
 
     #[test]
     fn entries_into_iter() {
+        with_both_reprs(|mut map| {
+            map.append(header::HOST, HeaderValue::from_static("duck.com"));
+            map.append(header::COOKIE, HeaderValue::from_static("one=1"));
+            map.append(header::COOKIE, HeaderValue::from_static("two=2"));
+
+            let mut iter = map.into_iter();
+            assert!(iter.next().is_some());
+            assert!(iter.next().is_some());
+            assert!(iter.next().is_some());
+            assert!(iter.next().is_none());
+        });
+    }
+
+    #[test]
+    fn iter_and_into_iter_same_order() {
+        with_both_reprs(|mut map| {
+            map.append(header::HOST, HeaderValue::from_static("duck.com"));
+            map.append(header::COOKIE, HeaderValue::from_static("one=1"));
+            map.append(header::COOKIE, HeaderValue::from_static("two=2"));
+
+            let mut iter = map.iter();
+            let mut into_iter = map.clone().into_iter();
+
+            assert_eq!(iter.next().map(owned_pair), into_iter.next());
+            assert_eq!(iter.next().map(owned_pair), into_iter.next());
+            assert_eq!(iter.next().map(owned_pair), into_iter.next());
+            assert_eq!(iter.next().map(owned_pair), into_iter.next());
+        });
+    }
+
+    #[test]
+    fn get_all_and_remove_same_order() {
+        with_both_reprs(|mut map| {
+            map.append(header::COOKIE, HeaderValue::from_static("one=1"));
+            map.append(header::COOKIE, HeaderValue::from_static("two=2"));
+
+            let mut vals = map.get_all(header::COOKIE);
+            let mut removed = map.clone().remove(header::COOKIE);
+
+            assert_eq!(vals.next(), removed.next().as_ref());
+            assert_eq!(vals.next(), removed.next().as_ref());
+            assert_eq!(vals.next(), removed.next().as_ref());
+        });
+    }
+
+    #[test]
+    fn get_all_iteration_order_matches_insertion_order() {
+        with_both_reprs(|mut map| {
+            let mut vals = map.get_all(header::COOKIE);
+            assert!(vals.next().is_none());
+
+            map.append(header::COOKIE, HeaderValue::from_static("1"));
+            let mut vals = map.get_all(header::COOKIE);
+            assert_eq!(vals.next().unwrap().as_bytes(), b"1");
+            assert!(vals.next().is_none());
+
+            map.append(header::COOKIE, HeaderValue::from_static("2"));
+            let mut vals = map.get_all(header::COOKIE);
+            assert_eq!(vals.next().unwrap().as_bytes(), b"1");
+            assert_eq!(vals.next().unwrap().as_bytes(), b"2");
+            assert!(vals.next().is_none());
+
+            map.append(header::COOKIE, HeaderValue::from_static("3"));
+            map.append(header::COOKIE, HeaderValue::from_static("4"));
+            map.append(header::COOKIE, HeaderValue::from_static("5"));
+            let mut vals = map.get_all(header::COOKIE);
+            assert_eq!(vals.next().unwrap().as_bytes(), b"1");
+            assert_eq!(vals.next().unwrap().as_bytes(), b"2");
+            assert_eq!(vals.next().unwrap().as_bytes(), b"3");
+            assert_eq!(vals.next().unwrap().as_bytes(), b"4");
+            assert_eq!(vals.next().unwrap().as_bytes(), b"5");
+            assert!(vals.next().is_none());
+
+            let _ = map.insert(header::COOKIE, HeaderValue::from_static("6"));
+            let mut vals = map.get_all(header::COOKIE);
+            assert_eq!(vals.next().unwrap().as_bytes(), b"6");
+            assert!(vals.next().is_none());
+
+            let _ = map.insert(header::COOKIE, HeaderValue::from_static("7"));
+            let _ = map.insert(header::COOKIE, HeaderValue::from_static("8"));
+            let mut vals = map.get_all(header::COOKIE);
+            assert_eq!(vals.next().unwrap().as_bytes(), b"8");
+            assert!(vals.next().is_none());
+
+            map.append(header::COOKIE, HeaderValue::from_static("9"));
+            let mut vals = map.get_all(header::COOKIE);
+            assert_eq!(vals.next().unwrap().as_bytes(), b"8");
+            assert_eq!(vals.next().unwrap().as_bytes(), b"9");
+            assert!(vals.next().is_none());
+
+            // check for fused-ness
+            assert!(vals.next().is_none());
+        });
+    }
+
+    #[test]
+    fn spills_to_hashed_storage() {
         let mut map = HeaderMap::new();
 
-        map.append(header::HOST, HeaderValue::from_static("duck.com"));
-        map.append(header::COOKIE, HeaderValue::from_static("one=1"));
-        map.append(header::COOKIE, HeaderValue::from_static("two=2"));
+        map.append(header::SET_COOKIE, HeaderValue::from_static("one=1"));
+        map.append(header::SET_COOKIE, HeaderValue::from_static("two=2"));
+
+        for i in 0..INLINE_CAP {
+            let name = HeaderName::from_bytes(format!("x-header-{}", i).as_bytes()).unwrap();
+            map.insert(name, HeaderValue::from(i));
+            assert!(matches!(map.inner, Inner::Inline(_)) || i == INLINE_CAP - 1);
+        }
+
+        assert!(matches!(map.inner, Inner::Hashed(_)));
+        assert_eq!(map.len_keys(), INLINE_CAP + 1);
+        assert_eq!(map.len(), INLINE_CAP + 2);
+
+        // multi-value order survives the spill
+        let mut cookies = map.get_all(header::SET_COOKIE);
+        assert_eq!(cookies.next().unwrap(), "one=1");
+        assert_eq!(cookies.next().unwrap(), "two=2");
+        assert!(cookies.next().is_none());
+
+        for i in 0..INLINE_CAP {
+            let name = format!("x-header-{}", i);
+            assert_eq!(map.get(name.as_str()).unwrap(), &HeaderValue::from(i));
+        }
 
-        let mut iter = map.into_iter();
-        assert!(iter.next().is_some());
-        assert!(iter.next().is_some());
-        assert!(iter.next().is_some());
-        assert!(iter.next().is_none());
+        let mut removed = map.remove(header::SET_COOKIE);
+        assert_eq!(removed.next().unwrap(), "one=1");
+        assert_eq!(map.len_keys(), INLINE_CAP);
     }
 
     #[test]
-    fn iter_and_into_iter_same_order() {
+    fn reserve_spills_when_exceeding_inline_capacity() {
         let mut map = HeaderMap::new();
+        map.insert(header::HOST, HeaderValue::from_static("duck.com"));
 
-        map.append(header::HOST, HeaderValue::from_static("duck.com"));
-        map.append(header::COOKIE, HeaderValue::from_static("one=1"));
-        map.append(header::COOKIE, HeaderValue::from_static("two=2"));
+        map.reserve(INLINE_CAP - 1);
+        assert!(matches!(map.inner, Inner::Inline(_)));
 
-        let mut iter = map.iter();
-        let mut into_iter = map.clone().into_iter();
+        map.reserve(INLINE_CAP);
+        assert!(matches!(map.inner, Inner::Hashed(_)));
+        assert!(map.capacity() > INLINE_CAP);
+        assert_eq!(map.get(header::HOST).unwrap(), "duck.com");
+    }
 
-        assert_eq!(iter.next().map(owned_pair), into_iter.next());
-        assert_eq!(iter.next().map(owned_pair), into_iter.next());
-        assert_eq!(iter.next().map(owned_pair), into_iter.next());
-        assert_eq!(iter.next().map(owned_pair), into_iter.next());
+    #[test]
+    fn inline_insert_respects_explicit_capacity() {
+        let mut map = HeaderMap::with_capacity(3);
+        assert_eq!(map.capacity(), 3);
+
+        map.insert(header::HOST, HeaderValue::from_static("duck.com"));
+        map.insert(header::ACCEPT, HeaderValue::from_static("text/html"));
+        map.insert(header::ORIGIN, HeaderValue::from_static("example.com"));
+        assert_eq!(map.capacity(), 3);
+
+        map.insert(header::DATE, HeaderValue::from_static("now"));
+        assert_eq!(map.capacity(), INLINE_CAP);
+        assert!(matches!(map.inner, Inner::Inline(_)));
     }
 
     #[test]
-    fn get_all_and_remove_same_order() {
+    fn inline_remove_keeps_remaining_entries() {
         let mut map = HeaderMap::new();
 
-        map.append(header::COOKIE, HeaderValue::from_static("one=1"));
-        map.append(header::COOKIE, HeaderValue::from_static("two=2"));
+        map.insert(header::HOST, HeaderValue::from_static("duck.com"));
+        map.insert(header::ACCEPT, HeaderValue::from_static("text/html"));
+        map.insert(header::ORIGIN, HeaderValue::from_static("example.com"));
 
-        let mut vals = map.get_all(header::COOKIE);
-        let mut removed = map.clone().remove(header::COOKIE);
+        assert!(!map.remove(header::ACCEPT).is_empty());
+        assert!(map.remove(header::ACCEPT).is_empty());
 
-        assert_eq!(vals.next(), removed.next().as_ref());
-        assert_eq!(vals.next(), removed.next().as_ref());
-        assert_eq!(vals.next(), removed.next().as_ref());
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(header::HOST).unwrap(), "duck.com");
+        assert_eq!(map.get(header::ORIGIN).unwrap(), "example.com");
     }
 
     #[test]
-    fn get_all_iteration_order_matches_insertion_order() {
+    fn append_typed_proxy_headers() {
         let mut map = HeaderMap::new();
 
-        let mut vals = map.get_all(header::COOKIE);
-        assert!(vals.next().is_none());
-
-        map.append(header::COOKIE, HeaderValue::from_static("1"));
-        let mut vals = map.get_all(header::COOKIE);
-        assert_eq!(vals.next().unwrap().as_bytes(), b"1");
-        assert!(vals.next().is_none());
-
-        map.append(header::COOKIE, HeaderValue::from_static("2"));
-        let mut vals = map.get_all(header::COOKIE);
-        assert_eq!(vals.next().unwrap().as_bytes(), b"1");
-        assert_eq!(vals.next().unwrap().as_bytes(), b"2");
-        assert!(vals.next().is_none());
-
-        map.append(header::COOKIE, HeaderValue::from_static("3"));
-        map.append(header::COOKIE, HeaderValue::from_static("4"));
-        map.append(header::COOKIE, HeaderValue::from_static("5"));
-        let mut vals = map.get_all(header::COOKIE);
-        assert_eq!(vals.next().unwrap().as_bytes(), b"1");
-        assert_eq!(vals.next().unwrap().as_bytes(), b"2");
-        assert_eq!(vals.next().unwrap().as_bytes(), b"3");
-        assert_eq!(vals.next().unwrap().as_bytes(), b"4");
-        assert_eq!(vals.next().unwrap().as_bytes(), b"5");
-        assert!(vals.next().is_none());
-
-        let _ = map.insert(header::COOKIE, HeaderValue::from_static("6"));
-        let mut vals = map.get_all(header::COOKIE);
-        assert_eq!(vals.next().unwrap().as_bytes(), b"6");
-        assert!(vals.next().is_none());
-
-        let _ = map.insert(header::COOKIE, HeaderValue::from_static("7"));
-        let _ = map.insert(header::COOKIE, HeaderValue::from_static("8"));
-        let mut vals = map.get_all(header::COOKIE);
-        assert_eq!(vals.next().unwrap().as_bytes(), b"8");
-        assert!(vals.next().is_none());
-
-        map.append(header::COOKIE, HeaderValue::from_static("9"));
-        let mut vals = map.get_all(header::COOKIE);
-        assert_eq!(vals.next().unwrap().as_bytes(), b"8");
-        assert_eq!(vals.next().unwrap().as_bytes(), b"9");
-        assert!(vals.next().is_none());
-
-        // check for fused-ness
-        assert!(vals.next().is_none());
+        map.append_via(ViaEntry::new("1.0", "fred")).unwrap();
+        map.append_via(ViaEntry::new("1.1", "gw").comment("a, b"))
+            .unwrap();
+        assert!(map.append_via(ViaEntry::new("1.1", "bad\nhost")).is_err());
+
+        map.append_warning(WarningEntry::new(110, "-", "Response is Stale"))
+            .unwrap();
+
+        let via = map.get_all(header::VIA).collect::<Vec<_>>();
+        assert_eq!(via, ["1.0 fred", "1.1 gw (a, b)"]);
+        assert_eq!(
+            map.get(header::WARNING).unwrap(),
+            r#"110 - "Response is Stale""#
+        );
     }
 
     fn owned_pair<'a>(
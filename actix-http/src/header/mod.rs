@@ -27,22 +27,35 @@ pub use http::header::{
     X_XSS_PROTECTION,
 };
 
+// re-export const header names, not in http
+pub use self::common::{
+    CACHE_STATUS, CONTENT_DIGEST, CONTENT_MD5, DEPRECATION, IF_SCHEDULE_TAG_MATCH, NEL,
+    PERMISSIONS_POLICY, SCHEDULE_TAG, SUNSET, X_FORWARDED_PORT,
+};
+
 use crate::{error::ParseError, HttpMessage};
 
 mod as_name;
+#[doc(hidden)]
+pub mod b64;
+mod common;
 mod into_pair;
 mod into_value;
 pub mod map;
+mod preserve_case;
 mod shared;
+pub mod structured;
 mod utils;
 
 pub use self::as_name::AsHeaderName;
 pub use self::into_pair::TryIntoHeaderPair;
 pub use self::into_value::TryIntoHeaderValue;
 pub use self::map::HeaderMap;
+pub(crate) use self::preserve_case::preserved_case;
+pub use self::preserve_case::PreserveCase;
 pub use self::shared::{
-    parse_extended_value, q, Charset, ContentEncoding, ExtendedValue, HttpDate, LanguageTag,
-    Quality, QualityItem,
+    parse_extended_value, q, Charset, ContentEncoding, Encoding, ExtendedValue, HttpDate,
+    LanguageTag, Quality, QualityItem, Via, ViaEntry, Warning, WarningEntry,
 };
 pub use self::utils::{
     fmt_comma_delimited, from_comma_delimited, from_one_raw_str, http_percent_encode,
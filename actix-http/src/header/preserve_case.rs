@@ -0,0 +1,116 @@
+//! Registry of header names written with their exact casing by title-case HTTP/1 encoders.
+
+use std::{
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use super::HeaderName;
+
+/// Constructs header names whose casing is preserved when written by title-case encoders.
+///
+/// Header names are case-insensitive and stored in lowercase. When HTTP/1 responses are written
+/// with title-case header names, using
+/// [`HttpServiceBuilder::h1_title_case_headers`](crate::HttpServiceBuilder::h1_title_case_headers)
+/// or [`ResponseHead::set_camel_case_headers`](crate::ResponseHead::set_camel_case_headers), each
+/// word is capitalized, so `x-api-id` is written as `X-Api-Id`. Names constructed using
+/// [`from_static_preserve_case`](Self::from_static_preserve_case) are written exactly as given
+/// instead, such as `X-API-ID`.
+///
+/// The casing is registered process-wide, for all header names equal to the constructed one. If
+/// a name is registered several times with different casings, the first one is used.
+///
+/// # Examples
+/// ```
+/// use actix_http::header::{HeaderName, PreserveCase as _};
+///
+/// let name = HeaderName::from_static_preserve_case("X-API-ID");
+/// assert_eq!(name, "x-api-id");
+/// ```
+pub trait PreserveCase: Sized {
+    /// Converts a static string to a header name, registering its exact casing.
+    ///
+    /// # Panics
+    /// Panics if `name` is not a valid header name.
+    fn from_static_preserve_case(name: &'static str) -> Self;
+}
+
+impl PreserveCase for HeaderName {
+    fn from_static_preserve_case(name: &'static str) -> Self {
+        let header_name = HeaderName::from_bytes(name.as_bytes()).expect("invalid header name");
+
+        if preserved_case(name.as_bytes()).is_none() {
+            register(name);
+        }
+
+        header_name
+    }
+}
+
+/// Entry of the append-only list of registered names.
+struct Entry {
+    name: &'static str,
+    next: *const Entry,
+}
+
+static PRESERVED: AtomicPtr<Entry> = AtomicPtr::new(ptr::null_mut());
+
+fn register(name: &'static str) {
+    // entries are never freed, so that readers never need to synchronize with writers
+    let entry = Box::leak(Box::new(Entry {
+        name,
+        next: ptr::null(),
+    }));
+
+    let mut head = PRESERVED.load(Ordering::Acquire);
+
+    loop {
+        entry.next = head;
+
+        match PRESERVED.compare_exchange_weak(head, entry, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => break,
+            Err(current) => head = current,
+        }
+    }
+}
+
+/// Returns the registered casing of the header name `name`, if any.
+#[inline]
+pub(crate) fn preserved_case(name: &[u8]) -> Option<&'static str> {
+    let mut entry = PRESERVED.load(Ordering::Acquire) as *const Entry;
+
+    while !entry.is_null() {
+        // SAFETY: entries are leaked, immutable once published, and linked only to older entries
+        let Entry {
+            name: preserved,
+            next,
+        } = unsafe { &*entry };
+
+        if preserved.len() == name.len() && preserved.as_bytes().eq_ignore_ascii_case(name) {
+            return Some(preserved);
+        }
+
+        entry = *next;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registers_casing() {
+        assert_eq!(preserved_case(b"x-preserve-test"), None);
+
+        let name = HeaderName::from_static_preserve_case("X-PreSERVE-test");
+        assert_eq!(name, "x-preserve-test");
+        assert_eq!(preserved_case(b"x-preserve-test"), Some("X-PreSERVE-test"));
+
+        // first registration wins
+        HeaderName::from_static_preserve_case("X-PRESERVE-TEST");
+        assert_eq!(preserved_case(b"x-preserve-test"), Some("X-PreSERVE-test"));
+    }
+}
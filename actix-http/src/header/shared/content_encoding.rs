@@ -5,7 +5,9 @@ use http::header::InvalidHeaderValue;
 
 use crate::{
     error::ParseError,
-    header::{self, from_one_raw_str, Header, HeaderName, HeaderValue, TryIntoHeaderValue},
+    header::{
+        self, from_one_raw_str, Encoding, Header, HeaderName, HeaderValue, TryIntoHeaderValue,
+    },
     HttpMessage,
 };
 
@@ -17,7 +19,8 @@ pub struct ContentEncodingParseError;
 /// Represents a supported content encoding.
 ///
 /// Includes a commonly-used subset of media types appropriate for use as HTTP content encodings.
-/// See [IANA HTTP Content Coding Registry].
+/// See [IANA HTTP Content Coding Registry]. Converts to and from the more general [`Encoding`],
+/// which also represents unsupported codings.
 ///
 /// [IANA HTTP Content Coding Registry]: https://www.iana.org/assignments/http-parameters/http-parameters.xhtml
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -78,21 +81,10 @@ impl FromStr for ContentEncoding {
     type Err = ContentEncodingParseError;
 
     fn from_str(enc: &str) -> Result<Self, Self::Err> {
-        let enc = enc.trim();
-
-        if enc.eq_ignore_ascii_case("br") {
-            Ok(ContentEncoding::Brotli)
-        } else if enc.eq_ignore_ascii_case("gzip") {
-            Ok(ContentEncoding::Gzip)
-        } else if enc.eq_ignore_ascii_case("deflate") {
-            Ok(ContentEncoding::Deflate)
-        } else if enc.eq_ignore_ascii_case("identity") {
-            Ok(ContentEncoding::Identity)
-        } else if enc.eq_ignore_ascii_case("zstd") {
-            Ok(ContentEncoding::Zstd)
-        } else {
-            Err(ContentEncodingParseError)
-        }
+        enc.parse::<Encoding>()
+            .ok()
+            .and_then(|enc| ContentEncoding::try_from(enc).ok())
+            .ok_or(ContentEncodingParseError)
     }
 }
 
@@ -121,3 +113,27 @@ impl Header for ContentEncoding {
         from_one_raw_str(msg.headers().get(Self::name()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            "br".parse::<ContentEncoding>().unwrap(),
+            ContentEncoding::Brotli
+        );
+        assert_eq!(
+            " GZIP ".parse::<ContentEncoding>().unwrap(),
+            ContentEncoding::Gzip
+        );
+        assert_eq!(
+            ContentEncoding::try_from("identity").unwrap(),
+            ContentEncoding::Identity
+        );
+
+        assert!("compress".parse::<ContentEncoding>().is_err());
+        assert!("".parse::<ContentEncoding>().is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use std::{convert::TryFrom, fmt, str};
+
+use http::header::InvalidHeaderValue;
+
+use crate::{
+    error::ParseError,
+    header::{ContentEncoding, HeaderValue, TryIntoHeaderValue},
+};
+
+/// A content coding, as used in the `Accept-Encoding` and `Content-Encoding` headers.
+///
+/// Codings that are supported by the [`ContentEncoding`] encoders have their own variants; any
+/// other coding is represented by [`Other`](Self::Other), holding its lowercase token.
+///
+/// Encodings are ordered by variant, with `Other` codings last and ordered by token.
+///
+/// Content codings are case-insensitive. Parsing normalizes tokens so that, for example, `GZIP` is
+/// parsed as [`Gzip`](Self::Gzip) and `X-Custom` as `Other("x-custom")`.
+///
+/// # Examples
+/// ```
+/// use actix_http::header::Encoding;
+///
+/// assert_eq!("br".parse::<Encoding>().unwrap(), Encoding::Brotli);
+/// assert_eq!(Encoding::Gzip.to_string(), "gzip");
+///
+/// let list = Encoding::parse_list("gzip, X-Custom").unwrap();
+/// assert_eq!(list, [Encoding::Gzip, Encoding::Other("x-custom".to_owned())]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum Encoding {
+    /// Indicates the no-op identity encoding.
+    ///
+    /// I.e., no compression or modification.
+    Identity,
+
+    /// Gzip algorithm.
+    Gzip,
+
+    /// A format using the zlib structure with deflate algorithm.
+    Deflate,
+
+    /// A format using the Brotli algorithm.
+    Brotli,
+
+    /// Zstd algorithm.
+    Zstd,
+
+    /// Some other content coding, stored as a lowercase token.
+    Other(String),
+}
+
+impl Encoding {
+    /// The identity encoding.
+    pub const fn identity() -> Self {
+        Self::Identity
+    }
+
+    /// The Brotli encoding.
+    pub const fn brotli() -> Self {
+        Self::Brotli
+    }
+
+    /// The deflate encoding.
+    pub const fn deflate() -> Self {
+        Self::Deflate
+    }
+
+    /// The Gzip encoding.
+    pub const fn gzip() -> Self {
+        Self::Gzip
+    }
+
+    /// The Zstd encoding.
+    pub const fn zstd() -> Self {
+        Self::Zstd
+    }
+
+    /// Returns the content coding token.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Encoding::Identity => "identity",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+            Encoding::Brotli => "br",
+            Encoding::Zstd => "zstd",
+            Encoding::Other(token) => token,
+        }
+    }
+
+    /// Parses a comma-separated list of content codings, such as a `Content-Encoding` header value.
+    ///
+    /// Empty list elements are ignored.
+    pub fn parse_list(value: &str) -> Result<Vec<Self>, ParseError> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Self::Identity
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl str::FromStr for Encoding {
+    type Err = ParseError;
+
+    fn from_str(enc: &str) -> Result<Self, Self::Err> {
+        let enc = enc.trim();
+
+        if enc.eq_ignore_ascii_case("identity") {
+            Ok(Encoding::Identity)
+        } else if enc.eq_ignore_ascii_case("gzip") {
+            Ok(Encoding::Gzip)
+        } else if enc.eq_ignore_ascii_case("deflate") {
+            Ok(Encoding::Deflate)
+        } else if enc.eq_ignore_ascii_case("br") {
+            Ok(Encoding::Brotli)
+        } else if enc.eq_ignore_ascii_case("zstd") {
+            Ok(Encoding::Zstd)
+        } else if !enc.is_empty() && enc.bytes().all(is_tchar) {
+            Ok(Encoding::Other(enc.to_ascii_lowercase()))
+        } else {
+            Err(ParseError::Header)
+        }
+    }
+}
+
+impl From<ContentEncoding> for Encoding {
+    fn from(enc: ContentEncoding) -> Self {
+        match enc {
+            ContentEncoding::Identity => Encoding::Identity,
+            ContentEncoding::Gzip => Encoding::Gzip,
+            ContentEncoding::Deflate => Encoding::Deflate,
+            ContentEncoding::Brotli => Encoding::Brotli,
+            ContentEncoding::Zstd => Encoding::Zstd,
+        }
+    }
+}
+
+impl TryFrom<Encoding> for ContentEncoding {
+    type Error = Encoding;
+
+    /// Converts to the matching [`ContentEncoding`], returning the encoding back if it is `Other`.
+    fn try_from(enc: Encoding) -> Result<Self, Self::Error> {
+        match enc {
+            Encoding::Identity => Ok(ContentEncoding::Identity),
+            Encoding::Gzip => Ok(ContentEncoding::Gzip),
+            Encoding::Deflate => Ok(ContentEncoding::Deflate),
+            Encoding::Brotli => Ok(ContentEncoding::Brotli),
+            Encoding::Zstd => Ok(ContentEncoding::Zstd),
+            other @ Encoding::Other(_) => Err(other),
+        }
+    }
+}
+
+impl TryIntoHeaderValue for Encoding {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        HeaderValue::from_str(self.as_str())
+    }
+}
+
+/// Returns true if `byte` may appear in a token, as defined in RFC 7230 §3.2.6.
+fn is_tchar(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips() {
+        for token in [
+            "identity", "gzip", "deflate", "br", "zstd", "compress", "x-custom",
+        ] {
+            let enc = token.parse::<Encoding>().unwrap();
+            assert_eq!(enc.to_string(), token);
+            assert_eq!(enc.to_string().parse::<Encoding>().unwrap(), enc);
+        }
+
+        assert_eq!("GZip".parse::<Encoding>().unwrap(), Encoding::Gzip);
+        assert_eq!(
+            " X-Custom ".parse::<Encoding>().unwrap(),
+            Encoding::Other("x-custom".to_owned())
+        );
+
+        assert!("".parse::<Encoding>().is_err());
+        assert!("gzip;q=1".parse::<Encoding>().is_err());
+        assert!("x custom".parse::<Encoding>().is_err());
+    }
+
+    #[test]
+    fn lists() {
+        assert_eq!(
+            Encoding::parse_list("gzip, , br,compress").unwrap(),
+            [
+                Encoding::Gzip,
+                Encoding::Brotli,
+                Encoding::Other("compress".to_owned())
+            ]
+        );
+        assert!(Encoding::parse_list("").unwrap().is_empty());
+        assert!(Encoding::parse_list("gzip, x y").is_err());
+    }
+
+    #[test]
+    fn content_encoding_conversions() {
+        for enc in [
+            ContentEncoding::Identity,
+            ContentEncoding::Gzip,
+            ContentEncoding::Deflate,
+            ContentEncoding::Brotli,
+            ContentEncoding::Zstd,
+        ] {
+            let shared = Encoding::from(enc);
+            assert_eq!(shared.as_str(), enc.as_str());
+            assert_eq!(ContentEncoding::try_from(shared).unwrap(), enc);
+        }
+
+        let other = Encoding::Other("compress".to_owned());
+        assert_eq!(ContentEncoding::try_from(other.clone()).unwrap_err(), other);
+    }
+}
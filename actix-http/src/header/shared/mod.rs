@@ -2,15 +2,21 @@
 
 mod charset;
 mod content_encoding;
+mod encoding;
 mod extended;
 mod http_date;
 mod quality;
 mod quality_item;
+mod via;
+mod warning;
 
 pub use self::charset::Charset;
 pub use self::content_encoding::ContentEncoding;
+pub use self::encoding::Encoding;
 pub use self::extended::{parse_extended_value, ExtendedValue};
 pub use self::http_date::HttpDate;
 pub use self::quality::{q, Quality};
 pub use self::quality_item::QualityItem;
+pub use self::via::{Via, ViaEntry};
+pub use self::warning::{Warning, WarningEntry};
 pub use language_tags::LanguageTag;
@@ -0,0 +1,285 @@
+use std::{fmt, str::FromStr};
+
+use http::header::InvalidHeaderValue;
+
+use crate::{
+    error::ParseError,
+    header::{
+        fmt_comma_delimited, utils::from_comma_delimited_quoted, Header, HeaderName,
+        HeaderValue, TryIntoHeaderValue, VIA,
+    },
+    HttpMessage,
+};
+
+/// `Via` header, defined in [RFC 7230 §5.7.1].
+///
+/// The `Via` header field indicates the presence of intermediate protocols and recipients between
+/// the user agent and the server (on requests) or between the origin server and the client (on
+/// responses). Each intermediary appends an entry, so entries are ordered from the first hop to
+/// the last; use [`HeaderMap::append_via`](crate::header::HeaderMap::append_via) to add one.
+///
+/// # ABNF
+/// ```plain
+/// Via = 1#( received-protocol RWS received-by [ RWS comment ] )
+///
+/// received-protocol = [ protocol-name "/" ] protocol-version
+/// received-by       = ( uri-host [ ":" port ] ) / pseudonym
+/// ```
+///
+/// # Example Values
+/// * `1.1 my-gateway`
+/// * `1.0 fred, 1.1 p.example.net`
+/// * `HTTP/1.1 proxy.example.com:8080 (Proxy/2.4, cached)`
+///
+/// # Examples
+/// ```
+/// use actix_http::header::{Via, ViaEntry};
+///
+/// let via = Via(vec![
+///     ViaEntry::new("1.0", "fred"),
+///     ViaEntry::new("1.1", "p.example.net").comment("Apache/1.1"),
+/// ]);
+/// assert_eq!(via.to_string(), "1.0 fred, 1.1 p.example.net (Apache/1.1)");
+/// ```
+///
+/// [RFC 7230 §5.7.1]: https://datatracker.ietf.org/doc/html/rfc7230#section-5.7.1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Via(pub Vec<ViaEntry>);
+
+/// A single hop in a [`Via`] header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViaEntry {
+    /// Name of the received protocol, if not `HTTP`.
+    pub protocol: Option<String>,
+
+    /// Version of the received protocol, such as `1.1`.
+    pub version: String,
+
+    /// Host, optionally with port, or pseudonym of the intermediary.
+    pub received_by: String,
+
+    /// Unescaped comment, often identifying the intermediary's software.
+    pub comment: Option<String>,
+}
+
+impl ViaEntry {
+    /// Constructs an entry for an intermediary that received a message using the given protocol
+    /// version.
+    pub fn new(version: impl Into<String>, received_by: impl Into<String>) -> Self {
+        Self {
+            protocol: None,
+            version: version.into(),
+            received_by: received_by.into(),
+            comment: None,
+        }
+    }
+
+    /// Sets the name of the received protocol.
+    pub fn protocol(mut self, protocol: impl Into<String>) -> Self {
+        self.protocol = Some(protocol.into());
+        self
+    }
+
+    /// Sets the comment. Parentheses and backslashes are escaped as needed when formatting.
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+}
+
+impl fmt::Display for ViaEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(protocol) = &self.protocol {
+            write!(f, "{}/", protocol)?;
+        }
+
+        write!(f, "{} {}", self.version, self.received_by)?;
+
+        if let Some(comment) = &self.comment {
+            f.write_str(" (")?;
+            write_comment(f, comment)?;
+            f.write_str(")")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for ViaEntry {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let (protocol, rest) = s
+            .split_once(char::is_whitespace)
+            .ok_or(ParseError::Header)?;
+        let (received_by, comment) = match rest.trim_start().split_once(char::is_whitespace) {
+            Some((received_by, comment)) => (received_by, Some(comment.trim_start())),
+            None => (rest.trim_start(), None),
+        };
+
+        let (protocol, version) = match protocol.split_once('/') {
+            Some((name, version)) => (Some(name), version),
+            None => (None, protocol),
+        };
+
+        if version.is_empty() || protocol.map_or(false, str::is_empty) {
+            return Err(ParseError::Header);
+        }
+
+        let comment = comment
+            .map(|comment| {
+                comment
+                    .strip_prefix('(')
+                    .and_then(|comment| comment.strip_suffix(')'))
+                    .and_then(parse_comment)
+                    .ok_or(ParseError::Header)
+            })
+            .transpose()?;
+
+        Ok(ViaEntry {
+            protocol: protocol.map(str::to_owned),
+            version: version.to_owned(),
+            received_by: received_by.to_owned(),
+            comment,
+        })
+    }
+}
+
+/// Writes a comment's contents, escaping backslashes and, unless they are balanced, parentheses.
+fn write_comment(f: &mut fmt::Formatter<'_>, comment: &str) -> fmt::Result {
+    let escape_parens = !is_balanced(comment);
+
+    for ch in comment.chars() {
+        match ch {
+            '\\' => f.write_str("\\\\")?,
+            '(' | ')' if escape_parens => write!(f, "\\{}", ch)?,
+            ch => write!(f, "{}", ch)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Unescapes a comment's contents, returning `None` if it contains unbalanced parentheses.
+fn parse_comment(raw: &str) -> Option<String> {
+    let mut comment = String::with_capacity(raw.len());
+    let mut depth = 0_usize;
+    let mut chars = raw.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => comment.push(chars.next()?),
+            '(' => {
+                depth += 1;
+                comment.push(ch);
+            }
+            ')' => {
+                depth = depth.checked_sub(1)?;
+                comment.push(ch);
+            }
+            ch => comment.push(ch),
+        }
+    }
+
+    (depth == 0).then(|| comment)
+}
+
+fn is_balanced(comment: &str) -> bool {
+    let mut depth = 0_usize;
+
+    for ch in comment.chars() {
+        match ch {
+            '(' => depth += 1,
+            ')' => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return false,
+            },
+            _ => {}
+        }
+    }
+
+    depth == 0
+}
+
+impl fmt::Display for Via {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_comma_delimited(f, &self.0)
+    }
+}
+
+impl TryIntoHeaderValue for Via {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        HeaderValue::from_str(&self.to_string())
+    }
+}
+
+impl TryIntoHeaderValue for ViaEntry {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        HeaderValue::from_str(&self.to_string())
+    }
+}
+
+impl Header for Via {
+    fn name() -> HeaderName {
+        VIA
+    }
+
+    fn parse<T: HttpMessage>(msg: &T) -> Result<Self, ParseError> {
+        from_comma_delimited_quoted(msg.headers().get_all(Self::name())).map(Via)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    #[test]
+    fn multi_hop_round_trip() {
+        let raw = "1.0 fred, HTTP/1.1 p.example.net:8080 (Apache/1.1, mod_proxy (cached)), \
+            1.1 gw (a \\(b, c\\\\d)";
+
+        let req = TestRequest::default().insert_header((VIA, raw)).finish();
+        let via = Via::parse(&req).unwrap();
+
+        assert_eq!(
+            via.0,
+            [
+                ViaEntry::new("1.0", "fred"),
+                ViaEntry::new("1.1", "p.example.net:8080")
+                    .protocol("HTTP")
+                    .comment("Apache/1.1, mod_proxy (cached)"),
+                ViaEntry::new("1.1", "gw").comment("a (b, c\\d"),
+            ]
+        );
+
+        assert_eq!(via.to_string(), raw);
+        assert_eq!(via.try_into_value().unwrap(), raw);
+    }
+
+    #[test]
+    fn multiple_header_lines() {
+        let req = TestRequest::default()
+            .append_header((VIA, "1.0 fred"))
+            .append_header((VIA, "1.1 gw"))
+            .finish();
+
+        let via = Via::parse(&req).unwrap();
+        assert_eq!(via.to_string(), "1.0 fred, 1.1 gw");
+    }
+
+    #[test]
+    fn invalid_entries() {
+        assert!("1.1".parse::<ViaEntry>().is_err());
+        assert!("/1.1 gw".parse::<ViaEntry>().is_err());
+        assert!("1.1 gw comment".parse::<ViaEntry>().is_err());
+        assert!("1.1 gw (a))".parse::<ViaEntry>().is_err());
+        assert!("1.1 gw (a\\)".parse::<ViaEntry>().is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use std::{fmt, str::FromStr};
+
+use http::header::InvalidHeaderValue;
+
+use crate::{
+    error::ParseError,
+    header::{
+        fmt_comma_delimited, utils::from_comma_delimited_quoted, Header, HeaderName,
+        HeaderValue, HttpDate, TryIntoHeaderValue, WARNING,
+    },
+    HttpMessage,
+};
+
+/// `Warning` header, defined in [RFC 7234 §5.5].
+///
+/// The `Warning` header field carries additional information about the status or transformation
+/// of a message that might not be reflected in the status code, such as a cache serving a stale
+/// response. Use [`HeaderMap::append_warning`](crate::header::HeaderMap::append_warning) to add a
+/// warning to existing ones.
+///
+/// # ABNF
+/// ```plain
+/// Warning       = 1#warning-value
+/// warning-value = warn-code SP warn-agent SP warn-text [ SP warn-date ]
+///
+/// warn-code  = 3DIGIT
+/// warn-agent = ( uri-host [ ":" port ] ) / pseudonym
+/// warn-text  = quoted-string
+/// warn-date  = DQUOTE HTTP-date DQUOTE
+/// ```
+///
+/// # Example Values
+/// * `110 - "Response is Stale"`
+/// * `112 cache.example.com "Disconnected Operation" "Sat, 25 Aug 2012 23:34:45 GMT"`
+///
+/// # Examples
+/// ```
+/// use actix_http::header::{Warning, WarningEntry};
+///
+/// let warning = Warning(vec![WarningEntry::new(110, "-", "Response is Stale")]);
+/// assert_eq!(warning.to_string(), r#"110 - "Response is Stale""#);
+/// ```
+///
+/// [RFC 7234 §5.5]: https://datatracker.ietf.org/doc/html/rfc7234#section-5.5
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning(pub Vec<WarningEntry>);
+
+/// A single warning in a [`Warning`] header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarningEntry {
+    /// Three digit warning code, such as 110 for "Response is Stale".
+    pub code: u16,
+
+    /// Host, optionally with port, or pseudonym of the agent adding the warning; `-` if unknown.
+    pub agent: String,
+
+    /// Unescaped warning text.
+    pub text: String,
+
+    /// Date the warning was added, if present.
+    pub date: Option<HttpDate>,
+}
+
+impl WarningEntry {
+    /// Constructs a warning without a date.
+    ///
+    /// # Panics
+    /// Panics if `code` is not a three digit number.
+    pub fn new(code: u16, agent: impl Into<String>, text: impl Into<String>) -> Self {
+        assert!(
+            (100..1000).contains(&code),
+            "warning codes have three digits"
+        );
+
+        Self {
+            code,
+            agent: agent.into(),
+            text: text.into(),
+            date: None,
+        }
+    }
+
+    /// Sets the date the warning was added.
+    pub fn date(mut self, date: impl Into<HttpDate>) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+}
+
+impl fmt::Display for WarningEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} ", self.code, self.agent)?;
+        write_quoted(f, &self.text)?;
+
+        if let Some(date) = &self.date {
+            write!(f, " \"{}\"", date)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for WarningEntry {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let (code, rest) = s.split_once(' ').ok_or(ParseError::Header)?;
+        let (agent, rest) = rest.split_once(' ').ok_or(ParseError::Header)?;
+
+        if code.len() != 3 || !code.bytes().all(|b| b.is_ascii_digit()) || agent.is_empty() {
+            return Err(ParseError::Header);
+        }
+
+        let (text, rest) = parse_quoted(rest).ok_or(ParseError::Header)?;
+
+        let date = match rest {
+            "" => None,
+            rest => {
+                let date = rest
+                    .strip_prefix(" \"")
+                    .and_then(|date| date.strip_suffix('"'))
+                    .ok_or(ParseError::Header)?;
+
+                Some(date.parse()?)
+            }
+        };
+
+        Ok(WarningEntry {
+            code: code.parse().map_err(|_| ParseError::Header)?,
+            agent: agent.to_owned(),
+            text,
+            date,
+        })
+    }
+}
+
+/// Writes `text` as a quoted string, escaping quotes and backslashes.
+fn write_quoted(f: &mut fmt::Formatter<'_>, text: &str) -> fmt::Result {
+    f.write_str("\"")?;
+
+    for ch in text.chars() {
+        if ch == '"' || ch == '\\' {
+            f.write_str("\\")?;
+        }
+
+        write!(f, "{}", ch)?;
+    }
+
+    f.write_str("\"")
+}
+
+/// Parses a leading quoted string, returning its unescaped contents and the rest of the input.
+fn parse_quoted(s: &str) -> Option<(String, &str)> {
+    let mut text = String::new();
+    let mut chars = s.strip_prefix('"')?.char_indices();
+
+    while let Some((idx, ch)) = chars.next() {
+        match ch {
+            '\\' => text.push(chars.next()?.1),
+            '"' => return Some((text, &s[idx + 2..])),
+            ch => text.push(ch),
+        }
+    }
+
+    None
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt_comma_delimited(f, &self.0)
+    }
+}
+
+impl TryIntoHeaderValue for Warning {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        HeaderValue::from_str(&self.to_string())
+    }
+}
+
+impl TryIntoHeaderValue for WarningEntry {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        HeaderValue::from_str(&self.to_string())
+    }
+}
+
+impl Header for Warning {
+    fn name() -> HeaderName {
+        WARNING
+    }
+
+    fn parse<T: HttpMessage>(msg: &T) -> Result<Self, ParseError> {
+        from_comma_delimited_quoted(msg.headers().get_all(Self::name())).map(Warning)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::test::TestRequest;
+
+    #[test]
+    fn build_stale_warning() {
+        let warning = WarningEntry::new(110, "-", "Response is Stale");
+        assert_eq!(warning.to_string(), r#"110 - "Response is Stale""#);
+
+        let date = SystemTime::UNIX_EPOCH + Duration::from_secs(1_345_937_685);
+        let warning =
+            WarningEntry::new(112, "cache.example.com", r#"Say "hi", \o/"#).date(date);
+        assert_eq!(
+            warning.to_string(),
+            r#"112 cache.example.com "Say \"hi\", \\o/" "Sat, 25 Aug 2012 23:34:45 GMT""#
+        );
+        assert_eq!(
+            warning.to_string().parse::<WarningEntry>().unwrap(),
+            warning
+        );
+    }
+
+    #[test]
+    fn parse_list() {
+        let raw = r#"110 - "Response is Stale", 299 gw:8080 "a, \"b\"" "Sat, 25 Aug 2012 23:34:45 GMT""#;
+
+        let req = TestRequest::default()
+            .insert_header((WARNING, raw))
+            .finish();
+        let warning = Warning::parse(&req).unwrap();
+
+        assert_eq!(warning.0.len(), 2);
+        assert_eq!(
+            warning.0[0],
+            WarningEntry::new(110, "-", "Response is Stale")
+        );
+        assert_eq!(warning.0[1].agent, "gw:8080");
+        assert_eq!(warning.0[1].text, r#"a, "b""#);
+        assert!(warning.0[1].date.is_some());
+
+        assert_eq!(warning.to_string(), raw);
+    }
+
+    #[test]
+    fn invalid_entries() {
+        assert!("110 -".parse::<WarningEntry>().is_err());
+        assert!("11 - \"text\"".parse::<WarningEntry>().is_err());
+        assert!("110 - text".parse::<WarningEntry>().is_err());
+        assert!("110 - \"text".parse::<WarningEntry>().is_err());
+        assert!("110 - \"text\" date".parse::<WarningEntry>().is_err());
+    }
+}
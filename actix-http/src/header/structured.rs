@@ -0,0 +1,903 @@
+//! Structured field values for HTTP headers, as defined in [RFC 8941].
+//!
+//! Newer headers, such as `Priority` and `Cache-Status`, define their values in terms of three
+//! top-level types: an [`Item`], a [`List`], or a [`Dictionary`]. Members of lists and
+//! dictionaries are either items or [inner lists](InnerList), and both carry [`Parameters`].
+//!
+//! Each type is parsed using its `parse` method and serialized using its `Display` implementation.
+//!
+//! # Examples
+//! ```
+//! use actix_http::header::structured::{BareItem, Dictionary};
+//!
+//! let priority = Dictionary::parse(b"u=3, i").unwrap();
+//!
+//! let urgency = priority.get("u").and_then(|member| member.as_item()).unwrap();
+//! assert_eq!(urgency.bare_item, BareItem::Integer(3));
+//!
+//! let incremental = priority.get("i").and_then(|member| member.as_item()).unwrap();
+//! assert_eq!(incremental.bare_item, BareItem::Boolean(true));
+//!
+//! assert_eq!(priority.to_string(), "u=3, i");
+//! ```
+//!
+//! [RFC 8941]: https://datatracker.ietf.org/doc/html/rfc8941
+
+use std::fmt::{self, Write as _};
+
+use crate::{
+    error::ParseError,
+    header::{
+        b64::{self, Alphabet},
+        HeaderValue, InvalidHeaderValue, TryIntoHeaderValue,
+    },
+};
+
+/// The value of an [`Item`] or parameter, without parameters of its own.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BareItem {
+    /// An integer with at most 15 digits.
+    Integer(i64),
+
+    /// A decimal with at most 12 integer and 3 fractional digits.
+    Decimal(f64),
+
+    /// A string of printable ASCII characters.
+    String(String),
+
+    /// A token, such as `text/html` or `*`.
+    Token(String),
+
+    /// A sequence of bytes, serialized using base64.
+    ByteSequence(Vec<u8>),
+
+    /// A boolean, serialized as `?1` or `?0`.
+    Boolean(bool),
+}
+
+impl BareItem {
+    /// Returns the integer value, if this is an integer.
+    pub fn as_integer(&self) -> Option<i64> {
+        match self {
+            BareItem::Integer(int) => Some(*int),
+            _ => None,
+        }
+    }
+
+    /// Returns the string value, if this is a string or a token.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            BareItem::String(val) | BareItem::Token(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Returns the boolean value, if this is a boolean.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            BareItem::Boolean(val) => Some(*val),
+            _ => None,
+        }
+    }
+}
+
+/// An ordered map of parameter keys to [bare items](BareItem).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Parameters(Vec<(String, BareItem)>);
+
+impl Parameters {
+    /// Constructs an empty set of parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the value of the parameter `key`.
+    pub fn get(&self, key: &str) -> Option<&BareItem> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, val)| val)
+    }
+
+    /// Sets the parameter `key`, replacing the value of an existing parameter in place.
+    pub fn insert(&mut self, key: impl Into<String>, value: BareItem) {
+        let key = key.into();
+
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, val)) => *val = value,
+            None => self.0.push((key, value)),
+        }
+    }
+
+    /// Returns an iterator over parameters in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BareItem)> {
+        self.0.iter().map(|(key, val)| (key.as_str(), val))
+    }
+
+    /// Returns the number of parameters.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// A [bare item](BareItem) with [`Parameters`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    /// The value of the item.
+    pub bare_item: BareItem,
+
+    /// The parameters of the item.
+    pub params: Parameters,
+}
+
+impl Item {
+    /// Constructs an item without parameters.
+    pub fn new(bare_item: BareItem) -> Self {
+        Self {
+            bare_item,
+            params: Parameters::new(),
+        }
+    }
+
+    /// Adds the parameter `key` to the item.
+    pub fn with_param(mut self, key: impl Into<String>, value: BareItem) -> Self {
+        self.params.insert(key, value);
+        self
+    }
+
+    /// Parses a header value as an item.
+    pub fn parse(input: &[u8]) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(input);
+        let item = parser.parse_item()?;
+        parser.finish()?;
+        Ok(item)
+    }
+}
+
+/// A list of [items](Item) with [`Parameters`] of its own.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InnerList {
+    /// The items of the inner list.
+    pub items: Vec<Item>,
+
+    /// The parameters of the inner list.
+    pub params: Parameters,
+}
+
+/// A member of a [`List`] or [`Dictionary`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Member {
+    /// A single item.
+    Item(Item),
+
+    /// An inner list of items.
+    InnerList(InnerList),
+}
+
+impl Member {
+    /// Returns the item, if this member is an item.
+    pub fn as_item(&self) -> Option<&Item> {
+        match self {
+            Member::Item(item) => Some(item),
+            Member::InnerList(_) => None,
+        }
+    }
+
+    /// Returns the inner list, if this member is an inner list.
+    pub fn as_inner_list(&self) -> Option<&InnerList> {
+        match self {
+            Member::Item(_) => None,
+            Member::InnerList(list) => Some(list),
+        }
+    }
+
+    /// Returns the parameters of the member.
+    pub fn params(&self) -> &Parameters {
+        match self {
+            Member::Item(item) => &item.params,
+            Member::InnerList(list) => &list.params,
+        }
+    }
+}
+
+impl From<Item> for Member {
+    fn from(item: Item) -> Self {
+        Member::Item(item)
+    }
+}
+
+impl From<InnerList> for Member {
+    fn from(list: InnerList) -> Self {
+        Member::InnerList(list)
+    }
+}
+
+/// A comma-separated list of [members](Member).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct List(pub Vec<Member>);
+
+impl List {
+    /// Parses a header value as a list.
+    ///
+    /// Lists that span multiple header lines can be parsed after joining the lines with commas.
+    pub fn parse(input: &[u8]) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(input);
+        let list = parser.parse_list()?;
+        parser.finish()?;
+        Ok(list)
+    }
+}
+
+/// An ordered map of keys to [members](Member).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Dictionary(Vec<(String, Member)>);
+
+impl Dictionary {
+    /// Constructs an empty dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the member with the given `key`.
+    pub fn get(&self, key: &str) -> Option<&Member> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, val)| val)
+    }
+
+    /// Sets the member `key`, replacing the value of an existing member in place.
+    pub fn insert(&mut self, key: impl Into<String>, member: impl Into<Member>) {
+        let key = key.into();
+        let member = member.into();
+
+        match self.0.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, val)) => *val = member,
+            None => self.0.push((key, member)),
+        }
+    }
+
+    /// Returns an iterator over members in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Member)> {
+        self.0.iter().map(|(key, val)| (key.as_str(), val))
+    }
+
+    /// Returns the number of members.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns true if the dictionary has no members.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Parses a header value as a dictionary.
+    ///
+    /// Dictionaries that span multiple header lines can be parsed after joining the lines with
+    /// commas.
+    pub fn parse(input: &[u8]) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(input);
+        let dict = parser.parse_dictionary()?;
+        parser.finish()?;
+        Ok(dict)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        let mut parser = Self { input, pos: 0 };
+        parser.skip_sp();
+        parser
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let matched = self.peek() == Some(byte);
+        self.pos += matched as usize;
+        matched
+    }
+
+    fn skip_sp(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_ows(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Checks that only trailing spaces remain.
+    fn finish(&mut self) -> Result<(), ParseError> {
+        self.skip_sp();
+
+        if self.pos == self.input.len() {
+            Ok(())
+        } else {
+            Err(ParseError::Header)
+        }
+    }
+
+    /// Skips the separator after a list or dictionary member; returns false at end of input.
+    fn next_member(&mut self) -> Result<bool, ParseError> {
+        self.skip_ows();
+
+        if self.peek().is_none() {
+            return Ok(false);
+        }
+
+        if !self.eat(b',') {
+            return Err(ParseError::Header);
+        }
+
+        self.skip_ows();
+
+        // trailing comma
+        if self.peek().is_none() {
+            return Err(ParseError::Header);
+        }
+
+        Ok(true)
+    }
+
+    fn parse_list(&mut self) -> Result<List, ParseError> {
+        let mut members = Vec::new();
+
+        if self.peek().is_none() {
+            return Ok(List(members));
+        }
+
+        loop {
+            members.push(self.parse_member()?);
+
+            if !self.next_member()? {
+                return Ok(List(members));
+            }
+        }
+    }
+
+    fn parse_dictionary(&mut self) -> Result<Dictionary, ParseError> {
+        let mut dict = Dictionary::new();
+
+        if self.peek().is_none() {
+            return Ok(dict);
+        }
+
+        loop {
+            let key = self.parse_key()?;
+
+            let member = if self.eat(b'=') {
+                self.parse_member()?
+            } else {
+                Member::Item(Item {
+                    bare_item: BareItem::Boolean(true),
+                    params: self.parse_parameters()?,
+                })
+            };
+
+            dict.insert(key, member);
+
+            if !self.next_member()? {
+                return Ok(dict);
+            }
+        }
+    }
+
+    fn parse_member(&mut self) -> Result<Member, ParseError> {
+        if self.peek() == Some(b'(') {
+            self.parse_inner_list().map(Member::InnerList)
+        } else {
+            self.parse_item().map(Member::Item)
+        }
+    }
+
+    fn parse_inner_list(&mut self) -> Result<InnerList, ParseError> {
+        if !self.eat(b'(') {
+            return Err(ParseError::Header);
+        }
+
+        let mut items = Vec::new();
+
+        loop {
+            self.skip_sp();
+
+            if self.eat(b')') {
+                let params = self.parse_parameters()?;
+                return Ok(InnerList { items, params });
+            }
+
+            items.push(self.parse_item()?);
+
+            // items must be separated by a space or followed by the closing parenthesis
+            if !matches!(self.peek(), Some(b' ' | b')')) {
+                return Err(ParseError::Header);
+            }
+        }
+    }
+
+    fn parse_item(&mut self) -> Result<Item, ParseError> {
+        let bare_item = self.parse_bare_item()?;
+        let params = self.parse_parameters()?;
+        Ok(Item { bare_item, params })
+    }
+
+    fn parse_parameters(&mut self) -> Result<Parameters, ParseError> {
+        let mut params = Parameters::new();
+
+        while self.eat(b';') {
+            self.skip_sp();
+
+            let key = self.parse_key()?;
+
+            let value = if self.eat(b'=') {
+                self.parse_bare_item()?
+            } else {
+                BareItem::Boolean(true)
+            };
+
+            params.insert(key, value);
+        }
+
+        Ok(params)
+    }
+
+    fn parse_key(&mut self) -> Result<String, ParseError> {
+        let start = self.pos;
+
+        match self.peek() {
+            Some(b'a'..=b'z' | b'*') => self.pos += 1,
+            _ => return Err(ParseError::Header),
+        }
+
+        while let Some(b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-' | b'.' | b'*') = self.peek() {
+            self.pos += 1;
+        }
+
+        Ok(self.slice_str(start))
+    }
+
+    fn parse_bare_item(&mut self) -> Result<BareItem, ParseError> {
+        match self.peek() {
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            Some(b'"') => self.parse_string(),
+            Some(b'*' | b'A'..=b'Z' | b'a'..=b'z') => self.parse_token(),
+            Some(b':') => self.parse_byte_sequence(),
+            Some(b'?') => self.parse_boolean(),
+            _ => Err(ParseError::Header),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<BareItem, ParseError> {
+        let start = self.pos;
+        self.eat(b'-');
+
+        let int_start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        let int_digits = self.pos - int_start;
+
+        if int_digits == 0 {
+            return Err(ParseError::Header);
+        }
+
+        if !self.eat(b'.') {
+            if int_digits > 15 {
+                return Err(ParseError::Header);
+            }
+
+            return self
+                .slice_str(start)
+                .parse()
+                .map(BareItem::Integer)
+                .map_err(|_| ParseError::Header);
+        }
+
+        let frac_start = self.pos;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+        let frac_digits = self.pos - frac_start;
+
+        if int_digits > 12 || frac_digits == 0 || frac_digits > 3 {
+            return Err(ParseError::Header);
+        }
+
+        self.slice_str(start)
+            .parse()
+            .map(BareItem::Decimal)
+            .map_err(|_| ParseError::Header)
+    }
+
+    fn parse_string(&mut self) -> Result<BareItem, ParseError> {
+        self.pos += 1;
+        let mut string = String::new();
+
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.pos += 1;
+                    return Ok(BareItem::String(string));
+                }
+
+                Some(b'\\') => {
+                    self.pos += 1;
+
+                    match self.peek() {
+                        Some(byte @ (b'"' | b'\\')) => string.push(byte as char),
+                        _ => return Err(ParseError::Header),
+                    }
+                }
+
+                Some(byte @ 0x20..=0x7E) => string.push(byte as char),
+
+                _ => return Err(ParseError::Header),
+            }
+
+            self.pos += 1;
+        }
+    }
+
+    fn parse_token(&mut self) -> Result<BareItem, ParseError> {
+        let start = self.pos;
+        self.pos += 1;
+
+        while self
+            .peek()
+            .map_or(false, |byte| is_tchar(byte) || byte == b':' || byte == b'/')
+        {
+            self.pos += 1;
+        }
+
+        Ok(BareItem::Token(self.slice_str(start)))
+    }
+
+    fn parse_byte_sequence(&mut self) -> Result<BareItem, ParseError> {
+        self.pos += 1;
+        let start = self.pos;
+
+        while self.peek().map_or(false, |byte| byte != b':') {
+            self.pos += 1;
+        }
+
+        let encoded = &self.input[start..self.pos];
+
+        if !self.eat(b':') {
+            return Err(ParseError::Header);
+        }
+
+        b64::decode_lenient(encoded, Alphabet::Standard).map(BareItem::ByteSequence)
+    }
+
+    fn parse_boolean(&mut self) -> Result<BareItem, ParseError> {
+        self.pos += 1;
+
+        let val = match self.peek() {
+            Some(b'1') => true,
+            Some(b'0') => false,
+            _ => return Err(ParseError::Header),
+        };
+
+        self.pos += 1;
+        Ok(BareItem::Boolean(val))
+    }
+
+    /// Returns the input from `start` to the current position, which must be ASCII.
+    fn slice_str(&self, start: usize) -> String {
+        self.input[start..self.pos]
+            .iter()
+            .map(|&byte| byte as char)
+            .collect()
+    }
+}
+
+/// Returns true for `tchar` bytes, as defined in RFC 7230 §3.2.6.
+fn is_tchar(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+impl fmt::Display for BareItem {
+    /// Serializes the bare item.
+    ///
+    /// Values outside the ranges allowed by RFC 8941, such as integers with more than 15 digits or
+    /// strings with non-printable characters, are written as given and will not parse back.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BareItem::Integer(int) => write!(f, "{}", int),
+
+            BareItem::Decimal(dec) => {
+                let formatted = format!("{:.3}", dec);
+                let trimmed = formatted.trim_end_matches('0');
+
+                if trimmed.ends_with('.') {
+                    write!(f, "{}0", trimmed)
+                } else {
+                    f.write_str(trimmed)
+                }
+            }
+
+            BareItem::String(string) => {
+                f.write_char('"')?;
+
+                for ch in string.chars() {
+                    if ch == '"' || ch == '\\' {
+                        f.write_char('\\')?;
+                    }
+
+                    f.write_char(ch)?;
+                }
+
+                f.write_char('"')
+            }
+
+            BareItem::Token(token) => f.write_str(token),
+
+            BareItem::ByteSequence(bytes) => {
+                f.write_char(':')?;
+
+                f.write_str(&b64::encode(bytes, Alphabet::Standard))?;
+                f.write_char(':')
+            }
+
+            BareItem::Boolean(val) => f.write_str(if *val { "?1" } else { "?0" }),
+        }
+    }
+}
+
+impl fmt::Display for Parameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (key, val) in &self.0 {
+            write!(f, ";{}", key)?;
+
+            if *val != BareItem::Boolean(true) {
+                write!(f, "={}", val)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.bare_item, self.params)
+    }
+}
+
+impl fmt::Display for InnerList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('(')?;
+
+        for (idx, item) in self.items.iter().enumerate() {
+            if idx > 0 {
+                f.write_char(' ')?;
+            }
+
+            write!(f, "{}", item)?;
+        }
+
+        write!(f, "){}", self.params)
+    }
+}
+
+impl fmt::Display for Member {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Member::Item(item) => write!(f, "{}", item),
+            Member::InnerList(list) => write!(f, "{}", list),
+        }
+    }
+}
+
+impl fmt::Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, member) in self.0.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+
+            write!(f, "{}", member)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (key, member)) in self.0.iter().enumerate() {
+            if idx > 0 {
+                f.write_str(", ")?;
+            }
+
+            f.write_str(key)?;
+
+            match member {
+                // boolean true items are written as bare keys
+                Member::Item(Item {
+                    bare_item: BareItem::Boolean(true),
+                    params,
+                }) => write!(f, "{}", params)?,
+
+                member => write!(f, "={}", member)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+macro_rules! impl_try_into_value {
+    ($($ty:ty),+) => {
+        $(
+            impl TryIntoHeaderValue for $ty {
+                type Error = InvalidHeaderValue;
+
+                fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+                    HeaderValue::from_str(&self.to_string())
+                }
+            }
+        )+
+    };
+}
+
+impl_try_into_value!(Item, List, Dictionary);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_priority_dictionary() {
+        let dict = Dictionary::parse(b"u=3, i").unwrap();
+        assert_eq!(dict.len(), 2);
+
+        let urgency = dict.get("u").unwrap().as_item().unwrap();
+        assert_eq!(urgency.bare_item, BareItem::Integer(3));
+        assert!(urgency.params.is_empty());
+
+        let incremental = dict.get("i").unwrap().as_item().unwrap();
+        assert_eq!(incremental.bare_item, BareItem::Boolean(true));
+
+        assert_eq!(dict.to_string(), "u=3, i");
+    }
+
+    #[test]
+    fn parse_list_with_parameters() {
+        let list =
+            List::parse(br#"abc;a=1;b=2; cde_456, (ghi;jk=4 l);q="9";r=w, ?0;x=-1.5"#).unwrap();
+        assert_eq!(list.0.len(), 3);
+
+        let first = list.0[0].as_item().unwrap();
+        assert_eq!(first.bare_item, BareItem::Token("abc".to_owned()));
+        assert_eq!(first.params.get("a"), Some(&BareItem::Integer(1)));
+        assert_eq!(first.params.get("b"), Some(&BareItem::Integer(2)));
+        assert_eq!(first.params.get("cde_456"), Some(&BareItem::Boolean(true)));
+
+        let inner = list.0[1].as_inner_list().unwrap();
+        assert_eq!(inner.items.len(), 2);
+        assert_eq!(inner.items[0].params.get("jk"), Some(&BareItem::Integer(4)));
+        assert_eq!(inner.items[1].bare_item, BareItem::Token("l".to_owned()));
+        assert_eq!(
+            inner.params.get("q"),
+            Some(&BareItem::String("9".to_owned()))
+        );
+        assert_eq!(
+            inner.params.get("r"),
+            Some(&BareItem::Token("w".to_owned()))
+        );
+
+        let last = list.0[2].as_item().unwrap();
+        assert_eq!(last.bare_item, BareItem::Boolean(false));
+        assert_eq!(last.params.get("x"), Some(&BareItem::Decimal(-1.5)));
+
+        assert_eq!(
+            list.to_string(),
+            r#"abc;a=1;b=2;cde_456, (ghi;jk=4 l);q="9";r=w, ?0;x=-1.5"#
+        );
+    }
+
+    #[test]
+    fn round_trips() {
+        let cases: &[&[u8]] = &[
+            b"",
+            b"1, 42, -999999999999999",
+            b"4.5, 0.001, -123456789012.0",
+            br#""hello \"world\"", "back\\slash""#,
+            b"*foo, text/html, a:b/c",
+            b":cHJldGVuZCB0aGlzIGlzIGJpbmFyeSBjb250ZW50Lg==:, ::",
+            b"?1, ?0;a",
+            b"(), (1 2);a=?0, (\"a\" b)",
+        ];
+
+        for &case in cases {
+            let list = List::parse(case).unwrap();
+            assert_eq!(list.to_string().as_bytes(), case);
+            assert_eq!(List::parse(list.to_string().as_bytes()).unwrap(), list);
+        }
+
+        let dict = Dictionary::parse(b"a=?0, b, c;foo=bar, d=(1 2);x").unwrap();
+        assert_eq!(dict.to_string(), "a=?0, b, c;foo=bar, d=(1 2);x");
+
+        let item = Item::parse(b"  2.50;q  ").unwrap();
+        assert_eq!(item.to_string(), "2.5;q");
+    }
+
+    #[test]
+    fn byte_sequences() {
+        let item = Item::parse(b":aGk=:").unwrap();
+        assert_eq!(item.bare_item, BareItem::ByteSequence(b"hi".to_vec()));
+
+        // unpadded input is accepted but serialized with padding
+        let item = Item::parse(b":aGk:").unwrap();
+        assert_eq!(item.to_string(), ":aGk=:");
+
+        assert!(Item::parse(b":a=Gk:").is_err());
+        assert!(Item::parse(b":a:").is_err());
+    }
+
+    #[test]
+    fn builders() {
+        let mut dict = Dictionary::new();
+        dict.insert("u", Item::new(BareItem::Integer(1)));
+        dict.insert("i", Item::new(BareItem::Boolean(true)));
+        dict.insert("u", Item::new(BareItem::Integer(5)));
+        assert_eq!(dict.to_string(), "u=5, i");
+
+        let item = Item::new(BareItem::Token("hit".to_owned()))
+            .with_param("ttl", BareItem::Integer(30))
+            .with_param("detail", BareItem::String("a \"b\"".to_owned()));
+        assert_eq!(item.to_string(), r#"hit;ttl=30;detail="a \"b\"""#);
+
+        let value = item.try_into_value().unwrap();
+        assert_eq!(value, r#"hit;ttl=30;detail="a \"b\"""#);
+    }
+
+    #[test]
+    fn duplicate_keys_keep_first_position() {
+        let dict = Dictionary::parse(b"a=1, b=2, a=3").unwrap();
+        assert_eq!(dict.to_string(), "a=3, b=2");
+
+        let item = Item::parse(b"x;a=1;b;a=2").unwrap();
+        assert_eq!(item.to_string(), "x;a=2;b");
+    }
+
+    #[test]
+    fn invalid_values() {
+        let cases: &[&[u8]] = &[
+            b"1,",
+            b",1",
+            b"1 2",
+            b"1234567890123456",
+            b"1.2345",
+            b"1234567890123.0",
+            b"1.",
+            b"\"unterminated",
+            b"\"bad \\escape\"",
+            b"?2",
+            b"(1 2",
+            b"(1 2)a",
+            b"a;B=1",
+            b"\xC3\xA9",
+        ];
+
+        for &case in cases {
+            assert!(List::parse(case).is_err(), "{:?}", case);
+        }
+
+        assert!(Dictionary::parse(b"A=1").is_err());
+        assert!(Dictionary::parse(b"a=1,").is_err());
+        assert!(Item::parse(b"1, 2").is_err());
+        assert!(Item::parse(b"").is_err());
+    }
+}
@@ -1,11 +1,13 @@
 //! Header parsing utilities.
 
-use std::{fmt, str::FromStr};
+use std::{fmt, str, str::FromStr};
 
 use super::HeaderValue;
 use crate::{error::ParseError, header::HTTP_VALUE};
 
 /// Reads a comma-delimited raw header into a Vec.
+///
+/// Header values must be valid UTF-8; otherwise, a [`ParseError::Utf8`] error is returned.
 #[inline]
 pub fn from_comma_delimited<'a, I, T>(all: I) -> Result<Vec<T>, ParseError>
 where
@@ -16,7 +18,7 @@ where
     let mut result = Vec::with_capacity(size_guess);
 
     for h in all {
-        let s = h.to_str().map_err(|_| ParseError::Header)?;
+        let s = str::from_utf8(h.as_bytes())?;
 
         result.extend(
             s.split(',')
@@ -31,11 +33,77 @@ where
     Ok(result)
 }
 
+/// Reads a comma-delimited raw header into a Vec, ignoring commas inside quoted strings and
+/// comments.
+///
+/// Elements that fail to parse are skipped, as in [`from_comma_delimited`].
+pub(crate) fn from_comma_delimited_quoted<'a, I, T>(all: I) -> Result<Vec<T>, ParseError>
+where
+    I: Iterator<Item = &'a HeaderValue> + 'a,
+    T: FromStr,
+{
+    let mut result = Vec::new();
+
+    for h in all {
+        let s = str::from_utf8(h.as_bytes())?;
+
+        result.extend(
+            split_comma_delimited_quoted(s)
+                .into_iter()
+                .filter_map(|x| x.parse().ok()),
+        );
+    }
+
+    Ok(result)
+}
+
+/// Splits a comma-delimited header value into trimmed, non-empty elements.
+///
+/// Commas inside quoted strings (`"..."`) and comments (`(...)`, which may nest) do not split
+/// elements. Quoted pairs (`\x`) inside either are skipped over.
+fn split_comma_delimited_quoted(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+
+    let mut start = 0;
+    let mut depth = 0_usize;
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for (idx, ch) in value.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+
+        match ch {
+            '\\' if quoted || depth > 0 => escaped = true,
+            '"' if depth == 0 => quoted = !quoted,
+            '(' if !quoted => depth += 1,
+            ')' if !quoted => depth = depth.saturating_sub(1),
+            ',' if !quoted && depth == 0 => {
+                parts.push(&value[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+
+    parts.push(&value[start..]);
+
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect()
+}
+
 /// Reads a single string when parsing a header.
+///
+/// The header value must be valid UTF-8; otherwise, a [`ParseError::Utf8`] error is returned.
 #[inline]
 pub fn from_one_raw_str<T: FromStr>(val: Option<&HeaderValue>) -> Result<T, ParseError> {
     if let Some(line) = val {
-        let line = line.to_str().map_err(|_| ParseError::Header)?;
+        let line = str::from_utf8(line.as_bytes())?;
 
         if !line.is_empty() {
             return T::from_str(line).or(Err(ParseError::Header));
@@ -101,4 +169,34 @@ mod tests {
         let res: Vec<usize> = from_comma_delimited(headers.iter()).unwrap();
         assert_eq!(res, vec![1]);
     }
+
+    #[test]
+    fn non_ascii_values() {
+        let utf8 = HeaderValue::from_bytes("caf\u{e9}".as_bytes()).unwrap();
+        let res: String = from_one_raw_str(Some(&utf8)).unwrap();
+        assert_eq!(res, "caf\u{e9}");
+
+        let latin1 = HeaderValue::from_bytes(b"caf\xe9").unwrap();
+        let res = from_one_raw_str::<String>(Some(&latin1));
+        assert!(matches!(res, Err(ParseError::Utf8(_))));
+        let res = from_comma_delimited::<_, String>([latin1].iter());
+        assert!(matches!(res, Err(ParseError::Utf8(_))));
+    }
+
+    #[test]
+    fn quoted_comma_delimited_splitting() {
+        assert_eq!(
+            split_comma_delimited_quoted(r#"a, "b, c", (d, (e, f)), "g\", h", (i\), j), , k"#),
+            vec![
+                r#"a"#,
+                r#""b, c""#,
+                "(d, (e, f))",
+                r#""g\", h""#,
+                r#"(i\), j)"#,
+                "k"
+            ],
+        );
+
+        assert!(split_comma_delimited_quoted(" , ").is_empty());
+    }
 }
@@ -34,6 +34,8 @@ pub mod body;
 mod builder;
 mod config;
 mod date;
+mod disconnect;
+mod drain;
 #[cfg(feature = "__compress")]
 pub mod encoding;
 pub mod error;
@@ -45,6 +47,7 @@ pub mod header;
 mod helpers;
 mod http_message;
 mod keep_alive;
+mod lifecycle;
 mod message;
 #[cfg(test)]
 mod notify_on_drop;
@@ -58,17 +61,20 @@ pub mod ws;
 
 pub use self::builder::HttpServiceBuilder;
 pub use self::config::ServiceConfig;
+pub use self::disconnect::{DisconnectSignal, Disconnected};
+pub use self::drain::DrainSignal;
 pub use self::error::Error;
 pub use self::extensions::Extensions;
 pub use self::header::ContentEncoding;
 pub use self::http_message::HttpMessage;
 pub use self::keep_alive::KeepAlive;
+pub use self::lifecycle::{ConnectionClosed, ObserveResponse, ResponseObserver};
 pub use self::message::ConnectionType;
 pub use self::message::Message;
 #[allow(deprecated)]
 pub use self::payload::{BoxedPayloadStream, Payload, PayloadStream};
 pub use self::requests::{Request, RequestHead, RequestHeadType};
-pub use self::responses::{Response, ResponseBuilder, ResponseHead};
+pub use self::responses::{Response, ResponseBuilder, ResponseHead, ResponseRoute};
 pub use self::service::HttpService;
 #[cfg(any(feature = "openssl", feature = "rustls"))]
 pub use self::service::TlsAcceptorConfig;
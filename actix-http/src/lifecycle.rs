@@ -0,0 +1,167 @@
+//! Connection and response lifecycle events.
+
+use std::{
+    fmt, net,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crate::{config::ServiceConfig, Protocol, Response, StatusCode};
+
+/// Describes a connection that the server has finished with.
+///
+/// Passed to callbacks registered with
+/// [`HttpServiceBuilder::on_connection_close`](crate::HttpServiceBuilder::on_connection_close).
+#[derive(Debug, Clone)]
+pub struct ConnectionClosed {
+    protocol: Protocol,
+    peer_addr: Option<net::SocketAddr>,
+    requests: usize,
+    duration: Duration,
+}
+
+impl ConnectionClosed {
+    /// Returns the protocol that was used on the connection.
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// Returns the peer address of the connection, if known.
+    pub fn peer_addr(&self) -> Option<net::SocketAddr> {
+        self.peer_addr
+    }
+
+    /// Returns the number of requests received on the connection.
+    pub fn requests(&self) -> usize {
+        self.requests
+    }
+
+    /// Returns how long the connection was open.
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+pub(crate) type ConnectionCloseHook = Rc<dyn Fn(&ConnectionClosed)>;
+
+/// Callbacks run when a connection is closed, in registration order.
+#[derive(Clone, Default)]
+pub(crate) struct ConnectionCloseHooks(pub(crate) Vec<ConnectionCloseHook>);
+
+impl fmt::Debug for ConnectionCloseHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConnectionCloseHooks")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+/// Tracks a connection for its close event.
+///
+/// Only records the connection's start time when close hooks are registered.
+pub(crate) struct ConnectionLifecycle {
+    observed: Option<(ServiceConfig, Instant)>,
+    protocol: Protocol,
+    peer_addr: Option<net::SocketAddr>,
+    requests: usize,
+}
+
+impl ConnectionLifecycle {
+    pub(crate) fn new(
+        config: &ServiceConfig,
+        protocol: Protocol,
+        peer_addr: Option<net::SocketAddr>,
+    ) -> Self {
+        let observed = config
+            .has_connection_close_hooks()
+            .then(|| (config.clone(), Instant::now()));
+
+        Self {
+            observed,
+            protocol,
+            peer_addr,
+            requests: 0,
+        }
+    }
+
+    /// Counts a request received on the connection.
+    pub(crate) fn request_received(&mut self) {
+        self.requests += 1;
+    }
+}
+
+impl Drop for ConnectionLifecycle {
+    fn drop(&mut self) {
+        if let Some((config, started)) = self.observed.take() {
+            config.connection_closed(&ConnectionClosed {
+                protocol: self.protocol,
+                peer_addr: self.peer_addr,
+                requests: self.requests,
+                duration: started.elapsed(),
+            });
+        }
+    }
+}
+
+/// Observes the progress of sending a response.
+///
+/// Frameworks insert an observer into a response's extensions using [`ObserveResponse`]; the
+/// dispatcher then reports when the response head is written and when the body ends.
+#[doc(hidden)]
+pub trait ResponseObserver {
+    /// Called once the response head has been written to the connection's send buffer.
+    fn headers_written(&self, status: StatusCode);
+
+    /// Called once sending the response body ends, with the number of body bytes sent.
+    ///
+    /// `complete` is false if the body was cut short, such as when the body stream fails or the
+    /// connection is closed.
+    fn body_finished(&self, bytes: u64, complete: bool);
+}
+
+/// Response extension holding a [`ResponseObserver`].
+#[doc(hidden)]
+pub struct ObserveResponse(pub Rc<dyn ResponseObserver>);
+
+/// Reports the progress of a response to its observer.
+///
+/// If dropped before [`finish`](Self::finish) is called, the body is reported as incomplete.
+pub(crate) struct ResponseProgress {
+    observer: Rc<dyn ResponseObserver>,
+    bytes: u64,
+    finished: bool,
+}
+
+impl ResponseProgress {
+    /// Takes the observer of `res`, if it has one.
+    pub(crate) fn take<B>(res: &mut Response<B>) -> Option<Self> {
+        let ObserveResponse(observer) = res.extensions_mut().remove::<ObserveResponse>()?;
+
+        Some(Self {
+            observer,
+            bytes: 0,
+            finished: false,
+        })
+    }
+
+    pub(crate) fn headers_written(&self, status: StatusCode) {
+        self.observer.headers_written(status);
+    }
+
+    pub(crate) fn body_chunk(&mut self, len: usize) {
+        self.bytes += len as u64;
+    }
+
+    pub(crate) fn finish(mut self) {
+        self.finished = true;
+        self.observer.body_finished(self.bytes, true);
+    }
+}
+
+impl Drop for ResponseProgress {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.observer.body_finished(self.bytes, false);
+        }
+    }
+}
@@ -65,11 +65,18 @@ impl<T: Head> ops::DerefMut for Message<T> {
 
 impl<T: Head> Drop for Message<T> {
     fn drop(&mut self) {
-        T::with_pool(|p| p.release(self.head.clone()))
+        // a head that is still shared is left to its other owners instead of being recycled
+        if Rc::get_mut(&mut self.head).is_some() {
+            T::with_pool(|p| p.release(self.head.clone()))
+        }
     }
 }
 
 /// Generic `Head` object pool.
+///
+/// Heads are recycled across requests on the same worker thread, including consecutive requests
+/// on a keep-alive connection, so that their header maps keep their allocations. Recycled heads
+/// are [cleared](Head::clear) before reuse.
 #[doc(hidden)]
 pub struct MessagePool<T: Head>(RefCell<Vec<Rc<T>>>);
 
@@ -82,17 +89,17 @@ impl<T: Head> MessagePool<T> {
     #[inline]
     fn get_message(&self) -> Message<T> {
         if let Some(mut msg) = self.0.borrow_mut().pop() {
-            // Message is put in pool only when it's the last copy.
-            // which means it's guaranteed to be unique when popped out.
-            Rc::get_mut(&mut msg)
-                .expect("Multiple copies exist")
-                .clear();
-            Message { head: msg }
-        } else {
-            Message {
-                head: Rc::new(T::default()),
+            // Message is put in pool only when it's the last copy, so it should be unique when
+            // popped out; if not, fall back to a fresh allocation rather than share it.
+            if let Some(head) = Rc::get_mut(&mut msg) {
+                head.clear();
+                return Message { head: msg };
             }
         }
+
+        Message {
+            head: Rc::new(T::default()),
+        }
     }
 
     #[inline]
@@ -104,3 +111,65 @@ impl<T: Head> MessagePool<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        header::{self, HeaderValue},
+        Method, RequestHead, Uri, Version,
+    };
+
+    fn pool_len() -> usize {
+        RequestHead::with_pool(|pool| pool.0.borrow().len())
+    }
+
+    #[test]
+    fn shared_head_is_not_recycled() {
+        // fill the pool so the next message is recycled
+        drop(Message::<RequestHead>::new());
+        let pooled = pool_len();
+        assert!(pooled > 0);
+
+        let msg = Message::<RequestHead>::new();
+        assert_eq!(pool_len(), pooled - 1);
+
+        let retained = Rc::clone(&msg.head);
+        drop(msg);
+        assert_eq!(pool_len(), pooled - 1);
+
+        // a shared head that ends up in the pool is never handed out
+        RequestHead::with_pool(|pool| pool.release(Rc::clone(&retained)));
+        let mut msg = Message::<RequestHead>::new();
+        assert!(!Rc::ptr_eq(&msg.head, &retained));
+        assert!(Rc::get_mut(&mut msg.head).is_some());
+    }
+
+    #[test]
+    fn recycled_head_is_reset() {
+        let mut msg = Message::<RequestHead>::new();
+        msg.method = Method::POST;
+        msg.uri = Uri::from_static("/upload?id=1");
+        msg.version = Version::HTTP_10;
+        msg.peer_addr = Some("127.0.0.1:8080".parse().unwrap());
+        msg.headers_mut()
+            .insert(header::HOST, HeaderValue::from_static("example.com"));
+        msg.set_connection_type(ConnectionType::Upgrade);
+        msg.set_camel_case_headers(true);
+        msg.set_expect();
+
+        let ptr = Rc::as_ptr(&msg.head);
+        drop(msg);
+
+        let msg = Message::<RequestHead>::new();
+        assert_eq!(Rc::as_ptr(&msg.head), ptr);
+        assert_eq!(msg.method, Method::GET);
+        assert_eq!(msg.uri, Uri::default());
+        assert_eq!(msg.version, Version::HTTP_11);
+        assert_eq!(msg.peer_addr, None);
+        assert!(msg.headers().is_empty());
+        assert!(!msg.upgrade());
+        assert!(!msg.camel_case_headers());
+        assert!(!msg.expect());
+    }
+}
@@ -26,7 +26,7 @@ impl Default for RequestHead {
             method: Method::default(),
             uri: Uri::default(),
             version: Version::HTTP_11,
-            headers: HeaderMap::with_capacity(16),
+            headers: HeaderMap::new(),
             peer_addr: None,
             flags: Flags::empty(),
         }
@@ -35,8 +35,13 @@ impl Default for RequestHead {
 
 impl Head for RequestHead {
     fn clear(&mut self) {
-        self.flags = Flags::empty();
+        // header map is cleared in place to keep its allocation for the next request
+        self.method = Method::default();
+        self.uri = Uri::default();
+        self.version = Version::HTTP_11;
         self.headers.clear();
+        self.peer_addr = None;
+        self.flags = Flags::empty();
     }
 
     fn with_pool<F, R>(f: F) -> R
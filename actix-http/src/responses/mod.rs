@@ -4,8 +4,10 @@ mod builder;
 mod head;
 #[allow(clippy::module_inception)]
 mod response;
+mod route;
 
 pub use self::builder::ResponseBuilder;
 pub(crate) use self::head::BoxedResponseHead;
 pub use self::head::ResponseHead;
 pub use self::response::Response;
+pub use self::route::ResponseRoute;
@@ -0,0 +1,26 @@
+use std::fmt;
+
+/// Describes the route that produced a response, for naming it in server error logs.
+///
+/// Frameworks insert this into the response's extensions. The description is only built when an
+/// error involving the response is logged.
+#[doc(hidden)]
+pub struct ResponseRoute(Box<dyn Fn() -> Option<String>>);
+
+impl ResponseRoute {
+    /// Constructs a route description that is built by `describe` when needed.
+    pub fn new(describe: impl Fn() -> Option<String> + 'static) -> Self {
+        Self(Box::new(describe))
+    }
+
+    /// Returns the route description, such as a resource pattern, if the route is known.
+    pub fn describe(&self) -> Option<String> {
+        (self.0)()
+    }
+}
+
+impl fmt::Debug for ResponseRoute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ResponseRoute").finish()
+    }
+}
@@ -8,7 +8,8 @@ use actix_http::{
     body::{BodyStream, BoxBody, SizedStream},
     error::PayloadError,
     header::{self, HeaderValue},
-    Error, HttpService, Method, Request, Response, StatusCode, TlsAcceptorConfig, Version,
+    DisconnectSignal, Error, HttpMessage as _, HttpService, Method, Request, Response,
+    StatusCode, TlsAcceptorConfig, Version,
 };
 use actix_http_test::test_server;
 use actix_service::{fn_service, ServiceFactoryExt};
@@ -445,3 +446,47 @@ async fn h2_on_connect() {
     let response = srv.sget("/").send().await.unwrap();
     assert!(response.status().is_success());
 }
+
+#[actix_rt::test]
+async fn h2_stream_reset_raises_disconnect_signal() {
+    use std::sync::mpsc;
+
+    let (tx, rx) = mpsc::channel();
+
+    let srv = test_server(move || {
+        let tx = tx.clone();
+
+        HttpService::build()
+            .h2(move |req: Request| {
+                let signal = req.extensions().get::<DisconnectSignal>().cloned().unwrap();
+                let tx = tx.clone();
+
+                async move {
+                    let res =
+                        actix_rt::time::timeout(Duration::from_secs(5), signal.disconnected())
+                            .await;
+                    tx.send(res.is_ok()).unwrap();
+
+                    Ok::<_, Infallible>(Response::ok())
+                }
+            })
+            .openssl(tls_config())
+            .map_err(|_| ())
+    })
+    .await;
+
+    // the client resets the stream when the request times out
+    let res = srv
+        .sget("/")
+        .timeout(Duration::from_millis(100))
+        .send()
+        .await;
+    assert!(res.is_err());
+
+    // keep the client runtime free to send the reset frame while waiting
+    let disconnected =
+        actix_rt::task::spawn_blocking(move || rx.recv_timeout(Duration::from_secs(5)))
+            .await
+            .unwrap();
+    assert!(disconnected.unwrap());
+}
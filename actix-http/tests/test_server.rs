@@ -91,13 +91,16 @@ async fn expect_continue() {
     .await;
 
     let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
-    let _ = stream.write_all(b"GET /test HTTP/1.1\r\nexpect: 100-continue\r\n\r\n");
+    let _ = stream
+        .write_all(b"GET /test HTTP/1.1\r\nHost: localhost\r\nexpect: 100-continue\r\n\r\n");
     let mut data = String::new();
     let _ = stream.read_to_string(&mut data);
     assert!(data.starts_with("HTTP/1.1 417 Expectation Failed\r\ncontent-length"));
 
     let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
-    let _ = stream.write_all(b"GET /test?yes= HTTP/1.1\r\nexpect: 100-continue\r\n\r\n");
+    let _ = stream.write_all(
+        b"GET /test?yes= HTTP/1.1\r\nHost: localhost\r\nexpect: 100-continue\r\n\r\n",
+    );
     let mut data = String::new();
     let _ = stream.read_to_string(&mut data);
     assert!(data.starts_with("HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\n"));
@@ -124,13 +127,16 @@ async fn expect_continue_h1() {
     .await;
 
     let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
-    let _ = stream.write_all(b"GET /test HTTP/1.1\r\nexpect: 100-continue\r\n\r\n");
+    let _ = stream
+        .write_all(b"GET /test HTTP/1.1\r\nHost: localhost\r\nexpect: 100-continue\r\n\r\n");
     let mut data = String::new();
     let _ = stream.read_to_string(&mut data);
     assert!(data.starts_with("HTTP/1.1 417 Expectation Failed\r\ncontent-length"));
 
     let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
-    let _ = stream.write_all(b"GET /test?yes= HTTP/1.1\r\nexpect: 100-continue\r\n\r\n");
+    let _ = stream.write_all(
+        b"GET /test?yes= HTTP/1.1\r\nHost: localhost\r\nexpect: 100-continue\r\n\r\n",
+    );
     let mut data = String::new();
     let _ = stream.read_to_string(&mut data);
     assert!(data.starts_with("HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 200 OK\r\n"));
@@ -163,7 +169,9 @@ async fn chunked_payload() {
 
     let returned_size = {
         let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
-        let _ = stream.write_all(b"POST /test HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n");
+        let _ = stream.write_all(
+            b"POST /test HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n",
+        );
 
         for chunk_size in chunk_sizes.iter() {
             let mut bytes = Vec::new();
@@ -250,6 +258,149 @@ async fn http1_malformed_request() {
     srv.stop().await;
 }
 
+#[actix_rt::test]
+async fn http1_oversized_head() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .h1(|_| ok::<_, Infallible>(Response::ok()))
+            .tcp()
+    })
+    .await;
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let uri = format!("/{}", "a".repeat(100 * 1024));
+    let _ = stream.write_all(format!("GET {} HTTP/1.1\r\n\r\n", uri).as_bytes());
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 414 URI Too Long\r\n"));
+    assert!(data.contains("connection: close\r\n"));
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let mut req = String::from("GET / HTTP/1.1\r\n");
+    for idx in 0..100 {
+        req.push_str(&format!("x-header-{}: value\r\n", idx));
+    }
+    req.push_str("\r\n");
+    let _ = stream.write_all(req.as_bytes());
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 431 Request Header Fields Too Large\r\n"));
+    assert!(data.contains("connection: close\r\n"));
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn http1_host_validation() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .h1(|_| ok::<_, Infallible>(Response::ok()))
+            .tcp()
+    })
+    .await;
+
+    let rejected: [&[u8]; 3] = [
+        // missing host
+        b"GET /test HTTP/1.1\r\n\r\n",
+        // multiple hosts
+        b"GET /test HTTP/1.1\r\nHost: a.com\r\nHost: b.com\r\n\r\n",
+        // absolute-form authority differs from host
+        b"GET http://a.com/test HTTP/1.1\r\nHost: b.com\r\n\r\n",
+    ];
+
+    for req in rejected {
+        let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+        let _ = stream.write_all(req);
+        let mut data = String::new();
+        let _ = stream.read_to_string(&mut data);
+        assert!(data.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        assert!(data.contains("connection: close\r\n"));
+    }
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET http://a.com/test HTTP/1.1\r\nHost: a.com\r\n\r\n");
+    let mut data = vec![0; 1024];
+    let _ = stream.read(&mut data);
+    assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn http1_host_validation_disabled() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .strict_host_validation(false)
+            .h1(|_| ok::<_, Infallible>(Response::ok()))
+            .tcp()
+    })
+    .await;
+
+    let accepted: [&[u8]; 3] = [
+        b"GET /test HTTP/1.1\r\n\r\n",
+        b"GET /test HTTP/1.1\r\nHost: a.com\r\nHost: b.com\r\n\r\n",
+        b"GET http://a.com/test HTTP/1.1\r\nHost: b.com\r\n\r\n",
+    ];
+
+    for req in accepted {
+        let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+        let _ = stream.write_all(req);
+        let mut data = vec![0; 1024];
+        let _ = stream.read(&mut data);
+        assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
+    }
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn http1_title_case_headers() {
+    use actix_http::header::{HeaderName, PreserveCase as _};
+
+    let service = |title_case| {
+        HttpService::build()
+            .h1_title_case_headers(title_case)
+            .h1(|_| {
+                let mut res = Response::ok();
+                res.headers_mut().insert(
+                    header::CONTENT_TYPE,
+                    header::HeaderValue::from_static("text/plain"),
+                );
+                res.headers_mut().insert(
+                    HeaderName::from_static_preserve_case("X-API-ID"),
+                    header::HeaderValue::from_static("1"),
+                );
+                ok::<_, Infallible>(res)
+            })
+            .tcp()
+    };
+
+    let request = b"GET /test HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+
+    let mut srv = test_server(move || service(true)).await;
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(request);
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(data.contains("\r\nContent-Type: text/plain\r\n"));
+    assert!(data.contains("\r\nContent-Length: 0\r\n"));
+    assert!(data.contains("\r\nConnection: close\r\n"));
+    assert!(data.contains("\r\nDate: "));
+    assert!(data.contains("\r\nX-API-ID: 1\r\n"));
+    srv.stop().await;
+
+    let mut srv = test_server(move || service(false)).await;
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(request);
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.contains("\r\ncontent-type: text/plain\r\n"));
+    assert!(data.contains("\r\ncontent-length: 0\r\n"));
+    assert!(data.contains("\r\nx-api-id: 1\r\n"));
+    srv.stop().await;
+}
+
 #[actix_rt::test]
 async fn http1_keepalive() {
     let mut srv = test_server(|| {
@@ -260,12 +411,12 @@ async fn http1_keepalive() {
     .await;
 
     let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
-    let _ = stream.write_all(b"GET /test/tests/test HTTP/1.1\r\n\r\n");
+    let _ = stream.write_all(b"GET /test/tests/test HTTP/1.1\r\nHost: localhost\r\n\r\n");
     let mut data = vec![0; 1024];
     let _ = stream.read(&mut data);
     assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
 
-    let _ = stream.write_all(b"GET /test/tests/test HTTP/1.1\r\n\r\n");
+    let _ = stream.write_all(b"GET /test/tests/test HTTP/1.1\r\nHost: localhost\r\n\r\n");
     let mut data = vec![0; 1024];
     let _ = stream.read(&mut data);
     assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
@@ -285,7 +436,7 @@ async fn http1_keepalive_timeout() {
 
     let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
 
-    let _ = stream.write_all(b"GET /test HTTP/1.1\r\n\r\n");
+    let _ = stream.write_all(b"GET /test HTTP/1.1\r\nHost: localhost\r\n\r\n");
     let mut data = vec![0; 256];
     let _ = stream.read(&mut data);
     assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
@@ -309,7 +460,9 @@ async fn http1_keepalive_close() {
     .await;
 
     let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
-    let _ = stream.write_all(b"GET /test/tests/test HTTP/1.1\r\nconnection: close\r\n\r\n");
+    let _ = stream.write_all(
+        b"GET /test/tests/test HTTP/1.1\r\nHost: localhost\r\nconnection: close\r\n\r\n",
+    );
     let mut data = vec![0; 1024];
     let _ = stream.read(&mut data);
     assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
@@ -383,7 +536,7 @@ async fn http1_keepalive_disabled() {
     .await;
 
     let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
-    let _ = stream.write_all(b"GET /test/tests/test HTTP/1.1\r\n\r\n");
+    let _ = stream.write_all(b"GET /test/tests/test HTTP/1.1\r\nHost: localhost\r\n\r\n");
     let mut data = vec![0; 1024];
     let _ = stream.read(&mut data);
     assert_eq!(&data[..17], b"HTTP/1.1 200 OK\r\n");
@@ -858,3 +1011,46 @@ async fn not_modified_spec_h1() {
 
     srv.stop().await;
 }
+
+#[actix_rt::test]
+async fn h1_body_channel() {
+    let mut srv = test_server(|| {
+        HttpService::build()
+            .h1(fn_service(|req: Request| async move {
+                let (mut tx, body) = body::channel(1);
+                let abort = req.path() == "/abort";
+
+                actix_rt::spawn(async move {
+                    for chunk in ["one", "two", "three"] {
+                        tx.send(Bytes::from_static(chunk.as_bytes())).await.unwrap();
+                    }
+
+                    if abort {
+                        tx.abort("producer failed");
+                    }
+                });
+
+                Ok::<_, Infallible>(Response::ok().set_body(body))
+            }))
+            .tcp()
+    })
+    .await;
+
+    let response = srv.get("/").send().await.unwrap();
+    assert!(response.status().is_success());
+    assert!(response.headers().get(header::CONTENT_LENGTH).is_none());
+    let bytes = srv.load_body(response).await.unwrap();
+    assert_eq!(bytes, "onetwothree");
+
+    // an aborted body must not look like a complete response; the connection is closed without
+    // the terminating chunk
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    let _ = stream.write_all(b"GET /abort HTTP/1.1\r\nHost: localhost\r\n\r\n");
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(data.contains("transfer-encoding: chunked\r\n"));
+    assert!(!data.ends_with("0\r\n\r\n"));
+
+    srv.stop().await;
+}
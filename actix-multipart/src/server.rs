@@ -458,6 +458,16 @@ impl Field {
             .get_name()
             .expect("field name should be guaranteed to exist in multipart form-data")
     }
+
+    /// Returns the field's file name, if sent, made safe for use as a local file name.
+    ///
+    /// The extended *filename\** parameter is preferred over *filename*. Directory components,
+    /// leading dots, control characters and reserved characters are removed; see
+    /// [`ContentDisposition::get_sanitized_filename`]. The unmodified values are available from
+    /// [content_disposition](Self::content_disposition).
+    pub fn sanitized_filename(&self) -> Option<String> {
+        self.content_disposition().get_sanitized_filename()
+    }
 }
 
 impl Stream for Field {
@@ -1293,6 +1303,48 @@ mod tests {
         ));
     }
 
+    #[actix_rt::test]
+    async fn sanitized_filename() {
+        let bytes = Bytes::from(
+            "testasdadsad\r\n\
+             --abbc761f78ff4d7cb7573b5a23f96ef0\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"../../.profile\"\r\n\
+             Content-Type: text/plain; charset=utf-8\r\nContent-Length: 4\r\n\r\n\
+             test\r\n\
+             --abbc761f78ff4d7cb7573b5a23f96ef0\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"smile.png\"; \
+             filename*=UTF-8''%F0%9F%98%80.png\r\n\
+             Content-Type: image/png\r\nContent-Length: 4\r\n\r\n\
+             data\r\n\
+             --abbc761f78ff4d7cb7573b5a23f96ef0--\r\n",
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::CONTENT_TYPE,
+            header::HeaderValue::from_static(
+                "multipart/mixed; boundary=\"abbc761f78ff4d7cb7573b5a23f96ef0\"",
+            ),
+        );
+        let (sender, payload) = create_stream();
+        sender.send(Ok(bytes)).unwrap();
+        drop(sender); // eof
+
+        let mut multipart = Multipart::new(&headers, payload);
+
+        let mut field = multipart.next().await.unwrap().unwrap();
+        assert_eq!(
+            field.content_disposition().get_filename(),
+            Some("../../.profile")
+        );
+        assert_eq!(field.sanitized_filename().as_deref(), Some("profile"));
+        assert_eq!(field.next().await.unwrap().unwrap(), "test");
+        assert!(field.next().await.is_none());
+        drop(field);
+
+        let field = multipart.next().await.unwrap().unwrap();
+        assert_eq!(field.sanitized_filename().as_deref(), Some("\u{1f600}.png"));
+    }
+
     #[actix_rt::test]
     async fn test_drop_multipart_dont_hang() {
         let (sender, payload) = create_stream();
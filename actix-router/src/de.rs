@@ -7,7 +7,7 @@ use crate::path::{Path, PathIter};
 use crate::{Quoter, ResourcePath};
 
 thread_local! {
-    static FULL_QUOTER: Quoter = Quoter::new(b"", b"");
+    pub(crate) static FULL_QUOTER: Quoter = Quoter::new(b"", b"");
 }
 
 macro_rules! unsupported_type {
@@ -51,10 +51,7 @@ macro_rules! parse_value {
         where
             V: Visitor<'de>,
         {
-            let decoded = FULL_QUOTER
-                .with(|q| q.requote_str_lossy(self.value))
-                .map(Cow::Owned)
-                .unwrap_or(Cow::Borrowed(self.value));
+            let decoded = self.decode()?;
 
             let v = decoded.parse().map_err(|_| {
                 de::value::Error::custom(format!("can not parse {:?} to a {}", self.value, $tp))
@@ -287,6 +284,18 @@ struct Value<'de> {
     value: &'de str,
 }
 
+impl<'de> Value<'de> {
+    /// Percent-decodes the value, failing if the decoded bytes are not valid UTF-8.
+    fn decode(&self) -> Result<Cow<'de, str>, de::value::Error> {
+        match FULL_QUOTER.with(|q| q.requote(self.value.as_bytes())) {
+            Some(data) => String::from_utf8(data).map(Cow::Owned).map_err(|_| {
+                de::value::Error::custom(format!("{:?} is not valid UTF-8", self.value))
+            }),
+            None => Ok(Cow::Borrowed(self.value)),
+        }
+    }
+}
+
 impl<'de> Deserializer<'de> for Value<'de> {
     type Error = de::value::Error;
 
@@ -332,9 +341,9 @@ impl<'de> Deserializer<'de> for Value<'de> {
     where
         V: Visitor<'de>,
     {
-        match FULL_QUOTER.with(|q| q.requote_str_lossy(self.value)) {
-            Some(s) => visitor.visit_string(s),
-            None => visitor.visit_borrowed_str(self.value),
+        match self.decode()? {
+            Cow::Owned(s) => visitor.visit_string(s),
+            Cow::Borrowed(s) => visitor.visit_borrowed_str(s),
         }
     }
 
@@ -342,8 +351,8 @@ impl<'de> Deserializer<'de> for Value<'de> {
     where
         V: Visitor<'de>,
     {
-        match FULL_QUOTER.with(|q| q.requote_str_lossy(self.value)) {
-            Some(s) => visitor.visit_byte_buf(s.into()),
+        match FULL_QUOTER.with(|q| q.requote(self.value.as_bytes())) {
+            Some(data) => visitor.visit_byte_buf(data),
             None => visitor.visit_borrowed_bytes(self.value.as_bytes()),
         }
     }
@@ -3,7 +3,10 @@ use std::ops::{DerefMut, Index};
 
 use serde::de;
 
-use crate::{de::PathDeserializer, Resource, ResourcePath};
+use crate::{
+    de::{PathDeserializer, FULL_QUOTER},
+    Resource, ResourcePath,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) enum PathItem {
@@ -147,6 +150,20 @@ impl<T: ResourcePath> Path<T> {
         None
     }
 
+    /// Get matched parameter by name as percent-decoded bytes.
+    ///
+    /// Unlike [`get`](Self::get), all escape sequences are decoded and the result does not need to
+    /// be valid UTF-8, giving access to segments that were kept percent-encoded by
+    /// [`Url::preserve_invalid_utf8`](crate::Url::preserve_invalid_utf8).
+    pub fn get_bytes(&self, name: &str) -> Option<Cow<'_, [u8]>> {
+        let segment = self.get(name)?;
+
+        Some(match FULL_QUOTER.with(|q| q.requote(segment.as_bytes())) {
+            Some(data) => Cow::Owned(data),
+            None => Cow::Borrowed(segment.as_bytes()),
+        })
+    }
+
     /// Get matched parameter by name.
     ///
     /// If keyed parameter is not available empty string is used as default value.
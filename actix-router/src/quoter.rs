@@ -1,3 +1,5 @@
+use std::fmt::Write as _;
+
 /// Partial percent-decoding.
 ///
 /// Performs percent-decoding on a slice but can selectively skip decoding certain sequences.
@@ -86,9 +88,50 @@ impl Quoter {
         Some(decoded)
     }
 
-    pub(crate) fn requote_str_lossy(&self, val: &str) -> Option<String> {
-        self.requote(val.as_bytes())
-            .map(|data| String::from_utf8_lossy(&data).into_owned())
+    /// Partially percent-decodes the given string, handling decoded bytes that are not valid UTF-8
+    /// by either replacing them with `U+FFFD` or, if `preserve` is set, re-encoding them.
+    ///
+    /// Returns the requoted string, if modified, and whether any invalid sequences were found.
+    pub(crate) fn requote_str(&self, val: &str, preserve: bool) -> (Option<String>, bool) {
+        let data = match self.requote(val.as_bytes()) {
+            Some(data) => data,
+            None => return (None, false),
+        };
+
+        let data = match String::from_utf8(data) {
+            Ok(path) => return (Some(path), false),
+            Err(err) => err.into_bytes(),
+        };
+
+        if !preserve {
+            return (Some(String::from_utf8_lossy(&data).into_owned()), true);
+        }
+
+        let mut path = String::with_capacity(val.len());
+        let mut remaining = &data[..];
+
+        loop {
+            match std::str::from_utf8(remaining) {
+                Ok(valid) => {
+                    path.push_str(valid);
+                    break;
+                }
+
+                Err(err) => {
+                    let (valid, rest) = remaining.split_at(err.valid_up_to());
+                    path.push_str(std::str::from_utf8(valid).unwrap());
+
+                    let invalid_len = err.error_len().unwrap_or(rest.len());
+                    for byte in &rest[..invalid_len] {
+                        write!(path, "%{:02X}", byte).unwrap();
+                    }
+
+                    remaining = &rest[invalid_len..];
+                }
+            }
+        }
+
+        (Some(path), true)
     }
 }
 
@@ -169,4 +212,26 @@ mod tests {
         let q = Quoter::new(b"", b"");
         assert_eq!(q.requote(b"/abc/../efg"), None);
     }
+
+    #[test]
+    fn invalid_utf8() {
+        let q = Quoter::new(b"", b"%/+");
+        assert_eq!(
+            q.requote_str("/a%C3%A9", false),
+            (Some("/a\u{e9}".to_owned()), false)
+        );
+        assert_eq!(
+            q.requote_str("/a%FFb", false),
+            (Some("/a\u{FFFD}b".to_owned()), true)
+        );
+        assert_eq!(
+            q.requote_str("/a%FFb%C3%A9", true),
+            (Some("/a%FFb\u{e9}".to_owned()), true)
+        );
+        assert_eq!(
+            q.requote_str("/a%C3", true),
+            (Some("/a%C3".to_owned()), true)
+        );
+        assert_eq!(q.requote_str("/a%25FF", true), (None, false));
+    }
 }
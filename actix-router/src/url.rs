@@ -10,21 +10,29 @@ thread_local! {
 pub struct Url {
     uri: http::Uri,
     path: Option<String>,
+    invalid_utf8: bool,
+    preserve_invalid_utf8: bool,
 }
 
 impl Url {
     #[inline]
     pub fn new(uri: http::Uri) -> Url {
-        let path = DEFAULT_QUOTER.with(|q| q.requote_str_lossy(uri.path()));
-        Url { uri, path }
+        let mut url = Url {
+            uri,
+            ..Default::default()
+        };
+        url.requote(None);
+        url
     }
 
     #[inline]
     pub fn new_with_quoter(uri: http::Uri, quoter: &Quoter) -> Url {
-        Url {
-            path: quoter.requote_str_lossy(uri.path()),
+        let mut url = Url {
             uri,
-        }
+            ..Default::default()
+        };
+        url.requote(Some(quoter));
+        url
     }
 
     /// Returns URI.
@@ -34,6 +42,10 @@ impl Url {
     }
 
     /// Returns path.
+    ///
+    /// The path is percent-decoded, except for the `%`, `/`, and `+` characters. Decoded bytes
+    /// that are not valid UTF-8 are replaced with `U+FFFD`, or kept percent-encoded if
+    /// [`preserve_invalid_utf8`](Self::preserve_invalid_utf8) is enabled.
     #[inline]
     pub fn path(&self) -> &str {
         match self.path {
@@ -42,16 +54,49 @@ impl Url {
         }
     }
 
+    /// Returns true if the percent-decoded path contains bytes that are not valid UTF-8.
+    #[inline]
+    pub fn has_invalid_utf8(&self) -> bool {
+        self.invalid_utf8
+    }
+
+    /// Sets whether decoded bytes that are not valid UTF-8 are kept percent-encoded in the path,
+    /// instead of being replaced with `U+FFFD`.
+    ///
+    /// The setting is kept for paths set through [`update`](Self::update). If the current path
+    /// contains invalid UTF-8, it is decoded again using the default quoter.
+    pub fn preserve_invalid_utf8(&mut self, preserve: bool) {
+        if self.preserve_invalid_utf8 != preserve {
+            self.preserve_invalid_utf8 = preserve;
+
+            if self.invalid_utf8 {
+                self.requote(None);
+            }
+        }
+    }
+
     #[inline]
     pub fn update(&mut self, uri: &http::Uri) {
         self.uri = uri.clone();
-        self.path = DEFAULT_QUOTER.with(|q| q.requote_str_lossy(uri.path()));
+        self.requote(None);
     }
 
     #[inline]
     pub fn update_with_quoter(&mut self, uri: &http::Uri, quoter: &Quoter) {
         self.uri = uri.clone();
-        self.path = quoter.requote_str_lossy(uri.path());
+        self.requote(Some(quoter));
+    }
+
+    fn requote(&mut self, quoter: Option<&Quoter>) {
+        let preserve = self.preserve_invalid_utf8;
+
+        let (path, invalid_utf8) = match quoter {
+            Some(quoter) => quoter.requote_str(self.uri.path(), preserve),
+            None => DEFAULT_QUOTER.with(|q| q.requote_str(self.uri.path(), preserve)),
+        };
+
+        self.path = path;
+        self.invalid_utf8 = invalid_utf8;
     }
 }
 
@@ -137,4 +182,31 @@ mod tests {
         // We should always get a valid utf8 string
         assert!(String::from_utf8(path.as_str().as_bytes().to_owned()).is_ok());
     }
+
+    #[test]
+    fn preserve_invalid_utf8() {
+        let re = ResourceDef::new("/user/{id}/test");
+        let uri = Uri::try_from("/user/caf%E9%20%25FF/test").unwrap();
+
+        let mut url = Url::new(uri.clone());
+        assert!(url.has_invalid_utf8());
+        assert_eq!(url.path(), "/user/caf\u{FFFD} %25FF/test");
+
+        url.preserve_invalid_utf8(true);
+        assert!(url.has_invalid_utf8());
+        assert_eq!(url.path(), "/user/caf%E9 %25FF/test");
+
+        let mut path = Path::new(url);
+        assert!(re.capture_match_info(&mut path));
+        assert_eq!(path.get("id").unwrap(), "caf%E9 %25FF");
+        assert_eq!(path.get_bytes("id").unwrap(), &b"caf\xe9 %FF"[..]);
+        assert!(path.load::<(String,)>().is_err());
+
+        // setting is kept across updates
+        path.get_mut()
+            .update(&Uri::try_from("/user/%C3%A9/test").unwrap());
+        assert!(!path.get_ref().has_invalid_utf8());
+        path.get_mut().update(&uri);
+        assert_eq!(path.get_ref().path(), "/user/caf%E9 %25FF/test");
+    }
 }
@@ -150,6 +150,7 @@ where
             let factory = factory.clone();
             let srv_cfg = cfg.clone();
             let timeout = cfg.client_request_timeout;
+            let body_timeout = cfg.client_body_timeout;
 
             let builder = Server::build().workers(1).disable_signals().system_exit();
 
@@ -168,6 +169,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .client_body_timeout(body_timeout)
                             .h1(map_config(fac, move |_| app_cfg.clone()))
                             .tcp()
                     }),
@@ -184,6 +186,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .client_body_timeout(body_timeout)
                             .h2(map_config(fac, move |_| app_cfg.clone()))
                             .tcp()
                     }),
@@ -200,6 +203,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .client_body_timeout(body_timeout)
                             .finish(map_config(fac, move |_| app_cfg.clone()))
                             .tcp()
                     }),
@@ -219,6 +223,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .client_body_timeout(body_timeout)
                             .h1(map_config(fac, move |_| app_cfg.clone()))
                             .openssl(acceptor.clone())
                     }),
@@ -235,6 +240,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .client_body_timeout(body_timeout)
                             .h2(map_config(fac, move |_| app_cfg.clone()))
                             .openssl(acceptor.clone())
                     }),
@@ -251,6 +257,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .client_body_timeout(body_timeout)
                             .finish(map_config(fac, move |_| app_cfg.clone()))
                             .openssl(acceptor.clone())
                     }),
@@ -270,6 +277,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .client_body_timeout(body_timeout)
                             .h1(map_config(fac, move |_| app_cfg.clone()))
                             .rustls(config.clone())
                     }),
@@ -286,6 +294,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .client_body_timeout(body_timeout)
                             .h2(map_config(fac, move |_| app_cfg.clone()))
                             .rustls(config.clone())
                     }),
@@ -302,6 +311,7 @@ where
 
                         HttpService::build()
                             .client_request_timeout(timeout)
+                            .client_body_timeout(body_timeout)
                             .finish(map_config(fac, move |_| app_cfg.clone()))
                             .rustls(config.clone())
                     }),
@@ -389,6 +399,7 @@ pub struct TestServerConfig {
     tp: HttpVer,
     stream: StreamType,
     client_request_timeout: Duration,
+    client_body_timeout: Duration,
 }
 
 impl Default for TestServerConfig {
@@ -404,6 +415,7 @@ impl TestServerConfig {
             tp: HttpVer::Both,
             stream: StreamType::Tcp,
             client_request_timeout: Duration::from_secs(5),
+            client_body_timeout: Duration::ZERO,
         }
     }
 
@@ -438,6 +450,12 @@ impl TestServerConfig {
         self.client_request_timeout = dur;
         self
     }
+
+    /// Set client timeout for receiving request body data. Disabled by default.
+    pub fn client_body_timeout(mut self, dur: Duration) -> Self {
+        self.client_body_timeout = dur;
+        self
+    }
 }
 
 /// A basic HTTP server controller that simplifies the process of writing integration tests for
@@ -57,12 +57,17 @@
 //!
 
 use std::{
+    cell::Cell,
     collections::VecDeque,
     convert::TryFrom,
+    error::Error as StdError,
+    fmt,
     future::Future,
     io, mem,
     pin::Pin,
+    rc::Rc,
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 
 use actix::{
@@ -75,7 +80,7 @@ use actix::{
     SpawnHandle,
 };
 use actix_codec::{Decoder as _, Encoder as _};
-use actix_http::ws::{hash_key, Codec};
+use actix_http::ws::{hash_key, Codec, Item};
 pub use actix_http::ws::{
     CloseCode, CloseReason, Frame, HandshakeError, Message, ProtocolError,
 };
@@ -154,6 +159,7 @@ where
     codec: Option<Codec>,
     protocols: Option<&'a [&'a str]>,
     frame_size: Option<usize>,
+    limits: Limits,
 }
 
 impl<'a, A, T> WsResponseBuilder<'a, A, T>
@@ -172,6 +178,7 @@ where
             codec: None,
             protocols: None,
             frame_size: None,
+            limits: Limits::default(),
         }
     }
 
@@ -196,6 +203,47 @@ where
         self
     }
 
+    /// Set the max size of incoming frames (in bytes), closing the session when it is exceeded.
+    ///
+    /// Unlike [`Self::frame_size`], which passes a [`ProtocolError::Overflow`] to the actor, the
+    /// session is closed with [`CloseCode::Size`] (1009) and the actor's stream of messages ends.
+    /// Takes precedence over [`Self::frame_size`].
+    pub fn max_frame_size(mut self, bytes: usize) -> Self {
+        self.limits.max_frame_size = Some(bytes);
+        self
+    }
+
+    /// Set the max size of incoming messages (in bytes), including all fragments of fragmented
+    /// messages.
+    ///
+    /// When exceeded, the session is closed with [`CloseCode::Size`] (1009) and the actor's stream
+    /// of messages ends.
+    pub fn max_message_size(mut self, bytes: usize) -> Self {
+        self.limits.max_message_size = Some(bytes);
+        self
+    }
+
+    /// Set the max number of incoming messages per second.
+    ///
+    /// Data and ping/pong messages are counted, with fragmented messages counted once. When
+    /// exceeded, the session is closed with [`CloseCode::Policy`] (1008) and the actor's stream of
+    /// messages ends.
+    pub fn incoming_rate_limit(mut self, msgs_per_sec: u32) -> Self {
+        self.limits.incoming_rate_limit = Some(msgs_per_sec);
+        self
+    }
+
+    /// Set the max number of outbound messages queued before they are written to the connection.
+    ///
+    /// Once the queue is full, [`WebsocketContext::try_write_raw`] returns an error. Messages
+    /// written using the infallible methods, such as [`WebsocketContext::text`], are dropped
+    /// instead and the session is closed with [`CloseCode::Policy`] (1008). Close frames are always
+    /// queued.
+    pub fn outbound_queue_limit(mut self, n: usize) -> Self {
+        self.limits.outbound_queue_limit = Some(n);
+        self
+    }
+
     fn handshake_resp(&self) -> Result<HttpResponseBuilder, HandshakeError> {
         match self.protocols {
             Some(protocols) => handshake_with_protocols(self.req, protocols),
@@ -204,7 +252,7 @@ where
     }
 
     fn set_frame_size(&mut self) {
-        if let Some(frame_size) = self.frame_size {
+        if let Some(frame_size) = self.limits.max_frame_size.or(self.frame_size) {
             match &mut self.codec {
                 Some(codec) => {
                     // modify existing codec's max frame size
@@ -220,7 +268,7 @@ where
         }
     }
 
-    /// Create a new Websocket context from an actor, request stream, and codec.
+    /// Create a new Websocket context from an actor, request stream, codec, and limits.
     ///
     /// Returns a pair, where the first item is an addr for the created actor, and the second item
     /// is a stream intended to be set as part of the response
@@ -229,17 +277,15 @@ where
         actor: A,
         stream: S,
         codec: Codec,
+        limits: Limits,
     ) -> (Addr<A>, impl Stream<Item = Result<Bytes, Error>>)
     where
         A: StreamHandler<Result<Message, ProtocolError>>,
         S: Stream<Item = Result<Bytes, PayloadError>> + 'static,
     {
         let mb = Mailbox::default();
-        let mut ctx = WebsocketContext {
-            inner: ContextParts::new(mb.sender_producer()),
-            messages: VecDeque::new(),
-        };
-        ctx.add_stream(WsStream::new(stream, codec.clone()));
+        let mut ctx = WebsocketContext::new(&mb, limits);
+        ctx.add_ws_stream(stream, codec.clone());
 
         let addr = ctx.address();
 
@@ -256,20 +302,8 @@ where
     ///
     /// If successful, consume the [`WsResponseBuilder`] and return a [`HttpResponse`] wrapped in
     /// a [`Result`].
-    pub fn start(mut self) -> Result<HttpResponse, Error> {
-        let mut res = self.handshake_resp()?;
-        self.set_frame_size();
-
-        match self.codec {
-            Some(codec) => {
-                let out_stream = WebsocketContext::with_codec(self.actor, self.stream, codec);
-                Ok(res.streaming(out_stream))
-            }
-            None => {
-                let out_stream = WebsocketContext::create(self.actor, self.stream);
-                Ok(res.streaming(out_stream))
-            }
-        }
+    pub fn start(self) -> Result<HttpResponse, Error> {
+        self.start_with_addr().map(|(_, res)| res)
     }
 
     /// Perform WebSocket handshake and start actor.
@@ -286,18 +320,10 @@ where
         let mut res = self.handshake_resp()?;
         self.set_frame_size();
 
-        match self.codec {
-            Some(codec) => {
-                let (addr, out_stream) =
-                    Self::create_with_codec_addr(self.actor, self.stream, codec);
-                Ok((addr, res.streaming(out_stream)))
-            }
-            None => {
-                let (addr, out_stream) =
-                    WebsocketContext::create_with_addr(self.actor, self.stream);
-                Ok((addr, res.streaming(out_stream)))
-            }
-        }
+        let codec = self.codec.unwrap_or_default();
+        let (addr, out_stream) =
+            Self::create_with_codec_addr(self.actor, self.stream, codec, self.limits);
+        Ok((addr, res.streaming(out_stream)))
     }
 }
 
@@ -461,6 +487,9 @@ where
 {
     inner: ContextParts<A>,
     messages: VecDeque<Option<Message>>,
+    limits: Limits,
+    /// Close code of the first exceeded limit, shared with the incoming message stream.
+    exceeded: Rc<Cell<Option<CloseCode>>>,
 }
 
 impl<A> ActorContext for WebsocketContext<A>
@@ -520,6 +549,24 @@ impl<A> WebsocketContext<A>
 where
     A: Actor<Context = Self>,
 {
+    fn new(mailbox: &Mailbox<A>, limits: Limits) -> Self {
+        WebsocketContext {
+            inner: ContextParts::new(mailbox.sender_producer()),
+            messages: VecDeque::new(),
+            limits,
+            exceeded: Rc::default(),
+        }
+    }
+
+    fn add_ws_stream<S>(&mut self, stream: S, codec: Codec)
+    where
+        A: StreamHandler<Result<Message, ProtocolError>>,
+        S: Stream<Item = Result<Bytes, PayloadError>> + 'static,
+    {
+        let stream = WsStream::new(stream, codec, self.limits, Rc::clone(&self.exceeded));
+        self.add_stream(stream);
+    }
+
     /// Create a new Websocket context from a request and an actor.
     #[inline]
     pub fn create<S>(actor: A, stream: S) -> impl Stream<Item = Result<Bytes, Error>>
@@ -545,11 +592,8 @@ where
         S: Stream<Item = Result<Bytes, PayloadError>> + 'static,
     {
         let mb = Mailbox::default();
-        let mut ctx = WebsocketContext {
-            inner: ContextParts::new(mb.sender_producer()),
-            messages: VecDeque::new(),
-        };
-        ctx.add_stream(WsStream::new(stream, Codec::new()));
+        let mut ctx = WebsocketContext::new(&mb, Limits::default());
+        ctx.add_ws_stream(stream, Codec::new());
 
         let addr = ctx.address();
 
@@ -567,11 +611,8 @@ where
         S: Stream<Item = Result<Bytes, PayloadError>> + 'static,
     {
         let mb = Mailbox::default();
-        let mut ctx = WebsocketContext {
-            inner: ContextParts::new(mb.sender_producer()),
-            messages: VecDeque::new(),
-        };
-        ctx.add_stream(WsStream::new(stream, codec.clone()));
+        let mut ctx = WebsocketContext::new(&mb, Limits::default());
+        ctx.add_ws_stream(stream, codec.clone());
 
         WebsocketContextFut::new(ctx, actor, mb, codec)
     }
@@ -584,11 +625,8 @@ where
         S: Stream<Item = Result<Bytes, PayloadError>> + 'static,
     {
         let mb = Mailbox::default();
-        let mut ctx = WebsocketContext {
-            inner: ContextParts::new(mb.sender_producer()),
-            messages: VecDeque::new(),
-        };
-        ctx.add_stream(WsStream::new(stream, Codec::new()));
+        let mut ctx = WebsocketContext::new(&mb, Limits::default());
+        ctx.add_ws_stream(stream, Codec::new());
 
         let act = f(&mut ctx);
 
@@ -606,9 +644,35 @@ where
     /// be created using `Frame::message()`. If you want to send text or binary
     /// data you should prefer the `text()` or `binary()` convenience functions
     /// that handle the framing for you.
+    ///
+    /// If the [outbound queue limit](WsResponseBuilder::outbound_queue_limit) is reached, the
+    /// message is dropped and the session is closed with [`CloseCode::Policy`]. To handle a full
+    /// queue instead, use [`try_write_raw()`](Self::try_write_raw).
     #[inline]
     pub fn write_raw(&mut self, msg: Message) {
-        self.messages.push_back(Some(msg));
+        if self.try_write_raw(msg).is_err() {
+            self.exceeded.set(Some(CloseCode::Policy));
+            self.stop();
+        }
+    }
+
+    /// Write payload, failing if the outbound queue limit is reached.
+    ///
+    /// The message is returned as part of the error. Close frames are always queued. See
+    /// [`WsResponseBuilder::outbound_queue_limit`].
+    pub fn try_write_raw(&mut self, msg: Message) -> Result<(), OutboundQueueFull> {
+        match self.limits.outbound_queue_limit {
+            Some(limit)
+                if self.messages.len() >= limit && !matches!(msg, Message::Close(_)) =>
+            {
+                Err(OutboundQueueFull(msg))
+            }
+
+            _ => {
+                self.messages.push_back(Some(msg));
+                Ok(())
+            }
+        }
     }
 
     /// Send text frame
@@ -623,6 +687,18 @@ where
         self.write_raw(Message::Binary(data.into()));
     }
 
+    /// Send text frame, failing if the outbound queue is full.
+    #[inline]
+    pub fn try_text(&mut self, text: impl Into<ByteString>) -> Result<(), OutboundQueueFull> {
+        self.try_write_raw(Message::Text(text.into()))
+    }
+
+    /// Send binary frame, failing if the outbound queue is full.
+    #[inline]
+    pub fn try_binary(&mut self, data: impl Into<Bytes>) -> Result<(), OutboundQueueFull> {
+        self.try_write_raw(Message::Binary(data.into()))
+    }
+
     /// Send ping frame
     #[inline]
     pub fn ping(&mut self, message: &[u8]) {
@@ -703,6 +779,14 @@ where
             let _ = Pin::new(&mut this.fut).poll(cx);
         }
 
+        // close the session once a limit is exceeded, after any messages already queued
+        let ctx = this.fut.ctx();
+        if let Some(code) = ctx.exceeded.take() {
+            ctx.messages
+                .push_back(Some(Message::Close(Some(code.into()))));
+            ctx.messages.push_back(None);
+        }
+
         // encode messages
         while let Some(item) = this.fut.ctx().messages.pop_front() {
             if let Some(msg) = item {
@@ -734,6 +818,30 @@ where
     }
 }
 
+/// Error returned when a message can not be sent because the
+/// [outbound queue limit](WsResponseBuilder::outbound_queue_limit) is reached.
+///
+/// Holds the message that was not sent.
+#[derive(Debug, PartialEq)]
+pub struct OutboundQueueFull(pub Message);
+
+impl fmt::Display for OutboundQueueFull {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("WebSocket outbound queue is full")
+    }
+}
+
+impl StdError for OutboundQueueFull {}
+
+/// Limits enforced on a session, set using [`WsResponseBuilder`].
+#[derive(Debug, Clone, Copy, Default)]
+struct Limits {
+    max_frame_size: Option<usize>,
+    max_message_size: Option<usize>,
+    incoming_rate_limit: Option<u32>,
+    outbound_queue_limit: Option<usize>,
+}
+
 pin_project! {
     #[derive(Debug)]
     struct WsStream<S> {
@@ -742,6 +850,12 @@ pin_project! {
         decoder: Codec,
         buf: BytesMut,
         closed: bool,
+        limits: Limits,
+        exceeded: Rc<Cell<Option<CloseCode>>>,
+        // size of the fragmented message being received so far
+        message_size: usize,
+        // start of the current rate limit window and the number of messages received in it
+        window: (Instant, u32),
     }
 }
 
@@ -749,12 +863,21 @@ impl<S> WsStream<S>
 where
     S: Stream<Item = Result<Bytes, PayloadError>>,
 {
-    fn new(stream: S, codec: Codec) -> Self {
+    fn new(
+        stream: S,
+        codec: Codec,
+        limits: Limits,
+        exceeded: Rc<Cell<Option<CloseCode>>>,
+    ) -> Self {
         Self {
             stream,
             decoder: codec,
             buf: BytesMut::new(),
             closed: false,
+            limits,
+            exceeded,
+            message_size: 0,
+            window: (Instant::now(), 0),
         }
     }
 }
@@ -789,7 +912,28 @@ where
             }
         }
 
-        match this.decoder.decode(this.buf)? {
+        let frame = match this.decoder.decode(this.buf) {
+            Err(ProtocolError::Overflow) if this.limits.max_frame_size.is_some() => {
+                Err(CloseCode::Size)
+            }
+            Err(err) => return Poll::Ready(Some(Err(err))),
+            Ok(Some(frm)) => check_limits(&frm, this.limits, this.message_size, this.window)
+                .map(|_| Some(frm)),
+            Ok(None) => Ok(None),
+        };
+
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(code) => {
+                // stop reading; the context sends the close frame
+                this.exceeded.set(Some(code));
+                *this.closed = true;
+                this.buf.clear();
+                return Poll::Ready(None);
+            }
+        };
+
+        match frame {
             None => {
                 if *this.closed {
                     Poll::Ready(None)
@@ -819,6 +963,51 @@ where
     }
 }
 
+/// Checks an incoming frame against the size and rate limits, returning the close code of the
+/// exceeded limit.
+fn check_limits(
+    frame: &Frame,
+    limits: &Limits,
+    message_size: &mut usize,
+    window: &mut (Instant, u32),
+) -> Result<(), CloseCode> {
+    let (size, starts_message) = match frame {
+        Frame::Text(data) | Frame::Binary(data) => (data.len(), true),
+        Frame::Ping(data) | Frame::Pong(data) => (data.len(), true),
+
+        Frame::Continuation(Item::FirstText(data) | Item::FirstBinary(data)) => {
+            *message_size = data.len();
+            (*message_size, true)
+        }
+        Frame::Continuation(Item::Continue(data) | Item::Last(data)) => {
+            *message_size += data.len();
+            (*message_size, false)
+        }
+
+        Frame::Close(_) => (0, false),
+    };
+
+    if matches!(limits.max_message_size, Some(max) if size > max) {
+        return Err(CloseCode::Size);
+    }
+
+    if let (Some(limit), true) = (limits.incoming_rate_limit, starts_message) {
+        let now = Instant::now();
+
+        if now.duration_since(window.0) >= Duration::from_secs(1) {
+            *window = (now, 0);
+        }
+
+        window.1 += 1;
+
+        if window.1 > limit {
+            return Err(CloseCode::Policy);
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use actix_web::{
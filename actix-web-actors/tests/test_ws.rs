@@ -1,5 +1,13 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
 use actix::prelude::*;
-use actix_http::ws::Codec;
+use actix_http::ws::{Codec, Item};
 use actix_web::{web, App, HttpRequest};
 use actix_web_actors::ws;
 use bytes::Bytes;
@@ -184,3 +192,195 @@ async fn simple_start() {
 
     common_test_code(srv, DEFAULT_FRAME_SIZE).await;
 }
+
+/// Echoes messages and records when it stops.
+struct LimitedWs {
+    stopped: Arc<AtomicBool>,
+}
+
+impl Actor for LimitedWs {
+    type Context = ws::WebsocketContext<Self>;
+
+    fn stopped(&mut self, _: &mut Self::Context) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+impl StreamHandler<Result<ws::Message, ws::ProtocolError>> for LimitedWs {
+    fn handle(&mut self, msg: Result<ws::Message, ws::ProtocolError>, ctx: &mut Self::Context) {
+        match msg {
+            // fill the outbound queue, then report how many messages fit
+            Ok(ws::Message::Text(text)) if text == "flood" => {
+                let mut queued = 0;
+
+                loop {
+                    match ctx.try_text("flood") {
+                        Ok(()) => queued += 1,
+                        Err(ws::OutboundQueueFull(msg)) => {
+                            assert_eq!(msg, ws::Message::Text("flood".into()));
+                            break;
+                        }
+                    }
+                }
+
+                ctx.close(Some(ws::CloseReason {
+                    code: ws::CloseCode::Normal,
+                    description: Some(format!("queued {}", queued)),
+                }));
+                ctx.stop();
+            }
+
+            // overflow the outbound queue without checking
+            Ok(ws::Message::Text(text)) if text == "flood unchecked" => {
+                for _ in 0..10 {
+                    ctx.text("flood");
+                }
+            }
+
+            Ok(ws::Message::Text(text)) => ctx.text(text),
+            Ok(ws::Message::Binary(bin)) => ctx.binary(bin),
+            Ok(ws::Message::Continuation(_)) => {}
+            Ok(ws::Message::Close(reason)) => ctx.close(reason),
+            _ => ctx.close(Some(ws::CloseCode::Error.into())),
+        }
+    }
+}
+
+type LimitedBuilder<'a> = ws::WsResponseBuilder<'a, LimitedWs, web::Payload>;
+
+fn start_limited(
+    configure: for<'a> fn(LimitedBuilder<'a>) -> LimitedBuilder<'a>,
+) -> (actix_test::TestServer, Arc<AtomicBool>) {
+    let stopped = Arc::new(AtomicBool::new(false));
+
+    let srv = actix_test::start({
+        let stopped = Arc::clone(&stopped);
+
+        move || {
+            let stopped = Arc::clone(&stopped);
+
+            App::new().service(web::resource("/").to(
+                move |req: HttpRequest, stream: web::Payload| {
+                    let actor = LimitedWs {
+                        stopped: Arc::clone(&stopped),
+                    };
+
+                    async move {
+                        let builder = ws::WsResponseBuilder::new(actor, &req, stream);
+                        configure(builder).start()
+                    }
+                },
+            ))
+        }
+    });
+
+    (srv, stopped)
+}
+
+/// Reads frames until a close frame, returning the frames before it and the close reason, and
+/// asserts that the connection and the actor are then shut down.
+async fn read_until_close(
+    framed: &mut (impl Stream<Item = Result<ws::Frame, ws::ProtocolError>> + Unpin),
+    stopped: &AtomicBool,
+) -> (Vec<ws::Frame>, ws::CloseReason) {
+    let mut frames = Vec::new();
+
+    let reason = loop {
+        match framed.next().await.unwrap().unwrap() {
+            ws::Frame::Close(Some(reason)) => break reason,
+            ws::Frame::Close(None) => panic!("close reason expected"),
+            frame => frames.push(frame),
+        }
+    };
+
+    assert!(framed.next().await.is_none());
+
+    for _ in 0..100 {
+        if stopped.load(Ordering::SeqCst) {
+            return (frames, reason);
+        }
+
+        actix_rt::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    panic!("actor did not stop");
+}
+
+#[actix_rt::test]
+async fn max_frame_size_exceeded() {
+    let (mut srv, stopped) = start_limited(|builder| builder.max_frame_size(64));
+    let mut framed = srv.ws().await.unwrap();
+
+    framed.send(ws::Message::Text("text".into())).await.unwrap();
+    let item = framed.next().await.unwrap().unwrap();
+    assert_eq!(item, ws::Frame::Text(Bytes::from_static(b"text")));
+
+    let bytes = Bytes::from(vec![0; 65]);
+    framed.send(ws::Message::Binary(bytes)).await.unwrap();
+
+    let (frames, reason) = read_until_close(&mut framed, &stopped).await;
+    assert!(frames.is_empty());
+    assert_eq!(reason.code, ws::CloseCode::Size);
+}
+
+#[actix_rt::test]
+async fn max_message_size_exceeded() {
+    let (mut srv, stopped) = start_limited(|builder| builder.max_message_size(64));
+    let mut framed = srv.ws().await.unwrap();
+
+    // fragments are within the limit, but the message is not
+    let fragment = Bytes::from(vec![0; 32]);
+    for item in [
+        Item::FirstBinary(fragment.clone()),
+        Item::Continue(fragment.clone()),
+        Item::Last(fragment),
+    ] {
+        framed.send(ws::Message::Continuation(item)).await.unwrap();
+    }
+
+    let (frames, reason) = read_until_close(&mut framed, &stopped).await;
+    assert!(frames.is_empty());
+    assert_eq!(reason.code, ws::CloseCode::Size);
+}
+
+#[actix_rt::test]
+async fn incoming_rate_limit_exceeded() {
+    let (mut srv, stopped) = start_limited(|builder| builder.incoming_rate_limit(3));
+    let mut framed = srv.ws().await.unwrap();
+
+    for _ in 0..4 {
+        framed.send(ws::Message::Text("text".into())).await.unwrap();
+    }
+
+    let (frames, reason) = read_until_close(&mut framed, &stopped).await;
+    assert_eq!(frames.len(), 3);
+    assert_eq!(reason.code, ws::CloseCode::Policy);
+}
+
+#[actix_rt::test]
+async fn outbound_queue_limit_exceeded() {
+    let (mut srv, stopped) = start_limited(|builder| builder.outbound_queue_limit(4));
+    let mut framed = srv.ws().await.unwrap();
+
+    framed
+        .send(ws::Message::Text("flood".into()))
+        .await
+        .unwrap();
+
+    let (frames, reason) = read_until_close(&mut framed, &stopped).await;
+    assert_eq!(frames.len(), 4);
+    assert_eq!(reason.code, ws::CloseCode::Normal);
+    assert_eq!(reason.description.as_deref(), Some("queued 4"));
+
+    let (mut srv, stopped) = start_limited(|builder| builder.outbound_queue_limit(4));
+    let mut framed = srv.ws().await.unwrap();
+
+    framed
+        .send(ws::Message::Text("flood unchecked".into()))
+        .await
+        .unwrap();
+
+    let (frames, reason) = read_until_close(&mut framed, &stopped).await;
+    assert_eq!(frames.len(), 4);
+    assert_eq!(reason.code, ws::CloseCode::Policy);
+}
@@ -0,0 +1,97 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use actix_web::{
+    dev::{Extensions, Service as _},
+    test::{self, TestRequest},
+    web, App, HttpRequest, HttpResponse,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Global allocator wrapper that counts the number of allocations made.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Returns the number of allocations made while running `f`.
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    f();
+    ALLOCATIONS.load(Ordering::Relaxed) - before
+}
+
+struct Small(u64);
+
+fn bench_type_map(c: &mut Criterion) {
+    let mut group = c.benchmark_group("type map with two entries");
+
+    let hash_map = || {
+        let mut map = HashMap::<TypeId, Box<dyn Any>>::new();
+        map.insert(TypeId::of::<u32>(), Box::new(1u32));
+        map.insert(TypeId::of::<Small>(), Box::new(Small(2)));
+        black_box(map.get(&TypeId::of::<Small>()));
+    };
+
+    let extensions = || {
+        let mut map = Extensions::new();
+        map.insert(1u32);
+        map.insert(Small(2));
+        black_box(map.get::<Small>().map(|small| small.0));
+    };
+
+    println!(
+        "allocations: hash map = {}, extensions = {}",
+        count_allocations(hash_map),
+        count_allocations(extensions),
+    );
+
+    group.bench_function("hash map", |b| b.iter(hash_map));
+    group.bench_function("extensions", |b| b.iter(extensions));
+
+    group.finish();
+}
+
+async fn handler(req: HttpRequest) -> HttpResponse {
+    let host = req.connection_info().host().to_owned();
+    HttpResponse::Ok().body(host)
+}
+
+fn bench_minimal_handler(c: &mut Criterion) {
+    let rt = actix_rt::System::new();
+    let srv = rt.block_on(test::init_service(
+        App::new().route("/", web::get().to(handler)),
+    ));
+
+    let call = || {
+        let req = TestRequest::get().uri("/").to_request();
+        let res = rt.block_on(srv.call(req)).unwrap();
+        assert!(res.status().is_success());
+    };
+
+    // warm up request and connection pools before counting
+    call();
+    println!("allocations per request: {}", count_allocations(call));
+
+    c.bench_function("minimal handler using connection info", |b| b.iter(call));
+}
+
+criterion_group!(benches, bench_type_map, bench_minimal_handler);
+criterion_main!(benches);
@@ -0,0 +1,74 @@
+use std::{cell::RefCell, rc::Rc};
+
+use actix_service::Service;
+use actix_web::{
+    body::MessageBody,
+    dev::ServiceResponse,
+    http::StatusCode,
+    test::{init_service, TestRequest},
+    web, App, Error,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+
+const ROUTES: usize = 50;
+
+fn routes(cfg: &mut web::ServiceConfig) {
+    for idx in 0..ROUTES {
+        cfg.route(
+            &format!("/route{}/{{id}}", idx),
+            web::get().to(|| async { "" }),
+        );
+    }
+}
+
+fn bench_redirect<S, B>(c: &mut Criterion, name: &str, srv: S, rt: &actix_rt::SystemRunner)
+where
+    S: Service<actix_http::Request, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: MessageBody,
+{
+    let srv = Rc::new(RefCell::new(srv));
+
+    let req = TestRequest::get().uri("/not/routed").to_request();
+    let res = rt.block_on(srv.borrow_mut().call(req)).unwrap();
+    assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+
+    c.bench_function(name, move |b| {
+        b.iter_custom(|iters| {
+            let srv = srv.clone();
+            // exclude request generation from the measurement
+            let futs = (0..iters)
+                .map(|_| TestRequest::get().uri("/not/routed").to_request())
+                .map(|req| srv.borrow_mut().call(req));
+
+            let start = std::time::Instant::now();
+            rt.block_on(async move {
+                for fut in futs {
+                    fut.await.unwrap();
+                }
+            });
+            start.elapsed()
+        })
+    });
+}
+
+// Compares a site-wide redirect registered last in the router, which is only reached after
+// trying every other route, with the middleware that redirects before routing.
+fn redirect_benches(c: &mut Criterion) {
+    let rt = actix_rt::System::new();
+
+    let routed =
+        rt.block_on(init_service(App::new().configure(routes).service(
+            web::Redirect::all().to_absolute("/maintenance").temporary(),
+        )));
+    bench_redirect(c, "redirect_routed", routed, &rt);
+
+    let middleware = rt.block_on(init_service(
+        App::new()
+            .wrap(web::Redirect::all().to_absolute("/maintenance").temporary())
+            .configure(routes),
+    ));
+    bench_redirect(c, "redirect_middleware", middleware, &rt);
+}
+
+criterion_group!(benches, redirect_benches);
+criterion_main!(benches);
@@ -0,0 +1,90 @@
+//! Shows how middleware can read and replace response bodies using the `ServiceResponse` body
+//! helpers, without having to name `EitherBody` types.
+
+use std::future::{ready, Ready};
+
+use actix_web::{
+    body::{BoxBody, MessageBody},
+    dev::{Service, ServiceRequest, ServiceResponse, Transform},
+    http::header::{ContentType, CONTENT_TYPE},
+    web, App, Error, HttpResponse, HttpServer,
+};
+use futures_core::future::LocalBoxFuture;
+
+/// Upper-cases plain text responses of up to 16KiB.
+struct Shout;
+
+impl<S, B> Transform<S, ServiceRequest> for Shout
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = ShoutMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ShoutMiddleware { service }))
+    }
+}
+
+struct ShoutMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for ShoutMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+
+            let is_text = res
+                .headers()
+                .get(CONTENT_TYPE)
+                .map_or(false, |ct| ct.as_bytes().starts_with(b"text/plain"));
+
+            if !is_text {
+                return Ok(res.map_into_boxed_body());
+            }
+
+            let (body, res) = res.buffer_body(16 * 1024).await?;
+            let (res, _) = res.replace_body(body.to_ascii_uppercase());
+
+            Ok(res.map_boxed_body(|_, body| body))
+        })
+    }
+}
+
+async fn greet() -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(ContentType::plaintext())
+        .body("hello world\n")
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+
+    log::info!("starting HTTP server at http://localhost:8080");
+
+    HttpServer::new(|| App::new().wrap(Shout).route("/", web::get().to(greet)))
+        .bind(("127.0.0.1", 8080))?
+        .workers(1)
+        .run()
+        .await
+}
@@ -1,24 +1,37 @@
-use std::{cell::RefCell, fmt, future::Future, rc::Rc};
+use std::{
+    cell::RefCell,
+    fmt,
+    future::{self, Future},
+    panic::Location,
+    rc::Rc,
+};
 
 use actix_http::{body::MessageBody, Extensions, Request};
 use actix_service::{
-    apply, apply_fn_factory, boxed, IntoServiceFactory, ServiceFactory, ServiceFactoryExt,
-    Transform,
+    apply, apply_fn_factory, boxed, fn_service, IntoServiceFactory, ServiceFactory,
+    ServiceFactoryExt, Transform,
 };
 use futures_util::future::FutureExt as _;
 
 use crate::{
     app_service::{AppEntry, AppInit, AppRoutingFactory},
-    config::ServiceConfig,
-    data::{Data, DataFactory, FnDataFactory},
+    config::{self, InvalidUtf8Policy, ServiceConfig},
+    data::{Data, DataFactory, DataLocations, FnDataFactory},
     dev::ResourceDef,
     error::Error,
+    http::{
+        header::{Allow, HeaderName},
+        Method,
+    },
+    lifecycle::{LifecycleHooks, RequestEvent, ResponseEvent},
     resource::Resource,
     route::Route,
     service::{
         AppServiceFactory, BoxedHttpServiceFactory, HttpServiceFactory, ServiceFactoryWrapper,
         ServiceRequest, ServiceResponse,
     },
+    trace::TraceEcho,
+    HttpResponse,
 };
 
 /// The top-level builder for an Actix Web application.
@@ -26,10 +39,19 @@ pub struct App<T> {
     endpoint: T,
     services: Vec<Box<dyn AppServiceFactory>>,
     default: Option<Rc<BoxedHttpServiceFactory>>,
+    config_default: Option<&'static Location<'static>>,
     factory_ref: Rc<RefCell<Option<AppRoutingFactory>>>,
     data_factories: Vec<FnDataFactory>,
     external: Vec<ResourceDef>,
     extensions: Extensions,
+    data_locations: DataLocations,
+    trace: TraceEcho,
+    validate_routes: Option<bool>,
+    auto_options: bool,
+    invalid_utf8_policy: InvalidUtf8Policy,
+    trusted_proxies: Option<usize>,
+    server_options: Option<Rc<BoxedHttpServiceFactory>>,
+    lifecycle: LifecycleHooks,
 }
 
 impl App<AppEntry> {
@@ -43,9 +65,18 @@ impl App<AppEntry> {
             data_factories: Vec::new(),
             services: Vec::new(),
             default: None,
+            config_default: None,
             factory_ref,
             external: Vec::new(),
             extensions: Extensions::new(),
+            data_locations: DataLocations::default(),
+            trace: TraceEcho::default(),
+            validate_routes: None,
+            auto_options: false,
+            invalid_utf8_policy: InvalidUtf8Policy::default(),
+            trusted_proxies: None,
+            server_options: None,
+            lifecycle: LifecycleHooks::default(),
         }
     }
 }
@@ -111,8 +142,29 @@ where
     ///         .route("/", web::get().to(handler))
     /// })
     /// ```
+    ///
+    /// # Duplicate Data
+    /// Data is stored by type, so registering a second value of the same type replaces the first.
+    /// In debug builds, doing so on the same `App` logs a warning naming both call sites; use
+    /// [`app_data_replace`](Self::app_data_replace) where this is intended. Data registered on a
+    /// scope or resource may shadow app data of the same type without a warning.
     #[doc(alias = "manage")]
+    #[track_caller]
     pub fn app_data<U: 'static>(mut self, ext: U) -> Self {
+        self.data_locations.register::<U>();
+        self.extensions.insert(ext);
+        self
+    }
+
+    /// Set application data, overwriting data of the same type registered earlier on this app.
+    ///
+    /// In debug builds, [`app_data`](Self::app_data) logs a warning naming both call sites when a
+    /// type is registered twice on the same app, since the first value is silently discarded.
+    /// Use this method instead where overwriting is intended, for example to replace a default
+    /// value set by a shared helper.
+    #[track_caller]
+    pub fn app_data_replace<U: 'static>(mut self, ext: U) -> Self {
+        self.data_locations.replace::<U>();
         self.extensions.insert(ext);
         self
     }
@@ -121,6 +173,7 @@ where
     ///
     /// Deprecated in favor of [`app_data`](Self::app_data).
     #[deprecated(since = "4.0.0", note = "Use `.app_data(Data::new(val))` instead.")]
+    #[track_caller]
     pub fn data<U: 'static>(self, data: U) -> Self {
         self.app_data(Data::new(data))
     }
@@ -187,13 +240,21 @@ where
         let mut cfg = ServiceConfig::new();
 
         f(&mut cfg);
+        cfg.apply_scoping();
 
         self.services.extend(cfg.services);
         self.external.extend(cfg.external);
         self.extensions.extend(cfg.app_data);
+        self.data_locations.merge(cfg.data_locations);
 
         if let Some(default) = cfg.default {
+            let location = cfg
+                .default_location
+                .expect("default service location is tracked");
+            config::check_default_conflict(self.config_default, location);
+
             self.default = Some(default);
+            self.config_default = Some(location);
         }
 
         self
@@ -281,6 +342,168 @@ where
         self
     }
 
+    /// Enables a built-in responder for `TRACE` requests.
+    ///
+    /// When enabled, `TRACE` requests to any path are answered before routing with a
+    /// `message/http` body echoing the received request head, which helps debug what proxies in
+    /// front of the application change. The application acts as the final recipient of the request,
+    /// so requests are answered locally whatever their [`MaxForwards`] value.
+    ///
+    /// Values of headers that may carry credentials are redacted from the echo; see
+    /// [`trace_redacted_headers`](Self::trace_redacted_headers). Disabled by default.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::App;
+    ///
+    /// let app = App::new().enable_trace(true);
+    /// ```
+    ///
+    /// [`MaxForwards`]: crate::http::header::MaxForwards
+    pub fn enable_trace(mut self, enable: bool) -> Self {
+        self.trace.enabled = enable;
+        self
+    }
+
+    /// Sets the request headers whose values are redacted from `TRACE` echoes.
+    ///
+    /// Replaces the default list of `Authorization`, `Proxy-Authorization`, and `Cookie`.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{http::header::{self, HeaderName}, App};
+    ///
+    /// let app = App::new().enable_trace(true).trace_redacted_headers([
+    ///     header::AUTHORIZATION,
+    ///     header::COOKIE,
+    ///     HeaderName::from_static("x-api-key"),
+    /// ]);
+    /// ```
+    pub fn trace_redacted_headers<I>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = HeaderName>,
+    {
+        self.trace.redact(headers);
+        self
+    }
+
+    /// Sets the service answering server-wide `OPTIONS *` requests.
+    ///
+    /// These requests ask about the capabilities of the server as a whole rather than any one
+    /// resource, so they are handled before routing. If no handler is set, they are passed to the
+    /// router like any other request and usually answered by the default service.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{http::header, web, App, HttpResponse};
+    ///
+    /// let app = App::new().server_options_handler(web::to(|| async {
+    ///     HttpResponse::NoContent()
+    ///         .insert_header((header::ALLOW, "GET, HEAD, OPTIONS"))
+    ///         .finish()
+    /// }));
+    /// ```
+    pub fn server_options_handler<F, U>(mut self, svc: F) -> Self
+    where
+        F: IntoServiceFactory<U, ServiceRequest>,
+        U: ServiceFactory<
+                ServiceRequest,
+                Config = (),
+                Response = ServiceResponse,
+                Error = Error,
+            > + 'static,
+        U::InitError: fmt::Debug,
+    {
+        let svc = svc
+            .into_factory()
+            .map_init_err(|e| log::error!("Can not construct server options service: {:?}", e));
+
+        self.server_options = Some(Rc::new(boxed::factory(svc)));
+
+        self
+    }
+
+    /// Answers server-wide `OPTIONS *` requests with "204 No Content" and an [`Allow`] header
+    /// listing `methods`.
+    ///
+    /// Shorthand for [`server_options_handler`](Self::server_options_handler) with a handler that
+    /// reports the methods supported by the server. `OPTIONS` requests for a path, such as
+    /// `OPTIONS /users`, are routed as usual.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{http::Method, App};
+    ///
+    /// let app = App::new().server_options_allow([Method::GET, Method::HEAD, Method::OPTIONS]);
+    /// ```
+    ///
+    /// [`Allow`]: crate::http::header::Allow
+    pub fn server_options_allow<I>(self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = Method>,
+    {
+        let allow = Allow(methods.into_iter().collect());
+
+        self.server_options_handler(fn_service(move |req: ServiceRequest| {
+            let res = HttpResponse::NoContent()
+                .insert_header(allow.clone())
+                .finish();
+            future::ready(Ok(req.into_response(res)))
+        }))
+    }
+
+    /// Registers a callback run when the app starts handling a request, before routing.
+    ///
+    /// The callback receives a read-only [`RequestEvent`] with the request's method, URI, and
+    /// matching resource pattern. Callbacks run in registration order. Unlike middleware, they
+    /// cannot change or respond to the request, and no per-request work is done for lifecycle
+    /// hooks unless some are registered.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::App;
+    ///
+    /// let app = App::new().on_request(|ev| log::debug!("{} {}", ev.method(), ev.uri()));
+    /// ```
+    pub fn on_request<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&RequestEvent<'_>) + 'static,
+    {
+        self.lifecycle.add_request_hook(f);
+        self
+    }
+
+    /// Registers a callback run as the server sends a response from the app.
+    ///
+    /// The callback is run twice per response: once the response head is written, and once the
+    /// body ends, with the number of body bytes sent and whether the body was sent completely.
+    /// See [`ResponseStage`](crate::dev::ResponseStage). The read-only [`ResponseEvent`] also
+    /// carries the status, the request's method, the pattern of the route that handled it, and
+    /// the time since the request started. Callbacks run in registration order.
+    ///
+    /// Responses are observed by the HTTP/1 and HTTP/2 dispatchers, so callbacks are not run for
+    /// services called directly, such as in tests using [`test::call_service`].
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{dev::ResponseStage, App};
+    ///
+    /// let app = App::new().on_response(|ev| {
+    ///     if let ResponseStage::BodyFinished { bytes, .. } = ev.stage() {
+    ///         log::info!("{} {} sent {} bytes in {:?}", ev.method(), ev.status(), bytes, ev.duration());
+    ///     }
+    /// });
+    /// ```
+    ///
+    /// [`test::call_service`]: crate::test::call_service
+    pub fn on_response<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&ResponseEvent<'_>) + 'static,
+    {
+        self.lifecycle.add_response_hook(f);
+        self
+    }
+
     /// Register an external resource.
     ///
     /// External resources are useful for URL generation purposes only
@@ -312,6 +535,105 @@ where
         self
     }
 
+    /// Enables validation of the routing table when the app is started.
+    ///
+    /// Within the app and each scope, services that can never be reached are reported: services
+    /// registered twice with the same pattern, and literal patterns registered after a dynamic
+    /// pattern or scope that matches them, like `/users/me` after `/users/{id}`. Services are only
+    /// reported if the earlier one handles some of the same methods and neither has guards other
+    /// than method guards.
+    ///
+    /// Conflicts are logged as warnings or, if `strict` is true, cause a panic. Disabled by default.
+    ///
+    /// # Examples
+    /// ```should_panic
+    /// use actix_web::{test, web, App};
+    ///
+    /// # actix_web::rt::System::new().block_on(async {
+    /// let app = App::new()
+    ///     .validate_routes(true)
+    ///     .route("/users/{id}", web::get().to(|| async { "user" }))
+    ///     .route("/users/me", web::get().to(|| async { "me" }));
+    ///
+    /// // panics: "`/users/{id}` shadows `/users/me`, which is registered after it"
+    /// test::init_service(app).await;
+    /// # });
+    /// ```
+    pub fn validate_routes(mut self, strict: bool) -> Self {
+        self.validate_routes = Some(strict);
+        self
+    }
+
+    /// Sets whether resources answer `OPTIONS` requests automatically, unless configured otherwise
+    /// using [`Resource::auto_options`](crate::Resource::auto_options). Disabled by default.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// // `OPTIONS /items` responds with "204 No Content" and `Allow: GET, PUT, OPTIONS`
+    /// let app = App::new().auto_options(true).service(
+    ///     web::resource("/items")
+    ///         .route(web::get().to(|| async { "items" }))
+    ///         .route(web::put().to(|| async { "updated" })),
+    /// );
+    /// ```
+    pub fn auto_options(mut self, enabled: bool) -> Self {
+        self.auto_options = enabled;
+        self
+    }
+
+    /// Sets how request paths and header values that are not valid UTF-8 are handled.
+    ///
+    /// Defaults to [`InvalidUtf8Policy::Reject`]; see [`InvalidUtf8Policy`] for the behavior of
+    /// each policy.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{dev::InvalidUtf8Policy, web, App, HttpRequest};
+    ///
+    /// // `GET /files/caf%E9` is routed, with the segment available as raw bytes
+    /// let app = App::new()
+    ///     .invalid_utf8_policy(InvalidUtf8Policy::Preserve)
+    ///     .route("/files/{name}", web::get().to(|req: HttpRequest| async move {
+    ///         let name = req.match_info().get_bytes("name").unwrap();
+    ///         format!("{} bytes", name.len())
+    ///     }));
+    /// ```
+    pub fn invalid_utf8_policy(mut self, policy: InvalidUtf8Policy) -> Self {
+        self.invalid_utf8_policy = policy;
+        self
+    }
+
+    /// Sets the number of proxies in front of the app whose forwarding headers are trusted.
+    ///
+    /// [`ConnectionInfo`](crate::dev::ConnectionInfo) resolves the client address, scheme, and
+    /// host from the `Forwarded` header, falling back to the `X-Forwarded-*` headers. Each proxy
+    /// appends to these headers, so with `count` trusted proxies, the value added by the outermost
+    /// trusted proxy is used and anything prepended by the client is ignored. All `Forwarded`
+    /// properties are taken from the element added by that proxy; a header with fewer values than
+    /// trusted proxies provides no value. With a count of zero, forwarding headers are ignored
+    /// entirely.
+    ///
+    /// By default, the first value is used, which is only correct when a single proxy overwrites
+    /// the headers sent by clients.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpRequest};
+    ///
+    /// // deployed behind a CDN and a load balancer
+    /// let app = App::new()
+    ///     .trusted_proxies(2)
+    ///     .route("/", web::get().to(|req: HttpRequest| async move {
+    ///         req.connection_info().realip_remote_addr().unwrap_or("unknown").to_owned()
+    ///     }));
+    /// ```
+    pub fn trusted_proxies(mut self, count: usize) -> Self {
+        self.trusted_proxies = Some(count);
+        self
+    }
+
     /// Registers an app-wide middleware.
     ///
     /// Registers middleware, in the form of a middleware component (type), that runs during
@@ -375,9 +697,18 @@ where
             data_factories: self.data_factories,
             services: self.services,
             default: self.default,
+            config_default: self.config_default,
             factory_ref: self.factory_ref,
             external: self.external,
             extensions: self.extensions,
+            data_locations: self.data_locations,
+            trace: self.trace,
+            validate_routes: self.validate_routes,
+            auto_options: self.auto_options,
+            invalid_utf8_policy: self.invalid_utf8_policy,
+            trusted_proxies: self.trusted_proxies,
+            server_options: self.server_options,
+            lifecycle: self.lifecycle,
         }
     }
 
@@ -438,9 +769,18 @@ where
             data_factories: self.data_factories,
             services: self.services,
             default: self.default,
+            config_default: self.config_default,
             factory_ref: self.factory_ref,
             external: self.external,
             extensions: self.extensions,
+            data_locations: self.data_locations,
+            trace: self.trace,
+            validate_routes: self.validate_routes,
+            auto_options: self.auto_options,
+            invalid_utf8_policy: self.invalid_utf8_policy,
+            trusted_proxies: self.trusted_proxies,
+            server_options: self.server_options,
+            lifecycle: self.lifecycle,
         }
     }
 }
@@ -457,12 +797,22 @@ where
     B: MessageBody,
 {
     fn into_factory(self) -> AppInit<T, B> {
+        let trace = self.trace;
+        let lifecycle = self.lifecycle;
+
         AppInit {
             async_data_factories: self.data_factories.into_boxed_slice().into(),
             endpoint: self.endpoint,
             services: Rc::new(RefCell::new(self.services)),
             external: RefCell::new(self.external),
             default: self.default,
+            trace: trace.enabled.then(move || Rc::new(trace)),
+            validate_routes: self.validate_routes,
+            auto_options: self.auto_options,
+            invalid_utf8_policy: self.invalid_utf8_policy,
+            trusted_proxies: self.trusted_proxies,
+            server_options: self.server_options,
+            lifecycle: (!lifecycle.is_empty()).then(move || Rc::new(lifecycle)),
             factory_ref: self.factory_ref,
             extensions: RefCell::new(Some(self.extensions)),
         }
@@ -483,7 +833,10 @@ mod tests {
         },
         middleware::DefaultHeaders,
         service::ServiceRequest,
-        test::{call_service, init_service, read_body, try_init_service, TestRequest},
+        test::{
+            call_and_read_body, call_service, init_service, read_body, try_init_service,
+            TestRequest,
+        },
         web, HttpRequest, HttpResponse,
     };
 
@@ -694,6 +1047,148 @@ mod tests {
         assert_eq!(body, Bytes::from_static(b"https://youtube.com/watch/12345"));
     }
 
+    #[actix_rt::test]
+    async fn trace_echoes_redacted_request_head() {
+        let srv = init_service(
+            App::new()
+                .enable_trace(true)
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/some/path?q=1")
+            .method(Method::TRACE)
+            .insert_header((header::AUTHORIZATION, "Bearer secret"))
+            .insert_header(("x-forwarded-for", "10.0.0.1"))
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "message/http"
+        );
+
+        let body = read_body(res).await;
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(body.starts_with("TRACE /some/path?q=1 HTTP/1.1\r\n"));
+        assert!(body.contains("authorization: [redacted]\r\n"));
+        assert!(body.contains("x-forwarded-for: 10.0.0.1\r\n"));
+        assert!(!body.contains("secret"));
+        assert!(body.ends_with("\r\n\r\n"));
+    }
+
+    #[actix_rt::test]
+    async fn trace_redacted_headers_are_configurable() {
+        let srv = init_service(
+            App::new()
+                .enable_trace(true)
+                .trace_redacted_headers([HeaderName::from_static("x-api-key")]),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .method(Method::TRACE)
+            .insert_header((header::AUTHORIZATION, "Basic Zm9v"))
+            .insert_header(("x-api-key", "secret"))
+            .to_request();
+        let body = read_body(call_service(&srv, req).await).await;
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(body.contains("authorization: Basic Zm9v\r\n"));
+        assert!(body.contains("x-api-key: [redacted]\r\n"));
+    }
+
+    #[actix_rt::test]
+    async fn trace_max_forwards_zero_is_answered_locally() {
+        let srv = init_service(
+            App::new()
+                .enable_trace(true)
+                .route("/", web::method(Method::TRACE).to(HttpResponse::BadGateway)),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .method(Method::TRACE)
+            .insert_header(header::MaxForwards(0))
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = read_body(res).await;
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(body.contains("max-forwards: 0\r\n"));
+    }
+
+    #[actix_rt::test]
+    async fn trace_is_disabled_by_default() {
+        let srv = init_service(App::new().route("/", web::get().to(HttpResponse::Ok))).await;
+
+        let req = TestRequest::default().method(Method::TRACE).to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn server_options_handler() {
+        let srv = init_service(
+            App::new()
+                .server_options_handler(web::to(|| {
+                    HttpResponse::NoContent()
+                        .insert_header((header::ALLOW, "GET, OPTIONS"))
+                        .finish()
+                }))
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("*")
+            .method(Method::OPTIONS)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert_eq!(res.headers().get(header::ALLOW).unwrap(), "GET, OPTIONS");
+
+        // resource-level OPTIONS requests are routed as usual
+        let req = TestRequest::default().method(Method::OPTIONS).to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn server_options_allow() {
+        let srv = init_service(
+            App::new()
+                .server_options_allow([Method::GET, Method::POST, Method::OPTIONS])
+                .route("/path", web::get().to(HttpResponse::Ok))
+                .route(
+                    "/path",
+                    web::method(Method::OPTIONS).to(HttpResponse::Accepted),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("*")
+            .method(Method::OPTIONS)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        assert_eq!(
+            res.headers().get(header::ALLOW).unwrap(),
+            "GET, POST, OPTIONS"
+        );
+
+        // asterisk-form is only recognized for OPTIONS requests
+        let req = TestRequest::with_uri("*").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let req = TestRequest::with_uri("/path")
+            .method(Method::OPTIONS)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+        assert!(res.headers().get(header::ALLOW).is_none());
+    }
+
     #[test]
     fn can_be_returned_from_fn() {
         /// compile-only test for returning app type from function
@@ -714,4 +1209,106 @@ mod tests {
 
         let _ = init_service(my_app());
     }
+
+    crate::http::header::common_header! {
+        (Label, header::HeaderName::from_static("x-label")) => [String]
+    }
+
+    #[actix_rt::test]
+    async fn test_trusted_proxies() {
+        let srv = init_service(App::new().trusted_proxies(1).route(
+            "/",
+            web::get().to(|req: HttpRequest| async move {
+                let conn = req.connection_info();
+                format!("{} {}", conn.scheme(), conn.realip_remote_addr().unwrap())
+            }),
+        ))
+        .await;
+
+        let req = TestRequest::default()
+            .insert_header((
+                header::FORWARDED,
+                "for=10.0.0.1, for=192.0.2.60;proto=https",
+            ))
+            .insert_header(("x-forwarded-proto", "http"))
+            .to_request();
+        let body = call_and_read_body(&srv, req).await;
+        assert_eq!(body, "https 192.0.2.60");
+    }
+
+    #[actix_rt::test]
+    async fn test_invalid_utf8_policy() {
+        use std::collections::HashMap;
+
+        use crate::FromRequest as _;
+
+        async fn inspect(req: HttpRequest) -> String {
+            let path = web::Path::<String>::extract(&req).await.ok();
+            let bytes = req.match_info().get_bytes("name").map(|bytes| {
+                bytes
+                    .iter()
+                    .map(|b| format!("{:02x}", b))
+                    .collect::<String>()
+            });
+            let query = web::Query::<HashMap<String, String>>::extract(&req)
+                .await
+                .ok();
+            let label = web::Header::<Label>::extract(&req).await.ok();
+            let label_str = req.header_str("x-label");
+
+            // the raw header bytes are never rewritten
+            assert_eq!(req.headers().get("x-label").unwrap().as_bytes(), b"caf\xe9");
+
+            format!(
+                "{:?} {:?} {:?} {:?} {:?}",
+                path.map(web::Path::into_inner),
+                bytes,
+                query.and_then(|query| query.into_inner().remove("q")),
+                label.map(|label| label.into_inner().0),
+                label_str,
+            )
+        }
+
+        async fn call(policy: InvalidUtf8Policy, uri: &str) -> (StatusCode, Bytes) {
+            let srv = init_service(
+                App::new()
+                    .invalid_utf8_policy(policy)
+                    .route("/files/{name}", web::get().to(inspect)),
+            )
+            .await;
+
+            let req = TestRequest::with_uri(uri)
+                .insert_header(("x-label", HeaderValue::from_bytes(b"caf\xe9").unwrap()))
+                .to_request();
+            let res = call_service(&srv, req).await;
+
+            (res.status(), read_body(res).await)
+        }
+
+        let (status, body) = call(InvalidUtf8Policy::Reject, "/files/caf%E9?q=%E9").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body, "Request path is not valid UTF-8");
+
+        let (status, body) = call(InvalidUtf8Policy::Reject, "/files/ok?q=%E9").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, r#"Some("ok") Some("6f6b") None None None"#);
+
+        let (_, body) = call(InvalidUtf8Policy::Reject, "/files/ok?q=%C3%A9").await;
+        assert_eq!(
+            body,
+            "Some(\"ok\") Some(\"6f6b\") Some(\"\u{e9}\") None None"
+        );
+
+        let (status, body) = call(InvalidUtf8Policy::Lossy, "/files/caf%E9?q=%E9").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            "Some(\"caf\u{FFFD}\") Some(\"636166efbfbd\") Some(\"\u{FFFD}\") Some(\"caf\u{FFFD}\") \
+            Some(\"caf\u{FFFD}\")"
+        );
+
+        let (status, body) = call(InvalidUtf8Policy::Preserve, "/files/caf%E9?q=%E9").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, r#"None Some("636166e9") None None None"#);
+    }
 }
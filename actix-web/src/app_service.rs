@@ -1,23 +1,37 @@
-use std::{cell::RefCell, mem, rc::Rc};
+use std::{
+    cell::RefCell,
+    future::Future,
+    mem,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Instant,
+};
 
-use actix_http::Request;
+use actix_http::{body::MessageBody, Request};
 use actix_router::{Path, ResourceDef, Router, Url};
 use actix_service::{boxed, fn_service, Service, ServiceFactory};
-use futures_core::future::LocalBoxFuture;
-use futures_util::future::join_all;
+use futures_core::{future::LocalBoxFuture, ready};
+use futures_util::future::{join_all, ready};
+use pin_project_lite::pin_project;
 
 use crate::{
     body::BoxBody,
-    config::{AppConfig, AppService},
+    config::{AppConfig, AppService, InvalidUtf8Policy},
     data::FnDataFactory,
     dev::Extensions,
+    error::ErrorBadRequest,
     guard::Guard,
+    http::Method,
+    lifecycle::LifecycleHooks,
+    redirect,
     request::{HttpRequest, HttpRequestPool},
     rmap::ResourceMap,
     service::{
         AppServiceFactory, BoxedHttpService, BoxedHttpServiceFactory, ServiceRequest,
         ServiceResponse,
     },
+    trace::TraceEcho,
     Error, HttpResponse,
 };
 
@@ -39,6 +53,13 @@ where
     pub(crate) async_data_factories: Rc<[FnDataFactory]>,
     pub(crate) services: Rc<RefCell<Vec<Box<dyn AppServiceFactory>>>>,
     pub(crate) default: Option<Rc<BoxedHttpServiceFactory>>,
+    pub(crate) trace: Option<Rc<TraceEcho>>,
+    pub(crate) validate_routes: Option<bool>,
+    pub(crate) auto_options: bool,
+    pub(crate) invalid_utf8_policy: InvalidUtf8Policy,
+    pub(crate) trusted_proxies: Option<usize>,
+    pub(crate) server_options: Option<Rc<BoxedHttpServiceFactory>>,
+    pub(crate) lifecycle: Option<Rc<LifecycleHooks>>,
     pub(crate) factory_ref: Rc<RefCell<Option<AppRoutingFactory>>>,
     pub(crate) external: RefCell<Vec<ResourceDef>>,
 }
@@ -53,6 +74,7 @@ where
         InitError = (),
     >,
     T::Future: 'static,
+    B: MessageBody,
 {
     type Response = ServiceResponse<B>;
     type Error = T::Error;
@@ -61,7 +83,10 @@ where
     type InitError = T::InitError;
     type Future = LocalBoxFuture<'static, Result<Self::Service, Self::InitError>>;
 
-    fn new_service(&self, config: AppConfig) -> Self::Future {
+    fn new_service(&self, mut config: AppConfig) -> Self::Future {
+        config.set_invalid_utf8_policy(self.invalid_utf8_policy);
+        config.set_trusted_proxies(self.trusted_proxies);
+
         // set AppService's default service to 404 NotFound
         // if no user defined default service exists.
         let default = self.default.clone().unwrap_or_else(|| {
@@ -70,8 +95,17 @@ where
             })))
         });
 
+        let worker = config.worker().cloned();
+
         // create App config to pass to child services
         let mut config = AppService::new(config, default.clone());
+        config.set_validate_routes(self.validate_routes);
+        config.set_auto_options(self.auto_options);
+        config.set_record_routes(
+            self.lifecycle
+                .as_ref()
+                .map_or(false, |hooks| hooks.has_response_hooks()),
+        );
 
         // register services
         mem::take(&mut *self.services.borrow_mut())
@@ -80,11 +114,14 @@ where
 
         let mut rmap = ResourceMap::new(ResourceDef::prefix(""));
 
+        let redirect_checks = config.take_redirect_checks();
         let (config, services) = config.into_services();
 
         // complete pipeline creation.
         *self.factory_ref.borrow_mut() = Some(AppRoutingFactory {
             default,
+            trace: self.trace.clone(),
+            server_options: self.server_options.clone(),
             services: services
                 .into_iter()
                 .map(|(mut rdef, srv, guards, nested)| {
@@ -105,6 +142,8 @@ where
         let rmap = Rc::new(rmap);
         ResourceMap::finish(&rmap);
 
+        redirect::check_targets(&redirect_checks, &rmap);
+
         // construct all async data factory futures
         let factory_futs = join_all(self.async_data_factories.iter().map(|f| f()));
 
@@ -118,6 +157,8 @@ where
             .take()
             .unwrap_or_else(Extensions::new);
 
+        let lifecycle = self.lifecycle.clone();
+
         Box::pin(async move {
             // async data factories
             let async_data_factories = factory_futs
@@ -134,10 +175,15 @@ where
                 factory.create(&mut app_data);
             }
 
+            // worker data shadows app data of the same type
+            if let Some(worker) = worker {
+                worker.create(&mut app_data);
+            }
+
             Ok(AppInitService {
                 service,
                 app_data: Rc::new(app_data),
-                app_state: AppInitServiceState::new(rmap, config),
+                app_state: AppInitServiceState::new(rmap, config, lifecycle),
             })
         })
     }
@@ -159,15 +205,23 @@ where
 pub(crate) struct AppInitServiceState {
     rmap: Rc<ResourceMap>,
     config: AppConfig,
+    lifecycle: Option<Rc<LifecycleHooks>>,
     pool: HttpRequestPool,
+    started: Instant,
 }
 
 impl AppInitServiceState {
-    pub(crate) fn new(rmap: Rc<ResourceMap>, config: AppConfig) -> Rc<Self> {
+    pub(crate) fn new(
+        rmap: Rc<ResourceMap>,
+        config: AppConfig,
+        lifecycle: Option<Rc<LifecycleHooks>>,
+    ) -> Rc<Self> {
         Rc::new(AppInitServiceState {
             rmap,
             config,
+            lifecycle,
             pool: HttpRequestPool::default(),
+            started: Instant::now(),
         })
     }
 
@@ -176,6 +230,16 @@ impl AppInitServiceState {
         &*self.rmap
     }
 
+    #[inline]
+    pub(crate) fn shared_rmap(&self) -> Rc<ResourceMap> {
+        Rc::clone(&self.rmap)
+    }
+
+    #[inline]
+    pub(crate) fn lifecycle(&self) -> Option<&Rc<LifecycleHooks>> {
+        self.lifecycle.as_ref()
+    }
+
     #[inline]
     pub(crate) fn config(&self) -> &AppConfig {
         &self.config
@@ -185,15 +249,22 @@ impl AppInitServiceState {
     pub(crate) fn pool(&self) -> &HttpRequestPool {
         &self.pool
     }
+
+    /// Returns the time this app instance was started on the current worker.
+    #[inline]
+    pub(crate) fn started(&self) -> Instant {
+        self.started
+    }
 }
 
 impl<T, B> Service<Request> for AppInitService<T, B>
 where
     T: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
 {
     type Response = ServiceResponse<B>;
     type Error = T::Error;
-    type Future = T::Future;
+    type Future = AppInitServiceFuture<T::Future>;
 
     actix_service::forward_ready!(service);
 
@@ -202,6 +273,8 @@ where
         let conn_data = req.take_conn_data();
         let (head, payload) = req.into_parts();
 
+        let policy = self.app_state.config().invalid_utf8_policy();
+
         let req = match self.app_state.pool().pop() {
             Some(mut req) => {
                 let inner = Rc::get_mut(&mut req.inner).unwrap();
@@ -210,20 +283,57 @@ where
                 inner.head = head;
                 inner.conn_data = conn_data;
                 inner.extensions = extensions;
+                inner.original_uri = None;
                 req
             }
 
-            None => HttpRequest::new(
-                Path::new(Url::new(head.uri.clone())),
-                head,
-                Rc::clone(&self.app_state),
-                Rc::clone(&self.app_data),
-                conn_data,
-                extensions,
-            ),
+            None => {
+                let mut url = Url::new(head.uri.clone());
+                url.preserve_invalid_utf8(policy == InvalidUtf8Policy::Preserve);
+
+                HttpRequest::new(
+                    Path::new(url),
+                    head,
+                    Rc::clone(&self.app_state),
+                    Rc::clone(&self.app_data),
+                    conn_data,
+                    extensions,
+                )
+            }
         };
 
-        self.service.call(ServiceRequest::new(req, payload))
+        if let Some(lifecycle) = self.app_state.lifecycle() {
+            lifecycle.request_started(&req);
+        }
+
+        AppInitServiceFuture {
+            fut: self.service.call(ServiceRequest::new(req, payload)),
+        }
+    }
+}
+
+pin_project! {
+    /// Future returned by [`AppInitService`]; checks responses for debug diagnostics.
+    pub struct AppInitServiceFuture<F> {
+        #[pin]
+        fut: F,
+    }
+}
+
+impl<F, B> Future for AppInitServiceFuture<F>
+where
+    F: Future<Output = Result<ServiceResponse<B>, Error>>,
+    B: MessageBody,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let res = ready!(self.project().fut.poll(cx))?;
+
+        #[cfg(debug_assertions)]
+        crate::service::check_content_length(res.request(), res.response());
+
+        Poll::Ready(Ok(res))
     }
 }
 
@@ -246,6 +356,8 @@ pub struct AppRoutingFactory {
         )],
     >,
     default: Rc<BoxedHttpServiceFactory>,
+    trace: Option<Rc<TraceEcho>>,
+    server_options: Option<Rc<BoxedHttpServiceFactory>>,
 }
 
 impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
@@ -271,9 +383,19 @@ impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
         // construct default service factory future
         let default_fut = self.default.new_service(());
 
+        // construct server-wide OPTIONS service factory future
+        let server_options_fut = self.server_options.as_ref().map(|f| f.new_service(()));
+
+        let trace = self.trace.clone();
+
         Box::pin(async move {
             let default = default_fut.await?;
 
+            let server_options = match server_options_fut {
+                Some(fut) => Some(fut.await?),
+                None => None,
+            };
+
             // build router from the factory future result.
             let router = factory_fut
                 .await
@@ -286,7 +408,12 @@ impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
                 })
                 .finish();
 
-            Ok(AppRouting { router, default })
+            Ok(AppRouting {
+                router,
+                default,
+                trace,
+                server_options,
+            })
         })
     }
 }
@@ -295,6 +422,8 @@ impl ServiceFactory<ServiceRequest> for AppRoutingFactory {
 pub struct AppRouting {
     router: Router<BoxedHttpService, Vec<Box<dyn Guard>>>,
     default: BoxedHttpService,
+    trace: Option<Rc<TraceEcho>>,
+    server_options: Option<BoxedHttpService>,
 }
 
 impl Service<ServiceRequest> for AppRouting {
@@ -305,6 +434,26 @@ impl Service<ServiceRequest> for AppRouting {
     actix_service::always_ready!();
 
     fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        if req.match_info().get_ref().has_invalid_utf8()
+            && req.app_config().invalid_utf8_policy() == InvalidUtf8Policy::Reject
+        {
+            let err = ErrorBadRequest("Request path is not valid UTF-8");
+            return Box::pin(ready(Ok(req.error_response(err))));
+        }
+
+        if let Some(trace) = &self.trace {
+            if req.method() == Method::TRACE {
+                let res = trace.respond(req.request());
+                return Box::pin(ready(Ok(req.into_response(res))));
+            }
+        }
+
+        if let Some(server_options) = &self.server_options {
+            if req.method() == Method::OPTIONS && req.uri() == "*" {
+                return server_options.call(req);
+            }
+        }
+
         let res = self.router.recognize_fn(&mut req, |req, guards| {
             let guard_ctx = req.guard_ctx();
             guards.iter().all(|guard| guard.check(&guard_ctx))
@@ -0,0 +1,224 @@
+//! See [`BlockingPool`] for documentation.
+
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+use tokio::sync::oneshot;
+
+use crate::error::BlockingError;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A named pool of threads for running blocking work, with a bounded queue.
+///
+/// [`web::block`](crate::web::block) sends all blocking work to one shared pool. Separate pools
+/// keep one kind of work, like resizing images, from delaying another, like verifying password
+/// hashes. Use [`web::block_on`](crate::web::block_on) to run work on a pool.
+///
+/// At most `queue_limit` jobs wait for a free thread. When the queue is full, new jobs are
+/// rejected with [`BlockingError::Overloaded`], which responds with "503 Service Unavailable".
+/// This sheds load instead of letting waiting times grow without limit.
+///
+/// Pools are cheap to clone and are usually shared with handlers as app data. Their threads stop
+/// once all clones are dropped and any queued jobs have run.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App, Error, HttpResponse};
+///
+/// async fn resize(pool: web::Data<web::BlockingPool>) -> Result<HttpResponse, Error> {
+///     let thumbnail = web::block_on(&pool, || {
+///         // expensive image processing
+///         vec![0u8; 64]
+///     })
+///     .await?;
+///
+///     Ok(HttpResponse::Ok().body(thumbnail))
+/// }
+///
+/// // created once, outside the app factory, so that all workers share the same pool
+/// let images = web::Data::new(web::BlockingPool::new("images", 2, 16));
+///
+/// let app = App::new()
+///     .app_data(images.clone())
+///     .route("/thumbnail", web::post().to(resize));
+/// ```
+#[derive(Clone)]
+pub struct BlockingPool {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    name: String,
+    threads: usize,
+    queue_limit: usize,
+    tx: SyncSender<Job>,
+}
+
+impl BlockingPool {
+    /// Starts a new pool with `threads` threads named after `name`, queueing at most
+    /// `queue_limit` jobs that are waiting for a free thread.
+    ///
+    /// A `queue_limit` of zero only accepts jobs while a thread is idle.
+    ///
+    /// # Panics
+    /// Panics if `threads` is zero or if a thread cannot be spawned.
+    pub fn new(name: impl Into<String>, threads: usize, queue_limit: usize) -> Self {
+        assert!(threads > 0, "blocking pool must have at least one thread");
+
+        let name = name.into();
+        let (tx, rx) = mpsc::sync_channel::<Job>(queue_limit);
+        let rx = Arc::new(Mutex::new(rx));
+
+        for idx in 0..threads {
+            let rx = Arc::clone(&rx);
+
+            thread::Builder::new()
+                .name(format!("{}-{}", name, idx))
+                .spawn(move || run_jobs(&rx))
+                .expect("failed to spawn blocking pool thread");
+        }
+
+        Self {
+            inner: Arc::new(Inner {
+                name,
+                threads,
+                queue_limit,
+                tx,
+            }),
+        }
+    }
+
+    /// Returns the name of the pool.
+    pub fn name(&self) -> &str {
+        &self.inner.name
+    }
+
+    /// Queues `f` to run on the pool, returning a receiver for its result.
+    pub(crate) fn spawn<F, R>(&self, f: F) -> Result<oneshot::Receiver<R>, BlockingError>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+
+        let job = Box::new(move || {
+            let _ = tx.send(f());
+        });
+
+        match self.inner.tx.try_send(job) {
+            Ok(()) => Ok(rx),
+            Err(TrySendError::Full(_)) => Err(BlockingError::Overloaded),
+            Err(TrySendError::Disconnected(_)) => Err(BlockingError::Canceled),
+        }
+    }
+}
+
+impl fmt::Debug for BlockingPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlockingPool")
+            .field("name", &self.inner.name)
+            .field("threads", &self.inner.threads)
+            .field("queue_limit", &self.inner.queue_limit)
+            .finish()
+    }
+}
+
+/// Runs jobs until all senders are dropped.
+fn run_jobs(rx: &Mutex<Receiver<Job>>) {
+    loop {
+        // lock is only held by the thread waiting for the next job
+        let job = match rx.lock() {
+            Ok(rx) => rx.recv(),
+            Err(_) => return,
+        };
+
+        match job {
+            // a panicking job drops its result sender, which reports cancellation to the caller
+            Ok(job) => {
+                let _ = panic::catch_unwind(AssertUnwindSafe(job));
+            }
+            Err(_) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{
+        http::{header, StatusCode},
+        web, ResponseError as _,
+    };
+
+    #[actix_rt::test]
+    async fn runs_jobs_on_named_threads() {
+        let pool = BlockingPool::new("named", 2, 4);
+        assert_eq!(pool.name(), "named");
+
+        let name = web::block_on(&pool, || thread::current().name().map(ToOwned::to_owned))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(name.starts_with("named-"));
+    }
+
+    #[actix_rt::test]
+    async fn saturated_pool_sheds_load() {
+        let pool = BlockingPool::new("saturated", 1, 1);
+
+        let (started_tx, started_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        // occupies the only thread
+        let first = web::block_on(&pool, move || {
+            started_tx.send(()).unwrap();
+            release_rx.recv().unwrap();
+            1
+        });
+        started_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+
+        // fills the queue
+        let second = web::block_on(&pool, || 2);
+
+        let err = web::block_on(&pool, || 3).await.unwrap_err();
+        assert!(matches!(err, BlockingError::Overloaded));
+
+        let res = err.error_response();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(res.headers().contains_key(header::RETRY_AFTER));
+
+        // default pool is unaffected
+        assert_eq!(web::block(|| 4).await.unwrap(), 4);
+
+        release_tx.send(()).unwrap();
+        assert_eq!(first.await.unwrap(), 1);
+        assert_eq!(second.await.unwrap(), 2);
+
+        // queue has drained
+        assert_eq!(web::block_on(&pool, || 5).await.unwrap(), 5);
+    }
+
+    #[actix_rt::test]
+    async fn panicking_job_is_canceled() {
+        let pool = BlockingPool::new("panicking", 1, 1);
+
+        let err = web::block_on(&pool, || panic!("job failed"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BlockingError::Canceled));
+        assert_eq!(err.status_code(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        // thread survives the panic
+        assert_eq!(web::block_on(&pool, || 1).await.unwrap(), 1);
+    }
+}
@@ -1,23 +1,34 @@
-use std::{net::SocketAddr, rc::Rc};
+use std::{
+    cell::RefCell, collections::HashMap, fmt, mem, net::SocketAddr, panic::Location, rc::Rc,
+};
 
-use actix_service::{boxed, IntoServiceFactory, ServiceFactory, ServiceFactoryExt as _};
+use actix_service::{
+    apply_fn_factory, boxed, IntoServiceFactory, Service as _, ServiceFactory,
+    ServiceFactoryExt as _,
+};
 
 use crate::{
-    data::Data,
+    data::{Data, DataLocations},
     dev::{Extensions, ResourceDef},
     error::Error,
     guard::Guard,
+    lifecycle::MatchedRoute,
+    redirect::RedirectTargetCheck,
     resource::Resource,
     rmap::ResourceMap,
-    route::Route,
+    route::{GuardSummary, Route},
     service::{
         AppServiceFactory, BoxedHttpServiceFactory, HttpServiceFactory, ServiceFactoryWrapper,
         ServiceRequest, ServiceResponse,
     },
+    worker::WorkerData,
+    HttpMessage as _, HttpRequest, HttpResponse,
 };
 
 type Guards = Vec<Box<dyn Guard>>;
 
+type ErrorRenderer = Rc<dyn Fn(&Error, &HttpRequest) -> HttpResponse>;
+
 /// Application configuration
 pub struct AppService {
     config: AppConfig,
@@ -30,6 +41,13 @@ pub struct AppService {
         Option<Guards>,
         Option<Rc<ResourceMap>>,
     )>,
+    guard_summaries: HashMap<usize, GuardSummary>,
+    validate_routes: Option<bool>,
+    auto_options: bool,
+    path_prefix: String,
+    record_routes: bool,
+    route_prefix: String,
+    redirect_checks: Rc<RefCell<Vec<RedirectTargetCheck>>>,
 }
 
 impl AppService {
@@ -40,6 +58,13 @@ impl AppService {
             default,
             root: true,
             services: Vec::new(),
+            guard_summaries: HashMap::new(),
+            validate_routes: None,
+            auto_options: false,
+            path_prefix: String::new(),
+            record_routes: false,
+            route_prefix: String::new(),
+            redirect_checks: Rc::default(),
         }
     }
 
@@ -60,9 +85,82 @@ impl AppService {
             Option<Rc<ResourceMap>>,
         )>,
     ) {
+        if let Some(strict) = self.validate_routes {
+            report_route_conflicts(
+                &find_route_conflicts(&self.services, &self.guard_summaries),
+                strict,
+            );
+        }
+
         (self.config, self.services)
     }
 
+    /// Enables route validation when services are collected; see [`App::validate_routes`].
+    ///
+    /// [`App::validate_routes`]: crate::App::validate_routes
+    pub(crate) fn set_validate_routes(&mut self, strict: Option<bool>) {
+        self.validate_routes = strict;
+    }
+
+    /// Sets the default for [`Resource::auto_options`]; see [`App::auto_options`].
+    ///
+    /// [`Resource::auto_options`]: crate::Resource::auto_options
+    /// [`App::auto_options`]: crate::App::auto_options
+    pub(crate) fn set_auto_options(&mut self, enabled: bool) {
+        self.auto_options = enabled;
+    }
+
+    /// Returns true if resources answer `OPTIONS` requests automatically by default.
+    pub(crate) fn auto_options(&self) -> bool {
+        self.auto_options
+    }
+
+    /// Returns the path pattern of the scopes enclosing the services being registered.
+    pub(crate) fn path_prefix(&self) -> &str {
+        &self.path_prefix
+    }
+
+    /// Appends the pattern of a nested scope to the path prefix; used by scopes on their inner
+    /// config.
+    pub(crate) fn push_path_prefix(&mut self, pattern: &str) {
+        let pattern = pattern.trim_end_matches('/');
+
+        if !pattern.is_empty() && !pattern.starts_with('/') {
+            self.path_prefix.push('/');
+        }
+
+        self.path_prefix.push_str(pattern);
+    }
+
+    /// Makes registered services record the pattern of the route that handles each request, for
+    /// [`ResponseEvent::match_pattern`](crate::dev::ResponseEvent::match_pattern).
+    pub(crate) fn set_record_routes(&mut self, enabled: bool) {
+        self.record_routes = enabled;
+    }
+
+    /// Appends the resource map pattern of a nested scope to the prefix of recorded route
+    /// patterns; used by scopes on their inner config.
+    pub(crate) fn push_route_prefix(&mut self, pattern: &str) {
+        self.route_prefix.push_str(pattern);
+    }
+
+    /// Queues a redirect target to be checked once all routes of the app are registered.
+    pub(crate) fn add_redirect_check(&self, check: RedirectTargetCheck) {
+        self.redirect_checks.borrow_mut().push(check);
+    }
+
+    /// Takes the redirect target checks queued by this config and all nested configs.
+    pub(crate) fn take_redirect_checks(&self) -> Vec<RedirectTargetCheck> {
+        mem::take(&mut *self.redirect_checks.borrow_mut())
+    }
+
+    /// Records what is known about the guards of the most recently registered service.
+    pub(crate) fn set_guard_summary(&mut self, summary: GuardSummary) {
+        if let Some(idx) = self.services.len().checked_sub(1) {
+            self.guard_summaries.insert(idx, summary);
+        }
+    }
+
     /// Clones inner config and default service, returning new `AppService` with empty service list
     /// marked as non-root.
     pub(crate) fn clone_config(&self) -> Self {
@@ -71,6 +169,13 @@ impl AppService {
             default: self.default.clone(),
             services: Vec::new(),
             root: false,
+            guard_summaries: HashMap::new(),
+            validate_routes: self.validate_routes,
+            auto_options: self.auto_options,
+            path_prefix: self.path_prefix.clone(),
+            record_routes: self.record_routes,
+            route_prefix: self.route_prefix.clone(),
+            redirect_checks: Rc::clone(&self.redirect_checks),
         }
     }
 
@@ -101,8 +206,25 @@ impl AppService {
                 InitError = (),
             > + 'static,
     {
-        self.services
-            .push((rdef, boxed::factory(factory.into_factory()), guards, nested));
+        let route = match rdef.pattern() {
+            Some(pattern) if self.record_routes && !rdef.is_prefix() => Some(MatchedRoute(
+                Rc::from(format!("{}{}", self.route_prefix, pattern)),
+            )),
+            _ => None,
+        };
+
+        let factory = match route {
+            Some(route) => boxed::factory(apply_fn_factory(
+                factory.into_factory(),
+                move |req: ServiceRequest, srv| {
+                    req.extensions_mut().insert(route.clone());
+                    srv.call(req)
+                },
+            )),
+            None => boxed::factory(factory.into_factory()),
+        };
+
+        self.services.push((rdef, factory, guards, nested));
     }
 }
 
@@ -112,11 +234,21 @@ pub struct AppConfig {
     secure: bool,
     host: String,
     addr: SocketAddr,
+    worker: Option<Rc<WorkerData>>,
+    invalid_utf8_policy: InvalidUtf8Policy,
+    trusted_proxies: Option<usize>,
 }
 
 impl AppConfig {
     pub(crate) fn new(secure: bool, host: String, addr: SocketAddr) -> Self {
-        AppConfig { secure, host, addr }
+        AppConfig {
+            secure,
+            host,
+            addr,
+            worker: None,
+            invalid_utf8_policy: InvalidUtf8Policy::default(),
+            trusted_proxies: None,
+        }
     }
 
     /// Needed in actix-test crate. Semver exempt.
@@ -146,12 +278,84 @@ impl AppConfig {
         self.addr
     }
 
+    /// Returns the index of the worker running the app, if the server was configured with an
+    /// [`on_worker_start`](crate::HttpServer::on_worker_start) hook.
+    pub fn worker_index(&self) -> Option<usize> {
+        self.worker.as_ref().map(|worker| worker.index())
+    }
+
+    pub(crate) fn worker(&self) -> Option<&Rc<WorkerData>> {
+        self.worker.as_ref()
+    }
+
+    pub(crate) fn set_worker(&mut self, worker: Rc<WorkerData>) {
+        self.worker = Some(worker);
+    }
+
+    /// Returns how the app handles request paths and headers that are not valid UTF-8.
+    ///
+    /// See [`App::invalid_utf8_policy`](crate::App::invalid_utf8_policy).
+    pub fn invalid_utf8_policy(&self) -> InvalidUtf8Policy {
+        self.invalid_utf8_policy
+    }
+
+    pub(crate) fn set_invalid_utf8_policy(&mut self, policy: InvalidUtf8Policy) {
+        self.invalid_utf8_policy = policy;
+    }
+
+    /// Returns the number of proxies in front of the app whose forwarding headers are trusted.
+    ///
+    /// See [`App::trusted_proxies`](crate::App::trusted_proxies).
+    pub fn trusted_proxies(&self) -> Option<usize> {
+        self.trusted_proxies
+    }
+
+    pub(crate) fn set_trusted_proxies(&mut self, count: Option<usize>) {
+        self.trusted_proxies = count;
+    }
+
     #[cfg(test)]
     pub(crate) fn set_host(&mut self, host: &str) {
         self.host = host.to_owned();
     }
 }
 
+/// How an app handles request paths and header values that are not valid UTF-8.
+///
+/// Set with [`App::invalid_utf8_policy`](crate::App::invalid_utf8_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidUtf8Policy {
+    /// Rejects invalid UTF-8. This is the default.
+    ///
+    /// Requests with a path that is not valid UTF-8 once percent-decoded are responded to with
+    /// "400 Bad Request" before routing. The `Query` extractor fails on query strings that decode
+    /// to invalid UTF-8, and typed headers fail to parse from values that are not valid UTF-8.
+    Reject,
+
+    /// Replaces invalid UTF-8 with `U+FFFD REPLACEMENT CHARACTER`.
+    ///
+    /// The replacement is visible in the request path, match info, and extracted query strings.
+    /// Header values are converted lossily by
+    /// [`HttpRequest::header_str`](crate::HttpRequest::header_str) and the
+    /// [`Header`](crate::web::Header) extractor; the request's headers keep their raw bytes.
+    Lossy,
+
+    /// Keeps invalid UTF-8 accessible as raw bytes.
+    ///
+    /// Invalid byte sequences remain percent-encoded in the request path, and the decoded bytes of
+    /// path segments are available through [`Path::get_bytes`](crate::dev::Path::get_bytes).
+    /// String-based access still fails: `Path` and `Query` extractors, typed headers, and
+    /// `HttpRequest::header_str` fail for invalid UTF-8, while raw header bytes remain available
+    /// through [`HeaderValue::as_bytes`](crate::http::header::HeaderValue::as_bytes).
+    Preserve,
+}
+
+impl Default for InvalidUtf8Policy {
+    fn default() -> Self {
+        Self::Reject
+    }
+}
+
 impl Default for AppConfig {
     /// Returns the default AppConfig.
     /// Note: The included socket address is "127.0.0.1".
@@ -192,11 +396,26 @@ impl Default for AppConfig {
 /// // merge `/test` routes from config function to App
 /// App::new().configure(config);
 /// ```
+///
+/// # Scoping
+/// Data added with [`app_data`](Self::app_data) and the [error renderer](Self::error_renderer)
+/// apply to the services and default service registered by the same configuration, including any
+/// nested calls to [`configure`](Self::configure), so separate configurations do not affect each
+/// other. Data is looked up from the innermost level outwards: resource data takes precedence
+/// over scope data, which takes precedence over configuration data, which takes precedence over
+/// the data of the app or scope the configuration is merged into.
+///
+/// A configuration that registers no services only contributes data; its data is added to the app
+/// or scope it is merged into, where it is shared with all services.
 pub struct ServiceConfig {
     pub(crate) services: Vec<Box<dyn AppServiceFactory>>,
     pub(crate) external: Vec<ResourceDef>,
     pub(crate) app_data: Extensions,
+    pub(crate) data_locations: DataLocations,
     pub(crate) default: Option<Rc<BoxedHttpServiceFactory>>,
+    pub(crate) default_location: Option<&'static Location<'static>>,
+    has_data: bool,
+    error_renderer: Option<ErrorRenderer>,
 }
 
 impl ServiceConfig {
@@ -205,7 +424,11 @@ impl ServiceConfig {
             services: Vec::new(),
             external: Vec::new(),
             app_data: Extensions::new(),
+            data_locations: DataLocations::default(),
             default: None,
+            default_location: None,
+            has_data: false,
+            error_renderer: None,
         }
     }
 
@@ -213,6 +436,7 @@ impl ServiceConfig {
     ///
     /// Counterpart to [`App::data()`](crate::App::data).
     #[deprecated(since = "4.0.0", note = "Use `.app_data(Data::new(val))` instead.")]
+    #[track_caller]
     pub fn data<U: 'static>(&mut self, data: U) -> &mut Self {
         self.app_data(Data::new(data));
         self
@@ -220,15 +444,37 @@ impl ServiceConfig {
 
     /// Add arbitrary app data item.
     ///
+    /// The item is only visible to the services registered by this configuration; see
+    /// [scoping](Self#scoping) for details.
+    ///
     /// Counterpart to [`App::app_data()`](crate::App::app_data).
+    #[track_caller]
     pub fn app_data<U: 'static>(&mut self, ext: U) -> &mut Self {
+        self.data_locations.register::<U>();
         self.app_data.insert(ext);
+        self.has_data = true;
+        self
+    }
+
+    /// Add arbitrary app data item, overwriting data of the same type added earlier.
+    ///
+    /// Counterpart to [`App::app_data_replace()`](crate::App::app_data_replace).
+    #[track_caller]
+    pub fn app_data_replace<U: 'static>(&mut self, ext: U) -> &mut Self {
+        self.data_locations.replace::<U>();
+        self.app_data.insert(ext);
+        self.has_data = true;
         self
     }
 
     /// Default service to be used if no matching resource could be found.
     ///
     /// Counterpart to [`App::default_service()`](crate::App::default_service).
+    ///
+    /// # Panics
+    /// Panics if this configuration, or another configuration merged into the same app or scope,
+    /// already registered a default service. The panic message names both call sites.
+    #[track_caller]
     pub fn default_service<F, U>(&mut self, f: F) -> &mut Self
     where
         F: IntoServiceFactory<U, ServiceRequest>,
@@ -240,12 +486,45 @@ impl ServiceConfig {
             > + 'static,
         U::InitError: std::fmt::Debug,
     {
+        let location = Location::caller();
+        check_default_conflict(self.default_location, location);
+
         let svc = f
             .into_factory()
             .map_init_err(|err| log::error!("Can not construct default service: {:?}", err));
 
         self.default = Some(Rc::new(boxed::factory(svc)));
+        self.default_location = Some(location);
+
+        self
+    }
 
+    /// Sets a function for rendering error responses from the services registered by this
+    /// configuration.
+    ///
+    /// The renderer is called for responses carrying an [`Error`], such as those from handlers
+    /// returning an error or from failed extractors, and for the default service. Errors returned
+    /// from services as `Err` values are rendered by the app as usual.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpResponse};
+    ///
+    /// fn api(cfg: &mut web::ServiceConfig) {
+    ///     cfg.error_renderer(|err, _req| {
+    ///         HttpResponse::build(err.as_response_error().status_code())
+    ///             .json(serde_json::json!({ "error": err.to_string() }))
+    ///     })
+    ///     .route("/items", web::get().to(|| HttpResponse::Ok()));
+    /// }
+    ///
+    /// App::new().service(web::scope("/api").configure(api));
+    /// ```
+    pub fn error_renderer<F>(&mut self, renderer: F) -> &mut Self
+    where
+        F: Fn(&Error, &HttpRequest) -> HttpResponse + 'static,
+    {
+        self.error_renderer = Some(Rc::new(renderer));
         self
     }
 
@@ -302,6 +581,248 @@ impl ServiceConfig {
     }
 }
 
+impl ServiceConfig {
+    /// Applies this configuration's data and error renderer to the services and default service it
+    /// contributes, before they are merged into an app or scope.
+    pub(crate) fn apply_scoping(&mut self) {
+        if self.services.is_empty() && self.default.is_none() {
+            return;
+        }
+
+        let data = if mem::take(&mut self.has_data) {
+            self.data_locations = DataLocations::default();
+            Some(Rc::new(mem::replace(&mut self.app_data, Extensions::new())))
+        } else {
+            None
+        };
+
+        let renderer = self.error_renderer.take();
+
+        if data.is_none() && renderer.is_none() {
+            return;
+        }
+
+        if let Some(default) = self.default.take() {
+            let default = scoped_factory(default, data.clone(), renderer.clone());
+            self.default = Some(Rc::new(default));
+        }
+
+        let services = mem::take(&mut self.services);
+        self.services.push(Box::new(ScopedServices {
+            services,
+            data,
+            renderer,
+        }));
+    }
+}
+
+/// Panics if a default service was already registered at `prev`.
+#[track_caller]
+pub(crate) fn check_default_conflict(
+    prev: Option<&'static Location<'static>>,
+    location: &'static Location<'static>,
+) {
+    if let Some(prev) = prev {
+        panic!(
+            "Default service registered by configuration at {} conflicts with the default service \
+            registered at {}",
+            location, prev
+        );
+    }
+}
+
+/// A pair of services where requests matching the later one are routed to the earlier one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RouteConflict {
+    /// Description of the service registered first.
+    earlier: String,
+
+    /// Description of the service registered later, which is never reached.
+    later: String,
+
+    /// Whether both services have the same pattern, as opposed to `earlier` matching `later`.
+    duplicate: bool,
+}
+
+impl fmt::Display for RouteConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.duplicate {
+            write!(f, "{} is registered again as {}", self.earlier, self.later)
+        } else {
+            write!(
+                f,
+                "{} shadows {}, which is registered after it",
+                self.earlier, self.later
+            )
+        }
+    }
+}
+
+/// Finds services that can never be reached because a service registered before them matches the
+/// same requests.
+///
+/// Two services are only compared when their guards are known to be method guards and allow a
+/// common method; other guards, such as host guards, may route requests to either of them.
+#[allow(clippy::type_complexity)]
+fn find_route_conflicts(
+    services: &[(
+        ResourceDef,
+        BoxedHttpServiceFactory,
+        Option<Guards>,
+        Option<Rc<ResourceMap>>,
+    )],
+    summaries: &HashMap<usize, GuardSummary>,
+) -> Vec<RouteConflict> {
+    let summary = |idx: usize| match summaries.get(&idx) {
+        Some(summary) => summary.clone(),
+        None => GuardSummary {
+            methods: None,
+            opaque: services[idx].2.is_some(),
+            excluded: Vec::new(),
+        },
+    };
+
+    let describe = |rdef: &ResourceDef, pattern: &str| match rdef.name() {
+        Some(name) => format!("`{}` (name: {})", pattern, name),
+        None => format!("`{}`", pattern),
+    };
+
+    let mut conflicts = Vec::new();
+
+    for (later_idx, (later, ..)) in services.iter().enumerate() {
+        let later_summary = summary(later_idx);
+
+        if later_summary.opaque {
+            continue;
+        }
+
+        for (earlier_idx, (earlier, ..)) in services[..later_idx].iter().enumerate() {
+            let earlier_summary = summary(earlier_idx);
+
+            if earlier_summary.opaque || !earlier_summary.methods_overlap(&later_summary) {
+                continue;
+            }
+
+            for pattern in later.pattern_iter() {
+                let duplicate = earlier.is_prefix() == later.is_prefix()
+                    && earlier.pattern_iter().any(|p| p == pattern);
+
+                let shadowed =
+                    !duplicate && !pattern.contains('{') && earlier.is_match(pattern);
+
+                if duplicate || shadowed {
+                    let earlier_pattern = match duplicate {
+                        true => pattern,
+                        false => earlier.pattern_iter().next().unwrap_or(pattern),
+                    };
+
+                    conflicts.push(RouteConflict {
+                        earlier: describe(earlier, earlier_pattern),
+                        later: describe(later, pattern),
+                        duplicate,
+                    });
+                }
+            }
+        }
+    }
+
+    conflicts
+}
+
+/// Logs `conflicts` as warnings or, if `strict`, panics when there are any.
+fn report_route_conflicts(conflicts: &[RouteConflict], strict: bool) {
+    if conflicts.is_empty() {
+        return;
+    }
+
+    if strict {
+        let report = conflicts
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("\n  ");
+
+        panic!("conflicting routes:\n  {}", report);
+    }
+
+    for conflict in conflicts {
+        log::warn!("conflicting routes: {}", conflict);
+    }
+}
+
+/// Services registered by a configuration that has its own data or error renderer.
+struct ScopedServices {
+    services: Vec<Box<dyn AppServiceFactory>>,
+    data: Option<Rc<Extensions>>,
+    renderer: Option<ErrorRenderer>,
+}
+
+impl AppServiceFactory for ScopedServices {
+    fn register(&mut self, config: &mut AppService) {
+        let mut inner = config.clone_config();
+        inner.root = config.root;
+
+        for service in &mut self.services {
+            service.register(&mut inner);
+        }
+
+        // services are validated along with their siblings once re-registered below
+        inner.validate_routes = None;
+        let mut summaries = mem::take(&mut inner.guard_summaries);
+        let (_, services) = inner.into_services();
+
+        for (idx, (rdef, factory, guards, nested)) in services.into_iter().enumerate() {
+            let factory = scoped_factory(factory, self.data.clone(), self.renderer.clone());
+            config.register_service(rdef, guards, factory, nested);
+
+            if let Some(summary) = summaries.remove(&idx) {
+                config.set_guard_summary(summary);
+            }
+        }
+    }
+}
+
+/// Wraps `factory` so that its services see `data` and have their error responses rendered by
+/// `renderer`.
+fn scoped_factory<F>(
+    factory: F,
+    data: Option<Rc<Extensions>>,
+    renderer: Option<ErrorRenderer>,
+) -> BoxedHttpServiceFactory
+where
+    F: ServiceFactory<
+            ServiceRequest,
+            Config = (),
+            Response = ServiceResponse,
+            Error = Error,
+            InitError = (),
+        > + 'static,
+{
+    boxed::factory(apply_fn_factory(
+        factory,
+        move |mut req: ServiceRequest, srv: &F::Service| {
+            if let Some(data) = &data {
+                req.add_data_container(Rc::clone(data));
+            }
+
+            let fut = srv.call(req);
+            let renderer = renderer.clone();
+
+            async move {
+                let res = fut.await?;
+
+                match (renderer, res.response().error()) {
+                    (Some(renderer), Some(err)) => {
+                        let rendered = renderer(err, res.request());
+                        Ok(res.into_response(rendered))
+                    }
+                    _ => Ok(res),
+                }
+            }
+        },
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use actix_service::Service;
@@ -426,4 +947,264 @@ mod tests {
         assert_eq!(res.status(), StatusCode::OK);
         assert_body_eq!(res, b"hello world");
     }
+
+    #[actix_rt::test]
+    async fn configs_are_isolated() {
+        fn config(name: &'static str, limit: usize) -> impl FnOnce(&mut ServiceConfig) {
+            move |cfg| {
+                cfg.app_data(web::JsonConfig::default().limit(limit))
+                    .route(
+                        "/echo",
+                        web::post().to(|body: web::Json<serde_json::Value>| async move {
+                            body.to_string()
+                        }),
+                    )
+                    .default_service(web::to(move || HttpResponse::NotFound().body(name)));
+            }
+        }
+
+        let srv = init_service(
+            App::new()
+                .app_data(web::JsonConfig::default().limit(1))
+                .service(web::scope("/small").configure(config("small", 8)))
+                .service(web::scope("/large").configure(config("large", 1024))),
+        )
+        .await;
+
+        let json = r#"{"name":"actix"}"#;
+
+        let req = TestRequest::post()
+            .uri("/small/echo")
+            .insert_header(("content-type", "application/json"))
+            .set_payload(json)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let req = TestRequest::post()
+            .uri("/large/echo")
+            .insert_header(("content-type", "application/json"))
+            .set_payload(json)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_body_eq!(res, json.as_bytes());
+
+        let req = TestRequest::with_uri("/small/missing").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_body_eq!(res, b"small");
+
+        let req = TestRequest::with_uri("/large/missing").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert_body_eq!(res, b"large");
+    }
+
+    #[actix_rt::test]
+    async fn config_data_precedence() {
+        let srv = init_service(
+            App::new()
+                .app_data(1u8)
+                .app_data(1u16)
+                .configure(|cfg| {
+                    cfg.app_data(2u8).app_data(2u16).service(
+                        web::resource("/")
+                            .app_data(3u8)
+                            .to(|req: HttpRequest| async move {
+                                let small = req.app_data::<u8>().unwrap();
+                                let large = req.app_data::<u16>().unwrap();
+                                format!("{} {}", small, large)
+                            }),
+                    );
+                })
+                .route(
+                    "/outside",
+                    web::get().to(|req: HttpRequest| async move {
+                        req.app_data::<u8>().unwrap().to_string()
+                    }),
+                ),
+        )
+        .await;
+
+        // resource data, then config data, then app data
+        let req = TestRequest::with_uri("/").to_request();
+        let res = call_service(&srv, req).await;
+        assert_body_eq!(res, b"3 2");
+
+        // config data is not visible outside the config
+        let req = TestRequest::with_uri("/outside").to_request();
+        let res = call_service(&srv, req).await;
+        assert_body_eq!(res, b"1");
+    }
+
+    #[actix_rt::test]
+    async fn error_renderer() {
+        let srv = init_service(
+            App::new()
+                .service(web::scope("/api").configure(|cfg| {
+                    cfg.error_renderer(|err, req| {
+                        HttpResponse::build(err.as_response_error().status_code())
+                            .body(format!("{} failed: {}", req.path(), err))
+                    })
+                    .route(
+                        "/fail",
+                        web::get().to(|| async {
+                            Err::<HttpResponse, _>(crate::error::ErrorBadRequest("oops"))
+                        }),
+                    )
+                    .route("/ok", web::get().to(HttpResponse::Ok));
+                }))
+                .route(
+                    "/fail",
+                    web::get().to(|| async {
+                        Err::<HttpResponse, _>(crate::error::ErrorBadRequest("oops"))
+                    }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/api/fail").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_body_eq!(res, b"/api/fail failed: oops");
+
+        let req = TestRequest::with_uri("/api/ok").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // errors outside the config are rendered as usual
+        let req = TestRequest::with_uri("/fail").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_body_eq!(res, b"oops");
+    }
+
+    #[test]
+    fn conflicting_default_services() {
+        fn not_found(cfg: &mut ServiceConfig) {
+            cfg.default_service(web::to(HttpResponse::NotFound));
+        }
+
+        let err = std::panic::catch_unwind(|| {
+            App::new().configure(not_found).configure(not_found);
+        })
+        .unwrap_err();
+
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert_eq!(msg.matches(file!()).count(), 2);
+        assert!(msg.contains("conflicts with the default service"));
+
+        let err = std::panic::catch_unwind(|| {
+            web::scope("/scope").configure(|cfg| {
+                cfg.configure(not_found).configure(not_found);
+            });
+        })
+        .unwrap_err();
+
+        let msg = err.downcast_ref::<String>().unwrap();
+        assert_eq!(msg.matches(file!()).count(), 2);
+    }
+
+    fn conflicts(register: impl FnOnce(&mut AppService)) -> Vec<String> {
+        let default = Rc::new(boxed::factory(crate::dev::fn_service(
+            |req: ServiceRequest| async { Ok(req.into_response(HttpResponse::NotFound())) },
+        )));
+
+        let mut config = AppService::new(AppConfig::default(), default);
+        register(&mut config);
+
+        find_route_conflicts(&config.services, &config.guard_summaries)
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn finds_shadowed_routes() {
+        let found = conflicts(|config| {
+            web::resource("/users/{id}")
+                .name("user")
+                .route(web::get().to(HttpResponse::Ok))
+                .register(config);
+            web::resource("/users/me")
+                .add_guards(web::get().take_guards())
+                .to(HttpResponse::Ok)
+                .register(config);
+
+            // same path, different methods
+            web::resource("/items")
+                .add_guards(web::get().take_guards())
+                .to(HttpResponse::Ok)
+                .register(config);
+            web::resource("/items")
+                .add_guards(web::post().take_guards())
+                .to(HttpResponse::Ok)
+                .register(config);
+            web::resource("/items")
+                .add_guards(web::get().take_guards())
+                .to(HttpResponse::Ok)
+                .register(config);
+
+            // other guards may route requests to either resource
+            web::resource("/users/me")
+                .guard(crate::guard::Host("example.com"))
+                .to(HttpResponse::Ok)
+                .register(config);
+
+            web::scope("/admin").register(config);
+            web::resource("/admin/stats")
+                .to(HttpResponse::Ok)
+                .register(config);
+        });
+
+        assert_eq!(
+            found,
+            [
+                "`/users/{id}` (name: user) shadows `/users/me`, which is registered after it",
+                "`/items` is registered again as `/items`",
+                "`/admin` shadows `/admin/stats`, which is registered after it",
+            ]
+        );
+    }
+
+    #[actix_rt::test]
+    async fn valid_routes_pass() {
+        let app = App::new()
+            .validate_routes(true)
+            .route("/users/me", web::get().to(HttpResponse::Ok))
+            .route("/users/{id}", web::get().to(HttpResponse::Ok))
+            .route("/users/{id}", web::delete().to(HttpResponse::Ok))
+            .service(
+                web::scope("/admin")
+                    .route("/stats", web::get().to(HttpResponse::Ok))
+                    .route("/{page}", web::get().to(HttpResponse::Ok)),
+            )
+            .service(
+                web::resource("/home")
+                    .guard(crate::guard::Host("a.example.com"))
+                    .to(HttpResponse::Ok),
+            )
+            .service(
+                web::resource("/home")
+                    .guard(crate::guard::Host("b.example.com"))
+                    .to(HttpResponse::Ok),
+            );
+
+        let srv = init_service(app).await;
+        let req = TestRequest::with_uri("/users/me").to_request();
+        assert_eq!(call_service(&srv, req).await.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    #[should_panic(expected = "`/{page}` shadows `/stats`, which is registered after it")]
+    async fn shadowed_routes_in_scope_panic() {
+        let app = App::new().validate_routes(true).service(
+            web::scope("/admin")
+                .route("/{page}", web::get().to(HttpResponse::Ok))
+                .route("/stats", web::get().to(HttpResponse::Ok)),
+        );
+
+        init_service(app).await;
+    }
 }
@@ -1,4 +1,11 @@
-use std::{any::type_name, ops::Deref, sync::Arc};
+#[cfg(debug_assertions)]
+use std::collections::HashMap;
+use std::{
+    any::{type_name, TypeId},
+    ops::Deref,
+    panic::Location,
+    sync::Arc,
+};
 
 use actix_http::Extensions;
 use actix_utils::future::{err, ok, Ready};
@@ -16,6 +23,86 @@ pub(crate) trait DataFactory {
 pub(crate) type FnDataFactory =
     Box<dyn Fn() -> LocalBoxFuture<'static, Result<Box<dyn DataFactory>, ()>>>;
 
+/// Call sites of the data registered on one app, scope, resource, or configuration.
+///
+/// Used to warn when data of the same type is registered twice on the same level, which silently
+/// discards the first value. Only tracked in debug builds.
+#[derive(Debug, Default)]
+pub(crate) struct DataLocations {
+    #[cfg(debug_assertions)]
+    locations: HashMap<TypeId, (&'static str, &'static Location<'static>)>,
+}
+
+impl DataLocations {
+    /// Records data of type `T` registered at the caller's location, warning if data of that type
+    /// was already registered.
+    #[track_caller]
+    pub(crate) fn register<T: 'static>(&mut self) {
+        self.insert(
+            TypeId::of::<T>(),
+            type_name::<T>(),
+            Location::caller(),
+            true,
+        );
+    }
+
+    /// Records data of type `T` registered at the caller's location, intentionally replacing any
+    /// data of that type.
+    #[track_caller]
+    pub(crate) fn replace<T: 'static>(&mut self) {
+        self.insert(
+            TypeId::of::<T>(),
+            type_name::<T>(),
+            Location::caller(),
+            false,
+        );
+    }
+
+    /// Records data registered elsewhere that is merged into this level, warning about types that
+    /// were already registered.
+    pub(crate) fn merge(&mut self, other: DataLocations) {
+        #[cfg(debug_assertions)]
+        for (type_id, (name, location)) in other.locations {
+            self.insert(type_id, name, location, true);
+        }
+
+        #[cfg(not(debug_assertions))]
+        let _ = other;
+    }
+
+    #[cfg(debug_assertions)]
+    fn insert(
+        &mut self,
+        type_id: TypeId,
+        name: &'static str,
+        location: &'static Location<'static>,
+        warn: bool,
+    ) {
+        if let Some((_, prev)) = self.locations.insert(type_id, (name, location)) {
+            if warn {
+                log::warn!(
+                    "App data of type `{}` registered at {} overwrites the data registered at {}; \
+                    use `app_data_replace` if this is intended",
+                    name,
+                    location,
+                    prev
+                );
+            }
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    #[inline]
+    fn insert(
+        &mut self,
+        _type_id: TypeId,
+        _name: &'static str,
+        _location: &'static Location<'static>,
+        _warn: bool,
+    ) {
+    }
+}
+
 /// Application data wrapper and extractor.
 ///
 /// # Setting Data
@@ -182,6 +269,115 @@ mod tests {
         web, App, HttpResponse,
     };
 
+    /// Returns the warnings logged on this thread while running `f`.
+    #[cfg(debug_assertions)]
+    fn captured_warnings(f: impl FnOnce()) -> Vec<String> {
+        use std::{cell::RefCell, sync::Once};
+
+        thread_local! {
+            static WARNINGS: RefCell<Vec<String>> = RefCell::new(Vec::new());
+        }
+
+        struct CaptureLogger;
+
+        impl log::Log for CaptureLogger {
+            fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+                metadata.level() <= log::Level::Warn
+            }
+
+            fn log(&self, record: &log::Record<'_>) {
+                if self.enabled(record.metadata()) {
+                    WARNINGS
+                        .with(|warnings| warnings.borrow_mut().push(record.args().to_string()));
+                }
+            }
+
+            fn flush(&self) {}
+        }
+
+        static INIT: Once = Once::new();
+        INIT.call_once(|| {
+            log::set_logger(&CaptureLogger).unwrap();
+            log::set_max_level(log::LevelFilter::Warn);
+        });
+
+        WARNINGS.with(|warnings| warnings.borrow_mut().clear());
+        f();
+        WARNINGS.with(|warnings| warnings.take())
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn duplicate_registration_warning() {
+        let warnings = captured_warnings(|| {
+            App::new()
+                .app_data(Data::new(1u32))
+                .app_data(Data::new(2u32));
+        });
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("actix_web::data::Data<u32>"));
+        // both call sites are named
+        assert_eq!(warnings[0].matches(file!()).count(), 2, "{}", warnings[0]);
+
+        let warnings = captured_warnings(|| {
+            App::new()
+                .configure(|cfg| {
+                    cfg.app_data(1u8);
+                })
+                .app_data(2u8);
+
+            web::scope("/").app_data(1u8).configure(|cfg| {
+                cfg.app_data(2u8);
+            });
+
+            web::resource("/").app_data(1u8).app_data(2u8);
+        });
+        assert_eq!(warnings.len(), 3, "{:?}", warnings);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn replace_and_shadowing_do_not_warn() {
+        let warnings = captured_warnings(|| {
+            App::new()
+                .app_data(Data::new(1u32))
+                .app_data_replace(Data::new(2u32))
+                .app_data(Data::new(1u64))
+                .configure(|cfg| {
+                    cfg.app_data(1u8).app_data_replace(2u8);
+                })
+                .service(
+                    web::scope("/")
+                        .app_data(Data::new(3u32))
+                        .app_data_replace(Data::new(4u32))
+                        .service(
+                            web::resource("/")
+                                .app_data(Data::new(5u32))
+                                .app_data_replace(Data::new(6u32)),
+                        ),
+                );
+        });
+        assert!(warnings.is_empty(), "{:?}", warnings);
+    }
+
+    #[actix_rt::test]
+    async fn replaced_data_is_used() {
+        let srv = init_service(
+            App::new()
+                .app_data(Data::new(1u32))
+                .app_data_replace(Data::new(2u32))
+                .service(web::resource("/").to(|data: web::Data<u32>| {
+                    assert_eq!(**data, 2);
+                    HttpResponse::Ok()
+                })),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let resp = srv.call(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
     // allow deprecated App::data
     #[allow(deprecated)]
     #[actix_rt::test]
@@ -7,7 +7,10 @@
 //! - [`ConnectionInfo`]: Connection information
 //! - [`PeerAddr`]: Connection information
 
-pub use actix_http::{Extensions, Payload, RequestHead, Response, ResponseHead};
+pub use actix_http::{
+    ConnectionClosed, DisconnectSignal, Disconnected, DrainSignal, Extensions, Payload,
+    Protocol, RequestHead, Response, ResponseHead,
+};
 pub use actix_router::{Path, ResourceDef, ResourcePath, Url};
 pub use actix_server::{Server, ServerHandle};
 pub use actix_service::{
@@ -17,14 +20,16 @@ pub use actix_service::{
 #[cfg(feature = "__compress")]
 pub use actix_http::encoding::Decoder as Decompress;
 
-pub use crate::config::{AppConfig, AppService};
+pub use crate::config::{AppConfig, AppService, InvalidUtf8Policy};
 #[doc(hidden)]
 pub use crate::handler::Handler;
 pub use crate::info::{ConnectionInfo, PeerAddr};
+pub use crate::lifecycle::{RequestEvent, ResponseEvent, ResponseStage};
 pub use crate::rmap::ResourceMap;
 pub use crate::service::{HttpServiceFactory, ServiceRequest, ServiceResponse, WebService};
 
 pub use crate::types::{JsonBody, Readlines, UrlEncoded};
+pub use crate::worker::WorkerCtx;
 
 use actix_router::Patterns;
 
@@ -15,7 +15,11 @@ use serde_urlencoded::de::Error as FormDeError;
 use serde_urlencoded::ser::Error as FormError;
 use url::ParseError as UrlParseError;
 
-use crate::http::StatusCode;
+use crate::{
+    http::{header, StatusCode},
+    web::ByteSize,
+    HttpResponse,
+};
 
 #[allow(clippy::module_inception)]
 mod error;
@@ -35,11 +39,134 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// An error representing a problem running a blocking task on a thread pool.
 #[derive(Debug, Display, Error)]
-#[display(fmt = "Blocking thread pool is shut down unexpectedly")]
 #[non_exhaustive]
-pub struct BlockingError;
+pub enum BlockingError {
+    /// The task did not complete, because it panicked or its thread pool shut down.
+    #[display(fmt = "Blocking thread pool is shut down unexpectedly")]
+    Canceled,
+
+    /// The task was rejected because the thread pool's queue is full.
+    #[display(fmt = "Blocking thread pool is overloaded")]
+    Overloaded,
+}
+
+impl ResponseError for BlockingError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            BlockingError::Overloaded => StatusCode::SERVICE_UNAVAILABLE,
+            BlockingError::Canceled => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut res = HttpResponse::build(self.status_code());
+        res.insert_header(header::ContentType::plaintext());
+
+        // overload is expected to be short-lived; ask clients to retry soon
+        if let BlockingError::Overloaded = self {
+            res.insert_header((header::RETRY_AFTER, 1));
+        }
+
+        res.body(self.to_string())
+    }
+}
+
+/// An error representing a request body that is larger than a [`BodyLimit`] allows.
+///
+/// Responds with "413 Payload Too Large" and a JSON problem details body, as defined in
+/// [RFC 7807](https://datatracker.ietf.org/doc/html/rfc7807).
+///
+/// [`BodyLimit`]: crate::middleware::BodyLimit
+#[derive(Debug, Display, Error)]
+#[display(
+    fmt = "Request body is larger than allowed (limit: {}).",
+    "ByteSize(*limit)"
+)]
+pub struct BodyLimitExceeded {
+    limit: usize,
+    retry_after: Option<u64>,
+}
+
+impl BodyLimitExceeded {
+    pub(crate) fn new(limit: usize, retry_after: Option<u64>) -> Self {
+        Self { limit, retry_after }
+    }
+
+    /// Returns the maximum number of body bytes that were allowed.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+}
+
+impl ResponseError for BodyLimitExceeded {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::PAYLOAD_TOO_LARGE
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        let mut res = HttpResponse::build(self.status_code());
+        res.insert_header((header::CONTENT_TYPE, "application/problem+json"));
+
+        if let Some(secs) = self.retry_after {
+            res.insert_header((header::RETRY_AFTER, secs));
+        }
+
+        res.body(
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Payload Too Large",
+                "status": self.status_code().as_u16(),
+                "detail": self.to_string(),
+            })
+            .to_string(),
+        )
+    }
+}
 
-impl ResponseError for crate::error::BlockingError {}
+/// An error representing a request header that could not be parsed into its typed form.
+///
+/// Responds with "400 Bad Request". The message names the header but, since the header value is
+/// supplied by the client, does not include it.
+///
+/// # Examples
+/// ```
+/// use actix_web::{error::HeaderParseError, HttpRequest};
+/// use actix_web::http::header::{ContentLength, Header as _};
+///
+/// async fn handler(req: HttpRequest) -> Result<String, HeaderParseError> {
+///     let len = ContentLength::parse(&req).map_err(|_| HeaderParseError::of::<ContentLength>())?;
+///     Ok(format!("body is {} bytes", len.into_inner()))
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Display, Error)]
+#[display(fmt = "Invalid `{}` header", name)]
+pub struct HeaderParseError {
+    #[error(not(source))]
+    name: header::HeaderName,
+}
+
+impl HeaderParseError {
+    /// Constructs an error for the header `name`.
+    pub fn new(name: header::HeaderName) -> Self {
+        Self { name }
+    }
+
+    /// Constructs an error for the typed header `H`.
+    pub fn of<H: header::Header>() -> Self {
+        Self::new(H::name())
+    }
+
+    /// Returns the name of the header that could not be parsed.
+    pub fn header_name(&self) -> &header::HeaderName {
+        &self.name
+    }
+}
+
+impl ResponseError for HeaderParseError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
 
 /// Errors which can occur when attempting to generate resource uri.
 #[derive(Debug, PartialEq, Display, Error, From)]
@@ -60,6 +187,264 @@ pub enum UrlGenerationError {
 
 impl ResponseError for UrlGenerationError {}
 
+/// Errors which can occur when validating a [`RedirectRule`](crate::web::RedirectRule).
+///
+/// Each variant holds the `index` of the offending rule in the loaded rule set.
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum RedirectRuleError {
+    /// The `from` path is not a path, or has an unclosed dynamic segment.
+    #[display(
+        fmt = "Redirect rule {}: `from` must be a path pattern starting with `/`",
+        index
+    )]
+    InvalidFrom { index: usize },
+
+    /// The `to` target is empty, is not a valid header value, or is not a path for a relative rule.
+    #[display(fmt = "Redirect rule {}: invalid `to` target", index)]
+    InvalidTarget { index: usize },
+
+    /// The status code is not one of 301, 302, 303, 307, or 308.
+    #[display(
+        fmt = "Redirect rule {}: {} is not a redirect status code",
+        index,
+        status
+    )]
+    InvalidStatus { index: usize, status: u16 },
+
+    /// The `to` target has a `{name}` placeholder that is not a dynamic segment of `from`.
+    #[display(
+        fmt = "Redirect rule {}: placeholder `{{{}}}` is not a dynamic segment of `from`",
+        index,
+        name
+    )]
+    UnresolvedPlaceholder { index: usize, name: String },
+}
+
+/// Errors which can occur when resolving a templated [`Redirect`](crate::web::Redirect) target.
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum RedirectTemplateError {
+    /// A placeholder is not defined in the provided variables.
+    #[display(fmt = "Redirect target placeholder `${{{}}}` is not defined", name)]
+    UnknownPlaceholder {
+        #[error(not(source))]
+        name: String,
+    },
+
+    /// A placeholder is missing its closing `}`.
+    #[display(fmt = "Redirect target has an unclosed placeholder")]
+    UnclosedPlaceholder,
+
+    /// The resolved target is empty or not a valid URI.
+    #[display(fmt = "Redirect target `{}` is not a valid URI", target)]
+    InvalidTarget {
+        #[error(not(source))]
+        target: String,
+    },
+}
+
+/// Error returned when a [`Redirect`](crate::web::Redirect) that rejects `http://` targets resolves
+/// one.
+///
+/// Responds with "500 Internal Server Error".
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[display(fmt = "Redirect target `{}` does not use HTTPS", target)]
+#[non_exhaustive]
+pub struct InsecureRedirectTarget {
+    /// The rejected target.
+    #[error(not(source))]
+    pub target: String,
+}
+
+impl ResponseError for InsecureRedirectTarget {}
+
+/// Error returned when a [`Redirect`](crate::web::Redirect) target has a `{name}` placeholder that
+/// is not a dynamic segment of the request path.
+///
+/// Responds with "500 Internal Server Error".
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[display(
+    fmt = "Redirect target placeholder `{{{}}}` does not match a path segment",
+    name
+)]
+#[non_exhaustive]
+pub struct UnresolvedRedirectPlaceholder {
+    /// Name of the placeholder.
+    #[error(not(source))]
+    pub name: String,
+}
+
+impl ResponseError for UnresolvedRedirectPlaceholder {}
+
+/// Error returned when a [`Redirect`](crate::web::Redirect) with loop protection receives a
+/// request that has already been redirected the maximum number of times.
+///
+/// Responds with "508 Loop Detected".
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[display(fmt = "Redirect chain exceeded the maximum of {} redirects", max)]
+#[non_exhaustive]
+pub struct RedirectLoopDetected {
+    /// The maximum number of redirects allowed.
+    pub max: u32,
+}
+
+impl ResponseError for RedirectLoopDetected {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::LOOP_DETECTED
+    }
+}
+
+/// Errors which can occur when verifying a URL using [`UrlSigner`](crate::web::UrlSigner).
+///
+/// Responds with "403 Forbidden".
+#[cfg(feature = "signed-urls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "signed-urls")))]
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum SignedUrlError {
+    /// The URL has no signature or expiry.
+    #[display(fmt = "URL is not signed")]
+    Missing,
+
+    /// The signature does not match the URL.
+    #[display(fmt = "URL signature is invalid")]
+    Invalid,
+
+    /// The signature is valid, but the URL has expired.
+    #[display(fmt = "Signed URL has expired")]
+    Expired,
+}
+
+#[cfg(feature = "signed-urls")]
+impl ResponseError for SignedUrlError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::FORBIDDEN
+    }
+}
+
+/// Errors which can occur when parsing a [`ByteSize`](crate::web::ByteSize).
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum ParseByteSizeError {
+    /// The number is missing or malformed.
+    #[display(fmt = "Byte size has an invalid number")]
+    InvalidNumber,
+
+    /// The unit is not one of the supported units.
+    #[display(fmt = "Byte size has an unknown unit")]
+    UnknownUnit,
+
+    /// The size does not fit in a `usize`.
+    #[display(fmt = "Byte size is too large")]
+    Overflow,
+}
+
+/// Errors which can occur when constructing a byte [`ContentRange`](crate::http::header::ContentRange).
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum InvalidContentRange {
+    /// The first byte of the range is after its last byte.
+    #[display(
+        fmt = "Content range starts at byte {} but ends at byte {}",
+        start,
+        end
+    )]
+    StartAfterEnd { start: u64, end: u64 },
+
+    /// The last byte of the range is not within the complete length.
+    #[display(
+        fmt = "Content range ends at byte {} of a {}-byte representation",
+        end,
+        total
+    )]
+    EndOutOfBounds { end: u64, total: u64 },
+}
+
+/// Errors which can occur when encoding text using [`encode_text`](crate::web::encode_text).
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum EncodeTextError {
+    /// The charset is not one that text can be encoded in.
+    #[display(fmt = "Text can not be encoded in charset `{}`", charset)]
+    UnsupportedCharset {
+        #[error(not(source))]
+        charset: String,
+    },
+
+    /// A character has no representation in the charset.
+    #[display(fmt = "Character {:?} can not be encoded in charset `{}`", ch, charset)]
+    Unmappable {
+        #[error(not(source))]
+        ch: char,
+        charset: String,
+    },
+}
+
+/// Return `InternalServerError` for `EncodeTextError`.
+impl ResponseError for EncodeTextError {}
+
+/// Errors which can occur when resolving the [`ApiVersion`](crate::web::ApiVersion) of a request.
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum ApiVersionError {
+    /// None of the configured sources hold a version and no default is set.
+    #[display(fmt = "API version is missing")]
+    Missing,
+
+    /// A source holds a value that is not a version number.
+    #[display(fmt = "API version {:?} is not a version number", value)]
+    Invalid {
+        #[error(not(source))]
+        value: String,
+    },
+
+    /// The version is newer than the latest supported version.
+    #[display(
+        fmt = "API version {} is not supported; the latest version is {}",
+        version,
+        max
+    )]
+    Unsupported { version: u16, max: u16 },
+}
+
+/// Return `BadRequest` for `ApiVersionError`.
+impl ResponseError for ApiVersionError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+/// Errors which can occur when verifying a request body using the
+/// [`VerifyDigest`](crate::middleware::VerifyDigest) middleware.
+#[cfg(feature = "digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+#[derive(Debug, PartialEq, Eq, Display, Error)]
+#[non_exhaustive]
+pub enum ContentDigestError {
+    /// A digest header could not be parsed.
+    #[display(fmt = "Invalid `{}` header", name)]
+    Malformed {
+        #[error(not(source))]
+        name: header::HeaderName,
+    },
+
+    /// The body does not match the digest computed using the algorithm.
+    #[display(fmt = "Request body does not match its `{}` digest", algorithm)]
+    Mismatch {
+        #[error(not(source))]
+        algorithm: String,
+    },
+}
+
+/// Return `BadRequest` for `ContentDigestError`.
+#[cfg(feature = "digest")]
+impl ResponseError for ContentDigestError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
 /// A set of errors that can occur during parsing urlencoded payloads
 #[derive(Debug, Display, Error, From)]
 #[non_exhaustive]
@@ -70,9 +455,9 @@ pub enum UrlencodedError {
 
     /// Payload size is larger than allowed. (default limit: 256kB).
     #[display(
-        fmt = "URL encoded payload is larger ({} bytes) than allowed (limit: {} bytes).",
+        fmt = "URL encoded payload is larger ({} bytes) than allowed (limit: {}).",
         size,
-        limit
+        "ByteSize(*limit)"
     )]
     Overflow { size: usize, limit: usize },
 
@@ -118,14 +503,14 @@ impl ResponseError for UrlencodedError {
 pub enum JsonPayloadError {
     /// Payload size is bigger than allowed & content length header set. (default: 2MB)
     #[display(
-        fmt = "JSON payload ({} bytes) is larger than allowed (limit: {} bytes).",
+        fmt = "JSON payload ({} bytes) is larger than allowed (limit: {}).",
         length,
-        limit
+        "ByteSize(*limit)"
     )]
     OverflowKnownLength { length: usize, limit: usize },
 
     /// Payload size is bigger than allowed but no content length header set. (default: 2MB)
-    #[display(fmt = "JSON payload has exceeded limit ({} bytes).", limit)]
+    #[display(fmt = "JSON payload has exceeded limit ({}).", "ByteSize(*limit)")]
     Overflow { limit: usize },
 
     /// Content type error
@@ -189,6 +574,13 @@ pub enum QueryPayloadError {
     /// Query deserialize error.
     #[display(fmt = "Query deserialize error: {}", _0)]
     Deserialize(serde::de::value::Error),
+
+    /// Query string is not valid UTF-8 once percent-decoded.
+    ///
+    /// Only returned when the app's [`InvalidUtf8Policy`](crate::dev::InvalidUtf8Policy) is not
+    /// `Lossy`.
+    #[display(fmt = "Query string is not valid UTF-8")]
+    InvalidUtf8,
 }
 
 impl ResponseError for QueryPayloadError {
@@ -264,6 +656,39 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
     }
 
+    #[actix_rt::test]
+    async fn test_header_parse_error() {
+        use crate::{
+            http::header::{Header as _, MaxForwards},
+            test, web, App, HttpRequest,
+        };
+
+        async fn handler(req: HttpRequest) -> Result<String, HeaderParseError> {
+            let hops =
+                MaxForwards::parse(&req).map_err(|_| HeaderParseError::of::<MaxForwards>())?;
+            Ok(hops.to_string())
+        }
+
+        let srv = test::init_service(App::new().default_service(web::to(handler))).await;
+
+        let req = test::TestRequest::default()
+            .insert_header((header::MAX_FORWARDS, "many"))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let body = test::read_body(res).await;
+        assert_eq!(body, "Invalid `max-forwards` header");
+
+        let req = test::TestRequest::default()
+            .insert_header(MaxForwards(3))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let err = HeaderParseError::new(header::MAX_FORWARDS);
+        assert_eq!(err.header_name(), header::MAX_FORWARDS);
+    }
+
     #[test]
     fn test_readlines_error() {
         let resp = ReadlinesError::LimitOverflow.error_response();
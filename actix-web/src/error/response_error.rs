@@ -119,8 +119,13 @@ impl ResponseError for actix_http::error::ParseError {
 
 impl ResponseError for actix_http::error::PayloadError {
     fn status_code(&self) -> StatusCode {
+        use actix_http::error::PayloadError;
+
         match *self {
-            actix_http::error::PayloadError::Overflow => StatusCode::PAYLOAD_TOO_LARGE,
+            PayloadError::Overflow | PayloadError::LimitExceeded { .. } => {
+                StatusCode::PAYLOAD_TOO_LARGE
+            }
+            PayloadError::Timeout { .. } => StatusCode::REQUEST_TIMEOUT,
             _ => StatusCode::BAD_REQUEST,
         }
     }
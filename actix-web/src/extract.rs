@@ -62,6 +62,14 @@ use crate::{dev::Payload, Error, HttpRequest};
 /// [`Bytes`]: crate::web::Bytes#impl-FromRequest
 /// [`Either`]: crate::web::Either
 #[doc(alias = "extract", alias = "extractor")]
+#[rustversion::attr(
+    since(1.78),
+    diagnostic::on_unimplemented(
+        message = "`{Self}` cannot be used as a handler parameter",
+        label = "does not implement `FromRequest`",
+        note = "handler parameters must be extractors; see the `FromRequest` trait"
+    )
+)]
 pub trait FromRequest: Sized {
     /// The associated error which can be returned.
     type Error: Into<Error>;
@@ -416,6 +424,10 @@ mod tuple_from_req {
     tuple_from_req! { TupleFromRequest10; A, B, C, D, E, F, G, H, I, J }
     tuple_from_req! { TupleFromRequest11; A, B, C, D, E, F, G, H, I, J, K }
     tuple_from_req! { TupleFromRequest12; A, B, C, D, E, F, G, H, I, J, K, L }
+    tuple_from_req! { TupleFromRequest13; A, B, C, D, E, F, G, H, I, J, K, L, M }
+    tuple_from_req! { TupleFromRequest14; A, B, C, D, E, F, G, H, I, J, K, L, M, N }
+    tuple_from_req! { TupleFromRequest15; A, B, C, D, E, F, G, H, I, J, K, L, M, N, O }
+    tuple_from_req! { TupleFromRequest16; A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P }
 }
 
 #[cfg(test)]
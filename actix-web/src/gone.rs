@@ -0,0 +1,123 @@
+//! See [`Gone`] for service documentation.
+
+use std::{borrow::Cow, rc::Rc};
+
+use actix_utils::future::ready;
+
+use crate::{
+    dev::{fn_service, AppService, HttpServiceFactory, ResourceDef, ServiceRequest},
+    HttpResponse,
+};
+
+/// An HTTP service for paths whose content was permanently removed without a replacement.
+///
+/// Requests matching the path are answered with "410 Gone" and, optionally, a plain text body.
+/// Where content has moved rather than been removed, use [`Redirect`](crate::web::Redirect)
+/// instead.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App};
+///
+/// App::new()
+///     .service(web::Gone::from("/old-feature"))
+///     .service(
+///         web::scope("/api")
+///             .service(web::Gone::from("/v1/users").body("API v1 has been retired.")),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Gone {
+    from: Cow<'static, str>,
+    body: Option<Cow<'static, str>>,
+}
+
+impl Gone {
+    /// Create a new `Gone` service, matching requests for the path `from`.
+    ///
+    /// Paths are matched in the same way as [`web::resource`](crate::web::resource) paths.
+    pub fn from(from: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            from: from.into(),
+            body: None,
+        }
+    }
+
+    /// Respond with a plain text `body` instead of an empty one.
+    pub fn body(mut self, body: impl Into<Cow<'static, str>>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Builds the "410 Gone" response.
+    fn respond(&self) -> HttpResponse {
+        match &self.body {
+            Some(body) => HttpResponse::Gone()
+                .content_type(mime::TEXT_PLAIN_UTF_8)
+                .body(body.clone().into_owned()),
+
+            None => HttpResponse::Gone().finish(),
+        }
+    }
+}
+
+impl HttpServiceFactory for Gone {
+    fn register(self, config: &mut AppService) {
+        let rdef = ResourceDef::new(self.from.clone().into_owned());
+        let gone = Rc::new(self);
+
+        let gone_factory = fn_service(move |req: ServiceRequest| {
+            let res = gone.respond();
+            ready(Ok(req.into_response(res)))
+        });
+
+        config.register_service(rdef, None, gone_factory, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dev::Service,
+        http::{header, StatusCode},
+        test::{self, TestRequest},
+        web, App,
+    };
+
+    #[actix_rt::test]
+    async fn gone_without_body() {
+        let svc = test::init_service(App::new().service(Gone::from("/removed"))).await;
+
+        let req = TestRequest::default().uri("/removed").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::GONE);
+        assert!(res.headers().get(header::LOCATION).is_none());
+
+        let body = test::read_body(res).await;
+        assert!(body.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn gone_with_body() {
+        let svc = test::init_service(App::new().service(
+            web::scope("/api").service(Gone::from("/v1/{tail}*").body("v1 is retired")),
+        ))
+        .await;
+
+        let req = TestRequest::default().uri("/api/v1/users/42").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::GONE);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+
+        let body = test::read_body(res).await;
+        assert_eq!(body, "v1 is retired");
+
+        let req = TestRequest::default().uri("/api/v2/users").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -49,12 +49,18 @@
 use std::{
     cell::{Ref, RefMut},
     convert::TryFrom,
+    ops::{Bound, RangeBounds},
     rc::Rc,
 };
 
 use actix_http::{header, uri::Uri, Extensions, Method as HttpMethod, RequestHead};
 
-use crate::{http::header::Header, service::ServiceRequest, HttpMessage as _};
+use crate::{
+    http::header::Header,
+    service::ServiceRequest,
+    web::{ApiVersion as Version, ApiVersionConfig},
+    HttpMessage as _,
+};
 
 /// Provides access to request parts that are useful during routing.
 #[derive(Debug)]
@@ -288,6 +294,25 @@ impl Guard for MethodGuard {
     }
 }
 
+/// Guard matching a set of HTTP methods, or all methods except those in the set, using a single
+/// comparison against the list.
+pub(crate) struct MethodSetGuard {
+    methods: Vec<HttpMethod>,
+    exclude: bool,
+}
+
+impl MethodSetGuard {
+    pub(crate) fn new(methods: Vec<HttpMethod>, exclude: bool) -> Self {
+        Self { methods, exclude }
+    }
+}
+
+impl Guard for MethodSetGuard {
+    fn check(&self, ctx: &GuardContext<'_>) -> bool {
+        self.methods.contains(&ctx.head().method) != self.exclude
+    }
+}
+
 macro_rules! method_guard {
     ($method_fn:ident, $method_const:ident) => {
         #[doc = concat!("Creates a guard that matches the `", stringify!($method_const), "` request method.")]
@@ -468,6 +493,50 @@ impl Guard for HostGuard {
     }
 }
 
+/// Creates a guard that matches requests for an API version within `range`.
+///
+/// The version is resolved as by the [`ApiVersion`](crate::web::ApiVersion) extractor, using the
+/// [`ApiVersionConfig`] registered as app data. Requests whose version can not be resolved, such
+/// as unsupported versions, match no `ApiVersion` guard.
+///
+/// # Examples
+/// Requests for `/users` are handled by `users_v1` for API versions up to 1, and by `users_v2`
+/// for later versions.
+/// ```
+/// use actix_web::{guard, web, App, HttpResponse};
+///
+/// # async fn users_v1() -> HttpResponse { HttpResponse::Ok().finish() }
+/// # async fn users_v2() -> HttpResponse { HttpResponse::Ok().finish() }
+/// App::new()
+///     .app_data(web::ApiVersionConfig::new().header("x-api-version").default_version(1).max(3))
+///     .service(web::resource("/users").guard(guard::ApiVersion(..=1)).to(users_v1))
+///     .service(web::resource("/users").guard(guard::ApiVersion(2..)).to(users_v2));
+/// ```
+#[allow(non_snake_case)]
+pub fn ApiVersion(range: impl RangeBounds<u16>) -> ApiVersionGuard {
+    ApiVersionGuard {
+        start: range.start_bound().cloned(),
+        end: range.end_bound().cloned(),
+    }
+}
+
+#[doc(hidden)]
+pub struct ApiVersionGuard {
+    start: Bound<u16>,
+    end: Bound<u16>,
+}
+
+impl Guard for ApiVersionGuard {
+    fn check(&self, ctx: &GuardContext<'_>) -> bool {
+        let config = ctx.req.app_data::<ApiVersionConfig>();
+
+        match Version::resolve(config, ctx.head()) {
+            Ok(version) => (self.start, self.end).contains(&version),
+            Err(_) => false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use actix_http::{header, Method};
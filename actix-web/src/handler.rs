@@ -4,7 +4,7 @@ use actix_service::{boxed, fn_service};
 
 use crate::{
     service::{BoxedHttpServiceFactory, ServiceRequest, ServiceResponse},
-    FromRequest, HttpResponse, Responder,
+    types, FromRequest, HttpResponse, Responder,
 };
 
 /// The interface for request handlers.
@@ -15,22 +15,23 @@ use crate::{
 ///
 /// In particular, a request handler has three requirements:
 /// 1. It is an async function (or a function/closure that returns an appropriate future);
-/// 1. The function parameters (up to 12) implement [`FromRequest`];
+/// 1. The function parameters (up to 16) implement [`FromRequest`];
 /// 1. The async function (or future) resolves to a type that can be converted into an
 ///   [`HttpResponse`] (i.e., it implements the [`Responder`] trait).
 ///
 ///
 /// # Compiler Errors
-/// If you get the error `the trait Handler<_> is not implemented`, then your handler does not
-/// fulfill the _first_ of the above requirements. Missing other requirements manifest as errors on
-/// implementing [`FromRequest`] and [`Responder`], respectively.
+/// If you get the error ``... is not a valid request handler``, then your handler does not fulfill
+/// the _first_ of the above requirements, or it has too many parameters. Missing other
+/// requirements manifest as errors naming the parameter or return type that does not implement
+/// [`FromRequest`] or [`Responder`], respectively.
 ///
 /// # How Do Handlers Receive Variable Numbers Of Arguments
 /// Rest assured there is no macro magic here; it's just traits.
 ///
-/// The first thing to note is that [`FromRequest`] is implemented for tuples (up to 12 in length).
+/// The first thing to note is that [`FromRequest`] is implemented for tuples (up to 16 in length).
 ///
-/// Secondly, the `Handler` trait is implemented for functions (up to an [arity] of 12) in a way
+/// Secondly, the `Handler` trait is implemented for functions (up to an [arity] of 16) in a way
 /// that aligns their parameter positions with a corresponding tuple of types (becoming the `Args`
 /// type parameter for this trait).
 ///
@@ -79,6 +80,14 @@ use crate::{
 ///
 /// [arity]: https://en.wikipedia.org/wiki/Arity
 /// [`from_request`]: FromRequest::from_request
+#[rustversion::attr(
+    since(1.78),
+    diagnostic::on_unimplemented(
+        message = "`{Self}` is not a valid request handler",
+        label = "not a valid request handler",
+        note = "handlers are async functions, or closures returning futures, with at most 16 parameters"
+    )
+)]
 pub trait Handler<Args>: Clone + 'static {
     type Output;
     type Future: Future<Output = Self::Output>;
@@ -101,11 +110,15 @@ where
             let res = match Args::from_request(&req, &mut payload).await {
                 Err(err) => HttpResponse::from_error(err),
 
-                Ok(data) => handler
-                    .call(data)
-                    .await
-                    .respond_to(&req)
-                    .map_into_boxed_body(),
+                Ok(data) => {
+                    let res = handler
+                        .call(data)
+                        .await
+                        .respond_to(&req)
+                        .map_into_boxed_body();
+
+                    types::apply_json_serializer(&req, res)
+                }
             };
 
             Ok(ServiceResponse::new(req, res))
@@ -151,6 +164,10 @@ factory_tuple! { A B C D E F G H I }
 factory_tuple! { A B C D E F G H I J }
 factory_tuple! { A B C D E F G H I J K }
 factory_tuple! { A B C D E F G H I J K L }
+factory_tuple! { A B C D E F G H I J K L M }
+factory_tuple! { A B C D E F G H I J K L M N }
+factory_tuple! { A B C D E F G H I J K L M N O }
+factory_tuple! { A B C D E F G H I J K L M N O P }
 
 #[cfg(test)]
 mod tests {
@@ -165,11 +182,69 @@ mod tests {
         #[rustfmt::skip]
         #[allow(clippy::too_many_arguments, clippy::just_underscores_and_digits)]
         async fn handler_max(
-            _01: (), _02: (), _03: (), _04: (), _05: (), _06: (),
-            _07: (), _08: (), _09: (), _10: (), _11: (), _12: (),
+            _01: (), _02: (), _03: (), _04: (), _05: (), _06: (), _07: (), _08: (),
+            _09: (), _10: (), _11: (), _12: (), _13: (), _14: (), _15: (), _16: (),
         ) {}
 
         assert_impl_handler(handler_min);
         assert_impl_handler(handler_max);
     }
+
+    #[actix_rt::test]
+    async fn many_extractors() {
+        use crate::{
+            http::Method,
+            test::{call_and_read_body, init_service, TestRequest},
+            web, App, HttpRequest,
+        };
+
+        #[allow(clippy::too_many_arguments)]
+        async fn handler(
+            path: web::Path<(u32, String)>,
+            query: web::Query<std::collections::HashMap<String, String>>,
+            method: Method,
+            req: HttpRequest,
+            data: web::Data<u8>,
+            body: String,
+            _: (),
+            _: Option<web::Json<u8>>,
+            _: web::Header<crate::http::header::ContentType>,
+            _: crate::dev::ConnectionInfo,
+            _: crate::dev::PeerAddr,
+            _: web::ReqData<bool>,
+            _: web::Payload,
+            _: Result<web::Form<u8>, crate::Error>,
+        ) -> String {
+            format!(
+                "{} {} {} {} {} {} {}",
+                path.0,
+                path.1,
+                query["q"],
+                method,
+                req.path(),
+                data.get_ref(),
+                body
+            )
+        }
+
+        let srv = init_service(
+            App::new()
+                .app_data(web::Data::new(7u8))
+                .wrap_fn(|req, srv| {
+                    crate::HttpMessage::extensions_mut(&req).insert(true);
+                    actix_service::Service::call(srv, req)
+                })
+                .route("/{id}/{name}", web::post().to(handler)),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .uri("/1/foo?q=bar")
+            .insert_header(crate::http::header::ContentType::plaintext())
+            .peer_addr("127.0.0.1:8080".parse().unwrap())
+            .set_payload("body")
+            .to_request();
+        let body = call_and_read_body(&srv, req).await;
+        assert_eq!(body, "1 foo bar POST /1/foo 7 body");
+    }
 }
@@ -0,0 +1,387 @@
+//! For health endpoint documentation, see [`health_scope`](crate::web::health_scope).
+
+use std::{borrow::Cow, fmt, future::Future, sync::Arc, time::Duration};
+
+use actix_rt::time::{timeout, Instant};
+use futures_core::future::LocalBoxFuture;
+use futures_util::future::join_all;
+use serde::Serialize;
+
+use crate::{
+    guard,
+    http::header::{CacheControl, CacheDirective},
+    web, HttpRequest, HttpResponse, Scope,
+};
+
+type CheckFn = dyn Fn() -> LocalBoxFuture<'static, Result<(), String>> + Send + Sync;
+
+/// Registry of readiness checks, consulted by the `/readyz` route of
+/// [`health_scope`](crate::web::health_scope).
+///
+/// Register it as app data, either directly or wrapped in [`Data`](crate::web::Data). Each check
+/// is a named async closure with its own timeout. A check fails if it returns an error, panics, or
+/// does not complete within its timeout.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use actix_web::{web, App};
+///
+/// let checks = web::ReadinessChecks::new()
+///     .check("database", Duration::from_secs(1), || async {
+///         // e.g., run `SELECT 1` using a pool cloned into the closure
+///         Ok::<_, String>(())
+///     })
+///     .check("cache", Duration::from_millis(200), || async { Ok::<_, String>(()) });
+///
+/// App::new().app_data(checks).service(web::health_scope());
+/// ```
+#[derive(Clone, Default)]
+pub struct ReadinessChecks {
+    checks: Vec<Check>,
+}
+
+#[derive(Clone)]
+struct Check {
+    name: Cow<'static, str>,
+    timeout: Duration,
+    check: Arc<CheckFn>,
+}
+
+impl ReadinessChecks {
+    /// Constructs a registry without checks.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the check `name`, which fails unless `check` resolves to `Ok` within `timeout`.
+    ///
+    /// Errors are included in the readiness report using their `Display` implementation.
+    pub fn check<F, Fut, E>(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        timeout: Duration,
+        check: F,
+    ) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + 'static,
+        E: fmt::Display,
+    {
+        self.checks.push(Check {
+            name: name.into(),
+            timeout,
+            check: Arc::new(move || {
+                let fut = check();
+                Box::pin(async move { fut.await.map_err(|err| err.to_string()) })
+            }),
+        });
+
+        self
+    }
+
+    /// Extracts the registry from app data. Checks both `T` and `Data<T>`, in that order.
+    fn from_req(req: &HttpRequest) -> Option<&Self> {
+        req.app_data::<Self>()
+            .or_else(|| req.app_data::<web::Data<Self>>().map(|d| d.as_ref()))
+    }
+}
+
+impl fmt::Debug for ReadinessChecks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.checks.iter().map(|check| (&check.name, check.timeout)))
+            .finish()
+    }
+}
+
+impl Check {
+    /// Runs the check in its own task, so that a panic only fails this check.
+    async fn run(&self) -> CheckReport<'_> {
+        let start = Instant::now();
+        let mut handle = actix_rt::spawn((self.check)());
+
+        let (status, error) = match timeout(self.timeout, &mut handle).await {
+            Ok(Ok(Ok(()))) => (CheckStatus::Ok, None),
+            Ok(Ok(Err(err))) => (CheckStatus::Failed, Some(err)),
+            Ok(Err(_)) => (CheckStatus::Failed, Some("check panicked".to_owned())),
+            Err(_) => {
+                handle.abort();
+                (CheckStatus::TimedOut, None)
+            }
+        };
+
+        CheckReport {
+            name: &self.name,
+            status,
+            latency_ms: start.elapsed().as_millis() as u64,
+            error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum CheckStatus {
+    Ok,
+    Failed,
+    TimedOut,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckReport<'a> {
+    name: &'a str,
+    status: CheckStatus,
+    latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReadinessReport<'a> {
+    status: &'static str,
+    checks: Vec<CheckReport<'a>>,
+}
+
+/// Creates a scope serving `/healthz` liveness and `/readyz` readiness endpoints.
+///
+/// - `GET /healthz` always responds with "200 OK" while the server is able to handle requests.
+/// - `GET /readyz` runs the [`ReadinessChecks`] registered as app data concurrently and responds
+///   with "200 OK" if all of them pass, or "503 Service Unavailable" otherwise. Without registered
+///   checks, the server is considered ready.
+///
+/// Both respond with a JSON body that is not cached. For `/readyz`, it lists the status and
+/// latency of each check, in registration order:
+///
+/// ```json
+/// {
+///   "status": "unavailable",
+///   "checks": [
+///     { "name": "database", "status": "ok", "latency_ms": 3 },
+///     { "name": "cache", "status": "failed", "latency_ms": 1, "error": "connection refused" },
+///     { "name": "search", "status": "timed_out", "latency_ms": 200 }
+///   ]
+/// }
+/// ```
+///
+/// The scope only matches requests for its two routes, so it can be registered alongside other
+/// services, including inside another scope.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App};
+///
+/// App::new()
+///     .app_data(web::ReadinessChecks::new())
+///     .service(web::health_scope())
+///     .service(web::resource("/").to(|| async { "Hello!" }));
+/// ```
+pub fn health_scope() -> Scope {
+    web::scope("")
+        .guard(guard::fn_guard(|ctx| {
+            let path = ctx.head().uri.path();
+            path.ends_with("/healthz") || path.ends_with("/readyz")
+        }))
+        .service(web::resource("/healthz").route(web::get().to(liveness)))
+        .service(web::resource("/readyz").route(web::get().to(readiness)))
+}
+
+async fn liveness() -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+        .json(serde_json::json!({ "status": "ok" }))
+}
+
+async fn readiness(req: HttpRequest) -> HttpResponse {
+    let checks = match ReadinessChecks::from_req(&req) {
+        Some(checks) => join_all(checks.checks.iter().map(Check::run)).await,
+        None => Vec::new(),
+    };
+
+    let ready = checks.iter().all(|check| check.status == CheckStatus::Ok);
+
+    let mut res = if ready {
+        HttpResponse::Ok()
+    } else {
+        HttpResponse::ServiceUnavailable()
+    };
+
+    res.insert_header(CacheControl(vec![CacheDirective::NoStore]))
+        .json(ReadinessReport {
+            status: if ready { "ok" } else { "unavailable" },
+            checks,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        http::StatusCode,
+        test::{call_service, init_service, read_body_json, TestRequest},
+        App,
+    };
+
+    fn checks(calls: Arc<AtomicUsize>) -> ReadinessChecks {
+        let passing = Arc::clone(&calls);
+        let failing = Arc::clone(&calls);
+        let slow = Arc::clone(&calls);
+
+        ReadinessChecks::new()
+            .check("database", Duration::from_secs(1), move || {
+                passing.fetch_add(1, Ordering::SeqCst);
+                async { Ok::<_, String>(()) }
+            })
+            .check("cache", Duration::from_secs(1), move || {
+                failing.fetch_add(1, Ordering::SeqCst);
+                async { Err("connection refused") }
+            })
+            .check("search", Duration::from_millis(50), move || {
+                slow.fetch_add(1, Ordering::SeqCst);
+
+                async {
+                    actix_rt::time::sleep(Duration::from_secs(10)).await;
+                    Ok::<_, String>(())
+                }
+            })
+    }
+
+    /// Removes latencies from a readiness report, since they vary between runs.
+    fn without_latencies(mut report: Value) -> Value {
+        for check in report["checks"].as_array_mut().unwrap() {
+            assert!(check["latency_ms"].is_u64());
+            check.as_object_mut().unwrap().remove("latency_ms");
+        }
+
+        report
+    }
+
+    #[actix_rt::test]
+    async fn readiness_aggregates_checks() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let srv = init_service(
+            App::new()
+                .app_data(checks(Arc::clone(&calls)))
+                .service(health_scope())
+                .route("/", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let start = Instant::now();
+        let req = TestRequest::get().uri("/readyz").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(res.headers().get("cache-control").unwrap(), "no-store");
+
+        let report: Value = read_body_json(res).await;
+        assert_eq!(
+            without_latencies(report),
+            json!({
+                "status": "unavailable",
+                "checks": [
+                    { "name": "database", "status": "ok" },
+                    { "name": "cache", "status": "failed", "error": "connection refused" },
+                    { "name": "search", "status": "timed_out" },
+                ],
+            })
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        // other services are not shadowed by the scope
+        let req = TestRequest::get().uri("/").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn readiness_passes() {
+        let checks = ReadinessChecks::new()
+            .check("a", Duration::from_secs(1), || async {
+                Ok::<_, String>(())
+            })
+            .check("b", Duration::from_secs(1), || async {
+                Ok::<_, String>(())
+            });
+
+        let srv = init_service(
+            App::new()
+                .app_data(web::Data::new(checks))
+                .service(web::scope("/internal").service(health_scope())),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/internal/readyz").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let report: Value = read_body_json(res).await;
+        assert_eq!(report["status"], "ok");
+        assert_eq!(report["checks"].as_array().unwrap().len(), 2);
+
+        // without registered checks, the server is ready
+        let srv = init_service(App::new().service(health_scope())).await;
+        let req = TestRequest::get().uri("/readyz").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn panicking_check_fails() {
+        async fn explode() -> Result<(), String> {
+            panic!("check exploded")
+        }
+
+        let checks = ReadinessChecks::new()
+            .check("ok", Duration::from_secs(1), || async {
+                Ok::<_, String>(())
+            })
+            .check("panics", Duration::from_secs(1), explode);
+
+        let srv = init_service(App::new().app_data(checks).service(health_scope())).await;
+
+        let req = TestRequest::get().uri("/readyz").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let report: Value = read_body_json(res).await;
+        assert_eq!(
+            without_latencies(report),
+            json!({
+                "status": "unavailable",
+                "checks": [
+                    { "name": "ok", "status": "ok" },
+                    { "name": "panics", "status": "failed", "error": "check panicked" },
+                ],
+            })
+        );
+    }
+
+    #[actix_rt::test]
+    async fn liveness_ignores_checks() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let srv = init_service(
+            App::new()
+                .app_data(checks(Arc::clone(&calls)))
+                .service(health_scope()),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/healthz").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get("cache-control").unwrap(), "no-store");
+
+        let body: Value = read_body_json(res).await;
+        assert_eq!(body, json!({ "status": "ok" }));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let req = TestRequest::post().uri("/healthz").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+}
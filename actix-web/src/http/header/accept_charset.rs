@@ -1,4 +1,6 @@
-use super::{common_header, Charset, QualityItem, ACCEPT_CHARSET};
+use std::cmp::Reverse;
+
+use super::{common_header, Charset, Preference, Quality, QualityItem, ACCEPT_CHARSET};
 
 common_header! {
     /// `Accept-Charset` header, defined in [RFC 7231 §5.3.3].
@@ -17,46 +19,203 @@ common_header! {
     ///
     /// # Example Values
     /// * `iso-8859-5, unicode-1-1;q=0.8`
+    /// * `windows-1252, *;q=0.1`
     ///
     /// # Examples
     /// ```
     /// use actix_web::HttpResponse;
-    /// use actix_web::http::header::{AcceptCharset, Charset, QualityItem};
+    /// use actix_web::http::header::{AcceptCharset, Charset, Preference, QualityItem};
     ///
     /// let mut builder = HttpResponse::Ok();
     /// builder.insert_header(
-    ///     AcceptCharset(vec![QualityItem::max(Charset::Us_Ascii)])
+    ///     AcceptCharset(vec![QualityItem::max(Preference::Specific(Charset::Us_Ascii))])
     /// );
     /// ```
     ///
     /// ```
     /// use actix_web::HttpResponse;
-    /// use actix_web::http::header::{AcceptCharset, Charset, q, QualityItem};
+    /// use actix_web::http::header::{AcceptCharset, Charset, q, Preference, QualityItem};
     ///
     /// let mut builder = HttpResponse::Ok();
     /// builder.insert_header(
     ///     AcceptCharset(vec![
-    ///         QualityItem::new(Charset::Us_Ascii, q(0.9)),
-    ///         QualityItem::new(Charset::Iso_8859_10, q(0.2)),
+    ///         QualityItem::new(Preference::Specific(Charset::Us_Ascii), q(0.9)),
+    ///         QualityItem::new(Preference::Specific(Charset::Iso_8859_10), q(0.2)),
     ///     ])
     /// );
     /// ```
     ///
     /// ```
     /// use actix_web::HttpResponse;
-    /// use actix_web::http::header::{AcceptCharset, Charset, QualityItem};
+    /// use actix_web::http::header::AcceptCharset;
     ///
     /// let mut builder = HttpResponse::Ok();
     /// builder.insert_header(
-    ///     AcceptCharset(vec![QualityItem::max(Charset::Ext("utf-8".to_owned()))])
+    ///     AcceptCharset(vec!["utf-8".parse().unwrap(), "*;q=0.5".parse().unwrap()])
     /// );
     /// ```
     ///
     /// [RFC 7231 §5.3.3]: https://datatracker.ietf.org/doc/html/rfc7231#section-5.3.3
-    (AcceptCharset, ACCEPT_CHARSET) => (QualityItem<Charset>)*
+    (AcceptCharset, ACCEPT_CHARSET) => (QualityItem<Preference<Charset>>)*
 
     test_parse_and_format {
         // Test case from RFC
         common_header_test!(test1, vec![b"iso-8859-5, unicode-1-1;q=0.8"]);
+
+        common_header_test!(
+            any,
+            vec![b"WINDOWS-1252, *; q=0.1"],
+            Some(AcceptCharset(vec![
+                QualityItem::max(Preference::Specific(Charset::Ext("WINDOWS-1252".to_owned()))),
+                QualityItem::new(Preference::Any, crate::http::header::q(0.1)),
+            ]))
+        );
+    }
+}
+
+impl AcceptCharset {
+    /// Selects the most acceptable charset according to client preference and supported charsets.
+    ///
+    /// Charsets are compared case-insensitively. A `*` item makes every supported charset that is
+    /// not listed acceptable, at its quality. If the header is empty, the first supported charset
+    /// is selected.
+    ///
+    /// If `None` is returned, none of the supported charsets are acceptable to the client. The
+    /// caller may respond with "406 Not Acceptable" or disregard the header and use a default.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::http::header::{AcceptCharset, Charset};
+    ///
+    /// let accept = AcceptCharset(vec!["windows-1252".parse().unwrap()]);
+    /// let utf8 = Charset::Ext("utf-8".to_owned());
+    /// let windows_1252 = Charset::Ext("windows-1252".to_owned());
+    ///
+    /// assert_eq!(accept.negotiate([&utf8, &windows_1252]), Some(windows_1252));
+    /// ```
+    pub fn negotiate<'a>(
+        &self,
+        supported: impl IntoIterator<Item = &'a Charset>,
+    ) -> Option<Charset> {
+        let supported = supported.into_iter().collect::<Vec<_>>();
+
+        if self.0.is_empty() {
+            return supported.first().map(|&charset| charset.clone());
+        }
+
+        let is_listed = |charset: &Charset| {
+            self.0.iter().any(|item| match &item.item {
+                Preference::Specific(listed) => same_charset(listed, charset),
+                Preference::Any => false,
+            })
+        };
+
+        self.ranked_items()
+            .into_iter()
+            .filter(|item| item.quality > Quality::ZERO)
+            // search relies on item list being in descending order of quality
+            .find_map(|item| match item.item {
+                Preference::Specific(listed) => supported
+                    .iter()
+                    .find(|&&charset| same_charset(&listed, charset))
+                    .map(|&charset| charset.clone()),
+
+                Preference::Any => supported
+                    .iter()
+                    .find(|&&charset| !is_listed(charset))
+                    .map(|&charset| charset.clone()),
+            })
+    }
+
+    /// Returns a sorted list of charsets from highest to lowest precedence, accounting
+    /// for [q-factor weighting].
+    ///
+    /// [q-factor weighting]: https://datatracker.ietf.org/doc/html/rfc7231#section-5.3.2
+    pub fn ranked(&self) -> Vec<Preference<Charset>> {
+        self.ranked_items()
+            .into_iter()
+            .map(|item| item.item)
+            .collect()
+    }
+
+    fn ranked_items(&self) -> Vec<QualityItem<Preference<Charset>>> {
+        let mut items = self.0.clone();
+
+        // use stable sort so items with equal q-factor retain listed order
+        items.sort_by_key(|item| Reverse(item.quality));
+
+        items
+    }
+}
+
+/// Returns true if both charsets have the same label, ignoring case.
+fn same_charset(a: &Charset, b: &Charset) -> bool {
+    a.to_string().eq_ignore_ascii_case(&b.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::header::q;
+
+    fn charset(label: &str) -> Charset {
+        label.parse().unwrap()
+    }
+
+    fn accept(items: &[&str]) -> AcceptCharset {
+        AcceptCharset(items.iter().map(|item| item.parse().unwrap()).collect())
+    }
+
+    #[test]
+    fn negotiation() {
+        let utf8 = charset("utf-8");
+        let windows_1252 = Charset::Ext("windows-1252".to_owned());
+        let supported = [&utf8, &windows_1252];
+
+        assert_eq!(accept(&[]).negotiate(supported), Some(utf8.clone()));
+        assert_eq!(accept(&[]).negotiate([]), None);
+
+        assert_eq!(
+            accept(&["windows-1252"]).negotiate(supported),
+            Some(windows_1252.clone())
+        );
+        assert_eq!(
+            accept(&["Windows-1252;q=0.5", "utf-8"]).negotiate(supported),
+            Some(utf8.clone())
+        );
+        assert_eq!(accept(&["iso-8859-5"]).negotiate(supported), None);
+
+        // "*" matches charsets that are not listed
+        assert_eq!(
+            accept(&["iso-8859-5", "*;q=0.1"]).negotiate(supported),
+            Some(utf8.clone())
+        );
+        assert_eq!(
+            accept(&["utf-8;q=0", "*"]).negotiate(supported),
+            Some(windows_1252.clone())
+        );
+        assert_eq!(accept(&["*;q=0"]).negotiate(supported), None);
+        assert_eq!(
+            accept(&["utf-8;q=0", "windows-1252;q=0", "*"]).negotiate(supported),
+            None
+        );
+    }
+
+    #[test]
+    fn ranking() {
+        let header = AcceptCharset(vec![
+            QualityItem::new(Preference::Any, q(0.1)),
+            QualityItem::new(Preference::Specific(Charset::Iso_8859_1), q(0.5)),
+            QualityItem::max(Preference::Specific(charset("utf-8"))),
+        ]);
+
+        assert_eq!(
+            header.ranked(),
+            vec![
+                Preference::Specific(charset("utf-8")),
+                Preference::Specific(Charset::Iso_8859_1),
+                Preference::Any,
+            ]
+        );
     }
 }
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use super::{common_header, ContentEncoding, Encoding, Preference, Quality, QualityItem};
+use super::{common_header, Encoding, Preference, Quality, QualityItem};
 use crate::http::header;
 
 common_header! {
@@ -190,8 +190,7 @@ impl AcceptEncoding {
             match self.0.iter().find(|pref| {
                 matches!(
                     pref.item,
-                    Preference::Any
-                        | Preference::Specific(Encoding::Known(ContentEncoding::Identity))
+                    Preference::Any | Preference::Specific(Encoding::Identity)
                 )
             }) {
                 // "identity" or "*" found so no representation is acceptable
@@ -241,9 +240,7 @@ fn is_identity_acceptable(items: &'_ [QualityItem<Preference<Encoding>>]) -> boo
     for q in items {
         match (q.quality, &q.item) {
             // occurrence of "identity;q=n"; return true if quality is non-zero
-            (q, Preference::Specific(Encoding::Known(ContentEncoding::Identity))) => {
-                return q > Quality::ZERO
-            }
+            (q, Preference::Specific(Encoding::Identity)) => return q > Quality::ZERO,
 
             // occurrence of "*;q=n"; return true if quality is non-zero
             (q, Preference::Any) => return q > Quality::ZERO,
@@ -0,0 +1,126 @@
+use std::{fmt, str};
+
+use super::{
+    from_one_raw_str, Header, HeaderName, HeaderValue, InvalidHeaderValue, TryIntoHeaderValue,
+    ACCEPT_RANGES,
+};
+use crate::{error::ParseError, HttpMessage};
+
+/// `Accept-Ranges` header, defined
+/// in [RFC 7233 §2.3](https://datatracker.ietf.org/doc/html/rfc7233#section-2.3)
+///
+/// The `Accept-Ranges` header field allows a server to indicate that it supports range requests
+/// for the target resource, or, using `none`, that it does not.
+///
+/// # ABNF
+/// ```plain
+/// Accept-Ranges     = acceptable-ranges
+/// acceptable-ranges = 1#range-unit / "none"
+/// ```
+///
+/// # Example Values
+/// * `bytes`
+/// * `none`
+/// * `unknown-unit`
+///
+/// # Examples
+/// ```
+/// use actix_web::HttpResponse;
+/// use actix_web::http::header::AcceptRanges;
+///
+/// let mut builder = HttpResponse::Ok();
+/// builder.insert_header(AcceptRanges::None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AcceptRanges {
+    /// No range requests are supported.
+    None,
+
+    /// Byte ranges are supported.
+    Bytes,
+
+    /// Ranges using a unit not registered at IANA are supported.
+    Unregistered(String),
+}
+
+impl fmt::Display for AcceptRanges {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AcceptRanges::None => f.write_str("none"),
+            AcceptRanges::Bytes => f.write_str("bytes"),
+            AcceptRanges::Unregistered(unit) => f.write_str(unit),
+        }
+    }
+}
+
+impl str::FromStr for AcceptRanges {
+    type Err = ParseError;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let val = val.trim();
+
+        if val.eq_ignore_ascii_case("none") {
+            Ok(AcceptRanges::None)
+        } else if val.eq_ignore_ascii_case("bytes") {
+            Ok(AcceptRanges::Bytes)
+        } else if !val.is_empty() {
+            Ok(AcceptRanges::Unregistered(val.to_owned()))
+        } else {
+            Err(ParseError::Header)
+        }
+    }
+}
+
+impl TryIntoHeaderValue for AcceptRanges {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        match self {
+            AcceptRanges::None => Ok(HeaderValue::from_static("none")),
+            AcceptRanges::Bytes => Ok(HeaderValue::from_static("bytes")),
+            AcceptRanges::Unregistered(unit) => HeaderValue::from_str(&unit),
+        }
+    }
+}
+
+impl Header for AcceptRanges {
+    fn name() -> HeaderName {
+        ACCEPT_RANGES
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        from_one_raw_str(msg.headers().get(Self::name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    #[test]
+    fn parse_and_format() {
+        for (raw, parsed) in [
+            ("none", AcceptRanges::None),
+            ("bytes", AcceptRanges::Bytes),
+            ("letters", AcceptRanges::Unregistered("letters".to_owned())),
+        ] {
+            let req = TestRequest::default()
+                .insert_header((ACCEPT_RANGES, raw))
+                .to_http_request();
+            assert_eq!(AcceptRanges::parse(&req).unwrap(), parsed);
+            assert_eq!(parsed.to_string(), raw);
+            assert_eq!(parsed.try_into_value().unwrap(), raw);
+        }
+
+        let req = TestRequest::default()
+            .insert_header((ACCEPT_RANGES, "Bytes"))
+            .to_http_request();
+        assert_eq!(AcceptRanges::parse(&req).unwrap(), AcceptRanges::Bytes);
+
+        let req = TestRequest::default()
+            .insert_header((ACCEPT_RANGES, ""))
+            .to_http_request();
+        assert!(AcceptRanges::parse(&req).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! Typed headers used by [Cross-Origin Resource Sharing (CORS)][fetch].
+//!
+//! [fetch]: https://fetch.spec.whatwg.org/#http-cors-protocol
+
+use std::{convert::Infallible, fmt};
+
+use actix_http::Method;
+
+use super::{
+    common_header, from_one_raw_str, Header, HeaderName, HeaderValue, InvalidHeaderValue,
+    TryIntoHeaderValue, ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_HEADERS,
+    ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN, ACCESS_CONTROL_EXPOSE_HEADERS,
+    ACCESS_CONTROL_MAX_AGE, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD,
+};
+use crate::{error::ParseError, HttpMessage};
+
+common_header! {
+    /// `Access-Control-Allow-Origin` response header, defined in the [Fetch Standard].
+    ///
+    /// Indicates whether the response can be shared with requesting code from the given origin.
+    /// The value is either `*`, a serialized origin such as `https://example.com`, or `null`.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::AccessControlAllowOrigin;
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// builder.insert_header(AccessControlAllowOrigin("https://example.com".to_owned()));
+    /// ```
+    ///
+    /// [Fetch Standard]: https://fetch.spec.whatwg.org/#http-access-control-allow-origin
+    (AccessControlAllowOrigin, ACCESS_CONTROL_ALLOW_ORIGIN) => [String]
+
+    test_allow_origin {
+        common_header_test!(test1, vec![b"*"], Some(AccessControlAllowOrigin("*".to_owned())));
+        common_header_test!(
+            test2,
+            vec![b"https://example.com"],
+            Some(AccessControlAllowOrigin("https://example.com".to_owned()))
+        );
+        common_header_test!(test3, vec![b""], None);
+    }
+}
+
+/// `Access-Control-Allow-Credentials` response header, defined in the [Fetch Standard].
+///
+/// Indicates that the response can be shared when the request includes credentials. The only
+/// valid value is `true`; the header is omitted otherwise.
+///
+/// # Examples
+/// ```
+/// use actix_web::HttpResponse;
+/// use actix_web::http::header::AccessControlAllowCredentials;
+///
+/// let mut builder = HttpResponse::Ok();
+/// builder.insert_header(AccessControlAllowCredentials);
+/// ```
+///
+/// [Fetch Standard]: https://fetch.spec.whatwg.org/#http-access-control-allow-credentials
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccessControlAllowCredentials;
+
+impl Header for AccessControlAllowCredentials {
+    fn name() -> HeaderName {
+        ACCESS_CONTROL_ALLOW_CREDENTIALS
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        match msg.headers().get(Self::name()) {
+            Some(val) if val == "true" => Ok(Self),
+            _ => Err(ParseError::Header),
+        }
+    }
+}
+
+impl fmt::Display for AccessControlAllowCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("true")
+    }
+}
+
+impl TryIntoHeaderValue for AccessControlAllowCredentials {
+    type Error = Infallible;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        Ok(HeaderValue::from_static("true"))
+    }
+}
+
+common_header! {
+    /// `Access-Control-Allow-Methods` response header, defined in the [Fetch Standard].
+    ///
+    /// Sent in response to a preflight request to list the methods allowed for the actual request.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::{header::AccessControlAllowMethods, Method};
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// builder.insert_header(AccessControlAllowMethods(vec![Method::GET, Method::PUT]));
+    /// ```
+    ///
+    /// [Fetch Standard]: https://fetch.spec.whatwg.org/#http-access-control-allow-methods
+    (AccessControlAllowMethods, ACCESS_CONTROL_ALLOW_METHODS) => (Method)*
+
+    test_allow_methods {
+        common_header_test!(
+            test1,
+            vec![b"GET, PUT"],
+            Some(AccessControlAllowMethods(vec![Method::GET, Method::PUT]))
+        );
+    }
+}
+
+common_header! {
+    /// `Access-Control-Allow-Headers` response header, defined in the [Fetch Standard].
+    ///
+    /// Sent in response to a preflight request to list the request headers allowed for the actual
+    /// request.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::{AccessControlAllowHeaders, CONTENT_TYPE};
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// builder.insert_header(AccessControlAllowHeaders(vec![CONTENT_TYPE]));
+    /// ```
+    ///
+    /// [Fetch Standard]: https://fetch.spec.whatwg.org/#http-access-control-allow-headers
+    (AccessControlAllowHeaders, ACCESS_CONTROL_ALLOW_HEADERS) => (HeaderName)*
+
+    test_allow_headers {
+        common_header_test!(
+            test1,
+            vec![b"content-type, x-requested-with"],
+            Some(AccessControlAllowHeaders(vec![
+                header::CONTENT_TYPE,
+                HeaderName::from_static("x-requested-with"),
+            ]))
+        );
+    }
+}
+
+common_header! {
+    /// `Access-Control-Expose-Headers` response header, defined in the [Fetch Standard].
+    ///
+    /// Lists the response headers, beyond the CORS-safelisted ones, that requesting code may read.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::{AccessControlExposeHeaders, ETAG};
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// builder.insert_header(AccessControlExposeHeaders(vec![ETAG]));
+    /// ```
+    ///
+    /// [Fetch Standard]: https://fetch.spec.whatwg.org/#http-access-control-expose-headers
+    (AccessControlExposeHeaders, ACCESS_CONTROL_EXPOSE_HEADERS) => (HeaderName)*
+
+    test_expose_headers {
+        common_header_test!(
+            test1,
+            vec![b"etag"],
+            Some(AccessControlExposeHeaders(vec![header::ETAG]))
+        );
+    }
+}
+
+common_header! {
+    /// `Access-Control-Max-Age` response header, defined in the [Fetch Standard].
+    ///
+    /// The number of seconds the result of a preflight request can be cached for.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::AccessControlMaxAge;
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// builder.insert_header(AccessControlMaxAge(3600));
+    /// ```
+    ///
+    /// [Fetch Standard]: https://fetch.spec.whatwg.org/#http-access-control-max-age
+    (AccessControlMaxAge, ACCESS_CONTROL_MAX_AGE) => [u32]
+
+    test_max_age {
+        common_header_test!(test1, vec![b"600"], Some(AccessControlMaxAge(600)));
+        common_header_test!(test2, vec![b"-1"], None);
+    }
+}
+
+/// `Access-Control-Request-Method` request header, defined in the [Fetch Standard].
+///
+/// Sent with a preflight request to indicate the method of the actual request.
+///
+/// [Fetch Standard]: https://fetch.spec.whatwg.org/#http-access-control-request-method
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessControlRequestMethod(pub Method);
+
+impl Header for AccessControlRequestMethod {
+    fn name() -> HeaderName {
+        ACCESS_CONTROL_REQUEST_METHOD
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        from_one_raw_str(msg.headers().get(Self::name())).map(Self)
+    }
+}
+
+impl fmt::Display for AccessControlRequestMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.as_str())
+    }
+}
+
+impl TryIntoHeaderValue for AccessControlRequestMethod {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        HeaderValue::from_str(self.0.as_str())
+    }
+}
+
+common_header! {
+    /// `Access-Control-Request-Headers` request header, defined in the [Fetch Standard].
+    ///
+    /// Sent with a preflight request to list the headers, beyond the CORS-safelisted ones, that
+    /// the actual request will include.
+    ///
+    /// [Fetch Standard]: https://fetch.spec.whatwg.org/#http-access-control-request-headers
+    (AccessControlRequestHeaders, ACCESS_CONTROL_REQUEST_HEADERS) => (HeaderName)*
+
+    test_request_headers {
+        common_header_test!(
+            test1,
+            vec![b"content-type, x-api-key"],
+            Some(AccessControlRequestHeaders(vec![
+                header::CONTENT_TYPE,
+                HeaderName::from_static("x-api-key"),
+            ]))
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_http::test::TestRequest;
+
+    use super::*;
+
+    #[test]
+    fn allow_credentials() {
+        let req = TestRequest::default()
+            .insert_header(AccessControlAllowCredentials)
+            .finish();
+        assert_eq!(
+            AccessControlAllowCredentials::parse(&req).unwrap(),
+            AccessControlAllowCredentials
+        );
+        assert_eq!(
+            req.headers().get(ACCESS_CONTROL_ALLOW_CREDENTIALS).unwrap(),
+            "true"
+        );
+
+        let req = TestRequest::default()
+            .insert_header((ACCESS_CONTROL_ALLOW_CREDENTIALS, "false"))
+            .finish();
+        assert!(AccessControlAllowCredentials::parse(&req).is_err());
+    }
+
+    #[test]
+    fn request_method() {
+        let req = TestRequest::default()
+            .insert_header(AccessControlRequestMethod(Method::PUT))
+            .finish();
+        assert_eq!(
+            req.headers().get(ACCESS_CONTROL_REQUEST_METHOD).unwrap(),
+            "PUT"
+        );
+        assert_eq!(
+            AccessControlRequestMethod::parse(&req).unwrap(),
+            AccessControlRequestMethod(Method::PUT)
+        );
+
+        let req = TestRequest::default().finish();
+        assert!(AccessControlRequestMethod::parse(&req).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+use std::{
+    convert::TryFrom as _,
+    fmt::{self, Write as _},
+};
+
+use super::{
+    structured::{BareItem, Item, List, Member, Parameters},
+    Header, HeaderName, HeaderValue, InvalidHeaderValue, TryIntoHeaderValue, Writer,
+    CACHE_STATUS,
+};
+use crate::{error::ParseError, HttpMessage};
+
+/// `Cache-Status` header, defined in [RFC 9211].
+///
+/// The `Cache-Status` response header field describes how each cache along the response's path
+/// handled the request. Entries are ordered from the cache closest to the origin server to the one
+/// closest to the client, so caches append their own entry when forwarding a response.
+///
+/// # ABNF
+/// ```plain
+/// Cache-Status = sf-list
+/// ```
+///
+/// # Example Values
+/// * `ExampleCache; hit; ttl=376`
+/// * `OriginCache; hit; ttl=1100, "CDN Company Here"; hit; ttl=545`
+/// * `ExampleCache; fwd=uri-miss; stored`
+///
+/// # Examples
+/// ```
+/// use actix_web::HttpResponse;
+/// use actix_web::http::header::{CacheStatus, CacheStatusEntry};
+///
+/// let mut builder = HttpResponse::Ok();
+/// builder.insert_header(CacheStatus(vec![
+///     CacheStatusEntry::hit("ExampleCache").ttl(376),
+/// ]));
+/// ```
+///
+/// [RFC 9211]: https://datatracker.ietf.org/doc/html/rfc9211
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheStatus(pub Vec<CacheStatusEntry>);
+
+/// A single cache's entry in a [`CacheStatus`] header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheStatusEntry {
+    /// Identifier of the cache, such as its hostname or product name.
+    pub cache: String,
+
+    /// True if the request was satisfied by the cache without contacting the next hop.
+    pub hit: bool,
+
+    /// Why the request was forwarded towards the origin server, if it was.
+    pub fwd: Option<CacheForwardReason>,
+
+    /// Status code of the response received from the next hop when forwarding.
+    pub fwd_status: Option<u16>,
+
+    /// Remaining freshness lifetime of the response, in seconds. Negative for stale responses.
+    pub ttl: Option<i64>,
+
+    /// True if the response received from the next hop was stored by the cache.
+    pub stored: bool,
+
+    /// True if the request was collapsed together with other requests for the same response.
+    pub collapsed: bool,
+
+    /// Representation of the cache key used for the response.
+    pub key: Option<String>,
+
+    /// Implementation-specific details about how the cache handled the request.
+    pub detail: Option<String>,
+}
+
+impl CacheStatusEntry {
+    fn new(cache: impl Into<String>, hit: bool) -> Self {
+        Self {
+            cache: cache.into(),
+            hit,
+            fwd: None,
+            fwd_status: None,
+            ttl: None,
+            stored: false,
+            collapsed: false,
+            key: None,
+            detail: None,
+        }
+    }
+
+    /// Constructs an entry for a request that `cache` satisfied without forwarding.
+    pub fn hit(cache: impl Into<String>) -> Self {
+        Self::new(cache, true)
+    }
+
+    /// Constructs an entry for a request that `cache` forwarded for the given `reason`.
+    pub fn forward(cache: impl Into<String>, reason: CacheForwardReason) -> Self {
+        Self {
+            fwd: Some(reason),
+            ..Self::new(cache, false)
+        }
+    }
+
+    /// Sets the status code of the response received from the next hop.
+    pub fn fwd_status(mut self, status: u16) -> Self {
+        self.fwd_status = Some(status);
+        self
+    }
+
+    /// Sets the remaining freshness lifetime of the response, in seconds.
+    pub fn ttl(mut self, ttl: i64) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Marks the response received from the next hop as stored.
+    pub fn stored(mut self) -> Self {
+        self.stored = true;
+        self
+    }
+
+    /// Marks the request as collapsed with other requests.
+    pub fn collapsed(mut self) -> Self {
+        self.collapsed = true;
+        self
+    }
+
+    /// Sets the representation of the cache key.
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    /// Sets implementation-specific details.
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    fn from_member(member: &Member) -> Result<Self, ParseError> {
+        let item = member.as_item().ok_or(ParseError::Header)?;
+        let cache = item.bare_item.as_str().ok_or(ParseError::Header)?;
+
+        let mut entry = Self::new(cache, false);
+
+        // unknown parameters and parameters of unexpected types are ignored, as required
+        for (key, value) in item.params.iter() {
+            match (key, value) {
+                ("hit", BareItem::Boolean(hit)) => entry.hit = *hit,
+                ("fwd", BareItem::Token(fwd)) => {
+                    entry.fwd = Some(CacheForwardReason::from(&**fwd))
+                }
+                ("fwd-status", BareItem::Integer(status)) => {
+                    entry.fwd_status = u16::try_from(*status).ok()
+                }
+                ("ttl", BareItem::Integer(ttl)) => entry.ttl = Some(*ttl),
+                ("stored", BareItem::Boolean(stored)) => entry.stored = *stored,
+                ("collapsed", BareItem::Boolean(collapsed)) => entry.collapsed = *collapsed,
+                ("key", BareItem::String(key)) => entry.key = Some(key.clone()),
+                ("detail", BareItem::String(detail) | BareItem::Token(detail)) => {
+                    entry.detail = Some(detail.clone())
+                }
+                _ => {}
+            }
+        }
+
+        Ok(entry)
+    }
+
+    fn to_item(&self) -> Item {
+        let mut params = Parameters::new();
+
+        if self.hit {
+            params.insert("hit", BareItem::Boolean(true));
+        }
+
+        if let Some(fwd) = &self.fwd {
+            params.insert("fwd", BareItem::Token(fwd.to_string()));
+        }
+
+        if let Some(status) = self.fwd_status {
+            params.insert("fwd-status", BareItem::Integer(status.into()));
+        }
+
+        if let Some(ttl) = self.ttl {
+            params.insert("ttl", BareItem::Integer(ttl));
+        }
+
+        if self.stored {
+            params.insert("stored", BareItem::Boolean(true));
+        }
+
+        if self.collapsed {
+            params.insert("collapsed", BareItem::Boolean(true));
+        }
+
+        if let Some(key) = &self.key {
+            params.insert("key", BareItem::String(key.clone()));
+        }
+
+        if let Some(detail) = &self.detail {
+            params.insert("detail", string_or_token(detail));
+        }
+
+        Item {
+            bare_item: string_or_token(&self.cache),
+            params,
+        }
+    }
+}
+
+/// Uses a token where possible, since cache identifiers and details are usually simple names.
+fn string_or_token(val: &str) -> BareItem {
+    let mut bytes = val.bytes();
+
+    let is_token = bytes
+        .next()
+        .map_or(false, |first| first.is_ascii_alphabetic() || first == b'*')
+        && bytes
+            .all(|byte| byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~:/".contains(&byte));
+
+    if is_token {
+        BareItem::Token(val.to_owned())
+    } else {
+        BareItem::String(val.to_owned())
+    }
+}
+
+/// Reason a cache forwarded a request towards the origin server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CacheForwardReason {
+    /// The cache was configured not to handle this request.
+    Bypass,
+
+    /// The request method's semantics require the request to be forwarded.
+    Method,
+
+    /// The cache did not contain any responses that matched the request URI.
+    UriMiss,
+
+    /// The cache contained a response for the request URI, but its `Vary` header did not match.
+    VaryMiss,
+
+    /// The cache did not contain any responses that could be used to satisfy the request.
+    Miss,
+
+    /// The cache was able to select a fresh response, but the request's semantics did not allow
+    /// its use.
+    Request,
+
+    /// The cache was able to select a response, but it was stale.
+    Stale,
+
+    /// The cache was able to select a partial response, but it did not contain all of the
+    /// requested ranges.
+    Partial,
+
+    /// An extension reason.
+    Other(String),
+}
+
+impl From<&str> for CacheForwardReason {
+    fn from(reason: &str) -> Self {
+        match reason {
+            "bypass" => CacheForwardReason::Bypass,
+            "method" => CacheForwardReason::Method,
+            "uri-miss" => CacheForwardReason::UriMiss,
+            "vary-miss" => CacheForwardReason::VaryMiss,
+            "miss" => CacheForwardReason::Miss,
+            "request" => CacheForwardReason::Request,
+            "stale" => CacheForwardReason::Stale,
+            "partial" => CacheForwardReason::Partial,
+            other => CacheForwardReason::Other(other.to_owned()),
+        }
+    }
+}
+
+impl fmt::Display for CacheForwardReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CacheForwardReason::Bypass => "bypass",
+            CacheForwardReason::Method => "method",
+            CacheForwardReason::UriMiss => "uri-miss",
+            CacheForwardReason::VaryMiss => "vary-miss",
+            CacheForwardReason::Miss => "miss",
+            CacheForwardReason::Request => "request",
+            CacheForwardReason::Stale => "stale",
+            CacheForwardReason::Partial => "partial",
+            CacheForwardReason::Other(other) => other,
+        })
+    }
+}
+
+impl CacheStatus {
+    /// Parses a `Cache-Status` value from its comma-joined header lines.
+    fn parse_value(value: &[u8]) -> Result<Self, ParseError> {
+        let list = List::parse(value)?;
+
+        if list.0.is_empty() {
+            return Err(ParseError::Header);
+        }
+
+        list.0
+            .iter()
+            .map(CacheStatusEntry::from_member)
+            .collect::<Result<_, _>>()
+            .map(CacheStatus)
+    }
+}
+
+impl fmt::Display for CacheStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = List(
+            self.0
+                .iter()
+                .map(|entry| Member::Item(entry.to_item()))
+                .collect(),
+        );
+
+        write!(f, "{}", list)
+    }
+}
+
+impl TryIntoHeaderValue for CacheStatus {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        let mut writer = Writer::new();
+        let _ = write!(&mut writer, "{}", self);
+        HeaderValue::from_maybe_shared(writer.take())
+    }
+}
+
+impl Header for CacheStatus {
+    fn name() -> HeaderName {
+        CACHE_STATUS
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        // list members may be split across multiple header lines
+        let mut value = Vec::new();
+
+        for (idx, line) in msg.headers().get_all(CACHE_STATUS).enumerate() {
+            if idx > 0 {
+                value.push(b',');
+            }
+
+            value.extend_from_slice(line.as_bytes());
+        }
+
+        Self::parse_value(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    fn parse(lines: &[&'static str]) -> Result<CacheStatus, ParseError> {
+        let mut req = TestRequest::default();
+
+        for line in lines {
+            req = req.append_header((CACHE_STATUS, *line));
+        }
+
+        CacheStatus::parse(&req.to_http_request())
+    }
+
+    #[test]
+    fn hit_entry() {
+        let status = parse(&["ExampleCache; hit; ttl=376"]).unwrap();
+
+        assert_eq!(
+            status,
+            CacheStatus(vec![CacheStatusEntry::hit("ExampleCache").ttl(376)])
+        );
+        assert_eq!(status.to_string(), "ExampleCache;hit;ttl=376");
+    }
+
+    #[test]
+    fn forward_entries() {
+        let status = parse(&[
+            r#""Origin Cache"; fwd=uri-miss; fwd-status=200; stored, CDN; fwd=miss"#,
+            r#"ExampleCache; fwd=x-custom; collapsed; key="/a?b"; detail="evicted early""#,
+        ])
+        .unwrap();
+
+        assert_eq!(
+            status.0,
+            vec![
+                CacheStatusEntry::forward("Origin Cache", CacheForwardReason::UriMiss)
+                    .fwd_status(200)
+                    .stored(),
+                CacheStatusEntry::forward("CDN", CacheForwardReason::Miss),
+                CacheStatusEntry::forward(
+                    "ExampleCache",
+                    CacheForwardReason::Other("x-custom".to_owned())
+                )
+                .collapsed()
+                .key("/a?b")
+                .detail("evicted early"),
+            ]
+        );
+
+        assert!(status.0.iter().all(|entry| !entry.hit));
+
+        assert_eq!(
+            status.to_string(),
+            r#""Origin Cache";fwd=uri-miss;fwd-status=200;stored, CDN;fwd=miss, ExampleCache;fwd=x-custom;collapsed;key="/a?b";detail="evicted early""#
+        );
+    }
+
+    #[test]
+    fn ignores_unknown_parameters() {
+        let status = parse(&["ExampleCache; hit; ttl=-5; foo=\"bar\"; stored=1"]).unwrap();
+        assert_eq!(
+            status,
+            CacheStatus(vec![CacheStatusEntry::hit("ExampleCache").ttl(-5)])
+        );
+    }
+
+    #[test]
+    fn invalid_values() {
+        assert!(parse(&[]).is_err());
+        assert!(parse(&[""]).is_err());
+        assert!(parse(&["ExampleCache; hit,"]).is_err());
+        assert!(parse(&["?1; hit"]).is_err());
+        assert!(parse(&["(ExampleCache); hit"]).is_err());
+    }
+
+    #[test]
+    fn into_value() {
+        let status = CacheStatus(vec![CacheStatusEntry::forward(
+            "ExampleCache",
+            CacheForwardReason::Stale,
+        )
+        .fwd_status(304)
+        .ttl(-12)]);
+
+        assert_eq!(
+            status.try_into_value().unwrap(),
+            "ExampleCache;fwd=stale;fwd-status=304;ttl=-12"
+        );
+    }
+}
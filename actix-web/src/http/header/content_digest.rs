@@ -0,0 +1,250 @@
+use std::{
+    convert::TryInto as _,
+    fmt::{self, Write as _},
+};
+
+use super::{
+    b64::{self, Alphabet},
+    structured::{BareItem, Dictionary, Item, Member},
+    Header, HeaderName, HeaderValue, InvalidHeaderValue, TryIntoHeaderValue, Writer,
+    CONTENT_DIGEST, CONTENT_MD5,
+};
+use crate::{error::ParseError, HttpMessage};
+
+/// `Content-Digest` header, defined in [RFC 9530 §2].
+///
+/// The `Content-Digest` header field carries one or more digests of the message content, each
+/// keyed by the name of the hashing algorithm, such as `sha-256` or `sha-512`. Digests are computed
+/// over the content as sent, that is, after any content coding has been applied.
+///
+/// # ABNF
+/// ```plain
+/// Content-Digest = sf-dictionary
+/// ```
+///
+/// # Example Values
+/// * `sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:`
+/// * `sha-256=:...:, sha-512=:...:`
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::{ContentDigest, Header as _}, test::TestRequest};
+///
+/// let req = TestRequest::default()
+///     .insert_header(("content-digest", "sha-256=:aGk=:"))
+///     .to_http_request();
+///
+/// let digest = ContentDigest::parse(&req).unwrap();
+/// assert_eq!(digest.get("sha-256"), Some(&b"hi"[..]));
+/// ```
+///
+/// [RFC 9530 §2]: https://datatracker.ietf.org/doc/html/rfc9530#section-2
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ContentDigest {
+    digests: Vec<(String, Vec<u8>)>,
+}
+
+impl ContentDigest {
+    /// Constructs a header without digests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the `digest` computed using `algorithm`, replacing any previous digest for it.
+    ///
+    /// Algorithm names are lowercase, such as `sha-256`.
+    pub fn digest(mut self, algorithm: impl Into<String>, digest: impl Into<Vec<u8>>) -> Self {
+        let algorithm = algorithm.into();
+        let digest = digest.into();
+
+        match self.digests.iter_mut().find(|(name, _)| *name == algorithm) {
+            Some((_, value)) => *value = digest,
+            None => self.digests.push((algorithm, digest)),
+        }
+
+        self
+    }
+
+    /// Returns the digest computed using `algorithm`, if the header has one.
+    pub fn get(&self, algorithm: &str) -> Option<&[u8]> {
+        self.digests
+            .iter()
+            .find(|(name, _)| name == algorithm)
+            .map(|(_, digest)| digest.as_slice())
+    }
+
+    /// Returns an iterator over algorithm names and their digests, in header order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.digests
+            .iter()
+            .map(|(name, digest)| (name.as_str(), digest.as_slice()))
+    }
+
+    fn parse_value(value: &[u8]) -> Result<Self, ParseError> {
+        let dict = Dictionary::parse(value)?;
+        let mut header = Self::new();
+
+        for (algorithm, member) in dict.iter() {
+            // parameters are reserved for future use and ignored
+            match member {
+                Member::Item(Item {
+                    bare_item: BareItem::ByteSequence(digest),
+                    ..
+                }) => header = header.digest(algorithm, digest.clone()),
+                _ => return Err(ParseError::Header),
+            }
+        }
+
+        Ok(header)
+    }
+}
+
+impl fmt::Display for ContentDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dict = Dictionary::new();
+
+        for (algorithm, digest) in &self.digests {
+            dict.insert(
+                algorithm.clone(),
+                Item::new(BareItem::ByteSequence(digest.clone())),
+            );
+        }
+
+        write!(f, "{}", dict)
+    }
+}
+
+impl TryIntoHeaderValue for ContentDigest {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        let mut writer = Writer::new();
+        let _ = write!(&mut writer, "{}", self);
+        HeaderValue::from_maybe_shared(writer.take())
+    }
+}
+
+impl Header for ContentDigest {
+    fn name() -> HeaderName {
+        CONTENT_DIGEST
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        // dictionary members may be split across multiple header lines
+        let mut value = Vec::new();
+
+        for (idx, line) in msg.headers().get_all(CONTENT_DIGEST).enumerate() {
+            if idx > 0 {
+                value.push(b',');
+            }
+
+            value.extend_from_slice(line.as_bytes());
+        }
+
+        if value.is_empty() {
+            return Err(ParseError::Header);
+        }
+
+        Self::parse_value(&value)
+    }
+}
+
+/// `Content-MD5` header, defined in [RFC 1864].
+///
+/// The `Content-MD5` header field carries the base64-encoded MD5 digest of the message content. It
+/// was removed from HTTP by [RFC 7231 Appendix B] and is superseded by [`ContentDigest`], but is
+/// still sent by some clients.
+///
+/// # Example Values
+/// * `Q2hlY2sgSW50ZWdyaXR5IQ==`
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::{ContentMd5, Header as _}, test::TestRequest};
+///
+/// let req = TestRequest::default()
+///     .insert_header(ContentMd5(*b"Check Integrity!"))
+///     .to_http_request();
+///
+/// assert_eq!(
+///     req.headers().get("content-md5").unwrap(),
+///     "Q2hlY2sgSW50ZWdyaXR5IQ==",
+/// );
+/// assert_eq!(ContentMd5::parse(&req).unwrap(), ContentMd5(*b"Check Integrity!"));
+/// ```
+///
+/// [RFC 1864]: https://datatracker.ietf.org/doc/html/rfc1864
+/// [RFC 7231 Appendix B]: https://datatracker.ietf.org/doc/html/rfc7231#appendix-B
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ContentMd5(pub [u8; 16]);
+
+impl fmt::Display for ContentMd5 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&b64::encode(&self.0, Alphabet::Standard))
+    }
+}
+
+impl TryIntoHeaderValue for ContentMd5 {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        HeaderValue::from_str(&self.to_string())
+    }
+}
+
+impl Header for ContentMd5 {
+    fn name() -> HeaderName {
+        CONTENT_MD5
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        let val = msg.headers().get(CONTENT_MD5).ok_or(ParseError::Header)?;
+
+        b64::decode(val.as_bytes(), Alphabet::Standard)?
+            .as_slice()
+            .try_into()
+            .map(ContentMd5)
+            .map_err(|_| ParseError::Header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    #[test]
+    fn content_digest_round_trip() {
+        let digest = ContentDigest::new()
+            .digest("sha-256", b"hi".to_vec())
+            .digest("sha-512", b"there".to_vec());
+        assert_eq!(digest.to_string(), "sha-256=:aGk=:, sha-512=:dGhlcmU=:");
+
+        let req = TestRequest::default()
+            .append_header((CONTENT_DIGEST, "sha-256=:aGk=:"))
+            .append_header((CONTENT_DIGEST, "sha-512=:dGhlcmU=:;future=1"))
+            .to_http_request();
+        assert_eq!(ContentDigest::parse(&req).unwrap(), digest);
+
+        let req = TestRequest::default()
+            .insert_header((CONTENT_DIGEST, "sha-256=aGk"))
+            .to_http_request();
+        assert!(ContentDigest::parse(&req).is_err());
+
+        let req = TestRequest::default().to_http_request();
+        assert!(ContentDigest::parse(&req).is_err());
+    }
+
+    #[test]
+    fn content_md5_length() {
+        let req = TestRequest::default()
+            .insert_header((CONTENT_MD5, "aGk="))
+            .to_http_request();
+        assert!(ContentMd5::parse(&req).is_err());
+
+        let req = TestRequest::default()
+            .insert_header((CONTENT_MD5, "not base64!"))
+            .to_http_request();
+        assert!(ContentMd5::parse(&req).is_err());
+    }
+}
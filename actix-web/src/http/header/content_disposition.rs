@@ -14,7 +14,7 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use std::fmt::{self, Write};
 
-use super::{ExtendedValue, Header, TryIntoHeaderValue, Writer};
+use super::{Charset, ExtendedValue, Header, TryIntoHeaderValue, Writer};
 use crate::http::header;
 
 /// Split at the index of the first `needle` if it exists or at the end.
@@ -35,6 +35,62 @@ fn split_once_and_trim(haystack: &str, needle: char) -> (&str, &str) {
     (first.trim_end(), last.trim_start())
 }
 
+/// Maximum length, in bytes, of file names produced by [`ContentDisposition::with_filename`].
+const MAX_FILENAME_LEN: usize = 255;
+
+/// Longest extension, including the dot, that is kept when a file name has to be shortened.
+const MAX_KEPT_EXTENSION_LEN: usize = 16;
+
+/// File name used when nothing is left after sanitization.
+const FALLBACK_FILENAME: &str = "download";
+
+/// Makes a file name safe to suggest to a client or to use as a local file name.
+///
+/// Directory components, control characters and characters reserved by common file systems are
+/// removed, as are leading dots (so that hidden files can not be suggested) and surrounding
+/// whitespace. Long names are shortened to [`MAX_FILENAME_LEN`] bytes, keeping the extension if it
+/// is reasonably short.
+fn sanitize_filename(filename: &str) -> String {
+    // only the last path component is kept
+    let name = filename
+        .rsplit(|c| c == '/' || c == '\\')
+        .next()
+        .unwrap_or("");
+
+    let name = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| match c {
+            '"' | '<' | '>' | ':' | '|' | '?' | '*' => '_',
+            c => c,
+        })
+        .collect::<String>();
+
+    let name = name
+        .trim_start_matches(|c: char| c == '.' || c.is_whitespace())
+        .trim_end();
+
+    if name.is_empty() {
+        return FALLBACK_FILENAME.to_owned();
+    }
+
+    if name.len() <= MAX_FILENAME_LEN {
+        return name.to_owned();
+    }
+
+    let (stem, ext) = match name.rfind('.') {
+        Some(idx) if name.len() - idx <= MAX_KEPT_EXTENSION_LEN => name.split_at(idx),
+        _ => (name, ""),
+    };
+
+    let mut stem_len = MAX_FILENAME_LEN - ext.len();
+    while !stem.is_char_boundary(stem_len) {
+        stem_len -= 1;
+    }
+
+    format!("{}{}", &stem[..stem_len], ext)
+}
+
 /// The implied disposition of the content of the HTTP body.
 #[derive(Clone, Debug, PartialEq)]
 pub enum DispositionType {
@@ -398,6 +454,66 @@ impl ContentDisposition {
         Ok(cd)
     }
 
+    /// Constructs an `attachment` disposition with the given file name.
+    ///
+    /// The file name is sanitized as described in [`with_filename`](Self::with_filename).
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::http::header::ContentDisposition;
+    ///
+    /// let cd = ContentDisposition::attachment("../reports/2021 \"final\".pdf");
+    /// assert!(cd.is_attachment());
+    /// assert_eq!(cd.to_string(), r#"attachment; filename="2021 _final_.pdf""#);
+    ///
+    /// let cd = ContentDisposition::attachment("\u{1f600}.svg");
+    /// assert_eq!(cd.to_string(), "attachment; filename*=UTF-8''%F0%9F%98%80.svg");
+    /// ```
+    pub fn attachment(filename: impl AsRef<str>) -> Self {
+        ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: Vec::new(),
+        }
+        .with_filename(filename)
+    }
+
+    /// Constructs an `inline` disposition without parameters.
+    ///
+    /// A file name, used if the user chooses to save the content, can be added with
+    /// [`with_filename`](Self::with_filename).
+    pub fn inline() -> Self {
+        ContentDisposition {
+            disposition: DispositionType::Inline,
+            parameters: Vec::new(),
+        }
+    }
+
+    /// Sets the file name parameter, replacing any existing *filename* and *filename\**.
+    ///
+    /// The file name is sanitized first: directory components, control characters, characters
+    /// reserved by common file systems (which are replaced with `_`), and leading dots are
+    /// removed, and names longer than 255 bytes are shortened while keeping short extensions.
+    /// ASCII names are sent as *filename*; other names are sent as a UTF-8 encoded *filename\**.
+    pub fn with_filename(mut self, filename: impl AsRef<str>) -> Self {
+        self.parameters
+            .retain(|param| !param.is_filename() && !param.is_filename_ext());
+
+        let filename = sanitize_filename(filename.as_ref());
+
+        let param = if filename.is_ascii() {
+            DispositionParam::Filename(filename)
+        } else {
+            DispositionParam::FilenameExt(ExtendedValue {
+                charset: Charset::Ext(String::from("UTF-8")),
+                language_tag: None,
+                value: filename.into_bytes(),
+            })
+        };
+
+        self.parameters.push(param);
+        self
+    }
+
     /// Returns `true` if type is [`Inline`](DispositionType::Inline).
     pub fn is_inline(&self) -> bool {
         matches!(self.disposition, DispositionType::Inline)
@@ -440,6 +556,26 @@ impl ContentDisposition {
             .find_map(DispositionParam::as_filename_ext)
     }
 
+    /// Returns the file name, sanitized for local use, if either *filename\** or *filename*
+    /// exists.
+    ///
+    /// *filename\** is preferred when its charset is UTF-8, US-ASCII or ISO-8859-1. Sanitization
+    /// is the same as in [`with_filename`](Self::with_filename); notably, any directory
+    /// components sent by the peer are removed.
+    pub fn get_sanitized_filename(&self) -> Option<String> {
+        let ext = self.get_filename_ext().and_then(|ext| match ext.charset {
+            Charset::Iso_8859_1 => Some(ext.value.iter().map(|&b| char::from(b)).collect()),
+            Charset::Us_Ascii => Some(String::from_utf8_lossy(&ext.value).into_owned()),
+            Charset::Ext(ref cs) if cs.eq_ignore_ascii_case("UTF-8") => {
+                Some(String::from_utf8_lossy(&ext.value).into_owned())
+            }
+            _ => None,
+        });
+
+        ext.or_else(|| self.get_filename().map(str::to_owned))
+            .map(|filename| sanitize_filename(&filename))
+    }
+
     /// Return the value of the parameter which the `name` matches.
     pub fn get_unknown(&self, name: impl AsRef<str>) -> Option<&str> {
         let name = name.as_ref();
@@ -985,4 +1121,89 @@ mod tests {
         assert_eq!(cd.get_unknown_ext("dummy"), None);
         assert_eq!(cd.get_unknown("duMMy"), Some("3"));
     }
+
+    #[test]
+    fn test_attachment_sanitizes_filename() {
+        let cd = ContentDisposition::attachment("../../etc/passwd");
+        assert_eq!(cd.to_string(), "attachment; filename=\"passwd\"");
+
+        let cd = ContentDisposition::attachment("..\\windows\\..hidden.ini");
+        assert_eq!(cd.to_string(), "attachment; filename=\"hidden.ini\"");
+
+        let cd = ContentDisposition::attachment("say \"hi\"\r\n.txt");
+        assert_eq!(cd.to_string(), "attachment; filename=\"say _hi_.txt\"");
+
+        let cd = ContentDisposition::attachment("../");
+        assert_eq!(cd.get_filename(), Some("download"));
+    }
+
+    #[test]
+    fn test_attachment_non_ascii_filename() {
+        let cd = ContentDisposition::attachment("\u{1f600} smile.png");
+        assert_eq!(cd.get_filename(), None);
+        assert_eq!(
+            cd.to_string(),
+            "attachment; filename*=UTF-8''%F0%9F%98%80%20smile.png"
+        );
+
+        let hv = HeaderValue::from_str(&cd.to_string()).unwrap();
+        let parsed = ContentDisposition::from_raw(&hv).unwrap();
+        assert_eq!(
+            parsed.get_filename_ext().unwrap().value,
+            "\u{1f600} smile.png".as_bytes()
+        );
+        assert_eq!(
+            parsed.get_sanitized_filename().as_deref(),
+            Some("\u{1f600} smile.png")
+        );
+    }
+
+    #[test]
+    fn test_attachment_long_filename() {
+        let long = format!("{}.pdf", "a".repeat(1000));
+        let cd = ContentDisposition::attachment(&long);
+        let filename = cd.get_filename().unwrap();
+        assert_eq!(filename.len(), 255);
+        assert!(filename.ends_with("a.pdf"));
+
+        // truncation does not split multi-byte characters
+        let long = "\u{e9}".repeat(1000);
+        let cd = ContentDisposition::attachment(&long);
+        let filename = cd.get_filename_ext().unwrap().value.clone();
+        let filename = String::from_utf8(filename).unwrap();
+        assert_eq!(filename.len(), 254);
+
+        // a very long "extension" is not preserved
+        let long = format!("a.{}", "b".repeat(1000));
+        let cd = ContentDisposition::attachment(&long);
+        assert!(cd.get_filename().unwrap().starts_with("a.bbb"));
+    }
+
+    #[test]
+    fn test_with_filename_replaces() {
+        let cd = ContentDisposition::inline();
+        assert_eq!(cd.to_string(), "inline");
+
+        let cd = ContentDisposition::inline()
+            .with_filename("\u{1f600}.svg")
+            .with_filename("report.pdf");
+        assert_eq!(cd.to_string(), "inline; filename=\"report.pdf\"");
+    }
+
+    #[test]
+    fn test_get_sanitized_filename() {
+        let hv = HeaderValue::from_static("form-data; name=upload; filename=\"../../.bashrc\"");
+        let cd = ContentDisposition::from_raw(&hv).unwrap();
+        assert_eq!(cd.get_filename(), Some("../../.bashrc"));
+        assert_eq!(cd.get_sanitized_filename().as_deref(), Some("bashrc"));
+
+        let hv = HeaderValue::from_static(
+            "attachment; filename=\"fallback.txt\"; filename*=iso-8859-1''%A9%20c.txt",
+        );
+        let cd = ContentDisposition::from_raw(&hv).unwrap();
+        assert_eq!(cd.get_sanitized_filename().as_deref(), Some("\u{a9} c.txt"));
+
+        let cd = ContentDisposition::inline();
+        assert_eq!(cd.get_sanitized_filename(), None);
+    }
 }
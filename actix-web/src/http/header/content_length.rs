@@ -0,0 +1,127 @@
+use std::convert::TryFrom as _;
+
+use super::{ContentRange, ContentRangeSpec, CONTENT_LENGTH};
+
+crate::http::header::common_header! {
+    /// `Content-Length` header, defined
+    /// in [RFC 7230 §3.3.2](https://datatracker.ietf.org/doc/html/rfc7230#section-3.3.2)
+    ///
+    /// The `Content-Length` header field indicates the size of the message body, in bytes, when
+    /// no `Transfer-Encoding` is present.
+    ///
+    /// Note that the length of a response is usually determined from its body type. Setting this
+    /// header manually is only needed when the body's size is not otherwise known.
+    ///
+    /// # ABNF
+    /// ```plain
+    /// Content-Length = 1*DIGIT
+    /// ```
+    ///
+    /// # Example Values
+    /// * `0`
+    /// * `3495`
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::ContentLength;
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// builder.insert_header(ContentLength(3495));
+    /// ```
+    (ContentLength, CONTENT_LENGTH) => [usize]
+
+    test_parse_and_format {
+        crate::http::header::common_header_test!(test1, vec![b"3495"], Some(ContentLength(3495)));
+        crate::http::header::common_header_test!(test2, vec![b"0"], Some(ContentLength(0)));
+        crate::http::header::common_header_test!(test3, vec![b"-1"], None);
+        crate::http::header::common_header_test!(test4, vec![b"1.5"], None);
+        crate::http::header::common_header_test!(test5, vec![b""], None);
+    }
+}
+
+impl ContentLength {
+    /// Returns the wrapped length.
+    #[inline]
+    pub fn into_inner(self) -> usize {
+        self.0
+    }
+
+    /// Returns the length of the part of a representation served with the given
+    /// [`ContentRange`].
+    ///
+    /// Returns `None` for unsatisfied ranges, ranges with a unit other than `bytes`, and ranges
+    /// whose last byte precedes their first.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::http::header::{ContentLength, ContentRange, ContentRangeSpec};
+    ///
+    /// let range = ContentRange(ContentRangeSpec::Bytes {
+    ///     range: Some((0, 499)),
+    ///     instance_length: Some(2048),
+    /// });
+    ///
+    /// assert_eq!(ContentLength::from_range(&range), Some(ContentLength(500)));
+    /// ```
+    pub fn from_range(range: &ContentRange) -> Option<ContentLength> {
+        match range.0 {
+            ContentRangeSpec::Bytes {
+                range: Some((first, last)),
+                ..
+            } if first <= last => usize::try_from(last - first + 1).ok().map(ContentLength),
+
+            _ => None,
+        }
+    }
+}
+
+impl From<usize> for ContentLength {
+    fn from(len: usize) -> Self {
+        ContentLength(len)
+    }
+}
+
+impl From<ContentLength> for usize {
+    fn from(ContentLength(len): ContentLength) -> Self {
+        len
+    }
+}
+
+impl PartialEq<usize> for ContentLength {
+    fn eq(&self, other: &usize) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_range() {
+        let range = ContentRange("bytes 0-499/2048".parse().unwrap());
+        assert_eq!(ContentLength::from_range(&range), Some(ContentLength(500)));
+
+        let range = ContentRange(ContentRangeSpec::Bytes {
+            range: Some((2047, 2047)),
+            instance_length: None,
+        });
+        assert_eq!(ContentLength::from_range(&range), Some(ContentLength(1)));
+
+        let range = ContentRange("bytes */2048".parse().unwrap());
+        assert_eq!(ContentLength::from_range(&range), None);
+
+        let range = ContentRange(ContentRangeSpec::Bytes {
+            range: Some((10, 5)),
+            instance_length: None,
+        });
+        assert_eq!(ContentLength::from_range(&range), None);
+
+        let range = ContentRange(ContentRangeSpec::Unregistered {
+            unit: "pages".to_owned(),
+            resp: "1-2".to_owned(),
+        });
+        assert_eq!(ContentLength::from_range(&range), None);
+    }
+}
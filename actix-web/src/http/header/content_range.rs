@@ -4,7 +4,7 @@ use std::{
 };
 
 use super::{HeaderValue, InvalidHeaderValue, TryIntoHeaderValue, Writer, CONTENT_RANGE};
-use crate::error::ParseError;
+use crate::error::{InvalidContentRange, ParseError};
 
 crate::http::header::common_header! {
     /// `Content-Range` header, defined
@@ -71,6 +71,64 @@ crate::http::header::common_header! {
     }
 }
 
+impl ContentRange {
+    /// Constructs a `Content-Range` header for the bytes from `start` to `end`, inclusive, of a
+    /// representation that is `total` bytes long.
+    ///
+    /// # Panics
+    /// Panics if `start` is greater than `end` or if `end` is not less than `total`. See
+    /// [`try_bytes`](Self::try_bytes) for a non-panicking version.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::http::header::ContentRange;
+    ///
+    /// assert_eq!(ContentRange::bytes(0, 499, 2048).to_string(), "bytes 0-499/2048");
+    /// ```
+    pub fn bytes(start: u64, end: u64, total: u64) -> Self {
+        match Self::try_bytes(start, end, total) {
+            Ok(range) => range,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Constructs a `Content-Range` header for the bytes from `start` to `end`, inclusive, of a
+    /// representation that is `total` bytes long.
+    ///
+    /// # Errors
+    /// Returns an error if `start` is greater than `end` or if `end` is not less than `total`.
+    pub fn try_bytes(start: u64, end: u64, total: u64) -> Result<Self, InvalidContentRange> {
+        if start > end {
+            return Err(InvalidContentRange::StartAfterEnd { start, end });
+        }
+
+        if end >= total {
+            return Err(InvalidContentRange::EndOutOfBounds { end, total });
+        }
+
+        Ok(ContentRange(ContentRangeSpec::Bytes {
+            range: Some((start, end)),
+            instance_length: Some(total),
+        }))
+    }
+
+    /// Constructs a `Content-Range` header for a "416 Range Not Satisfiable" response to a request
+    /// for a representation that is `total` bytes long.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::http::header::ContentRange;
+    ///
+    /// assert_eq!(ContentRange::unsatisfied(2048).to_string(), "bytes */2048");
+    /// ```
+    pub fn unsatisfied(total: u64) -> Self {
+        ContentRange(ContentRangeSpec::Bytes {
+            range: None,
+            instance_length: Some(total),
+        })
+    }
+}
+
 /// Content-Range header, defined
 /// in [RFC 7233 §4.2](https://datatracker.ietf.org/doc/html/rfc7233#section-4.2)
 ///
@@ -205,3 +263,58 @@ impl TryIntoHeaderValue for ContentRangeSpec {
         HeaderValue::from_maybe_shared(writer.take())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes() {
+        let range = ContentRange::bytes(0, 499, 500);
+        assert_eq!(
+            range,
+            ContentRange(ContentRangeSpec::Bytes {
+                range: Some((0, 499)),
+                instance_length: Some(500),
+            })
+        );
+        assert_eq!(range.try_into_value().unwrap(), "bytes 0-499/500");
+
+        assert_eq!(ContentRange::bytes(7, 7, 8).to_string(), "bytes 7-7/8");
+    }
+
+    #[test]
+    fn try_bytes_invalid() {
+        assert_eq!(
+            ContentRange::try_bytes(500, 499, 1000),
+            Err(InvalidContentRange::StartAfterEnd {
+                start: 500,
+                end: 499
+            })
+        );
+        assert_eq!(
+            ContentRange::try_bytes(0, 500, 500),
+            Err(InvalidContentRange::EndOutOfBounds {
+                end: 500,
+                total: 500
+            })
+        );
+        assert_eq!(
+            ContentRange::try_bytes(0, 0, 0),
+            Err(InvalidContentRange::EndOutOfBounds { end: 0, total: 0 })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "starts at byte 500 but ends at byte 499")]
+    fn bytes_start_after_end() {
+        ContentRange::bytes(500, 499, 1000);
+    }
+
+    #[test]
+    fn unsatisfied() {
+        let range = ContentRange::unsatisfied(500);
+        assert_eq!(range, ContentRange("bytes */500".parse().unwrap()));
+        assert_eq!(range.try_into_value().unwrap(), "bytes */500");
+    }
+}
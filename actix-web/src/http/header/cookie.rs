@@ -0,0 +1,269 @@
+use std::{collections::HashSet, fmt, iter::FromIterator};
+
+use super::{Header, HeaderName, HeaderValue, InvalidHeaderValue, TryIntoHeaderValue, COOKIE};
+use crate::{error::ParseError, HttpMessage};
+
+/// `Cookie` header, defined in [RFC 6265 §5.4].
+///
+/// The `Cookie` request header contains the name-value pairs of cookies stored by the user agent,
+/// previously set by the server using `Set-Cookie`. Values wrapped in double quotes are unquoted
+/// when parsed. Cookies sent in multiple header lines, as is common with HTTP/2, are combined.
+///
+/// All cookies are retained, including those with duplicate names; [`get`](Self::get) returns the
+/// value of the last one. Use [`parse_with`](Self::parse_with) to discard duplicates while
+/// parsing. This type only parses the header; with the `cookies` feature, `HttpRequest::cookies`
+/// provides a `cookie` crate based API instead.
+///
+/// # ABNF
+/// ```plain
+/// cookie-header = "Cookie:" OWS cookie-string OWS
+/// cookie-string = cookie-pair *( ";" SP cookie-pair )
+/// cookie-pair   = cookie-name "=" cookie-value
+/// cookie-value  = *cookie-octet / ( DQUOTE *cookie-octet DQUOTE )
+/// ```
+///
+/// # Example Values
+/// * `SID=31d4d96e407aad42`
+/// * `SID=31d4d96e407aad42; lang=en-US`
+/// * `theme="dark mode"`
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::{self, Cookie, Header as _}, test::TestRequest};
+///
+/// let req = TestRequest::default()
+///     .insert_header((header::COOKIE, "SID=31d4d96e407aad42; lang=en-US"))
+///     .to_http_request();
+///
+/// let cookies = Cookie::parse(&req).unwrap();
+/// assert_eq!(cookies.get("lang"), Some("en-US"));
+/// assert_eq!(cookies.iter().count(), 2);
+/// ```
+///
+/// [RFC 6265 §5.4]: https://datatracker.ietf.org/doc/html/rfc6265#section-5.4
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cookie {
+    pairs: Vec<(String, String)>,
+}
+
+/// How [`Cookie::parse_with`] treats cookies with the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateCookies {
+    /// Retain all cookies.
+    KeepAll,
+
+    /// Retain only the last cookie with each name.
+    KeepLast,
+}
+
+impl Cookie {
+    /// Constructs an empty `Cookie` header.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a cookie, after any existing ones.
+    pub fn add(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.pairs.push((name.into(), value.into()));
+        self
+    }
+
+    /// Parses the `Cookie` headers of `msg`, treating duplicate names as specified.
+    pub fn parse_with<T: HttpMessage>(
+        msg: &T,
+        duplicates: DuplicateCookies,
+    ) -> Result<Self, ParseError> {
+        let mut pairs = Vec::new();
+
+        for value in msg.headers().get_all(COOKIE) {
+            let value = value.to_str().map_err(|_| ParseError::Header)?;
+            pairs.extend(value.split(';').filter_map(parse_pair));
+        }
+
+        if duplicates == DuplicateCookies::KeepLast {
+            let mut seen = HashSet::new();
+
+            // retain the last occurrence of each name, keeping the order of those retained
+            pairs.reverse();
+            pairs.retain(|(name, _): &(String, String)| seen.insert(name.clone()));
+            pairs.reverse();
+        }
+
+        Ok(Cookie { pairs })
+    }
+
+    /// Returns the value of the last cookie named `name`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the values of all cookies named `name`, in the order they were sent.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.iter()
+            .filter(move |(n, _)| *n == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Returns true if a cookie named `name` is present.
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Returns an iterator over the name-value pairs of all cookies, in the order they were sent.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Returns the number of cookies.
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns true if there are no cookies.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+/// Parses a single `name=value` pair, skipping empty and malformed pairs like browsers do.
+fn parse_pair(pair: &str) -> Option<(String, String)> {
+    let (name, value) = pair.split_once('=')?;
+    let name = name.trim();
+
+    if name.is_empty() {
+        return None;
+    }
+
+    let value = value.trim();
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value);
+
+    Some((name.to_owned(), value.to_owned()))
+}
+
+impl<N, V> FromIterator<(N, V)> for Cookie
+where
+    N: Into<String>,
+    V: Into<String>,
+{
+    fn from_iter<I: IntoIterator<Item = (N, V)>>(iter: I) -> Self {
+        Cookie {
+            pairs: iter
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, (name, value)) in self.iter().enumerate() {
+            if idx > 0 {
+                f.write_str("; ")?;
+            }
+
+            // quote values that would otherwise be split or trimmed when parsed
+            if value.contains(|c: char| c == ' ' || c == ',') {
+                write!(f, "{}=\"{}\"", name, value)?;
+            } else {
+                write!(f, "{}={}", name, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TryIntoHeaderValue for Cookie {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        HeaderValue::from_str(&self.to_string())
+    }
+}
+
+impl Header for Cookie {
+    fn name() -> HeaderName {
+        COOKIE
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        Self::parse_with(msg, DuplicateCookies::KeepAll)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    fn parse(duplicates: DuplicateCookies, lines: &[&'static str]) -> Cookie {
+        let mut req = TestRequest::default();
+
+        for line in lines {
+            req = req.append_header((COOKIE, *line));
+        }
+
+        Cookie::parse_with(&req.to_http_request(), duplicates).unwrap()
+    }
+
+    #[test]
+    fn multiple_cookies() {
+        let cookies = parse(
+            DuplicateCookies::KeepAll,
+            &[
+                "SID=31d4d96e407aad42; lang=en-US;;",
+                "empty=; =skipped; flag",
+            ],
+        );
+
+        assert_eq!(
+            cookies.iter().collect::<Vec<_>>(),
+            [
+                ("SID", "31d4d96e407aad42"),
+                ("lang", "en-US"),
+                ("empty", "")
+            ]
+        );
+        assert_eq!(cookies.get("lang"), Some("en-US"));
+        assert_eq!(cookies.get("flag"), None);
+        assert_eq!(
+            cookies.to_string(),
+            "SID=31d4d96e407aad42; lang=en-US; empty="
+        );
+    }
+
+    #[test]
+    fn quoted_value() {
+        let cookies = parse(DuplicateCookies::KeepAll, &[r#"theme="dark mode"; q="""#]);
+
+        assert_eq!(cookies.get("theme"), Some("dark mode"));
+        assert_eq!(cookies.get("q"), Some(""));
+        assert_eq!(cookies.to_string(), r#"theme="dark mode"; q="#);
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let lines = &["id=1; lang=en", "id=2"];
+
+        let cookies = parse(DuplicateCookies::KeepAll, lines);
+        assert_eq!(cookies.len(), 3);
+        assert_eq!(cookies.get("id"), Some("2"));
+        assert_eq!(cookies.get_all("id").collect::<Vec<_>>(), ["1", "2"]);
+
+        let cookies = parse(DuplicateCookies::KeepLast, lines);
+        assert_eq!(
+            cookies.iter().collect::<Vec<_>>(),
+            [("lang", "en"), ("id", "2")]
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use super::{common_header, EntityTag, ScheduleTag, IF_SCHEDULE_TAG_MATCH};
+
+common_header! {
+    /// `If-Schedule-Tag-Match` header, defined
+    /// in [RFC 6638 §3.2](https://datatracker.ietf.org/doc/html/rfc6638#section-3.2)
+    ///
+    /// The `If-Schedule-Tag-Match` header field makes a request to a CalDAV calendar object
+    /// resource conditional on the resource's current [`Schedule-Tag`](super::ScheduleTag)
+    /// matching the one provided. It works like [`If-Match`](super::IfMatch), but ignores changes
+    /// that are not significant to scheduling. Servers should respond with "412 Precondition
+    /// Failed" when [`matches`](Self::matches) returns false.
+    ///
+    /// # ABNF
+    /// ```plain
+    /// If-Schedule-Tag-Match = opaque-tag
+    /// ```
+    ///
+    /// # Example Values
+    /// * `"12345"`
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::{EntityTag, IfScheduleTagMatch};
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// builder.insert_header(IfScheduleTagMatch(EntityTag::new_strong("12345".to_owned())));
+    /// ```
+    (IfScheduleTagMatch, IF_SCHEDULE_TAG_MATCH) => [EntityTag]
+
+    test_parse_and_format {
+        crate::http::header::common_header_test!(test1,
+            vec![b"\"12345\""],
+            Some(IfScheduleTagMatch(EntityTag::new_strong("12345".to_owned()))));
+        crate::http::header::common_header_test!(test2,
+            vec![b""],
+            None::<IfScheduleTagMatch>);
+
+        #[test]
+        fn strong_comparison() {
+            let condition = IfScheduleTagMatch(EntityTag::new_strong("12345".to_owned()));
+
+            assert!(condition.matches(&ScheduleTag(EntityTag::new_strong("12345".to_owned()))));
+            assert!(!condition.matches(&ScheduleTag(EntityTag::new_strong("12346".to_owned()))));
+            assert!(!condition.matches(&ScheduleTag(EntityTag::new_weak("12345".to_owned()))));
+
+            let weak = IfScheduleTagMatch(EntityTag::new_weak("12345".to_owned()));
+            assert!(!weak.matches(&ScheduleTag(EntityTag::new_strong("12345".to_owned()))));
+        }
+    }
+}
+
+impl IfScheduleTagMatch {
+    /// Returns true if the `current` schedule tag of the resource matches this condition.
+    ///
+    /// Schedule tags are compared using the strong comparison function, so weak tags never match.
+    pub fn matches(&self, current: &ScheduleTag) -> bool {
+        self.0.strong_eq(&current.0)
+    }
+}
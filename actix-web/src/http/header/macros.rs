@@ -243,7 +243,7 @@ macro_rules! common_header {
                 let is_any = msg
                     .headers()
                     .get(Self::name())
-                    .and_then(|hdr| hdr.to_str().ok())
+                    .and_then(|hdr| ::std::str::from_utf8(hdr.as_bytes()).ok())
                     .map(|hdr| hdr.trim() == "*");
 
                 if let Some(true) = is_any {
@@ -313,7 +313,78 @@ macro_rules! common_header {
     };
 }
 
+/// Declares a custom header name as a `HeaderName` constant.
+///
+/// The name is checked when the crate is compiled: names that are not valid, lowercase header
+/// field names fail to build instead of panicking on first use. Declaring each app-specific header
+/// once also keeps every use site spelling the name the same way.
+///
+/// # Examples
+/// ```
+/// use actix_web::{header_name, http::header::HeaderName, HttpResponse};
+///
+/// header_name! {
+///     /// Identifies a request across services.
+///     pub X_REQUEST_ID = "x-request-id";
+/// }
+///
+/// let res = HttpResponse::Ok().insert_header((X_REQUEST_ID, "abc")).finish();
+/// assert_eq!(res.headers().get(X_REQUEST_ID).unwrap(), "abc");
+/// ```
+///
+/// Invalid names are rejected at compile time:
+/// ```compile_fail
+/// actix_web::header_name!(X_MY_HEADER = "x my header");
+/// ```
+#[macro_export]
+macro_rules! header_name {
+    ($($(#[$attrs:meta])* $vis:vis $id:ident = $name:expr;)+) => {
+        $(
+            $(#[$attrs])*
+            $vis const $id: $crate::http::header::HeaderName =
+                $crate::http::header::HeaderName::from_static($name);
+        )+
+    };
+
+    ($(#[$attrs:meta])* $vis:vis $id:ident = $name:expr) => {
+        $crate::header_name! { $(#[$attrs])* $vis $id = $name; }
+    };
+}
+
 pub(crate) use {common_header, common_header_test_module};
 
 #[cfg(test)]
 pub(crate) use common_header_test;
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use crate::{http::header::HeaderName, test::TestRequest};
+
+    crate::header_name! {
+        X_MY_HEADER = "x-my-header";
+        pub(crate) X_OTHER_HEADER = "x-other-header";
+    }
+
+    #[test]
+    fn declared_header_name() {
+        assert_eq!(X_MY_HEADER, "x-my-header");
+        assert_eq!(X_OTHER_HEADER.as_str(), "x-other-header");
+
+        let req = TestRequest::default()
+            .insert_header((X_MY_HEADER, "value"))
+            .to_http_request();
+        assert_eq!(req.headers().get(X_MY_HEADER).unwrap(), "value");
+    }
+
+    #[test]
+    fn invalid_header_name() {
+        // the same validation that `header_name!` runs at compile time
+        let res = panic::catch_unwind(|| HeaderName::from_static("x my header"));
+        assert!(res.is_err());
+
+        let res = panic::catch_unwind(|| HeaderName::from_static("X-My-Header"));
+        assert!(res.is_err());
+    }
+}
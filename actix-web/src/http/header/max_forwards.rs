@@ -0,0 +1,53 @@
+use super::MAX_FORWARDS;
+
+crate::http::header::common_header! {
+    /// `Max-Forwards` header, defined
+    /// in [RFC 7231 §5.1.2](https://datatracker.ietf.org/doc/html/rfc7231#section-5.1.2)
+    ///
+    /// The `Max-Forwards` header field provides a mechanism with the `TRACE` and `OPTIONS` request
+    /// methods to limit the number of times that the request is forwarded by proxies. A recipient
+    /// that receives a value of zero must not forward the request and should respond as its final
+    /// recipient instead.
+    ///
+    /// # ABNF
+    /// ```plain
+    /// Max-Forwards = 1*DIGIT
+    /// ```
+    ///
+    /// # Example Values
+    /// * `0`
+    /// * `10`
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::MaxForwards;
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// builder.insert_header(MaxForwards(10));
+    /// ```
+    (MaxForwards, MAX_FORWARDS) => [u32]
+
+    test_parse_and_format {
+        crate::http::header::common_header_test!(test1, [b"10"], Some(MaxForwards(10)));
+        crate::http::header::common_header_test!(test2, [b"0"], Some(MaxForwards(0)));
+        crate::http::header::common_header_test!(test3, [b"-1"], None);
+        crate::http::header::common_header_test!(test4, [b""], None);
+    }
+}
+
+impl MaxForwards {
+    /// Returns the value to send when forwarding the request, or `None` if the request must not
+    /// be forwarded.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::http::header::MaxForwards;
+    ///
+    /// assert_eq!(MaxForwards(2).decrement(), Some(MaxForwards(1)));
+    /// assert_eq!(MaxForwards(0).decrement(), None);
+    /// ```
+    pub fn decrement(self) -> Option<Self> {
+        self.0.checked_sub(1).map(MaxForwards)
+    }
+}
@@ -21,14 +21,19 @@ mod accept;
 mod accept_charset;
 mod accept_encoding;
 mod accept_language;
+mod accept_ranges;
+mod access_control;
 mod allow;
 mod cache_control;
+mod cache_status;
+mod content_digest;
 mod content_disposition;
 mod content_language;
+mod content_length;
 mod content_range;
 mod content_type;
+mod cookie;
 mod date;
-mod encoding;
 mod entity;
 mod etag;
 mod expires;
@@ -36,11 +41,21 @@ mod if_match;
 mod if_modified_since;
 mod if_none_match;
 mod if_range;
+mod if_schedule_tag_match;
 mod if_unmodified_since;
 mod last_modified;
 mod macros;
+mod max_forwards;
+mod nel;
+mod permissions_policy;
 mod preference;
 mod range;
+mod refresh;
+mod schedule_tag;
+mod sec_websocket_extensions;
+mod sec_websocket_protocol;
+mod sunset;
+mod x_forwarded_port;
 
 #[cfg(test)]
 pub(crate) use macros::common_header_test;
@@ -50,14 +65,23 @@ pub use self::accept::Accept;
 pub use self::accept_charset::AcceptCharset;
 pub use self::accept_encoding::AcceptEncoding;
 pub use self::accept_language::AcceptLanguage;
+pub use self::accept_ranges::AcceptRanges;
+pub use self::access_control::{
+    AccessControlAllowCredentials, AccessControlAllowHeaders, AccessControlAllowMethods,
+    AccessControlAllowOrigin, AccessControlExposeHeaders, AccessControlMaxAge,
+    AccessControlRequestHeaders, AccessControlRequestMethod,
+};
 pub use self::allow::Allow;
 pub use self::cache_control::{CacheControl, CacheDirective};
+pub use self::cache_status::{CacheForwardReason, CacheStatus, CacheStatusEntry};
+pub use self::content_digest::{ContentDigest, ContentMd5};
 pub use self::content_disposition::{ContentDisposition, DispositionParam, DispositionType};
 pub use self::content_language::ContentLanguage;
+pub use self::content_length::ContentLength;
 pub use self::content_range::{ContentRange, ContentRangeSpec};
 pub use self::content_type::ContentType;
+pub use self::cookie::{Cookie, DuplicateCookies};
 pub use self::date::Date;
-pub use self::encoding::Encoding;
 pub use self::entity::EntityTag;
 pub use self::etag::ETag;
 pub use self::expires::Expires;
@@ -65,10 +89,20 @@ pub use self::if_match::IfMatch;
 pub use self::if_modified_since::IfModifiedSince;
 pub use self::if_none_match::IfNoneMatch;
 pub use self::if_range::IfRange;
+pub use self::if_schedule_tag_match::IfScheduleTagMatch;
 pub use self::if_unmodified_since::IfUnmodifiedSince;
 pub use self::last_modified::LastModified;
+pub use self::max_forwards::MaxForwards;
+pub use self::nel::Nel;
+pub use self::permissions_policy::PermissionsPolicy;
 pub use self::preference::Preference;
 pub use self::range::{ByteRangeSpec, Range};
+pub use self::refresh::Refresh;
+pub use self::schedule_tag::ScheduleTag;
+pub use self::sec_websocket_extensions::{SecWebSocketExtensions, WebSocketExtension};
+pub use self::sec_websocket_protocol::SecWebSocketProtocol;
+pub use self::sunset::Sunset;
+pub use self::x_forwarded_port::XForwardedPort;
 
 /// Format writer ([`fmt::Write`]) for a [`BytesMut`].
 #[derive(Debug, Default)]
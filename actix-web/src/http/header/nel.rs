@@ -0,0 +1,177 @@
+use std::fmt::{self, Write as _};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Header, HeaderName, HeaderValue, InvalidHeaderValue, TryIntoHeaderValue, Writer, NEL,
+};
+use crate::{error::ParseError, HttpMessage};
+
+/// `NEL` header, defined in the [Network Error Logging] specification.
+///
+/// The `NEL` response header field registers a policy asking the user agent to report failed (and
+/// optionally successful) requests to the origin using the [Reporting API] endpoint group named by
+/// `report_to`. The policy is cached for `max_age` seconds; a `max_age` of zero removes it.
+///
+/// # ABNF
+/// ```plain
+/// NEL = json-field-value
+/// ```
+///
+/// # Example Values
+/// * `{"report_to":"default","max_age":2592000}`
+/// * `{"report_to":"network-errors","max_age":86400,"include_subdomains":true}`
+///
+/// # Examples
+/// ```
+/// use actix_web::HttpResponse;
+/// use actix_web::http::header::Nel;
+///
+/// let mut builder = HttpResponse::Ok();
+/// builder.insert_header(Nel::new("default", 2_592_000));
+/// ```
+///
+/// ```
+/// use actix_web::http::header::Nel;
+///
+/// let nel = Nel {
+///     include_subdomains: true,
+///     failure_fraction: Some(0.5),
+///     ..Nel::new("network-errors", 86_400)
+/// };
+///
+/// assert_eq!(
+///     nel.to_string(),
+///     r#"{"report_to":"network-errors","max_age":86400,"include_subdomains":true,"failure_fraction":0.5}"#,
+/// );
+/// ```
+///
+/// [Network Error Logging]: https://www.w3.org/TR/network-error-logging/
+/// [Reporting API]: https://www.w3.org/TR/reporting-1/
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Nel {
+    /// Name of the endpoint group that reports are delivered to.
+    pub report_to: String,
+
+    /// Lifetime of the policy, in seconds.
+    pub max_age: u64,
+
+    /// Whether the policy also applies to all subdomains of the origin.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub include_subdomains: bool,
+
+    /// Sampling rate, between 0.0 and 1.0, for reports about successful requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub success_fraction: Option<f64>,
+
+    /// Sampling rate, between 0.0 and 1.0, for reports about failed requests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_fraction: Option<f64>,
+}
+
+fn is_false(val: &bool) -> bool {
+    !*val
+}
+
+impl Nel {
+    /// Constructs a policy reporting to the `report_to` endpoint group for `max_age` seconds.
+    pub fn new(report_to: impl Into<String>, max_age: u64) -> Self {
+        Self {
+            report_to: report_to.into(),
+            max_age,
+            include_subdomains: false,
+            success_fraction: None,
+            failure_fraction: None,
+        }
+    }
+
+    /// Constructs a policy that removes any cached policy for the origin.
+    pub fn remove(report_to: impl Into<String>) -> Self {
+        Self::new(report_to, 0)
+    }
+}
+
+impl fmt::Display for Nel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+impl TryIntoHeaderValue for Nel {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        let mut writer = Writer::new();
+        let _ = write!(&mut writer, "{}", self);
+        HeaderValue::from_maybe_shared(writer.take())
+    }
+}
+
+impl Header for Nel {
+    fn name() -> HeaderName {
+        NEL
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        let value = msg.headers().get(NEL).ok_or(ParseError::Header)?;
+        serde_json::from_slice(value.as_bytes()).map_err(|_| ParseError::Header)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    #[test]
+    fn format() {
+        let nel = Nel::new("default", 2_592_000);
+        assert_eq!(
+            nel.try_into_value().unwrap(),
+            r#"{"report_to":"default","max_age":2592000}"#
+        );
+
+        let nel = Nel {
+            include_subdomains: true,
+            success_fraction: Some(0.0),
+            failure_fraction: Some(1.0),
+            ..Nel::new("network-errors", 60)
+        };
+        assert_eq!(
+            nel.to_string(),
+            r#"{"report_to":"network-errors","max_age":60,"include_subdomains":true,"success_fraction":0.0,"failure_fraction":1.0}"#
+        );
+
+        assert_eq!(
+            Nel::remove("default").to_string(),
+            r#"{"report_to":"default","max_age":0}"#
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let req = TestRequest::default()
+            .insert_header((NEL, r#"{"report_to": "default", "max_age": 2592000}"#))
+            .to_http_request();
+        assert_eq!(Nel::parse(&req).unwrap(), Nel::new("default", 2_592_000));
+
+        let nel = Nel {
+            include_subdomains: true,
+            failure_fraction: Some(0.25),
+            ..Nel::new("errors", 86_400)
+        };
+        let req = TestRequest::default()
+            .insert_header(nel.clone())
+            .to_http_request();
+        assert_eq!(Nel::parse(&req).unwrap(), nel);
+
+        let req = TestRequest::default().to_http_request();
+        assert!(Nel::parse(&req).is_err());
+
+        let req = TestRequest::default()
+            .insert_header((NEL, r#"{"max_age": 60}"#))
+            .to_http_request();
+        assert!(Nel::parse(&req).is_err());
+    }
+}
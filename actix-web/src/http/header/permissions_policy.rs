@@ -0,0 +1,263 @@
+use std::fmt::{self, Write as _};
+
+use super::{
+    structured::{BareItem, Dictionary, InnerList, Item, Member},
+    Header, HeaderName, HeaderValue, InvalidHeaderValue, TryIntoHeaderValue, Writer,
+    PERMISSIONS_POLICY,
+};
+use crate::{error::ParseError, HttpMessage};
+
+/// `Permissions-Policy` header, defined in the [Permissions Policy] specification.
+///
+/// The `Permissions-Policy` response header field controls which origins may use browser
+/// features, such as `geolocation` or `camera`, in the document and its frames. Each feature maps
+/// to an allowlist of origins; an empty allowlist, written `()`, disables the feature entirely.
+///
+/// Allowlist entries `*`, `self`, and `src` are keywords; all other entries are origins.
+///
+/// # ABNF
+/// ```plain
+/// Permissions-Policy = sf-dictionary
+/// ```
+///
+/// # Example Values
+/// * `geolocation=(), camera=()`
+/// * `fullscreen=(self "https://example.com"), interest-cohort=()`
+///
+/// # Examples
+/// ```
+/// use actix_web::HttpResponse;
+/// use actix_web::http::header::PermissionsPolicy;
+///
+/// let mut builder = HttpResponse::Ok();
+/// builder.insert_header(
+///     PermissionsPolicy::deny_all(["camera", "microphone"])
+///         .allow("fullscreen", ["self", "https://example.com"]),
+/// );
+/// ```
+///
+/// [Permissions Policy]: https://www.w3.org/TR/permissions-policy-1/
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PermissionsPolicy {
+    directives: Vec<(String, Vec<String>)>,
+}
+
+impl PermissionsPolicy {
+    /// Constructs an empty policy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Constructs a policy that disables each of the given features.
+    pub fn deny_all<I>(features: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        features
+            .into_iter()
+            .fold(Self::new(), |policy, feature| policy.deny(feature))
+    }
+
+    /// Constructs a policy that opts out of interest-based advertising, by disabling the
+    /// `interest-cohort` (FLoC) and `browsing-topics` (Topics API) features.
+    pub fn interest_cohort_opt_out() -> Self {
+        Self::deny_all(["interest-cohort", "browsing-topics"])
+    }
+
+    /// Allows `feature` for the given allowlist entries, replacing any previous directive for it.
+    pub fn allow<I>(mut self, feature: impl Into<String>, allowlist: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        let feature = feature.into();
+        let allowlist = allowlist.into_iter().map(Into::into).collect();
+
+        match self
+            .directives
+            .iter_mut()
+            .find(|(name, _)| *name == feature)
+        {
+            Some((_, list)) => *list = allowlist,
+            None => self.directives.push((feature, allowlist)),
+        }
+
+        self
+    }
+
+    /// Disables `feature`, replacing any previous directive for it.
+    pub fn deny(self, feature: impl Into<String>) -> Self {
+        self.allow(feature, Vec::<String>::new())
+    }
+
+    /// Returns the allowlist of `feature`, if the policy has a directive for it.
+    pub fn allowlist(&self, feature: &str) -> Option<&[String]> {
+        self.directives
+            .iter()
+            .find(|(name, _)| name == feature)
+            .map(|(_, list)| list.as_slice())
+    }
+
+    /// Returns true if the policy disables `feature`.
+    pub fn is_denied(&self, feature: &str) -> bool {
+        self.allowlist(feature).map_or(false, <[String]>::is_empty)
+    }
+
+    fn parse_value(value: &[u8]) -> Result<Self, ParseError> {
+        let dict = Dictionary::parse(value)?;
+        let mut policy = Self::new();
+
+        for (feature, member) in dict.iter() {
+            let allowlist = match member {
+                Member::Item(item) => vec![allowlist_entry(item)?],
+                Member::InnerList(list) => list
+                    .items
+                    .iter()
+                    .map(allowlist_entry)
+                    .collect::<Result<_, _>>()?,
+            };
+
+            policy = policy.allow(feature, allowlist);
+        }
+
+        Ok(policy)
+    }
+}
+
+fn allowlist_entry(item: &Item) -> Result<String, ParseError> {
+    item.bare_item
+        .as_str()
+        .map(ToOwned::to_owned)
+        .ok_or(ParseError::Header)
+}
+
+impl fmt::Display for PermissionsPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut dict = Dictionary::new();
+
+        for (feature, allowlist) in &self.directives {
+            let items = allowlist
+                .iter()
+                .map(|entry| {
+                    Item::new(match entry.as_str() {
+                        "*" | "self" | "src" => BareItem::Token(entry.clone()),
+                        origin => BareItem::String(origin.to_owned()),
+                    })
+                })
+                .collect();
+
+            dict.insert(
+                feature.clone(),
+                InnerList {
+                    items,
+                    ..InnerList::default()
+                },
+            );
+        }
+
+        write!(f, "{}", dict)
+    }
+}
+
+impl TryIntoHeaderValue for PermissionsPolicy {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        let mut writer = Writer::new();
+        let _ = write!(&mut writer, "{}", self);
+        HeaderValue::from_maybe_shared(writer.take())
+    }
+}
+
+impl Header for PermissionsPolicy {
+    fn name() -> HeaderName {
+        PERMISSIONS_POLICY
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        // dictionary members may be split across multiple header lines
+        let mut value = Vec::new();
+
+        for (idx, line) in msg.headers().get_all(PERMISSIONS_POLICY).enumerate() {
+            if idx > 0 {
+                value.push(b',');
+            }
+
+            value.extend_from_slice(line.as_bytes());
+        }
+
+        if value.is_empty() {
+            return Err(ParseError::Header);
+        }
+
+        Self::parse_value(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    #[test]
+    fn deny_all() {
+        let policy = PermissionsPolicy::deny_all(["geolocation", "camera"]);
+        assert!(policy.is_denied("geolocation"));
+        assert!(policy.is_denied("camera"));
+        assert!(!policy.is_denied("microphone"));
+
+        assert_eq!(
+            policy.try_into_value().unwrap(),
+            "geolocation=(), camera=()"
+        );
+    }
+
+    #[test]
+    fn interest_cohort_opt_out() {
+        let policy = PermissionsPolicy::interest_cohort_opt_out();
+        assert_eq!(policy.to_string(), "interest-cohort=(), browsing-topics=()");
+    }
+
+    #[test]
+    fn allowlists() {
+        let policy = PermissionsPolicy::deny_all(["camera", "fullscreen"])
+            .allow("fullscreen", ["self", "https://example.com"])
+            .allow("autoplay", ["*"]);
+
+        assert!(!policy.is_denied("fullscreen"));
+        assert_eq!(
+            policy.allowlist("fullscreen").unwrap(),
+            ["self", "https://example.com"]
+        );
+
+        assert_eq!(
+            policy.to_string(),
+            r#"camera=(), fullscreen=(self "https://example.com"), autoplay=(*)"#
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let req = TestRequest::default()
+            .append_header((PERMISSIONS_POLICY, "geolocation=(), autoplay=*"))
+            .append_header((PERMISSIONS_POLICY, r#"fullscreen=(self "https://a.com")"#))
+            .to_http_request();
+
+        let policy = PermissionsPolicy::parse(&req).unwrap();
+        assert!(policy.is_denied("geolocation"));
+        assert_eq!(policy.allowlist("autoplay").unwrap(), ["*"]);
+        assert_eq!(
+            policy.allowlist("fullscreen").unwrap(),
+            ["self", "https://a.com"]
+        );
+
+        let req = TestRequest::default().to_http_request();
+        assert!(PermissionsPolicy::parse(&req).is_err());
+
+        let req = TestRequest::default()
+            .insert_header((PERMISSIONS_POLICY, "geolocation=(1)"))
+            .to_http_request();
+        assert!(PermissionsPolicy::parse(&req).is_err());
+    }
+}
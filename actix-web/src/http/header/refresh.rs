@@ -0,0 +1,212 @@
+use std::{
+    fmt::{self, Write as _},
+    str,
+    time::Duration,
+};
+
+use super::{
+    from_one_raw_str, Header, HeaderName, HeaderValue, InvalidHeaderValue, TryIntoHeaderValue,
+    Writer, REFRESH,
+};
+use crate::{error::ParseError, HttpMessage};
+
+/// `Refresh` header, defined
+/// in the [HTML standard](https://html.spec.whatwg.org/multipage/document-lifecycle.html#the-refresh-header)
+///
+/// The `Refresh` header field makes browsers reload the page after a delay, or load another URL
+/// instead if one is given. It is often used to show a page briefly before redirecting.
+///
+/// The delay is sent in whole seconds; fractions of a second are dropped. When parsing, a `url=`
+/// prefix and quotes around the URL are optional, as browsers accept both forms.
+///
+/// # ABNF
+/// ```plain
+/// Refresh = 1*DIGIT [ *WSP ( ";" / "," ) *WSP [ "url" *WSP "=" *WSP ] URL ]
+/// ```
+///
+/// # Example Values
+/// * `5`
+/// * `0; url=/login`
+/// * `3; url="https://example.com/next"`
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use actix_web::{http::header::Refresh, HttpResponse};
+///
+/// let mut builder = HttpResponse::Ok();
+/// builder.insert_header(Refresh::redirect(Duration::from_secs(5), "/target"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Refresh {
+    /// Time to wait before refreshing.
+    pub delay: Duration,
+
+    /// URL to load instead of reloading the page.
+    pub url: Option<String>,
+}
+
+impl Refresh {
+    /// Constructs a header that reloads the page after `delay`.
+    pub fn reload(delay: Duration) -> Self {
+        Self { delay, url: None }
+    }
+
+    /// Constructs a header that loads `url` after `delay`.
+    pub fn redirect(delay: Duration, url: impl Into<String>) -> Self {
+        Self {
+            delay,
+            url: Some(url.into()),
+        }
+    }
+}
+
+impl fmt::Display for Refresh {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.delay.as_secs())?;
+
+        if let Some(url) = &self.url {
+            write!(f, "; url={}", url)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl str::FromStr for Refresh {
+    type Err = ParseError;
+
+    fn from_str(val: &str) -> Result<Self, Self::Err> {
+        let val = val.trim();
+
+        let digits = val
+            .find(|ch: char| !ch.is_ascii_digit())
+            .unwrap_or(val.len());
+        let secs = val[..digits]
+            .parse::<u64>()
+            .map_err(|_| ParseError::Header)?;
+
+        // browsers ignore a fractional part of the delay
+        let mut rest = &val[digits..];
+        if let Some(fraction) = rest.strip_prefix('.') {
+            rest = fraction.trim_start_matches(|ch: char| ch.is_ascii_digit());
+        }
+
+        let rest = rest.trim_start();
+
+        let url = if rest.is_empty() {
+            None
+        } else {
+            let url = rest
+                .strip_prefix(|ch| ch == ';' || ch == ',')
+                .ok_or(ParseError::Header)?
+                .trim_start();
+
+            let url = match url.get(..3) {
+                Some(key) if key.eq_ignore_ascii_case("url") => {
+                    match url[3..].trim_start().strip_prefix('=') {
+                        Some(url) => url.trim_start(),
+                        None => url,
+                    }
+                }
+                _ => url,
+            };
+
+            let url = match url.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let url = &url[1..];
+                    url.find(quote).map_or(url, |end| &url[..end])
+                }
+                _ => url,
+            };
+
+            Some(url.trim_end().to_owned()).filter(|url| !url.is_empty())
+        };
+
+        Ok(Refresh {
+            delay: Duration::from_secs(secs),
+            url,
+        })
+    }
+}
+
+impl TryIntoHeaderValue for Refresh {
+    type Error = InvalidHeaderValue;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        let mut writer = Writer::new();
+        let _ = write!(&mut writer, "{}", self);
+        HeaderValue::from_maybe_shared(writer.take())
+    }
+}
+
+impl Header for Refresh {
+    fn name() -> HeaderName {
+        REFRESH
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        from_one_raw_str(msg.headers().get(Self::name()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    fn parse(raw: &str) -> Result<Refresh, ParseError> {
+        let req = TestRequest::default()
+            .insert_header((REFRESH, raw))
+            .to_http_request();
+        Refresh::parse(&req)
+    }
+
+    #[test]
+    fn parse_and_format() {
+        let refresh = parse("0; url=/x").unwrap();
+        assert_eq!(refresh, Refresh::redirect(Duration::ZERO, "/x"));
+        assert_eq!(refresh.try_into_value().unwrap(), "0; url=/x");
+
+        let refresh = parse("5").unwrap();
+        assert_eq!(refresh, Refresh::reload(Duration::from_secs(5)));
+        assert_eq!(refresh.try_into_value().unwrap(), "5");
+
+        assert_eq!(
+            Refresh::redirect(Duration::from_millis(2500), "/next").to_string(),
+            "2; url=/next"
+        );
+    }
+
+    #[test]
+    fn parse_browser_forms() {
+        for raw in [
+            "3;url=/next",
+            "3, URL = /next",
+            "3; url='/next'",
+            "3; url=\"/next\"",
+            "3.75; /next",
+            " 3 ; url=/next ",
+        ] {
+            assert_eq!(
+                parse(raw).unwrap(),
+                Refresh::redirect(Duration::from_secs(3), "/next"),
+                "{}",
+                raw
+            );
+        }
+
+        assert_eq!(
+            parse("3; url=").unwrap(),
+            Refresh::reload(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn parse_malformed() {
+        for raw in ["", "soon", "-1", "5 url=/x", "5x", "; url=/x"] {
+            assert!(parse(raw).is_err(), "{:?}", raw);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use super::{common_header, EntityTag, SCHEDULE_TAG};
+
+common_header! {
+    /// `Schedule-Tag` header, defined
+    /// in [RFC 6638 §3.3](https://datatracker.ietf.org/doc/html/rfc6638#section-3.3)
+    ///
+    /// The `Schedule-Tag` header field in a response provides the current schedule tag of a
+    /// CalDAV calendar object resource. Unlike an entity-tag, the schedule tag only changes when
+    /// the resource is modified in a way that is significant to scheduling, so that attendee
+    /// replies do not invalidate an organizer's pending changes. Schedule tags are always strong.
+    ///
+    /// Clients send a previously received schedule tag back in
+    /// [`If-Schedule-Tag-Match`](super::IfScheduleTagMatch).
+    ///
+    /// # ABNF
+    /// ```plain
+    /// Schedule-Tag = opaque-tag
+    /// ```
+    ///
+    /// # Example Values
+    /// * `"12345"`
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::{EntityTag, ScheduleTag};
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// builder.insert_header(ScheduleTag(EntityTag::new_strong("12345".to_owned())));
+    /// ```
+    (ScheduleTag, SCHEDULE_TAG) => [EntityTag]
+
+    test_parse_and_format {
+        crate::http::header::common_header_test!(test1,
+            vec![b"\"12345\""],
+            Some(ScheduleTag(EntityTag::new_strong("12345".to_owned()))));
+        crate::http::header::common_header_test!(test2,
+            vec![b"no-dquotes"],
+            None::<ScheduleTag>);
+    }
+}
@@ -0,0 +1,186 @@
+use std::{fmt, str};
+
+use super::{common_header, SEC_WEBSOCKET_EXTENSIONS};
+use crate::error::ParseError;
+
+common_header! {
+    /// `Sec-WebSocket-Extensions` header, defined in [RFC 6455 §11.3.2].
+    ///
+    /// The `Sec-WebSocket-Extensions` header field is used in the opening handshake to negotiate
+    /// protocol-level extensions, such as [`permessage-deflate`], along with their parameters.
+    ///
+    /// # ABNF
+    /// ```plain
+    /// Sec-WebSocket-Extensions = 1#extension
+    /// extension = extension-token *( ";" extension-param )
+    /// extension-param = token [ "=" (token / quoted-string) ]
+    /// ```
+    ///
+    /// # Example Values
+    /// * `permessage-deflate; client_max_window_bits`
+    /// * `permessage-deflate; server_max_window_bits=10, x-webkit-deflate-frame`
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::{SecWebSocketExtensions, WebSocketExtension};
+    ///
+    /// let mut builder = HttpResponse::SwitchingProtocols();
+    /// builder.insert_header(SecWebSocketExtensions(vec![
+    ///     WebSocketExtension::new("permessage-deflate")
+    ///         .param("server_no_context_takeover", None),
+    /// ]));
+    /// ```
+    ///
+    /// [RFC 6455 §11.3.2]: https://datatracker.ietf.org/doc/html/rfc6455#section-11.3.2
+    /// [`permessage-deflate`]: https://datatracker.ietf.org/doc/html/rfc7692
+    (SecWebSocketExtensions, SEC_WEBSOCKET_EXTENSIONS) => (WebSocketExtension)+
+
+    test_parse_and_format {
+        common_header_test!(
+            test1,
+            vec![b"permessage-deflate; client_max_window_bits"],
+            Some(SecWebSocketExtensions(vec![
+                WebSocketExtension::new("permessage-deflate").param("client_max_window_bits", None),
+            ]))
+        );
+
+        common_header_test!(
+            test2,
+            vec![b"permessage-deflate; server_max_window_bits=10, x-webkit-deflate-frame"],
+            Some(SecWebSocketExtensions(vec![
+                WebSocketExtension::new("permessage-deflate")
+                    .param("server_max_window_bits", Some("10")),
+                WebSocketExtension::new("x-webkit-deflate-frame"),
+            ]))
+        );
+
+        common_header_test!(test_empty, vec![b""], None);
+
+        #[test]
+        fn quoted_param_values() {
+            let ext = "foo; bar=\"baz\"".parse::<WebSocketExtension>().unwrap();
+            assert_eq!(ext.name(), "foo");
+            assert_eq!(ext.get_param("bar"), Some(Some("baz")));
+            assert_eq!(ext.get_param("qux"), None);
+
+            let ext = WebSocketExtension::new("foo").param("bar", Some("a b"));
+            assert_eq!(ext.to_string(), "foo; bar=\"a b\"");
+        }
+
+        #[test]
+        fn invalid_extensions() {
+            assert!("".parse::<WebSocketExtension>().is_err());
+            assert!("; foo".parse::<WebSocketExtension>().is_err());
+            assert!("foo; =bar".parse::<WebSocketExtension>().is_err());
+            assert!("fo o".parse::<WebSocketExtension>().is_err());
+        }
+    }
+}
+
+/// A single extension, with its parameters, in a [`SecWebSocketExtensions`] header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSocketExtension {
+    name: String,
+    params: Vec<(String, Option<String>)>,
+}
+
+impl WebSocketExtension {
+    /// Constructs a new extension with no parameters.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Adds a parameter, with an optional value, to the extension.
+    pub fn param(mut self, name: impl Into<String>, value: Option<&str>) -> Self {
+        self.params.push((name.into(), value.map(str::to_owned)));
+        self
+    }
+
+    /// Returns the extension token.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the extension's parameters, in the order they were given.
+    pub fn params(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_deref()))
+    }
+
+    /// Looks up a parameter by name.
+    ///
+    /// Returns `Some(None)` if the parameter is present without a value, and `None` if the
+    /// parameter is absent.
+    pub fn get_param(&self, name: &str) -> Option<Option<&str>> {
+        self.params()
+            .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+}
+
+impl fmt::Display for WebSocketExtension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.name)?;
+
+        for (name, value) in &self.params {
+            write!(f, "; {}", name)?;
+
+            match value {
+                Some(value) if is_token(value) => write!(f, "={}", value)?,
+                Some(value) => write!(f, "=\"{}\"", value)?,
+                None => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl str::FromStr for WebSocketExtension {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(';').map(str::trim);
+
+        let name = parts
+            .next()
+            .filter(|name| is_token(name))
+            .ok_or(ParseError::Header)?;
+        let mut ext = WebSocketExtension::new(name);
+
+        for param in parts {
+            let (name, value) = match param.split_once('=') {
+                Some((name, value)) => {
+                    let value = value.trim();
+                    let value = value
+                        .strip_prefix('"')
+                        .and_then(|value| value.strip_suffix('"'))
+                        .unwrap_or(value);
+
+                    (name.trim(), Some(value))
+                }
+                None => (param, None),
+            };
+
+            if !is_token(name) {
+                return Err(ParseError::Header);
+            }
+
+            ext = ext.param(name, value);
+        }
+
+        Ok(ext)
+    }
+}
+
+/// Returns true if `s` is a non-empty RFC 7230 token.
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
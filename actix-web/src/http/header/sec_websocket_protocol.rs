@@ -0,0 +1,76 @@
+use super::{common_header, SEC_WEBSOCKET_PROTOCOL};
+
+common_header! {
+    /// `Sec-WebSocket-Protocol` header, defined in [RFC 6455 §11.3.4].
+    ///
+    /// In a client's opening handshake, the `Sec-WebSocket-Protocol` header field lists the
+    /// subprotocols the client wishes to speak, ordered by preference. In the server's response,
+    /// it contains the single subprotocol the server selected.
+    ///
+    /// # ABNF
+    /// ```plain
+    /// Sec-WebSocket-Protocol-Client = 1#token
+    /// Sec-WebSocket-Protocol-Server = token
+    /// ```
+    ///
+    /// # Example Values
+    /// * `chat, superchat`
+    /// * `graphql-ws`
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::SecWebSocketProtocol;
+    ///
+    /// let mut builder = HttpResponse::SwitchingProtocols();
+    /// builder.insert_header(SecWebSocketProtocol(vec!["chat".to_owned()]));
+    /// ```
+    ///
+    /// [RFC 6455 §11.3.4]: https://datatracker.ietf.org/doc/html/rfc6455#section-11.3.4
+    (SecWebSocketProtocol, SEC_WEBSOCKET_PROTOCOL) => (String)+
+
+    test_parse_and_format {
+        common_header_test!(test1, [b"chat, superchat"]);
+        common_header_test!(test2, [b"graphql-ws"]);
+        common_header_test!(test_empty, vec![b""], None);
+
+        #[test]
+        fn select_supported_protocol() {
+            let offered = SecWebSocketProtocol(vec![
+                "v2.chat".to_owned(),
+                "v1.chat".to_owned(),
+                "superchat".to_owned(),
+            ]);
+
+            // client preference order wins
+            assert_eq!(offered.select(&["v1.chat", "v2.chat"]), Some("v2.chat"));
+            assert_eq!(offered.select(&["superchat"]), Some("superchat"));
+
+            // protocol tokens are case-sensitive
+            assert_eq!(offered.select(&["SuperChat"]), None);
+            assert_eq!(offered.select(&[]), None);
+        }
+    }
+}
+
+impl SecWebSocketProtocol {
+    /// Selects the first subprotocol offered by the client that is also in `supported`.
+    ///
+    /// Subprotocols are considered in the order they were offered by the client, which is its
+    /// order of preference. Returns `None` if no subprotocol is supported by both sides, in which
+    /// case the handshake response should not include this header.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::http::header::SecWebSocketProtocol;
+    ///
+    /// let offered = SecWebSocketProtocol(vec!["chat".to_owned(), "superchat".to_owned()]);
+    /// assert_eq!(offered.select(&["superchat", "chat"]), Some("chat"));
+    /// ```
+    pub fn select<'a>(&'a self, supported: &[&str]) -> Option<&'a str> {
+        self.0
+            .iter()
+            .map(String::as_str)
+            .find(|protocol| supported.contains(protocol))
+    }
+}
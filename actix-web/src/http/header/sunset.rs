@@ -0,0 +1,39 @@
+use super::{HttpDate, SUNSET};
+
+crate::http::header::common_header! {
+    /// `Sunset` header, defined
+    /// in [RFC 8594 §3](https://datatracker.ietf.org/doc/html/rfc8594#section-3)
+    ///
+    /// The `Sunset` header field gives the date/time after which the
+    /// resource is expected to become unresponsive.
+    ///
+    /// It is commonly sent alongside a `Deprecation` header, to signal
+    /// when a deprecated resource will be removed.
+    ///
+    /// # ABNF
+    /// ```plain
+    /// Sunset = HTTP-date
+    /// ```
+    ///
+    /// # Example Values
+    /// * `Sat, 31 Dec 2022 23:59:59 GMT`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{SystemTime, Duration};
+    /// use actix_web::HttpResponse;
+    /// use actix_web::http::header::Sunset;
+    ///
+    /// let mut builder = HttpResponse::Ok();
+    /// let removal = SystemTime::now() + Duration::from_secs(60 * 60 * 24 * 90);
+    /// builder.insert_header(
+    ///     Sunset(removal.into())
+    /// );
+    /// ```
+    (Sunset, SUNSET) => [HttpDate]
+
+    test_parse_and_format {
+        crate::http::header::common_header_test!(test1, [b"Mon, 31 Dec 2018 23:59:59 GMT"]);
+    }
+}
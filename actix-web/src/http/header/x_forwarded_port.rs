@@ -0,0 +1,105 @@
+use std::{convert::Infallible, fmt, str};
+
+use super::{Header, HeaderName, HeaderValue, TryIntoHeaderValue, X_FORWARDED_PORT};
+use crate::{error::ParseError, HttpMessage};
+
+/// `X-Forwarded-Port` request header, a de-facto standard set by proxies.
+///
+/// Identifies the port the client connected to on the proxy, which, together with
+/// `X-Forwarded-Host` and `X-Forwarded-Proto`, allows the public authority of a request to be
+/// reconstructed behind a proxy.
+///
+/// When a request passes through several proxies, the header may hold a comma-separated list of
+/// ports, one per proxy, of which the first is the one the client connected to. Parsing takes that
+/// first value.
+///
+/// # Example Values
+/// * `443`
+/// * `8443, 80`
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::{Header as _, XForwardedPort}, test::TestRequest};
+///
+/// let req = TestRequest::default()
+///     .insert_header(("x-forwarded-port", "8443, 80"))
+///     .to_http_request();
+///
+/// assert_eq!(XForwardedPort::parse(&req).unwrap(), XForwardedPort(8443));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct XForwardedPort(pub u16);
+
+impl Header for XForwardedPort {
+    fn name() -> HeaderName {
+        X_FORWARDED_PORT
+    }
+
+    fn parse<M: HttpMessage>(msg: &M) -> Result<Self, ParseError> {
+        let val = msg.headers().get(Self::name()).ok_or(ParseError::Header)?;
+        let val = str::from_utf8(val.as_bytes())?;
+
+        let first = val.split(',').next().unwrap_or_default().trim();
+
+        first
+            .parse()
+            .map(XForwardedPort)
+            .map_err(|_| ParseError::Header)
+    }
+}
+
+impl fmt::Display for XForwardedPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl TryIntoHeaderValue for XForwardedPort {
+    type Error = Infallible;
+
+    fn try_into_value(self) -> Result<HeaderValue, Self::Error> {
+        Ok(HeaderValue::from(self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    fn parse(val: &str) -> Result<XForwardedPort, ParseError> {
+        let req = TestRequest::default()
+            .insert_header((X_FORWARDED_PORT, val))
+            .to_http_request();
+
+        XForwardedPort::parse(&req)
+    }
+
+    #[test]
+    fn single_port() {
+        assert_eq!(parse("443").unwrap(), XForwardedPort(443));
+        assert_eq!(parse(" 8080 ").unwrap(), XForwardedPort(8080));
+        assert_eq!(
+            XForwardedPort(8443).try_into_value().unwrap(),
+            HeaderValue::from_static("8443")
+        );
+    }
+
+    #[test]
+    fn first_of_list() {
+        assert_eq!(parse("8443, 80, 8080").unwrap(), XForwardedPort(8443));
+        assert_eq!(parse("443,80").unwrap(), XForwardedPort(443));
+    }
+
+    #[test]
+    fn invalid_port() {
+        assert!(parse("").is_err());
+        assert!(parse("https").is_err());
+        assert!(parse("65536").is_err());
+        assert!(parse("-1").is_err());
+        assert!(parse(", 443").is_err());
+
+        let req = TestRequest::default().to_http_request();
+        assert!(XForwardedPort::parse(&req).is_err());
+    }
+}
@@ -1,5 +1,8 @@
 //! Various HTTP related types.
 
 pub mod header;
+pub mod uri;
 
-pub use actix_http::{uri, ConnectionType, Error, KeepAlive, Method, StatusCode, Uri, Version};
+pub use actix_http::{ConnectionType, Error, KeepAlive, Method, StatusCode, Uri, Version};
+
+pub use self::header::Encoding;
@@ -0,0 +1,283 @@
+//! URI types and percent-encoding for generated URLs.
+//!
+//! [`PathEncoder`] is used wherever Actix Web generates a URL, such as in
+//! [`HttpRequest::url_for`](crate::HttpRequest::url_for) and the `Location` header of
+//! [`Redirect`](crate::web::Redirect) responses, and can be used to encode URLs built by hand in
+//! the same way.
+
+use std::{borrow::Cow, ops::Range};
+
+pub use actix_http::uri::*;
+
+/// The characters left unencoded by a [`PathEncoder`], chosen by the URL component being encoded.
+///
+/// Unreserved characters (`A-Z a-z 0-9 - . _ ~`) are never encoded. All sets encode `%`,
+/// whitespace, control characters, and non-ASCII characters, which are encoded as their UTF-8
+/// bytes. The other characters of each set follow the grammar of [RFC 3986].
+///
+/// [RFC 3986]: https://datatracker.ietf.org/doc/html/rfc3986#section-3
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EncodeSet {
+    /// A single path segment.
+    ///
+    /// Sub-delimiters (`! $ & ' ( ) * + , ; =`), `:`, and `@` are kept. `/`, `?`, and `#` are
+    /// encoded, so the result is always one segment.
+    PathSegment,
+
+    /// A path of one or more segments.
+    ///
+    /// As [`PathSegment`](Self::PathSegment), but `/` is kept.
+    Path,
+
+    /// A key or value in a query string.
+    ///
+    /// As [`Path`](Self::Path), with `?` also kept, but `&`, `=`, `+`, and `;` are encoded so that
+    /// the result can be used as one side of a `key=value` pair.
+    QueryComponent,
+
+    /// A fragment.
+    ///
+    /// As [`Path`](Self::Path), with `?` also kept.
+    Fragment,
+
+    /// A complete URL or relative reference.
+    ///
+    /// Reserved characters (`: / ? # @` and sub-delimiters) are kept so that the structure of the
+    /// URL is unchanged. `[` and `]` are only kept in the authority, where they enclose IPv6
+    /// addresses.
+    Url,
+}
+
+impl EncodeSet {
+    /// Returns true if `byte` is kept as-is by this set.
+    fn keeps(self, byte: u8) -> bool {
+        let sub_delim = matches!(
+            byte,
+            b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'='
+        );
+
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => true,
+
+            b':' | b'@' => true,
+            b'/' => self != Self::PathSegment,
+            b'?' => matches!(self, Self::QueryComponent | Self::Fragment | Self::Url),
+            b'#' => self == Self::Url,
+
+            b'&' | b'=' | b'+' | b';' if self == Self::QueryComponent => false,
+            _ => sub_delim,
+        }
+    }
+}
+
+/// Percent-encodes URL components using an [`EncodeSet`].
+///
+/// By default, every `%` is encoded, so encoding is safe for arbitrary input such as user-provided
+/// names. In [`raw`](Self::raw) mode, valid `%XX` sequences are passed through so that input that
+/// is already partly encoded is not encoded twice.
+///
+/// # Examples
+/// ```
+/// use actix_web::http::uri::{EncodeSet, PathEncoder};
+///
+/// let segment = PathEncoder::new(EncodeSet::PathSegment);
+/// assert_eq!(segment.encode("a/b c"), "a%2Fb%20c");
+/// assert_eq!(segment.encode("100%"), "100%25");
+///
+/// let query = PathEncoder::new(EncodeSet::QueryComponent);
+/// assert_eq!(query.encode("fish & chips"), "fish%20%26%20chips");
+///
+/// let path = PathEncoder::new(EncodeSet::Path).raw();
+/// assert_eq!(path.encode("/caf%C3%A9/ü"), "/caf%C3%A9/%C3%BC");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathEncoder {
+    set: EncodeSet,
+    raw: bool,
+}
+
+impl PathEncoder {
+    /// Constructs an encoder for the URL component described by `set`.
+    pub const fn new(set: EncodeSet) -> Self {
+        Self { set, raw: false }
+    }
+
+    /// Passes valid `%XX` sequences through instead of encoding their `%`.
+    ///
+    /// A `%` that is not followed by two hex digits is still encoded.
+    pub const fn raw(mut self) -> Self {
+        self.raw = true;
+        self
+    }
+
+    /// Returns the set of characters this encoder keeps.
+    pub const fn encode_set(&self) -> EncodeSet {
+        self.set
+    }
+
+    /// Percent-encodes `input`, borrowing it if no characters need to be encoded.
+    ///
+    /// Encoded bytes are written as `%` followed by two upper case hex digits.
+    pub fn encode<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        let bytes = input.as_bytes();
+        let authority = self.authority(input);
+
+        let first = match (0..bytes.len()).find(|&idx| !self.keeps(bytes, idx, &authority)) {
+            Some(idx) => idx,
+            None => return Cow::Borrowed(input),
+        };
+
+        let mut encoded = String::with_capacity(bytes.len() + 8);
+        encoded.push_str(&input[..first]);
+
+        for (idx, &byte) in bytes.iter().enumerate().skip(first) {
+            if self.keeps(bytes, idx, &authority) {
+                encoded.push(byte as char);
+            } else {
+                encoded.push('%');
+                encoded.push(char::from(HEX[usize::from(byte >> 4)]));
+                encoded.push(char::from(HEX[usize::from(byte & 0xF)]));
+            }
+        }
+
+        Cow::Owned(encoded)
+    }
+
+    /// Returns the byte range of the authority of `input`, if encoding complete URLs.
+    fn authority(&self, input: &str) -> Range<usize> {
+        if self.set != EncodeSet::Url {
+            return 0..0;
+        }
+
+        let start = match input.find("//") {
+            Some(idx) if !input[..idx].contains(|c| matches!(c, '/' | '?' | '#')) => idx + 2,
+            _ => return 0..0,
+        };
+
+        let len = input[start..]
+            .find(|c| matches!(c, '/' | '?' | '#'))
+            .unwrap_or(input.len() - start);
+
+        start..start + len
+    }
+
+    /// Returns true if the byte at `idx` is kept as-is.
+    fn keeps(&self, bytes: &[u8], idx: usize, authority: &Range<usize>) -> bool {
+        match bytes[idx] {
+            b'%' => {
+                self.raw
+                    && bytes.len() > idx + 2
+                    && bytes[idx + 1].is_ascii_hexdigit()
+                    && bytes[idx + 2].is_ascii_hexdigit()
+            }
+
+            b'[' | b']' => authority.contains(&idx),
+
+            byte => self.set.keeps(byte),
+        }
+    }
+}
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESERVED: &str = ":/?#[]@!$&'()*+,;=";
+
+    fn encode(set: EncodeSet, input: &str) -> String {
+        PathEncoder::new(set).encode(input).into_owned()
+    }
+
+    #[test]
+    fn reserved_characters() {
+        assert_eq!(
+            encode(EncodeSet::PathSegment, RESERVED),
+            ":%2F%3F%23%5B%5D@!$&'()*+,;="
+        );
+        assert_eq!(
+            encode(EncodeSet::Path, RESERVED),
+            ":/%3F%23%5B%5D@!$&'()*+,;="
+        );
+        assert_eq!(
+            encode(EncodeSet::QueryComponent, RESERVED),
+            ":/?%23%5B%5D@!$%26'()*%2B,%3B%3D"
+        );
+        assert_eq!(
+            encode(EncodeSet::Fragment, RESERVED),
+            ":/?%23%5B%5D@!$&'()*+,;="
+        );
+        assert_eq!(encode(EncodeSet::Url, RESERVED), ":/?#%5B%5D@!$&'()*+,;=");
+    }
+
+    #[test]
+    fn url_authority() {
+        let url = PathEncoder::new(EncodeSet::Url);
+        assert_eq!(
+            url.encode("http://[::1]:8080/[a]?[b]#[c]"),
+            "http://[::1]:8080/%5Ba%5D?%5Bb%5D#%5Bc%5D"
+        );
+        assert_eq!(url.encode("//[::1]/[a]"), "//[::1]/%5Ba%5D");
+        assert_eq!(url.encode("/a//[b]"), "/a//%5Bb%5D");
+        assert_eq!(url.encode("[a]"), "%5Ba%5D");
+    }
+
+    #[test]
+    fn other_characters() {
+        let unreserved = "AZaz09-._~";
+        let other = " \"%<>\\^`{|}\t\n\x7f";
+        let encoded_other = "%20%22%25%3C%3E%5C%5E%60%7B%7C%7D%09%0A%7F";
+
+        for set in [
+            EncodeSet::PathSegment,
+            EncodeSet::Path,
+            EncodeSet::QueryComponent,
+            EncodeSet::Fragment,
+            EncodeSet::Url,
+        ] {
+            assert_eq!(encode(set, unreserved), unreserved);
+            assert_eq!(encode(set, other), encoded_other);
+        }
+    }
+
+    #[test]
+    fn non_ascii() {
+        let path = PathEncoder::new(EncodeSet::Path);
+        assert_eq!(path.encode("/café"), "/caf%C3%A9");
+        assert_eq!(path.encode("/日本"), "/%E6%97%A5%E6%9C%AC");
+        assert_eq!(path.encode("🦀"), "%F0%9F%A6%80");
+    }
+
+    #[test]
+    fn raw_mode() {
+        let path = PathEncoder::new(EncodeSet::Path);
+        assert_eq!(path.encode("/a%2Fb"), "/a%252Fb");
+
+        let raw = path.raw();
+        assert_eq!(raw.encode("/a%2Fb"), "/a%2Fb");
+        assert_eq!(raw.encode("/a%2fb c"), "/a%2fb%20c");
+        assert_eq!(raw.encode("/100%"), "/100%25");
+        assert_eq!(raw.encode("/a%zz"), "/a%25zz");
+        assert_eq!(raw.encode("/a%2"), "/a%252");
+
+        // encoding is idempotent in raw mode
+        let once = raw.encode("/naïve café/50% off").into_owned();
+        assert_eq!(once, "/na%C3%AFve%20caf%C3%A9/50%25%20off");
+        assert_eq!(raw.encode(&once), once);
+    }
+
+    #[test]
+    fn borrows_unchanged_input() {
+        let path = PathEncoder::new(EncodeSet::Path);
+        assert!(matches!(path.encode("/users/42"), Cow::Borrowed(_)));
+        assert!(matches!(path.encode("/users/4 2"), Cow::Owned(_)));
+        assert!(matches!(
+            PathEncoder::new(EncodeSet::Url)
+                .raw()
+                .encode("https://example.com/%20"),
+            Cow::Borrowed(_)
+        ));
+    }
+}
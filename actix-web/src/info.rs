@@ -25,11 +25,84 @@ fn unquote(val: &str) -> &str {
     val.trim().trim_start_matches('"').trim_end_matches('"')
 }
 
-/// Extracts and trims first value for given header name.
-fn first_header_value<'a>(req: &'a RequestHead, name: &'_ HeaderName) -> Option<&'a str> {
-    let hdr = req.headers.get(name)?.to_str().ok()?;
-    let val = hdr.split(',').next()?.trim();
-    Some(val)
+/// Extracts and trims the comma-separated values of all headers with the given name.
+fn header_values<'a>(req: &'a RequestHead, name: &'_ HeaderName) -> Vec<&'a str> {
+    req.headers
+        .get_all(name)
+        .filter_map(|hdr| hdr.to_str().ok())
+        .flat_map(|hdr| hdr.split(','))
+        .map(str::trim)
+        .collect()
+}
+
+/// Properties of a single `Forwarded` element, as added by one proxy.
+#[derive(Debug, Default)]
+struct ForwardedElement<'a> {
+    for_: Option<&'a str>,
+    proto: Option<&'a str>,
+    host: Option<&'a str>,
+}
+
+/// Parses all `Forwarded` headers into their elements, in the order they were added.
+fn forwarded_elements(req: &RequestHead) -> Vec<ForwardedElement<'_>> {
+    req.headers
+        .get_all(&header::FORWARDED)
+        .filter_map(|hdr| hdr.to_str().ok())
+        // "for=1.2.3.4, for=5.6.7.8; proto=https"
+        .flat_map(|val| val.split(','))
+        // ["for=1.2.3.4", " for=5.6.7.8; proto=https"]
+        .map(|elem| {
+            let mut el = ForwardedElement::default();
+
+            for pair in elem.split(';') {
+                let mut items = pair.trim().splitn(2, '=');
+
+                let (name, val) = match (items.next(), items.next()) {
+                    (Some(name), Some(val)) => (name, unquote(val)),
+                    _ => continue,
+                };
+
+                let prop = match name.trim().to_lowercase().as_str() {
+                    "for" => &mut el.for_,
+                    "proto" => &mut el.proto,
+                    "host" => &mut el.host,
+                    // TODO: implement "by"; https://datatracker.ietf.org/doc/html/rfc7239#section-5.1
+                    _ => continue,
+                };
+
+                prop.get_or_insert(val);
+            }
+
+            el
+        })
+        .collect()
+}
+
+/// Selects the value added by the outermost trusted proxy from values listed in the order they
+/// were added, from the client to the nearest proxy.
+///
+/// Without a trusted proxy count, the first value is used. Lists shorter than the count have no
+/// trusted value.
+fn trusted_value<T>(values: &[T], trusted_proxies: Option<usize>) -> Option<&T> {
+    match trusted_proxies {
+        None => values.first(),
+        Some(count) => values.get(values.len().checked_sub(count)?),
+    }
+}
+
+/// Selects a property of the `Forwarded` element added by the outermost trusted proxy.
+///
+/// Properties are only taken from that element; if it does not have the property, there is no
+/// trusted value. Without a trusted proxy count, the first element with the property is used.
+fn forwarded_value<'a>(
+    elements: &[ForwardedElement<'a>],
+    trusted_proxies: Option<usize>,
+    prop: impl Fn(&ForwardedElement<'a>) -> Option<&'a str>,
+) -> Option<&'a str> {
+    match trusted_proxies {
+        None => elements.iter().find_map(prop),
+        Some(_) => trusted_value(elements, trusted_proxies).and_then(prop),
+    }
 }
 
 /// HTTP connection information.
@@ -60,6 +133,12 @@ fn first_header_value<'a>(req: &'a RequestHead, name: &'_ HeaderName) -> Option<
 /// If the older, related headers are also present (eg. `X-Forwarded-For`), then `Forwarded`
 /// is preferred.
 ///
+/// By default, the first value of each property is used, as sent by the client or the first
+/// proxy. When the app is deployed behind a known number of proxies, set that number using
+/// [`App::trusted_proxies`](crate::App::trusted_proxies) so that values are taken from the
+/// outermost trusted proxy and values prepended by clients are ignored. All `Forwarded` properties
+/// are then taken from the single element added by that proxy.
+///
 /// [rfc7239]: https://datatracker.ietf.org/doc/html/rfc7239
 /// [rfc7239-62]: https://datatracker.ietf.org/doc/html/rfc7239#section-6.2
 /// [rfc7239-63]: https://datatracker.ietf.org/doc/html/rfc7239#section-6.3
@@ -73,65 +152,40 @@ pub struct ConnectionInfo {
 
 impl ConnectionInfo {
     pub(crate) fn new(req: &RequestHead, cfg: &AppConfig) -> ConnectionInfo {
-        let mut host = None;
-        let mut scheme = None;
-        let mut realip_remote_addr = None;
-
-        for (name, val) in req
-            .headers
-            .get_all(&header::FORWARDED)
-            .into_iter()
-            .filter_map(|hdr| hdr.to_str().ok())
-            // "for=1.2.3.4, for=5.6.7.8; scheme=https"
-            .flat_map(|val| val.split(';'))
-            // ["for=1.2.3.4, for=5.6.7.8", " scheme=https"]
-            .flat_map(|vals| vals.split(','))
-            // ["for=1.2.3.4", " for=5.6.7.8", " scheme=https"]
-            .flat_map(|pair| {
-                let mut items = pair.trim().splitn(2, '=');
-                Some((items.next()?, items.next()?))
+        let trusted_proxies = cfg.trusted_proxies();
+
+        // values are collected in order because spec states that first "for" value is client
+        // and rest are proxies; each proxy appends its own element
+        //
+        // > In a chain of proxy servers where this is fully utilized, the first
+        // > "for" parameter will disclose the client where the request was first
+        // > made, followed by any subsequent proxy identifiers.
+        // --- https://datatracker.ietf.org/doc/html/rfc7239#section-5.2
+        let forwarded = forwarded_elements(req);
+
+        let scheme = forwarded_value(&forwarded, trusted_proxies, |el| el.proto)
+            .or_else(|| {
+                trusted_value(&header_values(req, &X_FORWARDED_PROTO), trusted_proxies).copied()
             })
-        {
-            // [(name , val      ), ...                                    ]
-            // [("for", "1.2.3.4"), ("for", "5.6.7.8"), ("scheme", "https")]
-
-            // taking the first value for each property is correct because spec states that first
-            // "for" value is client and rest are proxies; multiple values other properties have
-            // no defined semantics
-            //
-            // > In a chain of proxy servers where this is fully utilized, the first
-            // > "for" parameter will disclose the client where the request was first
-            // > made, followed by any subsequent proxy identifiers.
-            // --- https://datatracker.ietf.org/doc/html/rfc7239#section-5.2
-
-            match name.trim().to_lowercase().as_str() {
-                "for" => realip_remote_addr.get_or_insert_with(|| unquote(val)),
-                "proto" => scheme.get_or_insert_with(|| unquote(val)),
-                "host" => host.get_or_insert_with(|| unquote(val)),
-                "by" => {
-                    // TODO: implement https://datatracker.ietf.org/doc/html/rfc7239#section-5.1
-                    continue;
-                }
-                _ => continue,
-            };
-        }
-
-        let scheme = scheme
-            .or_else(|| first_header_value(req, &*X_FORWARDED_PROTO))
             .or_else(|| req.uri.scheme().map(Scheme::as_str))
             .or_else(|| Some("https").filter(|_| cfg.secure()))
             .unwrap_or("http")
             .to_owned();
 
-        let host = host
-            .or_else(|| first_header_value(req, &*X_FORWARDED_HOST))
-            .or_else(|| req.headers.get(&header::HOST)?.to_str().ok())
+        let host = forwarded_value(&forwarded, trusted_proxies, |el| el.host)
+            .or_else(|| {
+                trusted_value(&header_values(req, &X_FORWARDED_HOST), trusted_proxies).copied()
+            })
+            // absolute-form target takes precedence over the Host header (RFC 9112 §3.2.2)
             .or_else(|| req.uri.authority().map(Authority::as_str))
+            .or_else(|| req.headers.get(&header::HOST)?.to_str().ok())
             .unwrap_or_else(|| cfg.host())
             .to_owned();
 
-        let realip_remote_addr = realip_remote_addr
-            .or_else(|| first_header_value(req, &*X_FORWARDED_FOR))
+        let realip_remote_addr = forwarded_value(&forwarded, trusted_proxies, |el| el.for_)
+            .or_else(|| {
+                trusted_value(&header_values(req, &X_FORWARDED_FOR), trusted_proxies).copied()
+            })
             .map(str::to_owned);
 
         let peer_addr = req.peer_addr.map(|addr| addr.ip().to_string());
@@ -175,9 +229,12 @@ impl ConnectionInfo {
     /// Hostname is resolved through the following, in order:
     /// - `Forwarded` header
     /// - `X-Forwarded-Host` header
+    /// - request target / URI, when sent in absolute form
     /// - `Host` header
-    /// - request target / URI
     /// - configured server hostname
+    ///
+    /// By default, the server rejects HTTP/1 requests whose target and `Host` header disagree; see
+    /// [`HttpServer::strict_host_validation`](crate::HttpServer::strict_host_validation).
     #[inline]
     pub fn host(&self) -> &str {
         &self.host
@@ -395,6 +452,108 @@ mod tests {
         assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
     }
 
+    #[test]
+    fn forwarded_over_x_forwarded() {
+        let req = TestRequest::default()
+            .insert_header((
+                header::FORWARDED,
+                "for=192.0.2.60; proto=https; host=rust-lang.org",
+            ))
+            .insert_header((X_FORWARDED_FOR, "198.51.100.17"))
+            .insert_header((X_FORWARDED_PROTO, "http"))
+            .insert_header((X_FORWARDED_HOST, "actix.rs"))
+            .to_http_request();
+        let info = req.connection_info();
+        assert_eq!(info.scheme(), "https");
+        assert_eq!(info.host(), "rust-lang.org");
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
+
+        // properties missing from `Forwarded` fall back to `X-Forwarded-*`
+        let req = TestRequest::default()
+            .insert_header((header::FORWARDED, "for=192.0.2.60"))
+            .insert_header((X_FORWARDED_PROTO, "https"))
+            .to_http_request();
+        let info = req.connection_info();
+        assert_eq!(info.scheme(), "https");
+        assert_eq!(info.realip_remote_addr(), Some("192.0.2.60"));
+    }
+
+    #[test]
+    fn trusted_proxies() {
+        fn info(trusted_proxies: Option<usize>, req: TestRequest) -> ConnectionInfo {
+            let mut cfg = AppConfig::default();
+            cfg.set_trusted_proxies(trusted_proxies);
+            ConnectionInfo::new(req.to_http_request().head(), &cfg)
+        }
+
+        // client spoofs the first element; two proxies each append an element
+        let forwarded = || {
+            TestRequest::default()
+                .append_header((header::FORWARDED, "for=10.0.0.1;proto=http"))
+                .append_header((
+                    header::FORWARDED,
+                    "for=192.0.2.60;proto=https;host=rust-lang.org, for=203.0.113.43;proto=https",
+                ))
+                .insert_header((X_FORWARDED_PROTO, "http"))
+        };
+
+        let conn = info(None, forwarded());
+        assert_eq!(conn.realip_remote_addr(), Some("10.0.0.1"));
+        assert_eq!(conn.scheme(), "http");
+
+        let conn = info(Some(2), forwarded());
+        assert_eq!(conn.realip_remote_addr(), Some("192.0.2.60"));
+        assert_eq!(conn.scheme(), "https");
+        assert_eq!(conn.host(), "rust-lang.org");
+
+        let conn = info(Some(1), forwarded());
+        assert_eq!(conn.realip_remote_addr(), Some("203.0.113.43"));
+
+        // more trusted proxies than values has no trusted value
+        let conn = info(Some(5), forwarded());
+        assert_eq!(conn.realip_remote_addr(), None);
+        assert_eq!(conn.scheme(), "http");
+
+        // the same rules apply to `X-Forwarded-*` headers
+        let req = TestRequest::default()
+            .insert_header((X_FORWARDED_FOR, "10.0.0.1, 192.0.2.60, 203.0.113.43"))
+            .insert_header((
+                X_FORWARDED_HOST,
+                "spoofed.example, rust-lang.org, lb.internal",
+            ));
+        let conn = info(Some(2), req);
+        assert_eq!(conn.realip_remote_addr(), Some("192.0.2.60"));
+        assert_eq!(conn.host(), "rust-lang.org");
+
+        // no trusted proxies ignores forwarding headers
+        let req = forwarded()
+            .insert_header((header::HOST, "actix.rs"))
+            .peer_addr("127.0.0.1:8080".parse().unwrap());
+        let conn = info(Some(0), req);
+        assert_eq!(conn.realip_remote_addr(), Some("127.0.0.1"));
+        assert_eq!(conn.scheme(), "http");
+        assert_eq!(conn.host(), "actix.rs");
+    }
+
+    #[test]
+    fn trusted_proxies_untrusted_element_properties() {
+        let mut cfg = AppConfig::default();
+        cfg.set_trusted_proxies(Some(1));
+
+        // host and proto only appear in the element added by the client
+        let req = TestRequest::default()
+            .insert_header((
+                header::FORWARDED,
+                "for=192.0.2.60;host=evil.example;proto=https, for=198.51.100.17, for=203.0.113.43",
+            ))
+            .insert_header((header::HOST, "actix.rs"))
+            .to_http_request();
+        let conn = ConnectionInfo::new(req.head(), &cfg);
+        assert_eq!(conn.realip_remote_addr(), Some("203.0.113.43"));
+        assert_eq!(conn.host(), "actix.rs");
+        assert_eq!(conn.scheme(), "http");
+    }
+
     #[test]
     fn scheme_from_uri() {
         let req = TestRequest::get()
@@ -413,6 +572,16 @@ mod tests {
         assert_eq!(info.host(), "actix.rs");
     }
 
+    #[test]
+    fn host_from_uri_over_host_header() {
+        let req = TestRequest::get()
+            .uri("https://actix.rs/test")
+            .insert_header((header::HOST, "rust-lang.org"))
+            .to_http_request();
+        let info = req.connection_info();
+        assert_eq!(info.host(), "actix.rs");
+    }
+
     #[test]
     fn host_from_server_hostname() {
         let mut req = TestRequest::get();
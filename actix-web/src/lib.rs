@@ -66,6 +66,8 @@
 //! - `openssl` - HTTPS support via `openssl` crate, supports `HTTP/2`
 //! - `rustls` - HTTPS support via `rustls` crate, supports `HTTP/2`
 //! - `secure-cookies` - secure cookies support
+//! - `digest` - request body digest verification using [`VerifyDigest`](middleware::VerifyDigest)
+//! - `signed-urls` - signing and verifying time-limited URLs using [`UrlSigner`](web::UrlSigner)
 
 #![deny(rust_2018_idioms, nonstandard_style)]
 #![warn(future_incompatible)]
@@ -75,17 +77,24 @@
 
 mod app;
 mod app_service;
+mod blocking;
 mod config;
 mod data;
 pub mod dev;
 pub mod error;
 mod extract;
+mod gone;
 pub mod guard;
 mod handler;
+mod health;
 mod helpers;
 pub mod http;
 mod info;
+mod lifecycle;
 pub mod middleware;
+#[cfg(feature = "cookies")]
+mod oauth_redirect;
+mod redirect;
 mod request;
 mod request_data;
 mod resource;
@@ -97,8 +106,12 @@ mod scope;
 mod server;
 mod service;
 pub mod test;
+mod trace;
 pub(crate) mod types;
+#[cfg(feature = "signed-urls")]
+mod url_signer;
 pub mod web;
+mod worker;
 
 pub use crate::app::App;
 #[doc(inline)]
@@ -106,9 +119,11 @@ pub use crate::error::Result;
 pub use crate::error::{Error, ResponseError};
 pub use crate::extract::FromRequest;
 pub use crate::handler::Handler;
-pub use crate::request::HttpRequest;
+pub use crate::request::{HttpRequest, WeakHttpRequest};
 pub use crate::resource::Resource;
-pub use crate::response::{CustomizeResponder, HttpResponse, HttpResponseBuilder, Responder};
+pub use crate::response::{
+    CustomizeResponder, HttpResponse, HttpResponseBuilder, PartialResponse, Responder,
+};
 pub use crate::route::Route;
 pub use crate::scope::Scope;
 pub use crate::server::HttpServer;
@@ -0,0 +1,243 @@
+//! Request and response lifecycle hooks.
+//!
+//! See [`App::on_request`](crate::App::on_request) and
+//! [`App::on_response`](crate::App::on_response).
+
+use std::{
+    cell::Cell,
+    fmt,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use actix_http::{ObserveResponse, ResponseObserver};
+
+use crate::{
+    http::{Method, StatusCode, Uri},
+    HttpMessage as _, HttpRequest,
+};
+
+/// A read-only view of a request that has started, passed to [`App::on_request`] hooks.
+///
+/// [`App::on_request`]: crate::App::on_request
+pub struct RequestEvent<'a> {
+    req: &'a HttpRequest,
+}
+
+impl<'a> RequestEvent<'a> {
+    /// Returns the request's method.
+    pub fn method(&self) -> &'a Method {
+        self.req.method()
+    }
+
+    /// Returns the request's URI.
+    pub fn uri(&self) -> &'a Uri {
+        self.req.uri()
+    }
+
+    /// Returns the resource pattern the request matches, if any.
+    ///
+    /// See [`HttpRequest::match_pattern`].
+    pub fn match_pattern(&self) -> Option<String> {
+        self.req.match_pattern()
+    }
+}
+
+impl fmt::Debug for RequestEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestEvent")
+            .field("method", self.method())
+            .field("uri", self.uri())
+            .finish()
+    }
+}
+
+/// Stage of a response reported to [`App::on_response`] hooks.
+///
+/// [`App::on_response`]: crate::App::on_response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResponseStage {
+    /// The response head was written to the connection.
+    HeadersWritten,
+
+    /// Sending the response body ended.
+    BodyFinished {
+        /// Number of body bytes sent.
+        bytes: u64,
+
+        /// False if the body was cut short, such as when the body stream failed or the client
+        /// disconnected.
+        complete: bool,
+    },
+}
+
+/// A read-only view of a response's progress, passed to [`App::on_response`] hooks.
+///
+/// [`App::on_response`]: crate::App::on_response
+pub struct ResponseEvent<'a> {
+    stage: ResponseStage,
+    status: StatusCode,
+    observed: &'a ObservedResponse,
+}
+
+impl ResponseEvent<'_> {
+    /// Returns the stage the response has reached.
+    pub fn stage(&self) -> ResponseStage {
+        self.stage
+    }
+
+    /// Returns the response's status code.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the method of the request being responded to.
+    pub fn method(&self) -> &Method {
+        &self.observed.method
+    }
+
+    /// Returns the URI of the request being responded to.
+    pub fn uri(&self) -> &Uri {
+        &self.observed.uri
+    }
+
+    /// Returns the resource pattern of the route that handled the request, if any.
+    ///
+    /// Unlike [`HttpRequest::match_pattern`], this is the route that was actually called, after
+    /// guards were checked, and is `None` for responses from default services.
+    pub fn match_pattern(&self) -> Option<&str> {
+        self.observed.route.as_deref()
+    }
+
+    /// Returns the time elapsed since the request started.
+    pub fn duration(&self) -> Duration {
+        self.observed.started.elapsed()
+    }
+}
+
+impl fmt::Debug for ResponseEvent<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseEvent")
+            .field("stage", &self.stage)
+            .field("status", &self.status)
+            .field("method", self.method())
+            .field("uri", self.uri())
+            .finish()
+    }
+}
+
+type RequestHook = Box<dyn Fn(&RequestEvent<'_>)>;
+type ResponseHook = Box<dyn Fn(&ResponseEvent<'_>)>;
+
+/// Lifecycle hooks registered on an [`App`](crate::App), run in registration order.
+#[derive(Default)]
+pub(crate) struct LifecycleHooks {
+    on_request: Vec<RequestHook>,
+    on_response: Vec<ResponseHook>,
+}
+
+impl LifecycleHooks {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.on_request.is_empty() && self.on_response.is_empty()
+    }
+
+    pub(crate) fn has_response_hooks(&self) -> bool {
+        !self.on_response.is_empty()
+    }
+
+    pub(crate) fn add_request_hook(&mut self, hook: impl Fn(&RequestEvent<'_>) + 'static) {
+        self.on_request.push(Box::new(hook));
+    }
+
+    pub(crate) fn add_response_hook(&mut self, hook: impl Fn(&ResponseEvent<'_>) + 'static) {
+        self.on_response.push(Box::new(hook));
+    }
+
+    /// Runs request hooks and records the start time of the request for response hooks.
+    pub(crate) fn request_started(&self, req: &HttpRequest) {
+        if !self.on_response.is_empty() {
+            req.extensions_mut().insert(RequestStarted(Instant::now()));
+        }
+
+        let event = RequestEvent { req };
+
+        for hook in &self.on_request {
+            hook(&event);
+        }
+    }
+
+    /// Returns a response extension that reports the progress of a response to `req` with the
+    /// given status to response hooks.
+    pub(crate) fn observe_response(
+        self: &Rc<Self>,
+        req: &HttpRequest,
+        status: StatusCode,
+    ) -> Option<ObserveResponse> {
+        if self.on_response.is_empty() {
+            return None;
+        }
+
+        let extensions = req.extensions();
+
+        let started = extensions
+            .get::<RequestStarted>()
+            .map_or_else(Instant::now, |started| started.0);
+        let route = extensions
+            .get::<MatchedRoute>()
+            .map(|route| Rc::clone(&route.0));
+
+        Some(ObserveResponse(Rc::new(ObservedResponse {
+            hooks: Rc::clone(self),
+            method: req.method().clone(),
+            uri: req.uri().clone(),
+            route,
+            started,
+            status: Cell::new(status),
+        })))
+    }
+}
+
+/// Start time of a request, kept in its extensions when response hooks are registered.
+struct RequestStarted(Instant);
+
+/// Full pattern of the route that handled a request, kept in its extensions when response hooks
+/// are registered.
+#[derive(Clone)]
+pub(crate) struct MatchedRoute(pub(crate) Rc<str>);
+
+/// Request data kept for response hooks while a response is sent.
+struct ObservedResponse {
+    hooks: Rc<LifecycleHooks>,
+    method: Method,
+    uri: Uri,
+    route: Option<Rc<str>>,
+    started: Instant,
+    // updated if the dispatcher sends a different response
+    status: Cell<StatusCode>,
+}
+
+impl ObservedResponse {
+    fn emit(&self, stage: ResponseStage) {
+        let event = ResponseEvent {
+            stage,
+            status: self.status.get(),
+            observed: self,
+        };
+
+        for hook in &self.hooks.on_response {
+            hook(&event);
+        }
+    }
+}
+
+impl ResponseObserver for ObservedResponse {
+    fn headers_written(&self, status: StatusCode) {
+        self.status.set(status);
+        self.emit(ResponseStage::HeadersWritten);
+    }
+
+    fn body_finished(&self, bytes: u64, complete: bool) {
+        self.emit(ResponseStage::BodyFinished { bytes, complete });
+    }
+}
@@ -0,0 +1,330 @@
+//! For middleware documentation, see [`BodyLimit`].
+
+use std::{
+    cell::Cell,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use actix_http::{error::PayloadError, BoxedPayloadStream, HttpMessage as _};
+use actix_service::{Service, Transform};
+use actix_utils::future::{ok, Ready};
+use bytes::Bytes;
+use futures_core::{future::LocalBoxFuture, Stream};
+
+use crate::{
+    body::EitherBody,
+    dev::Payload,
+    error::BodyLimitExceeded,
+    http::header::{ContentLength, Header as _},
+    service::{ServiceRequest, ServiceResponse},
+    web::ByteSize,
+    Error,
+};
+
+/// Middleware for limiting the size of request bodies.
+///
+/// The request payload is wrapped in a counting stream, so the limit applies to every extractor and
+/// to manual reads of [`web::Payload`](crate::web::Payload), whether the body is sized or chunked.
+/// Requests that declare a `Content-Length` above the limit are rejected before any of the body is
+/// read. Otherwise, the payload stream fails with [`PayloadError::LimitExceeded`] as soon as the
+/// limit is exceeded and the response of the wrapped service is discarded.
+///
+/// In both cases, the middleware responds with a [`BodyLimitExceeded`] error, which renders as
+/// "413 Payload Too Large" with a JSON problem details body. Like any other error response, it can
+/// be customized with [`ErrorHandlers`](crate::middleware::ErrorHandlers).
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use actix_web::{middleware::BodyLimit, web, App};
+///
+/// let app = App::new().service(
+///     web::scope("/api/upload")
+///         // allow bodies of up to 100MB
+///         .wrap(BodyLimit::new(100 * 1024 * 1024).retry_after(Duration::from_secs(60)))
+///         .route("", web::post().to(|body: web::Bytes| async move { body.len().to_string() })),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BodyLimit {
+    limit: usize,
+    retry_after: Option<u64>,
+}
+
+impl BodyLimit {
+    /// Constructs new middleware that allows request bodies of up to `limit` bytes.
+    pub fn new(limit: impl Into<ByteSize>) -> Self {
+        Self {
+            limit: limit.into().as_usize(),
+            retry_after: None,
+        }
+    }
+
+    /// Sets a `Retry-After` header, in whole seconds, on "413 Payload Too Large" responses.
+    pub fn retry_after(mut self, dur: Duration) -> Self {
+        self.retry_after = Some(dur.as_secs());
+        self
+    }
+
+    fn error(&self) -> Error {
+        BodyLimitExceeded::new(self.limit, self.retry_after).into()
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for BodyLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = BodyLimitMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(BodyLimitMiddleware {
+            service,
+            config: *self,
+        })
+    }
+}
+
+pub struct BodyLimitMiddleware<S> {
+    service: S,
+    config: BodyLimit,
+}
+
+impl<S, B> Service<ServiceRequest> for BodyLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let config = self.config;
+
+        if let Ok(ContentLength(len)) = ContentLength::parse(req.request()) {
+            if len > config.limit {
+                let res = req.error_response(config.error()).map_into_right_body();
+                return Box::pin(async move { Ok(res) });
+            }
+        }
+
+        let exceeded = Rc::new(Cell::new(false));
+
+        let payload = CountingPayload {
+            payload: req.take_payload(),
+            limit: config.limit,
+            remaining: config.limit,
+            exceeded: Rc::clone(&exceeded),
+        };
+        req.set_payload(Payload::from(Box::pin(payload) as BoxedPayloadStream));
+
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await;
+
+            // handlers may recover from the payload error; the body was still too large
+            if !exceeded.get() {
+                return res.map(ServiceResponse::map_into_left_body);
+            }
+
+            match res {
+                Ok(res) => {
+                    let (req, _) = res.into_parts();
+                    Ok(ServiceResponse::from_err(config.error(), req).map_into_right_body())
+                }
+                Err(_) => Err(config.error()),
+            }
+        })
+    }
+}
+
+/// Payload stream that fails once more than `remaining` bytes have been read.
+struct CountingPayload {
+    payload: Payload,
+    limit: usize,
+    remaining: usize,
+    exceeded: Rc<Cell<bool>>,
+}
+
+impl Stream for CountingPayload {
+    type Item = Result<Bytes, PayloadError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        if this.exceeded.get() {
+            return Poll::Ready(Some(Err(PayloadError::LimitExceeded { limit: this.limit })));
+        }
+
+        match Pin::new(&mut this.payload).poll_next(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                if chunk.len() > this.remaining {
+                    this.exceeded.set(true);
+                    return Poll::Ready(Some(Err(PayloadError::LimitExceeded {
+                        limit: this.limit,
+                    })));
+                }
+
+                this.remaining -= chunk.len();
+                Poll::Ready(Some(Ok(chunk)))
+            }
+
+            poll => poll,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_util::{stream, StreamExt as _};
+
+    use super::*;
+    use crate::{
+        http::{header, StatusCode},
+        middleware::{ErrorHandlerResponse, ErrorHandlers},
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    fn chunked(chunks: &'static [&'static str]) -> Payload {
+        let stream = stream::iter(
+            chunks
+                .iter()
+                .map(|chunk| Ok(Bytes::from_static(chunk.as_bytes()))),
+        );
+        Payload::from(Box::pin(stream) as BoxedPayloadStream)
+    }
+
+    #[actix_rt::test]
+    async fn chunked_overflow() {
+        let read = Rc::new(Cell::new(0));
+
+        let srv = test::init_service(
+            App::new().service(
+                web::scope("/api/upload")
+                    .wrap(BodyLimit::new(10).retry_after(Duration::from_secs(30)))
+                    .route(
+                        "",
+                        web::post().to({
+                            let read = Rc::clone(&read);
+
+                            move |mut body: web::Payload| {
+                                let read = Rc::clone(&read);
+
+                                async move {
+                                    while let Some(chunk) = body.next().await {
+                                        read.set(read.get() + chunk?.len());
+                                    }
+
+                                    Ok::<_, Error>(HttpResponse::Ok().finish())
+                                }
+                            }
+                        }),
+                    ),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::post().uri("/api/upload").to_request();
+        let (req, _) = req.replace_payload(chunked(&["abcd", "efgh", "ijkl", "mnop"]));
+
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+        assert_eq!(res.headers().get(header::RETRY_AFTER).unwrap(), "30");
+
+        // stream stopped at the third chunk
+        assert_eq!(read.get(), 8);
+
+        let body: serde_json::Value = test::read_body_json(res).await;
+        assert_eq!(body["status"], 413);
+        assert_eq!(body["title"], "Payload Too Large");
+    }
+
+    #[actix_rt::test]
+    async fn declared_length_rejected_early() {
+        let called = Rc::new(Cell::new(false));
+
+        let srv = test::init_service(App::new().wrap(BodyLimit::new(10)).default_service(
+            web::to({
+                let called = Rc::clone(&called);
+
+                move || {
+                    called.set(true);
+                    HttpResponse::Ok()
+                }
+            }),
+        ))
+        .await;
+
+        let req = TestRequest::post()
+            .insert_header((header::CONTENT_LENGTH, 1_000))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(!res.headers().contains_key(header::RETRY_AFTER));
+        assert!(!called.get());
+    }
+
+    #[actix_rt::test]
+    async fn compliant_request_passes() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(BodyLimit::new(10))
+                .route("/", web::post().to(|body: web::Bytes| async move { body })),
+        )
+        .await;
+
+        let req = TestRequest::post().set_payload("0123456789").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "0123456789");
+
+        let (req, _) = TestRequest::post()
+            .to_request()
+            .replace_payload(chunked(&["01234", "56789"]));
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "0123456789");
+    }
+
+    #[actix_rt::test]
+    async fn error_handlers_can_render() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(BodyLimit::new(1))
+                .wrap(
+                    ErrorHandlers::new().handler(StatusCode::PAYLOAD_TOO_LARGE, |mut res| {
+                        res.response_mut()
+                            .headers_mut()
+                            .insert(header::HeaderName::from_static("x-limited"), "1".parse()?);
+                        Ok(ErrorHandlerResponse::Response(res.map_into_left_body()))
+                    }),
+                )
+                .route("/", web::post().to(|_: web::Bytes| HttpResponse::Ok())),
+        )
+        .await;
+
+        let req = TestRequest::post().set_payload("too large").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(res.headers().contains_key("x-limited"));
+    }
+}
@@ -0,0 +1,180 @@
+//! For middleware documentation, see [`BufferAndSize`].
+
+use actix_service::{Service, Transform};
+use actix_utils::future::{ok, Ready};
+use futures_core::future::LocalBoxFuture;
+
+use crate::{
+    body::{self, BodySize, EitherBody, MessageBody},
+    http::header::{ContentLength, TryIntoHeaderValue as _, CONTENT_LENGTH},
+    service::{ServiceRequest, ServiceResponse},
+    web::ByteSize,
+    Error,
+};
+
+/// Middleware for sending small streaming responses with a `Content-Length` header.
+///
+/// Responses whose body size is not known up front are normally sent using chunked transfer
+/// encoding. This middleware wraps such bodies in [`body::BufferAndSize`], which collects them in
+/// memory up to `max_buffer` bytes. If the body ends within that limit, it is sent in one piece and
+/// the response gets a [`ContentLength`] header; otherwise the buffered chunks are sent followed by
+/// the rest of the body, which stays chunked.
+///
+/// Bodies of known size are passed through untouched. To buffer the body of a single response,
+/// use [`body::BufferAndSize`] directly in the handler.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::BufferAndSize, App};
+///
+/// // buffer streaming responses up to 16KiB
+/// let app = App::new().wrap(BufferAndSize::new(16 * 1024));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BufferAndSize {
+    max_buffer: usize,
+}
+
+impl BufferAndSize {
+    /// Constructs new middleware that buffers streaming bodies of up to `max_buffer` bytes.
+    pub fn new(max_buffer: impl Into<ByteSize>) -> Self {
+        Self {
+            max_buffer: max_buffer.into().as_usize(),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for BufferAndSize
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, body::BufferAndSize<B>>>;
+    type Error = Error;
+    type Transform = BufferAndSizeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(BufferAndSizeMiddleware {
+            service,
+            max_buffer: self.max_buffer,
+        })
+    }
+}
+
+pub struct BufferAndSizeMiddleware<S> {
+    service: S,
+    max_buffer: usize,
+}
+
+impl<S, B> Service<ServiceRequest> for BufferAndSizeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, body::BufferAndSize<B>>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let fut = self.service.call(req);
+        let max_buffer = self.max_buffer;
+
+        Box::pin(async move {
+            let res = fut.await?;
+
+            if !matches!(res.response().body().size(), BodySize::Stream) {
+                return Ok(res.map_into_left_body());
+            }
+
+            let (req, res) = res.into_parts();
+            let (mut res, body) = res.into_parts();
+
+            let body = body::BufferAndSize::new(body, max_buffer).await;
+
+            if let BodySize::Sized(len) = body.size() {
+                let len = ContentLength(len as usize).try_into_value().unwrap();
+                res.headers_mut().insert(CONTENT_LENGTH, len);
+            }
+
+            let res = res.set_body(EitherBody::right(body));
+            Ok(ServiceResponse::new(req, res))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{
+        body::BodyStream,
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    fn chunked(chunks: &'static [&'static str]) -> HttpResponse {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok::<_, Error>(Bytes::from_static(chunk.as_bytes())));
+
+        HttpResponse::Ok().body(BodyStream::new(stream::iter(chunks)))
+    }
+
+    #[actix_rt::test]
+    async fn small_body_is_sized() {
+        let app = test::init_service(
+            App::new()
+                .wrap(BufferAndSize::new(16))
+                .route("/", web::get().to(|| chunked(&["hello", " ", "world"]))),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert_eq!(res.headers().get(CONTENT_LENGTH).unwrap(), "11");
+        assert_eq!(res.response().body().size(), BodySize::Sized(11));
+        assert_eq!(test::read_body(res).await, "hello world");
+    }
+
+    #[actix_rt::test]
+    async fn large_body_stays_chunked() {
+        let app = test::init_service(App::new().wrap(BufferAndSize::new(8)).route(
+            "/",
+            web::get().to(|| chunked(&["hello", " ", "world", "!"])),
+        ))
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert!(res.headers().get(CONTENT_LENGTH).is_none());
+        assert_eq!(res.response().body().size(), BodySize::Stream);
+        assert_eq!(test::read_body(res).await, "hello world!");
+    }
+
+    #[actix_rt::test]
+    async fn sized_body_passes_through() {
+        let app = test::init_service(
+            App::new()
+                .wrap(BufferAndSize::new(4))
+                .route("/", web::get().to(|| async { "hello world" })),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&app, req).await;
+
+        assert!(res.headers().get(CONTENT_LENGTH).is_none());
+        assert_eq!(res.response().body().size(), BodySize::Sized(11));
+        assert_eq!(test::read_body(res).await, "hello world");
+    }
+}
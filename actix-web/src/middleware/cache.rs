@@ -0,0 +1,693 @@
+//! For middleware documentation, see [`Cache`].
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use actix_http::body::{self, BodySize, BoxBody, EitherBody, MessageBody};
+use actix_service::{Service, Transform};
+use actix_utils::future::{ok, Ready};
+use bytes::Bytes;
+use futures_core::future::LocalBoxFuture;
+
+use crate::{
+    http::{
+        header::{
+            CacheDirective, HeaderMap, HeaderName, HeaderValue, AGE, AUTHORIZATION,
+            CACHE_CONTROL, RANGE, SET_COOKIE, VARY,
+        },
+        Method, StatusCode,
+    },
+    service::{ServiceRequest, ServiceResponse},
+    web::ByteSize,
+    Error, HttpResponse,
+};
+
+const X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+
+/// Identifies a cached response.
+///
+/// Made up of the request method, path and query string, and the values of the request headers
+/// selected with [`Cache::vary`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    method: Method,
+    path: String,
+    vary: Vec<(HeaderName, Option<HeaderValue>)>,
+}
+
+/// A response stored by the [`Cache`] middleware.
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    stored_at: Instant,
+    ttl: Duration,
+}
+
+impl CachedResponse {
+    /// Returns the size of the cached body, in bytes.
+    pub fn body_size(&self) -> usize {
+        self.body.len()
+    }
+
+    /// Returns true if this response is no longer fresh at `now`.
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.stored_at) >= self.ttl
+    }
+}
+
+/// Storage backend for the [`Cache`] middleware.
+///
+/// Stores are shared by all services wrapped by a `Cache` on the same worker thread, so methods
+/// take `&self` and implementations use interior mutability. The middleware checks entries for
+/// expiry itself; stores may evict entries at any time.
+pub trait CacheStore {
+    /// Returns the response stored for `key`, if any.
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse>;
+
+    /// Stores `res` for `key`, replacing any existing entry.
+    fn insert(&self, key: CacheKey, res: CachedResponse);
+
+    /// Removes the response stored for `key`, if any.
+    fn remove(&self, key: &CacheKey);
+}
+
+/// In-memory [`CacheStore`] holding up to a fixed number of responses.
+///
+/// When full, the least recently used response is evicted.
+#[derive(Debug)]
+pub struct MemoryStore {
+    capacity: usize,
+    inner: RefCell<MemoryStoreInner>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryStoreInner {
+    /// Entries, along with the tick at which they were last used.
+    entries: HashMap<CacheKey, (CachedResponse, u64)>,
+    tick: u64,
+}
+
+impl MemoryStore {
+    /// Constructs a new store holding up to `capacity` responses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: RefCell::new(MemoryStoreInner::default()),
+        }
+    }
+}
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        let mut inner = self.inner.borrow_mut();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        let (res, used) = inner.entries.get_mut(key)?;
+        *used = tick;
+        Some(res.clone())
+    }
+
+    fn insert(&self, key: CacheKey, res: CachedResponse) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut inner = self.inner.borrow_mut();
+        inner.tick += 1;
+        let tick = inner.tick;
+
+        if !inner.entries.contains_key(&key) && inner.entries.len() >= self.capacity {
+            let lru = inner
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(key, _)| key.clone());
+
+            if let Some(lru) = lru {
+                inner.entries.remove(&lru);
+            }
+        }
+
+        inner.entries.insert(key, (res, tick));
+    }
+
+    fn remove(&self, key: &CacheKey) {
+        self.inner.borrow_mut().entries.remove(key);
+    }
+}
+
+/// Middleware for caching responses in-process.
+///
+/// Successful responses to `GET` and `HEAD` requests are stored and served again to later requests
+/// with the same method, path, query string, and values of the headers selected with
+/// [`vary`](Self::vary). Cached responses are served with an `Age` header and an `X-Cache: HIT`
+/// marker; other responses to cacheable requests are marked `X-Cache: MISS`.
+///
+/// The freshness lifetime of a response is taken from the `s-maxage` or `max-age` directive of its
+/// `Cache-Control` header or, if neither is present, from [`default_ttl`](Self::default_ttl).
+/// Stale responses are not revalidated, so an expired entry is simply a miss.
+///
+/// Responses are never stored when:
+/// - they have a `Cache-Control` header with the `no-store`, `no-cache`, or `private` directive;
+/// - they have a `Set-Cookie` header;
+/// - they have a `Vary` header naming a request header that is not selected with `vary`;
+/// - their body is streamed or larger than [`max_body_size`](Self::max_body_size);
+/// - the request has an `Authorization` or `Range` header.
+///
+/// By default, responses are stored in a [`MemoryStore`] holding up to 256 responses. Each worker
+/// thread has its own store.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use actix_web::{http::header, middleware::Cache, web, App};
+///
+/// let app = App::new().service(
+///     web::scope("/dashboard")
+///         .wrap(
+///             Cache::new()
+///                 .vary(header::ACCEPT_ENCODING)
+///                 .default_ttl(Duration::from_secs(10)),
+///         )
+///         .route("/stats", web::get().to(|| async { "busy" })),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct Cache {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    store: Rc<dyn CacheStore>,
+    vary: Vec<HeaderName>,
+    default_ttl: Option<Duration>,
+    max_body_size: usize,
+    clock: Rc<dyn Fn() -> Instant>,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Cache {
+    /// Constructs new middleware using an in-memory store.
+    pub fn new() -> Self {
+        Self::with_store(MemoryStore::new(256))
+    }
+
+    /// Constructs new middleware using the given store.
+    pub fn with_store(store: impl CacheStore + 'static) -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                store: Rc::new(store),
+                vary: Vec::new(),
+                default_ttl: None,
+                max_body_size: 1024 * 1024,
+                clock: Rc::new(Instant::now),
+            }),
+        }
+    }
+
+    /// Stores separate responses for each value of the request header `name`.
+    ///
+    /// Responses whose `Vary` header names a request header not selected here are not cached.
+    pub fn vary(mut self, name: HeaderName) -> Self {
+        self.inner_mut().vary.push(name);
+        self
+    }
+
+    /// Sets the freshness lifetime of responses without a `max-age` or `s-maxage` directive.
+    ///
+    /// By default, such responses are not cached.
+    pub fn default_ttl(mut self, ttl: Duration) -> Self {
+        self.inner_mut().default_ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the largest body size, in bytes, of responses that are cached. Defaults to 1MiB.
+    pub fn max_body_size(mut self, size: impl Into<ByteSize>) -> Self {
+        self.inner_mut().max_body_size = size.into().as_usize();
+        self
+    }
+
+    /// Sets the clock used to timestamp and expire responses. Defaults to [`Instant::now`].
+    ///
+    /// Mainly useful for testing expiry.
+    pub fn clock(mut self, clock: impl Fn() -> Instant + 'static) -> Self {
+        self.inner_mut().clock = Rc::new(clock);
+        self
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner {
+        Rc::get_mut(&mut self.inner).expect("Cache is configured before it is shared")
+    }
+}
+
+impl fmt::Debug for Cache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cache")
+            .field("vary", &self.inner.vary)
+            .field("default_ttl", &self.inner.default_ttl)
+            .field("max_body_size", &self.inner.max_body_size)
+            .finish()
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Cache
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = CacheMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(CacheMiddleware {
+            service,
+            inner: Rc::clone(&self.inner),
+        })
+    }
+}
+
+pub struct CacheMiddleware<S> {
+    service: S,
+    inner: Rc<Inner>,
+}
+
+impl<S, B> Service<ServiceRequest> for CacheMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let key = match self.inner.key(&req) {
+            Some(key) => key,
+            None => {
+                let fut = self.service.call(req);
+                return Box::pin(async move { Ok(fut.await?.map_into_left_body()) });
+            }
+        };
+
+        let now = (self.inner.clock)();
+
+        if let Some(cached) = self.inner.store.get(&key) {
+            if !cached.is_expired(now) {
+                let res = hit(cached, now);
+                return Box::pin(
+                    async move { Ok(req.into_response(res).map_into_right_body()) },
+                );
+            }
+
+            self.inner.store.remove(&key);
+        }
+
+        let fut = self.service.call(req);
+        let inner = Rc::clone(&self.inner);
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+            res.headers_mut()
+                .insert(X_CACHE, HeaderValue::from_static("MISS"));
+
+            let ttl = match inner.ttl(&res) {
+                Some(ttl) => ttl,
+                None => return Ok(res.map_into_left_body()),
+            };
+
+            let (req, res) = res.into_parts();
+            let (head, body) = res.into_parts();
+
+            let body = match body::to_bytes(body).await {
+                Ok(body) => body,
+                Err(err) => return Err(err.into().into()),
+            };
+
+            let mut headers = head.headers().clone();
+            headers.remove(X_CACHE);
+
+            inner.store.insert(
+                key,
+                CachedResponse {
+                    status: head.status(),
+                    headers,
+                    body: body.clone(),
+                    stored_at: now,
+                    ttl,
+                },
+            );
+
+            let res = head.set_body(BoxBody::new(body));
+            Ok(ServiceResponse::new(req, res).map_into_right_body())
+        })
+    }
+}
+
+impl Inner {
+    /// Returns the cache key for `req`, or `None` if its response must not be cached.
+    fn key(&self, req: &ServiceRequest) -> Option<CacheKey> {
+        if !matches!(*req.method(), Method::GET | Method::HEAD) {
+            return None;
+        }
+
+        let headers = req.headers();
+        if headers.contains_key(AUTHORIZATION) || headers.contains_key(RANGE) {
+            return None;
+        }
+
+        let path = req
+            .uri()
+            .path_and_query()
+            .map_or_else(|| req.path().to_owned(), |pq| pq.as_str().to_owned());
+
+        let vary = self
+            .vary
+            .iter()
+            .map(|name| (name.clone(), headers.get(name).cloned()))
+            .collect();
+
+        Some(CacheKey {
+            method: req.method().clone(),
+            path,
+            vary,
+        })
+    }
+
+    /// Returns the freshness lifetime of `res`, or `None` if it must not be cached.
+    fn ttl<B: MessageBody>(&self, res: &ServiceResponse<B>) -> Option<Duration> {
+        let status = res.status();
+        if !status.is_success() || status == StatusCode::PARTIAL_CONTENT {
+            return None;
+        }
+
+        match res.response().body().size() {
+            BodySize::None => {}
+            BodySize::Sized(size) if size <= self.max_body_size as u64 => {}
+            BodySize::Sized(_) | BodySize::Stream => return None,
+        }
+
+        let headers = res.headers();
+        if headers.contains_key(SET_COOKIE) {
+            return None;
+        }
+
+        for vary in headers.get_all(VARY) {
+            let vary = vary.to_str().ok()?;
+
+            for name in vary
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+            {
+                if !self
+                    .vary
+                    .iter()
+                    .any(|sel| sel.as_str().eq_ignore_ascii_case(name))
+                {
+                    return None;
+                }
+            }
+        }
+
+        let mut max_age = None;
+        let mut s_maxage = None;
+
+        for value in headers.get_all(CACHE_CONTROL) {
+            let value = value.to_str().ok()?;
+
+            for directive in value.split(',').map(str::trim) {
+                match directive.parse::<CacheDirective>() {
+                    Ok(CacheDirective::NoStore)
+                    | Ok(CacheDirective::NoCache)
+                    | Ok(CacheDirective::Private) => return None,
+                    Ok(CacheDirective::Extension(name, _))
+                        if name == "private" || name == "no-cache" =>
+                    {
+                        return None
+                    }
+                    Ok(CacheDirective::MaxAge(secs)) => max_age = Some(secs),
+                    Ok(CacheDirective::SMaxAge(secs)) => s_maxage = Some(secs),
+                    _ => {}
+                }
+            }
+        }
+
+        match s_maxage.or(max_age) {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(u64::from(secs))),
+            None => self.default_ttl,
+        }
+    }
+}
+
+/// Builds the response for a cache hit.
+fn hit(cached: CachedResponse, now: Instant) -> HttpResponse {
+    let mut res = HttpResponse::with_body(cached.status, cached.body).map_into_boxed_body();
+    *res.headers_mut() = cached.headers;
+
+    let age = now.saturating_duration_since(cached.stored_at).as_secs();
+    res.headers_mut().insert(AGE, HeaderValue::from(age));
+    res.headers_mut()
+        .insert(X_CACHE, HeaderValue::from_static("HIT"));
+
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{
+        http::header::{self, ACCEPT_ENCODING},
+        test::{self, TestRequest},
+        web, App,
+    };
+
+    fn counting_handler(
+        calls: &Rc<Cell<usize>>,
+        cache_control: &'static str,
+    ) -> impl Fn(crate::HttpRequest) -> HttpResponse + Clone {
+        let calls = Rc::clone(calls);
+
+        move |req| {
+            calls.set(calls.get() + 1);
+
+            let encoding = req
+                .headers()
+                .get(ACCEPT_ENCODING)
+                .map_or("none", |val| val.to_str().unwrap())
+                .to_owned();
+
+            HttpResponse::Ok()
+                .insert_header((CACHE_CONTROL, cache_control))
+                .insert_header((VARY, "accept-encoding"))
+                .body(format!("{} {}", calls.get(), encoding))
+        }
+    }
+
+    #[actix_rt::test]
+    async fn hit_after_miss() {
+        let calls = Rc::new(Cell::new(0));
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(Cache::new().vary(ACCEPT_ENCODING))
+                .route("/", web::get().to(counting_handler(&calls, "max-age=60"))),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.headers().get(X_CACHE).unwrap(), "MISS");
+        assert!(!res.headers().contains_key(AGE));
+        assert_eq!(test::read_body(res).await, "1 none");
+
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(X_CACHE).unwrap(), "HIT");
+        assert_eq!(res.headers().get(AGE).unwrap(), "0");
+        assert_eq!(res.headers().get(CACHE_CONTROL).unwrap(), "max-age=60");
+        assert_eq!(test::read_body(res).await, "1 none");
+
+        assert_eq!(calls.get(), 1);
+
+        // other methods are not cached
+        let req = TestRequest::post().to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        assert!(!res.headers().contains_key(X_CACHE));
+    }
+
+    #[actix_rt::test]
+    async fn expires_after_ttl() {
+        let calls = Rc::new(Cell::new(0));
+        let start = Instant::now();
+        let elapsed = Rc::new(Cell::new(Duration::ZERO));
+
+        let cache = Cache::new().vary(ACCEPT_ENCODING).clock({
+            let elapsed = Rc::clone(&elapsed);
+            move || start + elapsed.get()
+        });
+
+        let srv = test::init_service(App::new().wrap(cache).route(
+            "/",
+            web::get().to(counting_handler(&calls, "public, max-age=60")),
+        ))
+        .await;
+
+        let req = TestRequest::default().to_request();
+        assert_eq!(test::call_and_read_body(&srv, req).await, "1 none");
+
+        elapsed.set(Duration::from_secs(30));
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.headers().get(AGE).unwrap(), "30");
+        assert_eq!(test::read_body(res).await, "1 none");
+
+        elapsed.set(Duration::from_secs(60));
+        let req = TestRequest::default().to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.headers().get(X_CACHE).unwrap(), "MISS");
+        assert_eq!(test::read_body(res).await, "2 none");
+
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn vary_produces_distinct_entries() {
+        let calls = Rc::new(Cell::new(0));
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(Cache::new().vary(ACCEPT_ENCODING))
+                .route("/", web::get().to(counting_handler(&calls, "max-age=60"))),
+        )
+        .await;
+
+        for (encoding, body) in [("gzip", "1 gzip"), ("br", "2 br"), ("gzip", "1 gzip")] {
+            let req = TestRequest::default()
+                .insert_header((ACCEPT_ENCODING, encoding))
+                .to_request();
+            assert_eq!(test::call_and_read_body(&srv, req).await, body);
+        }
+
+        assert_eq!(calls.get(), 2);
+
+        // responses varying on headers that are not part of the key are not cached
+        let calls = Rc::new(Cell::new(0));
+        let srv = test::init_service(
+            App::new()
+                .wrap(Cache::new())
+                .route("/", web::get().to(counting_handler(&calls, "max-age=60"))),
+        )
+        .await;
+
+        for _ in 0..2 {
+            let req = TestRequest::default().to_request();
+            test::call_service(&srv, req).await;
+        }
+
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn uncacheable_responses_bypass() {
+        let calls = Rc::new(Cell::new(0));
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(
+                    Cache::new()
+                        .vary(ACCEPT_ENCODING)
+                        .default_ttl(Duration::from_secs(60)),
+                )
+                .route(
+                    "/no-store",
+                    web::get().to(counting_handler(&calls, "no-store")),
+                )
+                .route(
+                    "/private",
+                    web::get().to(counting_handler(&calls, "private, max-age=60")),
+                )
+                .route(
+                    "/cookie",
+                    web::get().to({
+                        let calls = Rc::clone(&calls);
+                        move || {
+                            calls.set(calls.get() + 1);
+                            HttpResponse::Ok()
+                                .insert_header((SET_COOKIE, "session=1"))
+                                .insert_header(header::CacheControl(vec![
+                                    CacheDirective::MaxAge(60),
+                                ]))
+                                .finish()
+                        }
+                    }),
+                ),
+        )
+        .await;
+
+        for path in ["/no-store", "/private", "/cookie"] {
+            calls.set(0);
+
+            for _ in 0..2 {
+                let req = TestRequest::with_uri(path).to_request();
+                let res = test::call_service(&srv, req).await;
+                assert_eq!(res.headers().get(X_CACHE).unwrap(), "MISS");
+            }
+
+            assert_eq!(calls.get(), 2, "{} was cached", path);
+        }
+    }
+
+    #[test]
+    fn memory_store_evicts_lru() {
+        let store = MemoryStore::new(2);
+        let now = Instant::now();
+
+        let key = |path: &str| CacheKey {
+            method: Method::GET,
+            path: path.to_owned(),
+            vary: Vec::new(),
+        };
+
+        let res = CachedResponse {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+            stored_at: now,
+            ttl: Duration::from_secs(60),
+        };
+
+        store.insert(key("/a"), res.clone());
+        store.insert(key("/b"), res.clone());
+        assert!(store.get(&key("/a")).is_some());
+
+        store.insert(key("/c"), res);
+        assert!(store.get(&key("/a")).is_some());
+        assert!(store.get(&key("/b")).is_none());
+        assert!(store.get(&key("/c")).is_some());
+    }
+}
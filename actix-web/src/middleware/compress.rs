@@ -1,9 +1,11 @@
 //! For middleware documentation, see [`Compress`].
 
 use std::{
+    convert::TryFrom as _,
     future::Future,
     marker::PhantomData,
     pin::Pin,
+    rc::Rc,
     task::{Context, Poll},
 };
 
@@ -17,7 +19,7 @@ use pin_project_lite::pin_project;
 use crate::{
     body::{EitherBody, MessageBody},
     http::{
-        header::{self, AcceptEncoding, Encoding, HeaderValue},
+        header::{self, AcceptEncoding, ContentEncoding, Encoding, HeaderValue},
         StatusCode,
     },
     service::{ServiceRequest, ServiceResponse},
@@ -70,10 +72,32 @@ use crate::{
 ///     .default_service(web::to(index_handler));
 /// ```
 ///
+/// Compression can be limited to some of the enabled encodings:
+/// ```
+/// use actix_web::{http::Encoding, middleware, App};
+///
+/// let app = App::new().wrap(middleware::Compress::default().encodings([Encoding::Gzip]));
+/// ```
+///
 /// [feature flags]: ../index.html#crate-features
 #[derive(Debug, Clone, Default)]
 #[non_exhaustive]
-pub struct Compress;
+pub struct Compress {
+    encodings: Option<Vec<Encoding>>,
+}
+
+impl Compress {
+    /// Limits compression to the given encodings.
+    ///
+    /// Encodings whose `compress-*` [feature flag](../index.html#crate-features) is not enabled
+    /// are ignored. Uncompressed (identity) responses remain available to clients that accept them.
+    ///
+    /// By default, all enabled encodings are used.
+    pub fn encodings(mut self, encodings: impl IntoIterator<Item = Encoding>) -> Self {
+        self.encodings = Some(encodings.into_iter().collect());
+        self
+    }
+}
 
 impl<S, B> Transform<S, ServiceRequest> for Compress
 where
@@ -87,12 +111,32 @@ where
     type Future = Ready<Result<Self::Transform, Self::InitError>>;
 
     fn new_transform(&self, service: S) -> Self::Future {
-        ok(CompressMiddleware { service })
+        let supported = match &self.encodings {
+            None => SUPPORTED_ENCODINGS.clone(),
+            Some(encodings) => SUPPORTED_ENCODINGS
+                .iter()
+                .filter(|enc| **enc == Encoding::Identity || encodings.contains(enc))
+                .cloned()
+                .collect(),
+        };
+
+        let supported_str = supported
+            .iter()
+            .filter(|enc| **enc != Encoding::Identity)
+            .map(Encoding::as_str)
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        ok(CompressMiddleware {
+            service,
+            supported: Rc::new((supported, supported_str)),
+        })
     }
 }
 
 pub struct CompressMiddleware<S> {
     service: S,
+    supported: Rc<(Vec<Encoding>, String)>,
 }
 
 impl<S, B> Service<ServiceRequest> for CompressMiddleware<S>
@@ -116,7 +160,7 @@ where
             // missing header; fallback to identity
             None => {
                 return Either::left(CompressResponse {
-                    encoding: Encoding::identity(),
+                    encoding: ContentEncoding::Identity,
                     fut: self.service.call(req),
                     _phantom: PhantomData,
                 })
@@ -126,12 +170,17 @@ where
             Some(accept_encoding) => accept_encoding,
         };
 
-        match accept_encoding.negotiate(SUPPORTED_ENCODINGS.iter()) {
+        let (supported, supported_str) = &*self.supported;
+
+        // only supported encodings are negotiated, so conversion fails only for unknown codings
+        let encoding = accept_encoding
+            .negotiate(supported.iter())
+            .map(|enc| ContentEncoding::try_from(enc).unwrap_or(ContentEncoding::Identity));
+
+        match encoding {
             None => {
-                let mut res = HttpResponse::with_body(
-                    StatusCode::NOT_ACCEPTABLE,
-                    SUPPORTED_ENCODINGS_STRING.as_str(),
-                );
+                let mut res =
+                    HttpResponse::with_body(StatusCode::NOT_ACCEPTABLE, supported_str.clone());
 
                 res.headers_mut()
                     .insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
@@ -158,7 +207,7 @@ pin_project! {
     {
         #[pin]
         fut: S::Future,
-        encoding: Encoding,
+        encoding: ContentEncoding,
         _phantom: PhantomData<B>,
     }
 }
@@ -175,12 +224,7 @@ where
 
         match ready!(this.fut.poll(cx)) {
             Ok(resp) => {
-                let enc = match this.encoding {
-                    Encoding::Known(enc) => *enc,
-                    Encoding::Unknown(enc) => {
-                        unimplemented!("encoding {} should not be here", enc);
-                    }
-                };
+                let enc = *this.encoding;
 
                 Poll::Ready(Ok(resp.map_body(move |head, body| {
                     EitherBody::left(Encoder::response(enc, head, body))
@@ -192,51 +236,23 @@ where
     }
 }
 
-static SUPPORTED_ENCODINGS_STRING: Lazy<String> = Lazy::new(|| {
-    #[allow(unused_mut)] // only unused when no compress features enabled
-    let mut encoding: Vec<&str> = vec![];
-
-    #[cfg(feature = "compress-brotli")]
-    {
-        encoding.push("br");
-    }
-
-    #[cfg(feature = "compress-gzip")]
-    {
-        encoding.push("gzip");
-        encoding.push("deflate");
-    }
-
-    #[cfg(feature = "compress-zstd")]
-    {
-        encoding.push("zstd");
-    }
-
-    assert!(
-        !encoding.is_empty(),
-        "encoding can not be empty unless __compress feature has been explicitly enabled by itself"
-    );
-
-    encoding.join(", ")
-});
-
 static SUPPORTED_ENCODINGS: Lazy<Vec<Encoding>> = Lazy::new(|| {
-    let mut encodings = vec![Encoding::identity()];
+    let mut encodings = vec![Encoding::Identity];
 
     #[cfg(feature = "compress-brotli")]
     {
-        encodings.push(Encoding::brotli());
+        encodings.push(Encoding::Brotli);
     }
 
     #[cfg(feature = "compress-gzip")]
     {
-        encodings.push(Encoding::gzip());
-        encodings.push(Encoding::deflate());
+        encodings.push(Encoding::Gzip);
+        encodings.push(Encoding::Deflate);
     }
 
     #[cfg(feature = "compress-zstd")]
     {
-        encodings.push(Encoding::zstd());
+        encodings.push(Encoding::Zstd);
     }
 
     assert!(
@@ -330,4 +346,40 @@ mod tests {
         assert!(vary_headers.contains(&HeaderValue::from_static("x-test")));
         assert!(vary_headers.contains(&HeaderValue::from_static("accept-encoding")));
     }
+
+    #[actix_rt::test]
+    async fn configured_encodings() {
+        let app = test::init_service({
+            App::new()
+                .wrap(
+                    Compress::default()
+                        .encodings([Encoding::Gzip, Encoding::Other("compress".to_owned())]),
+                )
+                .default_service(web::to(move || HttpResponse::Ok().body("hello world")))
+        })
+        .await;
+
+        let req = test::TestRequest::default()
+            .insert_header((header::ACCEPT_ENCODING, "br, gzip;q=0.5"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(header::CONTENT_ENCODING).unwrap(), "gzip");
+
+        // unsupported encodings fall back to identity
+        let req = test::TestRequest::default()
+            .insert_header((header::ACCEPT_ENCODING, "br, compress"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(header::CONTENT_ENCODING).is_none());
+        assert_eq!(test::read_body(res).await, "hello world");
+
+        let req = test::TestRequest::default()
+            .insert_header((header::ACCEPT_ENCODING, "br, *;q=0"))
+            .to_request();
+        let res = test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_ACCEPTABLE);
+        assert_eq!(test::read_body(res).await, "gzip");
+    }
 }
@@ -0,0 +1,220 @@
+//! For middleware documentation, see [`ContentLengthCap`].
+
+use std::convert::TryFrom as _;
+
+use actix_http::body::BodySize;
+use actix_service::{Service, Transform};
+use actix_utils::future::{ok, Ready};
+use futures_core::future::LocalBoxFuture;
+
+use crate::{
+    body::{EitherBody, MessageBody},
+    error::ErrorInternalServerError,
+    http::header::{from_one_raw_str, ContentLength, CONTENT_LENGTH},
+    service::{ServiceRequest, ServiceResponse},
+    web::ByteSize,
+    Error,
+};
+
+/// Middleware that flags responses whose `Content-Length` exceeds a configured maximum.
+///
+/// This is a safety valve for catching accidentally huge responses. The declared length is read
+/// from a [`ContentLength`] header, if one was set, or otherwise from the size of the response
+/// body. Streaming bodies of unknown size are not checked.
+///
+/// Over-cap responses are always logged as a warning. In [`reject`](Self::reject) mode, they are
+/// also replaced with a "500 Internal Server Error" response.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::ContentLengthCap, App};
+///
+/// // log responses larger than 10MiB
+/// let app = App::new().wrap(ContentLengthCap::log(10 * 1024 * 1024));
+///
+/// // refuse to send responses larger than 10MiB
+/// let app = App::new().wrap(ContentLengthCap::reject(10 * 1024 * 1024));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ContentLengthCap {
+    max: usize,
+    reject: bool,
+}
+
+impl ContentLengthCap {
+    /// Constructs new middleware that logs responses declaring more than `max` bytes.
+    pub fn log(max: impl Into<ByteSize>) -> Self {
+        Self {
+            max: max.into().as_usize(),
+            reject: false,
+        }
+    }
+
+    /// Constructs new middleware that logs and rejects responses declaring more than `max` bytes.
+    pub fn reject(max: impl Into<ByteSize>) -> Self {
+        Self {
+            max: max.into().as_usize(),
+            reject: true,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for ContentLengthCap
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = ContentLengthCapMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(ContentLengthCapMiddleware {
+            service,
+            config: *self,
+        })
+    }
+}
+
+pub struct ContentLengthCapMiddleware<S> {
+    service: S,
+    config: ContentLengthCap,
+}
+
+impl<S, B> Service<ServiceRequest> for ContentLengthCapMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let fut = self.service.call(req);
+        let ContentLengthCap { max, reject } = self.config;
+
+        Box::pin(async move {
+            let res = fut.await?;
+
+            let ContentLength(len) = match declared_length(&res) {
+                Some(len) if len.0 > max => len,
+                _ => return Ok(res.map_into_left_body()),
+            };
+
+            log::warn!(
+                "Response to {} {} declares {} bytes, over the {} byte cap",
+                res.request().method(),
+                res.request().path(),
+                len,
+                max
+            );
+
+            if !reject {
+                return Ok(res.map_into_left_body());
+            }
+
+            let (req, _) = res.into_parts();
+            let err = ErrorInternalServerError("response exceeds content length cap");
+            Ok(ServiceResponse::from_err(err, req).map_into_right_body())
+        })
+    }
+}
+
+/// Returns the length declared by the `Content-Length` header or, if absent, the body size.
+fn declared_length<B: MessageBody>(res: &ServiceResponse<B>) -> Option<ContentLength> {
+    if let Some(val) = res.headers().get(CONTENT_LENGTH) {
+        return from_one_raw_str(Some(val)).ok().map(ContentLength);
+    }
+
+    match res.response().body().size() {
+        BodySize::Sized(len) => usize::try_from(len).ok().map(ContentLength),
+        BodySize::None | BodySize::Stream => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use futures_util::stream;
+
+    use super::*;
+    use crate::{
+        http::StatusCode,
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    async fn app(
+        cap: ContentLengthCap,
+    ) -> impl Service<actix_http::Request, Response = ServiceResponse<impl MessageBody>, Error = Error>
+    {
+        test::init_service(
+            App::new()
+                .wrap(cap)
+                .route("/small", web::get().to(|| async { "small" }))
+                .route("/large", web::get().to(|| async { "x".repeat(100) }))
+                .route(
+                    "/declared",
+                    web::get().to(|| {
+                        HttpResponse::Ok()
+                            .insert_header(ContentLength(100))
+                            .streaming(stream::iter([Ok::<_, Error>(Bytes::from("x"))]))
+                    }),
+                )
+                .route(
+                    "/stream",
+                    web::get().to(|| {
+                        HttpResponse::Ok().streaming(stream::iter([Ok::<_, Error>(
+                            Bytes::from("x".repeat(100)),
+                        )]))
+                    }),
+                ),
+        )
+        .await
+    }
+
+    #[actix_rt::test]
+    async fn under_cap_passes() {
+        let srv = app(ContentLengthCap::reject(10)).await;
+
+        let req = TestRequest::with_uri("/small").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "small");
+
+        // streams of unknown size are not checked
+        let req = TestRequest::with_uri("/stream").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn over_cap_rejected() {
+        let srv = app(ContentLengthCap::reject(10)).await;
+
+        let req = TestRequest::with_uri("/large").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+        let req = TestRequest::with_uri("/declared").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[actix_rt::test]
+    async fn over_cap_logged() {
+        let srv = app(ContentLengthCap::log(10)).await;
+
+        let req = TestRequest::with_uri("/large").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await.len(), 100);
+    }
+}
@@ -0,0 +1,658 @@
+//! For middleware documentation, see [`Cors`].
+
+use futures_core::future::LocalBoxFuture;
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use crate::{
+    body::{EitherBody, MessageBody},
+    dev::{Service, Transform},
+    http::{
+        header::{
+            AccessControlAllowCredentials, AccessControlAllowHeaders,
+            AccessControlAllowMethods, AccessControlAllowOrigin, AccessControlExposeHeaders,
+            AccessControlMaxAge, AccessControlRequestHeaders, AccessControlRequestMethod,
+            Header, HeaderMap, HeaderName, HeaderValue, ACCESS_CONTROL_REQUEST_METHOD, ORIGIN,
+            VARY,
+        },
+        Method,
+    },
+    service::{ServiceRequest, ServiceResponse},
+    Error, HttpResponse,
+};
+
+/// Maximum number of headers a preflight request may ask for when any header is allowed.
+const MAX_REFLECTED_HEADERS: usize = 32;
+
+/// Middleware implementing a minimal subset of [Cross-Origin Resource Sharing (CORS)][cors].
+///
+/// Requests with an `Origin` header that does not match the request's own origin are handled as
+/// cross-origin requests:
+/// - Preflight requests (`OPTIONS` requests with an `Access-Control-Request-Method` header) are
+///   answered directly, before routing.
+/// - Other requests from allowed origins are passed on, and the response is given the
+///   `Access-Control-Allow-Origin` header and, if configured, the
+///   `Access-Control-Allow-Credentials` and `Access-Control-Expose-Headers` headers.
+/// - Requests from other origins, and preflights asking for a method or headers that are not
+///   allowed, are rejected with "403 Forbidden", or, after calling
+///   [`block_on_origin_mismatch(false)`](Self::block_on_origin_mismatch), handled as usual but
+///   without any CORS headers, leaving it to the browser to block the response.
+///
+/// Allowed origins are reflected in the `Access-Control-Allow-Origin` header, so every response
+/// gets a `Vary: Origin` header for caches.
+///
+/// `Cors::default()` allows no origins, the `GET`, `HEAD`, and `POST` methods, and no headers
+/// beyond the CORS-safelisted ones. [`Cors::permissive()`] allows any origin, method, and header.
+///
+/// # Examples
+/// ```
+/// use actix_web::{
+///     http::{header, Method},
+///     middleware::Cors,
+///     web, App, HttpResponse,
+/// };
+///
+/// let cors = Cors::default()
+///     .allowed_origins(["https://app.example.com"])
+///     .allowed_methods([Method::GET, Method::PUT])
+///     .allowed_headers([header::CONTENT_TYPE, header::AUTHORIZATION])
+///     .expose_headers([header::ETAG])
+///     .max_age(3600)
+///     .supports_credentials();
+///
+/// let app = App::new()
+///     .wrap(cors)
+///     .route("/", web::get().to(HttpResponse::Ok));
+/// ```
+///
+/// [cors]: https://developer.mozilla.org/en-US/docs/Web/HTTP/CORS
+#[derive(Debug, Clone)]
+pub struct Cors {
+    inner: Rc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    /// Allowed origins, or `None` if any origin is allowed.
+    origins: Option<Vec<String>>,
+
+    /// Allowed methods, or `None` if any method is allowed.
+    methods: Option<Vec<Method>>,
+
+    /// Allowed request headers, or `None` if any header is allowed.
+    headers: Option<Vec<HeaderName>>,
+
+    expose_headers: Vec<HeaderName>,
+    max_age: Option<u32>,
+    credentials: bool,
+    block: bool,
+}
+
+impl Default for Cors {
+    fn default() -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                origins: Some(Vec::new()),
+                methods: Some(vec![Method::GET, Method::HEAD, Method::POST]),
+                headers: Some(Vec::new()),
+                expose_headers: Vec::new(),
+                max_age: None,
+                credentials: false,
+                block: true,
+            }),
+        }
+    }
+}
+
+impl Cors {
+    /// Constructs a `Cors` middleware that allows any origin, method, and request header.
+    ///
+    /// The requesting origin, and the method and headers asked for by preflight requests, are
+    /// reflected back. Useful during development; prefer listing origins in production.
+    pub fn permissive() -> Self {
+        let mut cors = Self::default();
+
+        let inner = cors.inner_mut();
+        inner.origins = None;
+        inner.methods = None;
+        inner.headers = None;
+
+        cors
+    }
+
+    /// Allows requests from `origins`, such as `https://example.com`.
+    ///
+    /// Origins are matched exactly against the `Origin` request header. An origin of `*` allows
+    /// any origin.
+    ///
+    /// # Panics
+    /// Panics if `origins` contains `*` and credentials are [supported](Self::supports_credentials).
+    pub fn allowed_origins<I, S>(mut self, origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let inner = self.inner_mut();
+
+        for origin in origins {
+            let origin = origin.as_ref();
+
+            if origin == "*" {
+                assert!(
+                    !inner.credentials,
+                    "CORS credentials can not be supported for any origin"
+                );
+                inner.origins = None;
+            } else if let Some(list) = &mut inner.origins {
+                if !list.iter().any(|allowed| allowed == origin) {
+                    list.push(origin.to_owned());
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Allows `methods` to be used by cross-origin requests, replacing the default set.
+    pub fn allowed_methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.inner_mut().methods = Some(methods.into_iter().collect());
+        self
+    }
+
+    /// Allows `headers` to be sent with cross-origin requests, in addition to the CORS-safelisted
+    /// request headers.
+    pub fn allowed_headers(mut self, headers: impl IntoIterator<Item = HeaderName>) -> Self {
+        let inner = self.inner_mut();
+        let list = inner.headers.get_or_insert_with(Vec::new);
+
+        for header in headers {
+            if !list.contains(&header) {
+                list.push(header);
+            }
+        }
+
+        self
+    }
+
+    /// Allows cross-origin requests to read `headers` from responses, in addition to the
+    /// CORS-safelisted response headers.
+    pub fn expose_headers(mut self, headers: impl IntoIterator<Item = HeaderName>) -> Self {
+        self.inner_mut().expose_headers.extend(headers);
+        self
+    }
+
+    /// Allows browsers to cache preflight responses for `seconds`.
+    pub fn max_age(mut self, seconds: u32) -> Self {
+        self.inner_mut().max_age = Some(seconds);
+        self
+    }
+
+    /// Allows cross-origin requests to include credentials, such as cookies.
+    ///
+    /// # Panics
+    /// Panics if any origin is allowed. Credentials can only be supported for listed origins.
+    pub fn supports_credentials(mut self) -> Self {
+        let inner = self.inner_mut();
+
+        assert!(
+            inner.origins.is_some(),
+            "CORS credentials can not be supported for any origin"
+        );
+        inner.credentials = true;
+
+        self
+    }
+
+    /// Sets whether requests from disallowed origins are rejected with "403 Forbidden".
+    ///
+    /// Enabled by default. When disabled, such requests are handled as usual, but without any CORS
+    /// headers.
+    pub fn block_on_origin_mismatch(mut self, block: bool) -> Self {
+        self.inner_mut().block = block;
+        self
+    }
+
+    fn inner_mut(&mut self) -> &mut Inner {
+        Rc::get_mut(&mut self.inner).expect("CORS must be configured before cloning.")
+    }
+}
+
+impl Inner {
+    fn allows_origin(&self, origin: &str) -> bool {
+        match &self.origins {
+            Some(origins) => origins.iter().any(|allowed| allowed == origin),
+            None => true,
+        }
+    }
+
+    fn allows_method(&self, method: &Method) -> bool {
+        match &self.methods {
+            Some(methods) => methods.contains(method),
+            None => true,
+        }
+    }
+
+    fn allows_headers(&self, headers: &[HeaderName]) -> bool {
+        match &self.headers {
+            Some(allowed) => headers.iter().all(|header| allowed.contains(header)),
+            None => headers.len() <= MAX_REFLECTED_HEADERS,
+        }
+    }
+
+    /// Builds the response to a preflight request from `origin`.
+    fn preflight(&self, req: &ServiceRequest, origin: &str) -> HttpResponse {
+        let method = AccessControlRequestMethod::parse(req).ok();
+        let headers = AccessControlRequestHeaders::parse(req).ok();
+
+        let (method, headers) = match (method, headers) {
+            (
+                Some(AccessControlRequestMethod(method)),
+                Some(AccessControlRequestHeaders(headers)),
+            ) if self.allows_origin(origin)
+                && self.allows_method(&method)
+                && self.allows_headers(&headers) =>
+            {
+                (method, headers)
+            }
+
+            _ => {
+                let mut res = self.reject();
+                res.headers_mut().append(
+                    VARY,
+                    HeaderValue::from_static(
+                        "Access-Control-Request-Method, Access-Control-Request-Headers",
+                    ),
+                );
+                return res;
+            }
+        };
+
+        let mut res = HttpResponse::Ok();
+        res.append_header((
+            VARY,
+            "Origin, Access-Control-Request-Method, Access-Control-Request-Headers",
+        ));
+        res.insert_header(AccessControlAllowOrigin(origin.to_owned()));
+
+        if self.credentials {
+            res.insert_header(AccessControlAllowCredentials);
+        }
+
+        res.insert_header(AccessControlAllowMethods(match &self.methods {
+            Some(methods) => methods.clone(),
+            None => vec![method],
+        }));
+
+        let allowed_headers = match &self.headers {
+            Some(allowed) => allowed.clone(),
+            None => headers,
+        };
+
+        if !allowed_headers.is_empty() {
+            res.insert_header(AccessControlAllowHeaders(allowed_headers));
+        }
+
+        if let Some(max_age) = self.max_age {
+            res.insert_header(AccessControlMaxAge(max_age));
+        }
+
+        res.finish()
+    }
+
+    /// Builds the response to a request that is not allowed.
+    fn reject(&self) -> HttpResponse {
+        let mut res = if self.block {
+            HttpResponse::Forbidden()
+        } else {
+            HttpResponse::Ok()
+        };
+
+        res.append_header((VARY, "Origin")).finish()
+    }
+
+    /// Adds the CORS headers for an actual request from an allowed `origin` to `headers`.
+    fn add_headers(&self, headers: &mut HeaderMap, origin: &str) {
+        insert(headers, AccessControlAllowOrigin(origin.to_owned()));
+
+        if self.credentials {
+            insert(headers, AccessControlAllowCredentials);
+        }
+
+        if !self.expose_headers.is_empty() {
+            insert(
+                headers,
+                AccessControlExposeHeaders(self.expose_headers.clone()),
+            );
+        }
+    }
+}
+
+/// Inserts a typed header into `headers`, skipping values that can not be serialized.
+fn insert<H: Header>(headers: &mut HeaderMap, header: H) {
+    if let Ok(value) = header.try_into_value() {
+        headers.insert(H::name(), value);
+    }
+}
+
+/// Returns the request's `Origin` header if it is a cross-origin request.
+///
+/// Browsers also send `Origin` with some same-origin requests, which are compared against the
+/// request's own scheme and host.
+fn cross_origin(req: &ServiceRequest) -> Option<String> {
+    let origin = req.headers().get(ORIGIN)?.to_str().ok()?;
+
+    let info = req.connection_info();
+    let own = format!("{}://{}", info.scheme(), info.host());
+
+    if origin.eq_ignore_ascii_case(&own) {
+        None
+    } else {
+        Some(origin.to_owned())
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Cors
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = CorsMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CorsMiddleware {
+            service,
+            inner: Rc::clone(&self.inner),
+        }))
+    }
+}
+
+pub struct CorsMiddleware<S> {
+    service: S,
+    inner: Rc<Inner>,
+}
+
+impl<S, B> Service<ServiceRequest> for CorsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let origin = cross_origin(&req);
+
+        if let Some(origin) = &origin {
+            if req.method() == Method::OPTIONS
+                && req.headers().contains_key(ACCESS_CONTROL_REQUEST_METHOD)
+            {
+                let res = self.inner.preflight(&req, origin);
+                return Box::pin(ready(Ok(req.into_response(res).map_into_right_body())));
+            }
+
+            if !self.inner.allows_origin(origin) && self.inner.block {
+                let res = self.inner.reject();
+                return Box::pin(ready(Ok(req.into_response(res).map_into_right_body())));
+            }
+        }
+
+        let inner = Rc::clone(&self.inner);
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+            let headers = res.headers_mut();
+
+            match origin {
+                Some(origin) if inner.allows_origin(&origin) => {
+                    inner.add_headers(headers, &origin)
+                }
+                _ => {}
+            }
+
+            headers.append(VARY, HeaderValue::from_static("Origin"));
+
+            Ok(res.map_into_left_body())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        http::{
+            header::{self, ACCESS_CONTROL_REQUEST_HEADERS},
+            StatusCode,
+        },
+        test::{self, TestRequest},
+        web, App,
+    };
+
+    const ORIGIN_OK: &str = "https://app.example.com";
+    const ORIGIN_BAD: &str = "https://evil.example.com";
+
+    async fn app(
+        cors: Cors,
+    ) -> impl Service<actix_http::Request, Response = ServiceResponse<impl MessageBody>, Error = Error>
+    {
+        test::init_service(App::new().wrap(cors).route(
+            "/",
+            web::get().to(|| {
+                HttpResponse::Ok()
+                    .insert_header((header::ETAG, "\"1\""))
+                    .finish()
+            }),
+        ))
+        .await
+    }
+
+    fn strict() -> Cors {
+        Cors::default()
+            .allowed_origins([ORIGIN_OK])
+            .allowed_methods([Method::GET, Method::PUT])
+            .allowed_headers([header::CONTENT_TYPE])
+            .expose_headers([header::ETAG])
+            .max_age(600)
+            .supports_credentials()
+    }
+
+    fn preflight(origin: &str, method: &str, headers: &str) -> actix_http::Request {
+        let mut req = TestRequest::default()
+            .method(Method::OPTIONS)
+            .insert_header((ORIGIN, origin))
+            .insert_header((ACCESS_CONTROL_REQUEST_METHOD, method));
+
+        if !headers.is_empty() {
+            req = req.insert_header((ACCESS_CONTROL_REQUEST_HEADERS, headers));
+        }
+
+        req.to_request()
+    }
+
+    fn get<B>(res: &ServiceResponse<B>, name: HeaderName) -> Option<&str> {
+        res.headers().get(name).map(|val| val.to_str().unwrap())
+    }
+
+    fn vary<B>(res: &ServiceResponse<B>) -> Vec<&str> {
+        res.headers()
+            .get_all(VARY)
+            .flat_map(|val| val.to_str().unwrap().split(", "))
+            .collect()
+    }
+
+    #[actix_rt::test]
+    async fn matching_preflight() {
+        let srv = app(strict()).await;
+
+        let req = preflight(ORIGIN_OK, "PUT", "content-type");
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(ORIGIN_OK)
+        );
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_ALLOW_METHODS),
+            Some("GET, PUT")
+        );
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_ALLOW_HEADERS),
+            Some("content-type")
+        );
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
+        assert_eq!(get(&res, header::ACCESS_CONTROL_MAX_AGE), Some("600"));
+        assert_eq!(
+            vary(&res),
+            [
+                "Origin",
+                "Access-Control-Request-Method",
+                "Access-Control-Request-Headers"
+            ]
+        );
+
+        // disallowed method and header
+        let req = preflight(ORIGIN_OK, "DELETE", "");
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        assert!(!res
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let req = preflight(ORIGIN_OK, "PUT", "content-type, x-api-key");
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_rt::test]
+    async fn rejected_origin() {
+        let srv = app(strict()).await;
+
+        let req = preflight(ORIGIN_BAD, "GET", "");
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        assert!(!res
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+        let req = TestRequest::default()
+            .insert_header((ORIGIN, ORIGIN_BAD))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        assert_eq!(vary(&res), ["Origin"]);
+
+        // omit headers instead of blocking
+        let srv = app(strict().block_on_origin_mismatch(false)).await;
+
+        let req = TestRequest::default()
+            .insert_header((ORIGIN, ORIGIN_BAD))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!res
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+        assert_eq!(vary(&res), ["Origin"]);
+    }
+
+    #[actix_rt::test]
+    async fn header_reflection_limits() {
+        let srv = app(Cors::permissive()).await;
+
+        let req = preflight(ORIGIN_BAD, "PATCH", "x-one, x-two");
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(ORIGIN_BAD)
+        );
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_ALLOW_METHODS),
+            Some("PATCH")
+        );
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_ALLOW_HEADERS),
+            Some("x-one, x-two")
+        );
+        assert_eq!(get(&res, header::ACCESS_CONTROL_ALLOW_CREDENTIALS), None);
+
+        let many = (0..=MAX_REFLECTED_HEADERS)
+            .map(|idx| format!("x-header-{}", idx))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let req = preflight(ORIGIN_BAD, "GET", &many);
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[actix_rt::test]
+    async fn actual_request_headers() {
+        let srv = app(strict()).await;
+
+        let req = TestRequest::default()
+            .insert_header((ORIGIN, ORIGIN_OK))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            Some(ORIGIN_OK)
+        );
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+            Some("true")
+        );
+        assert_eq!(
+            get(&res, header::ACCESS_CONTROL_EXPOSE_HEADERS),
+            Some("etag")
+        );
+        assert_eq!(vary(&res), ["Origin"]);
+
+        // same-origin and non-browser requests are not cross-origin
+        for origin in [Some("http://localhost:8080"), None] {
+            let mut req = TestRequest::default();
+            if let Some(origin) = origin {
+                req = req.insert_header((ORIGIN, origin));
+            }
+
+            let res = test::call_service(&srv, req.to_request()).await;
+            assert_eq!(res.status(), StatusCode::OK);
+            assert!(!res
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+            assert_eq!(vary(&res), ["Origin"]);
+        }
+    }
+
+    #[test]
+    #[should_panic = "CORS credentials can not be supported for any origin"]
+    fn credentials_with_any_origin() {
+        Cors::default()
+            .allowed_origins(["*"])
+            .supports_credentials();
+    }
+
+    #[test]
+    #[should_panic = "CORS credentials can not be supported for any origin"]
+    fn any_origin_with_credentials() {
+        Cors::default()
+            .allowed_origins([ORIGIN_OK])
+            .supports_credentials()
+            .allowed_origins(["*"]);
+    }
+}
@@ -0,0 +1,515 @@
+//! For access log target documentation, see [`LogTarget`].
+
+use std::{
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use log::warn;
+
+/// Number of access lines that can be queued for a writer thread before lines are dropped.
+const DEFAULT_CAPACITY: usize = 1024;
+
+/// Minimum time between checks of whether a log file has been moved or replaced.
+const FILE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Destination for access lines written by the [`Logger`](super::Logger) middleware.
+///
+/// By default, access lines go through the `log` crate. Writer and file targets instead hand
+/// lines to a dedicated thread through a bounded queue so that slow I/O never blocks a worker.
+/// If the queue is full, or the thread has stopped, lines are dropped and counted; see
+/// [`LogTargetHandle::dropped_lines`].
+///
+/// File targets cooperate with log rotation tools. The file is re-opened when its path no longer
+/// refers to the opened file (checked at most once per second, on Unix only) or when
+/// [`LogTargetHandle::reopen`] is called, such as from a `SIGHUP` handler.
+///
+/// # Examples
+/// ```no_run
+/// use actix_web::{middleware::{LogTarget, Logger}, App};
+///
+/// let target = LogTarget::file("/var/log/my-app/access.log").unwrap();
+/// let handle = target.handle().unwrap();
+///
+/// let app = App::new().wrap(Logger::default().target(target));
+///
+/// // after logrotate has moved the file
+/// handle.reopen();
+/// ```
+#[derive(Clone, Default)]
+pub struct LogTarget {
+    queue: Option<LogTargetHandle>,
+}
+
+impl LogTarget {
+    /// Returns the default target, which writes through the `log` crate.
+    pub fn log() -> Self {
+        Self::default()
+    }
+
+    /// Returns a target that writes lines to `writer` from a dedicated thread.
+    pub fn writer(writer: impl Write + Send + 'static) -> Self {
+        Self::writer_with_capacity(writer, DEFAULT_CAPACITY)
+    }
+
+    /// Returns a target that writes lines to `writer`, queueing at most `capacity` lines.
+    pub fn writer_with_capacity(writer: impl Write + Send + 'static, capacity: usize) -> Self {
+        Self::spawn(Sink::Writer(Box::new(writer)), capacity)
+    }
+
+    /// Returns a target that appends lines to the file at `path`, creating it if needed.
+    ///
+    /// # Errors
+    /// Returns an error if the file can not be opened.
+    pub fn file(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::file_with_capacity(path, DEFAULT_CAPACITY)
+    }
+
+    /// Returns a target that appends lines to the file at `path`, queueing at most `capacity`
+    /// lines.
+    ///
+    /// # Errors
+    /// Returns an error if the file can not be opened.
+    pub fn file_with_capacity(path: impl AsRef<Path>, capacity: usize) -> io::Result<Self> {
+        let file = LogFile::open(path.as_ref().to_owned())?;
+        Ok(Self::spawn(Sink::File(file), capacity))
+    }
+
+    /// Returns a handle for controlling the writer thread, or `None` for the `log` crate target.
+    pub fn handle(&self) -> Option<LogTargetHandle> {
+        self.queue.clone()
+    }
+
+    /// Returns true if this target writes through the `log` crate.
+    pub(crate) fn is_log(&self) -> bool {
+        self.queue.is_none()
+    }
+
+    /// Queues an access line, dropping it if the queue is full or the writer thread has stopped.
+    pub(crate) fn write_line(&self, line: String) {
+        if let Some(queue) = &self.queue {
+            match queue.tx.try_send(Command::Line(line)) {
+                Ok(()) => {}
+                Err(TrySendError::Full(_)) => {
+                    queue.state.dropped.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(TrySendError::Disconnected(_)) => {
+                    queue.state.dropped.fetch_add(1, Ordering::Relaxed);
+
+                    if !queue.state.stopped.swap(true, Ordering::Relaxed) {
+                        warn!("Access log writer thread has stopped; access lines are dropped");
+                    }
+                }
+            }
+        }
+    }
+
+    fn spawn(mut sink: Sink, capacity: usize) -> Self {
+        let (tx, rx) = mpsc::sync_channel(capacity);
+        let state = Arc::new(QueueState::default());
+
+        let thread_state = Arc::clone(&state);
+        thread::Builder::new()
+            .name("actix-web-access-log".to_owned())
+            .spawn(move || sink.run(rx, &thread_state))
+            .expect("failed to spawn access log writer thread");
+
+        Self {
+            queue: Some(LogTargetHandle { tx, state }),
+        }
+    }
+}
+
+impl fmt::Debug for LogTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogTarget")
+            .field("is_log", &self.is_log())
+            .finish()
+    }
+}
+
+/// Handle to the writer thread of a [`LogTarget`].
+///
+/// The thread stops once its target and all handles have been dropped and remaining lines have
+/// been written.
+#[derive(Clone)]
+pub struct LogTargetHandle {
+    tx: SyncSender<Command>,
+    state: Arc<QueueState>,
+}
+
+/// State shared between a [`LogTargetHandle`] and its writer thread.
+#[derive(Default)]
+struct QueueState {
+    /// Number of lines dropped because the queue was full or the thread had stopped.
+    dropped: AtomicU64,
+
+    /// Set when a re-open is requested; checked by the thread before each line is written.
+    reopen: AtomicBool,
+
+    /// Set once a line is dropped because the thread has stopped, so the warning is logged once.
+    stopped: AtomicBool,
+}
+
+impl LogTargetHandle {
+    /// Re-opens the log file after lines queued so far have been written.
+    ///
+    /// Never blocks, so it can be called from a signal handling task. If the queue is full, the
+    /// file is instead re-opened before the next line is written. Returns `false` if the writer
+    /// thread has stopped and the file will not be re-opened.
+    ///
+    /// Has no effect on writer targets.
+    pub fn reopen(&self) -> bool {
+        self.state.reopen.store(true, Ordering::Relaxed);
+
+        match self.tx.try_send(Command::Reopen) {
+            Ok(()) | Err(TrySendError::Full(_)) => true,
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+
+    /// Blocks until lines queued so far have been written and flushed.
+    pub fn flush(&self) {
+        let (tx, rx) = mpsc::sync_channel(1);
+
+        if self.tx.send(Command::Flush(tx)).is_ok() {
+            let _ = rx.recv();
+        }
+    }
+
+    /// Returns the number of lines dropped because the queue was full or the writer thread had
+    /// stopped.
+    pub fn dropped_lines(&self) -> u64 {
+        self.state.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for LogTargetHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LogTargetHandle")
+            .field("dropped_lines", &self.dropped_lines())
+            .finish()
+    }
+}
+
+enum Command {
+    Line(String),
+    Reopen,
+    Flush(SyncSender<()>),
+}
+
+enum Sink {
+    Writer(Box<dyn Write + Send>),
+    File(LogFile),
+}
+
+impl Sink {
+    fn run(&mut self, rx: Receiver<Command>, state: &QueueState) {
+        while let Ok(cmd) = rx.recv() {
+            self.handle(cmd, state);
+
+            // flush once the queue has been drained
+            while let Ok(cmd) = rx.try_recv() {
+                self.handle(cmd, state);
+            }
+
+            if let Err(err) = self.writer().flush() {
+                warn!("Failed to flush access log: {}", err);
+            }
+        }
+    }
+
+    fn handle(&mut self, cmd: Command, state: &QueueState) {
+        match cmd {
+            Command::Line(mut line) => {
+                if let Sink::File(file) = self {
+                    if state.reopen.swap(false, Ordering::Relaxed) {
+                        file.reopen();
+                    } else {
+                        file.check_moved();
+                    }
+                }
+
+                line.push('\n');
+
+                if let Err(err) = self.writer().write_all(line.as_bytes()) {
+                    warn!("Failed to write access log: {}", err);
+                }
+            }
+
+            Command::Reopen => {
+                // the request may already have been handled before an earlier line
+                if state.reopen.swap(false, Ordering::Relaxed) {
+                    if let Sink::File(file) = self {
+                        file.reopen();
+                    }
+                }
+            }
+
+            Command::Flush(done) => {
+                if let Err(err) = self.writer().flush() {
+                    warn!("Failed to flush access log: {}", err);
+                }
+
+                let _ = done.send(());
+            }
+        }
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match self {
+            Sink::Writer(writer) => writer,
+            Sink::File(file) => &mut file.writer,
+        }
+    }
+}
+
+struct LogFile {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    id: Option<(u64, u64)>,
+    checked_at: Instant,
+}
+
+impl LogFile {
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let id = file.metadata().ok().and_then(|meta| file_id(&meta));
+
+        Ok(Self {
+            path,
+            writer: BufWriter::new(file),
+            id,
+            checked_at: Instant::now(),
+        })
+    }
+
+    /// Re-opens the file if its path refers to a different file than the one being written.
+    fn check_moved(&mut self) {
+        if self.id.is_none() || self.checked_at.elapsed() < FILE_CHECK_INTERVAL {
+            return;
+        }
+
+        self.checked_at = Instant::now();
+
+        let current = fs::metadata(&self.path)
+            .ok()
+            .and_then(|meta| file_id(&meta));
+
+        if current != self.id {
+            self.reopen();
+        }
+    }
+
+    fn reopen(&mut self) {
+        if let Err(err) = self.writer.flush() {
+            warn!("Failed to flush access log: {}", err);
+        }
+
+        match Self::open(self.path.clone()) {
+            Ok(file) => *self = file,
+            Err(err) => warn!(
+                "Failed to re-open access log {}: {}",
+                self.path.display(),
+                err
+            ),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn file_id(meta: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt as _;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_meta: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("actix-web-{}-{}.log", name, std::process::id()))
+    }
+
+    #[test]
+    fn reopen_after_rename() {
+        let path = temp_path("access");
+        let rotated = temp_path("access-rotated");
+
+        let target = LogTarget::file(&path).unwrap();
+        let handle = target.handle().unwrap();
+
+        target.write_line("first".to_owned());
+        handle.flush();
+
+        fs::rename(&path, &rotated).unwrap();
+
+        // lines written before re-opening still go to the moved file
+        target.write_line("second".to_owned());
+        handle.reopen();
+        target.write_line("third".to_owned());
+        handle.flush();
+
+        assert_eq!(fs::read_to_string(&rotated).unwrap(), "first\nsecond\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "third\n");
+        assert_eq!(handle.dropped_lines(), 0);
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&rotated).unwrap();
+    }
+
+    struct Blocking {
+        started: SyncSender<()>,
+        release: Receiver<()>,
+    }
+
+    impl Write for Blocking {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let _ = self.started.send(());
+            let _ = self.release.recv();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn reopen_with_full_queue() {
+        let (started_tx, started) = mpsc::sync_channel(0);
+        let (release, release_rx) = mpsc::channel();
+
+        let target = LogTarget::writer_with_capacity(
+            Blocking {
+                started: started_tx,
+                release: release_rx,
+            },
+            1,
+        );
+        let handle = target.handle().unwrap();
+
+        // block the writer thread and fill the queue
+        target.write_line("0".to_owned());
+        started.recv().unwrap();
+        target.write_line("1".to_owned());
+
+        // does not wait for the queue to drain
+        assert!(handle.reopen());
+
+        drop(started);
+        for _ in 0..10 {
+            release.send(()).unwrap();
+        }
+
+        handle.flush();
+        assert_eq!(handle.dropped_lines(), 0);
+    }
+
+    #[test]
+    fn reopen_requested_before_next_line() {
+        let path = temp_path("access-flag");
+        let rotated = temp_path("access-flag-rotated");
+
+        let mut sink = Sink::File(LogFile::open(path.clone()).unwrap());
+        let state = QueueState::default();
+
+        sink.handle(Command::Line("first".to_owned()), &state);
+        sink.writer().flush().unwrap();
+        fs::rename(&path, &rotated).unwrap();
+
+        // as when the reopen command could not be queued
+        state.reopen.store(true, Ordering::Relaxed);
+        sink.handle(Command::Line("second".to_owned()), &state);
+        sink.writer().flush().unwrap();
+        assert!(!state.reopen.load(Ordering::Relaxed));
+
+        assert_eq!(fs::read_to_string(&rotated).unwrap(), "first\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second\n");
+
+        // a late reopen command does not re-open the file again
+        fs::remove_file(&path).unwrap();
+        sink.handle(Command::Reopen, &state);
+        assert!(!path.exists());
+
+        fs::remove_file(&rotated).unwrap();
+    }
+
+    #[test]
+    fn stopped_writer_drops_lines() {
+        struct Panicking;
+
+        impl Write for Panicking {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                panic!("writer failed");
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let target = LogTarget::writer(Panicking);
+        let handle = target.handle().unwrap();
+
+        // the writer thread panics on the first line; flushing returns once it has stopped
+        target.write_line("0".to_owned());
+        handle.flush();
+
+        for idx in 1..=3 {
+            target.write_line(idx.to_string());
+        }
+
+        assert_eq!(handle.dropped_lines(), 3);
+        assert!(handle.state.stopped.load(Ordering::Relaxed));
+        assert!(!handle.reopen());
+    }
+
+    #[test]
+    fn overflow_drops_lines() {
+        let (started_tx, started) = mpsc::sync_channel(0);
+        let (release, release_rx) = mpsc::channel();
+
+        let target = LogTarget::writer_with_capacity(
+            Blocking {
+                started: started_tx,
+                release: release_rx,
+            },
+            2,
+        );
+        let handle = target.handle().unwrap();
+
+        // wait until the writer thread is blocked on the first line
+        target.write_line("0".to_owned());
+        started.recv().unwrap();
+
+        for idx in 1..=5 {
+            target.write_line(idx.to_string());
+        }
+
+        assert_eq!(handle.dropped_lines(), 3);
+
+        // unblock all remaining writes
+        drop(started);
+        for _ in 0..10 {
+            release.send(()).unwrap();
+        }
+
+        handle.flush();
+        assert_eq!(handle.dropped_lines(), 3);
+    }
+}
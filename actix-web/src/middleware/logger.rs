@@ -25,8 +25,10 @@ use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use crate::{
     body::{BodySize, MessageBody},
     http::header::HeaderName,
+    middleware::LogTarget,
     service::{ServiceRequest, ServiceResponse},
-    Error, HttpResponse, Result,
+    web::ApiVersion,
+    Error, HttpRequest, HttpResponse, Result,
 };
 
 /// Middleware for logging request and response summaries to the terminal.
@@ -67,13 +69,21 @@ use crate::{
 /// `%b` | Size of response in bytes, including HTTP headers
 /// `%T` | Time taken to serve the request, in seconds to 6 decimal places
 /// `%D` | Time taken to serve the request, in milliseconds
+/// `%Tf` | Time taken until the first chunk of the response body was produced, in seconds to 6 decimal places
+/// `%Tt` | Time taken until the response body finished (or was dropped), in seconds to 6 decimal places
 /// `%U` | Request URL
+/// `%V` | [API version](crate::web::ApiVersion) of the request, or `-` if it can not be resolved
 /// `%{r}a` | "Real IP" remote address **\***
 /// `%{FOO}i` |  `request.headers["FOO"]`
 /// `%{FOO}o` | `response.headers["FOO"]`
 /// `%{FOO}e` | `env_var["FOO"]`
 /// `%{FOO}xi` | [Custom request replacement](Logger::custom_request_replace) labelled "FOO"
 ///
+/// Access lines are written once the response body has completed (or is dropped), so `%T` and
+/// `%D` include the time spent streaming the body. For streaming responses, `%Tf` and `%Tt` can be
+/// used to distinguish a slow start from a slow stream. `%Tt` is taken when the body signals its
+/// end, so it includes any delay between the last data chunk and the end of the stream.
+///
 /// # Security
 /// **\*** "Real IP" remote address is calculated using
 /// [`ConnectionInfo::realip_remote_addr()`](crate::dev::ConnectionInfo::realip_remote_addr())
@@ -89,6 +99,7 @@ struct Inner {
     exclude: HashSet<String>,
     exclude_regex: RegexSet,
     log_target: Cow<'static, str>,
+    target: LogTarget,
 }
 
 impl Logger {
@@ -99,6 +110,7 @@ impl Logger {
             exclude: HashSet::new(),
             exclude_regex: RegexSet::empty(),
             log_target: Cow::Borrowed(module_path!()),
+            target: LogTarget::default(),
         }))
     }
 
@@ -139,6 +151,23 @@ impl Logger {
         self
     }
 
+    /// Sets the destination of access lines.
+    ///
+    /// By default, access lines are written through the `log` crate. See [`LogTarget`] for
+    /// writing them to a file that can be rotated while the server is running.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::middleware::{LogTarget, Logger};
+    ///
+    /// let logger = Logger::default().target(LogTarget::writer(std::io::stdout()));
+    /// ```
+    pub fn target(mut self, target: LogTarget) -> Self {
+        let inner = Rc::get_mut(&mut self.0).unwrap();
+        inner.target = target;
+        self
+    }
+
     /// Register a function that receives a ServiceRequest and returns a String for use in the
     /// log line. The label passed as the first argument should match a replacement substring in
     /// the logger format like `%{label}xi`.
@@ -193,6 +222,7 @@ impl Default for Logger {
             exclude: HashSet::new(),
             exclude_regex: RegexSet::empty(),
             log_target: Cow::Borrowed(module_path!()),
+            target: LogTarget::default(),
         }))
     }
 }
@@ -253,6 +283,7 @@ where
                 format: None,
                 time: OffsetDateTime::now_utc(),
                 log_target: Cow::Borrowed(""),
+                target: LogTarget::default(),
                 _phantom: PhantomData,
             }
         } else {
@@ -268,6 +299,7 @@ where
                 format: Some(format),
                 time: now,
                 log_target: self.inner.log_target.clone(),
+                target: self.inner.target.clone(),
                 _phantom: PhantomData,
             }
         }
@@ -285,6 +317,7 @@ pin_project! {
         time: OffsetDateTime,
         format: Option<Format>,
         log_target: Cow<'static, str>,
+        target: LogTarget,
         _phantom: PhantomData<B>,
     }
 }
@@ -311,19 +344,25 @@ where
         if let Some(ref mut format) = this.format {
             for unit in &mut format.0 {
                 unit.render_response(res.response());
+                unit.render_routed(res.request());
             }
         }
 
         let time = *this.time;
         let format = this.format.take();
         let log_target = this.log_target.clone();
+        let target = this.target.clone();
 
         Poll::Ready(Ok(res.map_body(move |_, body| StreamLog {
             body,
             time,
             format,
             size: 0,
+            first_chunk: None,
+            body_end: None,
+            clock: OffsetDateTime::now_utc,
             log_target,
+            target,
         })))
     }
 }
@@ -335,23 +374,48 @@ pin_project! {
         format: Option<Format>,
         size: usize,
         time: OffsetDateTime,
+        first_chunk: Option<OffsetDateTime>,
+        body_end: Option<OffsetDateTime>,
+        clock: fn() -> OffsetDateTime,
         log_target: Cow<'static, str>,
+        target: LogTarget,
     }
 
     impl<B> PinnedDrop for StreamLog<B> {
         fn drop(this: Pin<&mut Self>) {
-            if let Some(ref format) = this.format {
-                let render = |fmt: &mut fmt::Formatter<'_>| {
-                    for unit in &format.0 {
-                        unit.render(fmt, this.size, this.time)?;
-                    }
-                    Ok(())
-                };
+            this.emit();
+        }
+    }
+}
+
+impl<B> StreamLog<B> {
+    /// Writes the access line, if it has not been written yet.
+    fn emit(self: Pin<&mut Self>) {
+        let this = self.project();
+
+        if let Some(mut format) = this.format.take() {
+            let now = (this.clock)();
+            let first_chunk = this.first_chunk.unwrap_or(now);
+            let body_end = this.body_end.unwrap_or(now);
+
+            for unit in &mut format.0 {
+                unit.render_timing(*this.time, first_chunk, body_end);
+            }
+
+            let render = |fmt: &mut fmt::Formatter<'_>| {
+                for unit in &format.0 {
+                    unit.render(fmt, *this.size, *this.time)?;
+                }
+                Ok(())
+            };
 
+            if this.target.is_log() {
                 log::info!(
                     target: this.log_target.as_ref(),
                     "{}", FormatDisplay(&render)
                 );
+            } else {
+                this.target.write_line(FormatDisplay(&render).to_string());
             }
         }
     }
@@ -366,18 +430,28 @@ impl<B: MessageBody> MessageBody for StreamLog<B> {
     }
 
     fn poll_next(
-        self: Pin<&mut Self>,
+        mut self: Pin<&mut Self>,
         cx: &mut Context<'_>,
     ) -> Poll<Option<Result<Bytes, Self::Error>>> {
-        let this = self.project();
+        let this = self.as_mut().project();
 
         match ready!(this.body.poll_next(cx)) {
             Some(Ok(chunk)) => {
+                let now = (this.clock)();
+                this.first_chunk.get_or_insert(now);
+
                 *this.size += chunk.len();
                 Poll::Ready(Some(Ok(chunk)))
             }
             Some(Err(err)) => Poll::Ready(Some(Err(err))),
-            None => Poll::Ready(None),
+            None => {
+                let now = (this.clock)();
+                this.first_chunk.get_or_insert(now);
+                *this.body_end = Some(now);
+
+                self.emit();
+                Poll::Ready(None)
+            }
         }
     }
 }
@@ -399,7 +473,8 @@ impl Format {
     /// Returns `None` if the format string syntax is incorrect.
     pub fn new(s: &str) -> Format {
         log::trace!("Access log format: {}", s);
-        let fmt = Regex::new(r"%(\{([A-Za-z0-9\-_]+)\}([aioe]|xi)|[%atPrUsbTD]?)").unwrap();
+        let fmt =
+            Regex::new(r"%(\{([A-Za-z0-9\-_]+)\}([aioe]|xi)|T[ft]|[%atPrUsbTDV]?)").unwrap();
 
         let mut idx = 0;
         let mut results = Vec::new();
@@ -440,8 +515,11 @@ impl Format {
                     "s" => FormatText::ResponseStatus,
                     "b" => FormatText::ResponseSize,
                     "U" => FormatText::UrlPath,
+                    "V" => FormatText::ApiVersion,
                     "T" => FormatText::Time,
                     "D" => FormatText::TimeMillis,
+                    "Tf" => FormatText::TimeToFirstChunk,
+                    "Tt" => FormatText::TimeToBodyEnd,
                     _ => FormatText::Str(m.as_str().to_owned()),
                 });
             }
@@ -468,9 +546,12 @@ enum FormatText {
     ResponseSize,
     Time,
     TimeMillis,
+    TimeToFirstChunk,
+    TimeToBodyEnd,
     RemoteAddr,
     RealIpRemoteAddr,
     UrlPath,
+    ApiVersion,
     RequestHeader(HeaderName),
     ResponseHeader(HeaderName),
     EnvironHeader(String),
@@ -547,6 +628,32 @@ impl FormatText {
         }
     }
 
+    /// Renders values that depend on app data, which is only complete once the request is routed.
+    fn render_routed(&mut self, req: &HttpRequest) {
+        if let FormatText::ApiVersion = self {
+            *self = match ApiVersion::resolve(req.app_data(), req.head()) {
+                Ok(version) => FormatText::Str(version.to_string()),
+                Err(_) => FormatText::Str("-".to_owned()),
+            };
+        }
+    }
+
+    fn render_timing(
+        &mut self,
+        entry_time: OffsetDateTime,
+        first_chunk: OffsetDateTime,
+        body_end: OffsetDateTime,
+    ) {
+        let time = match self {
+            FormatText::TimeToFirstChunk => first_chunk,
+            FormatText::TimeToBodyEnd => body_end,
+            _ => return,
+        };
+
+        let rt = (time - entry_time).as_seconds_f64();
+        *self = FormatText::Str(format!("{:.6}", rt));
+    }
+
     fn render_request(&mut self, now: OffsetDateTime, req: &ServiceRequest) {
         match self {
             FormatText::RequestLine => {
@@ -652,6 +759,38 @@ mod tests {
         let _res = srv.call(req).await;
     }
 
+    #[actix_rt::test]
+    async fn writer_target() {
+        #[derive(Clone, Default)]
+        struct Shared(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Shared {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let srv = |req: ServiceRequest| ok(req.into_response(HttpResponse::Ok().finish()));
+
+        let buf = Shared::default();
+        let target = LogTarget::writer(buf.clone());
+        let handle = target.handle().unwrap();
+        let logger = Logger::new("%s %U").target(target);
+
+        let srv = logger.new_transform(srv.into_service()).await.unwrap();
+
+        let req = TestRequest::with_uri("/a").to_srv_request();
+        let res = srv.call(req).await.unwrap();
+        let _ = test::read_body(res).await;
+
+        handle.flush();
+        assert_eq!(&*buf.0.lock().unwrap(), b"200 /a\n");
+    }
+
     #[actix_rt::test]
     async fn test_logger_exclude_regex() {
         let srv = |req: ServiceRequest| {
@@ -871,4 +1010,151 @@ mod tests {
         let req = TestRequest::default().to_srv_request();
         srv.call(req).await.unwrap();
     }
+
+    thread_local! {
+        static MANUAL_CLOCK: std::cell::Cell<OffsetDateTime> =
+            std::cell::Cell::new(OffsetDateTime::UNIX_EPOCH);
+    }
+
+    fn manual_now() -> OffsetDateTime {
+        MANUAL_CLOCK.with(|clock| clock.get())
+    }
+
+    fn advance_clock(secs: i64) {
+        MANUAL_CLOCK.with(|clock| clock.set(clock.get() + time::Duration::seconds(secs)));
+    }
+
+    fn manual_stream_log<B>(body: B) -> StreamLog<B> {
+        StreamLog {
+            body,
+            format: None,
+            size: 0,
+            time: manual_now(),
+            first_chunk: None,
+            body_end: None,
+            clock: manual_now,
+            log_target: Cow::Borrowed(""),
+            target: LogTarget::default(),
+        }
+    }
+
+    fn render_chunk_timing<B>(log: &StreamLog<B>) -> String {
+        let mut format = Format::new("%Tf %Tt");
+
+        for unit in &mut format.0 {
+            unit.render_timing(log.time, log.first_chunk.unwrap(), log.body_end.unwrap());
+        }
+
+        let render = |fmt: &mut fmt::Formatter<'_>| {
+            for unit in &format.0 {
+                unit.render(fmt, log.size, log.time)?;
+            }
+            Ok(())
+        };
+        FormatDisplay(&render).to_string()
+    }
+
+    #[actix_rt::test]
+    async fn api_version_token() {
+        let render = |req: HttpRequest| {
+            let mut format = Format::new("%V");
+            for unit in &mut format.0 {
+                unit.render_routed(&req);
+            }
+
+            let entry_time = OffsetDateTime::now_utc();
+            let render = |fmt: &mut fmt::Formatter<'_>| {
+                for unit in &format.0 {
+                    unit.render(fmt, 0, entry_time)?;
+                }
+                Ok(())
+            };
+            format!("{}", FormatDisplay(&render))
+        };
+
+        let cfg = crate::web::ApiVersionConfig::new().path_prefix().max(3);
+
+        let req = TestRequest::with_uri("/v2/users")
+            .app_data(cfg.clone())
+            .to_http_request();
+        assert_eq!(render(req), "2");
+
+        let req = TestRequest::with_uri("/v4/users")
+            .app_data(cfg)
+            .to_http_request();
+        assert_eq!(render(req), "-");
+    }
+
+    #[test]
+    fn parse_chunk_timing_tokens() {
+        let format = Format::new("%Tf %Tt %T %Ta");
+
+        assert!(matches!(format.0[0], FormatText::TimeToFirstChunk));
+        assert!(matches!(format.0[2], FormatText::TimeToBodyEnd));
+        assert!(matches!(format.0[4], FormatText::Time));
+        assert!(matches!(format.0[6], FormatText::Time));
+        assert!(matches!(&format.0[7], FormatText::Str(s) if s == "a"));
+    }
+
+    #[actix_rt::test]
+    async fn streaming_chunk_timing() {
+        use futures_util::{future::poll_fn, stream, StreamExt as _};
+
+        use crate::body::BodyStream;
+
+        // each chunk is produced after an artificial delay of the given seconds
+        let body = BodyStream::new(stream::iter([1, 3]).map(|delay| {
+            advance_clock(delay);
+            Ok::<_, Error>(Bytes::from_static(b"chunk"))
+        }));
+
+        let mut log = manual_stream_log(body);
+
+        while let Some(chunk) = poll_fn(|cx| Pin::new(&mut log).poll_next(cx)).await {
+            chunk.unwrap();
+        }
+
+        assert_eq!(log.size, 10);
+        assert_eq!(render_chunk_timing(&log), "1.000000 4.000000");
+    }
+
+    #[actix_rt::test]
+    async fn single_chunk_timing() {
+        use futures_util::future::poll_fn;
+
+        let mut log = manual_stream_log(Bytes::from_static(b"hello"));
+
+        advance_clock(2);
+        let chunk = poll_fn(|cx| Pin::new(&mut log).poll_next(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), "hello");
+
+        advance_clock(5);
+        assert!(poll_fn(|cx| Pin::new(&mut log).poll_next(cx))
+            .await
+            .is_none());
+
+        assert_eq!(render_chunk_timing(&log), "2.000000 7.000000");
+    }
+
+    #[actix_rt::test]
+    async fn delayed_final_chunk_timing() {
+        use futures_util::{future::poll_fn, stream};
+
+        use crate::body::BodyStream;
+
+        // two chunks after 1 and 3 seconds, then the stream ends 6 seconds after the last chunk
+        let body = BodyStream::new(Box::pin(stream::unfold(0, |n| async move {
+            advance_clock([1, 3, 6][n]);
+            (n < 2).then(|| (Ok::<_, Error>(Bytes::from_static(b"chunk")), n + 1))
+        })));
+
+        let mut log = manual_stream_log(body);
+
+        while let Some(chunk) = poll_fn(|cx| Pin::new(&mut log).poll_next(cx)).await {
+            chunk.unwrap();
+        }
+
+        assert_eq!(log.size, 10);
+        assert_eq!(render_chunk_timing(&log), "1.000000 10.000000");
+    }
 }
@@ -1,22 +1,50 @@
 //! A collection of common middleware.
 
+mod body_limit;
+mod buffer_and_size;
+mod cache;
 mod compat;
 mod condition;
+mod content_length_cap;
+mod cors;
 mod default_headers;
 mod err_handlers;
+mod log_target;
 mod logger;
 #[cfg(test)]
 mod noop;
 mod normalize;
+mod range_support;
 
+pub use self::body_limit::BodyLimit;
+pub use self::buffer_and_size::BufferAndSize;
+pub use self::cache::{Cache, CacheKey, CacheStore, CachedResponse, MemoryStore};
 pub use self::compat::Compat;
 pub use self::condition::Condition;
+pub use self::content_length_cap::ContentLengthCap;
+pub use self::cors::Cors;
 pub use self::default_headers::DefaultHeaders;
 pub use self::err_handlers::{ErrorHandlerResponse, ErrorHandlers};
+pub use self::log_target::{LogTarget, LogTargetHandle};
 pub use self::logger::Logger;
 #[cfg(test)]
 pub(crate) use self::noop::Noop;
 pub use self::normalize::{NormalizePath, TrailingSlash};
+pub use self::range_support::RangeSupport;
+
+#[cfg(feature = "digest")]
+mod verify_digest;
+
+#[cfg(feature = "digest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "digest")))]
+pub use self::verify_digest::VerifyDigest;
+
+#[cfg(feature = "tracing")]
+mod tracing_logger;
+
+#[cfg(feature = "tracing")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tracing")))]
+pub use self::tracing_logger::TracingLogger;
 
 #[cfg(feature = "__compress")]
 mod compress;
@@ -35,14 +63,20 @@ mod tests {
         // ensure there's no reason that the built-in middleware cannot compose
 
         let _ = App::new()
+            .wrap(BodyLimit::new(1024))
+            .wrap(BufferAndSize::new(1024))
+            .wrap(Cache::new())
             .wrap(Compat::new(Logger::default()))
             .wrap(Condition::new(true, DefaultHeaders::new()))
+            .wrap(ContentLengthCap::log(1024))
+            .wrap(Cors::permissive())
             .wrap(DefaultHeaders::new().add(("X-Test2", "X-Value2")))
             .wrap(ErrorHandlers::new().handler(StatusCode::FORBIDDEN, |res| {
                 Ok(ErrorHandlerResponse::Response(res.map_into_left_body()))
             }))
             .wrap(Logger::default())
-            .wrap(NormalizePath::new(TrailingSlash::Trim));
+            .wrap(NormalizePath::new(TrailingSlash::Trim))
+            .wrap(RangeSupport::none());
 
         let _ = App::new()
             .wrap(NormalizePath::new(TrailingSlash::Trim))
@@ -61,5 +95,11 @@ mod tests {
             let _ = App::new().wrap(Compat::new(Compress::default()));
             let _ = App::new().wrap(Condition::new(true, Compat::new(Compress::default())));
         }
+
+        #[cfg(feature = "tracing")]
+        {
+            let _ = App::new().wrap(TracingLogger).wrap(Logger::default());
+            let _ = App::new().wrap(Logger::default()).wrap(TracingLogger);
+        }
     }
 }
@@ -186,6 +186,8 @@ where
             // so the change can not be deduced from the length comparison
             if path != original_path {
                 let mut parts = head.uri.clone().into_parts();
+
+                // the query is reused as received, without re-encoding
                 let query = parts.path_and_query.as_ref().and_then(|pq| pq.query());
 
                 let path = match query {
@@ -195,8 +197,7 @@ where
                 parts.path_and_query = Some(PathAndQuery::from_maybe_shared(path).unwrap());
 
                 let uri = Uri::from_parts(parts).unwrap();
-                req.match_info_mut().get_mut().update(&uri);
-                req.head_mut().uri = uri;
+                req.rewrite_uri(uri);
             }
         }
         self.service.call(req)
@@ -213,7 +214,7 @@ mod tests {
         dev::ServiceRequest,
         guard::fn_guard,
         test::{call_service, init_service, TestRequest},
-        web, App, HttpResponse,
+        web, App, HttpRequest, HttpResponse,
     };
 
     #[actix_rt::test]
@@ -309,6 +310,42 @@ mod tests {
         }
     }
 
+    #[actix_rt::test]
+    async fn query_preserved_byte_for_byte() {
+        const QUERY: &str = "exp=1700000000&path=a%2Fb~c&k=1&k=2&sig=ab+cd%2B%7E";
+
+        let app = init_service(
+            App::new()
+                .wrap(NormalizePath(TrailingSlash::Trim))
+                .service(web::resource("/v1/signed").to(|req: HttpRequest| {
+                    assert_eq!(req.path(), "/v1/signed");
+                    assert_eq!(req.query_string(), QUERY);
+
+                    assert_eq!(req.original_uri().path(), "//v1//signed/");
+                    assert_eq!(req.original_uri().query(), Some(QUERY));
+
+                    HttpResponse::Ok()
+                }))
+                .service(web::resource("/v1/plain").to(|req: HttpRequest| {
+                    assert_eq!(req.original_uri(), req.uri());
+                    assert_eq!(req.query_string(), "a=%7E");
+
+                    HttpResponse::Ok()
+                })),
+        )
+        .await;
+
+        let uri = format!("//v1//signed/?{}", QUERY);
+        let req = TestRequest::with_uri(&uri).to_request();
+        let res = call_service(&app, req).await;
+        assert!(res.status().is_success());
+
+        // pooled requests do not keep the original URI of a previous request
+        let req = TestRequest::with_uri("/v1/plain?a=%7E").to_request();
+        let res = call_service(&app, req).await;
+        assert!(res.status().is_success());
+    }
+
     #[actix_rt::test]
     async fn ensure_trailing_slash() {
         let app = init_service(
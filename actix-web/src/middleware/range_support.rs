@@ -0,0 +1,163 @@
+//! For middleware documentation, see [`RangeSupport`].
+
+use actix_service::{Service, Transform};
+use actix_utils::future::{ok, Ready};
+use futures_core::future::LocalBoxFuture;
+
+use crate::{
+    http::header::{AcceptRanges, Header as _, TryIntoHeaderValue as _},
+    service::{ServiceRequest, ServiceResponse},
+    Error,
+};
+
+/// Middleware for advertising whether the wrapped services support range requests.
+///
+/// Successful responses get an [`AcceptRanges`] header, unless the handler already set one. Wrap
+/// resources whose handlers cannot serve partial content with [`RangeSupport::none`], so clients
+/// do not attempt range requests, and those that can with [`RangeSupport::bytes`].
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::RangeSupport, web, App};
+///
+/// let app = App::new()
+///     .service(
+///         web::resource("/report")
+///             .wrap(RangeSupport::none())
+///             .to(|| async { "generated on demand" }),
+///     )
+///     .service(
+///         web::resource("/archive")
+///             .wrap(RangeSupport::bytes())
+///             .to(|| async { "served from disk" }),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct RangeSupport {
+    accept_ranges: AcceptRanges,
+}
+
+impl RangeSupport {
+    /// Constructs new middleware that sets `Accept-Ranges: none`.
+    pub fn none() -> Self {
+        Self {
+            accept_ranges: AcceptRanges::None,
+        }
+    }
+
+    /// Constructs new middleware that sets `Accept-Ranges: bytes`.
+    pub fn bytes() -> Self {
+        Self {
+            accept_ranges: AcceptRanges::Bytes,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RangeSupport
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RangeSupportMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RangeSupportMiddleware {
+            service,
+            accept_ranges: self.accept_ranges.clone(),
+        })
+    }
+}
+
+pub struct RangeSupportMiddleware<S> {
+    service: S,
+    accept_ranges: AcceptRanges,
+}
+
+impl<S, B> Service<ServiceRequest> for RangeSupportMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let fut = self.service.call(req);
+        let accept_ranges = self.accept_ranges.clone();
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+
+            if res.status().is_success() && !res.headers().contains_key(AcceptRanges::name()) {
+                if let Ok(value) = accept_ranges.try_into_value() {
+                    res.headers_mut().insert(AcceptRanges::name(), value);
+                }
+            }
+
+            Ok(res)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        http::{header::ACCEPT_RANGES, StatusCode},
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    #[actix_rt::test]
+    async fn emits_accept_ranges() {
+        let srv = test::init_service(
+            App::new()
+                .service(
+                    web::resource("/none")
+                        .wrap(RangeSupport::none())
+                        .to(HttpResponse::Ok),
+                )
+                .service(
+                    web::resource("/bytes")
+                        .wrap(RangeSupport::bytes())
+                        .to(HttpResponse::Ok),
+                )
+                .service(web::resource("/custom").wrap(RangeSupport::none()).to(|| {
+                    HttpResponse::Ok()
+                        .insert_header(AcceptRanges::Bytes)
+                        .finish()
+                }))
+                .service(
+                    web::resource("/error")
+                        .wrap(RangeSupport::bytes())
+                        .to(HttpResponse::InternalServerError),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/none").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.headers().get(ACCEPT_RANGES).unwrap(), "none");
+
+        let req = TestRequest::with_uri("/bytes").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.headers().get(ACCEPT_RANGES).unwrap(), "bytes");
+
+        // handlers can override the advertised support
+        let req = TestRequest::with_uri("/custom").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.headers().get(ACCEPT_RANGES).unwrap(), "bytes");
+
+        let req = TestRequest::with_uri("/error").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(!res.headers().contains_key(ACCEPT_RANGES));
+    }
+}
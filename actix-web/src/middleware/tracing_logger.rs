@@ -0,0 +1,378 @@
+//! For middleware documentation, see [`TracingLogger`].
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use actix_service::{Service, Transform};
+use actix_utils::future::{ok, Ready};
+use bytes::Bytes;
+use futures_core::{future::LocalBoxFuture, ready};
+use pin_project_lite::pin_project;
+use tracing::{field::Empty, Instrument as _, Span};
+
+use crate::{
+    body::{BodySize, MessageBody},
+    http::{header::HeaderName, StatusCode},
+    service::{ServiceRequest, ServiceResponse},
+    Error,
+};
+
+const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Middleware for opening a [`tracing`] span for each request.
+///
+/// The `HTTP request` span is entered while the wrapped service runs and while the response body
+/// is streamed, and is closed once the body is finished or dropped. So, the span's lifetime
+/// covers the full request, including the time taken to stream the response.
+///
+/// The span records these fields:
+/// - `http.method`: the request method;
+/// - `http.route`: the matched resource pattern, like `/users/{id}`, if any;
+/// - `http.status_code`: the response status code, also recorded for error responses;
+/// - `request_id`: the `X-Request-Id` request header, if present;
+/// - `error`: the error message, if the service responded with an error.
+///
+/// Events emitted by handlers and inner middleware are children of this span. Requires the
+/// `tracing` feature.
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::TracingLogger, App};
+///
+/// let app = App::new().wrap(TracingLogger::default());
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TracingLogger;
+
+impl<S, B> Transform<S, ServiceRequest> for TracingLogger
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<TracedBody<B>>;
+    type Error = Error;
+    type Transform = TracingLoggerMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(TracingLoggerMiddleware { service })
+    }
+}
+
+pub struct TracingLoggerMiddleware<S> {
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for TracingLoggerMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<TracedBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let span = tracing::info_span!(
+            "HTTP request",
+            http.method = %req.method(),
+            http.route = Empty,
+            http.status_code = Empty,
+            request_id = Empty,
+            error = Empty,
+        );
+
+        // pattern is only known here when wrapping a resource or scope
+        if let Some(pattern) = req.match_pattern() {
+            span.record("http.route", pattern.as_str());
+        }
+
+        if let Some(id) = req.headers().get(X_REQUEST_ID) {
+            if let Ok(id) = id.to_str() {
+                span.record("request_id", id);
+            }
+        }
+
+        let fut = span.in_scope(|| self.service.call(req));
+
+        Box::pin(async move {
+            match fut.instrument(span.clone()).await {
+                Ok(res) => {
+                    if let Some(pattern) = res.request().match_pattern() {
+                        span.record("http.route", pattern.as_str());
+                    }
+
+                    record_status(&span, res.status());
+
+                    if let Some(err) = res.response().error() {
+                        span.record("error", tracing::field::display(err));
+                    }
+
+                    Ok(res.map_body(move |_, body| TracedBody {
+                        body,
+                        span: Some(span),
+                    }))
+                }
+
+                Err(err) => {
+                    record_status(&span, err.as_response_error().status_code());
+                    span.record("error", tracing::field::display(&err));
+                    Err(err)
+                }
+            }
+        })
+    }
+}
+
+fn record_status(span: &Span, status: StatusCode) {
+    span.record("http.status_code", status.as_u16());
+}
+
+pin_project! {
+    /// Response body that keeps the request span open until it has been fully streamed.
+    pub struct TracedBody<B> {
+        #[pin]
+        body: B,
+        span: Option<Span>,
+    }
+}
+
+impl<B: MessageBody> MessageBody for TracedBody<B> {
+    type Error = B::Error;
+
+    #[inline]
+    fn size(&self) -> BodySize {
+        self.body.size()
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        let entered = this.span.as_ref().map(Span::enter);
+        let chunk = ready!(this.body.poll_next(cx));
+        drop(entered);
+
+        if chunk.is_none() {
+            // body is complete; close the span
+            this.span.take();
+        }
+
+        Poll::Ready(chunk)
+    }
+
+    #[inline]
+    fn try_into_bytes(self) -> Result<Bytes, Self> {
+        let TracedBody { body, span } = self;
+        body.try_into_bytes()
+            .map_err(|body| TracedBody { body, span })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        fmt,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    };
+
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Event, Metadata, Subscriber,
+    };
+
+    use super::*;
+    use crate::{
+        error::ErrorBadRequest,
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    type Fields = HashMap<&'static str, String>;
+
+    /// Subscriber that collects the fields recorded on each span and tracks which are still open.
+    #[derive(Clone, Default)]
+    struct Collector {
+        next_id: Arc<AtomicU64>,
+        spans: Arc<Mutex<HashMap<u64, (&'static str, Fields)>>>,
+        refs: Arc<Mutex<HashMap<u64, usize>>>,
+    }
+
+    impl Collector {
+        fn open_spans(&self) -> usize {
+            self.refs.lock().unwrap().len()
+        }
+
+        fn fields(&self, name: &str) -> Vec<Fields> {
+            let spans = self.spans.lock().unwrap();
+            let mut spans = spans
+                .iter()
+                .filter(|(_, (span, _))| *span == name)
+                .collect::<Vec<_>>();
+            spans.sort_by_key(|(id, _)| **id);
+            spans
+                .into_iter()
+                .map(|(_, (_, fields))| fields.clone())
+                .collect()
+        }
+    }
+
+    struct FieldVisitor<'a>(&'a mut Fields);
+
+    impl Visit for FieldVisitor<'_> {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            self.0.insert(field.name(), format!("{:?}", value));
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name(), value.to_owned());
+        }
+    }
+
+    impl Subscriber for Collector {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+            let mut fields = Fields::new();
+            attrs.record(&mut FieldVisitor(&mut fields));
+            self.spans
+                .lock()
+                .unwrap()
+                .insert(id, (attrs.metadata().name(), fields));
+            self.refs.lock().unwrap().insert(id, 1);
+            Id::from_u64(id)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let (_, fields) = spans.get_mut(&span.into_u64()).unwrap();
+            values.record(&mut FieldVisitor(fields));
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+
+        fn clone_span(&self, span: &Id) -> Id {
+            *self.refs.lock().unwrap().get_mut(&span.into_u64()).unwrap() += 1;
+            span.clone()
+        }
+
+        fn try_close(&self, span: Id) -> bool {
+            let mut refs = self.refs.lock().unwrap();
+            let count = refs.get_mut(&span.into_u64()).unwrap();
+            *count -= 1;
+
+            if *count == 0 {
+                refs.remove(&span.into_u64());
+                true
+            } else {
+                false
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn records_request_fields() {
+        let collector = Collector::default();
+        let _guard = tracing::subscriber::set_default(collector.clone());
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(TracingLogger)
+                .route(
+                    "/users/{id}",
+                    web::get().to(|| HttpResponse::Ok().body("user")),
+                )
+                .route(
+                    "/fail",
+                    web::post()
+                        .to(|| async { Err::<HttpResponse, _>(ErrorBadRequest("bad input")) }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/users/42")
+            .insert_header((X_REQUEST_ID, "abc-123"))
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // span stays open until the body has been streamed
+        assert_eq!(collector.open_spans(), 1);
+        assert_eq!(test::read_body(res).await, "user");
+        assert_eq!(collector.open_spans(), 0);
+
+        let req = TestRequest::post().uri("/fail").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let spans = collector.fields("HTTP request");
+        assert_eq!(spans.len(), 2);
+
+        let ok = &spans[0];
+        assert_eq!(ok["http.method"], "GET");
+        assert_eq!(ok["http.route"], "/users/{id}");
+        assert_eq!(ok["http.status_code"], "200");
+        assert_eq!(ok["request_id"], "abc-123");
+        assert!(!ok.contains_key("error"));
+
+        let err = &spans[1];
+        assert_eq!(err["http.method"], "POST");
+        assert_eq!(err["http.route"], "/fail");
+        assert_eq!(err["http.status_code"], "400");
+        assert_eq!(err["error"], "bad input");
+        assert!(!err.contains_key("request_id"));
+    }
+
+    #[actix_rt::test]
+    async fn records_service_errors() {
+        let collector = Collector::default();
+        let _guard = tracing::subscriber::set_default(collector.clone());
+
+        let mw = TracingLogger
+            .new_transform(test::status_service(StatusCode::OK))
+            .await
+            .unwrap();
+
+        let failing = crate::dev::fn_service(|_: ServiceRequest| async {
+            Err::<ServiceResponse, _>(ErrorBadRequest("rejected"))
+        });
+        let mw_err = TracingLogger.new_transform(failing).await.unwrap();
+
+        let req = TestRequest::default().to_srv_request();
+        let res = mw.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let req = TestRequest::default().to_srv_request();
+        assert!(mw_err.call(req).await.is_err());
+
+        let spans = collector.fields("HTTP request");
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0]["http.status_code"], "200");
+        assert!(!spans[0].contains_key("http.route"));
+        assert_eq!(spans[1]["http.status_code"], "400");
+        assert_eq!(spans[1]["error"], "rejected");
+    }
+}
@@ -0,0 +1,339 @@
+//! For middleware documentation, see [`VerifyDigest`].
+
+use std::rc::Rc;
+
+use actix_http::{error::PayloadError, HttpMessage as _};
+use actix_service::{Service, Transform};
+use actix_utils::future::{ok, Ready};
+use bytes::{Bytes, BytesMut};
+use futures_core::future::LocalBoxFuture;
+use futures_util::StreamExt as _;
+use md5::Md5;
+use sha2::{Digest as _, Sha256, Sha512};
+
+use crate::{
+    body::EitherBody,
+    dev::Payload,
+    error::ContentDigestError,
+    http::header::{ContentDigest, ContentMd5, Header as _, CONTENT_DIGEST, CONTENT_MD5},
+    service::{ServiceRequest, ServiceResponse},
+    web::ByteSize,
+    Error,
+};
+
+/// Default limit on buffered bodies, matching [`PayloadConfig`](crate::web::PayloadConfig).
+const DEFAULT_LIMIT: usize = 262_144;
+
+/// Middleware for verifying request bodies against the digests sent by the client.
+///
+/// When a request carries a [`ContentDigest`] or [`ContentMd5`] header, its body is read into
+/// memory and hashed before the wrapped service is called. If the body does not match every
+/// supported digest, the middleware responds with a [`ContentDigestError`], which renders as
+/// "400 Bad Request", and the handler does not run. Otherwise, the buffered body is passed on
+/// unchanged.
+///
+/// The `sha-256` and `sha-512` algorithms of `Content-Digest` are supported; digests using other
+/// algorithms are ignored, as allowed by [RFC 9530 §2]. Malformed digest headers are rejected.
+/// Requests without a digest header are passed through without buffering.
+///
+/// Bodies larger than the [limit](Self::limit) are rejected with "413 Payload Too Large".
+///
+/// # Examples
+/// ```
+/// use actix_web::{middleware::VerifyDigest, web, App};
+///
+/// let app = App::new().service(
+///     web::scope("/api/upload")
+///         .wrap(VerifyDigest::new().limit(1024 * 1024))
+///         .route("", web::post().to(|body: web::Bytes| async move { body.len().to_string() })),
+/// );
+/// ```
+///
+/// [RFC 9530 §2]: https://datatracker.ietf.org/doc/html/rfc9530#section-2
+#[derive(Debug, Clone, Copy)]
+pub struct VerifyDigest {
+    limit: usize,
+}
+
+impl VerifyDigest {
+    /// Constructs new middleware that verifies bodies of up to 256kB.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum size of bodies that are buffered for verification.
+    pub fn limit(mut self, limit: impl Into<ByteSize>) -> Self {
+        self.limit = limit.into().as_usize();
+        self
+    }
+}
+
+impl Default for VerifyDigest {
+    fn default() -> Self {
+        Self {
+            limit: DEFAULT_LIMIT,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for VerifyDigest
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = VerifyDigestMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(VerifyDigestMiddleware {
+            service: Rc::new(service),
+            limit: self.limit,
+        })
+    }
+}
+
+pub struct VerifyDigestMiddleware<S> {
+    service: Rc<S>,
+    limit: usize,
+}
+
+impl<S, B> Service<ServiceRequest> for VerifyDigestMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let digests = match expected_digests(&req) {
+            Ok(digests) => digests,
+            Err(err) => {
+                let res = req.error_response(err).map_into_right_body();
+                return Box::pin(async move { Ok(res) });
+            }
+        };
+
+        if digests.is_empty() {
+            let fut = self.service.call(req);
+            return Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) });
+        }
+
+        let service = Rc::clone(&self.service);
+        let limit = self.limit;
+
+        Box::pin(async move {
+            let mut payload = req.take_payload();
+            let mut body = BytesMut::new();
+
+            while let Some(chunk) = payload.next().await {
+                let chunk = match chunk {
+                    Ok(chunk) if body.len() + chunk.len() <= limit => chunk,
+                    Ok(_) => {
+                        let err = PayloadError::LimitExceeded { limit };
+                        return Ok(req.error_response(err).map_into_right_body());
+                    }
+                    Err(err) => return Ok(req.error_response(err).map_into_right_body()),
+                };
+
+                body.extend_from_slice(&chunk);
+            }
+
+            let body = body.freeze();
+
+            for (algorithm, digest) in &digests {
+                if algorithm.compute(&body) != *digest {
+                    let err = ContentDigestError::Mismatch {
+                        algorithm: algorithm.name().to_owned(),
+                    };
+                    return Ok(req.error_response(err).map_into_right_body());
+                }
+            }
+
+            req.set_payload(payload_from_bytes(body));
+
+            service
+                .call(req)
+                .await
+                .map(ServiceResponse::map_into_left_body)
+        })
+    }
+}
+
+/// Hashing algorithms that digests can be verified with.
+#[derive(Debug, Clone, Copy)]
+enum Algorithm {
+    Md5,
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sha-256" => Some(Self::Sha256),
+            "sha-512" => Some(Self::Sha512),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Md5 => "md5",
+            Self::Sha256 => "sha-256",
+            Self::Sha512 => "sha-512",
+        }
+    }
+
+    fn compute(self, body: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Md5 => Md5::digest(body).to_vec(),
+            Self::Sha256 => Sha256::digest(body).to_vec(),
+            Self::Sha512 => Sha512::digest(body).to_vec(),
+        }
+    }
+}
+
+/// Returns the supported digests sent with the request.
+fn expected_digests(
+    req: &ServiceRequest,
+) -> Result<Vec<(Algorithm, Vec<u8>)>, ContentDigestError> {
+    let mut digests = Vec::new();
+
+    if req.headers().contains_key(CONTENT_DIGEST) {
+        let header = ContentDigest::parse(req).map_err(|_| ContentDigestError::Malformed {
+            name: CONTENT_DIGEST,
+        })?;
+
+        digests.extend(header.iter().filter_map(|(name, digest)| {
+            Algorithm::from_name(name).map(|algorithm| (algorithm, digest.to_vec()))
+        }));
+    }
+
+    if req.headers().contains_key(CONTENT_MD5) {
+        let ContentMd5(digest) = ContentMd5::parse(req)
+            .map_err(|_| ContentDigestError::Malformed { name: CONTENT_MD5 })?;
+
+        digests.push((Algorithm::Md5, digest.to_vec()));
+    }
+
+    Ok(digests)
+}
+
+fn payload_from_bytes(bytes: Bytes) -> Payload {
+    let (_, mut h1_payload) = actix_http::h1::Payload::create(true);
+    h1_payload.unread_data(bytes);
+    Payload::from(h1_payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::{
+        http::StatusCode,
+        test::{self, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    fn sha256(body: &[u8]) -> ContentDigest {
+        ContentDigest::new().digest("sha-256", Sha256::digest(body).to_vec())
+    }
+
+    #[actix_rt::test]
+    async fn matching_digest_passes() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(VerifyDigest::new())
+                .route("/", web::post().to(|body: web::Bytes| async move { body })),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .insert_header(sha256(b"hello world"))
+            .set_payload("hello world")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "hello world");
+
+        // unsupported algorithms are ignored
+        let req = TestRequest::post()
+            .insert_header(ContentMd5(Md5::digest(b"hello world").into()))
+            .insert_header((CONTENT_DIGEST, "unixsum=:aGk=:"))
+            .set_payload("hello world")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "hello world");
+
+        let req = TestRequest::post().set_payload("no digest").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[actix_rt::test]
+    async fn tampered_body_rejected() {
+        let called = Rc::new(Cell::new(false));
+
+        let srv = test::init_service(
+            App::new()
+                .wrap(VerifyDigest::new().limit(16))
+                .default_service(web::to({
+                    let called = Rc::clone(&called);
+
+                    move || {
+                        called.set(true);
+                        HttpResponse::Ok()
+                    }
+                })),
+        )
+        .await;
+
+        let req = TestRequest::post()
+            .insert_header(sha256(b"hello world"))
+            .set_payload("hello w0rld")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let req = TestRequest::post()
+            .insert_header(ContentMd5(Md5::digest(b"hello world").into()))
+            .set_payload("hello w0rld")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        // every supported digest must match
+        let req = TestRequest::post()
+            .insert_header(ContentMd5(Md5::digest(b"hello world").into()))
+            .insert_header((CONTENT_DIGEST, "sha-256=:aGk=:"))
+            .set_payload("hello world")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let req = TestRequest::post()
+            .insert_header((CONTENT_DIGEST, "sha-256=aGk"))
+            .set_payload("hello world")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let req = TestRequest::post()
+            .insert_header(sha256(b"hello world, but longer"))
+            .set_payload("hello world, but longer")
+            .to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        assert!(!called.get());
+    }
+}
@@ -0,0 +1,361 @@
+//! See [`OAuthRedirect`] for service documentation.
+
+use std::{borrow::Cow, rc::Rc};
+
+use actix_utils::future::ready;
+use rand::{distributions::Alphanumeric, Rng as _};
+
+use crate::{
+    cookie::{time::Duration, Cookie, SameSite},
+    dev::{fn_service, AppService, HttpServiceFactory, ResourceDef, ServiceRequest},
+    http::{header, StatusCode},
+    redirect::{empty_redirect_response, encode_target},
+    HttpRequest, HttpResponse,
+};
+
+/// Length of generated `state` values.
+const STATE_LEN: usize = 32;
+
+/// How long the state cookie is kept while the user signs in with the provider.
+const STATE_MAX_AGE: Duration = Duration::minutes(10);
+
+/// An HTTP service that starts an OAuth 2.0 authorization code flow by redirecting to a provider.
+///
+/// Each request matching `from` is redirected, using "307 Temporary Redirect", to the provider's
+/// authorization endpoint with `response_type=code`, `client_id`, `redirect_uri`, `scope`, and a
+/// freshly generated `state` in the query string. The same `state` is set in a `Secure`,
+/// `HttpOnly`, `SameSite=Lax` cookie that expires after 10 minutes, so that the callback handler
+/// can reject forged responses using [`verify_state`](Self::verify_state).
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App, HttpRequest, HttpResponse};
+/// use serde::Deserialize;
+///
+/// let github = web::OAuthRedirect::new(
+///     "/login",
+///     "https://github.com/login/oauth/authorize",
+///     "my-client-id",
+///     "https://example.com/callback",
+/// )
+/// .scope("read:user")
+/// .scope("user:email");
+///
+/// #[derive(Deserialize)]
+/// struct Callback {
+///     code: String,
+///     state: String,
+/// }
+///
+/// let callback = {
+///     let github = github.clone();
+///
+///     move |req: HttpRequest, query: web::Query<Callback>| {
+///         let valid = github.verify_state(&req, &query.state);
+///
+///         async move {
+///             if !valid {
+///                 return HttpResponse::Forbidden().finish();
+///             }
+///
+///             // exchange `query.code` for an access token
+///             HttpResponse::Ok().finish()
+///         }
+///     }
+/// };
+///
+/// App::new()
+///     .service(github)
+///     .route("/callback", web::get().to(callback));
+/// ```
+#[derive(Debug, Clone)]
+pub struct OAuthRedirect {
+    from: Cow<'static, str>,
+    authorize_url: Cow<'static, str>,
+    params: Vec<(Cow<'static, str>, Cow<'static, str>)>,
+    scopes: Vec<Cow<'static, str>>,
+    state_cookie: Cow<'static, str>,
+}
+
+impl OAuthRedirect {
+    /// Create a new `OAuthRedirect` service, matching requests for the path `from`.
+    ///
+    /// Requests are redirected to `authorize_url`, identifying the app as `client_id` and asking
+    /// the provider to send the user back to `redirect_uri`. Query parameters already present in
+    /// `authorize_url` are kept.
+    pub fn new(
+        from: impl Into<Cow<'static, str>>,
+        authorize_url: impl Into<Cow<'static, str>>,
+        client_id: impl Into<Cow<'static, str>>,
+        redirect_uri: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        Self {
+            from: from.into(),
+            authorize_url: encode_target(authorize_url.into()),
+            params: vec![
+                (Cow::Borrowed("response_type"), Cow::Borrowed("code")),
+                (Cow::Borrowed("client_id"), client_id.into()),
+                (Cow::Borrowed("redirect_uri"), redirect_uri.into()),
+            ],
+            scopes: Vec::new(),
+            state_cookie: Cow::Borrowed("oauth_state"),
+        }
+    }
+
+    /// Request access to `scope`.
+    ///
+    /// Scopes are sent space-separated in a single `scope` query parameter, in the order added.
+    pub fn scope(mut self, scope: impl Into<Cow<'static, str>>) -> Self {
+        self.scopes.push(scope.into());
+        self
+    }
+
+    /// Add a provider-specific query parameter, such as `prompt` or `access_type`.
+    pub fn param(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    /// Store the state in a cookie with the given name instead of `oauth_state`.
+    pub fn state_cookie(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.state_cookie = name.into();
+        self
+    }
+
+    /// Returns true if `state`, as returned by the provider to the callback, matches the state
+    /// cookie sent with `req`.
+    pub fn verify_state(&self, req: &HttpRequest, state: &str) -> bool {
+        match req.cookie(&self.state_cookie) {
+            Some(cookie) => constant_time_eq(cookie.value().as_bytes(), state.as_bytes()),
+            None => false,
+        }
+    }
+
+    /// Returns a cookie that removes the state cookie, to be set by the callback handler once the
+    /// state has been verified.
+    pub fn removal_cookie(&self) -> Cookie<'static> {
+        let mut cookie = self.build_cookie(String::new());
+        cookie.make_removal();
+        cookie
+    }
+
+    /// Resolves the `Location` header value for a request with the given `state`.
+    fn location(&self, state: &str) -> String {
+        let scope = self.scopes.join(" ");
+
+        let mut pairs = self
+            .params
+            .iter()
+            .map(|(name, value)| (name.as_ref(), value.as_ref()))
+            .collect::<Vec<_>>();
+
+        if !scope.is_empty() {
+            pairs.push(("scope", &scope));
+        }
+
+        pairs.push(("state", state));
+
+        // serializing string pairs can not fail
+        let query = serde_urlencoded::to_string(&pairs).unwrap();
+
+        let separator = match self.authorize_url.find('?') {
+            None => "?",
+            Some(idx) if idx + 1 == self.authorize_url.len() => "",
+            Some(_) if self.authorize_url.ends_with('&') => "",
+            Some(_) => "&",
+        };
+
+        format!("{}{}{}", self.authorize_url, separator, query)
+    }
+
+    fn build_cookie(&self, state: String) -> Cookie<'static> {
+        Cookie::build(self.state_cookie.clone().into_owned(), state)
+            .path("/")
+            .secure(true)
+            .http_only(true)
+            // the provider sends the user back through a cross-site, top-level navigation
+            .same_site(SameSite::Lax)
+            .max_age(STATE_MAX_AGE)
+            .finish()
+    }
+
+    /// Builds the redirect response, setting a new state cookie.
+    fn respond(&self) -> HttpResponse {
+        let state = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(STATE_LEN)
+            .map(char::from)
+            .collect::<String>();
+
+        let mut res = empty_redirect_response(
+            StatusCode::TEMPORARY_REDIRECT,
+            &self.location(&state),
+            Some((
+                header::CACHE_CONTROL,
+                header::HeaderValue::from_static("no-store"),
+            )),
+        );
+
+        match res.add_cookie(&self.build_cookie(state)) {
+            Ok(()) => res,
+            Err(err) => HttpResponse::from_error(err),
+        }
+    }
+}
+
+impl HttpServiceFactory for OAuthRedirect {
+    fn register(self, config: &mut AppService) {
+        let rdef = ResourceDef::new(self.from.clone().into_owned());
+        let redirect = Rc::new(self);
+
+        let redirect_factory = fn_service(move |req: ServiceRequest| {
+            let res = redirect.respond();
+            ready(Ok(req.into_response(res)))
+        });
+
+        config.register_service(rdef, None, redirect_factory, None)
+    }
+}
+
+/// Compares two byte strings in time that depends only on their lengths.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cookie::Cookie,
+        dev::Service,
+        test::{self, TestRequest},
+        App,
+    };
+
+    fn github() -> OAuthRedirect {
+        OAuthRedirect::new(
+            "/login",
+            "https://github.com/login/oauth/authorize",
+            "client 1",
+            "https://example.com/callback?next=/home",
+        )
+        .scope("read:user")
+        .scope("user:email")
+    }
+
+    #[actix_rt::test]
+    async fn location_and_state_cookie() {
+        let svc = test::init_service(App::new().service(github())).await;
+
+        let req = TestRequest::default().uri("/login").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(
+            res.headers().get(header::CACHE_CONTROL).unwrap(),
+            "no-store"
+        );
+
+        let location = res
+            .headers()
+            .get(header::LOCATION)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        let (url, query) = location.split_once('?').unwrap();
+        assert_eq!(url, "https://github.com/login/oauth/authorize");
+
+        let params = serde_urlencoded::from_str::<Vec<(String, String)>>(query).unwrap();
+        let params = params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(params.len(), 5);
+        assert_eq!(params[0], ("response_type", "code"));
+        assert_eq!(params[1], ("client_id", "client 1"));
+        assert_eq!(
+            params[2],
+            ("redirect_uri", "https://example.com/callback?next=/home")
+        );
+        assert_eq!(params[3], ("scope", "read:user user:email"));
+        assert_eq!(params[4].0, "state");
+
+        let state = params[4].1;
+        assert_eq!(state.len(), STATE_LEN);
+        assert!(state.bytes().all(|b| b.is_ascii_alphanumeric()));
+
+        let cookie = res.response().cookies().next().unwrap();
+        assert_eq!(cookie.name(), "oauth_state");
+        assert_eq!(cookie.value(), state);
+        assert_eq!(cookie.path(), Some("/"));
+        assert_eq!(cookie.secure(), Some(true));
+        assert_eq!(cookie.http_only(), Some(true));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+        assert_eq!(cookie.max_age(), Some(STATE_MAX_AGE));
+
+        // each request gets a new state
+        let req = TestRequest::default().uri("/login").to_request();
+        let res = svc.call(req).await.unwrap();
+        let cookie = res.response().cookies().next().unwrap();
+        assert_ne!(cookie.value(), state);
+    }
+
+    #[actix_rt::test]
+    async fn existing_query_and_params() {
+        let redirect = OAuthRedirect::new(
+            "/login",
+            "https://accounts.example.com/auth?hl=en",
+            "id",
+            "https://example.com/cb",
+        )
+        .param("prompt", "select account")
+        .state_cookie("__Host-state");
+
+        let svc = test::init_service(App::new().service(redirect)).await;
+
+        let req = TestRequest::default().uri("/login").to_request();
+        let res = svc.call(req).await.unwrap();
+
+        let location = res
+            .headers()
+            .get(header::LOCATION)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(
+            location.starts_with(
+                "https://accounts.example.com/auth?hl=en&response_type=code&client_id=id\
+                 &redirect_uri=https%3A%2F%2Fexample.com%2Fcb&prompt=select+account&state="
+            ),
+            "{}",
+            location
+        );
+
+        let cookie = res.response().cookies().next().unwrap();
+        assert_eq!(cookie.name(), "__Host-state");
+    }
+
+    #[test]
+    fn verify_state() {
+        let redirect = github();
+
+        let req = TestRequest::default()
+            .cookie(Cookie::new("oauth_state", "abc123"))
+            .to_http_request();
+        assert!(redirect.verify_state(&req, "abc123"));
+        assert!(!redirect.verify_state(&req, "abc124"));
+        assert!(!redirect.verify_state(&req, "abc12"));
+        assert!(!redirect.verify_state(&req, ""));
+
+        let req = TestRequest::default().to_http_request();
+        assert!(!redirect.verify_state(&req, ""));
+
+        let removal = redirect.removal_cookie();
+        assert_eq!(removal.name(), "oauth_state");
+        assert_eq!(removal.value(), "");
+        assert_eq!(removal.max_age(), Some(Duration::ZERO));
+    }
+}
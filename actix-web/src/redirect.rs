@@ -0,0 +1,4043 @@
+//! See [`Redirect`] for service documentation.
+
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    convert::TryFrom as _,
+    fmt,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use actix_service::{Service, Transform};
+use actix_utils::future::{ok, ready, Ready};
+use futures_core::future::LocalBoxFuture;
+use serde::Deserialize;
+
+use crate::{
+    body::{BoxBody, EitherBody},
+    config::ServiceConfig,
+    dev::{
+        fn_service, AppService, HttpServiceFactory, ResourceDef, ServiceRequest,
+        ServiceResponse,
+    },
+    error::{
+        InsecureRedirectTarget, RedirectLoopDetected, RedirectRuleError, RedirectTemplateError,
+        UnresolvedRedirectPlaceholder,
+    },
+    guard::{self, Guard},
+    http::{
+        header::{self, TryIntoHeaderValue as _},
+        uri::{EncodeSet, PathEncoder},
+        Method, StatusCode, Uri,
+    },
+    rmap::ResourceMap,
+    Error, HttpRequest, HttpResponse, Responder,
+};
+
+#[cfg(feature = "signed-urls")]
+use crate::web::UrlSigner;
+
+/// An HTTP service for redirecting one path to another path or URL.
+///
+/// By default, the "308 Permanent Redirect" status is used when responding. See [this MDN
+/// article][mdn-redirects] on why 308 is preferred over 301.
+///
+/// Until a target is set using one of the `to_*` methods or [`append`](Self::append), requests are
+/// redirected to `/`.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App};
+///
+/// App::new()
+///     // redirect "/duck" to DuckDuckGo
+///     .service(web::Redirect::from("/duck").to_absolute("https://duckduckgo.com/"))
+///     .service(
+///         // redirect "/api/old" to "/api/new" using `307 Temporary Redirect`
+///         web::scope("/api").service(
+///             web::Redirect::from("/old")
+///                 .to_relative("/new")
+///                 .temporary()
+///         )
+///     );
+/// ```
+///
+/// # Middleware
+/// A `Redirect` can also be used as middleware, in which case requests with paths matching `from`
+/// are redirected before routing and other requests are passed on. For site-wide redirects, such
+/// as during maintenance, [`Redirect::all`] skips path matching entirely.
+///
+/// ```
+/// use actix_web::{web, App};
+///
+/// App::new()
+///     .wrap(web::Redirect::all().to_absolute("https://status.example.com/").temporary())
+///     .service(web::resource("/").to(|| async { "unreachable during maintenance" }));
+/// ```
+///
+/// # Responder
+/// Handlers that decide on a target at request time can return a `Redirect` created using
+/// [`Redirect::to`].
+///
+/// ```
+/// use actix_web::{web, App, HttpRequest, Responder};
+///
+/// async fn login(req: HttpRequest) -> impl Responder {
+///     let next = req.query_string().strip_prefix("next=").unwrap_or("/").to_owned();
+///     web::Redirect::to(next).see_other()
+/// }
+///
+/// App::new().route("/login", web::post().to(login));
+/// ```
+///
+/// [mdn-redirects]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Redirections#permanent_redirections
+#[derive(Debug, Clone)]
+pub struct Redirect {
+    from: Cow<'static, str>,
+    all: bool,
+    to: RedirectType,
+    status_code: StatusCode,
+    methods: Option<Vec<Method>>,
+    method_mismatch_405: bool,
+    query: Vec<(Cow<'static, str>, Cow<'static, str>)>,
+    guards: RedirectGuards,
+    permissions_policy: Option<header::PermissionsPolicy>,
+    sunset: Option<header::Sunset>,
+    echo_body: bool,
+    html_body: bool,
+    fragment: Option<Cow<'static, str>>,
+    fragment_param: Option<Cow<'static, str>>,
+    preserve_query: bool,
+    https_targets: Option<HttpsTargets>,
+    http_hosts: Vec<Cow<'static, str>>,
+    permanent_after: Option<Duration>,
+    started: Option<Instant>,
+    clock: RedirectClock,
+    validate_target: Option<bool>,
+    max_redirects: Option<u32>,
+    #[cfg(feature = "signed-urls")]
+    signed_expiry: Option<(UrlSigner, Duration)>,
+    target_transforms: TargetTransforms,
+    responder_only: bool,
+}
+
+/// Clock used by [`Redirect::permanent_after`]; see [`Redirect::clock`].
+#[derive(Clone)]
+struct RedirectClock(Rc<dyn Fn() -> Instant>);
+
+impl Default for RedirectClock {
+    fn default() -> Self {
+        Self(Rc::new(Instant::now))
+    }
+}
+
+impl fmt::Debug for RedirectClock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RedirectClock")
+    }
+}
+
+/// Guards added to a [`Redirect`] using [`Redirect::guard`].
+#[derive(Clone, Default)]
+struct RedirectGuards(Vec<Rc<dyn Guard>>);
+
+impl fmt::Debug for RedirectGuards {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "RedirectGuards({})", self.0.len())
+    }
+}
+
+/// A transformer added to a [`Redirect`] using [`Redirect::transform_target`].
+type TargetTransform = Rc<dyn Fn(&mut TargetParts)>;
+
+/// Transformers added to a [`Redirect`] using [`Redirect::transform_target`].
+#[derive(Clone, Default)]
+struct TargetTransforms(Vec<TargetTransform>);
+
+impl fmt::Debug for TargetTransforms {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "TargetTransforms({})", self.0.len())
+    }
+}
+
+/// Describes how the `Location` of a [`Redirect`] is resolved.
+#[derive(Debug, Clone)]
+enum RedirectType {
+    /// Target is used as-is.
+    Absolute(Cow<'static, str>),
+
+    /// Target replaces the matched `from` path, keeping any scope prefix.
+    Relative(Cow<'static, str>),
+
+    /// Target is appended to the full request path.
+    Append(Cow<'static, str>),
+
+    /// Target is generated from a named resource using the app's URL generation.
+    Named {
+        name: Cow<'static, str>,
+        params: HashMap<String, String>,
+    },
+}
+
+/// How `http://` redirect targets are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HttpsTargets {
+    /// Replace the scheme with `https`.
+    Upgrade,
+
+    /// Respond with an error instead of redirecting.
+    Reject,
+}
+
+/// The parts of a redirect target that can be changed using [`Redirect::transform_target`].
+///
+/// Parts are percent-encoded, as they appear in the `Location` header, and are written back as
+/// they are left by the transformers. The scheme and host of absolute targets are kept as they are.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetParts {
+    /// Path of the target, such as `/docs/install`.
+    pub path: String,
+
+    /// Query of the target, without the leading `?`.
+    pub query: Option<String>,
+
+    /// Fragment of the target, without the leading `#`.
+    pub fragment: Option<String>,
+}
+
+impl TargetParts {
+    /// Splits `location` into its scheme and host, if any, and its parts.
+    fn parse(location: &str) -> (&str, Self) {
+        let (location, fragment) = match location.split_once('#') {
+            Some((location, fragment)) => (location, Some(fragment.to_owned())),
+            None => (location, None),
+        };
+
+        let (origin, path_and_query) = split_origin(location);
+
+        let (path, query) = match path_and_query.split_once('?') {
+            Some((path, query)) => (path, Some(query.to_owned())),
+            None => (path_and_query, None),
+        };
+
+        let parts = TargetParts {
+            path: path.to_owned(),
+            query,
+            fragment,
+        };
+
+        (origin, parts)
+    }
+
+    /// Writes the parts back after the scheme and host `origin`.
+    fn write(&self, origin: &str) -> String {
+        let mut location = format!("{}{}", origin, self.path);
+
+        if let Some(query) = &self.query {
+            location.push('?');
+            location.push_str(query);
+        }
+
+        if let Some(fragment) = &self.fragment {
+            location.push('#');
+            location.push_str(fragment);
+        }
+
+        location
+    }
+}
+
+impl Redirect {
+    /// Create a new `Redirect` service, matching requests for the path `from`.
+    ///
+    /// Paths are matched in the same way as [`web::resource`](crate::web::resource) paths.
+    pub fn from(from: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            from: from.into(),
+            all: false,
+            to: RedirectType::Absolute(Cow::Borrowed("/")),
+            status_code: StatusCode::PERMANENT_REDIRECT,
+            methods: None,
+            method_mismatch_405: false,
+            query: Vec::new(),
+            guards: RedirectGuards::default(),
+            permissions_policy: None,
+            sunset: None,
+            echo_body: false,
+            html_body: false,
+            fragment: None,
+            fragment_param: None,
+            preserve_query: true,
+            https_targets: None,
+            http_hosts: Vec::new(),
+            permanent_after: None,
+            started: None,
+            clock: RedirectClock::default(),
+            validate_target: None,
+            max_redirects: None,
+            #[cfg(feature = "signed-urls")]
+            signed_expiry: None,
+            target_transforms: TargetTransforms::default(),
+            responder_only: false,
+        }
+    }
+
+    /// Create a `Redirect` to `target` for returning from a handler, without a `from` path.
+    ///
+    /// The target is used literally, so it can be built at request time; characters that are not
+    /// allowed in URLs, including control characters and braces, are percent-encoded. Unlike
+    /// services, the request's query string is not carried over unless
+    /// [`preserve_query`](Self::preserve_query) is used. Other builder methods, such as
+    /// [`temporary`](Self::temporary), [`see_other`](Self::see_other), and
+    /// [`using_status_code`](Self::using_status_code), work as usual.
+    ///
+    /// # Panics
+    /// A `Redirect` created using this method is only a [`Responder`]; registering it as a service
+    /// or using it as middleware panics.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{post, web, HttpResponse, Responder};
+    ///
+    /// #[post("/items")]
+    /// async fn create_item() -> impl Responder {
+    ///     let id = 42;
+    ///
+    ///     web::Redirect::to(format!("/items/{}", id)).see_other()
+    /// }
+    /// ```
+    pub fn to(target: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            preserve_query: false,
+            responder_only: true,
+            ..Self::from("").to_absolute(encode_target(target.into()))
+        }
+    }
+
+    /// Create a new `Redirect` service matching requests for all paths.
+    ///
+    /// When registered as a service, this matches every path in the app or scope. When used as
+    /// middleware, every request is redirected without any path matching, which is the cheapest
+    /// way to redirect a whole site.
+    pub fn all() -> Self {
+        Self {
+            all: true,
+            ..Self::from("")
+        }
+    }
+
+    /// Redirect to an absolute path or URL.
+    ///
+    /// The target is used as the `Location` header value, so it is not affected by the scope this
+    /// service is registered in. Characters that are not allowed in URLs, such as spaces, are
+    /// percent-encoded; existing percent-encoded sequences are left alone. For example,
+    /// `to_absolute("/path with space")` redirects to `/path%20with%20space`. This is the encoding
+    /// of a [`PathEncoder`] for [`EncodeSet::Url`] in raw mode.
+    ///
+    /// `{name}` placeholders are replaced with the dynamic segment of the request path that has the
+    /// same name, percent-encoded as a path. Segments of `from` and of enclosing scopes can be used.
+    /// Registering a service whose target has a placeholder that names neither panics. When used
+    /// as middleware, such placeholders are only found at request time, and an
+    /// [`UnresolvedRedirectPlaceholder`] error ("500 Internal Server Error") is returned instead of
+    /// redirecting. Literal braces can be written as `%7B` and `%7D`.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// // redirects "/users/42" to "https://people.example.com/42"
+    /// App::new().service(
+    ///     web::Redirect::from("/users/{id}").to_absolute("https://people.example.com/{id}"),
+    /// );
+    /// ```
+    pub fn to_absolute(mut self, to: impl Into<Cow<'static, str>>) -> Self {
+        self.to = RedirectType::Absolute(to.into());
+        self
+    }
+
+    /// Redirect to an absolute path or URL with `${NAME}` placeholders substituted from `vars`.
+    ///
+    /// Placeholders are resolved once, when this method is called, so that the same configuration
+    /// can be reused across environments. The resolved target is then handled as in
+    /// [`to_absolute`](Self::to_absolute).
+    ///
+    /// # Errors
+    /// Returns an error if a placeholder is not defined in `vars` or is not closed, or if the
+    /// resolved target is not a valid URI.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::collections::HashMap;
+    /// use actix_web::{web, App};
+    ///
+    /// let vars = HashMap::from([("BASE".to_owned(), "https://staging.example.com".to_owned())]);
+    ///
+    /// let redirect = web::Redirect::from("/docs")
+    ///     .to_absolute_templated("${BASE}/docs/latest", &vars)
+    ///     .unwrap();
+    ///
+    /// App::new().service(redirect);
+    /// ```
+    pub fn to_absolute_templated(
+        self,
+        template: &str,
+        vars: &HashMap<String, String>,
+    ) -> Result<Self, RedirectTemplateError> {
+        let mut target = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find("${") {
+            target.push_str(&rest[..start]);
+
+            let after = &rest[start + 2..];
+            let end = after
+                .find('}')
+                .ok_or(RedirectTemplateError::UnclosedPlaceholder)?;
+            let name = &after[..end];
+
+            let value =
+                vars.get(name)
+                    .ok_or_else(|| RedirectTemplateError::UnknownPlaceholder {
+                        name: name.to_owned(),
+                    })?;
+
+            target.push_str(value);
+            rest = &after[end + 1..];
+        }
+
+        target.push_str(rest);
+
+        let encoded = encode_target(Cow::Owned(target));
+
+        if encoded.is_empty() || encoded.parse::<Uri>().is_err() {
+            return Err(RedirectTemplateError::InvalidTarget {
+                target: encoded.into_owned(),
+            });
+        }
+
+        Ok(self.to_absolute(encoded))
+    }
+
+    /// Redirect to a path relative to the scope this service is registered in.
+    ///
+    /// The part of the request path matched by `from` is replaced with `to`, so registering
+    /// `Redirect::from("/old").to_relative("/new")` inside `web::scope("/api")` redirects
+    /// `/api/old` to `/api/new`. This also applies when `from` is a pattern; e.g.,
+    /// `Redirect::from("/users/{id}").to_relative("/new")` in the same scope redirects
+    /// `/api/users/5` to `/api/new`.
+    ///
+    /// `{name}` placeholders are replaced with dynamic segments of the request path, and the
+    /// resulting location is percent-encoded, as in [`to_absolute`](Self::to_absolute).
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// App::new()
+    ///     // "/users/42/old" => "/users/42/new"
+    ///     .service(web::Redirect::from("/users/{id}/old").to_relative("/users/{id}/new"))
+    ///     // "/v1/a/b" => "/v2/a/b"
+    ///     .service(web::Redirect::from("/v1/{tail:.*}").to_relative("/v2/{tail}"));
+    /// ```
+    pub fn to_relative(mut self, to: impl Into<Cow<'static, str>>) -> Self {
+        self.to = RedirectType::Relative(to.into());
+        self
+    }
+
+    /// Redirect to the request path with `suffix` appended.
+    ///
+    /// Unlike [`to_relative`](Self::to_relative), which replaces the part of the path matched by
+    /// `from`, the full request path is kept, including any scope prefix. For example,
+    /// `Redirect::from("/docs").append("/latest")` redirects `/docs` to `/docs/latest` and, when
+    /// registered inside `web::scope("/api")`, `/api/docs` to `/api/docs/latest`.
+    ///
+    /// The path and suffix are always joined by exactly one `/`: a trailing slash on the request
+    /// path and a leading slash on `suffix` are merged, so `/docs/` also redirects to
+    /// `/docs/latest`. A trailing slash on `suffix` is kept, which makes `append("/")` a way to
+    /// add a trailing slash. `{name}` placeholders in `suffix` are replaced with dynamic segments of
+    /// the request path, and the resulting location is percent-encoded, as in
+    /// [`to_absolute`](Self::to_absolute).
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// App::new()
+    ///     // "/docs" => "/docs/latest"
+    ///     .service(web::Redirect::from("/docs").append("/latest"))
+    ///     // "/blog" => "/blog/"
+    ///     .service(web::Redirect::from("/blog").append("/"));
+    /// ```
+    pub fn append(mut self, suffix: impl Into<Cow<'static, str>>) -> Self {
+        self.to = RedirectType::Append(suffix.into());
+        self
+    }
+
+    /// Redirect to a [named resource](crate::Resource::name).
+    ///
+    /// The target URL is generated at request time using [`HttpRequest::url_for_map`], filling
+    /// in the resource's dynamic segments from `params` by name. If URL generation fails (e.g.,
+    /// the resource does not exist or a parameter is missing), an error response is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App, HttpResponse};
+    ///
+    /// App::new()
+    ///     .service(
+    ///         web::resource("/users/{id}")
+    ///             .name("user_profile")
+    ///             .to(HttpResponse::Ok),
+    ///     )
+    ///     .service(
+    ///         web::Redirect::from("/me").to_named_route("user_profile", &[("id", "42")]),
+    ///     );
+    /// ```
+    pub fn to_named_route(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        params: &[(&str, &str)],
+    ) -> Self {
+        self.to = RedirectType::Named {
+            name: name.into(),
+            params: params
+                .iter()
+                .map(|(key, val)| ((*key).to_owned(), (*val).to_owned()))
+                .collect(),
+        };
+        self
+    }
+
+    /// Use the "307 Temporary Redirect" status when responding.
+    ///
+    /// See [this MDN article][mdn-redirects] on why 307 is preferred over 302.
+    ///
+    /// [mdn-redirects]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Redirections#temporary_redirections
+    pub fn temporary(self) -> Self {
+        self.using_status_code(StatusCode::TEMPORARY_REDIRECT)
+    }
+
+    /// Use the "303 See Other" status when responding.
+    ///
+    /// Clients follow this redirect using a `GET` request, which makes it the usual response to
+    /// a submitted form.
+    pub fn see_other(self) -> Self {
+        self.using_status_code(StatusCode::SEE_OTHER)
+    }
+
+    /// Allows the use of custom status codes for less common redirect types.
+    ///
+    /// In most cases, the default status ("308 Permanent Redirect") or using the `temporary`
+    /// method, which uses the "307 Temporary Redirect" status have more consistent behavior than
+    /// 301 and 302 codes, respectively.
+    ///
+    /// ```
+    /// # use actix_web::{http::StatusCode, web::Redirect};
+    /// // redirects would use "301 Moved Permanently" status code
+    /// Redirect::from("/old")
+    ///     .to_relative("/new")
+    ///     .using_status_code(StatusCode::MOVED_PERMANENTLY);
+    ///
+    /// // redirects would use "302 Found" status code
+    /// Redirect::from("/old")
+    ///     .to_relative("/new")
+    ///     .using_status_code(StatusCode::FOUND);
+    /// ```
+    pub fn using_status_code(mut self, status: StatusCode) -> Self {
+        self.status_code = status;
+        self.permanent_after = None;
+        self
+    }
+
+    /// Use the "302 Found" status for a `grace` period after the service starts, then switch to
+    /// "301 Moved Permanently".
+    ///
+    /// Useful for staged migrations, where clients should not cache a redirect until the new
+    /// target is known to work. The period starts when the service is constructed, which happens
+    /// separately for each worker. Setting a status code afterwards, using
+    /// [`temporary`](Self::temporary) or [`using_status_code`](Self::using_status_code), replaces
+    /// this behavior.
+    ///
+    /// Redirects returned from handlers are created anew for each request, so their period starts
+    /// when the app is started on the worker instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    /// use actix_web::web::Redirect;
+    ///
+    /// // "302 Found" for the first hour, "301 Moved Permanently" afterwards
+    /// Redirect::from("/old")
+    ///     .to_relative("/new")
+    ///     .permanent_after(Duration::from_secs(60 * 60));
+    /// ```
+    pub fn permanent_after(mut self, grace: Duration) -> Self {
+        self.permanent_after = Some(grace);
+        self
+    }
+
+    /// Sets the clock used to time the grace period of [`permanent_after`](Self::permanent_after).
+    /// Defaults to [`Instant::now`].
+    ///
+    /// For redirects returned from handlers, the clock is compared with the app's start time, as
+    /// measured by `Instant::now`.
+    pub fn clock(mut self, clock: impl Fn() -> Instant + 'static) -> Self {
+        self.clock = RedirectClock(Rc::new(clock));
+        self
+    }
+
+    /// Records the service start time, from which the `permanent_after` grace period is timed.
+    fn start(&mut self) {
+        self.start_at((self.clock.0)());
+    }
+
+    /// Records `started` as the start time of the `permanent_after` grace period.
+    fn start_at(&mut self, started: Instant) {
+        if self.permanent_after.is_some() {
+            self.started = Some(started);
+        }
+    }
+
+    /// Returns the status to respond with at the current time.
+    fn current_status_code(&self) -> StatusCode {
+        match (self.permanent_after, self.started) {
+            (Some(grace), Some(started)) => {
+                if (self.clock.0)().saturating_duration_since(started) < grace {
+                    StatusCode::FOUND
+                } else {
+                    StatusCode::MOVED_PERMANENTLY
+                }
+            }
+            _ => self.status_code,
+        }
+    }
+
+    /// Only redirect requests using one of the given `methods`.
+    ///
+    /// By default, requests with any method are redirected. Once methods are set, requests using
+    /// other methods are not matched by this service, so they fall through to any later services
+    /// registered for the same path, or to the default service. Use
+    /// [`method_mismatch_405`](Self::method_mismatch_405) to respond to them directly instead.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{http::Method, web, App, HttpResponse};
+    ///
+    /// App::new()
+    ///     // GET and HEAD requests to "/old" are redirected
+    ///     .service(
+    ///         web::Redirect::from("/old")
+    ///             .to_relative("/new")
+    ///             .methods([Method::GET, Method::HEAD]),
+    ///     )
+    ///     // other requests to "/old" are still handled
+    ///     .route("/old", web::post().to(HttpResponse::Ok));
+    /// ```
+    pub fn methods(mut self, methods: impl IntoIterator<Item = Method>) -> Self {
+        self.methods = Some(methods.into_iter().collect());
+        self
+    }
+
+    /// Respond with "405 Method Not Allowed" to matching paths requested with a method that is not
+    /// redirected, instead of letting another service respond.
+    ///
+    /// The response carries an [`Allow`](header::Allow) header listing the methods set using
+    /// [`methods`](Self::methods). Has no effect when no methods are set.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{http::Method, web, App};
+    ///
+    /// App::new().service(
+    ///     web::Redirect::from("/old")
+    ///         .to_relative("/new")
+    ///         .methods([Method::GET])
+    ///         .method_mismatch_405(),
+    /// );
+    /// ```
+    pub fn method_mismatch_405(mut self) -> Self {
+        self.method_mismatch_405 = true;
+        self
+    }
+
+    /// Only redirect requests whose query string contains the parameter `name` with the given
+    /// `value`.
+    ///
+    /// Calling this multiple times adds further conditions, all of which must match. Requests that
+    /// do not match fall through, as with [`methods`](Self::methods), regardless of
+    /// [`method_mismatch_405`](Self::method_mismatch_405).
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// App::new()
+    ///     // "/x?legacy=1" is redirected
+    ///     .service(web::Redirect::from("/x").when_query("legacy", "1").to_relative("/y"))
+    ///     // other requests to "/x" are still handled
+    ///     .route("/x", web::get().to(|| async { "current" }));
+    /// ```
+    pub fn when_query(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.query.push((name.into(), value.into()));
+        self
+    }
+
+    /// Only redirect requests that satisfy `guard`.
+    ///
+    /// Any [`Guard`] can be used, including combinations built with [`guard::All`],
+    /// [`guard::Any`], and [`guard::Not`]. Calling this multiple times adds further guards, all of
+    /// which must match. Requests that do not match fall through, as with
+    /// [`when_query`](Self::when_query). This applies both when registered as a service and when
+    /// used as middleware.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{guard, web, App};
+    ///
+    /// App::new().service(
+    ///     web::Redirect::from("/docs")
+    ///         .guard(guard::All(guard::Host("old.example.com")).and(guard::Get()))
+    ///         .to_absolute("https://docs.example.com/"),
+    /// );
+    /// ```
+    pub fn guard<G: Guard + 'static>(mut self, guard: G) -> Self {
+        self.guards.0.push(Rc::new(guard));
+        self
+    }
+
+    /// Adds a [`Permissions-Policy`](header::PermissionsPolicy) header to redirect responses.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{http::header::PermissionsPolicy, web, App};
+    ///
+    /// App::new().service(
+    ///     web::Redirect::from("/old")
+    ///         .to_relative("/new")
+    ///         .permissions_policy(PermissionsPolicy::interest_cohort_opt_out()),
+    /// );
+    /// ```
+    pub fn permissions_policy(mut self, policy: header::PermissionsPolicy) -> Self {
+        self.permissions_policy = Some(policy);
+        self
+    }
+
+    /// Marks the redirected path as deprecated, for endpoints that will stop redirecting at the
+    /// `sunset` date.
+    ///
+    /// Redirect responses get a `Deprecation: true` header, a [`Sunset`](header::Sunset) header
+    /// with the given date, and a `Link` header pointing at the redirect target with the
+    /// `successor-version` relation, as described in [RFC 8594].
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::{Duration, SystemTime};
+    /// use actix_web::{web, App};
+    ///
+    /// let sunset = SystemTime::now() + Duration::from_secs(60 * 60 * 24 * 90);
+    ///
+    /// App::new().service(
+    ///     web::Redirect::from("/api/v1/users")
+    ///         .to_absolute("/api/v2/users")
+    ///         .deprecated(sunset),
+    /// );
+    /// ```
+    ///
+    /// [RFC 8594]: https://datatracker.ietf.org/doc/html/rfc8594
+    pub fn deprecated(mut self, sunset: impl Into<header::HttpDate>) -> Self {
+        self.sunset = Some(header::Sunset(sunset.into()));
+        self
+    }
+
+    /// Adds a `text/plain` body, describing the request URI and the redirect target, to redirect
+    /// responses; useful for inspecting redirects during development.
+    ///
+    /// This has no effect in release builds (i.e., without `debug_assertions`).
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// App::new().service(web::Redirect::from("/old").to_relative("/new").echo_body());
+    /// ```
+    pub fn echo_body(mut self) -> Self {
+        self.echo_body = true;
+        self
+    }
+
+    /// Adds a `text/html` body with a link to the redirect target to redirect responses, for
+    /// clients that do not follow redirects automatically.
+    ///
+    /// Takes precedence over [`echo_body`](Self::echo_body).
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// App::new().service(web::Redirect::from("/old").to_relative("/new").html_body());
+    /// ```
+    pub fn html_body(mut self) -> Self {
+        self.html_body = true;
+        self
+    }
+
+    /// Sets the fragment of the redirect target, replacing any fragment it already has.
+    ///
+    /// Browsers do not send fragments to servers, so a fragment of the request can not be carried
+    /// over; use this, or [`fragment_from_query`](Self::fragment_from_query), to point the target
+    /// at a section of the page instead. The fragment is used in both the `Location` header and
+    /// the link of an [HTML body](Self::html_body). Characters that are not allowed in fragments
+    /// are percent-encoded.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// // redirects to "/docs/install#linux"
+    /// App::new().service(
+    ///     web::Redirect::from("/install/linux")
+    ///         .to_relative("/docs/install")
+    ///         .fragment("linux"),
+    /// );
+    /// ```
+    pub fn fragment(mut self, fragment: impl Into<Cow<'static, str>>) -> Self {
+        let fragment = fragment.into();
+        let fragment = match FRAGMENT_ENCODER.raw().encode(&fragment) {
+            Cow::Owned(encoded) => Cow::Owned(encoded),
+            Cow::Borrowed(_) => fragment,
+        };
+
+        self.fragment = Some(fragment);
+        self
+    }
+
+    /// Sets the fragment of the redirect target from the query parameter `param`, when the request
+    /// has a non-empty value for it.
+    ///
+    /// Otherwise, the fragment set using [`fragment`](Self::fragment), if any, is used.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// // redirects "/old?section=faq" to "/new#faq"
+    /// App::new().service(
+    ///     web::Redirect::from("/old")
+    ///         .to_relative("/new")
+    ///         .fragment_from_query("section")
+    ///         .preserve_query(false),
+    /// );
+    /// ```
+    pub fn fragment_from_query(mut self, param: impl Into<Cow<'static, str>>) -> Self {
+        self.fragment_param = Some(param.into());
+        self
+    }
+
+    /// Sets whether the query string of the request is carried over to the redirect target.
+    ///
+    /// Enabled by default. The target is resolved from the request path as usual, then the
+    /// request's query is added to it. If the target already has a query, the request's query is
+    /// appended to it, separated by `&`. Any fragment of the target is kept after the query.
+    /// Requests without a query are redirected to the target unchanged.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// App::new()
+    ///     // "/old?ref=email&page=2" => "/new?ref=email&page=2"
+    ///     .service(web::Redirect::from("/old").to_relative("/new"))
+    ///     // "/legacy?ref=email" => "/new"
+    ///     .service(web::Redirect::from("/legacy").to_relative("/new").preserve_query(false));
+    /// ```
+    pub fn preserve_query(mut self, enabled: bool) -> Self {
+        self.preserve_query = enabled;
+        self
+    }
+
+    /// Upgrades absolute redirect targets using the `http` scheme to `https`, preventing redirects
+    /// that would downgrade users to an insecure connection.
+    ///
+    /// Only the scheme is replaced; the host, any explicit port, and the rest of the target are
+    /// kept. Targets without a scheme, such as paths, are not affected. This applies to every kind
+    /// of target, including those generated for [named routes](Self::to_named_route). Hosts can be
+    /// exempted using [`allow_http_host`](Self::allow_http_host).
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// // redirects to "https://example.com/docs"
+    /// App::new().service(
+    ///     web::Redirect::from("/docs")
+    ///         .to_absolute("http://example.com/docs")
+    ///         .require_https_targets(),
+    /// );
+    /// ```
+    pub fn require_https_targets(mut self) -> Self {
+        self.https_targets = Some(HttpsTargets::Upgrade);
+        self
+    }
+
+    /// Responds with an [`InsecureRedirectTarget`] error ("500 Internal Server Error") instead of
+    /// redirecting to absolute targets using the `http` scheme.
+    ///
+    /// Use this instead of [`require_https_targets`](Self::require_https_targets) where an
+    /// `http://` target indicates a misconfiguration. Hosts can be exempted using
+    /// [`allow_http_host`](Self::allow_http_host).
+    pub fn reject_http_targets(mut self) -> Self {
+        self.https_targets = Some(HttpsTargets::Reject);
+        self
+    }
+
+    /// Allows `http://` targets on `host`, matched case-insensitively and ignoring any port, when
+    /// [`require_https_targets`](Self::require_https_targets) or
+    /// [`reject_http_targets`](Self::reject_http_targets) is used.
+    ///
+    /// Calling this multiple times allows further hosts.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// App::new().service(
+    ///     web::Redirect::from("/dashboard")
+    ///         .to_absolute("http://localhost:3000/")
+    ///         .require_https_targets()
+    ///         .allow_http_host("localhost"),
+    /// );
+    /// ```
+    pub fn allow_http_host(mut self, host: impl Into<Cow<'static, str>>) -> Self {
+        self.http_hosts.push(host.into());
+        self
+    }
+
+    /// Checks at startup that the redirect target is served by a route of the app.
+    ///
+    /// Once all services of the app are registered, the target path is matched against the app's
+    /// routes. Targets that no route matches, and named routes that do not exist, are logged as
+    /// warnings or, if `strict` is true, cause a panic, so that dead redirects are caught before
+    /// they are served. Relative targets, appended suffixes, named routes, and absolute targets
+    /// that are paths are checked; targets on other hosts are not. Dynamic segments of enclosing
+    /// scopes are matched as their pattern text, such as `{id}`.
+    ///
+    /// Has no effect when the redirect is used as middleware.
+    ///
+    /// # Examples
+    /// ```should_panic
+    /// use actix_web::{test, web, App};
+    ///
+    /// # actix_web::rt::System::new().block_on(async {
+    /// let app = App::new()
+    ///     .route("/new", web::get().to(|| async { "new" }))
+    ///     .service(web::Redirect::from("/old").to_relative("/nwe").validate_target(true));
+    ///
+    /// // panics: "redirect from `/old` to `/nwe` does not match any route"
+    /// test::init_service(app).await;
+    /// # });
+    /// ```
+    pub fn validate_target(mut self, strict: bool) -> Self {
+        self.validate_target = Some(strict);
+        self
+    }
+
+    /// Stops redirect loops by counting redirects in an `X-Redirect-Count` header.
+    ///
+    /// The count is read from the request, treating a missing or malformed header as zero, and
+    /// the redirect response carries the incremented count. Clients and proxies that copy the
+    /// header onto the request following the redirect make misconfigured redirect chains end
+    /// after `max` redirects: once a request arrives with a count of `max` or more, a
+    /// [`RedirectLoopDetected`] error ("508 Loop Detected") is returned instead of redirecting.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// App::new().service(web::Redirect::from("/old").to_relative("/new").max_redirects(5));
+    /// ```
+    pub fn max_redirects(mut self, max: u32) -> Self {
+        self.max_redirects = Some(max);
+        self
+    }
+
+    /// Appends an expiry and a signature to the target's query, making it valid for `ttl`.
+    ///
+    /// The target is signed using [`UrlSigner::sign`] when each redirect is sent, so the expiry is
+    /// `ttl` after the redirect. The destination checks the link using [`UrlSigner::verify`] with
+    /// a signer using the same key.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use actix_web::{web, App};
+    ///
+    /// let signer = web::UrlSigner::new("secret key");
+    ///
+    /// // redirects to "https://files.example.com/report.pdf?expires=...&signature=..."
+    /// App::new().service(
+    ///     web::Redirect::from("/report")
+    ///         .to_absolute("https://files.example.com/report.pdf")
+    ///         .temporary()
+    ///         .signed_expiry(signer, Duration::from_secs(300)),
+    /// );
+    /// ```
+    #[cfg(feature = "signed-urls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "signed-urls")))]
+    pub fn signed_expiry(mut self, signer: UrlSigner, ttl: Duration) -> Self {
+        self.signed_expiry = Some((signer, ttl));
+        self
+    }
+
+    /// Adds a transformer that changes the path, query, or fragment of the redirect target.
+    ///
+    /// Transformers run in the order they are added, once the target is resolved and its
+    /// [fragment](Self::fragment) is set, and before the target is
+    /// [signed](Self::signed_expiry). Each sees the [`TargetParts`] left by the previous one.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// // redirects "/Promo" to "/promo?utm_source=redirect"
+    /// App::new().service(
+    ///     web::Redirect::from("/Promo")
+    ///         .to_relative("/Promo")
+    ///         .transform_target(|target| target.path.make_ascii_lowercase())
+    ///         .transform_target(|target| {
+    ///             let query = target.query.get_or_insert_with(String::new);
+    ///
+    ///             if !query.is_empty() {
+    ///                 query.push('&');
+    ///             }
+    ///
+    ///             query.push_str("utm_source=redirect");
+    ///         }),
+    /// );
+    /// ```
+    pub fn transform_target(mut self, transform: impl Fn(&mut TargetParts) + 'static) -> Self {
+        self.target_transforms.0.push(Rc::new(transform));
+        self
+    }
+
+    /// Returns the redirect count to send with a redirect for `req`, if loop protection is
+    /// enabled using [`max_redirects`](Self::max_redirects).
+    fn next_redirect_count(
+        &self,
+        req: &HttpRequest,
+    ) -> Result<Option<u32>, RedirectLoopDetected> {
+        let max = match self.max_redirects {
+            Some(max) => max,
+            None => return Ok(None),
+        };
+
+        let count = req
+            .headers()
+            .get(&REDIRECT_COUNT)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u32>().ok())
+            .unwrap_or(0);
+
+        if count >= max {
+            return Err(RedirectLoopDetected { max });
+        }
+
+        Ok(Some(count + 1))
+    }
+
+    /// Returns the target to validate for a redirect registered under the scope path `prefix`,
+    /// or `None` if it is not served by the app.
+    fn target_check(&self, prefix: &str, strict: bool) -> Option<RedirectTargetCheck> {
+        let from = match self.all {
+            true => format!("{}/*", prefix),
+            false => format!("{}{}", prefix, self.from),
+        };
+
+        let target = match &self.to {
+            RedirectType::Absolute(to) if to.starts_with('/') && !to.starts_with("//") => {
+                CheckedTarget::Path(to.to_string())
+            }
+            RedirectType::Absolute(_) => return None,
+            RedirectType::Relative(to) => CheckedTarget::Path(format!("{}{}", prefix, to)),
+            RedirectType::Append(_) if self.all => return None,
+            RedirectType::Append(suffix) => CheckedTarget::Path(format!(
+                "{}{}/{}",
+                prefix,
+                self.from.trim_end_matches('/'),
+                suffix.trim_start_matches('/')
+            )),
+            RedirectType::Named { name, .. } => CheckedTarget::Name(name.to_string()),
+        };
+
+        Some(RedirectTargetCheck {
+            from,
+            target,
+            strict,
+        })
+    }
+
+    /// Applies the rules set using [`require_https_targets`](Self::require_https_targets) or
+    /// [`reject_http_targets`](Self::reject_http_targets) to `location`.
+    fn secure_location(&self, location: String) -> Result<String, InsecureRedirectTarget> {
+        let mode = match self.https_targets {
+            Some(mode) => mode,
+            None => return Ok(location),
+        };
+
+        let rest = match location.get(..7) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("http://") => &location[7..],
+            _ => return Ok(location),
+        };
+
+        let authority = rest.split(|c| matches!(c, '/' | '?' | '#')).next().unwrap();
+        let host_port = authority.rsplit('@').next().unwrap();
+        let host = match host_port.rfind(':') {
+            // keep IPv6 literals, such as "[::1]", intact
+            Some(idx) if !host_port[idx..].contains(']') => &host_port[..idx],
+            _ => host_port,
+        };
+
+        if self
+            .http_hosts
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(host))
+        {
+            return Ok(location);
+        }
+
+        match mode {
+            HttpsTargets::Upgrade => Ok(format!("https://{}", rest)),
+            HttpsTargets::Reject => Err(InsecureRedirectTarget { target: location }),
+        }
+    }
+
+    /// Panics if this redirect was created using [`to`](Self::to), which has no paths to match.
+    fn assert_routable(&self) {
+        assert!(
+            !self.responder_only,
+            "`Redirect::to` can only be returned from handlers; use `Redirect::from` to register \
+            a redirect service or middleware"
+        );
+    }
+
+    /// Panics if the target has a placeholder that is not a dynamic segment of `from` or of the
+    /// enclosing scopes, whose pattern is `prefix`.
+    fn assert_placeholders_resolve(&self, prefix: &str) {
+        if let Some(name) = self.unresolved_placeholder(prefix) {
+            panic!(
+                "redirect from `{}{}` to `{}` uses placeholder `{{{}}}`, which is not a dynamic \
+                segment of the path",
+                prefix,
+                self.from,
+                self.target().unwrap_or_default(),
+                name
+            );
+        }
+    }
+
+    /// Returns the first placeholder in the target that is not a dynamic segment of `from` or of
+    /// the enclosing scopes, whose pattern is `prefix`.
+    fn unresolved_placeholder(&self, prefix: &str) -> Option<&str> {
+        let target = self.target()?;
+
+        let mut segments = segment_names(prefix);
+        segments.extend(segment_names(&self.from));
+
+        placeholder_names(target).find(|name| !segments.contains(name))
+    }
+
+    /// Returns the target path or URL, unless the target is a named resource.
+    fn target(&self) -> Option<&str> {
+        match &self.to {
+            RedirectType::Absolute(target)
+            | RedirectType::Relative(target)
+            | RedirectType::Append(target) => Some(target),
+            RedirectType::Named { .. } => None,
+        }
+    }
+
+    /// Returns the resource definition matching the paths redirected by this service.
+    fn resource_def(&self) -> ResourceDef {
+        if self.all {
+            ResourceDef::root_prefix("")
+        } else {
+            ResourceDef::new(self.from.clone().into_owned())
+        }
+    }
+
+    /// Returns true if requests using `method` are redirected.
+    fn allows_method(&self, method: &Method) -> bool {
+        self.methods
+            .as_ref()
+            .map_or(true, |methods| methods.contains(method))
+    }
+
+    /// Returns true if `query` satisfies all conditions set using [`when_query`](Self::when_query).
+    fn matches_query(&self, query: &str) -> bool {
+        if self.query.is_empty() {
+            return true;
+        }
+
+        let params = match serde_urlencoded::from_str::<Vec<(String, String)>>(query) {
+            Ok(params) => params,
+            Err(_) => return false,
+        };
+
+        self.query.iter().all(|(name, value)| {
+            params
+                .iter()
+                .any(|(param, val)| param == name.as_ref() && val == value.as_ref())
+        })
+    }
+
+    /// Returns true if all guards added using [`guard`](Self::guard) match `req`.
+    fn matches_guards(&self, req: &ServiceRequest) -> bool {
+        if self.guards.0.is_empty() {
+            return true;
+        }
+
+        let ctx = req.guard_ctx();
+        self.guards.0.iter().all(|guard| guard.check(&ctx))
+    }
+
+    /// Resolves the `Location` header value for a request matched by this service.
+    ///
+    /// `rdef` is the resource definition built from `from` that matched the request.
+    fn location(&self, req: &HttpRequest, rdef: &ResourceDef) -> Result<String, Error> {
+        match &self.to {
+            RedirectType::Absolute(to) => {
+                let location = fill_placeholders(to, req)?;
+                Ok(TARGET_ENCODER.encode(&location).into_owned())
+            }
+
+            RedirectType::Relative(to) => {
+                let prefix = scope_prefix(req.path(), rdef);
+                let location = format!(
+                    "{}{}",
+                    prefix.trim_end_matches('/'),
+                    fill_placeholders(to, req)?
+                );
+                Ok(TARGET_ENCODER.encode(&location).into_owned())
+            }
+
+            RedirectType::Append(suffix) => {
+                let location = format!(
+                    "{}/{}",
+                    req.path().trim_end_matches('/'),
+                    fill_placeholders(suffix, req)?.trim_start_matches('/')
+                );
+                Ok(TARGET_ENCODER.encode(&location).into_owned())
+            }
+
+            RedirectType::Named { name, params } => req
+                .url_for_map(name, params)
+                .map(|url| url.to_string())
+                .map_err(Into::into),
+        }
+    }
+
+    /// Returns the fragment of the redirect target for `req`, percent-encoded.
+    fn target_fragment<'a>(&'a self, req: &HttpRequest) -> Option<Cow<'a, str>> {
+        let from_query = self.fragment_param.as_ref().and_then(|param| {
+            serde_urlencoded::from_str::<Vec<(String, String)>>(req.query_string())
+                .ok()?
+                .into_iter()
+                .find(|(name, value)| name == param.as_ref() && !value.is_empty())
+                .map(|(_, value)| FRAGMENT_ENCODER.encode(&value).into_owned())
+        });
+
+        match from_query {
+            Some(fragment) => Some(Cow::Owned(fragment)),
+            None => self.fragment.as_deref().map(Cow::Borrowed),
+        }
+    }
+
+    /// Builds the redirect response for a request matched by this service.
+    fn respond(&self, req: &HttpRequest, rdef: &ResourceDef) -> HttpResponse {
+        if !self.allows_method(req.method()) {
+            let methods = self.methods.clone().unwrap_or_default();
+
+            return HttpResponse::MethodNotAllowed()
+                .insert_header(header::Allow(methods))
+                .finish();
+        }
+
+        let redirect_count = match self.next_redirect_count(req) {
+            Ok(count) => count,
+            Err(err) => return HttpResponse::from_error(err),
+        };
+
+        let location = match self.location(req, rdef) {
+            Ok(location) => location,
+            Err(err) => return HttpResponse::from_error(err),
+        };
+
+        let mut location = match self.secure_location(location) {
+            Ok(location) => location,
+            Err(err) => return HttpResponse::from_error(err),
+        };
+
+        if self.preserve_query && !req.query_string().is_empty() {
+            location = merge_query(&location, req.query_string());
+        }
+
+        if let Some(fragment) = self.target_fragment(req) {
+            if let Some(idx) = location.find('#') {
+                location.truncate(idx);
+            }
+
+            location.push('#');
+            location.push_str(&fragment);
+        }
+
+        if !self.target_transforms.0.is_empty() {
+            let (origin, mut parts) = TargetParts::parse(&location);
+
+            for transform in &self.target_transforms.0 {
+                transform(&mut parts);
+            }
+
+            location = parts.write(origin);
+        }
+
+        #[cfg(feature = "signed-urls")]
+        if let Some((signer, ttl)) = &self.signed_expiry {
+            location = signer.sign(&location, std::time::SystemTime::now() + *ttl);
+        }
+
+        let mut extra_headers = self
+            .permissions_policy
+            .iter()
+            .filter_map(|policy| {
+                let value = policy.clone().try_into_value().ok()?;
+                Some((header::PERMISSIONS_POLICY, value))
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(count) = redirect_count {
+            extra_headers.push((REDIRECT_COUNT, header::HeaderValue::from(count)));
+        }
+
+        if let Some(sunset) = self.sunset.clone() {
+            let successor = format!("<{}>; rel=\"successor-version\"", location);
+
+            extra_headers.push((
+                header::DEPRECATION,
+                header::HeaderValue::from_static("true"),
+            ));
+            extra_headers.extend(
+                sunset
+                    .try_into_value()
+                    .ok()
+                    .map(|value| (header::SUNSET, value)),
+            );
+            extra_headers.extend(
+                header::HeaderValue::try_from(successor)
+                    .ok()
+                    .map(|value| (header::LINK, value)),
+            );
+        }
+
+        let status_code = self.current_status_code();
+        let res = empty_redirect_response(status_code, &location, extra_headers);
+
+        if self.html_body {
+            let href = escape_html(&location);
+            let html = format!(
+                "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Redirecting</title></head>\n\
+                <body><p>Redirecting to <a href=\"{0}\">{0}</a>.</p></body></html>\n",
+                href
+            );
+
+            let len = html.len();
+            let mut res = res.set_body(html);
+            res.headers_mut()
+                .insert(header::CONTENT_LENGTH, header::HeaderValue::from(len));
+            res.headers_mut().insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("text/html; charset=utf-8"),
+            );
+            return res.map_into_boxed_body();
+        }
+
+        #[cfg(debug_assertions)]
+        if self.echo_body {
+            let echo = format!("{}\nfrom: {}\nto: {}\n", status_code, req.uri(), location);
+
+            let mut res = res.set_body(echo);
+            res.headers_mut().remove(header::CONTENT_LENGTH);
+            res.headers_mut().insert(
+                header::CONTENT_TYPE,
+                header::HeaderValue::from_static("text/plain; charset=utf-8"),
+            );
+            return res.map_into_boxed_body();
+        }
+
+        res
+    }
+}
+
+impl HttpServiceFactory for Redirect {
+    fn register(mut self, config: &mut AppService) {
+        self.assert_routable();
+        self.assert_placeholders_resolve(config.path_prefix());
+        self.start();
+
+        if let Some(strict) = self.validate_target {
+            if let Some(check) = self.target_check(config.path_prefix(), strict) {
+                config.add_redirect_check(check);
+            }
+        }
+
+        let rdef = self.resource_def();
+
+        let mut guards = Vec::<Box<dyn Guard>>::new();
+
+        // unless 405 responses are requested, other methods fall through to later services
+        match &self.methods {
+            Some(methods) if !self.method_mismatch_405 => {
+                let methods = methods.clone();
+                let guard = guard::fn_guard(move |ctx| methods.contains(&ctx.head().method));
+                guards.push(Box::new(guard));
+            }
+            _ => {}
+        }
+
+        let redirect = Rc::new((self, rdef.clone()));
+
+        // requests not matching the query conditions always fall through
+        if !redirect.0.query.is_empty() {
+            let redirect = Rc::clone(&redirect);
+            let guard = guard::fn_guard(move |ctx| {
+                redirect
+                    .0
+                    .matches_query(ctx.head().uri.query().unwrap_or_default())
+            });
+            guards.push(Box::new(guard));
+        }
+
+        guards.extend(
+            redirect
+                .0
+                .guards
+                .0
+                .iter()
+                .map(|guard| Box::new(Rc::clone(guard)) as Box<dyn Guard>),
+        );
+
+        let guards = if guards.is_empty() {
+            None
+        } else {
+            Some(guards)
+        };
+
+        let redirect_factory = fn_service(move |req: ServiceRequest| {
+            let (redirect, rdef) = &*redirect;
+            let res = redirect.respond(req.request(), rdef);
+            ready(Ok(req.into_response(res)))
+        });
+
+        config.register_service(rdef, guards, redirect_factory, None)
+    }
+}
+
+impl Responder for Redirect {
+    type Body = BoxBody;
+
+    fn respond_to(mut self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        // handlers build a new redirect for each request, so the grace period is timed from the
+        // app's start instead of this redirect's creation
+        self.start_at(req.app_state().started());
+        let rdef = self.resource_def();
+        self.respond(req, &rdef)
+    }
+}
+
+/// A redirect target to match against the app's routes once they are all registered.
+///
+/// See [`Redirect::validate_target`].
+#[derive(Debug)]
+pub(crate) struct RedirectTargetCheck {
+    from: String,
+    target: CheckedTarget,
+    strict: bool,
+}
+
+#[derive(Debug)]
+enum CheckedTarget {
+    /// Path, without query string or fragment, that must match a route.
+    Path(String),
+
+    /// Name of a route that must exist.
+    Name(String),
+}
+
+impl RedirectTargetCheck {
+    /// Returns true if the target is served by a route in `rmap`.
+    fn resolves(&self, rmap: &ResourceMap) -> bool {
+        match &self.target {
+            CheckedTarget::Path(path) => {
+                let path = path.split(|c| c == '?' || c == '#').next().unwrap_or("");
+                rmap.has_resource(path)
+            }
+            CheckedTarget::Name(name) => rmap.has_named(name),
+        }
+    }
+}
+
+impl fmt::Display for RedirectTargetCheck {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.target {
+            CheckedTarget::Path(path) => write!(
+                f,
+                "redirect from `{}` to `{}` does not match any route",
+                self.from, path
+            ),
+            CheckedTarget::Name(name) => write!(
+                f,
+                "redirect from `{}` targets route `{}`, which does not exist",
+                self.from, name
+            ),
+        }
+    }
+}
+
+/// Logs redirects whose targets are not served by a route in `rmap` as warnings or, if any of
+/// them were validated strictly, panics.
+pub(crate) fn check_targets(checks: &[RedirectTargetCheck], rmap: &ResourceMap) {
+    let mut strict_failures = Vec::new();
+
+    for check in checks.iter().filter(|check| !check.resolves(rmap)) {
+        if check.strict {
+            strict_failures.push(check.to_string());
+        } else {
+            log::warn!("{}", check);
+        }
+    }
+
+    if !strict_failures.is_empty() {
+        panic!("dangling redirects:\n  {}", strict_failures.join("\n  "));
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Redirect
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RedirectMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        self.assert_routable();
+        let rdef = self.resource_def();
+
+        let mut redirect = self.clone();
+        redirect.start();
+
+        ok(RedirectMiddleware {
+            service,
+            redirect: Rc::new((redirect, rdef)),
+        })
+    }
+}
+
+/// Middleware service for [`Redirect`].
+pub struct RedirectMiddleware<S> {
+    service: S,
+    redirect: Rc<(Redirect, ResourceDef)>,
+}
+
+impl<S, B> Service<ServiceRequest> for RedirectMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, mut req: ServiceRequest) -> Self::Future {
+        let (redirect, rdef) = &*self.redirect;
+
+        let matches = (redirect.all || rdef.is_match(req.path()))
+            && redirect.matches_query(req.query_string())
+            && redirect.matches_guards(&req);
+
+        // as with the service, other methods pass through unless 405 responses are requested
+        if matches && (redirect.method_mismatch_405 || redirect.allows_method(req.method())) {
+            // requests are not routed yet, so capture the segments used by target placeholders
+            if !redirect.all {
+                rdef.capture_match_info(&mut req);
+            }
+
+            let res = redirect.respond(req.request(), rdef);
+            let res = req.into_response(res).map_into_right_body();
+            return Box::pin(async move { Ok(res) });
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) })
+    }
+}
+
+/// An HTTP service for redirecting all requests to another host, keeping their path and query.
+///
+/// Useful when migrating a site to a new domain. The `Location` is built from the target host,
+/// the full request path, including any scope prefix, and the query string. The scheme of the
+/// request is kept unless another is set using [`scheme`](Self::scheme). A port in the target host
+/// that is the default for the scheme, such as `:443` for `https`, is left out.
+///
+/// The service matches every path in the app or scope it is registered in, and responds with
+/// "308 Permanent Redirect", so it should usually be registered last or behind a guard.
+///
+/// # Examples
+/// ```
+/// use actix_web::{guard, web, App};
+///
+/// App::new().service(
+///     // "http://example.com/docs?page=2" => "https://new.example.com/docs?page=2"
+///     web::scope("")
+///         .guard(guard::Host("example.com"))
+///         .service(web::HostMigrationRedirect::new("new.example.com").scheme("https")),
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct HostMigrationRedirect {
+    host: Cow<'static, str>,
+    scheme: Option<Cow<'static, str>>,
+}
+
+impl HostMigrationRedirect {
+    /// Create a new `HostMigrationRedirect` service, redirecting to `host`.
+    ///
+    /// The host may include a port, as in `new.example.com:8443`.
+    pub fn new(host: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            host: host.into(),
+            scheme: None,
+        }
+    }
+
+    /// Redirect using the given scheme, such as `https`, instead of the scheme of the request.
+    pub fn scheme(mut self, scheme: impl Into<Cow<'static, str>>) -> Self {
+        self.scheme = Some(scheme.into());
+        self
+    }
+
+    /// Resolves the `Location` header value for `req`.
+    fn location(&self, req: &HttpRequest) -> String {
+        let scheme = match &self.scheme {
+            Some(scheme) => scheme.to_string(),
+            None => req.connection_info().scheme().to_owned(),
+        };
+
+        let default_port = match scheme.as_str() {
+            "http" | "ws" => Some(":80"),
+            "https" | "wss" => Some(":443"),
+            _ => None,
+        };
+
+        let host = default_port
+            .and_then(|port| self.host.strip_suffix(port))
+            .unwrap_or(&self.host);
+
+        let path = req.uri().path_and_query().map_or("/", |pq| pq.as_str());
+
+        format!("{}://{}{}", scheme, host, path)
+    }
+}
+
+impl HttpServiceFactory for HostMigrationRedirect {
+    fn register(self, config: &mut AppService) {
+        let rdef = ResourceDef::root_prefix("");
+        let redirect = Rc::new(self);
+
+        let redirect_factory = fn_service(move |req: ServiceRequest| {
+            let res = empty_redirect_response(
+                StatusCode::PERMANENT_REDIRECT,
+                &redirect.location(req.request()),
+                None,
+            );
+            ready(Ok(req.into_response(res)))
+        });
+
+        config.register_service(rdef, None, redirect_factory, None)
+    }
+}
+
+/// Middleware that redirects requests to the canonical form of their URL.
+///
+/// Host, scheme, trailing-slash, and case normalization are applied together, so a request is
+/// redirected at most once, straight to the fully canonical URL. Requests that are already
+/// canonical are passed on to the wrapped service. All normalizations are enabled by default and
+/// each can be turned off on its own:
+/// - [`strip_www`](Self::strip_www): removes a leading `www.` from the host;
+/// - [`https`](Self::https): upgrades `http` requests to `https`, leaving out an explicit port;
+/// - [`trim_trailing_slash`](Self::trim_trailing_slash): removes trailing slashes from the path,
+///   other than for the root path;
+/// - [`lowercase`](Self::lowercase): lowercases the host and path, leaving percent-encoded
+///   sequences as they are.
+///
+/// The query string is kept as is. Redirects use "308 Permanent Redirect" so that the method and
+/// body of the request are kept.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App};
+///
+/// // "http://www.Example.com/About/" => "https://example.com/about"
+/// App::new()
+///     .wrap(web::Canonicalize::new())
+///     .route("/about", web::get().to(|| async { "About" }));
+///
+/// // keep the scheme, for apps served behind a TLS-terminating proxy that handles upgrades
+/// App::new().wrap(web::Canonicalize::new().https(false));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Canonicalize {
+    strip_www: bool,
+    https: bool,
+    trim_trailing_slash: bool,
+    lowercase: bool,
+}
+
+impl Default for Canonicalize {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Canonicalize {
+    /// Create a new `Canonicalize` middleware, with all normalizations enabled.
+    pub fn new() -> Self {
+        Self {
+            strip_www: true,
+            https: true,
+            trim_trailing_slash: true,
+            lowercase: true,
+        }
+    }
+
+    /// Enable or disable removing a leading `www.` from the host.
+    pub fn strip_www(mut self, enabled: bool) -> Self {
+        self.strip_www = enabled;
+        self
+    }
+
+    /// Enable or disable upgrading `http` requests to `https`.
+    pub fn https(mut self, enabled: bool) -> Self {
+        self.https = enabled;
+        self
+    }
+
+    /// Enable or disable removing trailing slashes from the path.
+    pub fn trim_trailing_slash(mut self, enabled: bool) -> Self {
+        self.trim_trailing_slash = enabled;
+        self
+    }
+
+    /// Enable or disable lowercasing the host and path.
+    pub fn lowercase(mut self, enabled: bool) -> Self {
+        self.lowercase = enabled;
+        self
+    }
+
+    /// Resolves the canonical URL of `req`, if it differs from the requested URL.
+    fn location(&self, req: &HttpRequest) -> Option<String> {
+        let info = req.connection_info();
+        let req_scheme = info.scheme();
+        let req_host = info.host();
+        let req_path = req.uri().path();
+
+        let scheme = if self.https && req_scheme == "http" {
+            "https"
+        } else {
+            req_scheme
+        };
+
+        let mut host = Cow::Borrowed(req_host);
+
+        if self.lowercase && host.bytes().any(|byte| byte.is_ascii_uppercase()) {
+            host = Cow::Owned(host.to_ascii_lowercase());
+        }
+
+        if self.strip_www
+            && host.len() > 4
+            && host
+                .get(..4)
+                .map_or(false, |prefix| prefix.eq_ignore_ascii_case("www."))
+        {
+            host = Cow::Owned(host[4..].to_owned());
+        }
+
+        // an explicit port belongs to the old scheme
+        if scheme != req_scheme {
+            if let Some((name, _)) = host.rsplit_once(':').filter(|(_, port)| {
+                !port.is_empty() && port.bytes().all(|byte| byte.is_ascii_digit())
+            }) {
+                host = Cow::Owned(name.to_owned());
+            }
+        }
+
+        let mut path = Cow::Borrowed(req_path);
+
+        if self.lowercase && path.bytes().any(|byte| byte.is_ascii_uppercase()) {
+            path = Cow::Owned(lowercase_path(&path));
+        }
+
+        if self.trim_trailing_slash && path.len() > 1 && path.ends_with('/') {
+            let trimmed = path.trim_end_matches('/');
+            path = Cow::Owned(if trimmed.is_empty() { "/" } else { trimmed }.to_owned());
+        }
+
+        if scheme == req_scheme && host == req_host && path == req_path {
+            return None;
+        }
+
+        let query = req.query_string();
+
+        Some(if query.is_empty() {
+            format!("{}://{}{}", scheme, host, path)
+        } else {
+            format!("{}://{}{}?{}", scheme, host, path, query)
+        })
+    }
+}
+
+/// Lowercases ASCII letters in `path`, keeping the hex digits of `%XX` sequences as they are.
+fn lowercase_path(path: &str) -> String {
+    let mut lowercased = String::with_capacity(path.len());
+    let mut escape = 0;
+
+    for ch in path.chars() {
+        if escape > 0 {
+            escape -= 1;
+            lowercased.push(ch);
+        } else if ch == '%' {
+            escape = 2;
+            lowercased.push(ch);
+        } else {
+            lowercased.push(ch.to_ascii_lowercase());
+        }
+    }
+
+    lowercased
+}
+
+impl<S, B> Transform<S, ServiceRequest> for Canonicalize
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = CanonicalizeMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(CanonicalizeMiddleware {
+            service,
+            canonicalize: Rc::new(self.clone()),
+        })
+    }
+}
+
+/// Middleware service for [`Canonicalize`].
+pub struct CanonicalizeMiddleware<S> {
+    service: S,
+    canonicalize: Rc<Canonicalize>,
+}
+
+impl<S, B> Service<ServiceRequest> for CanonicalizeMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        if let Some(location) = self.canonicalize.location(req.request()) {
+            let res = empty_redirect_response(StatusCode::PERMANENT_REDIRECT, &location, None);
+            let res = req.into_response(res).map_into_right_body();
+            return Box::pin(async move { Ok(res) });
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move { fut.await.map(ServiceResponse::map_into_left_body) })
+    }
+}
+
+/// Middleware that turns marked responses into redirects.
+///
+/// Handlers, or upstream services whose responses are passed through, can ask for a redirect by
+/// responding with one of the configured statuses and a marker header holding the target. Such
+/// responses are replaced with an empty redirect response, using "307 Temporary Redirect" by
+/// default, with the target as its `Location`. The rest of the marked response is discarded.
+/// Responses with other statuses, or without the marker header, are passed on unchanged.
+///
+/// # Examples
+/// ```
+/// use actix_web::{
+///     http::{header::HeaderName, StatusCode},
+///     web, App, HttpResponse,
+/// };
+///
+/// const REDIRECT_INTENT: u16 = 399;
+///
+/// App::new()
+///     .wrap(
+///         web::RedirectFromStatus::new(HeaderName::from_static("x-redirect-to"))
+///             .status(StatusCode::from_u16(REDIRECT_INTENT).unwrap())
+///             .using_status_code(StatusCode::SEE_OTHER),
+///     )
+///     .route(
+///         "/checkout",
+///         web::post().to(|| async {
+///             // responds with "303 See Other" and `Location: /orders/42`
+///             HttpResponse::build(StatusCode::from_u16(REDIRECT_INTENT).unwrap())
+///                 .insert_header(("x-redirect-to", "/orders/42"))
+///                 .finish()
+///         }),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct RedirectFromStatus {
+    marker: header::HeaderName,
+    statuses: Vec<StatusCode>,
+    status_code: StatusCode,
+}
+
+impl RedirectFromStatus {
+    /// Creates a middleware that reads redirect targets from the `marker` header.
+    ///
+    /// No responses are converted until statuses are added using [`status`](Self::status).
+    pub fn new(marker: header::HeaderName) -> Self {
+        Self {
+            marker,
+            statuses: Vec::new(),
+            status_code: StatusCode::TEMPORARY_REDIRECT,
+        }
+    }
+
+    /// Converts marked responses with `status` into redirects.
+    ///
+    /// Calling this multiple times converts responses with any of the statuses.
+    pub fn status(mut self, status: StatusCode) -> Self {
+        self.statuses.push(status);
+        self
+    }
+
+    /// Use custom status code when redirecting.
+    ///
+    /// Defaults to "307 Temporary Redirect".
+    pub fn using_status_code(mut self, status: StatusCode) -> Self {
+        self.status_code = status;
+        self
+    }
+
+    /// Returns the redirect target of `res`, if it is marked for conversion.
+    fn location<B>(&self, res: &HttpResponse<B>) -> Option<Cow<'static, str>> {
+        if !self.statuses.contains(&res.status()) {
+            return None;
+        }
+
+        let target = res.headers().get(&self.marker)?.to_str().ok()?.trim();
+
+        if target.is_empty() {
+            return None;
+        }
+
+        Some(encode_target(Cow::Owned(target.to_owned())))
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RedirectFromStatus
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Transform = RedirectFromStatusMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ok(RedirectFromStatusMiddleware {
+            service,
+            redirect: Rc::new(self.clone()),
+        })
+    }
+}
+
+/// Middleware service for [`RedirectFromStatus`].
+pub struct RedirectFromStatusMiddleware<S> {
+    service: S,
+    redirect: Rc<RedirectFromStatus>,
+}
+
+impl<S, B> Service<ServiceRequest> for RedirectFromStatusMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_service::forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let redirect = Rc::clone(&self.redirect);
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let res = fut.await?;
+
+            let location = match redirect.location(res.response()) {
+                Some(location) => location,
+                None => return Ok(res.map_into_left_body()),
+            };
+
+            let redirect = empty_redirect_response(redirect.status_code, &location, None);
+            Ok(res.into_response(redirect).map_into_right_body())
+        })
+    }
+}
+
+/// A declarative redirect, as loaded from a configuration file.
+///
+/// Rules are deserialized from maps with these keys:
+/// - `from`: the path to match, as in [`Redirect::from`];
+/// - `to`: the redirect target, which may use `{name}` placeholders for dynamic segments of
+///   `from`;
+/// - `status` (optional): one of 301, 302, 303, 307, or 308; defaults to 308;
+/// - `relative` (optional): if true, `to` is resolved as in [`Redirect::to_relative`] instead of
+///   being used verbatim as in [`Redirect::to_absolute`]; defaults to false.
+///
+/// Use [`register_redirects`] to validate and register a set of rules.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RedirectRule {
+    /// Path to match.
+    pub from: String,
+
+    /// Redirect target.
+    pub to: String,
+
+    /// Redirect status code.
+    #[serde(default)]
+    pub status: Option<u16>,
+
+    /// Whether `to` is relative to the scope the rule is registered in.
+    #[serde(default)]
+    pub relative: bool,
+}
+
+impl RedirectRule {
+    /// Checks that this rule describes a valid redirect. `index` is used in error reports.
+    fn validate(&self, index: usize) -> Result<(), RedirectRuleError> {
+        if !self.from.starts_with('/') || !braces_balanced(&self.from) {
+            return Err(RedirectRuleError::InvalidFrom { index });
+        }
+
+        let valid_target = if self.relative {
+            self.to.starts_with('/')
+        } else {
+            !self.to.is_empty()
+        };
+
+        if !valid_target || header::HeaderValue::from_str(&self.to).is_err() {
+            return Err(RedirectRuleError::InvalidTarget { index });
+        }
+
+        if let Some(status) = self.status {
+            if !matches!(status, 301 | 302 | 303 | 307 | 308) {
+                return Err(RedirectRuleError::InvalidStatus { index, status });
+            }
+        }
+
+        // the same checks that would otherwise panic when the redirect is registered; placeholders
+        // can only refer to segments of the rule's own `from` path
+        let redirect = self.clone().into_redirect();
+        debug_assert!(!redirect.responder_only);
+
+        match redirect.unresolved_placeholder("") {
+            Some(name) => Err(RedirectRuleError::UnresolvedPlaceholder {
+                index,
+                name: name.to_owned(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Converts an already validated rule.
+    fn into_redirect(self) -> Redirect {
+        let redirect = Redirect::from(self.from);
+
+        let redirect = if self.relative {
+            redirect.to_relative(self.to)
+        } else {
+            redirect.to_absolute(self.to)
+        };
+
+        match self
+            .status
+            .and_then(|status| StatusCode::from_u16(status).ok())
+        {
+            Some(status) => redirect.using_status_code(status),
+            None => redirect,
+        }
+    }
+}
+
+/// Validates a set of redirect rules and registers a [`Redirect`] service for each one.
+///
+/// All rules are validated before any are registered, so a rule set containing an invalid rule
+/// registers nothing. Rules are registered in order, so earlier rules take precedence.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App};
+///
+/// let rules: Vec<web::RedirectRule> = serde_json::from_str(r#"[
+///     { "from": "/duck", "to": "https://duckduckgo.com/" },
+///     { "from": "/old", "to": "/new", "status": 307, "relative": true }
+/// ]"#).unwrap();
+///
+/// let app = App::new().configure(|cfg| {
+///     web::register_redirects(rules, cfg).expect("invalid redirect rules");
+/// });
+/// ```
+pub fn register_redirects(
+    rules: impl IntoIterator<Item = RedirectRule>,
+    cfg: &mut ServiceConfig,
+) -> Result<(), RedirectRuleError> {
+    let rules = rules.into_iter().collect::<Vec<_>>();
+
+    for (index, rule) in rules.iter().enumerate() {
+        rule.validate(index)?;
+    }
+
+    for rule in rules {
+        cfg.service(rule.into_redirect());
+    }
+
+    Ok(())
+}
+
+/// Builds a bodyless redirect response to `location`, with any `extra_headers`.
+///
+/// All redirect services use this so that their responses are framed alike, with an explicit
+/// `Content-Length: 0`.
+pub(crate) fn empty_redirect_response(
+    status: StatusCode,
+    location: &str,
+    extra_headers: impl IntoIterator<Item = (header::HeaderName, header::HeaderValue)>,
+) -> HttpResponse {
+    let mut res = HttpResponse::build(status);
+    res.insert_header((header::LOCATION, location));
+
+    for (name, value) in extra_headers {
+        res.insert_header((name, value));
+    }
+
+    res.insert_header(header::ContentLength(0)).finish()
+}
+
+/// Percent-encodes characters that may not appear in a URL, keeping existing `%XX` sequences.
+pub(crate) fn encode_target(target: Cow<'static, str>) -> Cow<'static, str> {
+    match TARGET_ENCODER.encode(&target) {
+        Cow::Owned(encoded) => Cow::Owned(encoded),
+        Cow::Borrowed(_) => target,
+    }
+}
+
+/// Header counting the redirects in a chain; see [`Redirect::max_redirects`].
+const REDIRECT_COUNT: header::HeaderName = header::HeaderName::from_static("x-redirect-count");
+
+/// Encoder for `Location` header values, which are complete URLs or relative references.
+const TARGET_ENCODER: PathEncoder = PathEncoder::new(EncodeSet::Url).raw();
+
+/// Encoder for path segments substituted into redirect targets, which may span several segments.
+const PLACEHOLDER_ENCODER: PathEncoder = PathEncoder::new(EncodeSet::Path).raw();
+
+/// Encoder for fragments of `Location` header values.
+const FRAGMENT_ENCODER: PathEncoder = PathEncoder::new(EncodeSet::Fragment);
+
+/// Returns the names of the dynamic segments of a resource pattern, such as `id` for `/{id}` and
+/// `tail` for `/{tail:.*}`.
+fn segment_names(pattern: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('{') {
+        let segment = &rest[start + 1..];
+
+        // regexes of segments may contain braces themselves
+        let mut depth = 1;
+        let end = segment
+            .char_indices()
+            .find(|&(_, ch)| {
+                match ch {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+
+                depth == 0
+            })
+            .map_or(segment.len(), |(idx, _)| idx);
+
+        names.extend(segment[..end].split(':').next());
+        rest = segment.get(end + 1..).unwrap_or_default();
+    }
+
+    names
+}
+
+/// Returns true if every `{` in a path pattern is closed by a matching `}`.
+fn braces_balanced(pattern: &str) -> bool {
+    let mut depth = 0_usize;
+
+    for ch in pattern.chars() {
+        match ch {
+            '{' => depth += 1,
+            '}' => match depth.checked_sub(1) {
+                Some(d) => depth = d,
+                None => return false,
+            },
+            _ => {}
+        }
+    }
+
+    depth == 0
+}
+
+/// Returns the names of the `{name}` placeholders in a redirect target.
+fn placeholder_names(target: &str) -> impl Iterator<Item = &str> {
+    let mut rest = target;
+
+    std::iter::from_fn(move || {
+        let start = rest.find('{')?;
+        let end = start + rest[start..].find('}')?;
+
+        let name = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+        Some(name)
+    })
+}
+
+/// Replaces `{name}` placeholders in `target` with the dynamic segments of the request path that
+/// have the same name, percent-encoded as paths.
+fn fill_placeholders<'a>(
+    target: &'a str,
+    req: &HttpRequest,
+) -> Result<Cow<'a, str>, UnresolvedRedirectPlaceholder> {
+    if !target.contains('{') {
+        return Ok(Cow::Borrowed(target));
+    }
+
+    let mut filled = String::with_capacity(target.len());
+    let mut rest = target;
+
+    while let Some(start) = rest.find('{') {
+        // an unclosed brace is not a placeholder
+        let end = match rest[start..].find('}') {
+            Some(len) => start + len,
+            None => break,
+        };
+
+        let name = &rest[start + 1..end];
+        let value =
+            req.match_info()
+                .get(name)
+                .ok_or_else(|| UnresolvedRedirectPlaceholder {
+                    name: name.to_owned(),
+                })?;
+
+        filled.push_str(&rest[..start]);
+        filled.push_str(&PLACEHOLDER_ENCODER.encode(value));
+        rest = &rest[end + 1..];
+    }
+
+    filled.push_str(rest);
+    Ok(Cow::Owned(filled))
+}
+
+/// Adds `query` to the query of `location`, before any fragment.
+fn merge_query(location: &str, query: &str) -> String {
+    let (target, fragment) = match location.find('#') {
+        Some(idx) => location.split_at(idx),
+        None => (location, ""),
+    };
+
+    let separator = match target.find('?') {
+        Some(idx) if idx + 1 == target.len() || target.ends_with('&') => "",
+        Some(_) => "&",
+        None => "?",
+    };
+
+    format!("{}{}{}{}", target, separator, query, fragment)
+}
+
+/// Escapes `text` for use in HTML text and double-quoted attribute values.
+fn escape_html(text: &str) -> Cow<'_, str> {
+    if !text.contains(|c| matches!(c, '&' | '<' | '>' | '"' | '\'')) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut escaped = String::with_capacity(text.len() + 16);
+
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            ch => escaped.push(ch),
+        }
+    }
+
+    Cow::Owned(escaped)
+}
+
+/// Splits `url` into its scheme and authority, if any, and its path and query.
+pub(crate) fn split_origin(url: &str) -> (&str, &str) {
+    let authority_start = match url.find("://") {
+        // paths may contain "://" in their query
+        Some(idx) if !url[..idx].contains(|ch| matches!(ch, '/' | '?')) => idx + 3,
+        _ => return ("", url),
+    };
+
+    match url[authority_start..].find(|ch| matches!(ch, '/' | '?')) {
+        Some(idx) => url.split_at(authority_start + idx),
+        None => (url, ""),
+    }
+}
+
+/// Returns the part of `path` that precedes the segment matched by `rdef`, i.e., the path of the
+/// enclosing scope.
+///
+/// Scope prefixes end at a segment boundary, so the leftmost `/` from which `rdef` matches the rest
+/// of the path is used. Falls back to the whole path if `rdef` does not match any suffix.
+fn scope_prefix<'a>(path: &'a str, rdef: &ResourceDef) -> &'a str {
+    path.match_indices('/')
+        .map(|(idx, _)| idx)
+        .chain(std::iter::once(path.len()))
+        .find(|&idx| rdef.is_match(&path[idx..]))
+        .map_or(path, |idx| &path[..idx])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::{
+        dev::Service,
+        http::StatusCode,
+        test::{self, TestRequest},
+        web, App,
+    };
+
+    #[actix_rt::test]
+    async fn absolute_redirects() {
+        let redirector = Redirect::from("/one").to_absolute("/two");
+
+        let svc = test::init_service(App::new().service(redirector)).await;
+
+        let req = TestRequest::default().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::from_u16(308).unwrap());
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/two");
+    }
+
+    #[actix_rt::test]
+    async fn absolute_targets_are_encoded() {
+        let redirect = Redirect::from("/one").to_absolute("/path with space/ü?q=a b&r=%20");
+        let srv = test::init_service(App::new().service(redirect)).await;
+
+        let req = TestRequest::with_uri("/one").to_request();
+        let res = test::call_service(&srv, req).await;
+        test::assert_redirect(&res, "/path%20with%20space/%C3%BC?q=a%20b&r=%20");
+
+        assert_eq!(
+            encode_target("/already%2Fencoded".into()),
+            "/already%2Fencoded"
+        );
+        assert_eq!(encode_target("/100%".into()), "/100%25");
+        assert_eq!(encode_target("/a%zz".into()), "/a%25zz");
+        assert!(matches!(
+            encode_target("https://example.com/".into()),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[actix_rt::test]
+    async fn templated_targets() {
+        let vars = HashMap::from([
+            ("BASE".to_owned(), "https://staging.example.com".to_owned()),
+            ("LANG".to_owned(), "en us".to_owned()),
+        ]);
+
+        let redirect = Redirect::from("/docs")
+            .to_absolute_templated("${BASE}/docs/${LANG}?v=$1", &vars)
+            .unwrap();
+        let srv = test::init_service(App::new().service(redirect)).await;
+
+        let req = TestRequest::with_uri("/docs").to_request();
+        let res = test::call_service(&srv, req).await;
+        test::assert_redirect(&res, "https://staging.example.com/docs/en%20us?v=$1");
+    }
+
+    #[test]
+    fn unresolved_templates() {
+        let vars = HashMap::from([("BASE".to_owned(), "".to_owned())]);
+        let template = |template| {
+            Redirect::from("/docs")
+                .to_absolute_templated(template, &vars)
+                .unwrap_err()
+        };
+
+        assert_eq!(
+            template("${BASE}/${ENV}/docs"),
+            RedirectTemplateError::UnknownPlaceholder {
+                name: "ENV".to_owned()
+            }
+        );
+        assert_eq!(
+            template("${BASE"),
+            RedirectTemplateError::UnclosedPlaceholder
+        );
+        assert_eq!(
+            template("${BASE}"),
+            RedirectTemplateError::InvalidTarget {
+                target: String::new()
+            }
+        );
+        assert_eq!(
+            template("https://${BASE}/docs"),
+            RedirectTemplateError::InvalidTarget {
+                target: "https:///docs".to_owned()
+            }
+        );
+    }
+
+    #[actix_rt::test]
+    async fn relative_redirects() {
+        let redirector = Redirect::from("/one").to_relative("/two");
+
+        let svc = test::init_service(App::new().service(redirector)).await;
+
+        let req = TestRequest::default().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::from_u16(308).unwrap());
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/two");
+    }
+
+    #[actix_rt::test]
+    async fn scoped_redirects() {
+        let svc = test::init_service(
+            App::new().service(
+                web::scope("/api")
+                    .service(Redirect::from("/relative").to_relative("/new"))
+                    .service(Redirect::from("/absolute").to_absolute("/new")),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/api/relative").to_request();
+        let res = svc.call(req).await.unwrap();
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/api/new");
+
+        let req = TestRequest::default().uri("/api/absolute").to_request();
+        let res = svc.call(req).await.unwrap();
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/new");
+    }
+
+    #[actix_rt::test]
+    async fn relative_redirects_from_patterns() {
+        let svc = test::init_service(
+            App::new()
+                .service(Redirect::from("/users/{id}").to_relative("/new"))
+                .service(
+                    web::scope("/api/{version}")
+                        .service(Redirect::from("/users/{id}").to_relative("/new"))
+                        .service(Redirect::from("/{a}/{b}").to_relative("/both")),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/users/5").to_request();
+        let res = svc.call(req).await.unwrap();
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/new");
+
+        let req = TestRequest::default().uri("/api/v1/users/5").to_request();
+        let res = svc.call(req).await.unwrap();
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/api/v1/new");
+
+        let req = TestRequest::default().uri("/api/v2/x/y").to_request();
+        let res = svc.call(req).await.unwrap();
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/api/v2/both");
+    }
+
+    #[actix_rt::test]
+    async fn appending_redirects() {
+        let svc = test::init_service(
+            App::new()
+                .service(Redirect::from("/docs").append("/latest"))
+                .service(Redirect::from("/blog").append("/"))
+                .service(
+                    web::scope("/api/{version}")
+                        .service(Redirect::from("/docs/").append("latest/")),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/docs?page=2").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/docs/latest?page=2");
+
+        let req = TestRequest::default().uri("/blog").to_request();
+        let res = svc.call(req).await.unwrap();
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/blog/");
+
+        let req = TestRequest::default().uri("/api/v2/docs/").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/api/v2/docs/latest/");
+    }
+
+    #[actix_rt::test]
+    async fn temporary_redirects() {
+        let external_service = Redirect::from("/external")
+            .to_absolute("https://duck.com")
+            .temporary();
+
+        let svc = test::init_service(App::new().service(external_service)).await;
+
+        let req = TestRequest::default().uri("/external").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::from_u16(307).unwrap());
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "https://duck.com");
+    }
+
+    #[actix_rt::test]
+    async fn as_service_with_custom_status() {
+        let svc = test::init_service(
+            App::new().service(
+                Redirect::from("/old")
+                    .to_relative("/new")
+                    .using_status_code(StatusCode::MOVED_PERMANENTLY),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/old").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::MOVED_PERMANENTLY);
+    }
+
+    #[actix_rt::test]
+    async fn middleware_redirects_all_paths() {
+        let svc = test::init_service(
+            App::new()
+                .wrap(Redirect::all().to_absolute("/maintenance").temporary())
+                .route("/", web::get().to(|| async { "home" }))
+                .service(web::scope("/api").route("/users", web::post().to(|| async { "" }))),
+        )
+        .await;
+
+        for (method, path) in [
+            (Method::GET, "/"),
+            (Method::POST, "/api/users"),
+            (Method::DELETE, "/not/routed"),
+        ] {
+            let req = TestRequest::default().method(method).uri(path).to_request();
+            let res = svc.call(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+            let hdr = res.headers().get(header::LOCATION).unwrap();
+            assert_eq!(hdr.to_str().unwrap(), "/maintenance");
+        }
+
+        // registering `Redirect::all` as a service also matches all paths
+        let svc = test::init_service(App::new().service(Redirect::all().append("/"))).await;
+
+        let req = TestRequest::default().uri("/a/b").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/a/b/");
+    }
+
+    #[actix_rt::test]
+    async fn middleware_matching_paths() {
+        let svc = test::init_service(
+            App::new()
+                .wrap(
+                    Redirect::from("/old/{tail}*")
+                        .to_relative("/new")
+                        .methods([Method::GET]),
+                )
+                .route("/old/page", web::post().to(|| async { "posted" }))
+                .route("/other", web::get().to(|| async { "other" })),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/old/page").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/new");
+
+        let req = TestRequest::post().uri("/old/page").to_request();
+        let res = test::call_and_read_body(&svc, req).await;
+        assert_eq!(res, "posted");
+
+        let req = TestRequest::get().uri("/other").to_request();
+        let res = test::call_and_read_body(&svc, req).await;
+        assert_eq!(res, "other");
+    }
+
+    #[actix_rt::test]
+    async fn named_route_redirects() {
+        let svc = test::init_service(
+            App::new()
+                .service(
+                    web::resource("/users/{id}/profile")
+                        .name("user_profile")
+                        .to(HttpResponse::Ok),
+                )
+                .service(
+                    web::scope("/posts/{user}")
+                        .service(web::resource("/{post}").name("post").to(HttpResponse::Ok)),
+                )
+                .service(Redirect::from("/me").to_named_route("user_profile", &[("id", "42")]))
+                .service(
+                    Redirect::from("/latest")
+                        .to_named_route("post", &[("post", "hello-world"), ("user", "bob")])
+                        .temporary(),
+                )
+                .service(Redirect::from("/missing").to_named_route("missing", &[])),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/me").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(
+            hdr.to_str().unwrap(),
+            "http://localhost:8080/users/42/profile"
+        );
+
+        let req = TestRequest::default().uri("/latest").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(
+            hdr.to_str().unwrap(),
+            "http://localhost:8080/posts/bob/hello-world"
+        );
+
+        let req = TestRequest::default().uri("/missing").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().get(&header::LOCATION).is_none());
+    }
+
+    #[actix_rt::test]
+    async fn method_mismatch_falls_through() {
+        let svc = test::init_service(
+            App::new()
+                .service(
+                    Redirect::from("/one")
+                        .to_relative("/two")
+                        .methods([Method::GET, Method::HEAD]),
+                )
+                .route("/one", web::post().to(|| async { "posted" })),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/two");
+
+        let req = TestRequest::post().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(test::read_body(res).await, "posted");
+
+        let req = TestRequest::put().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[actix_rt::test]
+    async fn query_conditions() {
+        let svc = test::init_service(
+            App::new()
+                .service(
+                    Redirect::from("/x")
+                        .when_query("legacy", "1")
+                        .to_relative("/y"),
+                )
+                .route("/x", web::get().to(|| async { "current" })),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/x?a=b&legacy=1").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/y?a=b&legacy=1");
+
+        let req = TestRequest::get().uri("/x?legacy=0").to_request();
+        let res = test::call_and_read_body(&svc, req).await;
+        assert_eq!(res, "current");
+
+        let req = TestRequest::get().uri("/x").to_request();
+        let res = test::call_and_read_body(&svc, req).await;
+        assert_eq!(res, "current");
+    }
+
+    #[actix_rt::test]
+    async fn middleware_query_conditions() {
+        let svc = test::init_service(
+            App::new()
+                .wrap(
+                    Redirect::from("/x")
+                        .when_query("legacy", "1")
+                        .when_query("v", "2")
+                        .to_relative("/y"),
+                )
+                .route("/x", web::get().to(|| async { "current" })),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/x?v=2&legacy=1").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+
+        let req = TestRequest::get().uri("/x?legacy=1").to_request();
+        let res = test::call_and_read_body(&svc, req).await;
+        assert_eq!(res, "current");
+    }
+
+    #[actix_rt::test]
+    async fn guard_conditions() {
+        let redirect = || {
+            Redirect::from("/docs")
+                .guard(guard::All(guard::Host("old.example.com")).and(guard::Get()))
+                .to_absolute("https://docs.example.com/")
+        };
+
+        let svc = test::init_service(
+            App::new()
+                .service(redirect())
+                .route("/docs", web::to(|| async { "docs" })),
+        )
+        .await;
+
+        let mw_svc = test::init_service(
+            App::new()
+                .wrap(redirect())
+                .route("/docs", web::to(|| async { "docs" })),
+        )
+        .await;
+
+        let requests = || {
+            [
+                (
+                    TestRequest::get().insert_header(("host", "old.example.com")),
+                    true,
+                ),
+                (
+                    TestRequest::post().insert_header(("host", "old.example.com")),
+                    false,
+                ),
+                (
+                    TestRequest::get().insert_header(("host", "new.example.com")),
+                    false,
+                ),
+                (
+                    TestRequest::post().insert_header(("host", "new.example.com")),
+                    false,
+                ),
+            ]
+        };
+
+        for (req, redirected) in requests() {
+            let res = svc.call(req.uri("/docs").to_request()).await.unwrap();
+            assert_eq!(res.status().is_redirection(), redirected);
+
+            if !redirected {
+                assert_eq!(test::read_body(res).await, "docs");
+            }
+        }
+
+        for (req, redirected) in requests() {
+            let res = mw_svc.call(req.uri("/docs").to_request()).await.unwrap();
+            assert_eq!(res.status().is_redirection(), redirected);
+
+            if !redirected {
+                assert_eq!(test::read_body(res).await, "docs");
+            }
+        }
+    }
+
+    #[actix_rt::test]
+    async fn method_mismatch_405() {
+        let svc = test::init_service(
+            App::new()
+                .service(
+                    Redirect::from("/one")
+                        .to_relative("/two")
+                        .methods([Method::GET, Method::HEAD])
+                        .method_mismatch_405(),
+                )
+                .route("/one", web::post().to(|| async { "posted" })),
+        )
+        .await;
+
+        let req = TestRequest::get().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+
+        let req = TestRequest::post().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        assert!(res.headers().get(header::LOCATION).is_none());
+        let hdr = res.headers().get(header::ALLOW).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "GET, HEAD");
+
+        // without methods set, every method is redirected
+        let svc = test::init_service(
+            App::new().service(
+                Redirect::from("/one")
+                    .to_relative("/two")
+                    .method_mismatch_405(),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::delete().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+    }
+
+    #[actix_rt::test]
+    async fn permissions_policy() {
+        let svc = test::init_service(
+            App::new().service(
+                Redirect::from("/one")
+                    .to_relative("/two")
+                    .permissions_policy(header::PermissionsPolicy::interest_cohort_opt_out()),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/one").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(header::PERMISSIONS_POLICY).unwrap();
+        assert_eq!(hdr, "interest-cohort=(), browsing-topics=()");
+    }
+
+    #[actix_rt::test]
+    async fn deprecated() {
+        let sunset = SystemTime::UNIX_EPOCH + Duration::from_secs(1_672_531_199);
+
+        let svc = test::init_service(
+            App::new().service(
+                web::scope("/api").service(
+                    Redirect::from("/v1/users")
+                        .to_relative("/v2/users")
+                        .deprecated(sunset),
+                ),
+            ),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/api/v1/users").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+
+        let hdr = |name| res.headers().get(name).unwrap().to_str().unwrap();
+        assert_eq!(hdr(header::LOCATION), "/api/v2/users");
+        assert_eq!(hdr(header::DEPRECATION), "true");
+        assert_eq!(hdr(header::SUNSET), "Sat, 31 Dec 2022 23:59:59 GMT");
+        assert_eq!(
+            hdr(header::LINK),
+            "</api/v2/users>; rel=\"successor-version\""
+        );
+    }
+
+    #[actix_rt::test]
+    async fn echo_body() {
+        let svc = test::init_service(
+            App::new().service(Redirect::from("/one").to_relative("/two").echo_body()),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/one?q=1").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/two?q=1");
+
+        let body = test::read_body(res).await;
+
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            body,
+            "308 Permanent Redirect\nfrom: /one?q=1\nto: /two?q=1\n"
+        );
+
+        #[cfg(not(debug_assertions))]
+        assert!(body.is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn html_body_with_fragment() {
+        let svc = test::init_service(
+            App::new()
+                .service(
+                    Redirect::from("/install/linux")
+                        .to_relative("/docs/install")
+                        .fragment("linux")
+                        .html_body(),
+                )
+                .service(
+                    Redirect::from("/faq")
+                        .to_absolute("https://example.com/help#top")
+                        .fragment("general")
+                        .fragment_from_query("section")
+                        .preserve_query(false)
+                        .html_body(),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/install/linux").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "/docs/install#linux"
+        );
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+        let len = res.headers().get(header::CONTENT_LENGTH).unwrap().clone();
+        let body = test::read_body(res).await;
+        assert_eq!(len, body.len().to_string().as_str());
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(
+            body.contains(r#"<a href="/docs/install#linux">/docs/install#linux</a>"#),
+            "{}",
+            body
+        );
+
+        // configured fragment replaces the target's fragment
+        let req = TestRequest::default().uri("/faq").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "https://example.com/help#general"
+        );
+
+        // query parameter takes precedence, and is encoded and escaped
+        let req = TestRequest::default()
+            .uri("/faq?section=%22a%20%26%20b%22")
+            .to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "https://example.com/help#%22a%20&%20b%22"
+        );
+        let body = test::read_body(res).await;
+        let body = std::str::from_utf8(&body).unwrap();
+        assert!(
+            body.contains(r#"<a href="https://example.com/help#%22a%20&amp;%20b%22">"#),
+            "{}",
+            body
+        );
+
+        // empty values fall back to the configured fragment
+        let req = TestRequest::default().uri("/faq?section=").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "https://example.com/help#general"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn permanent_after_grace_period() {
+        use std::cell::Cell;
+
+        let now = Rc::new(Cell::new(Instant::now()));
+        let clock = {
+            let now = Rc::clone(&now);
+            move || now.get()
+        };
+
+        let redirect = Redirect::from("/old")
+            .to_relative("/new")
+            .permanent_after(Duration::from_secs(60))
+            .clock(clock);
+
+        let svc = test::init_service(App::new().service(redirect.clone())).await;
+        let mw = test::init_service(
+            App::new()
+                .wrap(redirect)
+                .service(web::resource("/").to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let start = now.get();
+
+        for (elapsed, status) in [
+            (0, StatusCode::FOUND),
+            (59, StatusCode::FOUND),
+            (60, StatusCode::MOVED_PERMANENTLY),
+            (3600, StatusCode::MOVED_PERMANENTLY),
+        ] {
+            now.set(start + Duration::from_secs(elapsed));
+
+            let res =
+                test::call_service(&svc, TestRequest::with_uri("/old").to_request()).await;
+            assert_eq!(res.status(), status, "service after {}s", elapsed);
+            assert_eq!(res.headers().get(header::LOCATION).unwrap(), "/new");
+
+            let res = test::call_service(&mw, TestRequest::with_uri("/old").to_request()).await;
+            assert_eq!(res.status(), status, "middleware after {}s", elapsed);
+        }
+
+        // an explicit status code replaces the grace period
+        let svc = test::init_service(
+            App::new().service(
+                Redirect::from("/old")
+                    .permanent_after(Duration::from_secs(60))
+                    .temporary(),
+            ),
+        )
+        .await;
+        let res = test::call_service(&svc, TestRequest::with_uri("/old").to_request()).await;
+        assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+    }
+
+    #[actix_rt::test]
+    async fn https_targets() {
+        let svc = test::init_service(
+            App::new()
+                .service(
+                    Redirect::from("/upgrade")
+                        .to_absolute("HTTP://example.com:8443/docs?v=1")
+                        .require_https_targets(),
+                )
+                .service(
+                    Redirect::from("/secure")
+                        .to_absolute("https://example.com/")
+                        .reject_http_targets(),
+                )
+                .service(
+                    Redirect::from("/reject")
+                        .to_absolute("http://example.com/")
+                        .reject_http_targets(),
+                )
+                .service(
+                    Redirect::from("/local")
+                        .to_absolute("http://user@LocalHost:3000/")
+                        .reject_http_targets()
+                        .allow_http_host("localhost"),
+                )
+                .service(
+                    Redirect::from("/path")
+                        .to_relative("/http://example.com")
+                        .reject_http_targets(),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::default().uri("/upgrade").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "https://example.com:8443/docs?v=1"
+        );
+
+        let req = TestRequest::default().uri("/secure").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "https://example.com/"
+        );
+
+        let req = TestRequest::default().uri("/reject").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().get(header::LOCATION).is_none());
+
+        let req = TestRequest::default().uri("/local").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "http://user@LocalHost:3000/"
+        );
+
+        // paths are not affected
+        let req = TestRequest::default().uri("/path").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "/http://example.com"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn rules_from_config() {
+        let rules: Vec<RedirectRule> = serde_json::from_str(
+            r#"[
+                { "from": "/duck", "to": "https://duckduckgo.com/" },
+                { "from": "/old", "to": "/new", "status": 301, "relative": true },
+                { "from": "/moved", "to": "/elsewhere", "status": 307 }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rules[1],
+            RedirectRule {
+                from: "/old".to_owned(),
+                to: "/new".to_owned(),
+                status: Some(301),
+                relative: true,
+            }
+        );
+
+        let svc = test::init_service(App::new().service(web::scope("/api").configure(|cfg| {
+            register_redirects(rules, cfg).unwrap();
+        })))
+        .await;
+
+        for (path, status, location) in [
+            ("/api/duck", 308, "https://duckduckgo.com/"),
+            ("/api/old", 301, "/api/new"),
+            ("/api/moved", 307, "/elsewhere"),
+        ] {
+            let req = TestRequest::default().uri(path).to_request();
+            let res = svc.call(req).await.unwrap();
+            assert_eq!(res.status().as_u16(), status);
+            let hdr = res.headers().get(header::LOCATION).unwrap();
+            assert_eq!(hdr.to_str().unwrap(), location);
+        }
+    }
+
+    #[test]
+    fn invalid_rules() {
+        fn rule(from: &str, to: &str, status: Option<u16>, relative: bool) -> RedirectRule {
+            RedirectRule {
+                from: from.to_owned(),
+                to: to.to_owned(),
+                status,
+                relative,
+            }
+        }
+
+        let cases = [
+            (
+                rule("old", "/new", None, false),
+                RedirectRuleError::InvalidFrom { index: 1 },
+            ),
+            (
+                rule("/old", "", None, false),
+                RedirectRuleError::InvalidTarget { index: 1 },
+            ),
+            (
+                rule("/old", "https://example.com/", None, true),
+                RedirectRuleError::InvalidTarget { index: 1 },
+            ),
+            (
+                rule("/old", "/new\n", None, false),
+                RedirectRuleError::InvalidTarget { index: 1 },
+            ),
+            (
+                rule("/old", "/new", Some(200), false),
+                RedirectRuleError::InvalidStatus {
+                    index: 1,
+                    status: 200,
+                },
+            ),
+            (
+                rule("/old", "/new", Some(304), false),
+                RedirectRuleError::InvalidStatus {
+                    index: 1,
+                    status: 304,
+                },
+            ),
+            (
+                rule("/old/{id", "/new", None, false),
+                RedirectRuleError::InvalidFrom { index: 1 },
+            ),
+            (
+                rule("/old/{id}", "/new/{name}", None, true),
+                RedirectRuleError::UnresolvedPlaceholder {
+                    index: 1,
+                    name: "name".to_owned(),
+                },
+            ),
+        ];
+
+        for (invalid, err) in cases {
+            let rules = vec![rule("/valid", "/new", None, false), invalid];
+
+            let mut cfg = ServiceConfig::new();
+            assert_eq!(register_redirects(rules, &mut cfg).unwrap_err(), err);
+
+            // the valid rule was not registered either
+            assert!(cfg.services.is_empty());
+        }
+    }
+
+    #[test]
+    fn rule_with_unresolved_placeholder() {
+        let rules: Vec<RedirectRule> =
+            serde_json::from_str(r#"[{ "from": "/a", "to": "/b/{id}" }]"#).unwrap();
+
+        let mut cfg = ServiceConfig::new();
+        assert_eq!(
+            register_redirects(rules, &mut cfg).unwrap_err(),
+            RedirectRuleError::UnresolvedPlaceholder {
+                index: 0,
+                name: "id".to_owned(),
+            }
+        );
+        assert!(cfg.services.is_empty());
+
+        let rules: Vec<RedirectRule> =
+            serde_json::from_str(r#"[{ "from": "/a/{id}", "to": "/b/{id}" }]"#).unwrap();
+        let mut cfg = ServiceConfig::new();
+        register_redirects(rules, &mut cfg).unwrap();
+        assert_eq!(cfg.services.len(), 1);
+    }
+
+    #[actix_rt::test]
+    async fn host_migration() {
+        let svc =
+            test::init_service(
+                App::new()
+                    .service(web::scope("/secure").service(
+                        HostMigrationRedirect::new("new.example.com:443").scheme("https"),
+                    ))
+                    .service(HostMigrationRedirect::new("new.example.com:8080")),
+            )
+            .await;
+
+        let req = TestRequest::default()
+            .uri("/docs/page?lang=en&v=2")
+            .to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(header::LOCATION).unwrap();
+        assert_eq!(
+            hdr.to_str().unwrap(),
+            "http://new.example.com:8080/docs/page?lang=en&v=2"
+        );
+
+        // default port for the scheme is left out; scope prefix is kept
+        let req = TestRequest::default()
+            .uri("/secure/login?next=/")
+            .to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let hdr = res.headers().get(header::LOCATION).unwrap();
+        assert_eq!(
+            hdr.to_str().unwrap(),
+            "https://new.example.com/secure/login?next=/"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn empty_bodies_across_helpers() {
+        let svc = test::init_service(
+            App::new()
+                .service(
+                    Redirect::from("/old")
+                        .to_relative("/new")
+                        .permissions_policy(
+                            header::PermissionsPolicy::interest_cohort_opt_out(),
+                        ),
+                )
+                .service(Redirect::from("/echo").to_relative("/new").echo_body())
+                .service(
+                    web::scope("/moved").service(HostMigrationRedirect::new("example.org")),
+                ),
+        )
+        .await;
+
+        for path in ["/old", "/moved/page"] {
+            let req = TestRequest::with_uri(path).to_request();
+            let res = svc.call(req).await.unwrap();
+            assert!(res.status().is_redirection());
+            assert!(res.headers().contains_key(header::LOCATION));
+
+            let len = res.headers().get(header::CONTENT_LENGTH).unwrap();
+            assert_eq!(len, "0");
+            assert!(test::read_body(res).await.is_empty());
+        }
+
+        // echoed bodies are framed by their actual length
+        #[cfg(debug_assertions)]
+        {
+            let req = TestRequest::with_uri("/echo").to_request();
+            let res = svc.call(req).await.unwrap();
+            assert!(res.headers().get(header::CONTENT_LENGTH).is_none());
+            assert!(!test::read_body(res).await.is_empty());
+        }
+    }
+
+    #[actix_rt::test]
+    async fn valid_targets_pass() {
+        let app = App::new()
+            .service(
+                web::resource("/users/{id}")
+                    .name("user")
+                    .to(HttpResponse::Ok),
+            )
+            .service(Redirect::from("/me").to_named_route("user", &[("id", "1")]))
+            .service(
+                web::scope("/docs")
+                    .service(web::resource("/latest").to(HttpResponse::Ok))
+                    .service(web::resource("/v1/intro").to(HttpResponse::Ok))
+                    .service(
+                        Redirect::from("/old")
+                            .to_relative("/latest?from=old")
+                            .validate_target(true),
+                    )
+                    .service(Redirect::from("/v1").append("/intro").validate_target(true)),
+            )
+            .service(
+                Redirect::from("/u5")
+                    .to_absolute("/users/5")
+                    .validate_target(true),
+            )
+            .service(
+                Redirect::from("/me2")
+                    .to_named_route("user", &[])
+                    .validate_target(true),
+            )
+            .service(
+                Redirect::from("/ext")
+                    .to_absolute("https://example.com/nowhere")
+                    .validate_target(true),
+            );
+
+        let svc = test::init_service(app).await;
+
+        let req = TestRequest::default().uri("/docs/old").to_request();
+        let res = svc.call(req).await.unwrap();
+        let hdr = res.headers().get(&header::LOCATION).unwrap();
+        assert_eq!(hdr.to_str().unwrap(), "/docs/latest?from=old");
+    }
+
+    #[actix_rt::test]
+    async fn dangling_target_warns() {
+        let mut rmap = ResourceMap::new(ResourceDef::prefix(""));
+        rmap.add(&mut ResourceDef::new("/new"), None);
+
+        let check = Redirect::from("/old")
+            .to_relative("/nwe")
+            .target_check("", false)
+            .unwrap();
+        assert!(!check.resolves(&rmap));
+        assert_eq!(
+            check.to_string(),
+            "redirect from `/old` to `/nwe` does not match any route"
+        );
+
+        let check = Redirect::from("/me")
+            .to_named_route("user", &[])
+            .target_check("/api", false)
+            .unwrap();
+        assert!(!check.resolves(&rmap));
+        assert_eq!(
+            check.to_string(),
+            "redirect from `/api/me` targets route `user`, which does not exist"
+        );
+
+        // non-strict validation only logs a warning
+        let app = App::new()
+            .route("/new", web::get().to(HttpResponse::Ok))
+            .service(
+                Redirect::from("/old")
+                    .to_relative("/nwe")
+                    .validate_target(false),
+            );
+
+        let svc = test::init_service(app).await;
+        let req = TestRequest::default().uri("/old").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+    }
+
+    #[actix_rt::test]
+    #[should_panic(
+        expected = "redirect from `/api/old` to `/api/nwe` does not match any route"
+    )]
+    async fn dangling_target_in_scope_panics() {
+        let app = App::new().service(
+            web::scope("/api")
+                .route("/new", web::get().to(HttpResponse::Ok))
+                .service(
+                    Redirect::from("/old")
+                        .to_relative("/nwe")
+                        .validate_target(true),
+                ),
+        );
+
+        test::init_service(app).await;
+    }
+
+    #[test]
+    fn host_migration_default_ports() {
+        let req = TestRequest::default().uri("/").to_http_request();
+
+        for (host, scheme, location) in [
+            ("new.example.com:80", "http", "http://new.example.com/"),
+            ("new.example.com:443", "http", "http://new.example.com:443/"),
+            ("new.example.com:80", "https", "https://new.example.com:80/"),
+            ("[::1]:443", "https", "https://[::1]/"),
+            (
+                "new.example.com:4430",
+                "https",
+                "https://new.example.com:4430/",
+            ),
+        ] {
+            let redirect = HostMigrationRedirect::new(host).scheme(scheme);
+            assert_eq!(redirect.location(&req), location);
+        }
+
+        // scheme of the request is kept by default
+        let redirect = HostMigrationRedirect::new("new.example.com:80");
+        assert_eq!(redirect.location(&req), "http://new.example.com/");
+    }
+
+    #[actix_rt::test]
+    async fn canonicalize_in_one_redirect() {
+        let srv = test::init_service(
+            App::new()
+                .wrap(Canonicalize::new())
+                .route("/about", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let req = TestRequest::default()
+            .uri("http://www.Example.com/About/")
+            .to_request();
+        let res = srv.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "https://example.com/about"
+        );
+
+        // following the redirect does not redirect again
+        let req = TestRequest::default()
+            .uri("https://example.com/about")
+            .to_request();
+        let res = srv.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn canonicalize_toggles() {
+        let location = |canonicalize: Canonicalize, uri: &str| {
+            canonicalize.location(&TestRequest::default().uri(uri).to_http_request())
+        };
+
+        let uri = "http://www.Example.com:8080/About/%C3%A9//?Q=A";
+        assert_eq!(
+            location(Canonicalize::new(), uri).unwrap(),
+            "https://example.com/about/%C3%A9?Q=A"
+        );
+        assert_eq!(
+            location(Canonicalize::new().strip_www(false), uri).unwrap(),
+            "https://www.example.com/about/%C3%A9?Q=A"
+        );
+        assert_eq!(
+            location(Canonicalize::new().https(false), uri).unwrap(),
+            "http://example.com:8080/about/%C3%A9?Q=A"
+        );
+        assert_eq!(
+            location(Canonicalize::new().trim_trailing_slash(false), uri).unwrap(),
+            "https://example.com/about/%C3%A9//?Q=A"
+        );
+        assert_eq!(
+            location(Canonicalize::new().lowercase(false), uri).unwrap(),
+            "https://Example.com/About/%C3%A9?Q=A"
+        );
+
+        // canonical URLs are not redirected
+        for uri in ["https://example.com/", "https://example.com/about?Q=A"] {
+            assert_eq!(location(Canonicalize::new(), uri), None);
+        }
+
+        let disabled = Canonicalize::new()
+            .strip_www(false)
+            .https(false)
+            .trim_trailing_slash(false)
+            .lowercase(false);
+        assert_eq!(location(disabled, uri), None);
+
+        assert_eq!(
+            location(Canonicalize::new(), "https://example.com//").unwrap(),
+            "https://example.com/"
+        );
+    }
+
+    #[actix_rt::test]
+    async fn redirect_loop_ends_at_max_count() {
+        let svc = test::init_service(
+            App::new()
+                .service(Redirect::from("/a").to_relative("/b").max_redirects(3))
+                .service(Redirect::from("/b").to_relative("/c").max_redirects(3))
+                .service(Redirect::from("/c").to_relative("/a").max_redirects(3)),
+        )
+        .await;
+
+        // follow the chain like a client that copies the count onto the next request
+        let mut path = "/a".to_owned();
+        let mut count = None;
+        let mut redirects = 0;
+
+        let res = loop {
+            let mut req = TestRequest::with_uri(&path);
+            if let Some(count) = count.take() {
+                req = req.insert_header((REDIRECT_COUNT, count));
+            }
+
+            let res = svc.call(req.to_request()).await.unwrap();
+            if !res.status().is_redirection() {
+                break res;
+            }
+
+            redirects += 1;
+            assert!(redirects <= 3, "redirect chain did not end");
+
+            let hdr = res.headers().get(&REDIRECT_COUNT).unwrap();
+            assert_eq!(hdr.to_str().unwrap(), redirects.to_string());
+            count = Some(hdr.clone());
+
+            let location = res.headers().get(header::LOCATION).unwrap();
+            path = location.to_str().unwrap().to_owned();
+        };
+
+        assert_eq!(redirects, 3);
+        assert_eq!(path, "/a");
+        assert_eq!(res.status(), StatusCode::LOOP_DETECTED);
+        assert!(res.headers().get(header::LOCATION).is_none());
+
+        // malformed counts start a new chain
+        let req = TestRequest::with_uri("/a")
+            .insert_header((REDIRECT_COUNT, "many"))
+            .to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(res.headers().get(&REDIRECT_COUNT).unwrap(), "1");
+
+        // without loop protection, the header is neither read nor sent
+        let svc =
+            test::init_service(App::new().service(Redirect::from("/a").to_relative("/b")))
+                .await;
+        let req = TestRequest::with_uri("/a")
+            .insert_header((REDIRECT_COUNT, "10"))
+            .to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert!(res.headers().get(&REDIRECT_COUNT).is_none());
+    }
+
+    #[actix_rt::test]
+    async fn responder_redirects() {
+        let svc = test::init_service(
+            App::new()
+                .route(
+                    "/items",
+                    web::post().to(|| async {
+                        let id = 42;
+                        Redirect::to(format!("/items/{}", id)).see_other()
+                    }),
+                )
+                .route(
+                    "/login",
+                    web::get().to(|| async { Redirect::to("/login/{form}").temporary() }),
+                )
+                .route(
+                    "/moved",
+                    web::get().to(|| async {
+                        Redirect::to("https://example.com/a\r\nb")
+                            .using_status_code(StatusCode::FOUND)
+                    }),
+                )
+                .route(
+                    "/search",
+                    web::get().to(|| async { Redirect::to("/find").preserve_query(true) }),
+                ),
+        )
+        .await;
+
+        for (req, status, location) in [
+            (
+                TestRequest::post().uri("/items?draft=1"),
+                StatusCode::SEE_OTHER,
+                "/items/42",
+            ),
+            (
+                TestRequest::get().uri("/login"),
+                StatusCode::TEMPORARY_REDIRECT,
+                "/login/%7Bform%7D",
+            ),
+            (
+                TestRequest::get().uri("/moved"),
+                StatusCode::FOUND,
+                "https://example.com/a%0D%0Ab",
+            ),
+            (
+                TestRequest::get().uri("/search?q=rust"),
+                StatusCode::PERMANENT_REDIRECT,
+                "/find?q=rust",
+            ),
+        ] {
+            let res = svc.call(req.to_request()).await.unwrap();
+            assert_eq!(res.status(), status);
+            assert_eq!(res.headers().get(header::LOCATION).unwrap(), location);
+        }
+    }
+
+    #[actix_rt::test]
+    async fn responder_permanent_after_grace_period() {
+        use std::cell::Cell;
+
+        let start = Instant::now();
+        let now = Rc::new(Cell::new(start));
+
+        let srv = {
+            let now = Rc::clone(&now);
+
+            test::init_service(App::new().route(
+                "/old",
+                web::get().to(move || {
+                    let now = Rc::clone(&now);
+
+                    async move {
+                        Redirect::to("/new")
+                            .permanent_after(Duration::from_secs(60))
+                            .clock(move || now.get())
+                    }
+                }),
+            ))
+            .await
+        };
+
+        for (elapsed, status) in [
+            (0, StatusCode::FOUND),
+            (0, StatusCode::FOUND),
+            (59, StatusCode::FOUND),
+            (61, StatusCode::MOVED_PERMANENTLY),
+            (61, StatusCode::MOVED_PERMANENTLY),
+            (3600, StatusCode::MOVED_PERMANENTLY),
+        ] {
+            now.set(start + Duration::from_secs(elapsed));
+
+            let res =
+                test::call_service(&srv, TestRequest::with_uri("/old").to_request()).await;
+            assert_eq!(res.status(), status, "after {}s", elapsed);
+            assert_eq!(res.headers().get(header::LOCATION).unwrap(), "/new");
+        }
+    }
+
+    #[actix_rt::test]
+    #[should_panic = "`Redirect::to` can only be returned from handlers"]
+    async fn responder_redirects_can_not_be_registered() {
+        test::init_service(App::new().service(Redirect::to("/new"))).await;
+    }
+
+    #[actix_rt::test]
+    async fn placeholder_targets() {
+        let svc = test::init_service(
+            App::new()
+                .wrap(Redirect::from("/legacy/{page}").to_relative("/pages/{page}"))
+                .wrap(Redirect::from("/broken/{id}").to_relative("/new/{user}"))
+                .service(Redirect::from("/users/{id}/old").to_relative("/users/{id}/new"))
+                .service(
+                    Redirect::from("/repos/{owner}/{repo}/wiki")
+                        .to_absolute("https://wiki.example.com/{owner}/{repo}"),
+                )
+                .service(Redirect::from("/v1/{tail:.*}").to_relative("/v2/{tail}"))
+                .service(Redirect::from("/files/{name}").append("{name}.txt"))
+                .service(
+                    web::scope("/orgs/{org}")
+                        .service(Redirect::from("/teams/{team}").to_relative("/{org}/{team}")),
+                ),
+        )
+        .await;
+
+        for (uri, location) in [
+            ("/users/42/old", "/users/42/new"),
+            ("/users/42/old?page=2", "/users/42/new?page=2"),
+            (
+                "/repos/actix/actix-web/wiki",
+                "https://wiki.example.com/actix/actix-web",
+            ),
+            ("/v1/a/b/c", "/v2/a/b/c"),
+            ("/v1/a%20b/c?x=1", "/v2/a%20b/c?x=1"),
+            ("/files/notes", "/files/notes/notes.txt"),
+            ("/orgs/rust/teams/core", "/orgs/rust/rust/core"),
+            ("/legacy/about?lang=en", "/pages/about?lang=en"),
+        ] {
+            let req = TestRequest::with_uri(uri).to_request();
+            let res = svc.call(req).await.unwrap();
+            assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT, "{}", uri);
+            assert_eq!(
+                res.headers().get(header::LOCATION).unwrap(),
+                location,
+                "{}",
+                uri
+            );
+        }
+
+        // placeholders must name a segment of the request path
+        let req = TestRequest::with_uri("/broken/1").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert!(res.headers().get(header::LOCATION).is_none());
+        assert_eq!(
+            test::read_body(res).await,
+            "Redirect target placeholder `{user}` does not match a path segment"
+        );
+    }
+
+    #[actix_rt::test]
+    #[should_panic = "redirect from `/orgs/{org}/users/{id}` to `/{org}/{user}` uses placeholder \
+        `{user}`, which is not a dynamic segment of the path"]
+    async fn unresolved_placeholders_panic_on_registration() {
+        test::init_service(
+            App::new().service(
+                web::scope("/orgs/{org}")
+                    .service(Redirect::from("/users/{id}").to_relative("/{org}/{user}")),
+            ),
+        )
+        .await;
+    }
+
+    #[test]
+    fn pattern_segment_names() {
+        assert_eq!(segment_names("/users/{id}/posts/{post}"), ["id", "post"]);
+        assert_eq!(segment_names("/v1/{tail:.*}"), ["tail"]);
+        assert_eq!(segment_names("/files/{tail}*"), ["tail"]);
+        assert_eq!(segment_names(r"/{year:\d{4}}/{slug}"), ["year", "slug"]);
+        assert!(segment_names("/static").is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn preserved_query() {
+        let svc = test::init_service(
+            App::new()
+                .service(Redirect::from("/old").to_relative("/new"))
+                .service(Redirect::from("/lang").to_relative("/new?lang=en"))
+                .service(Redirect::from("/docs").to_absolute("https://example.com/docs#intro"))
+                .service(
+                    Redirect::from("/dropped")
+                        .to_relative("/new")
+                        .preserve_query(false),
+                ),
+        )
+        .await;
+
+        for (uri, location) in [
+            ("/old?x=1", "/new?x=1"),
+            ("/old?ref=email&page=2", "/new?ref=email&page=2"),
+            ("/old?", "/new"),
+            ("/old", "/new"),
+            ("/lang?x=1", "/new?lang=en&x=1"),
+            ("/lang", "/new?lang=en"),
+            ("/docs?x=1", "https://example.com/docs?x=1#intro"),
+            ("/dropped?x=1", "/new"),
+        ] {
+            let req = TestRequest::with_uri(uri).to_request();
+            let res = svc.call(req).await.unwrap();
+            assert_eq!(
+                res.headers().get(header::LOCATION).unwrap(),
+                location,
+                "{}",
+                uri
+            );
+        }
+    }
+
+    #[actix_rt::test]
+    async fn composed_target_transforms() {
+        fn strip_tracking(target: &mut TargetParts) {
+            let query = target.query.as_deref().unwrap_or_default();
+            let kept = query
+                .split('&')
+                .filter(|param| !param.is_empty() && !param.starts_with("utm_"))
+                .collect::<Vec<_>>();
+
+            target.query = (!kept.is_empty()).then(|| kept.join("&"));
+        }
+
+        fn add_campaign(target: &mut TargetParts) {
+            let query = target.query.get_or_insert_with(String::new);
+
+            if !query.is_empty() {
+                query.push('&');
+            }
+
+            query.push_str("utm_campaign=moved");
+        }
+
+        let svc = test::init_service(
+            App::new()
+                .service(
+                    Redirect::from("/shop")
+                        .to_absolute("https://shop.example.com/Sale?utm_source=ad&id=7")
+                        .fragment("top")
+                        .transform_target(strip_tracking)
+                        .transform_target(add_campaign),
+                )
+                .service(
+                    Redirect::from("/plain")
+                        .to_relative("/Landing?utm_medium=mail")
+                        .transform_target(strip_tracking)
+                        .transform_target(|target| target.path.make_ascii_lowercase()),
+                )
+                .service(
+                    Redirect::from("/reversed")
+                        .to_relative("/landing?utm_medium=mail")
+                        .transform_target(add_campaign)
+                        .transform_target(strip_tracking),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/shop").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "https://shop.example.com/Sale?id=7&utm_campaign=moved#top"
+        );
+
+        let req = TestRequest::with_uri("/plain").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.headers().get(header::LOCATION).unwrap(), "/landing");
+
+        // transformers run in the order they are added
+        let req = TestRequest::with_uri("/reversed").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.headers().get(header::LOCATION).unwrap(), "/landing");
+    }
+
+    #[cfg(feature = "signed-urls")]
+    #[actix_rt::test]
+    async fn signed_expiry_targets() {
+        use crate::error::SignedUrlError;
+
+        let signer = UrlSigner::new("secret");
+
+        let svc = test::init_service(
+            App::new()
+                .service(
+                    Redirect::from("/report")
+                        .to_absolute("https://files.example.com/report.pdf?v=2")
+                        .fragment("summary")
+                        .signed_expiry(signer.clone(), Duration::from_secs(300)),
+                )
+                .service(
+                    Redirect::from("/stale")
+                        .to_relative("/files/a")
+                        .signed_expiry(signer.clone(), Duration::ZERO),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/report").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        let location = res
+            .headers()
+            .get(header::LOCATION)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(
+            location.starts_with("https://files.example.com/report.pdf?v=2&expires="),
+            "{}",
+            location
+        );
+        assert!(location.contains("&signature="), "{}", location);
+        assert!(location.ends_with("#summary"), "{}", location);
+
+        // the destination accepts the fresh link
+        let path = location
+            .trim_start_matches("https://files.example.com")
+            .split('#')
+            .next()
+            .unwrap();
+        let req = TestRequest::with_uri(path).to_http_request();
+        assert_eq!(signer.verify(&req), Ok(()));
+
+        // and rejects expired ones
+        let req = TestRequest::with_uri("/stale").to_request();
+        let res = svc.call(req).await.unwrap();
+        let location = res
+            .headers()
+            .get(header::LOCATION)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert!(location.starts_with("/files/a?expires="), "{}", location);
+        let req = TestRequest::with_uri(location).to_http_request();
+        assert_eq!(signer.verify(&req), Err(SignedUrlError::Expired));
+    }
+
+    #[actix_rt::test]
+    async fn redirect_from_marked_status() {
+        let intent = StatusCode::from_u16(399).unwrap();
+        let marker = header::HeaderName::from_static("x-redirect-to");
+
+        let svc = test::init_service(
+            App::new()
+                .wrap(
+                    RedirectFromStatus::new(marker.clone())
+                        .status(intent)
+                        .status(StatusCode::ACCEPTED),
+                )
+                .route(
+                    "/marked",
+                    web::get().to(move || {
+                        HttpResponse::build(intent)
+                            .insert_header(("x-redirect-to", "/orders/42 new"))
+                            .body("discarded")
+                    }),
+                )
+                .route(
+                    "/accepted",
+                    web::get().to(|| {
+                        HttpResponse::Accepted()
+                            .insert_header(("x-redirect-to", "/queue"))
+                            .finish()
+                    }),
+                )
+                .route(
+                    "/unmarked",
+                    web::get().to(move || HttpResponse::build(intent).body("as is")),
+                )
+                .route(
+                    "/other-status",
+                    web::get().to(|| {
+                        HttpResponse::Ok()
+                            .insert_header(("x-redirect-to", "/elsewhere"))
+                            .body("ok")
+                    }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/marked").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "/orders/42%20new"
+        );
+        assert!(!res.headers().contains_key(&marker));
+        assert!(test::read_body(res).await.is_empty());
+
+        let req = TestRequest::with_uri("/accepted").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::TEMPORARY_REDIRECT);
+        assert_eq!(res.headers().get(header::LOCATION).unwrap(), "/queue");
+
+        let req = TestRequest::with_uri("/unmarked").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), intent);
+        assert!(!res.headers().contains_key(header::LOCATION));
+        assert_eq!(test::read_body(res).await, "as is");
+
+        let req = TestRequest::with_uri("/other-status").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(&marker).unwrap(), "/elsewhere");
+        assert_eq!(test::read_body(res).await, "ok");
+
+        // custom redirect status
+        let svc = test::init_service(
+            App::new()
+                .wrap(
+                    RedirectFromStatus::new(marker)
+                        .status(intent)
+                        .using_status_code(StatusCode::SEE_OTHER),
+                )
+                .route(
+                    "/marked",
+                    web::post().to(move || {
+                        HttpResponse::build(intent)
+                            .insert_header(("x-redirect-to", "https://example.com/done"))
+                            .finish()
+                    }),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::post().uri("/marked").to_request();
+        let res = svc.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SEE_OTHER);
+        assert_eq!(
+            res.headers().get(header::LOCATION).unwrap(),
+            "https://example.com/done"
+        );
+    }
+}
@@ -1,11 +1,15 @@
 use std::{
-    cell::{Ref, RefCell, RefMut},
-    fmt, net,
-    rc::Rc,
+    borrow::Cow,
+    cell::{Cell, Ref, RefCell, RefMut},
+    collections::HashMap,
+    fmt,
+    hash::{BuildHasher, Hash},
+    mem, net,
+    rc::{Rc, Weak},
     str,
 };
 
-use actix_http::{Message, RequestHead};
+use actix_http::{Message, RequestHead, ResponseRoute};
 use actix_router::{Path, Url};
 use actix_utils::future::{ok, Ready};
 #[cfg(feature = "cookies")]
@@ -14,10 +18,13 @@ use smallvec::SmallVec;
 
 use crate::{
     app_service::AppInitServiceState,
-    config::AppConfig,
-    dev::{Extensions, Payload},
-    error::UrlGenerationError,
-    http::{header::HeaderMap, Method, Uri, Version},
+    config::{AppConfig, InvalidUtf8Policy},
+    dev::{DisconnectSignal, Disconnected, Extensions, Payload},
+    error::{ParseError, UrlGenerationError},
+    http::{
+        header::{AsHeaderName, Header, HeaderMap, HeaderValue},
+        Method, Uri, Version,
+    },
     info::ConnectionInfo,
     rmap::ResourceMap,
     Error, FromRequest, HttpMessage,
@@ -33,6 +40,7 @@ pub struct HttpRequest {
     /// `Rc<HttpRequestInner>` is used exclusively and NO `Weak<HttpRequestInner>`
     /// is allowed anywhere in the code. Weak pointer is purposely ignored when
     /// doing `Rc`'s ref counter check. Expect panics if this invariant is violated.
+    /// Use [`WeakHttpRequest`] to refer to a request without keeping it alive.
     pub(crate) inner: Rc<HttpRequestInner>,
 }
 
@@ -42,6 +50,10 @@ pub(crate) struct HttpRequestInner {
     pub(crate) app_data: SmallVec<[Rc<Extensions>; 4]>,
     pub(crate) conn_data: Option<Rc<Extensions>>,
     pub(crate) extensions: Rc<RefCell<Extensions>>,
+    /// Request URI as received, saved by the first internal rewrite of the URI.
+    pub(crate) original_uri: Option<Uri>,
+    /// Cleared when strong clones outlive the response, so this allocation is not pooled.
+    pub(crate) reusable: Cell<bool>,
     app_state: Rc<AppInitServiceState>,
 }
 
@@ -66,6 +78,8 @@ impl HttpRequest {
                 app_data: data,
                 conn_data,
                 extensions,
+                original_uri: None,
+                reusable: Cell::new(true),
             }),
         }
     }
@@ -91,6 +105,28 @@ impl HttpRequest {
         &self.head().uri
     }
 
+    /// Returns the request URI as it was received, before any internal rewrites.
+    ///
+    /// Middleware such as [`NormalizePath`](crate::middleware::NormalizePath) may rewrite the URI
+    /// returned by [`uri`](Self::uri). The original request target, including its query string, is
+    /// kept byte-for-byte, making it suitable for verifying signed URLs.
+    #[inline]
+    pub fn original_uri(&self) -> &Uri {
+        self.inner.original_uri.as_ref().unwrap_or(&self.head().uri)
+    }
+
+    /// Replaces the request URI and updates the path used for routing, keeping the original URI.
+    ///
+    /// # Panics
+    /// Panics if this `HttpRequest` has been cloned.
+    pub(crate) fn rewrite_uri(&mut self, uri: Uri) {
+        let inner = Rc::get_mut(&mut self.inner).unwrap();
+
+        inner.path.get_mut().update(&uri);
+        let original = mem::replace(&mut inner.head.uri, uri);
+        inner.original_uri.get_or_insert(original);
+    }
+
     /// Read the Request method.
     #[inline]
     pub fn method(&self) -> &Method {
@@ -109,6 +145,51 @@ impl HttpRequest {
         &self.head().headers
     }
 
+    /// Returns the first value of the header `name` as a string.
+    ///
+    /// Any valid UTF-8 value is returned as is. Values that are not valid UTF-8 are converted
+    /// lossily when the app's [`InvalidUtf8Policy`] is `Lossy`; under the other policies, `None`
+    /// is returned for them, and their raw bytes remain available through [`headers`](Self::headers).
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_web::HttpRequest;
+    /// fn user_agent(req: &HttpRequest) -> String {
+    ///     req.header_str("user-agent").unwrap_or_default().into_owned()
+    /// }
+    /// ```
+    pub fn header_str(&self, name: impl AsHeaderName) -> Option<Cow<'_, str>> {
+        let val = self.headers().get(name)?;
+        header_value_str(val, self.app_config().invalid_utf8_policy())
+    }
+
+    /// Parses the typed header `H`, applying the app's [`InvalidUtf8Policy`].
+    ///
+    /// Unlike [`Header::parse`], values that are not valid UTF-8 are parsed from their lossy
+    /// conversion when the policy is `Lossy`. The request's headers are left untouched.
+    pub(crate) fn parse_header<H: Header>(&self) -> Result<H, ParseError> {
+        let policy = self.app_config().invalid_utf8_policy();
+
+        let invalid_utf8 = self
+            .headers()
+            .get_all(H::name())
+            .any(|val| str::from_utf8(val.as_bytes()).is_err());
+
+        if policy != InvalidUtf8Policy::Lossy || !invalid_utf8 {
+            return H::parse(self);
+        }
+
+        let mut headers = HeaderMap::new();
+
+        for val in self.headers().get_all(H::name()) {
+            let lossy = header_value_str(val, policy).unwrap_or_default();
+            let val = HeaderValue::from_str(&lossy).unwrap_or_else(|_| val.clone());
+            headers.append(H::name(), val);
+        }
+
+        H::parse(&LossyHeaders { req: self, headers })
+    }
+
     /// The target path of this request.
     #[inline]
     pub fn path(&self) -> &str {
@@ -181,15 +262,44 @@ impl HttpRequest {
             .and_then(|container| container.get::<T>())
     }
 
+    /// Returns a future that resolves when the client disconnects.
+    ///
+    /// Long-running handlers can race their work against this future to stop early once nobody is
+    /// waiting for the response. Requests that were not received from a connection, such as those
+    /// built with [`TestRequest`](crate::test::TestRequest), never resolve it. Also see
+    /// [`ConnectionWatcher`](crate::web::ConnectionWatcher).
+    ///
+    /// # Examples
+    /// ```
+    /// # use actix_web::{HttpRequest, HttpResponse};
+    /// # async fn expensive_work() -> String { String::new() }
+    /// async fn report(req: HttpRequest) -> HttpResponse {
+    ///     tokio::select! {
+    ///         report = expensive_work() => HttpResponse::Ok().body(report),
+    ///         _ = req.on_disconnect() => HttpResponse::NoContent().finish(),
+    ///     }
+    /// }
+    /// ```
+    pub fn on_disconnect(&self) -> Disconnected {
+        self.extensions()
+            .get::<DisconnectSignal>()
+            .cloned()
+            .unwrap_or_default()
+            .disconnected()
+    }
+
     /// Generates URL for a named resource.
     ///
     /// This substitutes in sequence all URL parameters that appear in the resource itself and in
     /// parent [scopes](crate::web::scope), if any.
     ///
-    /// It is worth noting that the characters `['/', '%']` are not escaped and therefore a single
+    /// It is worth noting that `/` and valid `%XX` sequences are not escaped and therefore a single
     /// URL parameter may expand into multiple path segments and `elements` can be percent-encoded
     /// beforehand without worrying about double encoding. Any other character that is not valid in
-    /// a URL path context is escaped using percent-encoding.
+    /// a URL path context is escaped using percent-encoding, as by a
+    /// [`PathEncoder`](crate::http::uri::PathEncoder) for [`EncodeSet::Path`] in raw mode.
+    ///
+    /// [`EncodeSet::Path`]: crate::http::uri::EncodeSet::Path
     ///
     /// # Examples
     /// ```
@@ -213,6 +323,42 @@ impl HttpRequest {
         self.resource_map().url_for(self, name, elements)
     }
 
+    /// Generates URL for a named resource, looking up URL parameters by name.
+    ///
+    /// This method is similar to [`url_for`](Self::url_for) but dynamic segments are filled in
+    /// from `elements` using their identifiers instead of their position.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::collections::HashMap;
+    /// # use actix_web::{web, App, HttpRequest, HttpResponse};
+    /// fn index(req: HttpRequest) -> HttpResponse {
+    ///     let mut params = HashMap::new();
+    ///     params.insert("id", "42");
+    ///
+    ///     let url = req.url_for_map("user", &params); // <- generate URL for "user" resource
+    ///     HttpResponse::Ok().into()
+    /// }
+    ///
+    /// let app = App::new()
+    ///     .service(web::resource("/user/{id}")
+    ///          .name("user")
+    ///          .route(web::get().to(|| HttpResponse::Ok()))
+    ///     );
+    /// ```
+    pub fn url_for_map<K, V, S>(
+        &self,
+        name: &str,
+        elements: &HashMap<K, V, S>,
+    ) -> Result<url::Url, UrlGenerationError>
+    where
+        K: std::borrow::Borrow<str> + Eq + Hash,
+        V: AsRef<str>,
+        S: BuildHasher,
+    {
+        self.resource_map().url_for_map(self, name, elements)
+    }
+
     /// Generate URL for named resource
     ///
     /// This method is similar to `HttpRequest::url_for()` but it can be used
@@ -249,15 +395,21 @@ impl HttpRequest {
     /// The return type, [`ConnectionInfo`], can also be used as an extractor.
     ///
     /// # Panics
-    /// Panics if request's extensions container is already borrowed.
+    /// Panics if request's extensions container is already mutably borrowed, or if it is borrowed
+    /// elsewhere when connection info is first requested.
     #[inline]
     pub fn connection_info(&self) -> Ref<'_, ConnectionInfo> {
-        if !self.extensions().contains::<ConnectionInfo>() {
-            let info = ConnectionInfo::new(self.head(), &*self.app_config());
-            self.extensions_mut().insert(info);
+        // a failed mutable borrow means a `Ref` returned from a previous call (or another
+        // extensions borrow) is still alive; in the former case the info is already cached
+        if let Ok(mut extensions) = self.inner.extensions.try_borrow_mut() {
+            extensions
+                .get_or_insert_with(|| ConnectionInfo::new(self.head(), self.app_config()));
         }
 
-        Ref::map(self.extensions(), |data| data.get().unwrap())
+        Ref::map(self.extensions(), |data| {
+            data.get()
+                .expect("connection info could not be cached while extensions were borrowed")
+        })
     }
 
     /// App config
@@ -304,8 +456,38 @@ impl HttpRequest {
         None
     }
 
+    /// Returns a handle to this request that can be held beyond the response.
+    ///
+    /// See [`WeakHttpRequest`] for the data that remains available.
+    pub fn downgrade(&self) -> WeakHttpRequest {
+        WeakHttpRequest {
+            method: self.method().clone(),
+            uri: self.uri().clone(),
+            version: self.version(),
+            headers: self.headers().clone(),
+            match_pattern: self.match_pattern(),
+            app_data: self.inner.app_data.iter().map(Rc::downgrade).collect(),
+            extensions: Rc::downgrade(&self.inner.extensions),
+        }
+    }
+
+    /// Returns a description of the route that matched the request, for naming it in server error
+    /// logs. The route pattern is only resolved when the description is used.
+    pub(crate) fn response_route(&self) -> ResponseRoute {
+        let rmap = self.app_state().shared_rmap();
+        let uri = self.uri().clone();
+        ResponseRoute::new(move || rmap.match_pattern(uri.path()))
+    }
+
+    /// Marks the request as not reusable if clones of it outlive the response being sent.
+    pub(crate) fn mark_responded(&self) {
+        if Rc::strong_count(&self.inner) > 1 {
+            self.inner.reusable.set(false);
+        }
+    }
+
     #[inline]
-    fn app_state(&self) -> &AppInitServiceState {
+    pub(crate) fn app_state(&self) -> &AppInitServiceState {
         &*self.inner.app_state
     }
 
@@ -370,12 +552,55 @@ impl HttpMessage for HttpRequest {
     }
 }
 
+/// Converts a header value to a string according to `policy`.
+fn header_value_str(val: &HeaderValue, policy: InvalidUtf8Policy) -> Option<Cow<'_, str>> {
+    match str::from_utf8(val.as_bytes()) {
+        Ok(val) => Some(Cow::Borrowed(val)),
+        Err(_) if policy == InvalidUtf8Policy::Lossy => {
+            Some(String::from_utf8_lossy(val.as_bytes()))
+        }
+        Err(_) => None,
+    }
+}
+
+/// A request's message with lossily converted copies of some of its header values.
+struct LossyHeaders<'a> {
+    req: &'a HttpRequest,
+    headers: HeaderMap,
+}
+
+impl HttpMessage for LossyHeaders<'_> {
+    type Stream = ();
+
+    fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    fn extensions(&self) -> Ref<'_, Extensions> {
+        self.req.extensions()
+    }
+
+    fn extensions_mut(&self) -> RefMut<'_, Extensions> {
+        self.req.extensions_mut()
+    }
+
+    fn take_payload(&mut self) -> Payload<Self::Stream> {
+        Payload::None
+    }
+}
+
 impl Drop for HttpRequest {
     fn drop(&mut self) {
         // if possible, contribute to current worker's HttpRequest allocation pool
 
         // This relies on no weak references to inner existing anywhere within the codebase.
         if let Some(inner) = Rc::get_mut(&mut self.inner) {
+            // requests that were cloned beyond their response are not reused; the same goes for
+            // requests whose extensions are still referred to by a `WeakHttpRequest`
+            if !inner.reusable.get() || Rc::weak_count(&inner.extensions) > 0 {
+                return;
+            }
+
             if inner.app_state.pool().is_available() {
                 // clear additional app_data and keep the root one for reuse.
                 inner.app_data.truncate(1);
@@ -449,6 +674,102 @@ impl fmt::Debug for HttpRequest {
     }
 }
 
+/// A handle to an [`HttpRequest`] that does not keep the request alive.
+///
+/// Created with [`HttpRequest::downgrade`], this is intended for background tasks that outlive the
+/// response, such as audit logging. Holding an `HttpRequest` clone in such tasks instead keeps
+/// all of its data, including the request's connection data, alive until the task completes and
+/// prevents its allocation from being reused for later requests.
+///
+/// The method, URI, version, headers, and matched pattern are copied when the handle is created
+/// and are always available. Later changes to the request, such as by middleware, are not
+/// reflected. Application data is available while the app (and the scope that registered it)
+/// is running, and request extensions only until the request is dropped, typically once the
+/// response is sent.
+///
+/// # Examples
+/// ```
+/// use actix_web::{rt, HttpRequest, HttpResponse};
+///
+/// async fn handler(req: HttpRequest) -> HttpResponse {
+///     let weak = req.downgrade();
+///
+///     rt::spawn(async move {
+///         log::info!("handled {} {}", weak.method(), weak.uri());
+///     });
+///
+///     HttpResponse::Ok().finish()
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WeakHttpRequest {
+    method: Method,
+    uri: Uri,
+    version: Version,
+    headers: HeaderMap,
+    match_pattern: Option<String>,
+    app_data: SmallVec<[Weak<Extensions>; 4]>,
+    extensions: Weak<RefCell<Extensions>>,
+}
+
+impl WeakHttpRequest {
+    /// Returns the request's method.
+    #[inline]
+    pub fn method(&self) -> &Method {
+        &self.method
+    }
+
+    /// Returns the request's URI.
+    #[inline]
+    pub fn uri(&self) -> &Uri {
+        &self.uri
+    }
+
+    /// Returns the request's HTTP version.
+    #[inline]
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Returns the request's headers, as they were when the handle was created.
+    #[inline]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the resource pattern the request matched, if any.
+    ///
+    /// See [`HttpRequest::match_pattern`].
+    #[inline]
+    pub fn match_pattern(&self) -> Option<&str> {
+        self.match_pattern.as_deref()
+    }
+
+    /// Returns a clone of the application data of type `T`, if it is still available.
+    ///
+    /// Data is looked up like [`HttpRequest::app_data`] and is unavailable once the app or scope
+    /// that registered it has shut down.
+    pub fn app_data<T: Clone + 'static>(&self) -> Option<T> {
+        self.app_data
+            .iter()
+            .rev()
+            .filter_map(Weak::upgrade)
+            .find_map(|container| container.get::<T>().cloned())
+    }
+
+    /// Returns a clone of the request extension of type `T`, if the request is still alive.
+    ///
+    /// Returns `None` once the request has been dropped.
+    pub fn extension<T: Clone + 'static>(&self) -> Option<T> {
+        self.extensions.upgrade()?.borrow().get::<T>().cloned()
+    }
+
+    /// Returns true if the request has been dropped.
+    pub fn is_completed(&self) -> bool {
+        self.extensions.strong_count() == 0
+    }
+}
+
 /// Slab-allocated `HttpRequest` Pool
 ///
 /// Since request processing may yield for asynchronous events to complete, a worker may have many
@@ -559,6 +880,23 @@ mod tests {
         assert!(cookie.is_none());
     }
 
+    #[test]
+    fn connection_info_is_cached() {
+        let req = TestRequest::default()
+            .insert_header((header::HOST, "example.com"))
+            .to_http_request();
+
+        let info = req.connection_info();
+        assert_eq!(info.host(), "example.com");
+
+        // holding on to a previous `Ref` must not panic
+        let info2 = req.connection_info();
+        assert_eq!(info2.host(), "example.com");
+        drop((info, info2));
+
+        assert!(req.extensions().contains::<ConnectionInfo>());
+    }
+
     #[test]
     fn test_request_query() {
         let req = TestRequest::with_uri("/?id=test").to_http_request();
@@ -670,6 +1008,96 @@ mod tests {
         assert_eq!(resp.headers().get("pool_cap").unwrap(), "128");
     }
 
+    #[actix_rt::test]
+    async fn weak_request_outlives_response() {
+        let task = Rc::new(RefCell::new(None));
+
+        let srv = init_service(App::new().app_data(10usize).service(
+            web::resource("/user/{id}").to({
+                let task = Rc::clone(&task);
+
+                move |req: HttpRequest| {
+                    req.extensions_mut().insert(5u8);
+
+                    let weak = req.downgrade();
+                    assert_eq!(weak.extension::<u8>(), Some(5));
+
+                    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+                    let handle = actix_rt::spawn(async move {
+                        rx.await.unwrap();
+                        weak
+                    });
+                    *task.borrow_mut() = Some((tx, handle));
+
+                    HttpResponse::Ok()
+                }
+            }),
+        ))
+        .await;
+
+        let req = TestRequest::post().uri("/user/42?q=1").to_request();
+        let res: actix_http::Response<_> = call_service(&srv, req).await.into();
+        assert_eq!(res.status(), StatusCode::OK);
+        drop(res);
+
+        let (tx, handle) = task.borrow_mut().take().unwrap();
+        tx.send(()).unwrap();
+        let weak = handle.await.unwrap();
+
+        assert_eq!(weak.method(), Method::POST);
+        assert_eq!(weak.uri(), "/user/42?q=1");
+        assert_eq!(weak.match_pattern(), Some("/user/{id}"));
+        assert_eq!(weak.app_data::<usize>(), Some(10));
+
+        assert!(weak.is_completed());
+        assert_eq!(weak.extension::<u8>(), None);
+
+        drop(srv);
+        assert_eq!(weak.app_data::<usize>(), None);
+    }
+
+    #[actix_rt::test]
+    async fn leaked_request_is_not_pooled() {
+        let leaked = Rc::new(RefCell::new(Vec::new()));
+
+        let srv = init_service(App::new().service(web::resource("/{name}").to({
+            let leaked = Rc::clone(&leaked);
+
+            move |req: HttpRequest| {
+                let body = match req.match_info().query("name") {
+                    "leak" => {
+                        leaked.borrow_mut().push(req.clone());
+                        req.path().to_owned()
+                    }
+                    "pooled" => req.app_state().pool().inner.borrow().len().to_string(),
+                    _ => req.path().to_owned(),
+                };
+
+                HttpResponse::Ok().body(body)
+            }
+        })))
+        .await;
+
+        let req = TestRequest::with_uri("/leak").to_request();
+        let res: actix_http::Response<_> = call_service(&srv, req).await.into();
+        drop(res);
+
+        let req = TestRequest::with_uri("/other").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(read_body(res).await, Bytes::from_static(b"/other"));
+
+        // the leaked clone is unaffected by later requests
+        assert_eq!(leaked.borrow()[0].path(), "/leak");
+        assert_eq!(leaked.borrow()[0].match_info().query("name"), "leak");
+
+        // dropping the leaked clone does not return it to the pool, which holds the other request
+        leaked.borrow_mut().clear();
+
+        let req = TestRequest::with_uri("/pooled").to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(read_body(res).await, Bytes::from_static(b"0"));
+    }
+
     #[actix_rt::test]
     async fn test_data() {
         let srv = init_service(App::new().app_data(10usize).service(web::resource("/").to(
@@ -1,9 +1,9 @@
 use std::{cell::RefCell, fmt, future::Future, rc::Rc};
 
-use actix_http::Extensions;
+use actix_http::{Extensions, Method};
 use actix_router::{IntoPatterns, Patterns};
 use actix_service::{
-    apply, apply_fn_factory, boxed, fn_service, IntoServiceFactory, Service, ServiceFactory,
+    apply, apply_fn_factory, boxed, IntoServiceFactory, Service, ServiceFactory,
     ServiceFactoryExt, Transform,
 };
 use futures_core::future::LocalBoxFuture;
@@ -11,11 +11,12 @@ use futures_util::future::join_all;
 
 use crate::{
     body::MessageBody,
-    data::Data,
+    data::{Data, DataLocations},
     dev::{ensure_leading_slash, AppService, ResourceDef},
     guard::Guard,
     handler::Handler,
-    route::{Route, RouteService},
+    http::header,
+    route::{GuardSummary, Route, RouteService},
     service::{
         BoxedHttpService, BoxedHttpServiceFactory, HttpServiceFactory, ServiceRequest,
         ServiceResponse,
@@ -40,16 +41,20 @@ use crate::{
 ///         .route(web::get().to(|| HttpResponse::Ok())));
 /// ```
 ///
-/// If no matching route could be found, *405* response code get returned. Default behavior could be
-/// overridden with `default_resource()` method.
+/// If no matching route could be found, *405* response code get returned, with an `Allow` header
+/// listing the methods of the routes' method guards. Default behavior could be overridden with
+/// `default_resource()` method.
 pub struct Resource<T = ResourceEndpoint> {
     endpoint: T,
     rdef: Patterns,
     name: Option<String>,
     routes: Vec<Route>,
     app_data: Option<Extensions>,
+    data_locations: DataLocations,
     guards: Vec<Box<dyn Guard>>,
-    default: BoxedHttpServiceFactory,
+    guard_summary: GuardSummary,
+    auto_options: Option<bool>,
+    default: Option<BoxedHttpServiceFactory>,
     factory_ref: Rc<RefCell<Option<ResourceFactory>>>,
 }
 
@@ -64,10 +69,11 @@ impl Resource {
             endpoint: ResourceEndpoint::new(fref.clone()),
             factory_ref: fref,
             guards: Vec::new(),
+            guard_summary: GuardSummary::default(),
+            auto_options: None,
             app_data: None,
-            default: boxed::factory(fn_service(|req: ServiceRequest| async {
-                Ok(req.into_response(HttpResponse::MethodNotAllowed()))
-            })),
+            data_locations: DataLocations::default(),
+            default: None,
         }
     }
 }
@@ -107,11 +113,16 @@ where
     /// ```
     pub fn guard<G: Guard + 'static>(mut self, guard: G) -> Self {
         self.guards.push(Box::new(guard));
+        self.guard_summary.opaque = true;
         self
     }
 
-    pub(crate) fn add_guards(mut self, guards: Vec<Box<dyn Guard>>) -> Self {
+    pub(crate) fn add_guards(
+        mut self,
+        (guards, summary): (Vec<Box<dyn Guard>>, GuardSummary),
+    ) -> Self {
         self.guards.extend(guards);
+        self.guard_summary.merge(summary);
         self
     }
 
@@ -151,6 +162,34 @@ where
         self
     }
 
+    /// Sets whether `OPTIONS` requests are answered automatically.
+    ///
+    /// When enabled, `OPTIONS` requests that are not handled by any route respond with
+    /// "204 No Content" and an [`Allow`](crate::http::header::Allow) header listing the methods of
+    /// the routes' method guards, plus `OPTIONS`. Routes handling `OPTIONS` requests always take
+    /// precedence. CORS middleware wrapping the resource, or the app, answers preflight requests
+    /// before they reach the resource.
+    ///
+    /// Defaults to the app's setting; see [`App::auto_options`](crate::App::auto_options). Routes
+    /// registered using `App::route` or `Scope::route` each form their own resource, so their
+    /// methods are only listed together when registered on a single resource, as below.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{web, App};
+    ///
+    /// let app = App::new().service(
+    ///     web::resource("/items")
+    ///         .auto_options(true)
+    ///         .route(web::get().to(|| async { "items" }))
+    ///         .route(web::put().to(|| async { "updated" })),
+    /// );
+    /// ```
+    pub fn auto_options(mut self, enabled: bool) -> Self {
+        self.auto_options = Some(enabled);
+        self
+    }
+
     /// Add resource data.
     ///
     /// Data of different types from parent contexts will still be accessible. Any `Data<T>` types
@@ -183,8 +222,29 @@ where
     ///         .route(web::get().to(handler))
     /// );
     /// ```
+    ///
+    /// In debug builds, registering data of the same type twice on this resource logs a warning; see
+    /// [`app_data_replace`](Self::app_data_replace).
     #[doc(alias = "manage")]
+    #[track_caller]
     pub fn app_data<U: 'static>(mut self, data: U) -> Self {
+        self.data_locations.register::<U>();
+        self.app_data
+            .get_or_insert_with(Extensions::new)
+            .insert(data);
+
+        self
+    }
+
+    /// Set application data, overwriting data of the same type registered earlier on this resource.
+    ///
+    /// In debug builds, [`app_data`](Self::app_data) logs a warning naming both call sites when a
+    /// type is registered twice on the same resource, since the first value is silently discarded.
+    /// Use this method instead where overwriting is intended, for example to replace a default
+    /// value set by a shared helper.
+    #[track_caller]
+    pub fn app_data_replace<U: 'static>(mut self, data: U) -> Self {
+        self.data_locations.replace::<U>();
         self.app_data
             .get_or_insert_with(Extensions::new)
             .insert(data);
@@ -196,6 +256,7 @@ where
     ///
     /// Deprecated in favor of [`app_data`](Self::app_data).
     #[deprecated(since = "4.0.0", note = "Use `.app_data(Data::new(val))` instead.")]
+    #[track_caller]
     pub fn data<U: 'static>(self, data: U) -> Self {
         self.app_data(Data::new(data))
     }
@@ -264,9 +325,12 @@ where
             rdef: self.rdef,
             name: self.name,
             guards: self.guards,
+            guard_summary: self.guard_summary,
+            auto_options: self.auto_options,
             routes: self.routes,
             default: self.default,
             app_data: self.app_data,
+            data_locations: self.data_locations,
             factory_ref: self.factory_ref,
         }
     }
@@ -302,9 +366,12 @@ where
             rdef: self.rdef,
             name: self.name,
             guards: self.guards,
+            guard_summary: self.guard_summary,
+            auto_options: self.auto_options,
             routes: self.routes,
             default: self.default,
             app_data: self.app_data,
+            data_locations: self.data_locations,
             factory_ref: self.factory_ref,
         }
     }
@@ -314,7 +381,8 @@ where
     /// You can use a [`Route`] as default service.
     ///
     /// If a default service is not registered, an empty `405 Method Not Allowed` response will be
-    /// sent to the client instead. Unlike [`Scope`](crate::Scope)s, a [`Resource`] does **not**
+    /// sent to the client instead, with an [`Allow`](crate::http::header::Allow) header listing the
+    /// methods of the routes' method guards, if any. Unlike [`Scope`](crate::Scope)s, a [`Resource`] does **not**
     /// inherit its parent's default service.
     pub fn default_service<F, U>(mut self, f: F) -> Self
     where
@@ -328,10 +396,9 @@ where
         U::InitError: fmt::Debug,
     {
         // create and configure default resource
-        self.default = boxed::factory(
-            f.into_factory()
-                .map_init_err(|e| log::error!("Can not construct default service: {:?}", e)),
-        );
+        self.default = Some(boxed::factory(f.into_factory().map_init_err(|e| {
+            log::error!("Can not construct default service: {:?}", e)
+        })));
 
         self
     }
@@ -365,8 +432,13 @@ where
             rdef.set_name(name);
         }
 
+        let auto_options = self.auto_options.unwrap_or_else(|| config.auto_options());
+        let allow = Rc::new(allowed_methods(&self.routes, auto_options));
+
         *self.factory_ref.borrow_mut() = Some(ResourceFactory {
             routes: self.routes,
+            allow,
+            auto_options,
             default: self.default,
         });
 
@@ -383,13 +455,39 @@ where
             async { Ok(fut.await?.map_into_boxed_body()) }
         });
 
-        config.register_service(rdef, guards, endpoint, None)
+        config.register_service(rdef, guards, endpoint, None);
+        config.set_guard_summary(self.guard_summary);
     }
 }
 
+/// Returns the methods allowed by the method guards of `routes`, followed by `OPTIONS` if automatic
+/// `OPTIONS` responses are enabled.
+///
+/// Routes that are not restricted to a set of methods, such as those created using
+/// [`web::route_except`](crate::web::route_except), do not contribute any methods.
+fn allowed_methods(routes: &[Route], auto_options: bool) -> Vec<Method> {
+    let mut allow = Vec::new();
+
+    let methods = routes
+        .iter()
+        .filter_map(|route| route.guard_summary().methods.as_deref())
+        .flatten()
+        .chain(auto_options.then(|| &Method::OPTIONS));
+
+    for method in methods {
+        if !allow.contains(method) {
+            allow.push(method.clone());
+        }
+    }
+
+    allow
+}
+
 pub struct ResourceFactory {
     routes: Vec<Route>,
-    default: BoxedHttpServiceFactory,
+    allow: Rc<Vec<Method>>,
+    auto_options: bool,
+    default: Option<BoxedHttpServiceFactory>,
 }
 
 impl ServiceFactory<ServiceRequest> for ResourceFactory {
@@ -402,26 +500,39 @@ impl ServiceFactory<ServiceRequest> for ResourceFactory {
 
     fn new_service(&self, _: ()) -> Self::Future {
         // construct default service factory future.
-        let default_fut = self.default.new_service(());
+        let default_fut = self.default.as_ref().map(|default| default.new_service(()));
 
         // construct route service factory futures
         let factory_fut = join_all(self.routes.iter().map(|route| route.new_service(())));
+        let allow = self.allow.clone();
+        let auto_options = self.auto_options;
 
         Box::pin(async move {
-            let default = default_fut.await?;
+            let default = match default_fut {
+                Some(fut) => Some(fut.await?),
+                None => None,
+            };
+
             let routes = factory_fut
                 .await
                 .into_iter()
                 .collect::<Result<Vec<_>, _>>()?;
 
-            Ok(ResourceService { routes, default })
+            Ok(ResourceService {
+                routes,
+                allow,
+                auto_options,
+                default,
+            })
         })
     }
 }
 
 pub struct ResourceService {
     routes: Vec<RouteService>,
-    default: BoxedHttpService,
+    allow: Rc<Vec<Method>>,
+    auto_options: bool,
+    default: Option<BoxedHttpService>,
 }
 
 impl Service<ServiceRequest> for ResourceService {
@@ -438,7 +549,27 @@ impl Service<ServiceRequest> for ResourceService {
             }
         }
 
-        self.default.call(req)
+        if self.auto_options && req.method() == Method::OPTIONS {
+            let res = req.into_response(
+                HttpResponse::NoContent()
+                    .insert_header(header::Allow(self.allow.to_vec()))
+                    .finish(),
+            );
+            return Box::pin(async move { Ok(res) });
+        }
+
+        if let Some(default) = &self.default {
+            return default.call(req);
+        }
+
+        let mut res = HttpResponse::MethodNotAllowed();
+
+        if !self.allow.is_empty() {
+            res.insert_header(header::Allow(self.allow.to_vec()));
+        }
+
+        let res = req.into_response(res.finish());
+        Box::pin(async move { Ok(res) })
     }
 }
 
@@ -483,7 +614,7 @@ mod tests {
         },
         middleware::DefaultHeaders,
         service::{ServiceRequest, ServiceResponse},
-        test::{call_service, init_service, TestRequest},
+        test::{call_service, init_service, read_body, TestRequest},
         web, App, Error, HttpMessage, HttpResponse,
     };
 
@@ -602,6 +733,156 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[actix_rt::test]
+    async fn auto_options() {
+        let srv = init_service(
+            App::new()
+                .service(
+                    web::resource("/items")
+                        .auto_options(true)
+                        .route(web::get().to(HttpResponse::Ok))
+                        .route(web::put().to(HttpResponse::Ok)),
+                )
+                .service(
+                    web::resource("/explicit")
+                        .auto_options(true)
+                        .route(web::get().to(HttpResponse::Ok))
+                        .route(web::method(Method::OPTIONS).to(HttpResponse::Ok)),
+                ),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/items")
+            .method(Method::OPTIONS)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        let allow = res.headers().get(header::ALLOW).unwrap();
+        assert_eq!(allow, "GET, PUT, OPTIONS");
+        assert!(read_body(res).await.is_empty());
+
+        // other unhandled methods still use the default service
+        let req = TestRequest::with_uri("/items")
+            .method(Method::DELETE)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        // explicit routes take precedence
+        let req = TestRequest::with_uri("/explicit")
+            .method(Method::OPTIONS)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(header::ALLOW).is_none());
+    }
+
+    #[actix_rt::test]
+    async fn auto_options_default() {
+        let resource = || web::resource("/items").route(web::get().to(HttpResponse::Ok));
+
+        let srv = init_service(App::new().service(resource())).await;
+        let req = TestRequest::with_uri("/items")
+            .method(Method::OPTIONS)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+
+        let srv = init_service(
+            App::new()
+                .auto_options(true)
+                .service(resource())
+                .service(web::resource("/off").auto_options(false)),
+        )
+        .await;
+
+        let req = TestRequest::with_uri("/items")
+            .method(Method::OPTIONS)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        let allow = res.headers().get(header::ALLOW).unwrap();
+        assert_eq!(allow, "GET, OPTIONS");
+
+        let req = TestRequest::with_uri("/off")
+            .method(Method::OPTIONS)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    #[actix_rt::test]
+    async fn method_set_routes() {
+        let srv = init_service(
+            App::new()
+                .service(
+                    web::resource("/form")
+                        .auto_options(true)
+                        .route(
+                            web::any_of([Method::GET, Method::POST])
+                                .to(|| HttpResponse::Ok().body("form")),
+                        )
+                        .route(web::put().to(|| HttpResponse::Ok().body("put"))),
+                )
+                .service(
+                    web::resource("/files")
+                        .route(web::any_of([Method::GET]).to(|| HttpResponse::Ok().body("get")))
+                        .route(
+                            web::route_except([Method::DELETE])
+                                .to(|| HttpResponse::Ok().body("catch-all")),
+                        ),
+                ),
+        )
+        .await;
+
+        for (method, body) in [
+            (Method::GET, "form"),
+            (Method::POST, "form"),
+            (Method::PUT, "put"),
+        ] {
+            let req = TestRequest::with_uri("/form").method(method).to_request();
+            let res = call_service(&srv, req).await;
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(read_body(res).await, body);
+        }
+
+        let req = TestRequest::with_uri("/form")
+            .method(Method::PATCH)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        let allow = res.headers().get(header::ALLOW).unwrap();
+        assert_eq!(allow, "GET, POST, PUT, OPTIONS");
+
+        let req = TestRequest::with_uri("/form")
+            .method(Method::OPTIONS)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NO_CONTENT);
+        let allow = res.headers().get(header::ALLOW).unwrap();
+        assert_eq!(allow, "GET, POST, PUT, OPTIONS");
+
+        for (method, body) in [
+            (Method::GET, "get"),
+            (Method::POST, "catch-all"),
+            (Method::PATCH, "catch-all"),
+        ] {
+            let req = TestRequest::with_uri("/files").method(method).to_request();
+            let res = call_service(&srv, req).await;
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(read_body(res).await, body);
+        }
+
+        // excluded methods are neither handled nor listed as allowed
+        let req = TestRequest::with_uri("/files")
+            .method(Method::DELETE)
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::METHOD_NOT_ALLOWED);
+        let allow = res.headers().get(header::ALLOW).unwrap();
+        assert_eq!(allow, "GET");
+    }
+
     #[actix_rt::test]
     async fn test_default_resource() {
         let srv = init_service(
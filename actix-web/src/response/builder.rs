@@ -17,6 +17,7 @@ use crate::{
     error::{Error, JsonPayloadError},
     http::header::{self, HeaderName, TryIntoHeaderPair, TryIntoHeaderValue},
     http::{ConnectionType, StatusCode},
+    types::BuilderJson,
     BoxError, HttpRequest, HttpResponse, Responder,
 };
 
@@ -305,12 +306,14 @@ impl HttpResponseBuilder {
             return Err(err.into());
         }
 
-        let res = self
+        let mut res = self
             .res
             .take()
             .expect("cannot reuse response builder")
             .set_body(body);
 
+        strip_informational_framing(res.head_mut());
+
         Ok(HttpResponse::from(res))
     }
 
@@ -328,6 +331,9 @@ impl HttpResponseBuilder {
 
     /// Set a JSON body and build the `HttpResponse`.
     ///
+    /// When the response is returned from a handler, the body is written using the serializer
+    /// registered with [`JsonConfig::serializer`](crate::web::JsonConfig::serializer), if any.
+    ///
     /// `HttpResponseBuilder` can not be used after this call.
     pub fn json(&mut self, value: impl Serialize) -> HttpResponse {
         match serde_json::to_string(&value) {
@@ -342,6 +348,7 @@ impl HttpResponseBuilder {
                     self.insert_header((header::CONTENT_TYPE, mime::APPLICATION_JSON));
                 }
 
+                self.extensions_mut().insert(BuilderJson);
                 self.body(body)
             }
             Err(err) => HttpResponse::from_error(JsonPayloadError::Serialize(err)),
@@ -373,6 +380,16 @@ impl HttpResponseBuilder {
     }
 }
 
+/// Removes framing headers that informational (1xx) responses must not carry.
+///
+/// See <https://datatracker.ietf.org/doc/html/rfc7230#section-3.3.2>.
+fn strip_informational_framing(head: &mut ResponseHead) {
+    if head.status.is_informational() {
+        head.headers.remove(header::CONTENT_LENGTH);
+        head.headers.remove(header::TRANSFER_ENCODING);
+    }
+}
+
 impl From<HttpResponseBuilder> for HttpResponse {
     fn from(mut builder: HttpResponseBuilder) -> Self {
         builder.finish()
@@ -422,6 +439,27 @@ mod tests {
         assert_eq!(resp.status(), StatusCode::OK);
     }
 
+    #[test]
+    fn informational_responses_never_carry_content_length() {
+        let res = HttpResponse::EarlyHints()
+            .insert_header(header::ContentLength(10))
+            .insert_header((header::LINK, "</style.css>; rel=preload; as=style"))
+            .finish();
+        assert_eq!(res.status().as_u16(), 103);
+        assert!(!res.headers().contains_key(header::CONTENT_LENGTH));
+        assert!(res.headers().contains_key(header::LINK));
+
+        let res = HttpResponse::Continue()
+            .insert_header(header::ContentLength(0))
+            .finish();
+        assert!(!res.headers().contains_key(header::CONTENT_LENGTH));
+
+        let res = HttpResponse::Ok()
+            .insert_header(header::ContentLength(0))
+            .finish();
+        assert!(res.headers().contains_key(header::CONTENT_LENGTH));
+    }
+
     #[test]
     fn test_upgrade() {
         let resp = HttpResponseBuilder::new(StatusCode::OK)
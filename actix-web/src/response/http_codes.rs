@@ -17,6 +17,7 @@ impl HttpResponse {
     static_resp!(Continue, StatusCode::CONTINUE);
     static_resp!(SwitchingProtocols, StatusCode::SWITCHING_PROTOCOLS);
     static_resp!(Processing, StatusCode::PROCESSING);
+    static_resp!(EarlyHints, StatusCode::from_u16(103).unwrap());
 
     static_resp!(Ok, StatusCode::OK);
     static_resp!(Created, StatusCode::CREATED);
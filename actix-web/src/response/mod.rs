@@ -1,12 +1,14 @@
 mod builder;
 mod customize_responder;
 mod http_codes;
+mod partial_response;
 mod responder;
 #[allow(clippy::module_inception)]
 mod response;
 
 pub use self::builder::HttpResponseBuilder;
 pub use self::customize_responder::CustomizeResponder;
+pub use self::partial_response::PartialResponse;
 pub use self::responder::Responder;
 pub use self::response::HttpResponse;
 
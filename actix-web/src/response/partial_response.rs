@@ -0,0 +1,200 @@
+use std::convert::TryFrom;
+
+use bytes::Bytes;
+
+use crate::{
+    body::EitherBody,
+    error::ErrorInternalServerError,
+    http::header::{AcceptRanges, ContentLength, ContentRange, Range},
+    HttpRequest, HttpResponse, Responder,
+};
+
+/// Responder serving the part of a representation requested by a [`Range`] header.
+///
+/// Responds with:
+/// - "206 Partial Content" for a satisfiable single byte range, with the body sliced to the range
+///   and matching `Content-Range` and `Content-Length` headers;
+/// - "416 Range Not Satisfiable" with a `Content-Range: bytes */<total>` header and an empty body
+///   if none of the requested byte ranges are satisfiable;
+/// - "200 OK" with the complete body otherwise, including when there is no `Range` header, the
+///   range unit is not `bytes`, or several byte ranges are requested, since multipart responses
+///   are not supported.
+///
+/// All but "416" responses carry an `Accept-Ranges: bytes` header.
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::{Header as _, Range}, web, HttpRequest, PartialResponse};
+///
+/// async fn video(req: HttpRequest) -> PartialResponse {
+///     let body = web::Bytes::from_static(b"0123456789");
+///     let total = body.len() as u64;
+///
+///     PartialResponse::new(body, Range::parse(&req).ok(), total)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PartialResponse {
+    body: Bytes,
+    range: Option<Range>,
+    total: u64,
+}
+
+impl PartialResponse {
+    /// Constructs a responder for `range` of a representation that is `total` bytes long.
+    ///
+    /// `body` holds the complete representation. Ranges are resolved against `total`; if `body`
+    /// turns out to be too short for the resolved range, a "500 Internal Server Error" response is
+    /// returned instead.
+    pub fn new(body: impl Into<Bytes>, range: Option<Range>, total: u64) -> Self {
+        Self {
+            body: body.into(),
+            range,
+            total,
+        }
+    }
+
+    /// Returns the end-inclusive byte range to serve, `Ok(None)` to serve the complete body, or
+    /// `Err(())` if the requested ranges are not satisfiable.
+    fn resolve(&self) -> Result<Option<(u64, u64)>, ()> {
+        let specs = match &self.range {
+            Some(Range::Bytes(specs)) if !specs.is_empty() => specs,
+            _ => return Ok(None),
+        };
+
+        let mut satisfiable = specs
+            .iter()
+            .filter_map(|spec| spec.to_satisfiable_range(self.total));
+
+        match (satisfiable.next(), satisfiable.next()) {
+            (None, _) => Err(()),
+            (Some(range), None) => Ok(Some(range)),
+            (Some(_), Some(_)) => Ok(None),
+        }
+    }
+}
+
+impl Responder for PartialResponse {
+    type Body = EitherBody<Bytes>;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let (start, end) = match self.resolve() {
+            Ok(Some(range)) => range,
+
+            Ok(None) => {
+                let len = self.body.len();
+
+                return HttpResponse::Ok()
+                    .insert_header(AcceptRanges::Bytes)
+                    .insert_header(ContentLength(len))
+                    .message_body(self.body)
+                    .unwrap()
+                    .map_into_left_body();
+            }
+
+            Err(()) => {
+                return HttpResponse::RangeNotSatisfiable()
+                    .insert_header(ContentRange::unsatisfied(self.total))
+                    .insert_header(ContentLength(0))
+                    .message_body(Bytes::new())
+                    .unwrap()
+                    .map_into_left_body();
+            }
+        };
+
+        let slice = match (usize::try_from(start), usize::try_from(end)) {
+            (Ok(start), Ok(end)) if end < self.body.len() => self.body.slice(start..=end),
+
+            _ => {
+                let err = ErrorInternalServerError("Body is shorter than the requested range");
+                return HttpResponse::from_error(err).map_into_right_body();
+            }
+        };
+
+        HttpResponse::PartialContent()
+            .insert_header(AcceptRanges::Bytes)
+            .insert_header(ContentRange::bytes(start, end, self.total))
+            .insert_header(ContentLength(slice.len()))
+            .message_body(slice)
+            .unwrap()
+            .map_into_left_body()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        body::to_bytes,
+        http::{header, StatusCode},
+        test::TestRequest,
+    };
+
+    const BODY: &[u8] = b"0123456789";
+
+    fn respond(range: Option<Range>) -> HttpResponse<EitherBody<Bytes>> {
+        let req = TestRequest::default().to_http_request();
+        PartialResponse::new(BODY, range, BODY.len() as u64).respond_to(&req)
+    }
+
+    #[actix_rt::test]
+    async fn satisfiable_range() {
+        let res = respond(Some(Range::bytes(2, 5)));
+        assert_eq!(res.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            res.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 2-5/10"
+        );
+        assert_eq!(res.headers().get(header::CONTENT_LENGTH).unwrap(), "4");
+        assert_eq!(res.headers().get(header::ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(to_bytes(res.into_body()).await.unwrap(), "2345");
+
+        // suffix ranges and ranges past the end are clamped to the representation
+        let res = respond(Some("bytes=-3".parse().unwrap()));
+        assert_eq!(
+            res.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 7-9/10"
+        );
+        assert_eq!(to_bytes(res.into_body()).await.unwrap(), "789");
+
+        let res = respond(Some(Range::bytes(8, 100)));
+        assert_eq!(
+            res.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 8-9/10"
+        );
+        assert_eq!(res.headers().get(header::CONTENT_LENGTH).unwrap(), "2");
+        assert_eq!(to_bytes(res.into_body()).await.unwrap(), "89");
+    }
+
+    #[actix_rt::test]
+    async fn unsatisfiable_range() {
+        let res = respond(Some(Range::bytes(10, 20)));
+        assert_eq!(res.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            res.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes */10"
+        );
+        assert_eq!(res.headers().get(header::CONTENT_LENGTH).unwrap(), "0");
+        assert!(to_bytes(res.into_body()).await.unwrap().is_empty());
+    }
+
+    #[actix_rt::test]
+    async fn complete_body() {
+        for range in [
+            None,
+            Some(Range::bytes_multi(vec![(0, 1), (4, 5)])),
+            Some(Range::Unregistered("letters".to_owned(), "a-f".to_owned())),
+        ] {
+            let res = respond(range);
+            assert_eq!(res.status(), StatusCode::OK);
+            assert!(res.headers().get(header::CONTENT_RANGE).is_none());
+            assert_eq!(res.headers().get(header::CONTENT_LENGTH).unwrap(), "10");
+            assert_eq!(to_bytes(res.into_body()).await.unwrap(), BODY);
+        }
+
+        // body shorter than the declared total length
+        let req = TestRequest::default().to_http_request();
+        let res = PartialResponse::new(BODY, Some(Range::bytes(5, 15)), 20).respond_to(&req);
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -36,6 +36,14 @@ use crate::{Error, HttpRequest, HttpResponse};
 /// Calling [`.customize()`](Responder::customize) on any responder type will wrap it in a
 /// [`CustomizeResponder`] capable of overriding various parts of the response such as the status
 /// code and header map.
+#[rustversion::attr(
+    since(1.78),
+    diagnostic::on_unimplemented(
+        message = "`{Self}` cannot be returned from a handler",
+        label = "does not implement `Responder`",
+        note = "handlers must return a type implementing `Responder`, such as `HttpResponse` or `String`"
+    )
+)]
 pub trait Responder {
     type Body: MessageBody + 'static;
 
@@ -1,11 +1,12 @@
 use std::{
     cell::{Ref, RefMut},
+    convert::TryFrom as _,
     fmt,
 };
 
 use actix_http::{
-    body::{BoxBody, EitherBody, MessageBody},
-    header::HeaderMap,
+    body::{BodySize, BoxBody, EitherBody, MessageBody},
+    header::{Header, HeaderMap},
     Extensions, Response, ResponseHead, StatusCode,
 };
 
@@ -18,7 +19,11 @@ use {
     cookie::Cookie,
 };
 
-use crate::{error::Error, HttpRequest, HttpResponseBuilder, Responder};
+use crate::{
+    error::Error,
+    http::header::{AcceptRanges, ContentLength},
+    HttpRequest, HttpResponseBuilder, Responder,
+};
 
 /// An outgoing response.
 pub struct HttpResponse<B = BoxBody> {
@@ -296,6 +301,53 @@ impl<B> HttpResponse<B> {
     }
 }
 
+impl<B: MessageBody> HttpResponse<B> {
+    /// Advertises whether byte range requests are supported, based on the size of the body.
+    ///
+    /// If the body's size is known, sets a [`ContentLength`] header to that size and an
+    /// [`AcceptRanges`] header of `bytes` if `seekable` is true, or of `none` otherwise. Ranges of
+    /// a body whose size is unknown can not be served, so such responses only get
+    /// `Accept-Ranges: none` and any `Content-Length` header is left as it is.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{http::header::{AcceptRanges, ContentLength, Header as _}, HttpResponse};
+    ///
+    /// let mut res = HttpResponse::Ok().body("0123456789");
+    /// res.advertise_ranges(true);
+    ///
+    /// assert_eq!(res.headers().get(ContentLength::name()).unwrap(), "10");
+    /// assert_eq!(res.headers().get(AcceptRanges::name()).unwrap(), "bytes");
+    /// ```
+    pub fn advertise_ranges(&mut self, seekable: bool) {
+        let accept_ranges = match self.body().size() {
+            BodySize::Sized(len) => match usize::try_from(len) {
+                Ok(len) => {
+                    self.insert_typed_header(ContentLength(len));
+
+                    if seekable {
+                        AcceptRanges::Bytes
+                    } else {
+                        AcceptRanges::None
+                    }
+                }
+
+                Err(_) => AcceptRanges::None,
+            },
+
+            BodySize::None | BodySize::Stream => AcceptRanges::None,
+        };
+
+        self.insert_typed_header(accept_ranges);
+    }
+
+    fn insert_typed_header<H: Header>(&mut self, header: H) {
+        if let Ok(value) = header.try_into_value() {
+            self.headers_mut().insert(H::name(), value);
+        }
+    }
+}
+
 impl<B> fmt::Debug for HttpResponse<B>
 where
     B: MessageBody,
@@ -407,6 +459,14 @@ mod tests {
     assert_impl_all!(HttpResponse<&'static str>: Responder);
     assert_impl_all!(HttpResponse<crate::body::None>: Responder);
 
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn response_size() {
+        // responses are moved around a lot; keep them small enough to be stored inline in
+        // enums like `InternalErrorType`, see `clippy::large_enum_variant`
+        assert!(std::mem::size_of::<HttpResponse>() <= 176);
+    }
+
     #[test]
     fn test_debug() {
         let resp = HttpResponse::Ok()
@@ -416,6 +476,26 @@ mod tests {
         let dbg = format!("{:?}", resp);
         assert!(dbg.contains("HttpResponse"));
     }
+
+    #[test]
+    fn advertise_ranges() {
+        let mut res = HttpResponse::Ok().body("0123456789");
+        res.advertise_ranges(true);
+        assert_eq!(res.headers().get(ContentLength::name()).unwrap(), "10");
+        assert_eq!(res.headers().get(AcceptRanges::name()).unwrap(), "bytes");
+
+        let mut res = HttpResponse::Ok().body("0123456789");
+        res.advertise_ranges(false);
+        assert_eq!(res.headers().get(ContentLength::name()).unwrap(), "10");
+        assert_eq!(res.headers().get(AcceptRanges::name()).unwrap(), "none");
+
+        let mut res = HttpResponse::Ok().streaming(futures_util::stream::empty::<
+            Result<bytes::Bytes, crate::Error>,
+        >());
+        res.advertise_ranges(true);
+        assert!(!res.headers().contains_key(ContentLength::name()));
+        assert_eq!(res.headers().get(AcceptRanges::name()).unwrap(), "none");
+    }
 }
 
 #[cfg(test)]
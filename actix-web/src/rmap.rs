@@ -1,7 +1,9 @@
 use std::{
-    borrow::Cow,
+    borrow::{Borrow, Cow},
     cell::RefCell,
+    collections::HashMap,
     fmt::Write as _,
+    hash::{BuildHasher, Hash},
     rc::{Rc, Weak},
 };
 
@@ -9,10 +11,17 @@ use actix_router::ResourceDef;
 use ahash::AHashMap;
 use url::Url;
 
-use crate::{error::UrlGenerationError, request::HttpRequest};
+use crate::{
+    error::UrlGenerationError,
+    http::uri::{EncodeSet, PathEncoder},
+    request::HttpRequest,
+};
 
 const AVG_PATH_LEN: usize = 24;
 
+/// Encoder for dynamic segment values; `/` and existing `%XX` sequences are kept.
+const SEGMENT_VALUE_ENCODER: PathEncoder = PathEncoder::new(EncodeSet::Path).raw();
+
 #[derive(Clone, Debug)]
 pub struct ResourceMap {
     pattern: ResourceDef,
@@ -127,7 +136,9 @@ impl ResourceMap {
         U: IntoIterator<Item = I>,
         I: AsRef<str>,
     {
-        let mut elements = elements.into_iter();
+        let mut elements = elements
+            .into_iter()
+            .map(|el| SEGMENT_VALUE_ENCODER.encode(el.as_ref()).into_owned());
 
         let path = self
             .named
@@ -140,11 +151,49 @@ impl ResourceMap {
             })
             .ok_or(UrlGenerationError::NotEnoughElements)?;
 
+        Self::url_from_path(req, &path)
+    }
+
+    /// Generate URL for named resource using a map of dynamic segment values.
+    ///
+    /// Check [`HttpRequest::url_for_map`] for detailed information.
+    pub fn url_for_map<K, V, S>(
+        &self,
+        req: &HttpRequest,
+        name: &str,
+        elements: &HashMap<K, V, S>,
+    ) -> Result<Url, UrlGenerationError>
+    where
+        K: Borrow<str> + Eq + Hash,
+        V: AsRef<str>,
+        S: BuildHasher,
+    {
+        let elements = elements
+            .iter()
+            .map(|(key, val)| (key.borrow(), SEGMENT_VALUE_ENCODER.encode(val.as_ref())))
+            .collect::<HashMap<&str, _>>();
+
+        let path = self
+            .named
+            .get(name)
+            .ok_or(UrlGenerationError::ResourceNotFound)?
+            .root_rmap_fn(String::with_capacity(AVG_PATH_LEN), |mut acc, node| {
+                node.pattern
+                    .resource_path_from_map(&mut acc, &elements)
+                    .then(|| acc)
+            })
+            .ok_or(UrlGenerationError::NotEnoughElements)?;
+
+        Self::url_from_path(req, &path)
+    }
+
+    /// Builds full URL from an assembled resource path.
+    fn url_from_path(req: &HttpRequest, path: &str) -> Result<Url, UrlGenerationError> {
         let (base, path): (Cow<'_, _>, _) = if path.starts_with('/') {
             // build full URL from connection info parts and resource path
             let conn = req.connection_info();
             let base = format!("{}://{}", conn.scheme(), conn.host());
-            (Cow::Owned(base), path.as_str())
+            (Cow::Owned(base), path)
         } else {
             // external resource; third slash would be the root slash in the path
             let third_slash_index = path
@@ -169,6 +218,11 @@ impl ResourceMap {
         self.find_matching_node(path).is_some()
     }
 
+    /// Returns true if there is a resource named `name`.
+    pub(crate) fn has_named(&self, name: &str) -> bool {
+        self.named.contains_key(name)
+    }
+
     /// Returns the name of the route that matches the given path or None if no full match
     /// is possible or the matching resource is not named.
     pub fn match_name(&self, path: &str) -> Option<&str> {
@@ -481,7 +535,7 @@ mod tests {
         let req = req.to_http_request();
 
         const INPUT: &[&str] = &["a/../quick brown%20fox/%nan?query#frag"];
-        const OUTPUT: &str = "/quick%20brown%20fox/%nan%3Fquery%23frag";
+        const OUTPUT: &str = "/quick%20brown%20fox/%25nan%3Fquery%23frag";
 
         let url = rmap.url_for(&req, "internal", INPUT).unwrap();
         assert_eq!(url.path(), OUTPUT);
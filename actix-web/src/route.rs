@@ -23,6 +23,7 @@ use crate::{
 pub struct Route {
     service: BoxedHttpServiceFactory,
     guards: Rc<Vec<Box<dyn Guard>>>,
+    summary: GuardSummary,
 }
 
 impl Route {
@@ -34,6 +35,7 @@ impl Route {
                 Ok(req.into_response(HttpResponse::NotFound()))
             })),
             guards: Rc::new(Vec::new()),
+            summary: GuardSummary::default(),
         }
     }
 
@@ -59,11 +61,84 @@ impl Route {
         Route {
             service: boxed::factory(apply(Compat::new(mw), self.service)),
             guards: self.guards,
+            summary: self.summary,
         }
     }
 
-    pub(crate) fn take_guards(&mut self) -> Vec<Box<dyn Guard>> {
-        mem::take(Rc::get_mut(&mut self.guards).unwrap())
+    /// Returns what is known about the guards of this route.
+    pub(crate) fn guard_summary(&self) -> &GuardSummary {
+        &self.summary
+    }
+
+    pub(crate) fn take_guards(&mut self) -> (Vec<Box<dyn Guard>>, GuardSummary) {
+        (
+            mem::take(Rc::get_mut(&mut self.guards).unwrap()),
+            mem::take(&mut self.summary),
+        )
+    }
+}
+
+/// What is known about the guards of a route or resource, used for route validation.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GuardSummary {
+    /// Methods allowed by method guards, or `None` if not restricted by method.
+    pub(crate) methods: Option<Vec<Method>>,
+
+    /// Whether there are guards other than method guards, which could match on anything.
+    pub(crate) opaque: bool,
+
+    /// Methods rejected by method guards, when other methods are not restricted.
+    pub(crate) excluded: Vec<Method>,
+}
+
+impl GuardSummary {
+    /// Records a method guard.
+    pub(crate) fn restrict_method(&mut self, method: Method) {
+        self.restrict_methods(vec![method]);
+    }
+
+    /// Records guards that allow only `methods`; all guards must pass, so allowed methods are
+    /// intersected with those already recorded.
+    fn restrict_methods(&mut self, methods: Vec<Method>) {
+        let excluded = &self.excluded;
+
+        self.methods = Some(match self.methods.take() {
+            None => methods
+                .into_iter()
+                .filter(|m| !excluded.contains(m))
+                .collect(),
+            Some(own) => own.into_iter().filter(|m| methods.contains(m)).collect(),
+        });
+    }
+
+    /// Records guards that reject `methods`.
+    fn exclude_methods(&mut self, methods: Vec<Method>) {
+        if let Some(own) = &mut self.methods {
+            own.retain(|m| !methods.contains(m));
+        }
+
+        self.excluded.extend(methods);
+    }
+
+    /// Records the guards summarized by `other` as applying in addition to these ones.
+    pub(crate) fn merge(&mut self, other: GuardSummary) {
+        self.exclude_methods(other.excluded);
+
+        if let Some(methods) = other.methods {
+            self.restrict_methods(methods);
+        }
+
+        self.opaque |= other.opaque;
+    }
+
+    /// Returns true if both summaries allow some request method.
+    pub(crate) fn methods_overlap(&self, other: &GuardSummary) -> bool {
+        match (&self.methods, &other.methods) {
+            (Some(a), Some(b)) => a.iter().any(|method| b.contains(method)),
+            (Some(methods), None) => methods.iter().any(|m| !other.excluded.contains(m)),
+            (None, Some(methods)) => methods.iter().any(|m| !self.excluded.contains(m)),
+            (None, None) => true,
+        }
     }
 }
 
@@ -135,7 +210,26 @@ impl Route {
     pub fn method(mut self, method: Method) -> Self {
         Rc::get_mut(&mut self.guards)
             .unwrap()
-            .push(Box::new(guard::Method(method)));
+            .push(Box::new(guard::Method(method.clone())));
+        self.summary.restrict_method(method);
+        self
+    }
+
+    /// Adds a guard matching the given methods or, if `exclude` is true, all other methods.
+    pub(crate) fn method_set(mut self, methods: &[Method], exclude: bool) -> Self {
+        Rc::get_mut(&mut self.guards)
+            .unwrap()
+            .push(Box::new(guard::MethodSetGuard::new(
+                methods.to_vec(),
+                exclude,
+            )));
+
+        if exclude {
+            self.summary.exclude_methods(methods.to_vec());
+        } else {
+            self.summary.restrict_methods(methods.to_vec());
+        }
+
         self
     }
 
@@ -155,6 +249,7 @@ impl Route {
     /// ```
     pub fn guard<F: Guard + 'static>(mut self, f: F) -> Self {
         Rc::get_mut(&mut self.guards).unwrap().push(Box::new(f));
+        self.summary.opaque = true;
         self
     }
 
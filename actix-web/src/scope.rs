@@ -1,4 +1,4 @@
-use std::{cell::RefCell, fmt, future::Future, mem, rc::Rc};
+use std::{cell::RefCell, fmt, future::Future, mem, panic::Location, rc::Rc};
 
 use actix_http::{body::MessageBody, Extensions};
 use actix_router::{ResourceDef, Router};
@@ -10,8 +10,8 @@ use futures_core::future::LocalBoxFuture;
 use futures_util::future::join_all;
 
 use crate::{
-    config::ServiceConfig,
-    data::Data,
+    config::{self, ServiceConfig},
+    data::{Data, DataLocations},
     dev::AppService,
     guard::Guard,
     rmap::ResourceMap,
@@ -58,9 +58,11 @@ pub struct Scope<T = ScopeEndpoint> {
     endpoint: T,
     rdef: String,
     app_data: Option<Extensions>,
+    data_locations: DataLocations,
     services: Vec<Box<dyn AppServiceFactory>>,
     guards: Vec<Box<dyn Guard>>,
     default: Option<Rc<BoxedHttpServiceFactory>>,
+    config_default: Option<&'static Location<'static>>,
     external: Vec<ResourceDef>,
     factory_ref: Rc<RefCell<Option<ScopeFactory>>>,
 }
@@ -74,9 +76,11 @@ impl Scope {
             endpoint: ScopeEndpoint::new(Rc::clone(&factory_ref)),
             rdef: path.to_string(),
             app_data: None,
+            data_locations: DataLocations::default(),
             guards: Vec::new(),
             services: Vec::new(),
             default: None,
+            config_default: None,
             external: Vec::new(),
             factory_ref,
         }
@@ -142,8 +146,29 @@ where
     ///         .route("/", web::get().to(handler))
     /// );
     /// ```
+    ///
+    /// In debug builds, registering data of the same type twice on this scope logs a warning; see
+    /// [`app_data_replace`](Self::app_data_replace).
     #[doc(alias = "manage")]
+    #[track_caller]
     pub fn app_data<U: 'static>(mut self, data: U) -> Self {
+        self.data_locations.register::<U>();
+        self.app_data
+            .get_or_insert_with(Extensions::new)
+            .insert(data);
+
+        self
+    }
+
+    /// Set application data, overwriting data of the same type registered earlier on this scope.
+    ///
+    /// In debug builds, [`app_data`](Self::app_data) logs a warning naming both call sites when a
+    /// type is registered twice on the same scope, since the first value is silently discarded.
+    /// Use this method instead where overwriting is intended, for example to replace a default
+    /// value set by a shared helper.
+    #[track_caller]
+    pub fn app_data_replace<U: 'static>(mut self, data: U) -> Self {
+        self.data_locations.replace::<U>();
         self.app_data
             .get_or_insert_with(Extensions::new)
             .insert(data);
@@ -155,6 +180,7 @@ where
     ///
     /// Deprecated in favor of [`app_data`](Self::app_data).
     #[deprecated(since = "4.0.0", note = "Use `.app_data(Data::new(val))` instead.")]
+    #[track_caller]
     pub fn data<U: 'static>(self, data: U) -> Self {
         self.app_data(Data::new(data))
     }
@@ -189,6 +215,7 @@ where
     {
         let mut cfg = ServiceConfig::new();
         cfg_fn(&mut cfg);
+        cfg.apply_scoping();
 
         self.services.extend(cfg.services);
         self.external.extend(cfg.external);
@@ -197,9 +224,16 @@ where
         self.app_data
             .get_or_insert_with(Extensions::new)
             .extend(cfg.app_data);
+        self.data_locations.merge(cfg.data_locations);
 
         if let Some(default) = cfg.default {
+            let location = cfg
+                .default_location
+                .expect("default service location is tracked");
+            config::check_default_conflict(self.config_default, location);
+
             self.default = Some(default);
+            self.config_default = Some(location);
         }
 
         self
@@ -323,9 +357,11 @@ where
             endpoint: apply(mw, self.endpoint),
             rdef: self.rdef,
             app_data: self.app_data,
+            data_locations: self.data_locations,
             guards: self.guards,
             services: self.services,
             default: self.default,
+            config_default: self.config_default,
             external: self.external,
             factory_ref: self.factory_ref,
         }
@@ -361,9 +397,11 @@ where
             endpoint: apply_fn_factory(self.endpoint, mw),
             rdef: self.rdef,
             app_data: self.app_data,
+            data_locations: self.data_locations,
             guards: self.guards,
             services: self.services,
             default: self.default,
+            config_default: self.config_default,
             external: self.external,
             factory_ref: self.factory_ref,
         }
@@ -387,6 +425,12 @@ where
 
         // register nested services
         let mut cfg = config.clone_config();
+        cfg.push_path_prefix(&self.rdef);
+        cfg.push_route_prefix(
+            ResourceDef::root_prefix(&self.rdef)
+                .pattern()
+                .unwrap_or_default(),
+        );
         self.services
             .into_iter()
             .for_each(|mut srv| srv.register(&mut cfg));
@@ -1,13 +1,18 @@
 use std::{
     any::Any,
-    cmp, fmt, io,
+    cmp, fmt,
+    future::Future,
+    io,
     marker::PhantomData,
     net,
     sync::{Arc, Mutex},
     time::Duration,
 };
 
-use actix_http::{body::MessageBody, Extensions, HttpService, KeepAlive, Request, Response};
+use actix_http::{
+    body::MessageBody, ConnectionClosed, DrainSignal, Extensions, HttpService, KeepAlive,
+    Request, Response,
+};
 use actix_server::{Server, ServerBuilder};
 use actix_service::{
     map_config, IntoServiceFactory, Service, ServiceFactory, ServiceFactoryExt as _,
@@ -21,18 +26,31 @@ use actix_tls::accept::rustls::reexports::ServerConfig as RustlsServerConfig;
 #[cfg(any(feature = "openssl", feature = "rustls"))]
 use actix_http::TlsAcceptorConfig;
 
-use crate::{config::AppConfig, Error};
+use crate::{
+    config::AppConfig,
+    worker::{WorkerCtx, WorkerInit, WorkerStart},
+    Error,
+};
 
 struct Socket {
     scheme: &'static str,
     addr: net::SocketAddr,
 }
 
+type ConnectionCloseHook = Arc<dyn Fn(&ConnectionClosed) + Send + Sync>;
+
 struct Config {
     host: Option<String>,
     keep_alive: KeepAlive,
     client_request_timeout: Duration,
     client_disconnect_timeout: Duration,
+    client_body_timeout: Duration,
+    strict_host_validation: bool,
+    h1_title_case_headers: bool,
+    max_response_header_size: usize,
+    max_response_headers: usize,
+    drain: Option<(DrainSignal, u32)>,
+    connection_close_hooks: Vec<ConnectionCloseHook>,
     #[cfg(any(feature = "openssl", feature = "rustls"))]
     tls_handshake_timeout: Option<Duration>,
 }
@@ -71,6 +89,8 @@ where
     builder: ServerBuilder,
     #[allow(clippy::type_complexity)]
     on_connect_fn: Option<Arc<dyn Fn(&dyn Any, &mut Extensions) + Send + Sync>>,
+    on_worker_start: Option<WorkerStart>,
+    handle_signals: bool,
     _phantom: PhantomData<(S, B)>,
 }
 
@@ -97,6 +117,13 @@ where
                 keep_alive: KeepAlive::default(),
                 client_request_timeout: Duration::from_secs(5),
                 client_disconnect_timeout: Duration::from_secs(1),
+                client_body_timeout: Duration::ZERO,
+                strict_host_validation: true,
+                h1_title_case_headers: false,
+                max_response_header_size: 256 * 1024,
+                max_response_headers: 1024,
+                drain: None,
+                connection_close_hooks: Vec::new(),
                 #[cfg(any(feature = "rustls", feature = "openssl"))]
                 tls_handshake_timeout: None,
             })),
@@ -104,6 +131,8 @@ where
             sockets: Vec::new(),
             builder: ServerBuilder::default(),
             on_connect_fn: None,
+            on_worker_start: None,
+            handle_signals: true,
             _phantom: PhantomData,
         }
     }
@@ -129,10 +158,51 @@ where
             sockets: self.sockets,
             builder: self.builder,
             on_connect_fn: Some(Arc::new(f)),
+            on_worker_start: self.on_worker_start,
+            handle_signals: self.handle_signals,
             _phantom: PhantomData,
         }
     }
 
+    /// Sets an async function that will be called once when each worker starts.
+    ///
+    /// It receives a [`WorkerCtx`] that provides the worker's index and can store worker-scoped
+    /// data. Worker data is available to handlers like app data (e.g., through the
+    /// [`Data`](crate::web::Data) extractor) and shadows app data of the same type. The worker
+    /// index is also available from
+    /// [`AppConfig::worker_index`](crate::dev::AppConfig::worker_index).
+    ///
+    /// The worker does not accept connections until the returned future has completed. The hook
+    /// only applies to listeners bound after this method is called.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use actix_web::{dev::WorkerCtx, web, App, HttpServer};
+    ///
+    /// async fn handler(shard: web::Data<String>) -> String {
+    ///     shard.to_string()
+    /// }
+    ///
+    /// #[actix_rt::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     HttpServer::new(|| App::new().route("/", web::get().to(handler)))
+    ///         .on_worker_start(|ctx: WorkerCtx| async move {
+    ///             ctx.app_data(web::Data::new(format!("shard-{}", ctx.index())));
+    ///         })
+    ///         .bind(("127.0.0.1", 8080))?
+    ///         .run()
+    ///         .await
+    /// }
+    /// ```
+    pub fn on_worker_start<CB, Fut>(mut self, f: CB) -> Self
+    where
+        CB: Fn(WorkerCtx) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        self.on_worker_start = Some(WorkerStart::new(f));
+        self
+    }
+
     /// Set number of workers to start.
     ///
     /// By default, the number of available physical CPUs is used as the worker count.
@@ -232,6 +302,129 @@ where
         self
     }
 
+    /// Sets the timeout for receiving HTTP/1 request body data.
+    ///
+    /// If a client sends no body data within this time while a handler is reading the payload,
+    /// the payload fails with [`PayloadError::Timeout`](crate::error::PayloadError::Timeout), which
+    /// renders as a 408 (Request Timeout) error, and the connection is closed after the response.
+    ///
+    /// To disable timeout set value to 0.
+    ///
+    /// By default, the body timeout is disabled.
+    pub fn client_body_timeout(self, dur: Duration) -> Self {
+        self.config.lock().unwrap().client_body_timeout = dur;
+        self
+    }
+
+    /// Sets whether HTTP/1 requests are checked for a consistent `Host` header.
+    ///
+    /// When enabled, HTTP/1.1 requests without a `Host` header, requests with more than one `Host`
+    /// header, and requests whose absolute-form target names a different authority than the `Host`
+    /// header are rejected with a `400 Bad Request` response. This keeps
+    /// [`ConnectionInfo::host`](crate::dev::ConnectionInfo::host) from depending on which of the
+    /// conflicting values is read.
+    ///
+    /// Disable this only to serve legacy clients that do not send a valid `Host` header.
+    ///
+    /// By default, strict host validation is enabled.
+    pub fn strict_host_validation(self, enabled: bool) -> Self {
+        self.config.lock().unwrap().strict_host_validation = enabled;
+        self
+    }
+
+    /// Sets whether HTTP/1 response header names are written in title case.
+    ///
+    /// Header names are written in lowercase by default. When enabled, they are written as
+    /// `Content-Type` instead of `content-type`, for legacy clients that match header names
+    /// case-sensitively. Names constructed with
+    /// [`from_static_preserve_case`](crate::http::header::PreserveCase::from_static_preserve_case)
+    /// keep their exact casing. HTTP/2 responses always use lowercase header names.
+    ///
+    /// By default, header names are written in lowercase.
+    pub fn h1_title_case_headers(self, enabled: bool) -> Self {
+        self.config.lock().unwrap().h1_title_case_headers = enabled;
+        self
+    }
+
+    /// Sets the limit on the size of HTTP/1 response header blocks, in bytes.
+    ///
+    /// A response whose header lines exceed the limit, such as one to which a middleware appended
+    /// thousands of `Set-Cookie` headers, is replaced by an empty 500 (Internal Server Error)
+    /// response, and an error naming the route pattern of the request is logged.
+    ///
+    /// By default, the limit is 256 KiB.
+    pub fn max_response_header_size(self, bytes: usize) -> Self {
+        self.config.lock().unwrap().max_response_header_size = bytes;
+        self
+    }
+
+    /// Sets the limit on the number of HTTP/1 response header lines.
+    ///
+    /// Each value of a multi-valued header counts as one line. Responses exceeding the limit are
+    /// handled as described in [`max_response_header_size`](Self::max_response_header_size).
+    ///
+    /// By default, the limit is 1024 header lines.
+    pub fn max_response_headers(self, count: usize) -> Self {
+        self.config.lock().unwrap().max_response_headers = count;
+        self
+    }
+
+    /// Rejects new requests on open connections once the server starts draining.
+    ///
+    /// After a graceful stop is signaled, HTTP/1 requests arriving on existing keep-alive
+    /// connections are answered with `503 Service Unavailable`, a `Retry-After` header set to
+    /// `retry_after` seconds, and `Connection: close`. Requests already being handled complete
+    /// normally. This lets load balancers move clients to other instances instead of waiting for
+    /// their connections to be dropped at the end of the shutdown timeout.
+    ///
+    /// Draining starts when the server receives `SIGTERM`, unless signal handling is disabled.
+    /// When stopping the server through a [`ServerHandle`](crate::dev::ServerHandle), call
+    /// [`DrainSignal::start`](crate::dev::DrainSignal::start) on the signal returned by
+    /// [`drain_signal`](Self::drain_signal) first.
+    ///
+    /// By default, requests are not rejected while draining.
+    pub fn reject_requests_while_draining(self, retry_after: u32) -> Self {
+        self.config.lock().unwrap().drain = Some((DrainSignal::new(), retry_after));
+        self
+    }
+
+    /// Registers a callback that runs in each worker when a connection closes.
+    ///
+    /// The callback receives the connection's protocol, peer address, number of requests, and
+    /// duration. Callbacks run in registration order.
+    ///
+    /// ```no_run
+    /// use actix_web::{App, HttpServer};
+    ///
+    /// # async fn run() -> std::io::Result<()> {
+    /// HttpServer::new(App::new)
+    ///     .on_connection_close(|conn| {
+    ///         log::info!("{} requests in {:?}", conn.requests(), conn.duration());
+    ///     })
+    ///     .bind(("127.0.0.1", 8080))?
+    ///     .run()
+    ///     .await
+    /// # }
+    /// ```
+    pub fn on_connection_close<CB>(self, f: CB) -> Self
+    where
+        CB: Fn(&ConnectionClosed) + Send + Sync + 'static,
+    {
+        self.config
+            .lock()
+            .unwrap()
+            .connection_close_hooks
+            .push(Arc::new(f));
+        self
+    }
+
+    /// Returns the signal that starts draining, if enabled using
+    /// [`reject_requests_while_draining`](Self::reject_requests_while_draining).
+    pub fn drain_signal(&self) -> Option<DrainSignal> {
+        let config = self.config.lock().unwrap();
+        config.drain.as_ref().map(|(signal, _)| signal.clone())
+    }
+
     /// Set TLS handshake timeout.
     ///
     /// Defines a timeout for TLS handshake. If the TLS handshake does not complete
@@ -277,6 +470,7 @@ where
     /// Disable signal handling
     pub fn disable_signals(mut self) -> Self {
         self.builder = self.builder.disable_signals();
+        self.handle_signals = false;
         self
     }
 
@@ -314,6 +508,7 @@ where
     pub fn listen(mut self, lst: net::TcpListener) -> io::Result<Self> {
         let cfg = self.config.clone();
         let factory = self.factory.clone();
+        let on_worker_start = self.on_worker_start.clone();
         let addr = lst.local_addr().unwrap();
         self.sockets.push(Socket {
             addr,
@@ -331,6 +526,11 @@ where
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
                         .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .client_body_timeout(c.client_body_timeout)
+                        .strict_host_validation(c.strict_host_validation)
+                        .h1_title_case_headers(c.h1_title_case_headers)
+                        .max_response_header_size(c.max_response_header_size)
+                        .max_response_headers(c.max_response_headers)
                         .local_addr(addr);
 
                     if let Some(handler) = on_connect_fn.clone() {
@@ -339,9 +539,19 @@ where
                         })
                     };
 
+                    if let Some((signal, retry_after)) = c.drain.clone() {
+                        svc = svc.reject_while_draining(signal, retry_after);
+                    }
+
+                    for hook in &c.connection_close_hooks {
+                        let hook = Arc::clone(hook);
+                        svc = svc.on_connection_close(move |conn| hook(conn));
+                    }
+
                     let fac = factory()
                         .into_factory()
                         .map_err(|err| err.into().error_response());
+                    let fac = WorkerInit::new(fac, on_worker_start.clone());
 
                     svc.finish(map_config(fac, move |_| {
                         AppConfig::new(false, host.clone(), addr)
@@ -370,6 +580,7 @@ where
         acceptor: SslAcceptor,
     ) -> io::Result<Self> {
         let factory = self.factory.clone();
+        let on_worker_start = self.on_worker_start.clone();
         let cfg = self.config.clone();
         let addr = lst.local_addr().unwrap();
         self.sockets.push(Socket {
@@ -389,6 +600,11 @@ where
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
                         .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .client_body_timeout(c.client_body_timeout)
+                        .strict_host_validation(c.strict_host_validation)
+                        .h1_title_case_headers(c.h1_title_case_headers)
+                        .max_response_header_size(c.max_response_header_size)
+                        .max_response_headers(c.max_response_headers)
                         .local_addr(addr);
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
@@ -397,9 +613,22 @@ where
                         svc
                     };
 
+                    let svc = match c.drain.clone() {
+                        Some((signal, retry_after)) => {
+                            svc.reject_while_draining(signal, retry_after)
+                        }
+                        None => svc,
+                    };
+
+                    let svc = c.connection_close_hooks.iter().fold(svc, |svc, hook| {
+                        let hook = Arc::clone(hook);
+                        svc.on_connection_close(move |conn| hook(conn))
+                    });
+
                     let fac = factory()
                         .into_factory()
                         .map_err(|err| err.into().error_response());
+                    let fac = WorkerInit::new(fac, on_worker_start.clone());
 
                     let acceptor_config = match c.tls_handshake_timeout {
                         Some(dur) => TlsAcceptorConfig::default().handshake_timeout(dur),
@@ -434,6 +663,7 @@ where
         config: RustlsServerConfig,
     ) -> io::Result<Self> {
         let factory = self.factory.clone();
+        let on_worker_start = self.on_worker_start.clone();
         let cfg = self.config.clone();
         let addr = lst.local_addr().unwrap();
         self.sockets.push(Socket {
@@ -452,7 +682,12 @@ where
                     let svc = HttpService::build()
                         .keep_alive(c.keep_alive)
                         .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout);
+                        .client_disconnect_timeout(c.client_disconnect_timeout)
+                        .client_body_timeout(c.client_body_timeout)
+                        .strict_host_validation(c.strict_host_validation)
+                        .h1_title_case_headers(c.h1_title_case_headers)
+                        .max_response_header_size(c.max_response_header_size)
+                        .max_response_headers(c.max_response_headers);
 
                     let svc = if let Some(handler) = on_connect_fn.clone() {
                         svc.on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext))
@@ -460,9 +695,22 @@ where
                         svc
                     };
 
+                    let svc = match c.drain.clone() {
+                        Some((signal, retry_after)) => {
+                            svc.reject_while_draining(signal, retry_after)
+                        }
+                        None => svc,
+                    };
+
+                    let svc = c.connection_close_hooks.iter().fold(svc, |svc, hook| {
+                        let hook = Arc::clone(hook);
+                        svc.on_connection_close(move |conn| hook(conn))
+                    });
+
                     let fac = factory()
                         .into_factory()
                         .map_err(|err| err.into().error_response());
+                    let fac = WorkerInit::new(fac, on_worker_start.clone());
 
                     let acceptor_config = match c.tls_handshake_timeout {
                         Some(dur) => TlsAcceptorConfig::default().handshake_timeout(dur),
@@ -561,6 +809,7 @@ where
 
         let cfg = self.config.clone();
         let factory = self.factory.clone();
+        let on_worker_start = self.on_worker_start.clone();
         let socket_addr =
             net::SocketAddr::new(net::IpAddr::V4(net::Ipv4Addr::new(127, 0, 0, 1)), 8080);
         self.sockets.push(Socket {
@@ -584,16 +833,31 @@ where
                 let mut svc = HttpService::build()
                     .keep_alive(c.keep_alive)
                     .client_request_timeout(c.client_request_timeout)
-                    .client_disconnect_timeout(c.client_disconnect_timeout);
+                    .client_disconnect_timeout(c.client_disconnect_timeout)
+                    .client_body_timeout(c.client_body_timeout)
+                    .strict_host_validation(c.strict_host_validation)
+                    .h1_title_case_headers(c.h1_title_case_headers)
+                    .max_response_header_size(c.max_response_header_size)
+                    .max_response_headers(c.max_response_headers);
 
                 if let Some(handler) = on_connect_fn.clone() {
                     svc = svc
                         .on_connect_ext(move |io: &_, ext: _| (handler)(io as &dyn Any, ext));
                 }
 
+                if let Some((signal, retry_after)) = c.drain.clone() {
+                    svc = svc.reject_while_draining(signal, retry_after);
+                }
+
+                for hook in &c.connection_close_hooks {
+                    let hook = Arc::clone(hook);
+                    svc = svc.on_connection_close(move |conn| hook(conn));
+                }
+
                 let fac = factory()
                     .into_factory()
                     .map_err(|err| err.into().error_response());
+                let fac = WorkerInit::new(fac, on_worker_start.clone());
 
                 svc.finish(map_config(fac, move |_| config.clone()))
             })
@@ -613,6 +877,7 @@ where
 
         let cfg = self.config.clone();
         let factory = self.factory.clone();
+        let on_worker_start = self.on_worker_start.clone();
         let socket_addr =
             net::SocketAddr::new(net::IpAddr::V4(net::Ipv4Addr::new(127, 0, 0, 1)), 8080);
 
@@ -635,14 +900,29 @@ where
                 let fac = factory()
                     .into_factory()
                     .map_err(|err| err.into().error_response());
+                let fac = WorkerInit::new(fac, on_worker_start.clone());
 
-                fn_service(|io: UnixStream| async { Ok((io, Protocol::Http1, None)) }).and_then(
-                    HttpService::build()
-                        .keep_alive(c.keep_alive)
-                        .client_request_timeout(c.client_request_timeout)
-                        .client_disconnect_timeout(c.client_disconnect_timeout)
-                        .finish(map_config(fac, move |_| config.clone())),
-                )
+                let mut svc = HttpService::build()
+                    .keep_alive(c.keep_alive)
+                    .client_request_timeout(c.client_request_timeout)
+                    .client_disconnect_timeout(c.client_disconnect_timeout)
+                    .client_body_timeout(c.client_body_timeout)
+                    .strict_host_validation(c.strict_host_validation)
+                    .h1_title_case_headers(c.h1_title_case_headers)
+                    .max_response_header_size(c.max_response_header_size)
+                    .max_response_headers(c.max_response_headers);
+
+                if let Some((signal, retry_after)) = c.drain.clone() {
+                    svc = svc.reject_while_draining(signal, retry_after);
+                }
+
+                for hook in &c.connection_close_hooks {
+                    let hook = Arc::clone(hook);
+                    svc = svc.on_connection_close(move |conn| hook(conn));
+                }
+
+                fn_service(|io: UnixStream| async { Ok((io, Protocol::Http1, None)) })
+                    .and_then(svc.finish(map_config(fac, move |_| config.clone())))
             },
         )?;
 
@@ -683,6 +963,28 @@ where
     /// }
     /// ```
     pub fn run(self) -> Server {
+        #[cfg(unix)]
+        if self.handle_signals {
+            let drain = self.config.lock().unwrap().drain.clone();
+
+            if let Some((signal, _)) = drain {
+                actix_rt::spawn(async move {
+                    use actix_rt::signal::unix::{signal as unix_signal, SignalKind};
+
+                    match unix_signal(SignalKind::terminate()) {
+                        Ok(mut term) => {
+                            if term.recv().await.is_some() {
+                                signal.start();
+                            }
+                        }
+                        Err(err) => {
+                            log::error!("Can not initialize drain signal handler: {}", err)
+                        }
+                    }
+                });
+            }
+        }
+
         self.builder.run()
     }
 }
@@ -1,12 +1,13 @@
 use std::{
     cell::{Ref, RefMut},
+    error::Error as StdError,
     fmt, net,
     rc::Rc,
 };
 
 use actix_http::{
-    body::{BoxBody, EitherBody, MessageBody},
-    header::HeaderMap,
+    body::{BodySize, BoxBody, EitherBody, MessageBody},
+    header::{self, HeaderMap},
     BoxedPayloadStream, Extensions, HttpMessage, Method, Payload, RequestHead, Response,
     ResponseHead, StatusCode, Uri, Version,
 };
@@ -15,12 +16,15 @@ use actix_service::{
     boxed::{BoxService, BoxServiceFactory},
     IntoServiceFactory, ServiceFactory,
 };
+use bytes::{Bytes, BytesMut};
 #[cfg(feature = "cookies")]
 use cookie::{Cookie, ParseError as CookieParseError};
+use futures_util::future::poll_fn;
 
 use crate::{
     config::{AppConfig, AppService},
     dev::ensure_leading_slash,
+    error::ErrorInternalServerError,
     guard::{Guard, GuardContext},
     info::ConnectionInfo,
     rmap::ResourceMap,
@@ -183,6 +187,17 @@ impl ServiceRequest {
         &self.head().uri
     }
 
+    /// Counterpart to [`HttpRequest::original_uri`].
+    #[inline]
+    pub fn original_uri(&self) -> &Uri {
+        self.req.original_uri()
+    }
+
+    /// Replaces the request URI, keeping the original for [`original_uri`](Self::original_uri).
+    pub(crate) fn rewrite_uri(&mut self, uri: Uri) {
+        self.req.rewrite_uri(uri)
+    }
+
     /// Returns the request method.
     #[inline]
     pub fn method(&self) -> &Method {
@@ -500,6 +515,74 @@ impl<B> ServiceResponse<B> {
         self.map_body(|_, body| body.boxed())
     }
 
+    /// Map the current body to another body type using a closure, then box it. Returns a new
+    /// response.
+    ///
+    /// Middleware that only sometimes replaces the body can use this for both outcomes so that
+    /// its response type is `ServiceResponse<BoxBody>`, instead of using `EitherBody`.
+    #[inline]
+    pub fn map_boxed_body<F, B2>(self, f: F) -> ServiceResponse<BoxBody>
+    where
+        F: FnOnce(&mut ResponseHead, B) -> B2,
+        B2: MessageBody + 'static,
+    {
+        self.map_body(|head, body| f(head, body).boxed())
+    }
+
+    /// Replaces the response body, returning the new response and the old body.
+    ///
+    /// The response head is kept as-is; headers that describe the old body, such as
+    /// `Content-Type`, are not updated.
+    pub fn replace_body<B2>(self, body: B2) -> (ServiceResponse<B2>, B) {
+        let (response, old_body) = self.response.into_parts();
+        let response = response.set_body(body);
+
+        (
+            ServiceResponse {
+                response,
+                request: self.request,
+            },
+            old_body,
+        )
+    }
+
+    /// Collects the response body into memory and installs the collected bytes as the new body.
+    ///
+    /// Returns the collected bytes and the response, which can then be inspected or changed before
+    /// being returned from a middleware. Both sized and streaming bodies are supported.
+    ///
+    /// # Errors
+    /// Returns an error, discarding the response, if the body is larger than `limit` bytes or if
+    /// reading the body fails.
+    ///
+    /// # Examples
+    /// ```
+    /// use actix_web::{body::MessageBody, dev::ServiceResponse, Error};
+    ///
+    /// // in a middleware, after calling the wrapped service
+    /// async fn add_footer<B: MessageBody>(res: ServiceResponse<B>) -> Result<ServiceResponse, Error> {
+    ///     let (body, res) = res.buffer_body(64 * 1024).await?;
+    ///
+    ///     let mut body = body.to_vec();
+    ///     body.extend_from_slice(b"\n-- footer");
+    ///
+    ///     let (res, _) = res.replace_body(body);
+    ///     Ok(res.map_into_boxed_body())
+    /// }
+    /// ```
+    pub async fn buffer_body(
+        self,
+        limit: usize,
+    ) -> Result<(Bytes, ServiceResponse<BoxBody>), Error>
+    where
+        B: MessageBody,
+    {
+        let (res, body) = self.replace_body(());
+        let body = collect_body(body, limit).await?;
+        let (res, _) = res.replace_body(BoxBody::new(body.clone()));
+        Ok((body, res))
+    }
+
     /// Consumes the response and returns its body.
     #[inline]
     pub fn into_body(self) -> B {
@@ -507,6 +590,44 @@ impl<B> ServiceResponse<B> {
     }
 }
 
+/// Reads `body` into memory, failing if it is larger than `limit` bytes.
+async fn collect_body<B: MessageBody>(body: B, limit: usize) -> Result<Bytes, Error> {
+    fn overflow(limit: usize) -> Error {
+        ErrorInternalServerError(format!("response body is larger than {} bytes", limit))
+    }
+
+    fn body_error<E: Into<Box<dyn StdError>>>(err: E) -> Error {
+        let err: Box<dyn StdError> = err.into();
+        err.into()
+    }
+
+    if let BodySize::Sized(len) = body.size() {
+        if len > limit as u64 {
+            return Err(overflow(limit));
+        }
+    }
+
+    let body = match body.try_into_bytes() {
+        Ok(body) => return Ok(body),
+        Err(body) => body,
+    };
+
+    let mut body = Box::pin(body);
+    let mut buf = BytesMut::new();
+
+    while let Some(chunk) = poll_fn(|cx| body.as_mut().poll_next(cx)).await {
+        let chunk = chunk.map_err(body_error)?;
+
+        if buf.len() + chunk.len() > limit {
+            return Err(overflow(limit));
+        }
+
+        buf.extend_from_slice(&chunk);
+    }
+
+    Ok(buf.freeze())
+}
+
 impl<B> From<ServiceResponse<B>> for HttpResponse<B> {
     fn from(res: ServiceResponse<B>) -> HttpResponse<B> {
         res.response
@@ -515,7 +636,67 @@ impl<B> From<ServiceResponse<B>> for HttpResponse<B> {
 
 impl<B> From<ServiceResponse<B>> for Response<B> {
     fn from(res: ServiceResponse<B>) -> Response<B> {
-        res.response.into()
+        res.request.mark_responded();
+
+        let route = res.request.response_route();
+        let observer = res
+            .request
+            .app_state()
+            .lifecycle()
+            .and_then(|hooks| hooks.observe_response(&res.request, res.response.status()));
+
+        let mut res: Response<B> = res.response.into();
+        res.extensions_mut().insert(route);
+
+        if let Some(observer) = observer {
+            res.extensions_mut().insert(observer);
+        }
+
+        res
+    }
+}
+
+/// Logs an error if a manually set `Content-Length` header does not match the length of the body.
+///
+/// Responses are framed using the body's length, so the mismatched header is replaced when the
+/// response is sent. Only checked in debug builds.
+#[cfg(debug_assertions)]
+pub(crate) fn check_content_length<B: MessageBody>(req: &HttpRequest, res: &HttpResponse<B>) {
+    let status = res.status();
+
+    // these may declare the length of a body that is not sent
+    if req.method() == Method::HEAD
+        || status.is_informational()
+        || status == StatusCode::NO_CONTENT
+        || status == StatusCode::NOT_MODIFIED
+    {
+        return;
+    }
+
+    let actual = match res.body().size() {
+        BodySize::Sized(len) => len,
+        BodySize::None | BodySize::Stream => return,
+    };
+
+    let declared = match res
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+    {
+        Some(declared) => declared,
+        None => return,
+    };
+
+    if declared != actual {
+        log::error!(
+            "Response to {} {} (route: {}) sets `Content-Length: {}` but its body is {} bytes",
+            req.method(),
+            req.path(),
+            req.match_pattern().as_deref().unwrap_or("unknown"),
+            declared,
+            actual,
+        );
     }
 }
 
@@ -875,4 +1056,65 @@ mod tests {
         let req = test::TestRequest::default().to_request();
         let _res = test::call_service(&app, req).await;
     }
+
+    fn streaming_response(chunks: &'static [&'static str]) -> ServiceResponse {
+        let chunks = chunks
+            .iter()
+            .map(|chunk| Ok::<_, Error>(Bytes::from_static(chunk.as_bytes())));
+
+        let res = HttpResponse::Ok().streaming(futures_util::stream::iter(chunks));
+        ServiceResponse::new(TestRequest::default().to_http_request(), res)
+    }
+
+    #[actix_rt::test]
+    async fn buffer_streaming_body_under_limit() {
+        let res = streaming_response(&["hello", " ", "world"]);
+        assert_eq!(res.response().body().size(), BodySize::Stream);
+
+        let (body, res) = res.buffer_body(11).await.unwrap();
+        assert_eq!(body, "hello world");
+        assert_eq!(res.response().body().size(), BodySize::Sized(11));
+        assert_eq!(test::read_body(res).await, "hello world");
+    }
+
+    #[actix_rt::test]
+    async fn buffer_streaming_body_over_limit() {
+        let res = streaming_response(&["hello", " ", "world"]);
+        let err = res.buffer_body(10).await.unwrap_err();
+        assert_eq!(
+            err.as_response_error().status_code(),
+            http::StatusCode::INTERNAL_SERVER_ERROR
+        );
+
+        // sized bodies are rejected without being read
+        let res = ServiceResponse::new(
+            TestRequest::default().to_http_request(),
+            HttpResponse::Ok().body("hello world"),
+        );
+        assert!(res.buffer_body(10).await.is_err());
+    }
+
+    #[actix_rt::test]
+    async fn replace_and_map_boxed_body() {
+        let res = ServiceResponse::new(
+            TestRequest::default().to_http_request(),
+            HttpResponse::Ok()
+                .insert_header(("x-test", "1"))
+                .body("old"),
+        );
+
+        let (res, old) = res.replace_body("new");
+        assert_eq!(old.try_into_bytes().unwrap(), "old");
+        assert_eq!(res.headers().get("x-test").unwrap(), "1");
+
+        let res = res.map_boxed_body(|head, body| {
+            head.headers.insert(
+                http::header::CONTENT_TYPE,
+                http::header::HeaderValue::from_static("text/plain"),
+            );
+            format!("{}er", body)
+        });
+        assert_eq!(res.headers().get("content-type").unwrap(), "text/plain");
+        assert_eq!(test::read_body(res).await, "newer");
+    }
 }
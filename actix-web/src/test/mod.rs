@@ -11,26 +11,38 @@
 //! - [`TestRequest`]
 //! - [`call_service`]
 //! - [`call_and_read_body`]
+//! - [`call_and_read_body_checked`]
 //! - [`call_and_read_body_json`]
 //!
 //! # Reading Response Payloads
 //! - [`read_body`]
+//! - [`read_body_checked`]
 //! - [`read_body_json`]
+//!
+//! # Asserting Redirects
+//! - [`assert_redirect`]
+//! - [`assert_no_redirect`]
+//!
+//! # Comparing Headers
+//! - [`HeaderSnapshot`]
 
 // TODO: more docs on generally how testing works with these parts
 
 pub use actix_http::test::TestBuffer;
 
+mod test_headers;
 mod test_request;
 mod test_services;
 mod test_utils;
 
+pub use self::test_headers::{HeaderChange, HeaderDiff, HeaderSnapshot};
 pub use self::test_request::TestRequest;
 #[allow(deprecated)]
 pub use self::test_services::{default_service, ok_service, simple_service, status_service};
 #[allow(deprecated)]
 pub use self::test_utils::{
-    call_and_read_body, call_and_read_body_json, call_service, init_service, read_body,
+    assert_no_redirect, assert_redirect, call_and_read_body, call_and_read_body_checked,
+    call_and_read_body_json, call_service, init_service, read_body, read_body_checked,
     read_body_json, read_response, read_response_json,
 };
 
@@ -0,0 +1,223 @@
+use std::fmt;
+
+use crate::http::header::{HeaderMap, HeaderName, HeaderValue};
+
+/// A copy of a [`HeaderMap`], taken to find out how the map changes afterwards.
+///
+/// Useful for testing middleware and helpers that set, replace, or remove headers.
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header, test::HeaderSnapshot, HttpResponse};
+///
+/// let mut res = HttpResponse::Ok()
+///     .insert_header((header::CONTENT_LENGTH, "5"))
+///     .finish();
+/// let snapshot = HeaderSnapshot::new(res.headers());
+///
+/// res.headers_mut().remove(header::CONTENT_LENGTH);
+/// res.headers_mut()
+///     .insert(header::CACHE_CONTROL, header::HeaderValue::from_static("no-store"));
+///
+/// let diff = snapshot.diff(res.headers());
+/// assert_eq!(diff.added(), [(header::CACHE_CONTROL, vec!["no-store".parse().unwrap()])]);
+/// assert_eq!(diff.removed(), [(header::CONTENT_LENGTH, vec!["5".parse().unwrap()])]);
+/// assert!(diff.changed().is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct HeaderSnapshot {
+    headers: HeaderMap,
+}
+
+impl HeaderSnapshot {
+    /// Takes a snapshot of `headers`.
+    pub fn new(headers: &HeaderMap) -> Self {
+        Self {
+            headers: headers.clone(),
+        }
+    }
+
+    /// Returns the headers that were added, removed, or changed in `headers` since the snapshot
+    /// was taken.
+    ///
+    /// Headers are compared by name, including all of their values in order.
+    pub fn diff(&self, headers: &HeaderMap) -> HeaderDiff {
+        let mut diff = HeaderDiff::default();
+
+        for name in sorted_names(&self.headers) {
+            let before = values(&self.headers, name);
+
+            if !headers.contains_key(name) {
+                diff.removed.push((name.clone(), before));
+                continue;
+            }
+
+            let after = values(headers, name);
+
+            if before != after {
+                diff.changed.push(HeaderChange {
+                    name: name.clone(),
+                    before,
+                    after,
+                });
+            }
+        }
+
+        for name in sorted_names(headers) {
+            if !self.headers.contains_key(name) {
+                diff.added.push((name.clone(), values(headers, name)));
+            }
+        }
+
+        diff
+    }
+}
+
+/// Differences between a [`HeaderSnapshot`] and a later state of a header map.
+///
+/// Each list is sorted by header name. The `Display` implementation lists all differences, one
+/// per line, for use in assertion messages.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeaderDiff {
+    added: Vec<(HeaderName, Vec<HeaderValue>)>,
+    removed: Vec<(HeaderName, Vec<HeaderValue>)>,
+    changed: Vec<HeaderChange>,
+}
+
+impl HeaderDiff {
+    /// Returns true if the header maps are equal.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Returns the headers that were added, with their values.
+    pub fn added(&self) -> &[(HeaderName, Vec<HeaderValue>)] {
+        &self.added
+    }
+
+    /// Returns the headers that were removed, with the values they had.
+    pub fn removed(&self) -> &[(HeaderName, Vec<HeaderValue>)] {
+        &self.removed
+    }
+
+    /// Returns the headers whose values changed.
+    pub fn changed(&self) -> &[HeaderChange] {
+        &self.changed
+    }
+}
+
+impl fmt::Display for HeaderDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("(no changes)");
+        }
+
+        for (name, values) in &self.added {
+            writeln!(f, "+ {}: {:?}", name, values)?;
+        }
+
+        for (name, values) in &self.removed {
+            writeln!(f, "- {}: {:?}", name, values)?;
+        }
+
+        for change in &self.changed {
+            writeln!(
+                f,
+                "~ {}: {:?} -> {:?}",
+                change.name, change.before, change.after
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A header whose values differ between a [`HeaderSnapshot`] and a later state of a header map.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderChange {
+    /// Name of the header.
+    pub name: HeaderName,
+
+    /// Values in the snapshot.
+    pub before: Vec<HeaderValue>,
+
+    /// Values in the later header map.
+    pub after: Vec<HeaderValue>,
+}
+
+fn sorted_names(headers: &HeaderMap) -> Vec<&HeaderName> {
+    let mut names = headers.keys().collect::<Vec<_>>();
+    names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+    names
+}
+
+fn values(headers: &HeaderMap, name: &HeaderName) -> Vec<HeaderValue> {
+    headers.get_all(name).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::header::{self, CacheControl, CacheDirective, TryIntoHeaderValue as _};
+
+    fn value(val: &'static str) -> HeaderValue {
+        HeaderValue::from_static(val)
+    }
+
+    #[test]
+    fn added_and_removed() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_LENGTH, value("42"));
+        headers.insert(header::CONTENT_TYPE, value("text/plain"));
+
+        let snapshot = HeaderSnapshot::new(&headers);
+        assert!(snapshot.diff(&headers).is_empty());
+
+        headers.remove(header::CONTENT_LENGTH);
+        headers.insert(
+            header::CACHE_CONTROL,
+            CacheControl(vec![CacheDirective::NoStore])
+                .try_into_value()
+                .unwrap(),
+        );
+
+        let diff = snapshot.diff(&headers);
+        assert_eq!(
+            diff.added(),
+            [(header::CACHE_CONTROL, vec![value("no-store")])]
+        );
+        assert_eq!(
+            diff.removed(),
+            [(header::CONTENT_LENGTH, vec![value("42")])]
+        );
+        assert!(diff.changed().is_empty());
+        assert_eq!(
+            diff.to_string(),
+            "+ cache-control: [\"no-store\"]\n- content-length: [\"42\"]\n"
+        );
+    }
+
+    #[test]
+    fn changed_values() {
+        let mut headers = HeaderMap::new();
+        headers.append(header::VARY, value("Origin"));
+        headers.insert(header::CONTENT_TYPE, value("text/plain"));
+
+        let snapshot = HeaderSnapshot::new(&headers);
+
+        // appending is a change to the existing header
+        headers.append(header::VARY, value("Accept-Encoding"));
+
+        let diff = snapshot.diff(&headers);
+        assert!(diff.added().is_empty());
+        assert!(diff.removed().is_empty());
+        assert_eq!(
+            diff.changed(),
+            [HeaderChange {
+                name: header::VARY,
+                before: vec![value("Origin")],
+                after: vec![value("Origin"), value("Accept-Encoding")],
+            }]
+        );
+    }
+}
@@ -264,7 +264,7 @@ impl TestRequest {
         head.peer_addr = self.peer_addr;
         self.path.get_mut().update(&head.uri);
 
-        let app_state = AppInitServiceState::new(Rc::new(self.rmap), self.config.clone());
+        let app_state = AppInitServiceState::new(Rc::new(self.rmap), self.config.clone(), None);
 
         ServiceRequest::new(
             HttpRequest::new(
@@ -290,7 +290,7 @@ impl TestRequest {
         head.peer_addr = self.peer_addr;
         self.path.get_mut().update(&head.uri);
 
-        let app_state = AppInitServiceState::new(Rc::new(self.rmap), self.config.clone());
+        let app_state = AppInitServiceState::new(Rc::new(self.rmap), self.config.clone(), None);
 
         HttpRequest::new(
             self.path,
@@ -308,7 +308,7 @@ impl TestRequest {
         head.peer_addr = self.peer_addr;
         self.path.get_mut().update(&head.uri);
 
-        let app_state = AppInitServiceState::new(Rc::new(self.rmap), self.config.clone());
+        let app_state = AppInitServiceState::new(Rc::new(self.rmap), self.config.clone(), None);
 
         let req = HttpRequest::new(
             self.path,
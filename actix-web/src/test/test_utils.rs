@@ -5,9 +5,10 @@ use actix_service::IntoServiceFactory;
 use serde::de::DeserializeOwned;
 
 use crate::{
-    body::{self, MessageBody},
+    body::{self, BodySize, MessageBody},
     config::AppConfig,
     dev::{Service, ServiceFactory},
+    http::header,
     service::ServiceResponse,
     web::Bytes,
     Error,
@@ -140,6 +141,46 @@ where
     read_body(res).await
 }
 
+/// Helper function that returns a response body of a TestRequest, optionally asserting that the
+/// declared length of the response matches the body.
+///
+/// See [`read_body_checked`] for the checks performed when `check_content_length` is true.
+///
+/// # Examples
+/// ```
+/// use actix_web::{test, web, App, HttpResponse};
+///
+/// #[actix_web::test]
+/// async fn test_index() {
+///     let app = test::init_service(
+///         App::new().route("/", web::get().to(|| async { "welcome!" })),
+///     )
+///     .await;
+///
+///     let req = test::TestRequest::get().uri("/").to_request();
+///     let result = test::call_and_read_body_checked(&app, req, true).await;
+///     assert_eq!(result, "welcome!");
+/// }
+/// ```
+///
+/// # Panics
+/// Panics if:
+/// - service call returns error;
+/// - body yields an error while it is being read;
+/// - `check_content_length` is true and the declared length does not match the body.
+pub async fn call_and_read_body_checked<S, B>(
+    app: &S,
+    req: Request,
+    check_content_length: bool,
+) -> Bytes
+where
+    S: Service<Request, Response = ServiceResponse<B>, Error = Error>,
+    B: MessageBody,
+{
+    let res = call_service(app, req).await;
+    read_body_checked(res, check_content_length).await
+}
+
 #[doc(hidden)]
 #[deprecated(since = "4.0.0", note = "Renamed to `call_and_read_body`.")]
 pub async fn read_response<S, B>(app: &S, req: Request) -> Bytes
@@ -192,6 +233,59 @@ where
         .expect("error reading test response body")
 }
 
+/// Helper function that returns a response body of a ServiceResponse, optionally asserting that
+/// the declared length of the response matches the body.
+///
+/// When `check_content_length` is true, both the [`ContentLength`](header::ContentLength) header,
+/// if present, and the size reported by the body type, if known, are compared to the number of
+/// bytes read. This surfaces framing bugs, such as handlers setting a stale `Content-Length`, that
+/// would otherwise only show up as truncated or hanging responses on a real connection.
+///
+/// # Panics
+/// Panics if:
+/// - body yields an error while it is being read;
+/// - `check_content_length` is true and the `Content-Length` header is invalid;
+/// - `check_content_length` is true and the declared length does not match the body.
+pub async fn read_body_checked<B>(res: ServiceResponse<B>, check_content_length: bool) -> Bytes
+where
+    B: MessageBody,
+{
+    if !check_content_length {
+        return read_body(res).await;
+    }
+
+    let declared = res.headers().get(header::CONTENT_LENGTH).map(|hdr| {
+        header::from_one_raw_str::<usize>(Some(hdr))
+            .map(header::ContentLength)
+            .unwrap_or_else(|_| panic!("invalid Content-Length header: {:?}", hdr))
+    });
+
+    let size = res.response().body().size();
+    let body = read_body(res).await;
+
+    if let Some(header::ContentLength(len)) = declared {
+        assert_eq!(
+            len,
+            body.len(),
+            "Content-Length header declares {} bytes but body has {} bytes",
+            len,
+            body.len(),
+        );
+    }
+
+    if let BodySize::Sized(size) = size {
+        assert_eq!(
+            size,
+            body.len() as u64,
+            "body type declares {} bytes but yielded {} bytes",
+            size,
+            body.len(),
+        );
+    }
+
+    body
+}
+
 /// Helper function that returns a deserialized response body of a ServiceResponse.
 ///
 /// # Examples
@@ -314,6 +408,86 @@ where
     call_and_read_body_json(app, req).await
 }
 
+/// Asserts that a response is a redirect (3xx) to `location`.
+///
+/// # Examples
+/// ```
+/// use actix_web::{test, web, App};
+///
+/// #[actix_web::test]
+/// async fn test_redirect() {
+///     let app = test::init_service(
+///         App::new().service(web::Redirect::from("/old").to_absolute("/new")),
+///     )
+///     .await;
+///
+///     let req = test::TestRequest::with_uri("/old").to_request();
+///     let res = test::call_service(&app, req).await;
+///     test::assert_redirect(&res, "/new");
+/// }
+/// ```
+///
+/// # Panics
+/// Panics if the response status is not 3xx or the `Location` header is missing or does not
+/// equal `location`.
+#[track_caller]
+pub fn assert_redirect<B>(res: &ServiceResponse<B>, location: &str) {
+    let status = res.status();
+    let actual = res.headers().get(header::LOCATION);
+
+    assert!(
+        status.is_redirection(),
+        "expected a redirect to {:?} but response status is {} (Location: {:?})",
+        location,
+        status,
+        actual,
+    );
+
+    assert_eq!(
+        actual.map(|hdr| hdr.as_bytes()),
+        Some(location.as_bytes()),
+        "expected a redirect to {:?} but Location header is {:?}",
+        location,
+        actual,
+    );
+}
+
+/// Asserts that a response is not a redirect; i.e., the request was passed through.
+///
+/// # Examples
+/// ```
+/// use actix_web::{test, web, App, HttpResponse};
+///
+/// #[actix_web::test]
+/// async fn test_pass_through() {
+///     let app = test::init_service(
+///         App::new()
+///             .service(web::Redirect::from("/old").to_absolute("/new"))
+///             .route("/new", web::get().to(HttpResponse::Ok)),
+///     )
+///     .await;
+///
+///     let req = test::TestRequest::with_uri("/new").to_request();
+///     let res = test::call_service(&app, req).await;
+///     test::assert_no_redirect(&res);
+/// }
+/// ```
+///
+/// # Panics
+/// Panics if the response status is 3xx or a `Location` header is present.
+#[track_caller]
+pub fn assert_no_redirect<B>(res: &ServiceResponse<B>) {
+    let status = res.status();
+    let location = res.headers().get(header::LOCATION);
+
+    assert!(
+        !status.is_redirection() && location.is_none(),
+        "expected no redirect but response status is {} (Location: {:?})",
+        status,
+        location,
+    );
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -321,7 +495,7 @@ mod tests {
 
     use super::*;
     use crate::{
-        dev::ServiceRequest, http::header, test::TestRequest, web, App, HttpMessage,
+        dev::ServiceRequest, http::StatusCode, test::TestRequest, web, App, HttpMessage,
         HttpResponse,
     };
 
@@ -502,4 +676,108 @@ mod tests {
             let _: String = read_body_json(call_service(&svc, req.pop().unwrap()).await).await;
         }
     }
+
+    #[actix_rt::test]
+    async fn checked_body_matching_length() {
+        let app = init_service(
+            App::new()
+                .route("/text", web::get().to(|| async { "hello" }))
+                .route(
+                    "/declared",
+                    web::get().to(|| {
+                        HttpResponse::Ok()
+                            .insert_header(header::ContentLength(5))
+                            .body("hello")
+                    }),
+                ),
+        )
+        .await;
+
+        for uri in ["/text", "/declared"] {
+            let req = TestRequest::with_uri(uri).to_request();
+            assert_eq!(call_and_read_body_checked(&app, req, true).await, "hello");
+        }
+    }
+
+    async fn mismatched_length_app(
+    ) -> impl Service<Request, Response = ServiceResponse, Error = Error> {
+        init_service(App::new().route(
+            "/",
+            web::get().to(|| {
+                HttpResponse::Ok()
+                    .insert_header(header::ContentLength(10))
+                    .body("hello")
+            }),
+        ))
+        .await
+    }
+
+    #[actix_rt::test]
+    async fn unchecked_body_mismatched_length() {
+        let app = mismatched_length_app().await;
+        let req = TestRequest::default().to_request();
+        assert_eq!(call_and_read_body_checked(&app, req, false).await, "hello");
+    }
+
+    #[actix_rt::test]
+    #[should_panic(expected = "Content-Length header declares 10 bytes but body has 5 bytes")]
+    async fn checked_body_mismatched_length() {
+        let app = mismatched_length_app().await;
+        let req = TestRequest::default().to_request();
+        call_and_read_body_checked(&app, req, true).await;
+    }
+
+    async fn redirect_app() -> impl Service<Request, Response = ServiceResponse, Error = Error>
+    {
+        init_service(
+            App::new()
+                .service(web::Redirect::from("/old").to_absolute("/new"))
+                .route("/new", web::get().to(HttpResponse::Ok))
+                .route(
+                    "/sneaky",
+                    web::get().to(|| {
+                        HttpResponse::Ok()
+                            .insert_header((header::LOCATION, "/elsewhere"))
+                            .finish()
+                    }),
+                ),
+        )
+        .await
+    }
+
+    #[actix_rt::test]
+    async fn assert_redirect_helpers() {
+        let app = redirect_app().await;
+
+        let res = call_service(&app, TestRequest::with_uri("/old").to_request()).await;
+        assert_eq!(res.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_redirect(&res, "/new");
+
+        let res = call_service(&app, TestRequest::with_uri("/new").to_request()).await;
+        assert_no_redirect(&res);
+    }
+
+    #[actix_rt::test]
+    #[should_panic(expected = "expected no redirect but response status is 308")]
+    async fn assert_no_redirect_fails_on_redirect() {
+        let app = redirect_app().await;
+        let res = call_service(&app, TestRequest::with_uri("/old").to_request()).await;
+        assert_no_redirect(&res);
+    }
+
+    #[actix_rt::test]
+    #[should_panic(expected = "expected no redirect but response status is 200 OK (Location")]
+    async fn assert_no_redirect_fails_on_location_header() {
+        let app = redirect_app().await;
+        let res = call_service(&app, TestRequest::with_uri("/sneaky").to_request()).await;
+        assert_no_redirect(&res);
+    }
+
+    #[actix_rt::test]
+    #[should_panic(expected = "expected a redirect to \"/other\" but Location header is")]
+    async fn assert_redirect_fails_on_wrong_location() {
+        let app = redirect_app().await;
+        let res = call_service(&app, TestRequest::with_uri("/old").to_request()).await;
+        assert_redirect(&res, "/other");
+    }
 }
@@ -0,0 +1,63 @@
+//! Built-in `TRACE` responder. See [`App::enable_trace`](crate::App::enable_trace).
+
+use bytes::{BufMut as _, BytesMut};
+
+use crate::{
+    http::header::{self, HeaderName},
+    HttpRequest, HttpResponse,
+};
+
+/// Echoes received request heads back to the client as a `message/http` body.
+#[derive(Debug, Clone)]
+pub(crate) struct TraceEcho {
+    pub(crate) enabled: bool,
+    redacted: Vec<HeaderName>,
+}
+
+impl Default for TraceEcho {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            redacted: vec![
+                header::AUTHORIZATION,
+                header::PROXY_AUTHORIZATION,
+                header::COOKIE,
+            ],
+        }
+    }
+}
+
+impl TraceEcho {
+    /// Replaces the list of headers whose values are redacted.
+    pub(crate) fn redact(&mut self, headers: impl IntoIterator<Item = HeaderName>) {
+        self.redacted = headers.into_iter().collect();
+    }
+
+    /// Builds the `TRACE` response for `req`.
+    ///
+    /// The application is always the final recipient of the request, so this answers locally
+    /// regardless of the `Max-Forwards` value, including zero. The value is echoed as received.
+    pub(crate) fn respond(&self, req: &HttpRequest) -> HttpResponse {
+        let mut body = BytesMut::new();
+
+        let line = format!("{} {} {:?}\r\n", req.method(), req.uri(), req.version());
+        body.put_slice(line.as_bytes());
+
+        for (name, value) in req.headers() {
+            body.put_slice(name.as_str().as_bytes());
+            body.put_slice(b": ");
+
+            if self.redacted.contains(name) {
+                body.put_slice(b"[redacted]");
+            } else {
+                body.put_slice(value.as_bytes());
+            }
+
+            body.put_slice(b"\r\n");
+        }
+
+        body.put_slice(b"\r\n");
+
+        HttpResponse::Ok().content_type("message/http").body(body)
+    }
+}
@@ -0,0 +1,403 @@
+//! For API version extractor documentation, see [`ApiVersion`].
+
+use std::{convert::TryFrom, fmt, ops};
+
+use actix_utils::future::{ready, Ready};
+
+use crate::{
+    dev::{Payload, RequestHead},
+    error::ApiVersionError,
+    extract::FromRequest,
+    http::header::HeaderName,
+    Error, HttpRequest,
+};
+
+/// Header that versions are read from when no [`ApiVersionConfig`] is registered.
+const DEFAULT_HEADER: &str = "x-api-version";
+
+/// Extracts the API version requested by the client.
+///
+/// The version is resolved from the sources configured using an [`ApiVersionConfig`] registered
+/// as app data; if none is registered, it is read from the `X-Api-Version` header. Extraction
+/// fails with an [`ApiVersionError`], responding with "400 Bad Request", if the version is missing,
+/// not a number, or newer than the configured maximum.
+///
+/// To serve different versions of the same path using different handlers, use the
+/// [`ApiVersion` guard](crate::guard::ApiVersion). The version can be logged using the `%V`
+/// [`Logger`](crate::middleware::Logger) token.
+///
+/// # Examples
+/// ```
+/// use actix_web::{get, web, App};
+///
+/// #[get("/users")]
+/// async fn users(version: web::ApiVersion) -> String {
+///     format!("users, as of API v{}", version)
+/// }
+///
+/// App::new()
+///     .app_data(
+///         web::ApiVersionConfig::new()
+///             .header("x-api-version")
+///             .path_prefix()
+///             .default_version(1)
+///             .max(3),
+///     )
+///     .service(users);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ApiVersion(pub u16);
+
+impl ApiVersion {
+    /// Unwraps into the version number.
+    pub fn into_inner(self) -> u16 {
+        self.0
+    }
+
+    /// Resolves the API version of a request, using `config` or, if `None`, the default header.
+    pub(crate) fn resolve(
+        config: Option<&ApiVersionConfig>,
+        head: &RequestHead,
+    ) -> Result<u16, ApiVersionError> {
+        match config {
+            Some(config) => config.resolve(head),
+            None => ApiVersionConfig::new().header(DEFAULT_HEADER).resolve(head),
+        }
+    }
+}
+
+impl ops::Deref for ApiVersion {
+    type Target = u16;
+
+    fn deref(&self) -> &u16 {
+        &self.0
+    }
+}
+
+impl fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl FromRequest for ApiVersion {
+    type Error = Error;
+    type Future = Ready<Result<Self, Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let res = Self::resolve(req.app_data::<ApiVersionConfig>(), req.head());
+
+        ready(res.map(ApiVersion).map_err(|err| {
+            log::debug!(
+                "Failed to resolve API version: {}. Request path: {:?}",
+                err,
+                req.path()
+            );
+
+            err.into()
+        }))
+    }
+}
+
+/// Where an API version is read from.
+#[derive(Debug, Clone)]
+enum VersionSource {
+    /// Value of a request header, such as `2`.
+    Header(HeaderName),
+
+    /// First segment of the request path, such as `/v2/`.
+    PathPrefix,
+}
+
+impl VersionSource {
+    /// Returns the version held by this source, `Ok(None)` if it holds none, or an error if it
+    /// holds a value that is not a version number.
+    fn version(&self, head: &RequestHead) -> Result<Option<u16>, ApiVersionError> {
+        let value = match self {
+            Self::Header(name) => match head.headers().get(name) {
+                Some(value) => {
+                    value
+                        .to_str()
+                        .map(str::trim)
+                        .map_err(|_| ApiVersionError::Invalid {
+                            value: String::from_utf8_lossy(value.as_bytes()).into_owned(),
+                        })?
+                }
+                None => return Ok(None),
+            },
+
+            Self::PathPrefix => {
+                let segment = head.uri.path().trim_start_matches('/');
+                let segment = segment.split('/').next().unwrap_or("");
+
+                match segment.strip_prefix('v') {
+                    // segments like "/videos" are not versions
+                    Some(digits) if digits.starts_with(|c: char| c.is_ascii_digit()) => digits,
+                    _ => return Ok(None),
+                }
+            }
+        };
+
+        parse_version(value).map(Some)
+    }
+}
+
+/// Parses a version number consisting only of ASCII digits.
+fn parse_version(value: &str) -> Result<u16, ApiVersionError> {
+    let invalid = || ApiVersionError::Invalid {
+        value: value.to_owned(),
+    };
+
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(invalid());
+    }
+
+    value.parse().map_err(|_| invalid())
+}
+
+/// Configuration for resolving the [`ApiVersion`] of requests.
+///
+/// Sources are checked in the order they are added, and the first one holding a version is used,
+/// even if later sources hold a different one. A source holding a value that is not a version
+/// number fails resolution, unless an earlier source holds a version.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App};
+///
+/// // `X-Api-Version: 2` takes precedence over a `/v1/` path prefix; requests with neither use
+/// // version 1, and versions newer than 3 are rejected
+/// let api_version_cfg = web::ApiVersionConfig::new()
+///     .header("x-api-version")
+///     .path_prefix()
+///     .default_version(1)
+///     .max(3);
+///
+/// App::new().app_data(api_version_cfg);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ApiVersionConfig {
+    sources: Vec<VersionSource>,
+    default: Option<u16>,
+    max: Option<u16>,
+}
+
+impl ApiVersionConfig {
+    /// Constructs a config without sources, default, or maximum.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the version from the header `name`, such as `X-Api-Version: 2`.
+    ///
+    /// # Panics
+    /// Panics if `name` is not a valid header name.
+    pub fn header(mut self, name: &str) -> Self {
+        let name = HeaderName::try_from(name).expect("invalid API version header name");
+        self.sources.push(VersionSource::Header(name));
+        self
+    }
+
+    /// Reads the version from the first segment of the request path, such as `/v2/users`.
+    ///
+    /// Routes still need to match the prefix, for example using `web::scope("/v{version}")`.
+    pub fn path_prefix(mut self) -> Self {
+        self.sources.push(VersionSource::PathPrefix);
+        self
+    }
+
+    /// Sets the version used when no source holds one.
+    ///
+    /// Without a default, requests without a version are rejected.
+    pub fn default_version(mut self, version: u16) -> Self {
+        self.default = Some(version);
+        self
+    }
+
+    /// Sets the latest supported version; requests for newer versions are rejected.
+    pub fn max(mut self, version: u16) -> Self {
+        self.max = Some(version);
+        self
+    }
+
+    /// Resolves the version of a request from the configured sources.
+    pub(crate) fn resolve(&self, head: &RequestHead) -> Result<u16, ApiVersionError> {
+        let mut version = None;
+
+        for source in &self.sources {
+            version = source.version(head)?;
+
+            if version.is_some() {
+                break;
+            }
+        }
+
+        let version = version.or(self.default).ok_or(ApiVersionError::Missing)?;
+
+        match self.max {
+            Some(max) if version > max => Err(ApiVersionError::Unsupported { version, max }),
+            _ => Ok(version),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        guard,
+        http::StatusCode,
+        test::{call_service, init_service, read_body, TestRequest},
+        web, App, HttpResponse,
+    };
+
+    fn config() -> ApiVersionConfig {
+        ApiVersionConfig::new()
+            .header("x-api-version")
+            .path_prefix()
+            .default_version(1)
+            .max(3)
+    }
+
+    fn resolve(req: TestRequest) -> Result<u16, ApiVersionError> {
+        let req = req.app_data(config()).to_http_request();
+        ApiVersion::resolve(req.app_data(), req.head())
+    }
+
+    #[test]
+    fn header_only() {
+        let req = TestRequest::default().insert_header(("x-api-version", "2"));
+        assert_eq!(resolve(req), Ok(2));
+
+        let req = TestRequest::default().insert_header(("x-api-version", " 3 "));
+        assert_eq!(resolve(req), Ok(3));
+
+        let req = TestRequest::default().insert_header(("x-api-version", "two"));
+        assert_eq!(
+            resolve(req),
+            Err(ApiVersionError::Invalid {
+                value: "two".to_owned()
+            })
+        );
+
+        // falls back to the default header without a config
+        let req = TestRequest::default()
+            .insert_header(("x-api-version", "7"))
+            .to_http_request();
+        assert_eq!(ApiVersion::resolve(None, req.head()), Ok(7));
+        let req = TestRequest::default().to_http_request();
+        assert_eq!(
+            ApiVersion::resolve(None, req.head()),
+            Err(ApiVersionError::Missing)
+        );
+    }
+
+    #[test]
+    fn path_only() {
+        assert_eq!(resolve(TestRequest::with_uri("/v2/users")), Ok(2));
+        assert_eq!(resolve(TestRequest::with_uri("/v3")), Ok(3));
+
+        // not versioned, so the default is used
+        assert_eq!(resolve(TestRequest::with_uri("/users")), Ok(1));
+        assert_eq!(resolve(TestRequest::with_uri("/videos/v2")), Ok(1));
+
+        assert_eq!(
+            resolve(TestRequest::with_uri("/v2x/users")),
+            Err(ApiVersionError::Invalid {
+                value: "2x".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn header_wins_conflict() {
+        let req = TestRequest::with_uri("/v1/users").insert_header(("x-api-version", "2"));
+        assert_eq!(resolve(req), Ok(2));
+
+        // sources are checked in the configured order
+        let cfg = ApiVersionConfig::new()
+            .path_prefix()
+            .header("x-api-version");
+        let req = TestRequest::with_uri("/v1/users")
+            .insert_header(("x-api-version", "2"))
+            .to_http_request();
+        assert_eq!(cfg.resolve(req.head()), Ok(1));
+    }
+
+    #[actix_rt::test]
+    async fn out_of_range_rejected() {
+        let req = TestRequest::default().insert_header(("x-api-version", "4"));
+        assert_eq!(
+            resolve(req),
+            Err(ApiVersionError::Unsupported { version: 4, max: 3 })
+        );
+        assert_eq!(
+            resolve(TestRequest::with_uri("/v70000/users")),
+            Err(ApiVersionError::Invalid {
+                value: "70000".to_owned()
+            })
+        );
+
+        let srv = init_service(App::new().app_data(config()).route(
+            "/users",
+            web::get().to(|version: ApiVersion| async move { version.to_string() }),
+        ))
+        .await;
+
+        let req = TestRequest::with_uri("/users")
+            .insert_header(("x-api-version", "3"))
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(read_body(res).await, "3");
+
+        let req = TestRequest::with_uri("/users")
+            .insert_header(("x-api-version", "4"))
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[actix_rt::test]
+    async fn guard_dispatch() {
+        let srv = init_service(
+            App::new()
+                .app_data(config())
+                .service(
+                    web::resource("/users")
+                        .guard(guard::ApiVersion(..=1))
+                        .to(|| HttpResponse::Ok().body("v1")),
+                )
+                .service(
+                    web::resource("/users")
+                        .guard(guard::ApiVersion(2..))
+                        .to(|| HttpResponse::Ok().body("v2")),
+                ),
+        )
+        .await;
+
+        for (version, body) in [
+            (None, "v1"),
+            (Some("1"), "v1"),
+            (Some("2"), "v2"),
+            (Some("3"), "v2"),
+        ] {
+            let mut req = TestRequest::with_uri("/users");
+            if let Some(version) = version {
+                req = req.insert_header(("x-api-version", version));
+            }
+
+            let res = call_service(&srv, req.to_request()).await;
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(read_body(res).await, body);
+        }
+
+        // unsupported versions match neither guard
+        let req = TestRequest::with_uri("/users")
+            .insert_header(("x-api-version", "4"))
+            .to_request();
+        let res = call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
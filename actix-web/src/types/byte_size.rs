@@ -0,0 +1,265 @@
+//! For byte size documentation, see [`ByteSize`].
+
+use std::{convert::TryFrom, fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::ParseByteSizeError;
+
+/// Units, largest first, used to parse and display byte sizes.
+const UNITS: &[(&str, u64)] = &[
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("kB", 1_000),
+];
+
+/// A number of bytes, used by size limits.
+///
+/// Limit-setting methods, such as [`JsonConfig::limit`](crate::web::JsonConfig::limit), accept
+/// anything that converts into a `ByteSize`, so both plain `usize` byte counts and parsed sizes can
+/// be used.
+///
+/// # Parsing
+/// Sizes are parsed from a number, optionally followed by a unit. Decimal units (`kB`, `MB`, `GB`,
+/// `TB`) are powers of 1000 and binary units (`KiB`, `MiB`, `GiB`, `TiB`) are powers of 1024.
+/// Units are matched case-insensitively, may be shortened to their first letter (e.g., `k` is the
+/// same as `kB`), and may be separated from the number by whitespace. A number without a unit, or
+/// with the unit `B`, is a number of bytes. Numbers may have a fractional part, which is rounded
+/// down to whole bytes.
+///
+/// # Display
+/// Sizes are displayed using the largest unit that represents them exactly, such as `10 MiB` or
+/// `1500 kB`, falling back to a number of bytes.
+///
+/// # Serde
+/// Deserializes from either an integer number of bytes or a string, and serializes as a string in
+/// the display format.
+///
+/// # Examples
+/// ```
+/// use actix_web::web::{ByteSize, JsonConfig};
+///
+/// let limit: ByteSize = "10MiB".parse().unwrap();
+/// assert_eq!(limit.as_usize(), 10_485_760);
+/// assert_eq!(limit.to_string(), "10 MiB");
+///
+/// let json_cfg = JsonConfig::default().limit(limit);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ByteSize(pub usize);
+
+impl ByteSize {
+    /// Returns the number of bytes.
+    pub fn as_usize(self) -> usize {
+        self.0
+    }
+}
+
+impl From<usize> for ByteSize {
+    fn from(bytes: usize) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<ByteSize> for usize {
+    fn from(size: ByteSize) -> Self {
+        size.0
+    }
+}
+
+impl FromStr for ByteSize {
+    type Err = ParseByteSizeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        let unit_start = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (number, unit) = s.split_at(unit_start);
+        let unit = unit.trim_start();
+
+        let multiplier = unit_multiplier(unit).ok_or(ParseByteSizeError::UnknownUnit)?;
+
+        let (whole, fraction) = match number.split_once('.') {
+            Some((whole, fraction)) => (whole, fraction),
+            None => (number, ""),
+        };
+
+        if whole.is_empty() && fraction.is_empty() || fraction.contains('.') {
+            return Err(ParseByteSizeError::InvalidNumber);
+        }
+
+        let mut bytes = 0u128;
+
+        if !whole.is_empty() {
+            let whole = whole
+                .parse::<u128>()
+                .map_err(|_| ParseByteSizeError::Overflow)?;
+            bytes = whole
+                .checked_mul(u128::from(multiplier))
+                .ok_or(ParseByteSizeError::Overflow)?;
+        }
+
+        // further digits can not add a whole byte, even to the largest multiplier
+        let fraction = &fraction[..fraction.len().min(24)];
+
+        if !fraction.is_empty() {
+            let numerator = fraction.parse::<u128>().unwrap();
+            let denominator = 10u128.pow(fraction.len() as u32);
+            bytes += numerator * u128::from(multiplier) / denominator;
+        }
+
+        usize::try_from(bytes)
+            .map(Self)
+            .map_err(|_| ParseByteSizeError::Overflow)
+    }
+}
+
+/// Returns the number of bytes in `unit`, or `None` if it is not a known unit.
+fn unit_multiplier(unit: &str) -> Option<u64> {
+    if unit.is_empty() || unit.eq_ignore_ascii_case("b") {
+        return Some(1);
+    }
+
+    UNITS.iter().find_map(|&(name, multiplier)| {
+        let short = &name[..1];
+        (unit.eq_ignore_ascii_case(name) || unit.eq_ignore_ascii_case(short) && name.len() == 2)
+            .then(|| multiplier)
+    })
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bytes = self.0 as u64;
+
+        let unit = UNITS
+            .iter()
+            .find(|&&(_, multiplier)| bytes != 0 && bytes % multiplier == 0);
+
+        match unit {
+            Some((name, multiplier)) => write!(f, "{} {}", bytes / multiplier, name),
+            None => write!(f, "{} B", bytes),
+        }
+    }
+}
+
+impl Serialize for ByteSize {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ByteSize {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteSizeVisitor;
+
+        impl<'de> de::Visitor<'de> for ByteSizeVisitor {
+            type Value = ByteSize;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a number of bytes or a size such as \"10MB\"")
+            }
+
+            fn visit_u64<E: de::Error>(self, bytes: u64) -> Result<Self::Value, E> {
+                usize::try_from(bytes)
+                    .map(ByteSize)
+                    .map_err(|_| E::custom(ParseByteSizeError::Overflow))
+            }
+
+            fn visit_i64<E: de::Error>(self, bytes: i64) -> Result<Self::Value, E> {
+                usize::try_from(bytes)
+                    .map(ByteSize)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Signed(bytes), &self))
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                s.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(ByteSizeVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<usize, ParseByteSizeError> {
+        s.parse::<ByteSize>().map(ByteSize::as_usize)
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!(parse("4096"), Ok(4096));
+        assert_eq!(parse("0"), Ok(0));
+        assert_eq!(parse("12 B"), Ok(12));
+        assert_eq!(parse("512k"), Ok(512_000));
+        assert_eq!(parse("512KiB"), Ok(524_288));
+        assert_eq!(parse("10MB"), Ok(10_000_000));
+        assert_eq!(parse(" 10 mib "), Ok(10_485_760));
+        assert_eq!(parse("1GiB"), Ok(1 << 30));
+        assert_eq!(parse("2g"), Ok(2_000_000_000));
+
+        assert_eq!(parse("1.5MB"), Ok(1_500_000));
+        assert_eq!(parse("1.5KiB"), Ok(1536));
+        assert_eq!(parse(".5kB"), Ok(500));
+        assert_eq!(parse("2."), Ok(2));
+        // fractional bytes are rounded down
+        assert_eq!(parse("1.9"), Ok(1));
+        assert_eq!(parse("0.0001kB"), Ok(0));
+
+        assert_eq!(parse(""), Err(ParseByteSizeError::InvalidNumber));
+        assert_eq!(parse("MB"), Err(ParseByteSizeError::InvalidNumber));
+        assert_eq!(parse("."), Err(ParseByteSizeError::InvalidNumber));
+        assert_eq!(parse("1.2.3"), Err(ParseByteSizeError::InvalidNumber));
+        assert_eq!(parse("-1"), Err(ParseByteSizeError::UnknownUnit));
+        assert_eq!(parse("10 XB"), Err(ParseByteSizeError::UnknownUnit));
+        assert_eq!(parse("10 Mi"), Err(ParseByteSizeError::UnknownUnit));
+
+        assert_eq!(
+            parse("99999999999999999999999999999999999999999"),
+            Err(ParseByteSizeError::Overflow)
+        );
+        assert_eq!(parse("100000000 TiB"), Err(ParseByteSizeError::Overflow));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(ByteSize(0).to_string(), "0 B");
+        assert_eq!(ByteSize(1023).to_string(), "1023 B");
+        assert_eq!(ByteSize(4096).to_string(), "4 KiB");
+        assert_eq!(ByteSize(1500).to_string(), "1500 B");
+        assert_eq!(ByteSize(1_500_000).to_string(), "1500 kB");
+        assert_eq!(ByteSize(10_485_760).to_string(), "10 MiB");
+        assert_eq!(ByteSize(2_000_000_000).to_string(), "2 GB");
+
+        for size in [0, 1500, 4096, 1_500_000, 10_485_760, 2_000_000_000] {
+            assert_eq!(parse(&ByteSize(size).to_string()), Ok(size));
+        }
+    }
+
+    #[test]
+    fn serde() {
+        #[derive(Debug, Deserialize, Serialize)]
+        struct Config {
+            limit: ByteSize,
+        }
+
+        let cfg: Config = serde_json::from_str(r#"{ "limit": "2MiB" }"#).unwrap();
+        assert_eq!(cfg.limit, ByteSize(2_097_152));
+
+        let cfg: Config = serde_json::from_str(r#"{ "limit": 4096 }"#).unwrap();
+        assert_eq!(cfg.limit, ByteSize(4096));
+        assert_eq!(serde_json::to_string(&cfg).unwrap(), r#"{"limit":"4 KiB"}"#);
+
+        assert!(serde_json::from_str::<Config>(r#"{ "limit": "2 parsecs" }"#).is_err());
+        assert!(serde_json::from_str::<Config>(r#"{ "limit": -1 }"#).is_err());
+    }
+}
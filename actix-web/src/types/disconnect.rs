@@ -0,0 +1,105 @@
+//! For client disconnect extractor documentation, see [`ConnectionWatcher`].
+
+use std::convert::Infallible;
+
+use actix_utils::future::{ok, Ready};
+
+use crate::{
+    dev::{DisconnectSignal, Disconnected, Payload},
+    FromRequest, HttpMessage as _, HttpRequest,
+};
+
+/// Extractor for observing whether the client is still connected.
+///
+/// Long-running handlers can race their work against [`disconnected`](Self::disconnected) and
+/// stop early once nobody is waiting for the response; dropping the work future cancels it.
+///
+/// On HTTP/1 connections, the client is considered gone once its connection is closed or reset.
+/// On HTTP/2 connections, it is considered gone once the request's stream is reset. Requests that
+/// were not received from a connection, such as those built with
+/// [`TestRequest`](crate::test::TestRequest), are never considered disconnected.
+///
+/// Also see [`HttpRequest::on_disconnect`].
+///
+/// # Examples
+/// ```
+/// use actix_web::{get, web, HttpResponse};
+/// # async fn expensive_work() -> String { String::new() }
+///
+/// #[get("/report")]
+/// async fn report(watcher: web::ConnectionWatcher) -> HttpResponse {
+///     tokio::select! {
+///         report = expensive_work() => HttpResponse::Ok().body(report),
+///         _ = watcher.disconnected() => HttpResponse::NoContent().finish(),
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConnectionWatcher(DisconnectSignal);
+
+impl ConnectionWatcher {
+    /// Returns true if the client has disconnected.
+    pub fn is_disconnected(&self) -> bool {
+        self.0.is_disconnected()
+    }
+
+    /// Returns a future that resolves once the client has disconnected.
+    pub fn disconnected(&self) -> Disconnected {
+        self.0.disconnected()
+    }
+}
+
+impl FromRequest for ConnectionWatcher {
+    type Error = Infallible;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    #[inline]
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let signal = req
+            .extensions()
+            .get::<DisconnectSignal>()
+            .cloned()
+            .unwrap_or_default();
+
+        ok(ConnectionWatcher(signal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::test::TestRequest;
+
+    #[actix_rt::test]
+    async fn observes_signal_in_extensions() {
+        let signal = DisconnectSignal::new();
+        let (req, mut pl) = TestRequest::default().to_http_parts();
+        req.extensions_mut().insert(signal.clone());
+
+        let watcher = ConnectionWatcher::from_request(&req, &mut pl)
+            .await
+            .unwrap();
+        assert!(!watcher.is_disconnected());
+
+        signal.notify();
+        assert!(watcher.is_disconnected());
+        watcher.disconnected().await;
+        req.on_disconnect().await;
+    }
+
+    #[actix_rt::test]
+    async fn never_disconnects_without_connection() {
+        let (req, mut pl) = TestRequest::default().to_http_parts();
+
+        let watcher = ConnectionWatcher::from_request(&req, &mut pl)
+            .await
+            .unwrap();
+        assert!(!watcher.is_disconnected());
+
+        let res =
+            actix_rt::time::timeout(Duration::from_millis(10), watcher.disconnected()).await;
+        assert!(res.is_err());
+    }
+}
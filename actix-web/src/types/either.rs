@@ -9,13 +9,14 @@ use std::{
 
 use bytes::Bytes;
 use futures_core::ready;
+use futures_util::future::{FutureExt as _, Map};
 use pin_project_lite::pin_project;
 
 use crate::{
     body::EitherBody,
     dev,
     web::{Form, Json},
-    Error, FromRequest, HttpRequest, HttpResponse, Responder,
+    Error, FromRequest, Handler, HttpRequest, HttpResponse, Responder,
 };
 
 /// Combines two extractor or responder types into a single type.
@@ -73,6 +74,10 @@ use crate::{
 ///     }
 /// }
 /// ```
+///
+/// `Result<T, E>` can only be returned from handlers when `E` converts into an [`Error`]. To
+/// return a `Result` whose sides are two arbitrary responders, such as a fully-formed error
+/// response, wrap the handler in [`RespondEither`](crate::web::RespondEither).
 #[derive(Debug, PartialEq)]
 pub enum Either<L, R> {
     /// A value of type `L`.
@@ -100,6 +105,16 @@ impl<T> Either<Json<T>, Form<T>> {
     }
 }
 
+/// Converts `Ok` values into `Left` and `Err` values into `Right`.
+impl<L, R> From<Result<L, R>> for Either<L, R> {
+    fn from(res: Result<L, R>) -> Self {
+        match res {
+            Ok(val) => Either::Left(val),
+            Err(val) => Either::Right(val),
+        }
+    }
+}
+
 #[cfg(test)]
 impl<L, R> Either<L, R> {
     pub(self) fn unwrap_left(self) -> L {
@@ -137,6 +152,44 @@ where
     }
 }
 
+/// Handler wrapper for handlers that return `Result<L, R>` where both `L` and `R` are responders.
+///
+/// A `Result` returned from a handler is normally only a [`Responder`] when its error type converts
+/// into an [`Error`]. Wrapping the handler in `RespondEither` instead responds with whichever side
+/// is returned, as [`Either`] does: `Ok` values like [`Either::Left`] and `Err` values like
+/// [`Either::Right`]. Each side keeps its own status, headers, and body.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web::{self, Json}, App, HttpResponse};
+///
+/// async fn create_user(name: web::Path<String>) -> Result<Json<String>, HttpResponse> {
+///     if name.as_str() == "bob" {
+///         return Err(HttpResponse::Conflict().body("name is taken"));
+///     }
+///
+///     Ok(Json(name.into_inner()))
+/// }
+///
+/// let app = App::new().route("/users/{name}", web::post().to(web::RespondEither(create_user)));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct RespondEither<F>(pub F);
+
+// keep "not a valid request handler" errors pointing at the handler, not at this wrapper
+#[rustversion::attr(since(1.85), diagnostic::do_not_recommend)]
+impl<F, Args, L, R> Handler<Args> for RespondEither<F>
+where
+    F: Handler<Args, Output = Result<L, R>>,
+{
+    type Output = Either<L, R>;
+    type Future = Map<F::Future, fn(Result<L, R>) -> Either<L, R>>;
+
+    fn call(&self, args: Args) -> Self::Future {
+        self.0.call(args).map(Either::from)
+    }
+}
+
 /// A composite error resulting from failure to extract an `Either<L, R>`.
 ///
 /// The implementation of `Into<actix_web::Error>` will return the payload buffering error or the
@@ -366,4 +419,53 @@ mod tests {
         .into_inner();
         assert_eq!(&form.hello, "world");
     }
+
+    #[actix_rt::test]
+    async fn respond_with_either_side_of_result() {
+        use serde_json::json;
+
+        use crate::{
+            http::{header, StatusCode},
+            test, web, App, HttpResponse,
+        };
+
+        async fn handler(path: web::Path<String>) -> Result<Json<TestForm>, HttpResponse> {
+            match path.as_str() {
+                "taken" => Err(HttpResponse::Conflict()
+                    .content_type("application/problem+json")
+                    .json(json!({ "title": "taken" }))),
+                name => Ok(Json(TestForm {
+                    hello: name.to_owned(),
+                })),
+            }
+        }
+
+        let srv = test::init_service(
+            App::new().route("/{name}", web::get().to(web::RespondEither(handler))),
+        )
+        .await;
+
+        let req = test::TestRequest::with_uri("/world").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        let body = test::read_body(res).await;
+        assert_eq!(body, r#"{"hello":"world"}"#);
+
+        let req = test::TestRequest::with_uri("/taken").to_request();
+        let res = test::call_service(&srv, req).await;
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/problem+json"
+        );
+        let body = test::read_body(res).await;
+        assert_eq!(body, r#"{"title":"taken"}"#);
+
+        let res: Either<u8, &str> = Err("err").into();
+        assert_eq!(res, Either::Right("err"));
+    }
 }
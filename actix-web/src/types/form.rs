@@ -17,6 +17,7 @@ use futures_core::{future::LocalBoxFuture, ready};
 use futures_util::{FutureExt as _, StreamExt as _};
 use serde::{de::DeserializeOwned, Serialize};
 
+use super::ByteSize;
 #[cfg(feature = "__compress")]
 use crate::dev::Decompress;
 use crate::{
@@ -230,8 +231,8 @@ pub struct FormConfig {
 
 impl FormConfig {
     /// Set maximum accepted payload size. By default this limit is 16kB.
-    pub fn limit(mut self, limit: usize) -> Self {
-        self.limit = limit;
+    pub fn limit(mut self, limit: impl Into<ByteSize>) -> Self {
+        self.limit = limit.into().as_usize();
         self
     }
 
@@ -344,8 +345,8 @@ impl<T> UrlEncoded<T> {
     }
 
     /// Set maximum accepted payload size. The default limit is 256kB.
-    pub fn limit(mut self, limit: usize) -> Self {
-        self.limit = limit;
+    pub fn limit(mut self, limit: impl Into<ByteSize>) -> Self {
+        self.limit = limit.into().as_usize();
         self
     }
 }
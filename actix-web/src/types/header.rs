@@ -14,6 +14,9 @@ use crate::{
 /// To extract a header, the inner type `T` must implement the
 /// [`Header`](crate::http::header::Header) trait.
 ///
+/// Header values that are not valid UTF-8 are handled according to the app's
+/// [`InvalidUtf8Policy`](crate::dev::InvalidUtf8Policy).
+///
 /// # Examples
 /// ```
 /// use actix_web::{get, web, http::header};
@@ -65,7 +68,7 @@ where
 
     #[inline]
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        match ParseHeader::parse(req) {
+        match req.parse_header::<T>() {
             Ok(header) => ok(Header(header)),
             Err(e) => err(e),
         }
@@ -16,14 +16,16 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use actix_http::Payload;
 
+use super::ByteSize;
 #[cfg(feature = "__compress")]
 use crate::dev::Decompress;
 use crate::{
-    body::EitherBody,
+    body::{BoxBody, EitherBody, MessageBody as _},
     error::{Error, JsonPayloadError},
     extract::FromRequest,
-    http::header::CONTENT_LENGTH,
+    http::header::{ContentLength, CONTENT_LENGTH},
     request::HttpRequest,
+    types::JsonSerializer,
     web, HttpMessage, HttpResponse, Responder,
 };
 
@@ -115,14 +117,22 @@ impl<T: Serialize> Serialize for Json<T> {
 
 /// Creates response with OK status code, correct content type header, and serialized JSON payload.
 ///
-/// If serialization failed
+/// The payload is serialized up front, so the response has an exact `Content-Length` header
+/// instead of using chunked encoding. If serialization failed, a "500 Internal Server Error"
+/// response is returned instead.
 impl<T: Serialize> Responder for Json<T> {
     type Body = EitherBody<String>;
 
-    fn respond_to(self, _: &HttpRequest) -> HttpResponse<Self::Body> {
-        match serde_json::to_string(&self.0) {
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let body = match JsonConfig::from_req(req).serializer.as_deref() {
+            Some(serializer) => serialize_with(serializer, &self.0),
+            None => serde_json::to_string(&self.0),
+        };
+
+        match body {
             Ok(body) => match HttpResponse::Ok()
                 .content_type(mime::APPLICATION_JSON)
+                .insert_header(ContentLength(body.len()))
                 .message_body(body)
             {
                 Ok(res) => res.map_into_left_body(),
@@ -136,6 +146,52 @@ impl<T: Serialize> Responder for Json<T> {
     }
 }
 
+/// Serializes `value` to a string using a custom serializer.
+fn serialize_with<T: Serialize + ?Sized>(
+    serializer: &dyn JsonSerializer,
+    value: &T,
+) -> Result<String, serde_json::Error> {
+    let mut buf = BytesMut::new();
+    serializer.serialize(value, &mut buf)?;
+    String::from_utf8(buf.to_vec()).map_err(serde::ser::Error::custom)
+}
+
+/// Marks responses with bodies written by [`HttpResponseBuilder::json`].
+///
+/// [`HttpResponseBuilder::json`]: crate::HttpResponseBuilder::json
+pub(crate) struct BuilderJson;
+
+/// Rewrites the body of a response built with [`HttpResponseBuilder::json`] using the serializer
+/// registered in the request's [`JsonConfig`], if any.
+///
+/// [`HttpResponseBuilder::json`]: crate::HttpResponseBuilder::json
+pub(crate) fn apply_json_serializer(req: &HttpRequest, res: HttpResponse) -> HttpResponse {
+    if !res.extensions().contains::<BuilderJson>() {
+        return res;
+    }
+
+    let serializer = match JsonConfig::from_req(req).serializer.as_deref() {
+        Some(serializer) => serializer,
+        None => return res,
+    };
+
+    let (res, body) = res.into_parts();
+
+    let body = match body.try_into_bytes() {
+        Ok(body) => body,
+        Err(body) => return res.set_body(body),
+    };
+
+    let mut buf = BytesMut::new();
+    let serialized = serde_json::from_slice::<serde_json::Value>(&body)
+        .and_then(|value| serializer.serialize_value(&value, &mut buf));
+
+    match serialized {
+        Ok(()) => res.set_body(BoxBody::new(buf.freeze())),
+        Err(err) => HttpResponse::from_error(JsonPayloadError::Serialize(err)),
+    }
+}
+
 /// See [here](#extractor) for example of usage as an extractor.
 impl<T: DeserializeOwned> FromRequest for Json<T> {
     type Error = Error;
@@ -236,12 +292,13 @@ pub struct JsonConfig {
     err_handler: JsonErrorHandler,
     content_type: Option<Arc<dyn Fn(mime::Mime) -> bool + Send + Sync>>,
     content_type_required: bool,
+    serializer: Option<Arc<dyn JsonSerializer>>,
 }
 
 impl JsonConfig {
     /// Set maximum accepted payload size. By default this limit is 2MB.
-    pub fn limit(mut self, limit: usize) -> Self {
-        self.limit = limit;
+    pub fn limit(mut self, limit: impl Into<ByteSize>) -> Self {
+        self.limit = limit.into().as_usize();
         self
     }
 
@@ -269,13 +326,29 @@ impl JsonConfig {
         self
     }
 
+    /// Set serializer used for JSON responses.
+    ///
+    /// Applies to [`Json`] responders and, when returned from handlers, to responses built with
+    /// [`HttpResponseBuilder::json`]. By default, `serde_json` is used directly.
+    ///
+    /// [`HttpResponseBuilder::json`]: crate::HttpResponseBuilder::json
+    pub fn serializer(mut self, serializer: impl JsonSerializer + 'static) -> Self {
+        self.serializer = Some(Arc::new(serializer));
+        self
+    }
+
     /// Extract payload config from app data. Check both `T` and `Data<T>`, in that order, and fall
     /// back to the default payload config.
-    fn from_req(req: &HttpRequest) -> &Self {
+    pub(crate) fn from_req(req: &HttpRequest) -> &Self {
         req.app_data::<Self>()
             .or_else(|| req.app_data::<web::Data<Self>>().map(|d| d.as_ref()))
             .unwrap_or(&DEFAULT_CONFIG)
     }
+
+    /// Returns the serializer registered with [`serializer`](Self::serializer), if any.
+    pub(crate) fn shared_serializer(&self) -> Option<Arc<dyn JsonSerializer>> {
+        self.serializer.clone()
+    }
 }
 
 const DEFAULT_LIMIT: usize = 2_097_152; // 2 mb
@@ -286,6 +359,7 @@ const DEFAULT_CONFIG: JsonConfig = JsonConfig {
     err_handler: None,
     content_type: None,
     content_type_required: true,
+    serializer: None,
 };
 
 impl Default for JsonConfig {
@@ -374,7 +448,9 @@ impl<T: DeserializeOwned> JsonBody<T> {
     }
 
     /// Set maximum accepted payload size. The default limit is 2MB.
-    pub fn limit(self, limit: usize) -> Self {
+    pub fn limit(self, limit: impl Into<ByteSize>) -> Self {
+        let limit = limit.into().as_usize();
+
         match self {
             JsonBody::Body {
                 length,
@@ -492,6 +568,90 @@ mod tests {
         assert_body_eq!(res, b"{\"name\":\"test\"}");
     }
 
+    #[actix_rt::test]
+    async fn responder_content_length() {
+        let app = crate::test::init_service(
+            crate::App::new().route("/", web::get().to(|| async { Json(GREETINGS) })),
+        )
+        .await;
+
+        let req = TestRequest::default().to_request();
+        let res = crate::test::call_service(&app, req).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+
+        let len = res.headers().get(CONTENT_LENGTH).unwrap().to_owned();
+        let body = crate::test::read_body(res).await;
+
+        // length is in bytes, not characters
+        let json = serde_json::to_string(&GREETINGS).unwrap();
+        assert_eq!(len, json.len().to_string().as_str());
+        assert_eq!(body.len(), json.len());
+        assert_ne!(json.chars().count(), json.len());
+    }
+
+    #[derive(Serialize)]
+    struct Greetings {
+        zh: &'static str,
+        de: &'static str,
+        en: &'static str,
+    }
+
+    const GREETINGS: Greetings = Greetings {
+        zh: "你好",
+        de: "Grüße",
+        en: "hello",
+    };
+
+    #[actix_rt::test]
+    async fn custom_serializer() {
+        let app = crate::test::init_service(
+            crate::App::new()
+                .app_data(
+                    JsonConfig::default()
+                        .serializer(web::CanonicalJsonSerializer::new().escape_non_ascii(true)),
+                )
+                .route("/json", web::get().to(|| async { Json(GREETINGS) }))
+                .route(
+                    "/builder",
+                    web::get().to(|| HttpResponse::Ok().json(GREETINGS)),
+                ),
+        )
+        .await;
+
+        for uri in ["/json", "/builder"] {
+            let req = TestRequest::with_uri(uri).to_request();
+            let res = crate::test::call_service(&app, req).await;
+            assert_eq!(res.status(), StatusCode::OK);
+            assert_eq!(res.headers().get(CONTENT_TYPE).unwrap(), "application/json");
+
+            let body = crate::test::read_body(res).await;
+            assert_eq!(
+                body,
+                r#"{"de":"Gr\u00fc\u00dfe","en":"hello","zh":"\u4f60\u597d"}"#
+            );
+        }
+    }
+
+    #[actix_rt::test]
+    async fn default_serializer_unchanged() {
+        let app = crate::test::init_service(
+            crate::App::new()
+                .route("/json", web::get().to(|| async { Json(GREETINGS) }))
+                .route(
+                    "/builder",
+                    web::get().to(|| HttpResponse::Ok().json(GREETINGS)),
+                ),
+        )
+        .await;
+
+        for uri in ["/json", "/builder"] {
+            let req = TestRequest::with_uri(uri).to_request();
+            let body = crate::test::call_and_read_body(&app, req).await;
+            assert_eq!(body, r#"{"zh":"你好","de":"Grüße","en":"hello"}"#);
+        }
+    }
+
     #[actix_rt::test]
     async fn test_custom_error_responder() {
         let (req, mut pl) = TestRequest::default()
@@ -561,7 +721,7 @@ mod tests {
 
         let s = Json::<MyObject>::from_request(&req, &mut pl).await;
         assert!(format!("{}", s.err().unwrap())
-            .contains("JSON payload (16 bytes) is larger than allowed (limit: 10 bytes)."));
+            .contains("JSON payload (16 bytes) is larger than allowed (limit: 10 B)."));
 
         let (req, mut pl) = TestRequest::default()
             .insert_header((
@@ -747,7 +907,8 @@ mod tests {
         assert!(s.is_err());
 
         let err_str = s.err().unwrap().to_string();
-        assert!(err_str
-            .contains("JSON payload (16 bytes) is larger than allowed (limit: 10 bytes)."));
+        assert!(
+            err_str.contains("JSON payload (16 bytes) is larger than allowed (limit: 10 B).")
+        );
     }
 }
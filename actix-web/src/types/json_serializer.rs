@@ -0,0 +1,217 @@
+//! For JSON serializer documentation, see [`JsonSerializer`].
+
+use std::io::Write as _;
+
+use bytes::{BufMut as _, BytesMut};
+use serde::Serialize;
+use serde_json::Value;
+
+/// Serializer used for JSON responses.
+///
+/// By default, JSON responses are written by `serde_json` using its default settings. Register a
+/// serializer with [`JsonConfig::serializer`](super::JsonConfig::serializer) to control the output
+/// of [`Json`](super::Json) responders and [`HttpResponseBuilder::json`] for the whole app or a
+/// part of it, such as to produce cache friendly output with sorted keys. Deserialization is not
+/// affected.
+///
+/// Serializers receive a [`Value`] so they can be shared as trait objects; the convenience method
+/// [`serialize`](#method.serialize) converts any serializable type first.
+///
+/// [`HttpResponseBuilder::json`]: crate::HttpResponseBuilder::json
+pub trait JsonSerializer: Send + Sync {
+    /// Writes `value` to `buf`.
+    fn serialize_value(
+        &self,
+        value: &Value,
+        buf: &mut BytesMut,
+    ) -> Result<(), serde_json::Error>;
+}
+
+impl dyn JsonSerializer + '_ {
+    /// Writes any serializable `value` to `buf`.
+    pub fn serialize<T: Serialize + ?Sized>(
+        &self,
+        value: &T,
+        buf: &mut BytesMut,
+    ) -> Result<(), serde_json::Error> {
+        let value = serde_json::to_value(value)?;
+        self.serialize_value(&value, buf)
+    }
+}
+
+/// The default `serde_json` serializer.
+///
+/// Object keys are written in the order of the [`Value`]'s map.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultJsonSerializer;
+
+impl JsonSerializer for DefaultJsonSerializer {
+    fn serialize_value(
+        &self,
+        value: &Value,
+        buf: &mut BytesMut,
+    ) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(buf.writer(), value)
+    }
+}
+
+/// Serializer producing stable output suitable for caching and signing.
+///
+/// Object keys are sorted by default. Non-ASCII characters in strings can optionally be written as
+/// `\uXXXX` escapes, for clients that mishandle UTF-8.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, App};
+///
+/// let app = App::new().app_data(
+///     web::JsonConfig::default()
+///         .serializer(web::CanonicalJsonSerializer::new().escape_non_ascii(true)),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CanonicalJsonSerializer {
+    sort_keys: bool,
+    escape_non_ascii: bool,
+}
+
+impl CanonicalJsonSerializer {
+    /// Constructs a serializer that sorts object keys and does not escape non-ASCII characters.
+    pub fn new() -> Self {
+        Self {
+            sort_keys: true,
+            escape_non_ascii: false,
+        }
+    }
+
+    /// Sets whether object keys are sorted. Defaults to true.
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    /// Sets whether non-ASCII characters in strings are written as `\uXXXX` escapes. Defaults to
+    /// false.
+    pub fn escape_non_ascii(mut self, escape_non_ascii: bool) -> Self {
+        self.escape_non_ascii = escape_non_ascii;
+        self
+    }
+
+    fn write_value(&self, value: &Value, buf: &mut BytesMut) -> Result<(), serde_json::Error> {
+        match value {
+            Value::String(string) => self.write_string(string, buf),
+
+            Value::Array(items) => {
+                buf.put_u8(b'[');
+
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        buf.put_u8(b',');
+                    }
+
+                    self.write_value(item, buf)?;
+                }
+
+                buf.put_u8(b']');
+                Ok(())
+            }
+
+            Value::Object(map) => {
+                let mut entries = map.iter().collect::<Vec<_>>();
+
+                if self.sort_keys {
+                    entries.sort_unstable_by_key(|&(key, _)| key);
+                }
+
+                buf.put_u8(b'{');
+
+                for (idx, (key, value)) in entries.into_iter().enumerate() {
+                    if idx > 0 {
+                        buf.put_u8(b',');
+                    }
+
+                    self.write_string(key, buf)?;
+                    buf.put_u8(b':');
+                    self.write_value(value, buf)?;
+                }
+
+                buf.put_u8(b'}');
+                Ok(())
+            }
+
+            scalar => serde_json::to_writer(buf.writer(), scalar),
+        }
+    }
+
+    fn write_string(&self, string: &str, buf: &mut BytesMut) -> Result<(), serde_json::Error> {
+        if !self.escape_non_ascii || string.is_ascii() {
+            return serde_json::to_writer(buf.writer(), string);
+        }
+
+        // non-ASCII characters can only appear unescaped in serde_json's output
+        let escaped = serde_json::to_string(string)?;
+        let mut writer = buf.writer();
+
+        for ch in escaped.chars() {
+            if ch.is_ascii() {
+                writer.get_mut().put_u8(ch as u8);
+            } else {
+                for unit in ch.encode_utf16(&mut [0; 2]) {
+                    write!(writer, "\\u{:04x}", unit).map_err(serde_json::Error::io)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for CanonicalJsonSerializer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JsonSerializer for CanonicalJsonSerializer {
+    fn serialize_value(
+        &self,
+        value: &Value,
+        buf: &mut BytesMut,
+    ) -> Result<(), serde_json::Error> {
+        self.write_value(value, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn serialize(serializer: &dyn JsonSerializer, value: &Value) -> String {
+        let mut buf = BytesMut::new();
+        serializer.serialize(value, &mut buf).unwrap();
+        String::from_utf8(buf.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn canonical_output() {
+        let value = json!({ "b": [1.5, null, "ü"], "a": { "d": true, "c": "😀\n" } });
+
+        let sorted = CanonicalJsonSerializer::new();
+        assert_eq!(
+            serialize(&sorted, &value),
+            r#"{"a":{"c":"😀\n","d":true},"b":[1.5,null,"ü"]}"#
+        );
+
+        let escaped = sorted.escape_non_ascii(true);
+        assert_eq!(
+            serialize(&escaped, &value),
+            r#"{"a":{"c":"\ud83d\ude00\n","d":true},"b":[1.5,null,"\u00fc"]}"#
+        );
+
+        let value = json!({ "ä": 1 });
+        assert_eq!(serialize(&escaped, &value), r#"{"\u00e4":1}"#);
+        assert_eq!(serialize(&DefaultJsonSerializer, &value), r#"{"ä":1}"#);
+    }
+}
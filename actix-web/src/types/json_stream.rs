@@ -0,0 +1,336 @@
+//! For streaming JSON array documentation, see [`JsonArrayStream`].
+
+use std::{
+    error::Error as StdError,
+    fmt,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::{BufMut as _, Bytes, BytesMut};
+use futures_core::{ready, Stream};
+use pin_project_lite::pin_project;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{JsonConfig, JsonSerializer};
+use crate::{
+    body::{BodySize, BoxBody, MessageBody},
+    HttpRequest, HttpResponse, Responder,
+};
+
+type ErrorSentinel = Box<dyn FnOnce(&dyn StdError) -> Value>;
+
+/// Responder streaming a JSON array from a stream of items.
+///
+/// The body starts with `[`, then each item is serialized as the stream yields it, separated by
+/// commas, and the array is closed with `]` once the stream ends. Items are only pulled from the
+/// stream when the body is read, so slow clients slow down the stream. The response has the
+/// `application/json` content type and no `Content-Length` header. Items are written using the
+/// serializer registered with [`JsonConfig::serializer`], if any.
+///
+/// If the stream yields an error, or an item fails to serialize, the body errors too, which aborts
+/// the response and closes the connection instead of sending an incomplete array. Use
+/// [`error_sentinel`](Self::error_sentinel) to finish the array with an error object instead.
+///
+/// # Examples
+/// ```
+/// use actix_web::{get, web, Responder};
+/// use futures_util::stream;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Row {
+///     id: u32,
+/// }
+///
+/// #[get("/rows")]
+/// async fn rows() -> impl Responder {
+///     let rows = stream::iter((0..1000).map(|id| Ok::<_, std::io::Error>(Row { id })));
+///     web::JsonArrayStream::new(rows)
+/// }
+/// ```
+pub struct JsonArrayStream<S> {
+    stream: S,
+    error_sentinel: Option<ErrorSentinel>,
+}
+
+impl<S> JsonArrayStream<S> {
+    /// Constructs a responder for the items yielded by `stream`.
+    pub fn new(stream: S) -> Self {
+        Self {
+            stream,
+            error_sentinel: None,
+        }
+    }
+
+    /// Finishes the array with an object built from the error, instead of aborting the response,
+    /// when the stream yields an error or an item fails to serialize.
+    ///
+    /// The object built by `sentinel` is written as the last item of the array, so the body is
+    /// always valid JSON; clients need to check the last item to tell whether the array is
+    /// complete.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io;
+    /// use actix_web::web;
+    /// use futures_util::stream;
+    /// use serde_json::json;
+    ///
+    /// let items = stream::iter([Ok(1), Err(io::Error::new(io::ErrorKind::Other, "db down"))]);
+    ///
+    /// // body: [1,{"error":"db down"}]
+    /// let res = web::JsonArrayStream::new(items)
+    ///     .error_sentinel(|err| json!({ "error": err.to_string() }));
+    /// ```
+    pub fn error_sentinel(
+        mut self,
+        sentinel: impl FnOnce(&dyn StdError) -> Value + 'static,
+    ) -> Self {
+        self.error_sentinel = Some(Box::new(sentinel));
+        self
+    }
+}
+
+impl<S> fmt::Debug for JsonArrayStream<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonArrayStream")
+            .field("error_sentinel", &self.error_sentinel.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, T, E> Responder for JsonArrayStream<S>
+where
+    S: Stream<Item = Result<T, E>> + 'static,
+    T: Serialize,
+    E: Into<Box<dyn StdError>> + 'static,
+{
+    type Body = BoxBody;
+
+    fn respond_to(self, req: &HttpRequest) -> HttpResponse<Self::Body> {
+        let body = JsonArrayBody {
+            stream: self.stream,
+            serializer: JsonConfig::from_req(req).shared_serializer(),
+            error_sentinel: self.error_sentinel,
+            started: false,
+            done: false,
+        };
+
+        HttpResponse::Ok()
+            .content_type(mime::APPLICATION_JSON)
+            .body(body)
+    }
+}
+
+pin_project! {
+    /// Body of a [`JsonArrayStream`] response.
+    struct JsonArrayBody<S> {
+        #[pin]
+        stream: S,
+        serializer: Option<Arc<dyn JsonSerializer>>,
+        error_sentinel: Option<ErrorSentinel>,
+        started: bool,
+        done: bool,
+    }
+}
+
+impl<S, T, E> MessageBody for JsonArrayBody<S>
+where
+    S: Stream<Item = Result<T, E>>,
+    T: Serialize,
+    E: Into<Box<dyn StdError>>,
+{
+    type Error = Box<dyn StdError>;
+
+    fn size(&self) -> BodySize {
+        BodySize::Stream
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+        let this = self.project();
+
+        if *this.done {
+            return Poll::Ready(None);
+        }
+
+        let item = ready!(this.stream.poll_next(cx));
+
+        let mut buf = BytesMut::new();
+        buf.put_u8(if *this.started { b',' } else { b'[' });
+
+        let err = match item {
+            Some(Ok(item)) => match serialize(this.serializer.as_deref(), &item, &mut buf) {
+                Ok(()) => {
+                    *this.started = true;
+                    return Poll::Ready(Some(Ok(buf.freeze())));
+                }
+                Err(err) => err.into(),
+            },
+
+            Some(Err(err)) => err.into(),
+
+            None => {
+                *this.done = true;
+
+                if *this.started {
+                    buf.clear();
+                }
+                buf.put_u8(b']');
+
+                return Poll::Ready(Some(Ok(buf.freeze())));
+            }
+        };
+
+        *this.done = true;
+
+        let sentinel = match this.error_sentinel.take() {
+            Some(sentinel) => sentinel(&*err),
+            None => return Poll::Ready(Some(Err(err))),
+        };
+
+        // a failed item may have been partially written
+        buf.truncate(1);
+
+        match serialize(this.serializer.as_deref(), &sentinel, &mut buf) {
+            Ok(()) => {
+                buf.put_u8(b']');
+                Poll::Ready(Some(Ok(buf.freeze())))
+            }
+            Err(err) => Poll::Ready(Some(Err(err.into()))),
+        }
+    }
+}
+
+/// Writes `value` to `buf` using the configured serializer, or `serde_json` by default.
+fn serialize<T: Serialize + ?Sized>(
+    serializer: Option<&dyn JsonSerializer>,
+    value: &T,
+    buf: &mut BytesMut,
+) -> Result<(), serde_json::Error> {
+    match serializer {
+        Some(serializer) => serializer.serialize(value, buf),
+        None => serde_json::to_writer(buf.writer(), value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, io, rc::Rc};
+
+    use futures_util::{future::poll_fn, stream, StreamExt as _};
+    use serde_json::json;
+
+    use super::*;
+    use crate::{body::to_bytes, http::header, test::TestRequest};
+
+    fn respond<S>(responder: JsonArrayStream<S>) -> HttpResponse
+    where
+        JsonArrayStream<S>: Responder<Body = BoxBody>,
+    {
+        let req = TestRequest::default().to_http_request();
+        responder.respond_to(&req)
+    }
+
+    #[derive(Serialize)]
+    struct Item {
+        id: usize,
+        name: String,
+    }
+
+    fn item(id: usize) -> Item {
+        Item {
+            id,
+            name: format!("item \"{}\"", id),
+        }
+    }
+
+    #[actix_rt::test]
+    async fn matches_buffered_serialization() {
+        let items = (0..10_000).map(item).collect::<Vec<_>>();
+        let expected = serde_json::to_vec(&items).unwrap();
+
+        let stream = stream::iter((0..10_000).map(|id| Ok::<_, io::Error>(item(id))));
+        let res = respond(JsonArrayStream::new(stream));
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert!(res.headers().get(header::CONTENT_LENGTH).is_none());
+
+        let body = to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(body, expected);
+
+        let stream = stream::iter(Vec::<Result<Item, io::Error>>::new());
+        let body = to_bytes(respond(JsonArrayStream::new(stream)).into_body()).await;
+        assert_eq!(body.unwrap(), "[]");
+    }
+
+    #[actix_rt::test]
+    async fn pulls_items_on_demand() {
+        let pulled = Rc::new(Cell::new(0));
+
+        let stream = stream::iter(0..100).map({
+            let pulled = Rc::clone(&pulled);
+            move |id| {
+                pulled.set(pulled.get() + 1);
+                Ok::<_, io::Error>(id)
+            }
+        });
+
+        let mut body = respond(JsonArrayStream::new(stream)).into_body();
+
+        let chunk = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), "[0");
+        let chunk = poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await;
+        assert_eq!(chunk.unwrap().unwrap(), ",1");
+        assert_eq!(pulled.get(), 2);
+    }
+
+    #[actix_rt::test]
+    async fn stream_error() {
+        let items = || {
+            stream::iter([
+                Ok(1),
+                Ok(2),
+                Err(io::Error::new(io::ErrorKind::Other, "db down")),
+                Ok(3),
+            ])
+        };
+
+        // body errors, aborting the response
+        let mut body = respond(JsonArrayStream::new(items())).into_body();
+        let mut chunks = Vec::new();
+        let err = loop {
+            match poll_fn(|cx| Pin::new(&mut body).poll_next(cx)).await {
+                Some(Ok(chunk)) => chunks.push(chunk),
+                Some(Err(err)) => break err,
+                None => panic!("body ended without an error"),
+            }
+        };
+        assert_eq!(chunks, ["[1", ",2"]);
+        assert_eq!(err.to_string(), "db down");
+        assert!(poll_fn(|cx| Pin::new(&mut body).poll_next(cx))
+            .await
+            .is_none());
+
+        // array is finished with the sentinel
+        let res = respond(
+            JsonArrayStream::new(items())
+                .error_sentinel(|err| json!({ "error": err.to_string() })),
+        );
+        let body = to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(body, r#"[1,2,{"error":"db down"}]"#);
+
+        // sentinel as the only item
+        let stream = stream::iter([Err::<u32, _>(io::Error::new(io::ErrorKind::Other, "x"))]);
+        let res = respond(JsonArrayStream::new(stream).error_sentinel(|_| json!(null)));
+        let body = to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(body, "[null]");
+    }
+}
@@ -1,19 +1,35 @@
 //! Common extractors and responders.
 
+mod api_version;
+mod byte_size;
+mod disconnect;
 mod either;
 mod form;
 mod header;
 mod json;
+mod json_serializer;
+mod json_stream;
 mod path;
 mod payload;
 mod query;
 mod readlines;
+mod text;
 
-pub use self::either::Either;
+pub use self::api_version::{ApiVersion, ApiVersionConfig};
+pub use self::byte_size::ByteSize;
+pub use self::disconnect::ConnectionWatcher;
+pub use self::either::{Either, RespondEither};
 pub use self::form::{Form, FormConfig, UrlEncoded};
 pub use self::header::Header;
 pub use self::json::{Json, JsonBody, JsonConfig};
+pub use self::json_serializer::{
+    CanonicalJsonSerializer, DefaultJsonSerializer, JsonSerializer,
+};
+pub use self::json_stream::JsonArrayStream;
 pub use self::path::{Path, PathConfig};
 pub use self::payload::{Payload, PayloadConfig};
 pub use self::query::{Query, QueryConfig};
 pub use self::readlines::Readlines;
+pub use self::text::{encode_text, Text, Unmappable};
+
+pub(crate) use self::json::{apply_json_serializer, BuilderJson};
@@ -73,34 +73,48 @@ where
 
     #[inline]
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        let error_handler = req
+        let config = req
             .app_data::<PathConfig>()
-            .or_else(|| req.app_data::<Data<PathConfig>>().map(Data::get_ref))
-            .and_then(|c| c.err_handler.clone());
+            .or_else(|| req.app_data::<Data<PathConfig>>().map(Data::get_ref));
 
-        ready(
+        let error_handler = config.and_then(|c| c.err_handler.clone());
+        let plus_as_space = config.map_or(false, |c| c.plus_as_space);
+
+        let res = if plus_as_space {
+            // literal `+` characters become encoded spaces; `%2B` is left to decode to `+`
+            let mut path = actix_router::Path::new(String::new());
+
+            for (name, value) in req.match_info().iter() {
+                path.add_static(name.to_owned(), value.replace('+', "%20"));
+            }
+
+            de::Deserialize::deserialize(PathDeserializer::new(&path))
+        } else {
             de::Deserialize::deserialize(PathDeserializer::new(req.match_info()))
-                .map(Path)
-                .map_err(move |err| {
-                    log::debug!(
-                        "Failed during Path extractor deserialization. \
+        };
+
+        ready(res.map(Path).map_err(move |err| {
+            log::debug!(
+                "Failed during Path extractor deserialization. \
                          Request path: {:?}",
-                        req.path()
-                    );
-
-                    if let Some(error_handler) = error_handler {
-                        let e = PathError::Deserialize(err);
-                        (error_handler)(e, req)
-                    } else {
-                        ErrorNotFound(err)
-                    }
-                }),
-        )
+                req.path()
+            );
+
+            if let Some(error_handler) = error_handler {
+                let e = PathError::Deserialize(err);
+                (error_handler)(e, req)
+            } else {
+                ErrorNotFound(err)
+            }
+        }))
     }
 }
 
 /// Path extractor configuration
 ///
+/// By default, `+` characters in path segments are extracted as-is. Use
+/// [`plus_as_space`](Self::plus_as_space) to accept clients that encode spaces as `+` in paths.
+///
 /// ```
 /// use actix_web::web::PathConfig;
 /// use actix_web::{error, web, App, FromRequest, HttpResponse};
@@ -135,6 +149,7 @@ where
 #[derive(Clone, Default)]
 pub struct PathConfig {
     err_handler: Option<Arc<dyn Fn(PathError, &HttpRequest) -> Error + Send + Sync>>,
+    plus_as_space: bool,
 }
 
 impl PathConfig {
@@ -146,6 +161,15 @@ impl PathConfig {
         self.err_handler = Some(Arc::new(f));
         self
     }
+
+    /// Sets whether `+` characters in path segments are extracted as spaces.
+    ///
+    /// Defaults to false, since `+` has no special meaning in paths. A percent-encoded `%2B` is
+    /// always extracted as a literal `+`, regardless of this setting.
+    pub fn plus_as_space(mut self, plus_as_space: bool) -> Self {
+        self.plus_as_space = plus_as_space;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -287,4 +311,38 @@ mod tests {
 
         assert_eq!(res.status(), http::StatusCode::CONFLICT);
     }
+
+    #[actix_rt::test]
+    async fn plus_as_space() {
+        async fn extract(config: Option<PathConfig>) -> MyStruct {
+            let resource = ResourceDef::new("/{key}/{value}");
+
+            let mut req = TestRequest::with_uri("/a+b/c%2Bd%20e");
+            if let Some(config) = config {
+                req = req.app_data(config);
+            }
+
+            let mut req = req.to_srv_request();
+            resource.capture_match_info(req.match_info_mut());
+
+            let (req, mut pl) = req.into_parts();
+            Path::<MyStruct>::from_request(&req, &mut pl)
+                .await
+                .unwrap()
+                .into_inner()
+        }
+
+        // default
+        let path = extract(None).await;
+        assert_eq!(path.key, "a+b");
+        assert_eq!(path.value, "c+d e");
+
+        let path = extract(Some(PathConfig::default().plus_as_space(false))).await;
+        assert_eq!(path.key, "a+b");
+        assert_eq!(path.value, "c+d e");
+
+        let path = extract(Some(PathConfig::default().plus_as_space(true))).await;
+        assert_eq!(path.key, "a b");
+        assert_eq!(path.value, "c+d e");
+    }
 }
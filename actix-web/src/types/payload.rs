@@ -15,6 +15,7 @@ use encoding_rs::{Encoding, UTF_8};
 use futures_core::{ready, stream::Stream};
 use mime::Mime;
 
+use super::ByteSize;
 use crate::{
     dev, error::ErrorBadRequest, http::header, web, Error, FromRequest, HttpMessage,
     HttpRequest,
@@ -212,16 +213,16 @@ pub struct PayloadConfig {
 
 impl PayloadConfig {
     /// Create new instance with a size limit (in bytes) and no mime type condition.
-    pub fn new(limit: usize) -> Self {
+    pub fn new(limit: impl Into<ByteSize>) -> Self {
         Self {
-            limit,
+            limit: limit.into().as_usize(),
             ..Default::default()
         }
     }
 
     /// Set maximum accepted payload size in bytes. The default limit is 256KiB.
-    pub fn limit(mut self, limit: usize) -> Self {
-        self.limit = limit;
+    pub fn limit(mut self, limit: impl Into<ByteSize>) -> Self {
+        self.limit = limit.into().as_usize();
         self
     }
 
@@ -332,7 +333,9 @@ impl HttpMessageBody {
     }
 
     /// Change max size of payload. By default max size is 256kB
-    pub fn limit(mut self, limit: usize) -> Self {
+    pub fn limit(mut self, limit: impl Into<ByteSize>) -> Self {
+        let limit = limit.into().as_usize();
+
         if let Some(l) = self.length {
             self.err = if l > limit {
                 Some(PayloadError::Overflow)
@@ -398,6 +401,44 @@ mod tests {
         assert!(cfg.check_mimetype(&req).is_ok());
     }
 
+    #[actix_rt::test]
+    async fn byte_size_limits() {
+        let srv = init_service(
+            App::new()
+                .service(
+                    web::resource("/int")
+                        .app_data(PayloadConfig::default().limit(4096))
+                        .route(
+                            web::post().to(|body: Bytes| async move { body.len().to_string() }),
+                        ),
+                )
+                .service(
+                    web::resource("/parsed")
+                        .app_data(PayloadConfig::new("4KiB".parse::<ByteSize>().unwrap()))
+                        .route(
+                            web::post().to(|body: Bytes| async move { body.len().to_string() }),
+                        ),
+                ),
+        )
+        .await;
+
+        for path in ["/int", "/parsed"] {
+            let req = TestRequest::post()
+                .uri(path)
+                .set_payload(vec![b'a'; 4096])
+                .to_request();
+            let res = call_service(&srv, req).await;
+            assert_eq!(res.status(), StatusCode::OK);
+
+            let req = TestRequest::post()
+                .uri(path)
+                .set_payload(vec![b'a'; 4097])
+                .to_request();
+            let res = call_service(&srv, req).await;
+            assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        }
+    }
+
     // allow deprecated App::data
     #[allow(deprecated)]
     #[actix_rt::test]
@@ -1,11 +1,16 @@
 //! For query parameter extractor documentation, see [`Query`].
 
-use std::{fmt, ops, sync::Arc};
+use std::{borrow::Cow, fmt, ops, str, sync::Arc};
 
+use actix_router::Quoter;
 use actix_utils::future::{err, ok, Ready};
 use serde::de::DeserializeOwned;
 
-use crate::{dev::Payload, error::QueryPayloadError, Error, FromRequest, HttpRequest};
+use crate::{
+    dev::{InvalidUtf8Policy, Payload},
+    error::QueryPayloadError,
+    Error, FromRequest, HttpRequest,
+};
 
 /// Extract typed information from the request's query.
 ///
@@ -112,34 +117,58 @@ impl<T: DeserializeOwned> FromRequest for Query<T> {
 
     #[inline]
     fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
-        let error_handler = req
-            .app_data::<QueryConfig>()
-            .and_then(|c| c.err_handler.clone());
-
-        serde_urlencoded::from_str::<T>(req.query_string())
-            .map(|val| ok(Query(val)))
-            .unwrap_or_else(move |e| {
-                let e = QueryPayloadError::Deserialize(e);
-
-                log::debug!(
-                    "Failed during Query extractor deserialization. \
-                     Request path: {:?}",
-                    req.path()
-                );
-
-                let e = if let Some(error_handler) = error_handler {
-                    (error_handler)(e, req)
-                } else {
-                    e.into()
-                };
-
-                err(e)
-            })
+        let config = req.app_data::<QueryConfig>();
+
+        let error_handler = config.and_then(|c| c.err_handler.clone());
+        let plus_as_space = config.map_or(true, |c| c.plus_as_space);
+
+        let query = if plus_as_space {
+            Cow::Borrowed(req.query_string())
+        } else {
+            // keep literal `+` characters by encoding them before form decoding
+            Cow::Owned(req.query_string().replace('+', "%2B"))
+        };
+
+        let res = if req.app_config().invalid_utf8_policy() != InvalidUtf8Policy::Lossy
+            && !decodes_to_utf8(&query)
+        {
+            Err(QueryPayloadError::InvalidUtf8)
+        } else {
+            serde_urlencoded::from_str::<T>(&query).map_err(QueryPayloadError::Deserialize)
+        };
+
+        res.map(|val| ok(Query(val))).unwrap_or_else(move |e| {
+            log::debug!(
+                "Failed during Query extractor deserialization. \
+                 Request path: {:?}",
+                req.path()
+            );
+
+            let e = if let Some(error_handler) = error_handler {
+                (error_handler)(e, req)
+            } else {
+                e.into()
+            };
+
+            err(e)
+        })
+    }
+}
+
+/// Returns true if the percent-decoded query string is valid UTF-8.
+fn decodes_to_utf8(query: &str) -> bool {
+    match Quoter::new(b"", b"").requote(query.as_bytes()) {
+        Some(decoded) => str::from_utf8(&decoded).is_ok(),
+        None => true,
     }
 }
 
 /// Query extractor configuration.
 ///
+/// By default, `+` characters in query strings are extracted as spaces, following the
+/// `application/x-www-form-urlencoded` rules also used by [`Form`](crate::web::Form). Use
+/// [`plus_as_space`](Self::plus_as_space) to extract them as-is instead.
+///
 /// # Examples
 /// ```
 /// use actix_web::{error, get, web, App, FromRequest, HttpResponse};
@@ -167,9 +196,19 @@ impl<T: DeserializeOwned> FromRequest for Query<T> {
 ///     .app_data(query_cfg)
 ///     .service(index);
 /// ```
-#[derive(Clone, Default)]
+#[derive(Clone)]
 pub struct QueryConfig {
     err_handler: Option<Arc<dyn Fn(QueryPayloadError, &HttpRequest) -> Error + Send + Sync>>,
+    plus_as_space: bool,
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            err_handler: None,
+            plus_as_space: true,
+        }
+    }
 }
 
 impl QueryConfig {
@@ -181,6 +220,15 @@ impl QueryConfig {
         self.err_handler = Some(Arc::new(f));
         self
     }
+
+    /// Sets whether `+` characters in query strings are extracted as spaces.
+    ///
+    /// Defaults to true. A percent-encoded `%2B` is always extracted as a literal `+`, regardless
+    /// of this setting.
+    pub fn plus_as_space(mut self, plus_as_space: bool) -> Self {
+        self.plus_as_space = plus_as_space;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -270,4 +318,35 @@ mod tests {
             StatusCode::UNPROCESSABLE_ENTITY
         );
     }
+
+    #[actix_rt::test]
+    async fn plus_as_space() {
+        #[derive(Deserialize)]
+        struct Search {
+            q: String,
+        }
+
+        async fn extract(config: Option<QueryConfig>) -> String {
+            let mut req = TestRequest::with_uri("/?q=a+b%2Bc%20d");
+            if let Some(config) = config {
+                req = req.app_data(config);
+            }
+
+            let (req, mut pl) = req.to_http_parts();
+            Query::<Search>::from_request(&req, &mut pl)
+                .await
+                .unwrap()
+                .into_inner()
+                .q
+        }
+
+        // default
+        assert_eq!(extract(None).await, "a b+c d");
+
+        let config = QueryConfig::default().plus_as_space(true);
+        assert_eq!(extract(Some(config)).await, "a b+c d");
+
+        let config = QueryConfig::default().plus_as_space(false);
+        assert_eq!(extract(Some(config)).await, "a+b+c d");
+    }
 }
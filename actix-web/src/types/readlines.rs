@@ -11,6 +11,7 @@ use bytes::{Bytes, BytesMut};
 use encoding_rs::{Encoding, UTF_8};
 use futures_core::{ready, stream::Stream};
 
+use super::ByteSize;
 use crate::{
     dev::Payload,
     error::{PayloadError, ReadlinesError},
@@ -50,8 +51,8 @@ where
     }
 
     /// Set maximum accepted payload size. The default limit is 256kB.
-    pub fn limit(mut self, limit: usize) -> Self {
-        self.limit = limit;
+    pub fn limit(mut self, limit: impl Into<ByteSize>) -> Self {
+        self.limit = limit.into().as_usize();
         self
     }
 
@@ -0,0 +1,357 @@
+//! For text response helper documentation, see [`Text`] and [`encode_text`].
+
+use bytes::Bytes;
+use encoding_rs::{EncoderResult, WINDOWS_1252};
+use mime::Mime;
+
+use crate::{
+    body::EitherBody,
+    error::EncodeTextError,
+    http::header::{Charset, ContentType},
+    HttpRequest, HttpResponse, Responder,
+};
+
+/// How characters that have no representation in the target charset are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unmappable {
+    /// Replace each unmappable character with `?`.
+    Replace,
+
+    /// Fail with [`EncodeTextError::Unmappable`].
+    Error,
+}
+
+impl Default for Unmappable {
+    fn default() -> Self {
+        Self::Replace
+    }
+}
+
+/// Charsets that text can be encoded in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextCharset {
+    Utf8,
+    Latin1,
+    Windows1252,
+}
+
+impl TextCharset {
+    fn from_charset(charset: &Charset) -> Result<Self, EncodeTextError> {
+        let label = charset.to_string();
+
+        if label.eq_ignore_ascii_case("utf-8") {
+            Ok(Self::Utf8)
+        } else if label.eq_ignore_ascii_case("iso-8859-1") {
+            Ok(Self::Latin1)
+        } else if label.eq_ignore_ascii_case("windows-1252") {
+            Ok(Self::Windows1252)
+        } else {
+            Err(EncodeTextError::UnsupportedCharset { charset: label })
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Utf8 => "utf-8",
+            Self::Latin1 => "iso-8859-1",
+            Self::Windows1252 => "windows-1252",
+        }
+    }
+
+    fn encode(self, text: &str, unmappable: Unmappable) -> Result<Bytes, EncodeTextError> {
+        let unmapped = |ch: char, out: &mut Vec<u8>| match unmappable {
+            Unmappable::Replace => {
+                out.push(b'?');
+                Ok(())
+            }
+            Unmappable::Error => Err(EncodeTextError::Unmappable {
+                ch,
+                charset: self.label().to_owned(),
+            }),
+        };
+
+        match self {
+            Self::Utf8 => Ok(Bytes::copy_from_slice(text.as_bytes())),
+
+            Self::Latin1 => {
+                let mut out = Vec::with_capacity(text.len());
+
+                for ch in text.chars() {
+                    // ISO-8859-1 maps bytes to the first 256 code points
+                    if u32::from(ch) <= 0xFF {
+                        out.push(ch as u8);
+                    } else {
+                        unmapped(ch, &mut out)?;
+                    }
+                }
+
+                Ok(Bytes::from(out))
+            }
+
+            Self::Windows1252 => {
+                let mut encoder = WINDOWS_1252.new_encoder();
+                let mut out = Vec::with_capacity(text.len());
+                let mut rest = text;
+
+                loop {
+                    if let Some(len) =
+                        encoder.max_buffer_length_from_utf8_without_replacement(rest.len())
+                    {
+                        out.reserve(len);
+                    }
+
+                    let (res, read) = encoder
+                        .encode_from_utf8_to_vec_without_replacement(rest, &mut out, true);
+                    rest = &rest[read..];
+
+                    match res {
+                        EncoderResult::InputEmpty => break,
+                        EncoderResult::OutputFull => {}
+                        EncoderResult::Unmappable(ch) => unmapped(ch, &mut out)?,
+                    }
+                }
+
+                Ok(Bytes::from(out))
+            }
+        }
+    }
+}
+
+/// Encodes `text` as `text/plain` in `charset`.
+///
+/// Supported charsets are UTF-8, ISO-8859-1, and Windows-1252, matched case-insensitively by
+/// label. Returns the encoded bytes and a `Content-Type` header with a matching `charset`
+/// parameter. Use [`AcceptCharset::negotiate`](crate::http::header::AcceptCharset::negotiate) to
+/// pick a charset the client accepts.
+///
+/// # Errors
+/// Returns an error if `charset` is not supported, or, when `unmappable` is
+/// [`Unmappable::Error`], if `text` contains a character that `charset` can not represent.
+///
+/// # Examples
+/// ```
+/// use actix_web::{http::header::Charset, web};
+///
+/// let charset = Charset::Ext("windows-1252".to_owned());
+/// let (body, content_type) = web::encode_text("café", &charset, web::Unmappable::Error).unwrap();
+///
+/// assert_eq!(&body[..], b"caf\xE9");
+/// assert_eq!(content_type.to_string(), "text/plain; charset=windows-1252");
+/// ```
+pub fn encode_text(
+    text: &str,
+    charset: &Charset,
+    unmappable: Unmappable,
+) -> Result<(Bytes, ContentType), EncodeTextError> {
+    encode_as(text, &mime::TEXT_PLAIN, charset, unmappable)
+}
+
+fn encode_as(
+    text: &str,
+    mime: &Mime,
+    charset: &Charset,
+    unmappable: Unmappable,
+) -> Result<(Bytes, ContentType), EncodeTextError> {
+    let charset = TextCharset::from_charset(charset)?;
+    let body = charset.encode(text, unmappable)?;
+
+    // essence string and label are both valid MIME tokens
+    let mime = format!("{}; charset={}", mime.essence_str(), charset.label())
+        .parse()
+        .unwrap();
+
+    Ok((body, ContentType(mime)))
+}
+
+/// Text responder, encoding its body in a chosen charset.
+///
+/// Responds with `text/plain` or, for [`Text::csv`], `text/csv` content, encoded in UTF-8 unless
+/// another charset is chosen using [`charset`](Self::charset). See [`encode_text`] for the
+/// supported charsets. If the body can not be encoded, a "500 Internal Server Error" response is
+/// returned instead.
+///
+/// # Examples
+/// ```
+/// use actix_web::{
+///     http::header::{AcceptCharset, Charset, Header as _},
+///     web, HttpRequest,
+/// };
+///
+/// async fn export(req: HttpRequest) -> web::Text {
+///     let utf8 = Charset::Ext("utf-8".to_owned());
+///     let windows_1252 = Charset::Ext("windows-1252".to_owned());
+///
+///     let charset = AcceptCharset::parse(&req)
+///         .ok()
+///         .and_then(|accept| accept.negotiate([&utf8, &windows_1252]))
+///         .unwrap_or(utf8);
+///
+///     web::Text::csv("name,city\nZoë,Zürich\n").charset(charset)
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Text {
+    body: String,
+    mime: Mime,
+    charset: Charset,
+    unmappable: Unmappable,
+}
+
+impl Text {
+    /// Constructs a `text/plain` response body.
+    pub fn new(body: impl Into<String>) -> Self {
+        Self::with_mime(body.into(), mime::TEXT_PLAIN)
+    }
+
+    /// Constructs a `text/csv` response body.
+    pub fn csv(body: impl Into<String>) -> Self {
+        Self::with_mime(body.into(), mime::TEXT_CSV)
+    }
+
+    fn with_mime(body: String, mime: Mime) -> Self {
+        Self {
+            body,
+            mime,
+            charset: Charset::Ext("utf-8".to_owned()),
+            unmappable: Unmappable::default(),
+        }
+    }
+
+    /// Encodes the body in `charset` instead of UTF-8.
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Sets how characters that `charset` can not represent are handled.
+    ///
+    /// By default, they are replaced with `?`.
+    pub fn unmappable(mut self, unmappable: Unmappable) -> Self {
+        self.unmappable = unmappable;
+        self
+    }
+}
+
+impl Responder for Text {
+    type Body = EitherBody<Bytes>;
+
+    fn respond_to(self, _req: &HttpRequest) -> HttpResponse<Self::Body> {
+        match encode_as(&self.body, &self.mime, &self.charset, self.unmappable) {
+            Ok((body, content_type)) => match HttpResponse::Ok()
+                .insert_header(content_type)
+                .message_body(body)
+            {
+                Ok(res) => res.map_into_left_body(),
+                Err(err) => HttpResponse::from_error(err).map_into_right_body(),
+            },
+
+            Err(err) => HttpResponse::from_error(err).map_into_right_body(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        body::to_bytes,
+        http::{
+            header::{self, AcceptCharset, Header as _},
+            StatusCode,
+        },
+        test::TestRequest,
+    };
+
+    fn windows_1252() -> Charset {
+        Charset::Ext("windows-1252".to_owned())
+    }
+
+    #[test]
+    fn encode_text_charsets() {
+        let (body, content_type) =
+            encode_text("é", &"UTF-8".parse().unwrap(), Unmappable::Error).unwrap();
+        assert_eq!(&body[..], b"\xC3\xA9");
+        assert_eq!(content_type.to_string(), "text/plain; charset=utf-8");
+
+        let (body, content_type) =
+            encode_text("é", &Charset::Iso_8859_1, Unmappable::Error).unwrap();
+        assert_eq!(&body[..], b"\xE9");
+        assert_eq!(content_type.to_string(), "text/plain; charset=iso-8859-1");
+
+        let (body, content_type) =
+            encode_text("é €", &windows_1252(), Unmappable::Error).unwrap();
+        assert_eq!(&body[..], b"\xE9 \x80");
+        assert_eq!(content_type.to_string(), "text/plain; charset=windows-1252");
+
+        assert_eq!(
+            encode_text("é", &Charset::Iso_8859_5, Unmappable::Replace).unwrap_err(),
+            EncodeTextError::UnsupportedCharset {
+                charset: "ISO-8859-5".to_owned()
+            }
+        );
+    }
+
+    #[test]
+    fn unmappable_characters() {
+        let (body, _) =
+            encode_text("a€b→c", &Charset::Iso_8859_1, Unmappable::Replace).unwrap();
+        assert_eq!(&body[..], b"a?b?c");
+
+        let (body, _) = encode_text("a€b→c", &windows_1252(), Unmappable::Replace).unwrap();
+        assert_eq!(&body[..], b"a\x80b?c");
+
+        assert_eq!(
+            encode_text("a€b→c", &windows_1252(), Unmappable::Error).unwrap_err(),
+            EncodeTextError::Unmappable {
+                ch: '→',
+                charset: "windows-1252".to_owned()
+            }
+        );
+        assert_eq!(
+            encode_text("a€b", &Charset::Iso_8859_1, Unmappable::Error).unwrap_err(),
+            EncodeTextError::Unmappable {
+                ch: '€',
+                charset: "iso-8859-1".to_owned()
+            }
+        );
+    }
+
+    #[actix_rt::test]
+    async fn negotiated_responses() {
+        let req = TestRequest::default()
+            .insert_header((header::ACCEPT_CHARSET, "windows-1252, utf-8;q=0.5"))
+            .to_http_request();
+
+        let utf8 = Charset::Ext("utf-8".to_owned());
+        let charset = AcceptCharset::parse(&req)
+            .unwrap()
+            .negotiate([&utf8, &windows_1252()])
+            .unwrap();
+        assert_eq!(charset, windows_1252());
+
+        let res = Text::csv("name\nJosé\n")
+            .charset(charset.clone())
+            .respond_to(&req);
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/csv; charset=windows-1252"
+        );
+        let body = to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(&body[..], b"name\nJos\xE9\n");
+
+        let res = Text::new("café").respond_to(&req);
+        assert_eq!(
+            res.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+        let body = to_bytes(res.into_body()).await.unwrap();
+        assert_eq!(&body[..], "café".as_bytes());
+
+        let res = Text::new("→")
+            .charset(charset)
+            .unmappable(Unmappable::Error)
+            .respond_to(&req);
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
@@ -0,0 +1,269 @@
+//! See [`UrlSigner`] for documentation.
+
+use std::{
+    fmt,
+    rc::Rc,
+    time::{Duration, SystemTime},
+};
+
+use actix_http::header::b64::{self, Alphabet};
+use hmac::{Hmac, Mac as _};
+use sha2::Sha256;
+
+use crate::{error::SignedUrlError, redirect::split_origin, HttpRequest};
+
+/// Name of the query parameter holding the expiry, in seconds since the Unix epoch.
+const EXPIRES_PARAM: &str = "expires";
+
+/// Name of the query parameter holding the signature, which is always the last parameter.
+const SIGNATURE_PARAM: &str = "signature";
+
+/// Signs URLs with an expiry, and verifies them on the destination.
+///
+/// [`sign`](Self::sign) appends an `expires` timestamp and an HMAC-SHA256 `signature` of the URL's
+/// path and query to the query string. Any change to the path or query, including the expiry,
+/// invalidates the signature. The scheme, host, and fragment are not signed, so the same signer
+/// can verify requests on the destination using [`verify`](Self::verify), even when it runs
+/// behind a different host name.
+///
+/// A signer is usually used with [`Redirect::signed_expiry`](crate::web::Redirect::signed_expiry)
+/// to hand out time-limited links, such as download URLs.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+///
+/// use actix_web::{web, App, HttpRequest, HttpResponse};
+///
+/// let signer = web::UrlSigner::new("secret key");
+///
+/// let download = {
+///     let signer = signer.clone();
+///
+///     move |req: HttpRequest| {
+///         let verified = signer.verify(&req);
+///
+///         async move {
+///             verified?;
+///             Ok::<_, actix_web::Error>(HttpResponse::Ok().body("report"))
+///         }
+///     }
+/// };
+///
+/// App::new()
+///     .service(
+///         web::Redirect::from("/download")
+///             .to_absolute("/files/report.pdf")
+///             .temporary()
+///             .signed_expiry(signer, Duration::from_secs(300)),
+///     )
+///     .route("/files/report.pdf", web::get().to(download));
+/// ```
+#[derive(Clone)]
+pub struct UrlSigner {
+    key: Rc<[u8]>,
+}
+
+impl UrlSigner {
+    /// Creates a signer using the secret `key`.
+    pub fn new(key: impl AsRef<[u8]>) -> Self {
+        Self {
+            key: Rc::from(key.as_ref()),
+        }
+    }
+
+    /// Returns `url` with an `expires` timestamp and a `signature` appended to its query.
+    ///
+    /// `url` may be a path or an absolute URL. The expiry is rounded down to whole seconds.
+    pub fn sign(&self, url: &str, expires: SystemTime) -> String {
+        let (url, fragment) = match url.find('#') {
+            Some(idx) => url.split_at(idx),
+            None => (url, ""),
+        };
+
+        let (origin, path_and_query) = split_origin(url);
+
+        let expires = expires
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+
+        // requests for a URL without a path are for the root path
+        let root = if !origin.is_empty() && !path_and_query.starts_with('/') {
+            "/"
+        } else {
+            ""
+        };
+
+        let separator = if path_and_query.contains('?') {
+            '&'
+        } else {
+            '?'
+        };
+
+        let signed = format!(
+            "{}{}{}{}={}",
+            root, path_and_query, separator, EXPIRES_PARAM, expires
+        );
+        let signature = b64::encode(
+            &self.mac(&signed).finalize().into_bytes(),
+            Alphabet::UrlSafe,
+        );
+
+        format!(
+            "{}{}&{}={}{}",
+            origin, signed, SIGNATURE_PARAM, signature, fragment
+        )
+    }
+
+    /// Verifies that the URL of `req` was signed by this signer and has not expired.
+    ///
+    /// The `signature` parameter must be the last parameter of the query string, as appended by
+    /// [`sign`](Self::sign). Failures are [`SignedUrlError`]s, which respond with
+    /// "403 Forbidden".
+    pub fn verify(&self, req: &HttpRequest) -> Result<(), SignedUrlError> {
+        let path_and_query = req
+            .uri()
+            .path_and_query()
+            .map_or("/", |path_and_query| path_and_query.as_str());
+
+        self.verify_at(path_and_query, SystemTime::now())
+    }
+
+    /// Verifies a signed path and query as of `now`.
+    fn verify_at(&self, path_and_query: &str, now: SystemTime) -> Result<(), SignedUrlError> {
+        let marker_len = SIGNATURE_PARAM.len() + 2;
+
+        let idx = path_and_query
+            .rfind(&format!("&{}=", SIGNATURE_PARAM))
+            .ok_or(SignedUrlError::Missing)?;
+
+        let (signed, signature) = (&path_and_query[..idx], &path_and_query[idx + marker_len..]);
+
+        let signature = b64::decode(signature.as_bytes(), Alphabet::UrlSafe)
+            .map_err(|_| SignedUrlError::Invalid)?;
+
+        self.mac(signed)
+            .verify_slice(&signature)
+            .map_err(|_| SignedUrlError::Invalid)?;
+
+        // the expiry is the last parameter covered by the signature
+        let expires = signed
+            .rsplit(|ch| ch == '?' || ch == '&')
+            .next()
+            .and_then(|param| param.strip_prefix(EXPIRES_PARAM)?.strip_prefix('='))
+            .and_then(|secs| secs.parse::<u64>().ok())
+            .ok_or(SignedUrlError::Missing)?;
+
+        let now = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or(Duration::ZERO)
+            .as_secs();
+
+        if now >= expires {
+            return Err(SignedUrlError::Expired);
+        }
+
+        Ok(())
+    }
+
+    fn mac(&self, signed: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.key).expect("HMAC keys can be of any length");
+        mac.update(signed.as_bytes());
+        mac
+    }
+}
+
+impl fmt::Debug for UrlSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UrlSigner").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::TestRequest;
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn sign_appends_expiry_and_signature() {
+        let signer = UrlSigner::new("secret");
+
+        let url = signer.sign("https://files.example.com/report.pdf?v=2#page=3", at(1_000));
+        let (signed, fragment) = url.split_at(url.find('#').unwrap());
+        assert!(
+            signed.starts_with(
+                "https://files.example.com/report.pdf?v=2&expires=1000&signature="
+            ),
+            "{}",
+            url
+        );
+        assert_eq!(fragment, "#page=3");
+
+        // only the path and query are signed
+        let path = signer.sign("/report.pdf?v=2", at(1_000));
+        assert_eq!(signed, format!("https://files.example.com{}", path));
+
+        // a host without a path signs the root
+        let root = signer.sign("https://example.com", at(1_000));
+        assert!(root.starts_with("https://example.com/?expires=1000&signature="));
+        assert_eq!(signer.verify_at(&root[19..], at(0)), Ok(()));
+    }
+
+    #[test]
+    fn verify_fresh_and_expired() {
+        let signer = UrlSigner::new("secret");
+        let url = signer.sign("/files/report.pdf?v=2", at(1_000));
+
+        assert_eq!(signer.verify_at(&url, at(999)), Ok(()));
+        assert_eq!(
+            signer.verify_at(&url, at(1_000)),
+            Err(SignedUrlError::Expired)
+        );
+
+        let req = TestRequest::with_uri(
+            &signer.sign("/files/a", SystemTime::now() + Duration::from_secs(60)),
+        )
+        .to_http_request();
+        assert_eq!(signer.verify(&req), Ok(()));
+
+        let req = TestRequest::with_uri(&signer.sign("/files/a", SystemTime::now()))
+            .to_http_request();
+        assert_eq!(signer.verify(&req), Err(SignedUrlError::Expired));
+    }
+
+    #[test]
+    fn verify_rejects_tampering() {
+        let signer = UrlSigner::new("secret");
+        let url = signer.sign("/files/report.pdf?v=2", at(1_000));
+
+        let tampered = [
+            url.replace("v=2", "v=3"),
+            url.replace("expires=1000", "expires=9000"),
+            url.replace("report", "secret"),
+            format!("{}A", url),
+        ];
+
+        for url in &tampered {
+            assert_eq!(
+                signer.verify_at(url, at(0)),
+                Err(SignedUrlError::Invalid),
+                "{}",
+                url
+            );
+        }
+
+        let other = UrlSigner::new("other secret");
+        assert_eq!(other.verify_at(&url, at(0)), Err(SignedUrlError::Invalid));
+
+        assert_eq!(
+            signer.verify_at("/files/report.pdf?v=2", at(0)),
+            Err(SignedUrlError::Missing)
+        );
+    }
+}
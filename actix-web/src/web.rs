@@ -24,10 +24,22 @@ use crate::{
     Responder, Route, Scope,
 };
 
+pub use crate::blocking::BlockingPool;
 pub use crate::config::ServiceConfig;
 pub use crate::data::Data;
+pub use crate::gone::Gone;
+pub use crate::health::{health_scope, ReadinessChecks};
+#[cfg(feature = "cookies")]
+pub use crate::oauth_redirect::OAuthRedirect;
+pub use crate::redirect::{
+    register_redirects, Canonicalize, HostMigrationRedirect, Redirect, RedirectFromStatus,
+    RedirectRule, TargetParts,
+};
 pub use crate::request_data::ReqData;
 pub use crate::types::*;
+#[cfg(feature = "signed-urls")]
+#[cfg_attr(docsrs, doc(cfg(feature = "signed-urls")))]
+pub use crate::url_signer::UrlSigner;
 
 /// Creates a new resource for a specific path.
 ///
@@ -141,6 +153,43 @@ pub fn method(method: Method) -> Route {
     Route::new().method(method)
 }
 
+/// Creates a new route with a guard matching any of the given methods.
+///
+/// The methods are checked together, which is cheaper than combining method guards with
+/// [`guard::Any`](crate::guard::Any). They are also listed in the `Allow` header of "405 Method
+/// Not Allowed" and automatic `OPTIONS` responses from the resource.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, http::Method, App, HttpResponse};
+///
+/// let app = App::new().service(
+///     web::resource("/form")
+///         .route(web::any_of([Method::GET, Method::POST]).to(|| HttpResponse::Ok()))
+/// );
+/// ```
+pub fn any_of(methods: impl AsRef<[Method]>) -> Route {
+    Route::new().method_set(methods.as_ref(), false)
+}
+
+/// Creates a new route with a guard matching all methods except the given ones.
+///
+/// Use this for catch-all routes that should leave some methods to other routes, or to the
+/// resource's "405 Method Not Allowed" response.
+///
+/// # Examples
+/// ```
+/// use actix_web::{web, http::Method, App, HttpResponse};
+///
+/// let app = App::new().service(
+///     web::resource("/files/{name}")
+///         .route(web::route_except([Method::DELETE]).to(|| HttpResponse::Ok()))
+/// );
+/// ```
+pub fn route_except(methods: impl AsRef<[Method]>) -> Route {
+    Route::new().method_set(methods.as_ref(), true)
+}
+
 /// Creates a new any-method route with handler.
 ///
 /// ```
@@ -191,5 +240,22 @@ where
     R: Send + 'static,
 {
     let fut = actix_rt::task::spawn_blocking(f);
-    async { fut.await.map_err(|_| BlockingError) }
+    async { fut.await.map_err(|_| BlockingError::Canceled) }
+}
+
+/// Executes blocking function on the given [`BlockingPool`], returns future that resolves to
+/// result of the function execution.
+///
+/// The function is queued immediately. If the pool's queue is full, the future resolves to
+/// [`BlockingError::Overloaded`] without running it.
+pub fn block_on<F, R>(
+    pool: &BlockingPool,
+    f: F,
+) -> impl Future<Output = Result<R, BlockingError>>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let rx = pool.spawn(f);
+    async { rx?.await.map_err(|_| BlockingError::Canceled) }
 }
@@ -0,0 +1,210 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    future::Future,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use actix_http::Extensions;
+use actix_service::ServiceFactory;
+use futures_core::future::LocalBoxFuture;
+use tokio::sync::OnceCell;
+
+use crate::{config::AppConfig, data::DataFactory};
+
+/// Source of unique IDs used to tell apart the worker state of different servers.
+static NEXT_SERVER_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Worker state of each server that has a worker running on this thread, keyed by server ID.
+    static WORKERS: RefCell<HashMap<usize, Rc<OnceCell<Rc<WorkerData>>>>> =
+        RefCell::new(HashMap::new());
+}
+
+/// Worker context passed to the [`HttpServer::on_worker_start`](crate::HttpServer::on_worker_start)
+/// hook.
+///
+/// Provides the index of the worker being started and allows inserting worker-scoped data.
+/// Worker data is added to the app data of every app created by that worker, shadowing
+/// [`App::app_data`](crate::App::app_data) items of the same type.
+///
+/// # Examples
+/// ```
+/// use actix_web::{dev::WorkerCtx, web};
+///
+/// async fn init_worker(ctx: WorkerCtx) {
+///     // e.g., open a connection that is local to this worker
+///     ctx.app_data(web::Data::new(format!("worker-{}", ctx.index())));
+/// }
+///
+/// async fn handler(tag: web::Data<String>) -> String {
+///     tag.to_string()
+/// }
+/// ```
+#[derive(Clone)]
+pub struct WorkerCtx {
+    idx: usize,
+    data: Rc<RefCell<Vec<Box<dyn DataFactory>>>>,
+}
+
+impl WorkerCtx {
+    fn new(idx: usize) -> Self {
+        Self {
+            idx,
+            data: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Returns the index of the worker being started.
+    ///
+    /// Indices are assigned in the order that workers start, beginning at zero.
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+
+    /// Sets worker-scoped data.
+    ///
+    /// The data is cloned into each app created by this worker. Wrap it in
+    /// [`Data`](crate::web::Data) to use it as an extractor. Setting data of the same type twice
+    /// replaces the first value.
+    pub fn app_data<U: Clone + 'static>(&self, data: U) {
+        self.data.borrow_mut().push(Box::new(WorkerAppData(data)));
+    }
+}
+
+impl fmt::Debug for WorkerCtx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkerCtx")
+            .field("idx", &self.idx)
+            .finish_non_exhaustive()
+    }
+}
+
+struct WorkerAppData<U>(U);
+
+impl<U: Clone + 'static> DataFactory for WorkerAppData<U> {
+    fn create(&self, extensions: &mut Extensions) -> bool {
+        extensions.insert(self.0.clone());
+        true
+    }
+}
+
+/// Worker index and data, carried to apps in their [`AppConfig`].
+pub(crate) struct WorkerData {
+    idx: usize,
+    data: Vec<Box<dyn DataFactory>>,
+}
+
+impl WorkerData {
+    pub(crate) fn index(&self) -> usize {
+        self.idx
+    }
+
+    /// Adds worker data to an app data container.
+    pub(crate) fn create(&self, extensions: &mut Extensions) {
+        for data in &self.data {
+            data.create(extensions);
+        }
+    }
+}
+
+impl fmt::Debug for WorkerData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WorkerData")
+            .field("idx", &self.idx)
+            .finish_non_exhaustive()
+    }
+}
+
+type WorkerStartFn = dyn Fn(WorkerCtx) -> LocalBoxFuture<'static, ()> + Send + Sync;
+
+/// Server-wide worker start hook, shared by the service factories of all listeners.
+#[derive(Clone)]
+pub(crate) struct WorkerStart {
+    server_id: usize,
+    next_idx: Arc<AtomicUsize>,
+    hook: Arc<WorkerStartFn>,
+}
+
+impl WorkerStart {
+    pub(crate) fn new<F, Fut>(hook: F) -> Self
+    where
+        F: Fn(WorkerCtx) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + 'static,
+    {
+        Self {
+            server_id: NEXT_SERVER_ID.fetch_add(1, Ordering::Relaxed),
+            next_idx: Arc::new(AtomicUsize::new(0)),
+            hook: Arc::new(move |ctx| Box::pin(hook(ctx))),
+        }
+    }
+
+    /// Runs the hook once per worker thread and resolves to that worker's data.
+    ///
+    /// Service factories of other listeners on the same worker wait for the first run to finish.
+    fn init(&self) -> LocalBoxFuture<'static, Rc<WorkerData>> {
+        let cell = WORKERS
+            .with(|workers| Rc::clone(workers.borrow_mut().entry(self.server_id).or_default()));
+
+        let this = self.clone();
+
+        Box::pin(async move {
+            let worker = cell
+                .get_or_init(|| async move {
+                    let ctx = WorkerCtx::new(this.next_idx.fetch_add(1, Ordering::Relaxed));
+                    (this.hook)(ctx.clone()).await;
+
+                    let data = ctx.data.take();
+                    Rc::new(WorkerData { idx: ctx.idx, data })
+                })
+                .await;
+
+            Rc::clone(worker)
+        })
+    }
+}
+
+/// Service factory that runs the worker start hook, if any, before creating the app service.
+pub(crate) struct WorkerInit<S> {
+    factory: Rc<S>,
+    start: Option<WorkerStart>,
+}
+
+impl<S> WorkerInit<S> {
+    pub(crate) fn new(factory: S, start: Option<WorkerStart>) -> Self {
+        Self {
+            factory: Rc::new(factory),
+            start,
+        }
+    }
+}
+
+impl<S, Req> ServiceFactory<Req> for WorkerInit<S>
+where
+    S: ServiceFactory<Req, Config = AppConfig> + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Config = AppConfig;
+    type Service = S::Service;
+    type InitError = S::InitError;
+    type Future = LocalBoxFuture<'static, Result<Self::Service, Self::InitError>>;
+
+    fn new_service(&self, mut config: AppConfig) -> Self::Future {
+        let factory = Rc::clone(&self.factory);
+        let start = self.start.clone();
+
+        Box::pin(async move {
+            if let Some(start) = start {
+                config.set_worker(start.init().await);
+            }
+
+            factory.new_service(config).await
+        })
+    }
+}
@@ -133,3 +133,218 @@ async fn test_start_ssl() {
 
     srv.stop(false).await;
 }
+
+#[cfg(unix)]
+#[actix_rt::test]
+async fn test_on_worker_start() {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use actix_web::{dev::WorkerCtx, HttpRequest};
+
+    let addr = actix_test::unused_addr();
+    let (tx, rx) = mpsc::channel();
+
+    let starts = Arc::new(AtomicUsize::new(0));
+    let hook_starts = Arc::clone(&starts);
+
+    thread::spawn(move || {
+        actix_rt::System::new()
+            .block_on(async move {
+                let srv = HttpServer::new(|| {
+                    App::new()
+                        .app_data(web::Data::new("app".to_owned()))
+                        .default_service(web::to(
+                            |req: HttpRequest, tag: web::Data<String>| async move {
+                                let idx = req.app_config().worker_index().unwrap();
+                                format!("{} {}", tag.as_str(), idx)
+                            },
+                        ))
+                })
+                .on_worker_start(move |ctx: WorkerCtx| {
+                    let starts = Arc::clone(&hook_starts);
+
+                    async move {
+                        // connections must not be accepted before initialization completes
+                        actix_rt::time::sleep(Duration::from_millis(50)).await;
+
+                        ctx.app_data(web::Data::new(format!("worker-{}", ctx.index())));
+                        starts.fetch_add(1, Ordering::SeqCst);
+                    }
+                })
+                .workers(2)
+                .system_exit()
+                .disable_signals()
+                .bind(format!("{}", addr))
+                .unwrap()
+                .run();
+
+                tx.send(srv.handle()).unwrap();
+
+                srv.await
+            })
+            .unwrap();
+    });
+
+    let srv = rx.recv().unwrap();
+
+    let host = format!("http://{}", addr);
+    for _ in 0..4 {
+        // a client with a single pooled connection sends all its requests to the same worker
+        let client = awc::Client::builder()
+            .connector(awc::Connector::new().limit(1))
+            .finish();
+
+        let mut conn_tags = Vec::new();
+
+        for _ in 0..3 {
+            let mut res = client.get(host.clone()).send().await.unwrap();
+            assert!(res.status().is_success());
+            let body = res.body().await.unwrap();
+            conn_tags.push(String::from_utf8(body.to_vec()).unwrap());
+        }
+
+        assert!(
+            conn_tags[0] == "worker-0 0" || conn_tags[0] == "worker-1 1",
+            "unexpected tag: {}",
+            conn_tags[0]
+        );
+        assert!(conn_tags.iter().all(|tag| *tag == conn_tags[0]));
+    }
+
+    assert_eq!(starts.load(Ordering::SeqCst), 2);
+
+    srv.stop(false).await;
+}
+
+#[cfg(unix)]
+#[actix_rt::test]
+async fn test_reject_requests_while_draining() {
+    use std::{
+        io::{Read as _, Write as _},
+        net::TcpStream,
+    };
+
+    let addr = actix_test::unused_addr();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        actix_rt::System::new()
+            .block_on(async {
+                let srv = HttpServer::new(|| {
+                    App::new()
+                        .default_service(web::to(|| async { HttpResponse::Ok().body("ok") }))
+                })
+                .reject_requests_while_draining(30)
+                .workers(1)
+                .system_exit()
+                .disable_signals()
+                .bind(format!("{}", addr))
+                .unwrap();
+
+                let drain = srv.drain_signal().unwrap();
+                let srv = srv.run();
+
+                tx.send((srv.handle(), drain)).unwrap();
+
+                srv.await
+            })
+            .unwrap();
+    });
+
+    let (srv, drain) = rx.recv().unwrap();
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut buf = [0; 1024];
+    let n = stream.read(&mut buf).unwrap();
+    let res = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+    assert!(res.starts_with("http/1.1 200 ok\r\n"), "{}", res);
+    assert!(res.ends_with("\r\n\r\nok"), "{}", res);
+
+    // the idle keep-alive connection is told to go elsewhere once draining starts
+    drain.start();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+    let mut res = String::new();
+    stream.read_to_string(&mut res).unwrap();
+    let res = res.to_lowercase();
+    assert!(
+        res.starts_with("http/1.1 503 service unavailable\r\n"),
+        "{}",
+        res
+    );
+    assert!(res.contains("\r\nretry-after: 30\r\n"), "{}", res);
+    assert!(res.contains("\r\nconnection: close\r\n"), "{}", res);
+
+    srv.stop(true).await;
+}
+
+#[cfg(unix)]
+#[actix_rt::test]
+async fn test_on_connection_close() {
+    use std::{
+        io::{Read as _, Write as _},
+        net::TcpStream,
+    };
+
+    use actix_web::dev::Protocol;
+
+    let addr = actix_test::unused_addr();
+    let (tx, rx) = mpsc::channel();
+    let (close_tx, close_rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        actix_rt::System::new()
+            .block_on(async {
+                let close_tx = std::sync::Mutex::new(close_tx);
+
+                let srv = HttpServer::new(|| {
+                    App::new()
+                        .default_service(web::to(|| async { HttpResponse::Ok().body("ok") }))
+                })
+                .on_connection_close(move |conn| {
+                    let event = (conn.protocol(), conn.requests(), conn.peer_addr().is_some());
+                    close_tx.lock().unwrap().send(event).unwrap();
+                })
+                .workers(1)
+                .system_exit()
+                .disable_signals()
+                .bind(format!("{}", addr))
+                .unwrap()
+                .run();
+
+                tx.send(srv.handle()).unwrap();
+
+                srv.await
+            })
+            .unwrap();
+    });
+
+    let srv = rx.recv().unwrap();
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(
+            b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n\
+            GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n",
+        )
+        .unwrap();
+    let mut res = String::new();
+    stream.read_to_string(&mut res).unwrap();
+    assert_eq!(res.matches("HTTP/1.1 200 OK").count(), 2, "{}", res);
+
+    let event = close_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert_eq!(event, (Protocol::Http1, 2, true));
+
+    srv.stop(true).await;
+}
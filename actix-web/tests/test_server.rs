@@ -7,15 +7,17 @@ use std::{
     future::Future,
     io::{Read, Write},
     pin::Pin,
+    sync::Mutex,
     task::{Context, Poll},
     time::Duration,
 };
 
 use actix_web::{
     cookie::Cookie,
+    error::PayloadError,
     http::{header, StatusCode},
-    middleware::{Compress, NormalizePath, TrailingSlash},
-    web, App, Error, HttpResponse,
+    middleware::{BodyLimit, Compress, NormalizePath, TrailingSlash},
+    web, App, Error, HttpResponse, ResponseError as _,
 };
 use bytes::Bytes;
 use futures_core::ready;
@@ -946,3 +948,496 @@ async fn test_accept_encoding_no_match() {
 
     srv.stop().await;
 }
+
+#[actix_rt::test]
+async fn test_handler_observes_client_disconnect() {
+    use std::{net, sync::mpsc};
+
+    struct SetOnDrop(Option<mpsc::Sender<&'static str>>);
+
+    impl Drop for SetOnDrop {
+        fn drop(&mut self) {
+            let _ = self.0.take().unwrap().send("work cancelled");
+        }
+    }
+
+    let (tx, rx) = mpsc::channel();
+
+    let srv = actix_test::start_with(actix_test::config().h1(), move || {
+        let tx = tx.clone();
+
+        App::new().route(
+            "/",
+            web::get().to(move |watcher: web::ConnectionWatcher| {
+                let tx = tx.clone();
+
+                async move {
+                    let guard = SetOnDrop(Some(tx.clone()));
+                    let work = async move {
+                        actix_rt::time::sleep(Duration::from_secs(30)).await;
+                        drop(guard);
+                    };
+
+                    tokio::select! {
+                        _ = work => HttpResponse::Ok().finish(),
+                        _ = watcher.disconnected() => {
+                            tx.send("disconnected").unwrap();
+                            HttpResponse::NoContent().finish()
+                        }
+                    }
+                }
+            }),
+        )
+    });
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .unwrap();
+
+    // give the handler time to start before the client goes away
+    actix_rt::time::sleep(Duration::from_millis(100)).await;
+    drop(stream);
+
+    let timeout = Duration::from_secs(5);
+    assert_eq!(rx.recv_timeout(timeout).unwrap(), "work cancelled");
+    assert_eq!(rx.recv_timeout(timeout).unwrap(), "disconnected");
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn test_server_options_asterisk() {
+    use std::net;
+
+    let srv = actix_test::start_with(actix_test::config().h1(), || {
+        App::new()
+            .server_options_handler(web::to(|| async {
+                HttpResponse::NoContent()
+                    .insert_header((header::ALLOW, "GET, TRACE"))
+                    .finish()
+            }))
+            .enable_trace(true)
+    });
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(b"OPTIONS * HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 204 No Content"));
+    assert!(data.contains("allow: GET, TRACE"));
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(b"TRACE / HTTP/1.1\r\nHost: localhost\r\nMax-Forwards: 0\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 200 OK"));
+    assert!(data.contains("content-type: message/http"));
+    assert!(data.contains("max-forwards: 0\r\n"));
+
+    srv.stop().await;
+}
+
+/// Starts a server whose handler streams the request body and reports the first payload error.
+fn payload_error_server(
+    cfg: actix_test::TestServerConfig,
+) -> (
+    actix_test::TestServer,
+    std::sync::mpsc::Receiver<PayloadError>,
+) {
+    use futures_util::StreamExt as _;
+
+    let (tx, rx) = std::sync::mpsc::channel();
+
+    let srv = actix_test::start_with(cfg.h1(), move || {
+        let tx = tx.clone();
+
+        App::new().wrap(BodyLimit::new(8)).route(
+            "/",
+            web::post().to(move |mut body: web::Payload| {
+                let tx = tx.clone();
+
+                async move {
+                    while let Some(chunk) = body.next().await {
+                        if let Err(err) = chunk {
+                            let res = err.error_response();
+                            tx.send(err).unwrap();
+                            return res;
+                        }
+                    }
+
+                    HttpResponse::Ok().finish()
+                }
+            }),
+        )
+    });
+
+    (srv, rx)
+}
+
+#[actix_rt::test]
+async fn test_payload_limit_exceeded() {
+    use std::net;
+
+    let (srv, rx) = payload_error_server(actix_test::config());
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(
+            b"POST / HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n\
+            10\r\n0123456789abcdef\r\n0\r\n\r\n",
+        )
+        .unwrap();
+
+    let err = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(err, PayloadError::LimitExceeded { limit: 8 }));
+
+    let mut data = vec![0; 1024];
+    let n = stream.read(&mut data).unwrap();
+    assert!(data[..n].starts_with(b"HTTP/1.1 413 Payload Too Large"));
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn test_payload_malformed_chunked_encoding() {
+    use std::net;
+
+    let (srv, rx) = payload_error_server(actix_test::config());
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(
+            b"POST / HTTP/1.1\r\nHost: localhost\r\nTransfer-Encoding: chunked\r\n\r\n\
+            zz\r\n0123\r\n",
+        )
+        .unwrap();
+
+    let err = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(err, PayloadError::MalformedChunkedEncoding(_)));
+
+    // the connection is closed after responding, since the end of the payload is unknown
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 400 Bad Request"));
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn test_payload_timeout() {
+    use std::net;
+
+    let timeout = Duration::from_millis(200);
+    let (srv, rx) = payload_error_server(actix_test::config().client_body_timeout(timeout));
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 8\r\n\r\n0123")
+        .unwrap();
+
+    let err = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(err, PayloadError::Timeout { timeout: t } if t == timeout));
+
+    let mut data = String::new();
+    let _ = stream.read_to_string(&mut data);
+    assert!(data.starts_with("HTTP/1.1 408 Request Timeout"));
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn test_payload_client_disconnect() {
+    use std::net;
+
+    let (srv, rx) = payload_error_server(actix_test::config());
+
+    let mut stream = net::TcpStream::connect(srv.addr()).unwrap();
+    stream
+        .write_all(b"POST / HTTP/1.1\r\nHost: localhost\r\nContent-Length: 8\r\n\r\n0123")
+        .unwrap();
+
+    // give the handler time to start reading before the client goes away
+    actix_rt::time::sleep(Duration::from_millis(100)).await;
+    drop(stream);
+
+    // no response can be sent; the payload must not end as if complete
+    let err = rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    assert!(matches!(err, PayloadError::Incomplete(_)));
+
+    srv.stop().await;
+}
+
+/// Starts capturing the errors logged by any thread and returns the capture buffer.
+///
+/// Tests run concurrently and share the logger, so each test should only look at errors that
+/// mention routes of its own; see [`logged_errors`].
+fn capture_logged_errors() -> &'static Mutex<Vec<String>> {
+    use std::sync::Once;
+
+    use once_cell::sync::Lazy;
+
+    static ERRORS: Lazy<Mutex<Vec<String>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+    struct CaptureLogger;
+
+    impl log::Log for CaptureLogger {
+        fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+            metadata.level() <= log::Level::Error
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            if self.enabled(record.metadata()) {
+                ERRORS.lock().unwrap().push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&CaptureLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Error);
+    });
+
+    &ERRORS
+}
+
+/// Returns the captured errors that mention `route`.
+fn logged_errors(route: &str) -> Vec<String> {
+    capture_logged_errors()
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|err| err.contains(route))
+        .cloned()
+        .collect()
+}
+
+#[actix_rt::test]
+async fn test_response_header_budget() {
+    capture_logged_errors();
+
+    let srv = actix_test::start(|| {
+        App::new()
+            .route(
+                "/header-budget/big/{id}",
+                web::get().to(|| async {
+                    HttpResponse::Ok()
+                        .insert_header(("x-big", "a".repeat(1024 * 1024)))
+                        .body("big")
+                }),
+            )
+            .route("/header-budget/small", web::get().to(|| async { "small" }))
+    });
+
+    let mut res = srv.get("/header-budget/big/1").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!res.headers().contains_key("x-big"));
+    assert!(res.body().await.unwrap().is_empty());
+
+    let errors = logged_errors("/header-budget/");
+    assert!(
+        errors
+            .iter()
+            .any(|err| err.contains("exceed") && err.contains("/header-budget/big/{id}")),
+        "{:?}",
+        errors
+    );
+
+    // the connection stays usable and normal responses are unaffected
+    let mut res = srv.get("/header-budget/small").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body().await.unwrap(), "small");
+
+    srv.stop().await;
+}
+
+#[actix_rt::test]
+async fn test_lifecycle_hooks() {
+    use std::sync::{Arc, Mutex};
+
+    use actix_web::dev::ResponseStage;
+
+    let events = Arc::new(Mutex::new(Vec::<String>::new()));
+
+    let app_events = Arc::clone(&events);
+    let srv = actix_test::start(move || {
+        let req_events = Arc::clone(&app_events);
+        let res_events = Arc::clone(&app_events);
+        let res_events2 = Arc::clone(&app_events);
+
+        App::new()
+            .on_request(move |ev| {
+                req_events.lock().unwrap().push(format!(
+                    "request {} {}",
+                    ev.method(),
+                    ev.match_pattern().unwrap_or_default()
+                ));
+            })
+            .on_response(move |ev| {
+                let stage = match ev.stage() {
+                    ResponseStage::HeadersWritten => "headers".to_owned(),
+                    ResponseStage::BodyFinished { bytes, complete } => {
+                        format!("body {} {}", bytes, complete)
+                    }
+                    _ => unreachable!(),
+                };
+
+                res_events.lock().unwrap().push(format!(
+                    "{} {} {}",
+                    stage,
+                    ev.status().as_u16(),
+                    ev.match_pattern().unwrap_or_default()
+                ));
+            })
+            .on_response(move |ev| {
+                if ev.stage() == ResponseStage::HeadersWritten {
+                    res_events2.lock().unwrap().push("second".to_owned());
+                }
+            })
+            .route(
+                "/stream/{id}",
+                web::get().to(|| async {
+                    let chunks = ["ab", "cde", "f"]
+                        .iter()
+                        .map(|&chunk| Ok::<_, Error>(Bytes::from_static(chunk.as_bytes())));
+                    HttpResponse::Ok().streaming(futures_util::stream::iter(chunks))
+                }),
+            )
+            .route(
+                "/fail",
+                web::get().to(|| async {
+                    Err::<HttpResponse, _>(actix_web::error::ErrorBadRequest("nope"))
+                }),
+            )
+            .service(
+                web::scope("/api")
+                    .service(
+                        web::resource("/items/{id}")
+                            .guard(actix_web::guard::Header("x-allowed", "1"))
+                            .to(HttpResponse::Ok),
+                    )
+                    .default_service(web::to(HttpResponse::NotFound)),
+            )
+    });
+
+    let mut res = srv.get("/stream/1").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body().await.unwrap(), "abcdef");
+
+    assert_eq!(
+        std::mem::take(&mut *events.lock().unwrap()),
+        [
+            "request GET /stream/{id}",
+            "headers 200 /stream/{id}",
+            "second",
+            "body 6 true 200 /stream/{id}",
+        ]
+    );
+
+    let mut res = srv.get("/fail").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(res.body().await.unwrap(), "nope");
+
+    assert_eq!(
+        std::mem::take(&mut *events.lock().unwrap()),
+        [
+            "request GET /fail",
+            "headers 400 /fail",
+            "second",
+            "body 4 true 400 /fail",
+        ]
+    );
+
+    // the route that handled the request is reported, not the first resource matching the path
+    let res = srv
+        .get("/api/items/1")
+        .insert_header(("x-allowed", "1"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = srv.get("/api/items/1").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    assert_eq!(
+        std::mem::take(&mut *events.lock().unwrap()),
+        [
+            "request GET /api/items/{id}",
+            "headers 200 /api/items/{id}",
+            "second",
+            "body 0 true 200 /api/items/{id}",
+            "request GET /api/items/{id}",
+            "headers 404 ",
+            "second",
+            "body 0 true 404 ",
+        ]
+    );
+
+    srv.stop().await;
+}
+
+#[cfg(debug_assertions)]
+#[actix_rt::test]
+async fn test_manual_content_length_guard() {
+    capture_logged_errors();
+
+    let srv = actix_test::start(|| {
+        App::new()
+            .route(
+                "/content-length/matching",
+                web::get().to(|| async {
+                    HttpResponse::Ok()
+                        .insert_header(header::ContentLength(5))
+                        .body("hello")
+                }),
+            )
+            .route(
+                "/content-length/mismatched/{id}",
+                web::get().to(|| async {
+                    HttpResponse::Ok()
+                        .insert_header(header::ContentLength(3))
+                        .body("hello")
+                }),
+            )
+    });
+
+    let mut res = srv.get("/content-length/matching").send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.body().await.unwrap(), "hello");
+
+    // the body's length is used for framing
+    let mut res = srv
+        .get("/content-length/mismatched/1")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.headers().get(header::CONTENT_LENGTH).unwrap(), "5");
+    assert_eq!(res.body().await.unwrap(), "hello");
+
+    let errors = logged_errors("/content-length/");
+    assert!(
+        errors.iter().any(|err| err.contains("Content-Length: 3")
+            && err.contains("body is 5 bytes")
+            && err.contains("/content-length/mismatched/{id}")),
+        "{:?}",
+        errors
+    );
+    assert!(
+        !errors
+            .iter()
+            .any(|err| err.contains("/content-length/matching")),
+        "{:?}",
+        errors
+    );
+
+    srv.stop().await;
+}
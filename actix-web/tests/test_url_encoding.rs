@@ -0,0 +1,89 @@
+use actix_web::{
+    http::{
+        header,
+        uri::{EncodeSet, PathEncoder},
+    },
+    test::{self, TestRequest},
+    web, App, HttpRequest, HttpResponse,
+};
+
+const INPUTS: &[&str] = &[
+    "naïve café/50% off",
+    "already%20encoded/ü",
+    "sub-delims!$&'()*+,;=:@",
+    "brackets [v2] {x} |pipe| ^caret^ `tick`",
+    "日本語/🦀",
+];
+
+async fn location(app_path: &str, input: &str) -> String {
+    // braces in redirect targets are placeholders, so literal braces are given pre-encoded
+    let target = format!("/files/{}", input.replace('{', "%7B").replace('}', "%7D"));
+
+    let redirect = web::Redirect::from("/redirect").to_absolute(target.clone());
+    let relative = web::Redirect::from("/relative").to_relative(target);
+
+    let app = test::init_service(
+        App::new()
+            .service(redirect)
+            .service(web::scope("/scope").service(relative)),
+    )
+    .await;
+
+    let req = TestRequest::with_uri(app_path).to_request();
+    let res = test::call_service(&app, req).await;
+    assert!(res.status().is_redirection());
+
+    res.headers()
+        .get(header::LOCATION)
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+async fn url_for_path(input: &str) -> String {
+    let app = test::init_service(
+        App::new()
+            .service(
+                web::resource("/files/{tail}*")
+                    .name("files")
+                    .to(HttpResponse::Ok),
+            )
+            .route(
+                "/gen",
+                web::get().to(|req: HttpRequest, tail: String| async move {
+                    req.url_for("files", [tail]).unwrap().path().to_owned()
+                }),
+            ),
+    )
+    .await;
+
+    let req = TestRequest::with_uri("/gen")
+        .set_payload(input.to_owned())
+        .to_request();
+    let body = test::call_and_read_body(&app, req).await;
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+#[actix_rt::test]
+async fn redirect_and_url_for_agree() {
+    let encoder = PathEncoder::new(EncodeSet::Path).raw();
+
+    for input in INPUTS {
+        let expected = format!("/files/{}", encoder.encode(input));
+
+        assert_eq!(url_for_path(input).await, expected, "url_for: {}", input);
+        assert_eq!(
+            location("/redirect", input).await,
+            expected,
+            "absolute: {}",
+            input
+        );
+        assert_eq!(
+            location("/scope/relative", input).await,
+            format!("/scope{}", expected),
+            "relative: {}",
+            input
+        );
+    }
+}
@@ -0,0 +1,10 @@
+// error messages differ between compiler versions, so they are only checked on one
+#[rustversion::stable(1.95)]
+#[test]
+fn handler_errors() {
+    let t = trybuild::TestCases::new();
+
+    t.compile_fail("tests/trybuild/handler-not-responder-fail.rs");
+    t.compile_fail("tests/trybuild/handler-not-extractor-fail.rs");
+    t.compile_fail("tests/trybuild/handler-too-many-args-fail.rs");
+}
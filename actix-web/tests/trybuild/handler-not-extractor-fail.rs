@@ -0,0 +1,11 @@
+use actix_web::{web, App};
+
+struct NotExtractor;
+
+async fn index(_: web::Path<u32>, _: NotExtractor) -> &'static str {
+    ""
+}
+
+fn main() {
+    App::new().route("/{id}", web::get().to(index));
+}
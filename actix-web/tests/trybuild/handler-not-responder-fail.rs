@@ -0,0 +1,11 @@
+use actix_web::{web, App};
+
+struct NotResponder;
+
+async fn index() -> NotResponder {
+    NotResponder
+}
+
+fn main() {
+    App::new().route("/", web::get().to(index));
+}
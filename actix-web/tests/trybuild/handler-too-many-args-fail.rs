@@ -0,0 +1,13 @@
+use actix_web::{web, App};
+
+#[rustfmt::skip]
+async fn index(
+    _: (), _: (), _: (), _: (), _: (), _: (), _: (), _: (), _: (),
+    _: (), _: (), _: (), _: (), _: (), _: (), _: (), _: (),
+) -> &'static str {
+    ""
+}
+
+fn main() {
+    App::new().route("/", web::get().to(index));
+}